@@ -1,22 +1,46 @@
+use std::num::ParseIntError;
+
 use camino::{Utf8Path, Utf8PathBuf};
 
+use ecow::EcoString;
 use gleam_core::{
     error::{FileIoAction, FileKind},
+    requirement::Requirement,
     Error, Result,
 };
+use hexpm::version::Version;
 
-use crate::{cli, dependencies::UseManifest, fs};
+use crate::{
+    cli,
+    dependencies::{UseManifest, UseNetwork},
+    fs,
+};
 
 pub fn command(packages: Vec<String>, dev: bool) -> Result<()> {
     let paths = crate::find_project_paths()?;
+    let packages = packages
+        .iter()
+        .map(|package| parse_package(package))
+        .collect::<Result<Vec<_>>>()?;
+    let requirements = packages
+        .iter()
+        .map(|(name, version)| {
+            let range = match version {
+                Some(version) => major_version_range(version),
+                None => ">= 0.0.0".into(),
+            };
+            (name.clone(), Requirement::hex(&range))
+        })
+        .collect();
 
     // Insert the new packages into the manifest and perform dependency
     // resolution to determine suitable versions
     let manifest = crate::dependencies::download(
         &paths,
         cli::Reporter::new(),
-        Some((packages.to_vec(), dev)),
+        Some((requirements, dev)),
         UseManifest::Yes,
+        UseNetwork::from_environment(),
     )?;
 
     // Read gleam.toml and manifest.toml so we can insert new deps into it
@@ -24,36 +48,30 @@ pub fn command(packages: Vec<String>, dev: bool) -> Result<()> {
     let mut manifest_toml = read_toml_edit("manifest.toml")?;
 
     // Insert the new deps
-    for package_to_add in packages {
+    for (package_to_add, _) in packages {
         // Pull the selected version out of the new manifest so we know what it is
         let version = &manifest
             .packages
             .iter()
-            .find(|package| package.name == *package_to_add)
+            .find(|package| package.name == package_to_add)
             .expect("Added package not found in resolved manifest")
             .version;
 
         tracing::info!(version=%version, "new_package_version_resolved");
 
-        // Produce a version requirement locked to the major version.
-        // i.e. if 1.2.3 is selected we want >= 1.2.3 and < 2.0.0
-        let range = format!(
-            ">= {}.{}.{} and < {}.0.0",
-            version.major,
-            version.minor,
-            version.patch,
-            version.major + 1
-        );
+        let range = major_version_range(version);
 
         // False positive. This package doesn't use the indexing API correctly.
         #[allow(clippy::indexing_slicing)]
         {
             if dev {
-                gleam_toml["dev-dependencies"][&package_to_add] = toml_edit::value(range.clone());
+                gleam_toml["dev-dependencies"][package_to_add.as_str()] =
+                    toml_edit::value(range.clone());
             } else {
-                gleam_toml["dependencies"][&package_to_add] = toml_edit::value(range.clone());
+                gleam_toml["dependencies"][package_to_add.as_str()] =
+                    toml_edit::value(range.clone());
             };
-            manifest_toml["requirements"][&package_to_add]
+            manifest_toml["requirements"][package_to_add.as_str()]
                 .as_inline_table_mut()
                 .expect("Invalid manifest format")["version"] = range.into();
         }
@@ -68,6 +86,45 @@ pub fn command(packages: Vec<String>, dev: bool) -> Result<()> {
     Ok(())
 }
 
+/// Parses a package given to `gleam add`, which is either a package name or a
+/// package name and the version to add, such as `lustre@4` or `lustre@4.2`.
+/// Any parts of the version that are left out are taken to be 0.
+fn parse_package(package: &str) -> Result<(EcoString, Option<Version>)> {
+    let Some((name, version)) = package.split_once('@') else {
+        return Ok((package.into(), None));
+    };
+    let invalid_version = |error: String| Error::InvalidVersionFormat {
+        input: version.to_string(),
+        error,
+    };
+    let parts: Vec<_> = version.split('.').collect();
+    if parts.len() > 3 {
+        return Err(invalid_version(
+            "A version has at most a major, minor, and patch number.".into(),
+        ));
+    }
+    let mut numbers = [0; 3];
+    for (number, part) in numbers.iter_mut().zip(parts) {
+        *number = part
+            .parse()
+            .map_err(|error: ParseIntError| invalid_version(error.to_string()))?;
+    }
+    let [major, minor, patch] = numbers;
+    Ok((name.into(), Some(Version::new(major, minor, patch))))
+}
+
+/// A version requirement locked to the major version of the given version.
+/// i.e. for 1.2.3 this is >= 1.2.3 and < 2.0.0
+fn major_version_range(version: &Version) -> String {
+    format!(
+        ">= {}.{}.{} and < {}.0.0",
+        version.major,
+        version.minor,
+        version.patch,
+        version.major + 1
+    )
+}
+
 fn read_toml_edit(name: &str) -> Result<toml_edit::Document, Error> {
     fs::read(name)?
         .parse::<toml_edit::Document>()
@@ -78,3 +135,52 @@ fn read_toml_edit(name: &str) -> Result<toml_edit::Document, Error> {
             err: Some(e.to_string()),
         })
 }
+
+#[test]
+fn parse_package_name() {
+    assert_eq!(parse_package("lustre").unwrap(), ("lustre".into(), None));
+}
+
+#[test]
+fn parse_package_with_version() {
+    assert_eq!(
+        parse_package("lustre@4").unwrap(),
+        ("lustre".into(), Some(Version::new(4, 0, 0)))
+    );
+    assert_eq!(
+        parse_package("lustre@4.2").unwrap(),
+        ("lustre".into(), Some(Version::new(4, 2, 0)))
+    );
+    assert_eq!(
+        parse_package("lustre@0.4.2").unwrap(),
+        ("lustre".into(), Some(Version::new(0, 4, 2)))
+    );
+}
+
+#[test]
+fn parse_package_with_invalid_version() {
+    assert!(matches!(
+        parse_package("lustre@four"),
+        Err(Error::InvalidVersionFormat { .. })
+    ));
+    assert!(matches!(
+        parse_package("lustre@1.2.3.4"),
+        Err(Error::InvalidVersionFormat { .. })
+    ));
+    assert!(matches!(
+        parse_package("lustre@"),
+        Err(Error::InvalidVersionFormat { .. })
+    ));
+}
+
+#[test]
+fn major_version_range_of_version() {
+    assert_eq!(
+        major_version_range(&Version::new(1, 2, 3)),
+        ">= 1.2.3 and < 2.0.0"
+    );
+    assert_eq!(
+        major_version_range(&Version::new(0, 4, 0)),
+        ">= 0.4.0 and < 1.0.0"
+    );
+}
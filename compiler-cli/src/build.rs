@@ -1,29 +1,105 @@
 use std::{sync::Arc, time::Instant};
 
+use camino::Utf8Path;
+use clap::ValueEnum;
 use gleam_core::{
-    build::{Built, Codegen, Options, ProjectCompiler},
+    build::{timings, Built, Codegen, NullTelemetry, Options, ProjectCompiler, Telemetry, Timings},
     manifest::Manifest,
     paths::ProjectPaths,
+    warning::WarningEmitterIO,
     Result,
 };
+use strum::{Display, EnumString, VariantNames};
 
 use crate::{
     build_lock::BuildLock,
     cli,
-    dependencies::UseManifest,
-    fs::{self, get_current_directory, get_project_root, ConsoleWarningEmitter},
+    dependencies::{UseManifest, UseNetwork},
+    fs::{
+        self, get_current_directory, get_project_root, ConsoleWarningEmitter, JsonWarningEmitter,
+    },
 };
 
+/// How the errors and warnings of a build are printed.
+#[derive(
+    Debug, Display, EnumString, VariantNames, ValueEnum, Clone, Copy, Default, PartialEq, Eq,
+)]
+#[strum(serialize_all = "kebab_case")]
+pub enum MessageFormat {
+    /// Printed for people to read, on stderr
+    #[default]
+    Human,
+    /// Printed as a JSON object per line, on stdout. Nothing else is printed
+    /// to stdout
+    Json,
+}
+
 pub fn download_dependencies() -> Result<Manifest> {
+    download_dependencies_for(MessageFormat::Human)
+}
+
+/// Downloads the dependencies of the project, without reporting the progress
+/// when the messages are printed as JSON, as stdout is only used for them.
+pub fn download_dependencies_for(message_format: MessageFormat) -> Result<Manifest> {
     let paths = crate::find_project_paths()?;
-    crate::dependencies::download(&paths, cli::Reporter::new(), None, UseManifest::Yes)
+    match message_format {
+        MessageFormat::Human => crate::dependencies::download(
+            &paths,
+            cli::Reporter::new(),
+            None,
+            UseManifest::Yes,
+            UseNetwork::from_environment(),
+        ),
+        MessageFormat::Json => crate::dependencies::download(
+            &paths,
+            NullTelemetry,
+            None,
+            UseManifest::Yes,
+            UseNetwork::from_environment(),
+        ),
+    }
 }
 
 pub fn main(options: Options, manifest: Manifest) -> Result<Built> {
+    main_with_message_format(
+        options,
+        manifest,
+        MessageFormat::Human,
+        &Timings::disabled(),
+    )
+}
+
+pub fn main_with_message_format(
+    options: Options,
+    manifest: Manifest,
+    message_format: MessageFormat,
+    timings: &Timings,
+) -> Result<Built> {
+    compile(options, manifest, message_format, timings, false)
+}
+
+/// Builds the project, type checking every module of the root package even if
+/// it has been cached, so the typed AST of all of them is returned.
+pub fn main_recompiling_root_package(options: Options, manifest: Manifest) -> Result<Built> {
+    compile(
+        options,
+        manifest,
+        MessageFormat::Human,
+        &Timings::disabled(),
+        true,
+    )
+}
+
+fn compile(
+    options: Options,
+    manifest: Manifest,
+    message_format: MessageFormat,
+    timings: &Timings,
+    recompile_root_package: bool,
+) -> Result<Built> {
     let paths = crate::find_project_paths()?;
     let perform_codegen = options.codegen;
     let root_config = crate::config::root_config()?;
-    let telemetry = Box::new(cli::Reporter::new());
     let io = fs::ProjectIO::new();
     let start = Instant::now();
     let lock = BuildLock::new_target(
@@ -33,25 +109,64 @@ pub fn main(options: Options, manifest: Manifest) -> Result<Built> {
     )?;
     let current_dir = get_project_root(get_current_directory()?)?;
 
+    // When the messages are printed as JSON the progress is not reported, so
+    // that only the messages are printed to stdout.
+    let (telemetry, warning_emitter): (Box<dyn Telemetry>, Arc<dyn WarningEmitterIO>) =
+        match message_format {
+            MessageFormat::Human => (
+                Box::new(cli::Reporter::new()),
+                Arc::new(ConsoleWarningEmitter),
+            ),
+            MessageFormat::Json => (Box::new(NullTelemetry), Arc::new(JsonWarningEmitter)),
+        };
+
     tracing::info!("Compiling packages");
     let result = {
-        let _guard = lock.lock(telemetry.as_ref());
-        let compiler = ProjectCompiler::new(
+        let _guard = match message_format {
+            MessageFormat::Human => lock.lock(&cli::Reporter::new()),
+            MessageFormat::Json => lock.lock(&NullTelemetry),
+        };
+        let mut compiler = ProjectCompiler::new(
             root_config,
             options,
             manifest.packages,
             telemetry,
-            Arc::new(ConsoleWarningEmitter),
+            warning_emitter,
             ProjectPaths::new(current_dir),
             io,
         );
+        compiler.timings = timings.clone();
+        compiler.recompile_root_package = recompile_root_package;
         compiler.compile()?
     };
 
-    match perform_codegen {
-        Codegen::All | Codegen::DepsOnly => cli::print_compiled(start.elapsed()),
-        Codegen::None => cli::print_checked(start.elapsed()),
+    match (message_format, perform_codegen) {
+        (MessageFormat::Json, _) => (),
+        (MessageFormat::Human, Codegen::All | Codegen::DepsOnly) => {
+            cli::print_compiled(start.elapsed())
+        }
+        (MessageFormat::Human, Codegen::None) => cli::print_checked(start.elapsed()),
     };
 
     Ok(result)
 }
+
+/// Prints a summary of the timings of the build, and writes them to the trace
+/// file in the Chrome trace event format if one is given.
+pub fn report_timings(
+    timings: &Timings,
+    message_format: MessageFormat,
+    trace: Option<&Utf8Path>,
+) -> Result<()> {
+    let recorded = timings.timings();
+    let summary = timings::summary(&recorded);
+    match message_format {
+        MessageFormat::Human => print!("\n{summary}"),
+        // Only the messages are printed to stdout.
+        MessageFormat::Json => eprint!("\n{summary}"),
+    }
+    if let Some(path) = trace {
+        fs::write(path, &timings::chrome_trace(&recorded))?;
+    }
+    Ok(())
+}
@@ -176,12 +176,12 @@ pub fn print_colourful_prefix(prefix: &str, text: &str) {
 
 pub fn stderr_buffer_writer() -> BufferWriter {
     // Don't add color codes to the output if standard error isn't connected to a terminal
-    termcolor::BufferWriter::stderr(color_choice())
+    termcolor::BufferWriter::stderr(color_choice(std::io::stderr().is_terminal()))
 }
 
 pub fn stdout_buffer_writer() -> BufferWriter {
-    // Don't add color codes to the output if standard error isn't connected to a terminal
-    termcolor::BufferWriter::stdout(color_choice())
+    // Don't add color codes to the output if standard output isn't connected to a terminal
+    termcolor::BufferWriter::stdout(color_choice(std::io::stdout().is_terminal()))
 }
 
 fn colour_forced() -> bool {
@@ -192,10 +192,10 @@ fn colour_forced() -> bool {
     }
 }
 
-fn color_choice() -> ColorChoice {
+fn color_choice(is_terminal: bool) -> ColorChoice {
     if colour_forced() {
         termcolor::ColorChoice::Always
-    } else if std::io::stderr().is_terminal() {
+    } else if is_terminal {
         termcolor::ColorChoice::Auto
     } else {
         termcolor::ColorChoice::Never
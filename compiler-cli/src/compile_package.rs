@@ -30,6 +30,7 @@ pub fn command(options: CompilePackage) -> Result<()> {
         Target::Erlang => TargetCodegenConfiguration::Erlang { app_file: None },
         Target::JavaScript => TargetCodegenConfiguration::JavaScript {
             emit_typescript_definitions: false,
+            emit_source_maps: false,
             prelude_location: options
                 .javascript_prelude
                 .ok_or_else(|| Error::JavaScriptPreludeRequired)?,
@@ -55,6 +56,7 @@ pub fn command(options: CompilePackage) -> Result<()> {
         &warnings,
         &mut type_manifests,
         &mut defined_modules,
+        &mut im::HashSet::new(),
         &mut StaleTracker::default(),
         &NullTelemetry,
     )?;
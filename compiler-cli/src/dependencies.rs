@@ -8,14 +8,14 @@ use ecow::EcoString;
 use flate2::read::GzDecoder;
 use futures::future;
 use gleam_core::{
-    build::{Mode, Target, Telemetry},
+    build::{Mode, NullTelemetry, Target, Telemetry},
     config::PackageConfig,
-    dependency,
+    dependency::{self, LatestVersions, PackageFetcher as _},
     error::{FileIoAction, FileKind, StandardIoAction},
     hex::{self, HEXPM_PUBLIC_KEY},
     io::{HttpClient as _, TarUnpacker, WrappedReader},
     manifest::{Base16Checksum, Manifest, ManifestPackage, ManifestPackageSource},
-    paths::ProjectPaths,
+    paths::{global_package_cache_package_tarball, ProjectPaths},
     requirement::Requirement,
     Error, Result,
 };
@@ -31,6 +31,10 @@ use crate::{
     http::HttpClient,
 };
 
+mod graph;
+
+pub use graph::GraphFormat;
+
 pub fn list() -> Result<()> {
     let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
     let project = fs::get_project_root(fs::get_current_directory()?)?;
@@ -43,10 +47,35 @@ pub fn list() -> Result<()> {
         &config,
         &cli::Reporter::new(),
         UseManifest::Yes,
+        UseNetwork::from_environment(),
     )?;
     list_manifest_packages(std::io::stdout(), manifest)
 }
 
+/// Prints the dependency graph of the project, resolving the versions of its
+/// dependencies first if there is no manifest.
+pub fn graph(format: GraphFormat) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let paths = crate::find_project_paths()?;
+    let config = crate::config::root_config()?;
+    let (_, manifest) = get_manifest(
+        &paths,
+        runtime.handle().clone(),
+        Mode::Dev,
+        &config,
+        // Progress is not reported so that only the graph is printed to stdout
+        &NullTelemetry,
+        UseManifest::Yes,
+        UseNetwork::from_environment(),
+    )?;
+    let graph = graph::DependencyGraph::new(&config, &manifest);
+    match format {
+        GraphFormat::Dot => print!("{}", graph.to_dot()),
+        GraphFormat::Json => print!("{}", graph.to_json()),
+    }
+    Ok(())
+}
+
 fn list_manifest_packages<W: std::io::Write>(mut buffer: W, manifest: Manifest) -> Result<()> {
     manifest
         .packages
@@ -106,26 +135,222 @@ zzz 0.4.0
     )
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum UseManifest {
     Yes,
     No,
+    /// Use the manifest, but resolve the versions of these packages anew.
+    Unlocking(Vec<EcoString>),
+}
+
+/// Whether Hex may be used to resolve and download dependencies, or only the
+/// packages already in the local cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UseNetwork {
+    Yes,
+    No,
+}
+
+impl UseNetwork {
+    /// The network is not used when the `GLEAM_OFFLINE` environment variable
+    /// is set.
+    pub fn from_environment() -> Self {
+        match std::env::var_os("GLEAM_OFFLINE") {
+            Some(value) if !value.is_empty() => Self::No,
+            _ => Self::Yes,
+        }
+    }
 }
 
-pub fn update() -> Result<()> {
+pub fn update(interactive: bool) -> Result<()> {
     let paths = crate::find_project_paths()?;
-    _ = download(&paths, cli::Reporter::new(), None, UseManifest::No)?;
+    let use_manifest = if interactive {
+        let mut packages = vec![];
+        for package in outdated_packages(&paths)? {
+            let Some(compatible) = package.compatible.filter(|v| v > &package.current) else {
+                continue;
+            };
+            let question = format!(
+                "Update {} from {} to {}?",
+                package.name, package.current, compatible
+            );
+            if cli::confirm(&question)? {
+                packages.push(package.name);
+            }
+        }
+        if packages.is_empty() {
+            return Ok(());
+        }
+        UseManifest::Unlocking(packages)
+    } else {
+        UseManifest::No
+    };
+    _ = download(
+        &paths,
+        cli::Reporter::new(),
+        None,
+        use_manifest,
+        UseNetwork::from_environment(),
+    )?;
     Ok(())
 }
 
+/// A direct dependency of the project with newer releases than the one it is
+/// locked to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OutdatedPackage {
+    name: EcoString,
+    current: Version,
+    /// The latest release satisfying the requirement in `gleam.toml`.
+    compatible: Option<Version>,
+    latest: Option<Version>,
+}
+
+pub fn outdated() -> Result<()> {
+    let paths = crate::find_project_paths()?;
+    let packages = outdated_packages(&paths)?;
+    write_outdated_packages(std::io::stdout(), &packages)
+}
+
+/// Looks up the releases of the Hex dependencies of the project to find the
+/// ones that have newer versions than those in the manifest.
+fn outdated_packages(paths: &ProjectPaths) -> Result<Vec<OutdatedPackage>> {
+    let config = crate::config::read(paths.root_config())?;
+    let requirements = config.all_dependencies()?;
+    let manifest = read_manifest_from_disc(paths)?;
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let fetcher = PackageFetcher::boxed(runtime.handle().clone());
+
+    let mut outdated = vec![];
+    for package in manifest
+        .packages
+        .iter()
+        .filter(|package| package.is_hex())
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+    {
+        let Some(Requirement::Hex { version: range }) = requirements.get(&package.name) else {
+            continue;
+        };
+        let release = fetcher
+            .get_dependencies(&package.name)
+            .map_err(|error| Error::Hex(error.to_string()))?;
+        let LatestVersions { compatible, latest } = dependency::latest_versions(&release, range);
+        let is_newer = |version: &Option<Version>| {
+            version
+                .as_ref()
+                .is_some_and(|version| version > &package.version)
+        };
+        if is_newer(&compatible) || is_newer(&latest) {
+            outdated.push(OutdatedPackage {
+                name: package.name.clone(),
+                current: package.version.clone(),
+                compatible,
+                latest,
+            });
+        }
+    }
+    Ok(outdated)
+}
+
+fn write_outdated_packages<W: std::io::Write>(
+    mut buffer: W,
+    packages: &[OutdatedPackage],
+) -> Result<()> {
+    let version = |version: &Option<Version>| {
+        version
+            .as_ref()
+            .map(|version| version.to_string())
+            .unwrap_or_else(|| "-".into())
+    };
+    let header = [
+        "Package".to_string(),
+        "Current".into(),
+        "Compatible".into(),
+        "Latest".into(),
+    ];
+    let rows = packages.iter().map(|package| {
+        [
+            package.name.to_string(),
+            package.current.to_string(),
+            version(&package.compatible),
+            version(&package.latest),
+        ]
+    });
+    let rows = std::iter::once(header).chain(rows).collect_vec();
+
+    let mut widths = [0; 4];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let result = if packages.is_empty() {
+        writeln!(buffer, "All dependencies are up to date")
+    } else {
+        rows.iter().try_for_each(|row| {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .join("  ");
+            writeln!(buffer, "{}", line.trim_end())
+        })
+    };
+    result.map_err(|e| Error::StandardIo {
+        action: StandardIoAction::Write,
+        err: Some(e.kind()),
+    })
+}
+
+#[test]
+fn outdated_packages_format() {
+    let mut buffer = vec![];
+    let packages = [
+        OutdatedPackage {
+            name: "gleam_stdlib".into(),
+            current: Version::new(0, 34, 0),
+            compatible: Some(Version::new(0, 34, 1)),
+            latest: Some(Version::new(1, 0, 0)),
+        },
+        OutdatedPackage {
+            name: "wibble".into(),
+            current: Version::new(1, 2, 3),
+            compatible: None,
+            latest: Some(Version::new(2, 0, 0)),
+        },
+    ];
+    write_outdated_packages(&mut buffer, &packages).unwrap();
+    assert_eq!(
+        std::str::from_utf8(&buffer).unwrap(),
+        r#"Package       Current  Compatible  Latest
+gleam_stdlib  0.34.0   0.34.1      1.0.0
+wibble        1.2.3    -           2.0.0
+"#
+    )
+}
+
+#[test]
+fn no_outdated_packages_format() {
+    let mut buffer = vec![];
+    write_outdated_packages(&mut buffer, &[]).unwrap();
+    assert_eq!(
+        std::str::from_utf8(&buffer).unwrap(),
+        "All dependencies are up to date\n"
+    )
+}
+
 pub fn download<Telem: Telemetry>(
     paths: &ProjectPaths,
     telemetry: Telem,
-    new_package: Option<(Vec<String>, bool)>,
+    new_package: Option<(Vec<(EcoString, Requirement)>, bool)>,
     // If true we read the manifest from disc. If not set then we ignore any
     // manifest which will result in the latest versions of the dependency
     // packages being resolved (not the locked ones).
     use_manifest: UseManifest,
+    // If not set then only the packages in the local cache are used, and an
+    // error is returned rather than using Hex.
+    use_network: UseNetwork,
 ) -> Result<Manifest> {
     let span = tracing::info_span!("download_deps");
     let _enter = span.enter();
@@ -147,12 +372,11 @@ pub fn download<Telem: Telemetry>(
 
     // Insert the new packages to add, if it exists
     if let Some((packages, dev)) = new_package {
-        for package in packages {
-            let version = Requirement::hex(">= 0.0.0");
+        for (package, requirement) in packages {
             let _ = if dev {
-                config.dev_dependencies.insert(package.into(), version)
+                config.dev_dependencies.insert(package, requirement)
             } else {
-                config.dependencies.insert(package.into(), version)
+                config.dependencies.insert(package, requirement)
             };
         }
     }
@@ -168,6 +392,7 @@ pub fn download<Telem: Telemetry>(
         &config,
         &telemetry,
         use_manifest,
+        use_network,
     )?;
     let local = LocalPackages::read_from_disc(paths)?;
 
@@ -182,6 +407,7 @@ pub fn download<Telem: Telemetry>(
         &local,
         project_name,
         &telemetry,
+        use_network,
     ))?;
 
     if manifest_updated {
@@ -202,27 +428,39 @@ async fn add_missing_packages<Telem: Telemetry>(
     local: &LocalPackages,
     project_name: EcoString,
     telemetry: &Telem,
+    use_network: UseNetwork,
 ) -> Result<(), Error> {
-    let missing_packages = local.missing_local_packages(manifest, &project_name);
-
-    let mut num_to_download = 0;
-    let mut missing_hex_packages = missing_packages
+    let missing_hex_packages = local
+        .missing_local_packages(manifest, &project_name)
         .into_iter()
         .filter(|package| package.is_hex())
-        .map(|package| {
-            num_to_download += 1;
-            package
-        })
-        .peekable();
+        .collect_vec();
+    let num_to_download = missing_hex_packages.len();
+
+    // Offline the packages can only be taken from the local cache, so any
+    // that are not in it are missing
+    if use_network == UseNetwork::No {
+        let packages = missing_hex_packages
+            .iter()
+            .filter(|package| {
+                !global_package_cache_package_tarball(&package.name, &package.version.to_string())
+                    .is_file()
+            })
+            .map(|package| package.name.clone())
+            .collect_vec();
+        if !packages.is_empty() {
+            return Err(Error::PackagesNotCached { packages });
+        }
+    }
 
     // If we need to download at-least one package
-    if missing_hex_packages.peek().is_some() {
+    if !missing_hex_packages.is_empty() {
         let http = HttpClient::boxed();
         let downloader = hex::Downloader::new(fs.clone(), fs, http, Untar::boxed(), paths.clone());
         let start = Instant::now();
         telemetry.downloading_package("packages");
         downloader
-            .download_hex_packages(missing_hex_packages, &project_name)
+            .download_hex_packages(missing_hex_packages.into_iter(), &project_name)
             .await?;
         telemetry.packages_downloaded(start, num_to_download);
     }
@@ -268,7 +506,7 @@ fn remove_extra_packages<Telem: Telemetry>(
     Ok(())
 }
 
-fn read_manifest_from_disc(paths: &ProjectPaths) -> Result<Manifest> {
+pub fn read_manifest_from_disc(paths: &ProjectPaths) -> Result<Manifest> {
     tracing::debug!("reading_manifest_toml");
     let manifest_path = paths.manifest();
     let toml = crate::fs::read(&manifest_path)?;
@@ -484,6 +722,7 @@ fn get_manifest<Telem: Telemetry>(
     config: &PackageConfig,
     telemetry: &Telem,
     use_manifest: UseManifest,
+    use_network: UseNetwork,
 ) -> Result<(bool, Manifest)> {
     // If there's no manifest (or we have been asked not to use it) then resolve
     // the versions anew
@@ -496,15 +735,24 @@ fn get_manifest<Telem: Telemetry>(
             tracing::debug!("ignoring_manifest");
             true
         }
-        UseManifest::Yes => false,
+        UseManifest::Yes | UseManifest::Unlocking(_) => false,
     };
 
     if should_resolve {
+        ensure_network_usable(use_network)?;
         let manifest = resolve_versions(runtime, mode, paths, config, None, telemetry)?;
         return Ok((true, manifest));
     }
 
-    let manifest = read_manifest_from_disc(paths)?;
+    let mut manifest = read_manifest_from_disc(paths)?;
+
+    // Packages are unlocked by treating their requirements as having changed
+    // since the manifest was written, so their versions are resolved anew.
+    if let UseManifest::Unlocking(packages) = &use_manifest {
+        manifest
+            .requirements
+            .retain(|name, _| !packages.contains(name));
+    }
 
     // If the config has unchanged since the manifest was written then it is up
     // to date so we can return it unmodified.
@@ -517,11 +765,21 @@ fn get_manifest<Telem: Telemetry>(
         Ok((false, manifest))
     } else {
         tracing::debug!("manifest_outdated");
+        ensure_network_usable(use_network)?;
         let manifest = resolve_versions(runtime, mode, paths, config, Some(&manifest), telemetry)?;
         Ok((true, manifest))
     }
 }
 
+/// Resolving versions looks up the releases of packages with Hex, which can
+/// only be done when the network may be used.
+fn ensure_network_usable(use_network: UseNetwork) -> Result<()> {
+    match use_network {
+        UseNetwork::Yes => Ok(()),
+        UseNetwork::No => Err(Error::OfflineResolutionRequired),
+    }
+}
+
 fn is_same_requirements(
     requirements1: &HashMap<EcoString, Requirement>,
     requirements2: &HashMap<EcoString, Requirement>,
@@ -703,7 +961,7 @@ fn resolve_versions<Telem: Telemetry>(
                 &mut provided_packages,
                 &mut vec![],
             )?,
-            Requirement::Git { git } => {
+            Requirement::Git { git, .. } => {
                 provide_git_package(name.clone(), &git, project_paths, &mut provided_packages)?
             }
         };
@@ -767,6 +1025,7 @@ fn provide_local_package(
 }
 
 /// Provide a package from a git repository
+/// The package is in the subdirectory of the repository if one is given.
 fn provide_git_package(
     _package_name: EcoString,
     _repo: &str,
@@ -845,7 +1104,7 @@ fn provide_package(
                     parents,
                 )?
             }
-            Requirement::Git { git } => {
+            Requirement::Git { git, .. } => {
                 provide_git_package(name.clone(), &git, project_paths, provided)?
             }
         };
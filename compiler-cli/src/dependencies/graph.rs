@@ -0,0 +1,263 @@
+//! The dependency graph of a project, as printed by `gleam deps graph` so it
+//! can be visualised with Graphviz or audited by other tools.
+
+use std::fmt::Write;
+
+use clap::ValueEnum;
+use ecow::EcoString;
+use gleam_core::{
+    config::PackageConfig,
+    manifest::{Manifest, ManifestPackageSource},
+};
+use hexpm::version::Version;
+use itertools::Itertools;
+use serde::Serialize;
+use strum::{Display, EnumString, VariantNames};
+
+#[derive(Debug, Display, EnumString, VariantNames, ValueEnum, Clone, Copy, Default)]
+#[strum(serialize_all = "kebab_case")]
+pub enum GraphFormat {
+    /// A Graphviz DOT graph
+    #[default]
+    Dot,
+    /// A JSON object with the packages and their dependencies
+    Json,
+}
+
+/// The packages the project depends on, with the versions locked in its
+/// manifest.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct DependencyGraph {
+    name: EcoString,
+    version: Version,
+    /// The packages the project depends on directly.
+    dependencies: Vec<EcoString>,
+    packages: Vec<GraphPackage>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct GraphPackage {
+    name: EcoString,
+    version: Version,
+    source: &'static str,
+    /// Whether the project depends on the package itself, rather than only
+    /// through other packages.
+    direct: bool,
+    dependencies: Vec<EcoString>,
+}
+
+impl DependencyGraph {
+    pub fn new(config: &PackageConfig, manifest: &Manifest) -> Self {
+        let dependencies = config
+            .dependencies
+            .keys()
+            .chain(config.dev_dependencies.keys())
+            .cloned()
+            .sorted()
+            .dedup()
+            .collect_vec();
+
+        let packages = manifest
+            .packages
+            .iter()
+            .sorted_by(|a, b| a.name.cmp(&b.name))
+            .map(|package| GraphPackage {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                source: match package.source {
+                    ManifestPackageSource::Hex { .. } => "hex",
+                    ManifestPackageSource::Git { .. } => "git",
+                    ManifestPackageSource::Local { .. } => "path",
+                },
+                direct: dependencies.contains(&package.name),
+                dependencies: package.requirements.iter().cloned().sorted().collect(),
+            })
+            .collect();
+
+        Self {
+            name: config.name.clone(),
+            version: config.version.clone(),
+            dependencies,
+            packages,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = serde_json::to_string_pretty(self).expect("dependency graph to json");
+        json.push('\n');
+        json
+    }
+
+    /// The graph in the DOT language. Each package is labelled with its
+    /// version and source, and the packages the project depends on directly
+    /// are drawn in bold.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph dependencies {\n");
+        let _ = writeln!(
+            dot,
+            "  \"{name}\" [label=\"{name}\\n{version}\", shape=box];",
+            name = self.name,
+            version = self.version,
+        );
+        for package in &self.packages {
+            let style = if package.direct { ", style=bold" } else { "" };
+            let _ = writeln!(
+                dot,
+                "  \"{name}\" [label=\"{name}\\n{version} ({source})\"{style}];",
+                name = package.name,
+                version = package.version,
+                source = package.source,
+            );
+        }
+        for dependency in &self.dependencies {
+            let _ = writeln!(dot, "  \"{}\" -> \"{dependency}\";", self.name);
+        }
+        for package in &self.packages {
+            for dependency in &package.dependencies {
+                let _ = writeln!(dot, "  \"{}\" -> \"{dependency}\";", package.name);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use camino::Utf8PathBuf;
+    use gleam_core::{
+        manifest::{Base16Checksum, ManifestPackage},
+        requirement::Requirement,
+    };
+
+    use super::*;
+
+    fn graph() -> DependencyGraph {
+        let mut config = PackageConfig::default();
+        config.name = "app".into();
+        config.version = Version::new(1, 0, 0);
+        config.dependencies = [
+            ("wibble".into(), Requirement::hex("~> 1.0")),
+            ("local".into(), Requirement::path("../local")),
+        ]
+        .into();
+        config.dev_dependencies = [("gleeunit".into(), Requirement::hex("~> 1.0"))].into();
+
+        let package = |name: &str, version, requirements: &[&str], source| ManifestPackage {
+            name: name.into(),
+            version,
+            build_tools: vec!["gleam".into()],
+            otp_app: None,
+            requirements: requirements.iter().map(|name| (*name).into()).collect(),
+            source,
+        };
+        let hex = || ManifestPackageSource::Hex {
+            outer_checksum: Base16Checksum(vec![1, 2, 3]),
+        };
+        let manifest = Manifest {
+            requirements: HashMap::new(),
+            packages: vec![
+                package(
+                    "wibble",
+                    Version::new(1, 2, 0),
+                    &["wobble", "gleam_stdlib"],
+                    hex(),
+                ),
+                package("gleam_stdlib", Version::new(0, 40, 0), &[], hex()),
+                package("gleeunit", Version::new(1, 0, 0), &["gleam_stdlib"], hex()),
+                package(
+                    "wobble",
+                    Version::new(0, 1, 0),
+                    &[],
+                    ManifestPackageSource::Git {
+                        repo: "https://github.com/wobble/wobble".into(),
+                        commit: "abc".into(),
+                    },
+                ),
+                package(
+                    "local",
+                    Version::new(0, 1, 0),
+                    &[],
+                    ManifestPackageSource::Local {
+                        path: Utf8PathBuf::from("/home/local"),
+                    },
+                ),
+            ],
+        };
+        DependencyGraph::new(&config, &manifest)
+    }
+
+    #[test]
+    fn dot_graph() {
+        assert_eq!(
+            graph().to_dot(),
+            r#"digraph dependencies {
+  "app" [label="app\n1.0.0", shape=box];
+  "gleam_stdlib" [label="gleam_stdlib\n0.40.0 (hex)"];
+  "gleeunit" [label="gleeunit\n1.0.0 (hex)", style=bold];
+  "local" [label="local\n0.1.0 (path)", style=bold];
+  "wibble" [label="wibble\n1.2.0 (hex)", style=bold];
+  "wobble" [label="wobble\n0.1.0 (git)"];
+  "app" -> "gleeunit";
+  "app" -> "local";
+  "app" -> "wibble";
+  "gleeunit" -> "gleam_stdlib";
+  "wibble" -> "gleam_stdlib";
+  "wibble" -> "wobble";
+}
+"#
+        );
+    }
+
+    #[test]
+    fn json_graph() {
+        let json: serde_json::Value = serde_json::from_str(&graph().to_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "name": "app",
+                "version": "1.0.0",
+                "dependencies": ["gleeunit", "local", "wibble"],
+                "packages": [
+                    {
+                        "name": "gleam_stdlib",
+                        "version": "0.40.0",
+                        "source": "hex",
+                        "direct": false,
+                        "dependencies": [],
+                    },
+                    {
+                        "name": "gleeunit",
+                        "version": "1.0.0",
+                        "source": "hex",
+                        "direct": true,
+                        "dependencies": ["gleam_stdlib"],
+                    },
+                    {
+                        "name": "local",
+                        "version": "0.1.0",
+                        "source": "path",
+                        "direct": true,
+                        "dependencies": [],
+                    },
+                    {
+                        "name": "wibble",
+                        "version": "1.2.0",
+                        "source": "hex",
+                        "direct": true,
+                        "dependencies": ["gleam_stdlib", "wobble"],
+                    },
+                    {
+                        "name": "wobble",
+                        "version": "0.1.0",
+                        "source": "git",
+                        "direct": false,
+                        "dependencies": [],
+                    },
+                ],
+            })
+        );
+    }
+}
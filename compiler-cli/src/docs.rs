@@ -1,6 +1,12 @@
+mod serve;
+
 use std::time::{Instant, SystemTime};
 
 use camino::{Utf8Path, Utf8PathBuf};
+use clap::ValueEnum;
+use strum::{Display, EnumString, VariantNames};
+
+pub use serve::{serve, ServeOptions};
 
 use crate::{cli, fs::ProjectIO, hex::ApiKeyCommand, http::HttpClient};
 use gleam_core::{
@@ -10,6 +16,8 @@ use gleam_core::{
     error::Error,
     hex,
     io::HttpClient as _,
+    manifest::Manifest,
+    paths::ProjectPaths,
     Result,
 };
 
@@ -52,13 +60,43 @@ impl ApiKeyCommand for RemoveCommand {
     }
 }
 
+#[derive(Debug, Display, EnumString, VariantNames, ValueEnum, Clone, Copy, Default)]
+#[strum(serialize_all = "kebab_case")]
+pub enum Format {
+    /// HTML pages with search, as published to HexDocs
+    #[default]
+    Html,
+    /// A Markdown file for each module, for wikis and static site generators
+    Markdown,
+}
+
 #[derive(Debug)]
 pub struct BuildOptions {
     /// Whether to open the docs after building.
     pub open: bool,
+    pub format: Format,
 }
 
 pub fn build(options: BuildOptions) -> Result<()> {
+    let config = crate::config::root_config()?;
+    let index = render(options.format)?;
+
+    println!(
+        "\nThe documentation for {package} has been rendered to \n{index}",
+        package = config.name,
+    );
+
+    if options.open {
+        open_docs(&index)?;
+    }
+
+    // We're done!
+    Ok(())
+}
+
+/// Builds the project and renders its documentation, returning the path of
+/// the index page.
+fn render(format: Format) -> Result<Utf8PathBuf> {
     let paths = crate::find_project_paths()?;
     let config = crate::config::root_config()?;
 
@@ -66,6 +104,7 @@ pub fn build(options: BuildOptions) -> Result<()> {
     crate::fs::delete_directory(&paths.build_directory_for_target(Mode::Prod, config.target))?;
 
     let out = paths.build_documentation_directory(&config.name);
+    let manifest = crate::build::download_dependencies()?;
     let mut built = crate::build::main(
         Options {
             mode: Mode::Prod,
@@ -73,29 +112,62 @@ pub fn build(options: BuildOptions) -> Result<()> {
             codegen: Codegen::All,
             warnings_as_errors: false,
             root_target_support: TargetSupport::Enforced,
+            doc_tests: false,
         },
-        crate::build::download_dependencies()?,
+        manifest.clone(),
     )?;
-    let outputs = build_documentation(&config, &mut built.root_package)?;
+    let (outputs, index) = match format {
+        Format::Html => {
+            let mut outputs = build_documentation(&config, &mut built.root_package, &manifest)?;
+            let runtime =
+                tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+            outputs.extend(versions_manifest(runtime.handle(), &config)?);
+            (outputs, "index.html")
+        }
+        Format::Markdown => (
+            build_markdown_documentation(&config, &mut built.root_package)?,
+            "index.md",
+        ),
+    };
 
     // Write
     crate::fs::delete_directory(&out)?;
     crate::fs::write_outputs_under(&outputs, &out)?;
 
-    let index_html = out.join("index.html");
+    Ok(out.join(index))
+}
 
-    println!(
-        "\nThe documentation for {package} has been rendered to \n{index_html}",
-        package = config.name,
-        index_html = index_html
-    );
+/// The manifest of the releases whose documentation is published under the
+/// `versions_url` set in `gleam.toml`, with this release added. If no manifest
+/// has been published yet the new one lists only this release.
+fn versions_manifest(
+    handle: &tokio::runtime::Handle,
+    config: &PackageConfig,
+) -> Result<Option<gleam_core::io::OutputFile>> {
+    let Some(url) = gleam_core::docs::versions_manifest_url(config) else {
+        return Ok(None);
+    };
+    let published = download_text(handle, &url)?;
+    Ok(gleam_core::docs::generate_versions_manifest(
+        config,
+        published.as_deref(),
+    ))
+}
 
-    if options.open {
-        open_docs(&index_html)?;
+/// Downloads a text file, returning `None` if there is no file at the URL.
+pub(crate) fn download_text(handle: &tokio::runtime::Handle, url: &str) -> Result<Option<String>> {
+    let request = http::Request::get(url).body(vec![]).map_err(Error::http)?;
+    let response = handle.block_on(HttpClient::new().send(request))?;
+    let status = response.status();
+    if status == http::StatusCode::NOT_FOUND {
+        return Ok(None);
     }
-
-    // We're done!
-    Ok(())
+    if !status.is_success() {
+        return Err(Error::Http(format!("{url} returned status {status}")));
+    }
+    String::from_utf8(response.into_body())
+        .map(Some)
+        .map_err(Error::http)
 }
 
 /// Opens the indicated path in the default program configured by the system.
@@ -114,24 +186,21 @@ fn open_docs(path: &Utf8Path) -> Result<()> {
 pub(crate) fn build_documentation(
     config: &PackageConfig,
     compiled: &mut Package,
+    manifest: &Manifest,
 ) -> Result<Vec<gleam_core::io::OutputFile>, Error> {
     compiled.attach_doc_and_module_comments();
     cli::print_generating_documentation();
     let paths = crate::find_project_paths()?;
-    let mut pages = vec![DocsPage {
-        title: "README".into(),
-        path: "index.html".into(),
-        source: paths.readme(), // TODO: support non markdown READMEs. Or a default if there is none.
-    }];
-    pages.extend(config.documentation.pages.iter().cloned());
+    let pages = documentation_pages(config, &paths);
     let mut outputs = gleam_core::docs::generate_html(
         &paths,
         config,
         compiled.modules.as_slice(),
         &pages,
+        manifest,
         ProjectIO::new(),
         SystemTime::now(),
-    );
+    )?;
 
     outputs.push(gleam_core::docs::generate_json_package_interface(
         Utf8PathBuf::from("package-interface.json"),
@@ -140,6 +209,34 @@ pub(crate) fn build_documentation(
     Ok(outputs)
 }
 
+fn build_markdown_documentation(
+    config: &PackageConfig,
+    compiled: &mut Package,
+) -> Result<Vec<gleam_core::io::OutputFile>, Error> {
+    compiled.attach_doc_and_module_comments();
+    cli::print_generating_documentation();
+    let paths = crate::find_project_paths()?;
+    let pages = documentation_pages(config, &paths);
+    Ok(gleam_core::docs::generate_markdown(
+        &paths,
+        config,
+        compiled.modules.as_slice(),
+        &pages,
+        ProjectIO::new(),
+    ))
+}
+
+/// The README followed by the user-supplied pages from `gleam.toml`.
+fn documentation_pages(config: &PackageConfig, paths: &ProjectPaths) -> Vec<DocsPage> {
+    let mut pages = vec![DocsPage {
+        title: "README".into(),
+        path: "index.html".into(),
+        source: paths.readme(), // TODO: support non markdown READMEs. Or a default if there is none.
+    }];
+    pages.extend(config.documentation.pages.iter().cloned());
+    pages
+}
+
 struct PublishCommand {
     config: PackageConfig,
     archive: Vec<u8>,
@@ -157,17 +254,19 @@ impl PublishCommand {
         // Reset the build directory so we know the state of the project
         crate::fs::delete_directory(&paths.build_directory_for_target(Mode::Prod, config.target))?;
 
+        let manifest = crate::build::download_dependencies()?;
         let mut built = crate::build::main(
             Options {
                 root_target_support: TargetSupport::Enforced,
+                doc_tests: false,
                 warnings_as_errors: false,
                 codegen: Codegen::All,
                 mode: Mode::Prod,
                 target: None,
             },
-            crate::build::download_dependencies()?,
+            manifest.clone(),
         )?;
-        let outputs = build_documentation(&config, &mut built.root_package)?;
+        let outputs = build_documentation(&config, &mut built.root_package, &manifest)?;
         let archive = crate::fs::create_tar_archive(outputs)?;
         Ok(Self { config, archive })
     }
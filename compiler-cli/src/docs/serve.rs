@@ -0,0 +1,317 @@
+//! `gleam docs serve`: a small HTTP server for the rendered documentation
+//! that rebuilds it when the project changes and reloads the pages open in
+//! the browser.
+//!
+//! Pages are told to reload with a server-sent event from the
+//! `/__gleam_reload` endpoint, which a script injected into each HTML page
+//! listens to. The script is added when a page is served, so the rendered
+//! documentation is the same as that from `gleam docs build`.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Condvar, Mutex},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use gleam_core::{error::Error, paths::ProjectPaths, Result};
+
+use super::Format;
+
+const RELOAD_PATH: &str = "/__gleam_reload";
+
+const RELOAD_SCRIPT: &str = r#"<script>
+  new EventSource("/__gleam_reload").onmessage = () => window.location.reload();
+</script>
+"#;
+
+/// How often the project is checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often a comment is sent to each open reload event stream, so that the
+/// connections of pages that have been closed are noticed.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug)]
+pub struct ServeOptions {
+    pub port: u16,
+    /// Whether to open the docs after building.
+    pub open: bool,
+}
+
+pub fn serve(options: ServeOptions) -> Result<()> {
+    let paths = crate::find_project_paths()?;
+    let config = crate::config::root_config()?;
+    let index = super::render(Format::Html)?;
+    let root = index
+        .parent()
+        .expect("documentation index has a directory")
+        .to_path_buf();
+
+    let address = format!("127.0.0.1:{}", options.port);
+    let listener = TcpListener::bind(&address).map_err(|error| Error::FailedToServeDocs {
+        address: address.clone(),
+        error: error.to_string(),
+    })?;
+    let builds = Arc::new(Builds::default());
+    {
+        let builds = builds.clone();
+        let _ = thread::spawn(move || listen(listener, root, builds));
+    }
+
+    let url = format!("http://{address}/");
+    println!(
+        "\nThe documentation for {package} is being served at\n{url}\n\nPress Ctrl+C to stop.",
+        package = config.name
+    );
+    if options.open {
+        super::open_docs(Utf8Path::new(&url))?;
+    }
+
+    let mut watched = watched_files(&paths);
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let latest = watched_files(&paths);
+        if latest == watched {
+            continue;
+        }
+        watched = latest;
+
+        match super::render(Format::Html) {
+            Ok(_) => {
+                crate::cli::print_colourful_prefix("Reloading", "documentation");
+                builds.finished();
+            }
+            Err(error) => {
+                let stderr = crate::cli::stderr_buffer_writer();
+                let mut buffer = stderr.buffer();
+                error.pretty(&mut buffer);
+                stderr.print(&buffer).expect("Documentation error writing");
+            }
+        }
+    }
+}
+
+/// The count of successful rebuilds, which the reload event streams wait on.
+#[derive(Debug, Default)]
+struct Builds {
+    count: Mutex<u64>,
+    changed: Condvar,
+}
+
+impl Builds {
+    fn finished(&self) {
+        *self.count.lock().expect("builds lock") += 1;
+        self.changed.notify_all();
+    }
+
+    fn current(&self) -> u64 {
+        *self.count.lock().expect("builds lock")
+    }
+
+    /// Waits for a build after the given one, returning true if there has
+    /// been one before the timeout.
+    fn wait_for_build_after(&self, build: u64, timeout: Duration) -> bool {
+        let count = self.count.lock().expect("builds lock");
+        let (count, _) = self
+            .changed
+            .wait_timeout_while(count, timeout, |count| *count == build)
+            .expect("builds lock");
+        *count != build
+    }
+}
+
+/// The modification times of the files the documentation is rendered from:
+/// the modules, the README, `gleam.toml`, and any documentation pages.
+fn watched_files(paths: &ProjectPaths) -> Vec<(Utf8PathBuf, Option<SystemTime>)> {
+    let modified = |path: &Utf8Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    let mut files: Vec<_> = walkdir::WalkDir::new(paths.src_directory())
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| Utf8PathBuf::from_path_buf(entry.into_path()).ok())
+        .map(|path| {
+            let time = modified(&path);
+            (path, time)
+        })
+        .collect();
+
+    let mut other_files = vec![paths.readme(), paths.root_config()];
+    if let Ok(config) = crate::config::root_config() {
+        other_files.extend(
+            config
+                .documentation
+                .pages
+                .iter()
+                .map(|page| paths.root().join(&page.source)),
+        );
+    }
+    for path in other_files {
+        let time = modified(&path);
+        files.push((path, time));
+    }
+
+    files.sort();
+    files
+}
+
+fn listen(listener: TcpListener, root: Utf8PathBuf, builds: Arc<Builds>) {
+    for stream in listener.incoming().filter_map(|stream| stream.ok()) {
+        let root = root.clone();
+        let builds = builds.clone();
+        let _ = thread::spawn(move || {
+            // Errors writing a response mean the browser has gone away, so
+            // there is nothing to be done with them.
+            let _ = respond(stream, &root, &builds);
+        });
+    }
+}
+
+fn respond(mut stream: TcpStream, root: &Utf8Path, builds: &Builds) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    let _ = reader.read_line(&mut request_line)?;
+    // The headers are not needed, but are read so the browser isn't cut off
+    // while sending them.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header.trim() != "" {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return write_response(&mut stream, "400 Bad Request", "text/plain", b"Bad request");
+    };
+    if method != "GET" {
+        return write_response(
+            &mut stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            b"Method not allowed",
+        );
+    }
+
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+    if path == RELOAD_PATH {
+        return reload_events(stream, builds);
+    }
+
+    let Some(file) = file_path(root, path) else {
+        return write_response(&mut stream, "404 Not Found", "text/plain", b"Not found");
+    };
+    let Ok(content) = std::fs::read(&file) else {
+        return write_response(&mut stream, "404 Not Found", "text/plain", b"Not found");
+    };
+
+    let content_type = content_type(&file);
+    let content = match content_type {
+        "text/html" => with_reload_script(content),
+        _ => content,
+    };
+    write_response(&mut stream, "200 OK", content_type, &content)
+}
+
+/// The file a request path refers to, or nothing if it is outside of the
+/// documentation.
+fn file_path(root: &Utf8Path, request_path: &str) -> Option<Utf8PathBuf> {
+    let relative = Utf8Path::new(request_path.trim_start_matches('/'));
+    if !relative
+        .components()
+        .all(|component| matches!(component, Utf8Component::Normal(_)))
+    {
+        return None;
+    }
+    let path = root.join(relative);
+    if path.is_dir() {
+        Some(path.join("index.html"))
+    } else {
+        Some(path)
+    }
+}
+
+fn content_type(path: &Utf8Path) -> &'static str {
+    match path.extension() {
+        Some("html") => "text/html",
+        Some("css") => "text/css",
+        Some("js") => "text/javascript",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("ico") => "image/x-icon",
+        Some("woff2") => "font/woff2",
+        Some("md") => "text/markdown",
+        _ => "application/octet-stream",
+    }
+}
+
+fn with_reload_script(html: Vec<u8>) -> Vec<u8> {
+    let html = String::from_utf8_lossy(&html);
+    match html.rfind("</body>") {
+        Some(end) => {
+            let (before, after) = html.split_at(end);
+            format!("{before}{RELOAD_SCRIPT}{after}")
+        }
+        None => format!("{html}{RELOAD_SCRIPT}"),
+    }
+    .into_bytes()
+}
+
+fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+/// An event stream that sends an event once the documentation has been
+/// rebuilt.
+fn reload_events(mut stream: TcpStream, builds: &Builds) -> std::io::Result<()> {
+    let build = builds.current();
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n"
+    )?;
+    stream.flush()?;
+
+    while !builds.wait_for_build_after(build, KEEP_ALIVE_INTERVAL) {
+        stream.write_all(b": keep-alive\n\n")?;
+        stream.flush()?;
+    }
+    stream.write_all(b"data: reload\n\n")?;
+    stream.flush()
+}
+
+#[test]
+fn request_paths_outside_of_the_documentation_are_not_served() {
+    let root = Utf8Path::new("/docs");
+    assert_eq!(
+        file_path(root, "/gleam/list.html"),
+        Some("/docs/gleam/list.html".into())
+    );
+    assert_eq!(file_path(root, "/../gleam.toml"), None);
+    assert_eq!(file_path(root, "/gleam/../../gleam.toml"), None);
+    assert_eq!(
+        file_path(root, "//etc/passwd"),
+        Some("/docs/etc/passwd".into())
+    );
+}
+
+#[test]
+fn reload_script_is_added_to_the_end_of_the_body() {
+    let html = b"<html><body><p>Hello</p></body></html>".to_vec();
+    assert_eq!(
+        String::from_utf8(with_reload_script(html)).expect("utf8"),
+        format!("<html><body><p>Hello</p>{RELOAD_SCRIPT}</body></html>")
+    );
+}
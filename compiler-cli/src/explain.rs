@@ -0,0 +1,12 @@
+use gleam_core::{diagnostic::Code, Error, Result};
+
+pub fn command(code: String) -> Result<()> {
+    let code = Code::parse(&code)
+        .filter(Code::is_assigned)
+        .ok_or(Error::UnknownDiagnosticCode { code })?;
+    let explanation = code
+        .explanation()
+        .ok_or(Error::NoDiagnosticExplanation { code })?;
+    print!("{explanation}");
+    Ok(())
+}
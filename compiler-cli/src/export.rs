@@ -2,9 +2,11 @@ use camino::Utf8PathBuf;
 use gleam_core::{
     analyse::TargetSupport,
     build::{Codegen, Mode, Options, Target},
-    Result,
+    Error, Result,
 };
 
+use crate::fs::ProjectIO;
+
 #[cfg(target_os = "windows")]
 static ENTRYPOINT_FILENAME: &str = "entrypoint.ps1";
 #[cfg(not(target_os = "windows"))]
@@ -43,6 +45,7 @@ pub(crate) fn erlang_shipment() -> Result<()> {
     let built = crate::build::main(
         Options {
             root_target_support: TargetSupport::Enforced,
+            doc_tests: false,
             warnings_as_errors: false,
             codegen: Codegen::All,
             mode,
@@ -127,6 +130,66 @@ pub fn typescript_prelude() -> Result<()> {
     Ok(())
 }
 
+/// Bundle the JavaScript generated for a module, along with the prelude and
+/// every module it imports, into a single ES module. Suitable for loading in a
+/// browser or a JavaScript runtime without a bundler.
+pub fn javascript_bundle(module: Option<String>) -> Result<()> {
+    let paths = crate::find_project_paths()?;
+    let target = Target::JavaScript;
+    let mode = Mode::Prod;
+
+    // Build project in production mode
+    let built = crate::build::main(
+        Options {
+            root_target_support: TargetSupport::Enforced,
+            doc_tests: false,
+            warnings_as_errors: false,
+            codegen: Codegen::All,
+            mode,
+            target: Some(target),
+        },
+        crate::build::download_dependencies()?,
+    )?;
+
+    let package = &built.root_package.config.name;
+    let module = module.unwrap_or_else(|| package.to_string());
+    if !built
+        .root_package
+        .modules
+        .iter()
+        .any(|built_module| built_module.name == module.as_str())
+    {
+        return Err(Error::ModuleDoesNotExist {
+            module: module.into(),
+            suggestion: None,
+        });
+    }
+
+    let entrypoint = paths
+        .build_directory_for_package(mode, target, package)
+        .join(format!("{module}.mjs"));
+    let bundle = gleam_core::javascript::bundle::bundle(
+        &ProjectIO::new(),
+        &paths.build_directory_for_target(mode, target),
+        &entrypoint,
+    )?;
+
+    let out = paths
+        .javascript_bundle_directory()
+        .join(format!("{module}.mjs"));
+    crate::fs::write(&out, &bundle)?;
+
+    crate::cli::print_exported(&module);
+
+    println!(
+        "
+Your JavaScript bundle has been generated to {out}.
+"
+    );
+
+    Ok(())
+}
+
 pub fn package_interface(path: Utf8PathBuf) -> Result<()> {
     // Build the project
     let mut built = crate::build::main(
@@ -136,6 +199,7 @@ pub fn package_interface(path: Utf8PathBuf) -> Result<()> {
             codegen: Codegen::All,
             warnings_as_errors: false,
             root_target_support: TargetSupport::Enforced,
+            doc_tests: false,
         },
         crate::build::download_dependencies()?,
     )?;
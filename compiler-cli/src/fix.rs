@@ -1,16 +1,91 @@
-use camino::{Utf8Path, Utf8PathBuf};
+use camino::Utf8PathBuf;
 use gleam_core::{
+    build::Target,
     error::{FileIoAction, FileKind},
+    paths::ProjectPaths,
     Error, Result,
 };
+use itertools::Itertools;
+use similar::TextDiff;
 
-pub fn run() -> Result<()> {
-    for path in crate::fs::gleam_files_excluding_gitignore(Utf8Path::new(".")) {
-        fix_file(path)?;
+/// Applies the fixes the language server offers as quick fixes to every
+/// module of the project: removing unused imports, replacing uses of
+/// deprecated values, and the fixes of the enabled lints. Every module is then
+/// formatted, which migrates any outdated syntax, and the Gleam version the
+/// project requires is set if it isn't already. With `dry_run` the changes are
+/// printed as a diff instead of being written.
+pub fn run(target: Option<Target>, dry_run: bool) -> Result<()> {
+    let paths = crate::find_project_paths()?;
+    let config = crate::config::root_config()?;
+    let built = crate::todos::build_all_modules(target)?;
+    let format_options = config.format.options();
+
+    let mut fixed_modules = 0;
+    let mut skipped = 0;
+    for module in built
+        .root_package
+        .modules
+        .iter()
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+    {
+        let fixes =
+            gleam_core::fix::module_fixes(&module.ast, built.module_interfaces(), &config.lints);
+        let fixed = gleam_core::fix::apply(&module.code, &fixes);
+        skipped += fixed.skipped;
+        let src = gleam_core::fix::parse_fix_and_format(
+            &fixed.src.into(),
+            &module.input_path,
+            format_options,
+        )?;
+        if src == module.code.as_str() {
+            continue;
+        }
+
+        fixed_modules += 1;
+        let path = module
+            .input_path
+            .strip_prefix(paths.root())
+            .unwrap_or(&module.input_path);
+        if dry_run {
+            let diff = TextDiff::from_lines(module.code.as_str(), src.as_str())
+                .unified_diff()
+                .header(&format!("a/{path}"), &format!("b/{path}"))
+                .to_string();
+            print!("{diff}");
+        } else {
+            crate::fs::write(&module.input_path, &src)?;
+            match fixed.applied.as_slice() {
+                [] => println!("Fixed {path}: Format"),
+                applied => println!("Fixed {path}: {}", applied.iter().unique().join(", ")),
+            }
+        }
+    }
+
+    if !dry_run {
+        set_gleam_version_requirement(&paths)?;
     }
 
-    // Set the version requirement in gleam.toml
-    let mut toml = crate::fs::read("gleam.toml")?
+    match (fixed_modules, dry_run) {
+        (0, _) => println!("No fixes to apply"),
+        (1, true) => println!("\n1 module would be fixed"),
+        (count, true) => println!("\n{count} modules would be fixed"),
+        (1, false) => println!("\n1 module fixed"),
+        (count, false) => println!("\n{count} modules fixed"),
+    }
+    if skipped > 0 && !dry_run {
+        println!(
+            "{skipped} fixes changed code already changed by another fix, run `gleam fix` \
+again to apply them"
+        );
+    }
+    Ok(())
+}
+
+/// Sets the version requirement in `gleam.toml` to the first version the
+/// migrated syntax is supported by, if the project doesn't have one already.
+fn set_gleam_version_requirement(paths: &ProjectPaths) -> Result<()> {
+    let path = paths.root_config();
+    let mut toml = crate::fs::read(&path)?
         .parse::<toml_edit::Document>()
         .map_err(|e| Error::FileIo {
             kind: FileKind::File,
@@ -18,28 +93,13 @@ pub fn run() -> Result<()> {
             path: Utf8PathBuf::from("gleam.toml"),
             err: Some(e.to_string()),
         })?;
+    if toml.contains_key("gleam") {
+        return Ok(());
+    }
 
     #[allow(clippy::indexing_slicing)]
     {
         toml["gleam"] = toml_edit::value(">= 0.32.0");
     }
-
-    // Write the updated config
-    crate::fs::write(Utf8Path::new("gleam.toml"), &toml.to_string())?;
-
-    println!(
-        "Your Gleam code has been fixed!
-
-If you have any JavaScript code that used the BitString class
-you will need to update it to use the BitArray class instead.
-"
-    );
-    Ok(())
-}
-
-fn fix_file(path: Utf8PathBuf) -> Result<()> {
-    let src = crate::fs::read(&path)?;
-    let out = gleam_core::fix::parse_fix_and_format(&src.into(), &path)?;
-    crate::fs::write(&path, &out)?;
-    Ok(())
+    crate::fs::write(&path, &toml.to_string())
 }
@@ -1,27 +1,203 @@
 use gleam_core::{
+    ast::SrcSpan,
     error::{Error, FileIoAction, FileKind, Result, StandardIoAction, Unformatted},
+    format::FormatOptions,
     io::Content,
     io::OutputFile,
+    line_numbers::{LineNumbers, PositionEncoding},
+    paths::ProjectPaths,
 };
 use std::{io::Read, str::FromStr};
 
 use camino::{Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
+use itertools::Itertools;
+use similar::{ChangeTag, TextDiff};
+use termcolor::{Color, ColorSpec, WriteColor};
 
-pub fn run(stdin: bool, check: bool, files: Vec<String>) -> Result<()> {
-    if stdin {
-        process_stdin(check)
+/// The exit code of `gleam format --check` when there are files that are not
+/// formatted.
+pub const UNFORMATTED_EXIT_CODE: i32 = 1;
+
+/// The exit code of `gleam format` when files could not be formatted, such
+/// as when they could not be read or parsed.
+pub const FAILED_EXIT_CODE: i32 = 2;
+
+/// A part of a file to format, given as `FILE:START..END`. The bounds are
+/// 1-based line numbers, or byte offsets when prefixed with `b`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Range {
+    path: Utf8PathBuf,
+    unit: RangeUnit,
+    start: u32,
+    end: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RangeUnit {
+    Lines,
+    Bytes,
+}
+
+impl Range {
+    /// The bytes of the source covered by the range. A line range covers
+    /// every line from the start line to the end line, inclusive.
+    fn span(&self, src: &str) -> SrcSpan {
+        match self.unit {
+            RangeUnit::Lines => {
+                let lines = LineNumbers::new(src);
+                SrcSpan::new(
+                    lines.byte_index(self.start - 1, 0, PositionEncoding::Utf8),
+                    lines.byte_index(self.end, 0, PositionEncoding::Utf8),
+                )
+            }
+            RangeUnit::Bytes => {
+                let length = src.len() as u32;
+                SrcSpan::new(self.start.min(length), self.end.min(length))
+            }
+        }
+    }
+}
+
+pub fn parse_range(range: &str) -> Result<Range, String> {
+    let format_error = || "expected FILE:START..END".to_string();
+    let (path, bounds) = range.rsplit_once(':').ok_or_else(format_error)?;
+    let (start, end) = bounds.split_once("..").ok_or_else(format_error)?;
+
+    let bound = |bound: &str| match bound.strip_prefix('b') {
+        Some(offset) => offset.parse().map(|offset| (RangeUnit::Bytes, offset)),
+        None => bound.parse().map(|line| (RangeUnit::Lines, line)),
+    };
+    let (start_unit, start) = bound(start).map_err(|_| format_error())?;
+    let (end_unit, end) = bound(end).map_err(|_| format_error())?;
+
+    if start_unit != end_unit {
+        return Err("both ends of the range must be lines or bytes".into());
+    }
+    if start_unit == RangeUnit::Lines && start == 0 {
+        return Err("line numbers start at 1".into());
+    }
+    if start > end {
+        return Err("the start of the range must not be after its end".into());
+    }
+
+    Ok(Range {
+        path: path.into(),
+        unit: start_unit,
+        start,
+        end,
+    })
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub stdin: bool,
+    pub check: bool,
+    /// Print a diff of the changes to the files that are not formatted when
+    /// checking them.
+    pub diff: bool,
+    pub files: Vec<String>,
+    pub line_width: Option<u16>,
+    pub range: Option<Range>,
+    pub cursor: Option<u32>,
+    pub verify: bool,
+}
+
+pub fn run(run_options: Options) -> Result<()> {
+    let Options {
+        stdin,
+        check,
+        diff,
+        files,
+        line_width,
+        range,
+        cursor,
+        verify,
+    } = run_options;
+    let mut options = configured_options()?;
+    if let Some(line_width) = line_width {
+        options.line_width = line_width as isize;
+    }
+    let result = if let Some(range) = range {
+        process_range(stdin, check, range, options, cursor)
+    } else if stdin {
+        process_stdin(check, options, cursor, verify)
     } else {
-        process_files(check, files)
+        process_files(check, files, options, verify)
+    };
+
+    if let (true, Err(Error::Format { problem_files })) = (diff, &result) {
+        print_diffs(problem_files);
+    }
+    result
+}
+
+/// Prints unified diffs of the changes formatting makes to the files to
+/// standard output, coloured when it is a terminal.
+fn print_diffs(problem_files: &[Unformatted]) {
+    let stdout = crate::cli::stdout_buffer_writer();
+    let mut buffer = stdout.buffer();
+    for file in problem_files
+        .iter()
+        .sorted_by(|a, b| a.source.cmp(&b.source))
+    {
+        write_diff(&mut buffer, file).expect("Writing format diff");
+    }
+    stdout.print(&buffer).expect("Printing format diff");
+}
+
+fn write_diff(buffer: &mut impl WriteColor, file: &Unformatted) -> std::io::Result<()> {
+    let diff = TextDiff::from_lines(file.input.as_str(), file.output.as_str());
+    let path = &file.source;
+
+    buffer.set_color(ColorSpec::new().set_bold(true))?;
+    writeln!(buffer, "--- a/{path}")?;
+    writeln!(buffer, "+++ b/{path}")?;
+    for hunk in diff.unified_diff().iter_hunks() {
+        buffer.set_color(ColorSpec::new().set_fg(Some(Color::Cyan)))?;
+        writeln!(buffer, "{}", hunk.header())?;
+        for change in hunk.iter_changes() {
+            let (sign, colour) = match change.tag() {
+                ChangeTag::Delete => ('-', Some(Color::Red)),
+                ChangeTag::Insert => ('+', Some(Color::Green)),
+                ChangeTag::Equal => (' ', None),
+            };
+            buffer.set_color(ColorSpec::new().set_fg(colour))?;
+            write!(buffer, "{sign}{}", change.value())?;
+            if change.missing_newline() {
+                writeln!(buffer)?;
+                buffer.reset()?;
+                writeln!(buffer, "\\ No newline at end of file")?;
+            }
+        }
+    }
+    buffer.reset()
+}
+
+/// The options set in the `gleam.toml` of the project being formatted, if
+/// there is one.
+fn configured_options() -> Result<FormatOptions> {
+    let directory = crate::fs::get_current_directory()?;
+    match crate::fs::get_project_root(directory) {
+        Ok(root) => {
+            let config = crate::config::read(ProjectPaths::new(root).root_config())?;
+            Ok(config.format.options())
+        }
+        Err(_) => Ok(FormatOptions::default()),
     }
 }
 
-fn process_stdin(check: bool) -> Result<()> {
+fn process_stdin(
+    check: bool,
+    options: FormatOptions,
+    cursor: Option<u32>,
+    verify: bool,
+) -> Result<()> {
     let src = read_stdin()?.into();
-    let mut out = String::new();
-    gleam_core::format::pretty(&mut out, &src, Utf8Path::new("<stdin>"))?;
+    let out = format(&src, Utf8Path::new("<stdin>"), options, verify)?;
 
     if !check {
-        print!("{out}");
+        print_formatted(&src, &out, cursor);
         return Ok(());
     }
 
@@ -39,16 +215,80 @@ fn process_stdin(check: bool) -> Result<()> {
     Ok(())
 }
 
-fn process_files(check: bool, files: Vec<String>) -> Result<()> {
+/// Formats the definitions in part of a file. The result is printed when the
+/// source is read from stdin, and written back to the file otherwise.
+fn process_range(
+    stdin: bool,
+    check: bool,
+    range: Range,
+    options: FormatOptions,
+    cursor: Option<u32>,
+) -> Result<()> {
+    let src = if stdin {
+        read_stdin()?.into()
+    } else {
+        crate::fs::read(&range.path)?.into()
+    };
+    let edits = gleam_core::format::pretty_range(&src, &range.path, options, range.span(&src))?;
+    let output = gleam_core::format::apply_edits(&src, &edits);
+
     if check {
-        check_files(files)
+        if edits.is_empty() {
+            return Ok(());
+        }
+        return Err(Error::Format {
+            problem_files: vec![Unformatted {
+                source: range.path.clone(),
+                destination: range.path,
+                input: src,
+                output,
+            }],
+        });
+    }
+
+    if stdin {
+        print_formatted(&src, &output, cursor);
+    } else if !edits.is_empty() {
+        crate::fs::write_output(&OutputFile {
+            path: range.path,
+            content: Content::Text(output),
+        })?;
+    }
+    Ok(())
+}
+
+/// Prints formatted source read from stdin. When a cursor offset was given
+/// the new offset of the cursor is printed first as a line of JSON, so that
+/// editors can keep the cursor in the same place in the code.
+///
+/// ```text
+/// {"cursor":42}
+/// pub fn main() {
+/// ...
+/// ```
+fn print_formatted(src: &str, formatted: &str, cursor: Option<u32>) {
+    if let Some(cursor) = cursor {
+        let cursor = gleam_core::format::formatted_cursor_position(src, formatted, cursor);
+        println!("{}", serde_json::json!({ "cursor": cursor }));
+    }
+    print!("{formatted}");
+}
+
+fn process_files(
+    check: bool,
+    files: Vec<String>,
+    options: FormatOptions,
+    verify: bool,
+) -> Result<()> {
+    if check {
+        check_files(files, options, verify)
     } else {
-        format_files(files)
+        format_files(files, options, verify)
     }
 }
 
-fn check_files(files: Vec<String>) -> Result<()> {
-    let problem_files = unformatted_files(files)?;
+fn check_files(files: Vec<String>, options: FormatOptions, verify: bool) -> Result<()> {
+    let problem_files = unformatted_files(files, options, verify)?;
 
     if problem_files.is_empty() {
         Ok(())
@@ -57,8 +297,8 @@ fn check_files(files: Vec<String>) -> Result<()> {
     }
 }
 
-fn format_files(files: Vec<String>) -> Result<()> {
-    for file in unformatted_files(files)? {
+fn format_files(files: Vec<String>, options: FormatOptions, verify: bool) -> Result<()> {
+    for file in unformatted_files(files, options, verify)? {
         crate::fs::write_output(&OutputFile {
             path: file.destination,
             content: Content::Text(file.output),
@@ -67,7 +307,11 @@ fn format_files(files: Vec<String>) -> Result<()> {
     Ok(())
 }
 
-pub fn unformatted_files(files: Vec<String>) -> Result<Vec<Unformatted>> {
+pub fn unformatted_files(
+    files: Vec<String>,
+    options: FormatOptions,
+    verify: bool,
+) -> Result<Vec<Unformatted>> {
     let mut problem_files = Vec::with_capacity(files.len());
 
     for file_path in files {
@@ -80,20 +324,24 @@ pub fn unformatted_files(files: Vec<String>) -> Result<Vec<Unformatted>> {
 
         if path.is_dir() {
             for path in crate::fs::gleam_files_excluding_gitignore(&path) {
-                format_file(&mut problem_files, path)?;
+                format_file(&mut problem_files, path, options, verify)?;
             }
         } else {
-            format_file(&mut problem_files, path)?;
+            format_file(&mut problem_files, path, options, verify)?;
         }
     }
 
     Ok(problem_files)
 }
 
-fn format_file(problem_files: &mut Vec<Unformatted>, path: Utf8PathBuf) -> Result<()> {
+fn format_file(
+    problem_files: &mut Vec<Unformatted>,
+    path: Utf8PathBuf,
+    options: FormatOptions,
+    verify: bool,
+) -> Result<()> {
     let src = crate::fs::read(&path)?.into();
-    let mut output = String::new();
-    gleam_core::format::pretty(&mut output, &src, &path)?;
+    let output = format(&src, &path, options, verify)?;
 
     if src != output {
         problem_files.push(Unformatted {
@@ -106,6 +354,22 @@ fn format_file(problem_files: &mut Vec<Unformatted>, path: Utf8PathBuf) -> Resul
     Ok(())
 }
 
+/// Formats a module, checking the result is equivalent to the source when
+/// `--verify` is given.
+fn format(
+    src: &EcoString,
+    path: &Utf8Path,
+    options: FormatOptions,
+    verify: bool,
+) -> Result<String> {
+    if verify {
+        return gleam_core::format::pretty_verified(src, path, options);
+    }
+    let mut output = String::new();
+    gleam_core::format::pretty_with_options(&mut output, src, path, options)?;
+    Ok(output)
+}
+
 pub fn read_stdin() -> Result<String> {
     let mut src = String::new();
     let _ = std::io::stdin()
@@ -116,3 +380,78 @@ pub fn read_stdin() -> Result<String> {
         })?;
     Ok(src)
 }
+
+#[test]
+fn parse_line_range() {
+    assert_eq!(
+        parse_range("src/app.gleam:3..10"),
+        Ok(Range {
+            path: "src/app.gleam".into(),
+            unit: RangeUnit::Lines,
+            start: 3,
+            end: 10,
+        })
+    );
+}
+
+#[test]
+fn parse_byte_range() {
+    assert_eq!(
+        parse_range("C:/src/app.gleam:b0..b120"),
+        Ok(Range {
+            path: "C:/src/app.gleam".into(),
+            unit: RangeUnit::Bytes,
+            start: 0,
+            end: 120,
+        })
+    );
+}
+
+#[test]
+fn parse_invalid_ranges() {
+    for range in [
+        "src/app.gleam",
+        "src/app.gleam:3",
+        "src/app.gleam:0..2",
+        "src/app.gleam:3..b10",
+        "src/app.gleam:10..3",
+        "src/app.gleam:one..two",
+    ] {
+        assert!(parse_range(range).is_err(), "{range}");
+    }
+}
+
+#[test]
+fn unformatted_file_diff() {
+    let file = Unformatted {
+        source: "src/app.gleam".into(),
+        destination: "src/app.gleam".into(),
+        input: "pub fn main() {\n  1+2\n}\n\n\n".into(),
+        output: "pub fn main() {\n  1 + 2\n}\n".into(),
+    };
+    let mut buffer = termcolor::NoColor::new(vec![]);
+    write_diff(&mut buffer, &file).expect("diff");
+    let diff = String::from_utf8(buffer.into_inner()).expect("utf8");
+    assert_eq!(
+        diff,
+        "--- a/src/app.gleam
++++ b/src/app.gleam
+@@ -1,5 +1,3 @@
+ pub fn main() {
+-  1+2
++  1 + 2
+ }
+-
+-
+"
+    );
+}
+
+#[test]
+fn line_range_span() {
+    let src = "one\ntwo\nthree\n";
+    let range = parse_range("app.gleam:2..2").expect("range");
+    assert_eq!(range.span(src), SrcSpan::new(4, 8));
+    let range = parse_range("app.gleam:2..9").expect("range");
+    assert_eq!(range.span(src), SrcSpan::new(4, 14));
+}
@@ -1,11 +1,12 @@
 use gleam_core::{
     build::{NullTelemetry, Target},
+    diagnostic::json::JsonDiagnostic,
     error::{Error, FileIoAction, FileKind},
     io::{
         CommandExecutor, Content, DirEntry, FileSystemReader, FileSystemWriter, OutputFile,
         ReadDir, Stdio, WrappedReader,
     },
-    language_server::{DownloadDependencies, Locker, MakeLocker},
+    language_server::{DownloadDependencies, Locker, MakeLocker, RunGleamCommand},
     manifest::Manifest,
     paths::ProjectPaths,
     warning::WarningEmitterIO,
@@ -22,7 +23,10 @@ use std::{
 
 use camino::{ReadDirUtf8, Utf8Path, Utf8PathBuf};
 
-use crate::{dependencies::UseManifest, lsp::LspLocker};
+use crate::{
+    dependencies::{UseManifest, UseNetwork},
+    lsp::LspLocker,
+};
 
 #[cfg(test)]
 mod tests;
@@ -227,7 +231,42 @@ impl MakeLocker for ProjectIO {
 
 impl DownloadDependencies for ProjectIO {
     fn download_dependencies(&self, paths: &ProjectPaths) -> Result<Manifest> {
-        crate::dependencies::download(paths, NullTelemetry, None, UseManifest::Yes)
+        crate::dependencies::download(
+            paths,
+            NullTelemetry,
+            None,
+            UseManifest::Yes,
+            UseNetwork::from_environment(),
+        )
+    }
+}
+
+impl RunGleamCommand for ProjectIO {
+    fn run_gleam_command(&self, paths: &ProjectPaths, arguments: Vec<String>) -> Result<()> {
+        let program = std::env::current_exe().map_err(|error| Error::ShellCommand {
+            program: "gleam".into(),
+            err: Some(error.kind()),
+        })?;
+        tracing::trace!(args=?arguments.join(" "), "running_gleam_command");
+
+        // The standard output of the language server is used to talk to the
+        // client, so the output of the command goes to its log instead.
+        let mut child = std::process::Command::new(program)
+            .args(arguments)
+            .current_dir(paths.root())
+            .stdin(std::process::Stdio::null())
+            .stdout(io::stderr())
+            .stderr(io::stderr())
+            .spawn()
+            .map_err(|error| Error::ShellCommand {
+                program: "gleam".into(),
+                err: Some(error.kind()),
+            })?;
+
+        // Wait for the command on another thread so the language server can
+        // carry on, and the finished process is cleaned up.
+        let _ = std::thread::spawn(move || child.wait());
+        Ok(())
     }
 }
 
@@ -414,8 +453,9 @@ pub fn erlang_files(dir: &Utf8Path) -> Result<impl Iterator<Item = Utf8PathBuf>
         }))
 }
 
-pub fn create_tar_archive(outputs: Vec<OutputFile>) -> Result<Vec<u8>, Error> {
+pub fn create_tar_archive(mut outputs: Vec<OutputFile>) -> Result<Vec<u8>, Error> {
     tracing::trace!("creating_tar_archive");
+    outputs.sort_by(|a, b| a.path.cmp(&b.path));
 
     let encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
     let mut builder = tar::Builder::new(encoder);
@@ -695,3 +735,14 @@ impl WarningEmitterIO for ConsoleWarningEmitter {
             .expect("Writing warning to stderr");
     }
 }
+
+/// Prints each warning to stdout as a JSON object on its own line, for
+/// `--message-format=json`.
+#[derive(Debug, Clone, Copy)]
+pub struct JsonWarningEmitter;
+
+impl WarningEmitterIO for JsonWarningEmitter {
+    fn emit_warning(&self, warning: Warning) {
+        println!("{}", JsonDiagnostic::from_warning(&warning).to_json());
+    }
+}
@@ -57,6 +57,7 @@ mod compile_package;
 mod config;
 mod dependencies;
 mod docs;
+mod explain;
 mod export;
 mod fix;
 mod format;
@@ -70,21 +71,25 @@ mod publish;
 mod remove;
 mod run;
 mod shell;
+mod todos;
+mod watch;
 
+use build::MessageFormat;
 use config::root_config;
-use dependencies::UseManifest;
+use dependencies::{UseManifest, UseNetwork};
 use fs::{get_current_directory, get_project_root};
 pub use gleam_core::error::{Error, Result};
 
 use gleam_core::{
     analyse::TargetSupport,
-    build::{Codegen, Mode, Options, Runtime, Target},
+    build::{Codegen, Mode, Options, Phase, Runtime, Target, Timings},
+    diagnostic::json::JsonDiagnostic,
     hex::RetirementReason,
     paths::ProjectPaths,
     version::COMPILER_VERSION,
 };
 use hex::ApiKeyCommand as _;
-use std::str::FromStr;
+use std::{str::FromStr, time::Instant};
 
 use camino::Utf8PathBuf;
 
@@ -119,12 +124,49 @@ enum Command {
 
         #[arg(short, long, ignore_case = true, help = target_doc())]
         target: Option<Target>,
+
+        /// How to print errors and warnings
+        #[arg(long, ignore_case = true, default_value = "human")]
+        message_format: MessageFormat,
+
+        /// Print how long each phase of the build took, along with the
+        /// packages and modules that took the longest
+        #[arg(long)]
+        timings: bool,
+
+        /// Write the timings of the build to a file in the Chrome trace event
+        /// format, to be viewed with Perfetto or `chrome://tracing`
+        #[arg(long, requires = "timings")]
+        timings_trace: Option<Utf8PathBuf>,
     },
 
     /// Type check the project
     Check {
         #[arg(short, long, ignore_case = true, help = target_doc())]
         target: Option<Target>,
+
+        /// List every `todo` in the project with the type it is expected to have
+        #[arg(long)]
+        todos: bool,
+
+        /// How to print errors and warnings
+        #[arg(long, ignore_case = true, default_value = "human")]
+        message_format: MessageFormat,
+    },
+
+    /// List the `todo`s, `panic as` placeholders and uses of deprecated
+    /// values in the project
+    Todos {
+        #[arg(short, long, ignore_case = true, help = target_doc())]
+        target: Option<Target>,
+
+        /// How to order the items
+        #[arg(long, ignore_case = true, default_value = "file")]
+        sort: todos::Sort,
+
+        /// Print the items as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Publish the project to the Hex package manager
@@ -139,6 +181,17 @@ enum Command {
         replace: bool,
         #[arg(short, long)]
         yes: bool,
+        /// Refuse to publish a release that makes breaking changes to the API
+        /// of the latest release before it, unless it is a new major version
+        #[arg(long)]
+        check_semver: bool,
+        /// Build the package and its documentation without publishing them
+        #[arg(long)]
+        dry_run: bool,
+        /// Write the package tarball to this path, and its documentation
+        /// tarball next to it, when doing a dry run
+        #[arg(long, requires = "dry_run")]
+        output: Option<Utf8PathBuf>,
     },
 
     /// Render HTML documentation
@@ -169,15 +222,70 @@ enum Command {
         #[arg(long)]
         stdin: bool,
 
-        /// Check if inputs are formatted without changing them
+        /// Check if inputs are formatted without changing them. Exits with
+        /// status 1 if any are not formatted, and 2 if they could not be
+        /// formatted
         #[arg(long)]
         check: bool,
+
+        /// Print a unified diff of the changes formatting would make to the
+        /// files that are not formatted
+        #[arg(long, requires = "check")]
+        diff: bool,
+
+        /// The width to keep lines within, overriding the `line_width` set in
+        /// the `[format]` section of gleam.toml (default 80)
+        #[arg(long)]
+        line_width: Option<u16>,
+
+        /// Only format the top-level definitions overlapping part of a file.
+        /// START and END are line numbers, or byte offsets when prefixed with
+        /// `b`. The result is written to the file, or printed when the source
+        /// is read from STDIN
+        #[arg(
+            long,
+            value_name = "FILE:START..END",
+            value_parser = format::parse_range,
+            conflicts_with = "files"
+        )]
+        range: Option<format::Range>,
+
+        /// The byte offset of the cursor in the source read from STDIN. The
+        /// offset of the cursor in the formatted source is printed as a line
+        /// of JSON before it
+        #[arg(
+            long,
+            value_name = "OFFSET",
+            requires = "stdin",
+            conflicts_with = "check"
+        )]
+        cursor: Option<u32>,
+
+        /// Check that formatting doesn't change what the code means, and that
+        /// formatting the result again doesn't change it. Files that fail
+        /// the check are left unchanged
+        #[arg(long, conflicts_with = "range")]
+        verify: bool,
     },
-    /// Rewrite deprecated Gleam code
-    Fix,
+    /// Apply automatic fixes to the project's code, such as removing unused
+    /// imports and the rewrites suggested by lints
+    Fix {
+        #[arg(short, long, ignore_case = true, help = target_doc())]
+        target: Option<Target>,
 
-    /// Start an Erlang shell
-    Shell,
+        /// Print the changes as a diff instead of writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Start a shell evaluating Gleam expressions with the project's modules
+    Shell {
+        #[arg(short, long, ignore_case = true, help = target_doc())]
+        target: Option<Target>,
+
+        #[arg(long, ignore_case = true, help = runtime_doc())]
+        runtime: Option<Runtime>,
+    },
 
     /// Run the project
     #[command(trailing_var_arg = true)]
@@ -192,6 +300,16 @@ enum Command {
         #[arg(short, long)]
         module: Option<String>,
 
+        /// The public function of the module to run. It must take no
+        /// arguments, or a `List(String)` of the arguments given after the
+        /// module and function
+        #[arg(short, long, default_value = "main")]
+        function: String,
+
+        /// Rebuild and run the project again each time its files change
+        #[arg(long)]
+        watch: bool,
+
         arguments: Vec<String>,
     },
 
@@ -204,6 +322,40 @@ enum Command {
         #[arg(long, ignore_case = true, help = runtime_doc())]
         runtime: Option<Runtime>,
 
+        /// Only run the tests with this text in their `module.function` name
+        #[arg(long)]
+        filter: Option<String>,
+
+        /// Only run the tests in this module, or in the modules nested within it.
+        /// Can be given multiple times
+        #[arg(long = "module")]
+        modules: Vec<String>,
+
+        /// Only run the tests that failed the last time the tests were run
+        #[arg(long)]
+        failed: bool,
+
+        /// Write a report of the test results to this path
+        #[arg(long)]
+        report: Option<Utf8PathBuf>,
+
+        /// The format of the report of the test results
+        #[arg(long, ignore_case = true, default_value = "json")]
+        report_format: run::ReportFormat,
+
+        /// Stop after the first test that fails
+        #[arg(long)]
+        fail_fast: bool,
+
+        /// Report the line coverage of the project's modules, writing LCOV and
+        /// HTML reports to `build/coverage`. Erlang only
+        #[arg(long)]
+        coverage: bool,
+
+        /// Rebuild and run the tests again each time the project's files change
+        #[arg(long)]
+        watch: bool,
+
         arguments: Vec<String>,
     },
 
@@ -217,7 +369,8 @@ enum Command {
 
     /// Add new project dependencies
     Add {
-        /// The names of Hex packages to add
+        /// The names of Hex packages to add, optionally with the version to
+        /// add such as `lustre@4.2`
         #[arg(required = true)]
         packages: Vec<String>,
 
@@ -243,6 +396,12 @@ enum Command {
     /// Export something useful from the Gleam project
     #[command(subcommand)]
     Export(ExportTarget),
+
+    /// Explain an error or warning code in detail, with examples
+    Explain {
+        /// The code to explain, such as GE0215
+        code: String,
+    },
 }
 
 fn target_doc() -> String {
@@ -263,6 +422,13 @@ pub enum ExportTarget {
     JavascriptPrelude,
     /// The TypeScript prelude module
     TypescriptPrelude,
+    /// A module and every module it imports bundled into a single JavaScript
+    /// module, suitable for loading without a bundler
+    JavascriptBundle {
+        /// The module to bundle, defaulting to the module named after the project
+        #[arg(long)]
+        module: Option<String>,
+    },
     /// Information on the modules, functions, and types in the project in JSON format
     PackageInterface {
         #[arg(long = "out", required = true)]
@@ -280,7 +446,9 @@ pub struct NewOptions {
     #[arg(long)]
     pub name: Option<String>,
 
-    #[arg(long, ignore_case = true, default_value = "lib")]
+    /// The template to create the project from: lib, cli, web, escript, or
+    /// the URL of a git repository containing a `gleam-template.toml` file
+    #[arg(long, default_value = "lib")]
     pub template: new::Template,
 
     /// Skip git initialization and creation of .gitignore, .git/* and .github/* files
@@ -332,10 +500,36 @@ enum Dependencies {
     List,
 
     /// Download all dependency packages
-    Download,
+    ///
+    /// This command uses this environment variable:
+    ///
+    /// - GLEAM_OFFLINE: (optional) Use only the packages in the local cache,
+    ///   as with the --offline flag.
+    #[command(verbatim_doc_comment)]
+    Download {
+        /// Use only the packages in the local cache, without using Hex
+        #[arg(long)]
+        offline: bool,
+    },
 
     /// Update dependency packages to their latest versions
-    Update,
+    Update {
+        /// Choose which of the dependencies with newer compatible versions to
+        /// update
+        #[arg(long)]
+        interactive: bool,
+    },
+
+    /// List the dependencies with newer versions on Hex
+    Outdated,
+
+    /// Print the graph of dependency packages with their versions and
+    /// sources, for Graphviz or other tools
+    Graph {
+        /// The format to print the graph in
+        #[arg(long, ignore_case = true, default_value = "dot")]
+        format: dependencies::GraphFormat,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -370,11 +564,27 @@ enum Hex {
 
 #[derive(Subcommand, Debug)]
 enum Docs {
-    /// Render HTML docs locally
+    /// Render HTML or Markdown docs locally
     Build {
         /// Opens the docs in a browser after rendering
         #[arg(long)]
         open: bool,
+
+        /// The format to render the docs in
+        #[arg(long, ignore_case = true, default_value = "html")]
+        format: docs::Format,
+    },
+
+    /// Render HTML docs and serve them locally, rebuilding them and
+    /// reloading the browser when the project changes
+    Serve {
+        /// The port to serve the docs on
+        #[arg(long, default_value_t = 8000)]
+        port: u16,
+
+        /// Opens the docs in a browser once they are being served
+        #[arg(long)]
+        open: bool,
     },
 
     /// Publish HTML docs to HexDocs
@@ -409,15 +619,38 @@ fn main() {
     panic::add_handler();
     let stderr = cli::stderr_buffer_writer();
 
-    let result = match Command::parse() {
+    let command = Command::parse();
+    let message_format = command.message_format();
+    let is_format = matches!(command, Command::Format { .. });
+
+    let result = match command {
         Command::Build {
             target,
             warnings_as_errors,
-        } => command_build(target, warnings_as_errors),
+            message_format,
+            timings,
+            timings_trace,
+        } => command_build(
+            target,
+            warnings_as_errors,
+            message_format,
+            timings,
+            timings_trace,
+        ),
+
+        Command::Check {
+            target,
+            todos,
+            message_format,
+        } => command_check(target, todos, message_format),
+
+        Command::Todos { target, sort, json } => todos::command(target, sort, json),
 
-        Command::Check { target } => command_check(target),
+        Command::Docs(Docs::Build { open, format }) => {
+            docs::build(docs::BuildOptions { open, format })
+        }
 
-        Command::Docs(Docs::Build { open }) => docs::build(docs::BuildOptions { open }),
+        Command::Docs(Docs::Serve { port, open }) => docs::serve(docs::ServeOptions { port, open }),
 
         Command::Docs(Docs::Publish) => docs::publish(),
 
@@ -427,36 +660,91 @@ fn main() {
             stdin,
             files,
             check,
-        } => format::run(stdin, check, files),
+            diff,
+            line_width,
+            range,
+            cursor,
+            verify,
+        } => format::run(format::Options {
+            stdin,
+            check,
+            diff,
+            files,
+            line_width,
+            range,
+            cursor,
+            verify,
+        }),
 
-        Command::Fix => fix::run(),
+        Command::Fix { target, dry_run } => fix::run(target, dry_run),
 
         Command::Deps(Dependencies::List) => dependencies::list(),
 
-        Command::Deps(Dependencies::Download) => download_dependencies(),
+        Command::Deps(Dependencies::Download { offline }) => download_dependencies(offline),
 
-        Command::Deps(Dependencies::Update) => dependencies::update(),
+        Command::Deps(Dependencies::Update { interactive }) => dependencies::update(interactive),
+
+        Command::Deps(Dependencies::Outdated) => dependencies::outdated(),
+
+        Command::Deps(Dependencies::Graph { format }) => dependencies::graph(format),
 
         Command::New(options) => new::create(options, COMPILER_VERSION),
 
-        Command::Shell => shell::command(),
+        Command::Shell { target, runtime } => shell::command(target, runtime),
 
         Command::Run {
             target,
             arguments,
             runtime,
             module,
-        } => run::command(arguments, target, runtime, module, run::Which::Src),
+            function,
+            watch,
+        } => {
+            let which = run::Which::Src { function };
+            run::command(arguments, target, runtime, module, which, watch)
+        }
 
         Command::Test {
             target,
             arguments,
             runtime,
-        } => run::command(arguments, target, runtime, None, run::Which::Test),
+            filter,
+            modules,
+            failed,
+            report,
+            report_format,
+            fail_fast,
+            coverage,
+            watch,
+        } => {
+            let options = run::TestOptions {
+                filter,
+                modules,
+                failed,
+                report,
+                report_format,
+                fail_fast,
+                coverage,
+            };
+            let which = run::Which::Test(options);
+            run::command(arguments, target, runtime, None, which, watch)
+        }
 
         Command::CompilePackage(opts) => compile_package::command(opts),
 
-        Command::Publish { replace, yes } => publish::command(replace, yes),
+        Command::Publish {
+            replace,
+            yes,
+            check_semver,
+            dry_run,
+            output,
+        } => publish::command(publish::PublishOptions {
+            replace,
+            yes,
+            check_semver,
+            dry_run,
+            output,
+        }),
 
         Command::PrintConfig => print_config(),
 
@@ -475,7 +763,7 @@ fn main() {
 
         Command::Remove { packages } => remove::command(packages),
 
-        Command::Update => dependencies::update(),
+        Command::Update => dependencies::update(false),
 
         Command::Clean => clean(),
 
@@ -485,9 +773,14 @@ fn main() {
         Command::Export(ExportTarget::HexTarball) => export::hex_tarball(),
         Command::Export(ExportTarget::JavascriptPrelude) => export::javascript_prelude(),
         Command::Export(ExportTarget::TypescriptPrelude) => export::typescript_prelude(),
+        Command::Export(ExportTarget::JavascriptBundle { module }) => {
+            export::javascript_bundle(module)
+        }
         Command::Export(ExportTarget::PackageInterface { output }) => {
             export::package_interface(output)
         }
+
+        Command::Explain { code } => explain::command(code),
     };
 
     match result {
@@ -496,39 +789,99 @@ fn main() {
         }
         Err(error) => {
             tracing::error!(error = ?error, "Failed");
-            let mut buffer = stderr.buffer();
-            error.pretty(&mut buffer);
-            stderr.print(&buffer).expect("Final result error writing");
-            std::process::exit(1);
+            match message_format {
+                MessageFormat::Human => {
+                    let mut buffer = stderr.buffer();
+                    error.pretty(&mut buffer);
+                    stderr.print(&buffer).expect("Final result error writing");
+                }
+                MessageFormat::Json => {
+                    for diagnostic in JsonDiagnostic::from_error(&error) {
+                        println!("{}", diagnostic.to_json());
+                    }
+                }
+            }
+            // Unformatted files are told apart from the formatter failing,
+            // so CI can tell which happened.
+            let exit_code = match error {
+                Error::Format { .. } => format::UNFORMATTED_EXIT_CODE,
+                _ if is_format => format::FAILED_EXIT_CODE,
+                _ => 1,
+            };
+            std::process::exit(exit_code);
         }
     }
 }
 
-fn command_check(target: Option<Target>) -> Result<()> {
-    let _ = build::main(
+impl Command {
+    /// How the errors and warnings of the command are printed.
+    fn message_format(&self) -> MessageFormat {
+        match self {
+            Self::Build { message_format, .. } | Self::Check { message_format, .. } => {
+                *message_format
+            }
+            _ => MessageFormat::Human,
+        }
+    }
+}
+
+fn command_check(target: Option<Target>, todos: bool, message_format: MessageFormat) -> Result<()> {
+    if todos {
+        let paths = find_project_paths()?;
+        let built = todos::build_all_modules(target)?;
+        todos::print(&built.root_package.modules, paths.root());
+        return Ok(());
+    }
+
+    let _ = build::main_with_message_format(
         Options {
             root_target_support: TargetSupport::Enforced,
+            doc_tests: false,
             warnings_as_errors: false,
             codegen: Codegen::DepsOnly,
             mode: Mode::Dev,
             target,
         },
-        build::download_dependencies()?,
+        build::download_dependencies_for(message_format)?,
+        message_format,
+        &Timings::disabled(),
     )?;
     Ok(())
 }
 
-fn command_build(target: Option<Target>, warnings_as_errors: bool) -> Result<()> {
-    let _ = build::main(
+fn command_build(
+    target: Option<Target>,
+    warnings_as_errors: bool,
+    message_format: MessageFormat,
+    timings: bool,
+    timings_trace: Option<Utf8PathBuf>,
+) -> Result<()> {
+    let timings = if timings {
+        Timings::enabled()
+    } else {
+        Timings::disabled()
+    };
+    let start = Instant::now();
+    let manifest = build::download_dependencies_for(message_format)?;
+    timings.record(Phase::Download, &root_config()?.name, None, start);
+
+    let _ = build::main_with_message_format(
         Options {
             root_target_support: TargetSupport::Enforced,
+            doc_tests: false,
             warnings_as_errors,
             codegen: Codegen::All,
             mode: Mode::Dev,
             target,
         },
-        build::download_dependencies()?,
+        manifest,
+        message_format,
+        &timings,
     )?;
+
+    if timings.is_enabled() {
+        build::report_timings(&timings, message_format, timings_trace.as_deref())?;
+    }
     Ok(())
 }
 
@@ -565,8 +918,19 @@ fn project_paths_at_current_directory_without_toml() -> ProjectPaths {
     ProjectPaths::new(current_dir)
 }
 
-fn download_dependencies() -> Result<()> {
+fn download_dependencies(offline: bool) -> Result<()> {
     let paths = find_project_paths()?;
-    _ = dependencies::download(&paths, cli::Reporter::new(), None, UseManifest::Yes)?;
+    let use_network = if offline {
+        UseNetwork::No
+    } else {
+        UseNetwork::from_environment()
+    };
+    _ = dependencies::download(
+        &paths,
+        cli::Reporter::new(),
+        None,
+        UseManifest::Yes,
+        use_network,
+    )?;
     Ok(())
 }
@@ -1,32 +1,114 @@
 use camino::{Utf8Path, Utf8PathBuf};
-use clap::ValueEnum;
 use gleam_core::{
     erlang,
     error::{Error, FileIoAction, FileKind, InvalidProjectNameReason},
+    io::{CommandExecutor, Stdio},
     parse, Result,
 };
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use std::fs::File;
-use std::{env, io::Write};
-use strum::{Display, EnumIter, EnumString, IntoEnumIterator, VariantNames};
+use std::{env, fmt, io::Write, str::FromStr};
+use strum::{EnumIter, IntoEnumIterator};
 
 #[cfg(test)]
 mod tests;
 
-use crate::{fs::get_current_directory, NewOptions};
+use crate::{
+    fs::{get_current_directory, ProjectIO},
+    NewOptions,
+};
 
 const GLEAM_STDLIB_REQUIREMENT: &str = ">= 0.34.0 and < 2.0.0";
 const GLEEUNIT_REQUIREMENT: &str = ">= 1.0.0 and < 2.0.0";
+const ARGV_REQUIREMENT: &str = ">= 1.0.0 and < 2.0.0";
+const GLEAM_ERLANG_REQUIREMENT: &str = ">= 0.25.0 and < 1.0.0";
+const MIST_REQUIREMENT: &str = ">= 1.2.0 and < 3.0.0";
+const WISP_REQUIREMENT: &str = ">= 1.0.0 and < 2.0.0";
+const GLEESCRIPT_REQUIREMENT: &str = ">= 1.0.0 and < 2.0.0";
 const ERLANG_OTP_VERSION: &str = "26.0.2";
 const REBAR3_VERSION: &str = "3";
 const ELIXIR_VERSION: &str = "1.15.4";
 
-#[derive(
-    Debug, Serialize, Deserialize, Display, EnumString, VariantNames, ValueEnum, Clone, Copy,
-)]
-#[strum(serialize_all = "kebab_case")]
+/// The name of the file that marks a git repository as a project template.
+const TEMPLATE_MANIFEST: &str = "gleam-template.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Template {
+    /// A library, to be published to Hex.
     Lib,
+    /// A command line program, reading its arguments with `argv`.
+    Cli,
+    /// A web application, served with `wisp` and `mist`.
+    Web,
+    /// A program built into an escript with `gleescript`.
+    Escript,
+    /// A project copied from a git repository, with `{{project_name}}` and
+    /// `{{gleam_version}}` replaced in the names and contents of its files.
+    Git(String),
+}
+
+impl Template {
+    fn dependencies(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Cli => &[("argv", ARGV_REQUIREMENT)],
+            Self::Web => &[
+                ("gleam_erlang", GLEAM_ERLANG_REQUIREMENT),
+                ("mist", MIST_REQUIREMENT),
+                ("wisp", WISP_REQUIREMENT),
+            ],
+            Self::Lib | Self::Escript | Self::Git(_) => &[],
+        }
+    }
+
+    fn dev_dependencies(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Escript => &[("gleescript", GLEESCRIPT_REQUIREMENT)],
+            Self::Lib | Self::Cli | Self::Web | Self::Git(_) => &[],
+        }
+    }
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        match template.to_lowercase().as_str() {
+            "lib" => Ok(Self::Lib),
+            "cli" => Ok(Self::Cli),
+            "web" => Ok(Self::Web),
+            "escript" => Ok(Self::Escript),
+            _ if is_git_repository_url(template) => Ok(Self::Git(template.to_string())),
+            _ => Err(format!(
+                "unknown template `{template}`, expected one of lib, cli, web, escript, \
+or the URL of a git repository"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lib => f.write_str("lib"),
+            Self::Cli => f.write_str("cli"),
+            Self::Web => f.write_str("web"),
+            Self::Escript => f.write_str("escript"),
+            Self::Git(repository) => f.write_str(repository),
+        }
+    }
+}
+
+fn is_git_repository_url(template: &str) -> bool {
+    template.contains("://") || template.starts_with("git@") || template.ends_with(".git")
+}
+
+/// The `gleam-template.toml` file at the root of a template repository.
+#[derive(Debug, Default, Deserialize)]
+struct TemplateManifest {
+    /// Files and directories of the repository that are not copied into new
+    /// projects, such as the documentation of the template itself.
+    #[serde(default)]
+    exclude: Vec<Utf8PathBuf>,
 }
 
 #[derive(Debug)]
@@ -76,6 +158,34 @@ impl FileToCreate {
         let gleam_version = creator.gleam_version;
 
         match self {
+            Self::Readme if creator.options.template != Template::Lib => {
+                let usage = match creator.options.template {
+                    Template::Cli => "```sh\ngleam run hello Lucy\n```".to_string(),
+                    Template::Web => {
+                        "The application is served at <http://localhost:8000> when run.".into()
+                    }
+                    Template::Escript => format!(
+                        "The project can be built into an escript that runs anywhere Erlang is \
+installed:\n\n```sh\ngleam run -m gleescript\n./{project_name}\n```"
+                    ),
+                    Template::Lib | Template::Git(_) => String::new(),
+                };
+                Some(format!(
+                    r#"# {project_name}
+
+{usage}
+
+## Development
+
+```sh
+gleam run   # Run the project
+gleam test  # Run the tests
+gleam shell # Run an Erlang shell
+```
+"#,
+                ))
+            }
+
             Self::Readme => Some(format!(
                 r#"# {project_name}
 
@@ -114,6 +224,46 @@ erl_crash.dump
                 .into(),
             ),
 
+            Self::SrcModule if creator.options.template == Template::Cli => Some(format!(
+                r#"import argv
+import gleam/io
+
+pub fn main() {{
+  case argv.load().arguments {{
+    ["hello", name] -> io.println("Hello from {project_name}, " <> name <> "!")
+    _ -> io.println("usage: {project_name} hello <name>")
+  }}
+}}
+"#,
+            )),
+
+            Self::SrcModule if creator.options.template == Template::Web => Some(format!(
+                r#"import gleam/erlang/process
+import mist
+import wisp
+import wisp/wisp_mist
+
+pub fn main() {{
+  wisp.configure_logger()
+  let secret_key_base = wisp.random_string(64)
+
+  let assert Ok(_) =
+    wisp_mist.handler(handle_request, secret_key_base)
+    |> mist.new
+    |> mist.port(8000)
+    |> mist.start_http
+
+  process.sleep_forever()
+}}
+
+fn handle_request(request: wisp.Request) -> wisp.Response {{
+  use <- wisp.log_request(request)
+  wisp.ok()
+  |> wisp.string_body("Hello from {project_name}!")
+}}
+"#,
+            )),
+
             Self::SrcModule => Some(format!(
                 r#"import gleam/io
 
@@ -157,10 +307,12 @@ version = "1.0.0"
 
 [dependencies]
 gleam_stdlib = "{GLEAM_STDLIB_REQUIREMENT}"
-
+{dependencies}
 [dev-dependencies]
 gleeunit = "{GLEEUNIT_REQUIREMENT}"
-"#,
+{dev_dependencies}"#,
+                dependencies = requirements(creator.options.template.dependencies()),
+                dev_dependencies = requirements(creator.options.template.dev_dependencies()),
             )),
 
             Self::GithubCi if !skip_git && !skip_github => Some(format!(
@@ -228,6 +380,10 @@ impl Creator {
     }
 
     fn run(&self) -> Result<()> {
+        if let Template::Git(repository) = &self.options.template {
+            return self.create_from_git(repository);
+        }
+
         crate::fs::mkdir(&self.root)?;
         crate::fs::mkdir(&self.src)?;
         crate::fs::mkdir(&self.test)?;
@@ -241,19 +397,145 @@ impl Creator {
             crate::fs::git_init(&self.root)?;
         }
 
-        match self.options.template {
-            Template::Lib => {
-                for file in FileToCreate::iter() {
-                    let path = file.location(self);
-                    if let Some(contents) = file.contents(self) {
-                        write(path, &contents)?;
-                    }
-                }
+        for file in FileToCreate::iter() {
+            let path = file.location(self);
+            if let Some(contents) = file.contents(self) {
+                write(path, &contents)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn create_from_git(&self, repository: &str) -> Result<()> {
+        let checkout = env::temp_dir().join(format!("gleam-template-{}", std::process::id()));
+        let checkout = Utf8PathBuf::from_path_buf(checkout).expect("Non Utf8 Path");
+        crate::fs::delete_directory(&checkout)?;
+        clone_template(repository, &checkout)?;
+        crate::fs::mkdir(&self.root)?;
+        let result = self.copy_template(repository, &checkout);
+        crate::fs::delete_directory(&checkout)?;
+        result?;
+
+        if !self.options.skip_git {
+            crate::fs::git_init(&self.root)?;
+        }
+        Ok(())
+    }
+
+    /// Copies the files of a template into the project, replacing the
+    /// template variables in their paths and contents. Nothing is written if
+    /// any of the files already exist in the project.
+    fn copy_template(&self, template: &str, directory: &Utf8Path) -> Result<()> {
+        let manifest_path = directory.join(TEMPLATE_MANIFEST);
+        if !manifest_path.is_file() {
+            return Err(Error::ProjectTemplate {
+                template: template.into(),
+                error: format!("The repository has no `{TEMPLATE_MANIFEST}` file."),
+            });
+        }
+        let manifest: TemplateManifest = toml::from_str(&crate::fs::read(&manifest_path)?)
+            .map_err(|error| Error::ProjectTemplate {
+                template: template.into(),
+                error: error.to_string(),
+            })?;
+
+        let mut files = vec![];
+        for entry in walkdir::WalkDir::new(directory)
+            .min_depth(1)
+            .into_iter()
+            .filter_entry(|entry| entry.depth() > 1 || entry.file_name() != ".git")
+        {
+            let entry = entry.map_err(|error| Error::FileIo {
+                kind: FileKind::Directory,
+                path: directory.to_path_buf(),
+                action: FileIoAction::Read,
+                err: Some(error.to_string()),
+            })?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = Utf8PathBuf::from_path_buf(entry.into_path()).expect("Non Utf8 Path");
+            let relative = path
+                .strip_prefix(directory)
+                .expect("template file in template directory");
+            if relative.as_str() == TEMPLATE_MANIFEST
+                || !self.copies_template_file(&manifest, relative)
+            {
+                continue;
             }
+            let destination = self.root.join(self.fill_in_template(relative.as_str()));
+            files.push((path, destination));
+        }
+
+        let duplicate_files: Vec<_> = files
+            .iter()
+            .map(|(_, destination)| destination)
+            .filter(|destination| destination.exists())
+            .cloned()
+            .collect();
+        if !duplicate_files.is_empty() {
+            return Err(Error::OutputFilesAlreadyExist {
+                file_names: duplicate_files,
+            });
         }
 
+        for (source, destination) in files {
+            if let Some(parent) = destination.parent() {
+                crate::fs::mkdir(parent)?;
+            }
+            let contents = crate::fs::read_bytes(&source)?;
+            match String::from_utf8(contents) {
+                Ok(text) => write(destination, &self.fill_in_template(&text))?,
+                Err(error) => crate::fs::write_bytes(&destination, &error.into_bytes())?,
+            }
+        }
         Ok(())
     }
+
+    fn copies_template_file(&self, manifest: &TemplateManifest, path: &Utf8Path) -> bool {
+        let skip_git = self.options.skip_git;
+        let skip_github = self.options.skip_git || self.options.skip_github;
+        let excluded = manifest
+            .exclude
+            .iter()
+            .any(|excluded| path.starts_with(excluded));
+        let skipped = (skip_git && path.as_str() == ".gitignore")
+            || (skip_github && path.starts_with(".github"));
+        !excluded && !skipped
+    }
+
+    fn fill_in_template(&self, text: &str) -> String {
+        text.replace("{{project_name}}", &self.project_name)
+            .replace("{{gleam_version}}", self.gleam_version)
+    }
+}
+
+fn requirements(requirements: &[(&str, &str)]) -> String {
+    requirements
+        .iter()
+        .map(|(name, requirement)| format!("{name} = \"{requirement}\"\n"))
+        .collect()
+}
+
+fn clone_template(repository: &str, destination: &Utf8Path) -> Result<()> {
+    let args = [
+        "clone",
+        "--depth",
+        "1",
+        "--quiet",
+        repository,
+        destination.as_str(),
+    ]
+    .map(String::from);
+    let status = ProjectIO::new().exec("git", &args, &[], None, Stdio::Inherit)?;
+    if status != 0 {
+        return Err(Error::ProjectTemplate {
+            template: repository.into(),
+            error: "The repository could not be cloned with git.".into(),
+        });
+    }
+    Ok(())
 }
 
 pub fn create(options: NewOptions, version: &'static str) -> Result<()> {
@@ -297,6 +579,12 @@ fn write(path: Utf8PathBuf, contents: &str) -> Result<()> {
 }
 
 fn validate_root_folder(creator: &Creator) -> Result<(), Error> {
+    // The files of a git template are only known once it has been cloned, so
+    // they are checked then.
+    if let Template::Git(_) = creator.options.template {
+        return Ok(());
+    }
+
     let mut duplicate_files: Vec<Utf8PathBuf> = Vec::new();
 
     for t in FileToCreate::iter() {
@@ -241,3 +241,128 @@ fn conflict_with_existing_files() {
         })
     );
 }
+
+#[test]
+fn parse_template() {
+    assert_eq!("lib".parse(), Ok(super::Template::Lib));
+    assert_eq!("CLI".parse(), Ok(super::Template::Cli));
+    assert_eq!("web".parse(), Ok(super::Template::Web));
+    assert_eq!("escript".parse(), Ok(super::Template::Escript));
+    assert_eq!(
+        "https://github.com/someone/template".parse(),
+        Ok(super::Template::Git(
+            "https://github.com/someone/template".into()
+        ))
+    );
+    assert_eq!(
+        "git@github.com:someone/template.git".parse(),
+        Ok(super::Template::Git(
+            "git@github.com:someone/template.git".into()
+        ))
+    );
+    assert!("website".parse::<super::Template>().is_err());
+}
+
+#[test]
+fn new_cli() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = Utf8PathBuf::from_path_buf(tmp.path().join("my_project")).expect("Non Utf8 Path");
+
+    let creator = super::Creator::new(
+        super::NewOptions {
+            project_root: path.to_string(),
+            template: super::Template::Cli,
+            name: None,
+            skip_git: true,
+            skip_github: true,
+        },
+        "1.0.0-gleam",
+    )
+    .unwrap();
+
+    creator.run().unwrap();
+
+    let toml = crate::fs::read(path.join("gleam.toml")).unwrap();
+    assert!(toml.contains(&format!("argv = \"{}\"", super::ARGV_REQUIREMENT)));
+    let module = crate::fs::read(path.join("src/my_project.gleam")).unwrap();
+    assert!(module.contains("import argv"));
+}
+
+#[test]
+fn new_from_template_directory() {
+    let tmp = tempfile::tempdir().unwrap();
+    let template = Utf8PathBuf::from_path_buf(tmp.path().join("template")).expect("Non Utf8 Path");
+    let path = Utf8PathBuf::from_path_buf(tmp.path().join("my_project")).expect("Non Utf8 Path");
+
+    crate::fs::mkdir(template.join("src")).unwrap();
+    crate::fs::mkdir(template.join("docs")).unwrap();
+    crate::fs::write(
+        &template.join("gleam-template.toml"),
+        "exclude = [\"docs\"]\n",
+    )
+    .unwrap();
+    crate::fs::write(
+        &template.join("gleam.toml"),
+        "name = \"{{project_name}}\"\n",
+    )
+    .unwrap();
+    crate::fs::write(
+        &template.join("src/{{project_name}}.gleam"),
+        "// Made with Gleam {{gleam_version}}\n",
+    )
+    .unwrap();
+    crate::fs::write(&template.join("docs/template.md"), "# Template\n").unwrap();
+    crate::fs::write(&template.join(".gitignore"), "/build\n").unwrap();
+
+    let creator = super::Creator::new(
+        super::NewOptions {
+            project_root: path.to_string(),
+            template: super::Template::Git(template.to_string()),
+            name: None,
+            skip_git: true,
+            skip_github: true,
+        },
+        "1.0.0-gleam",
+    )
+    .unwrap();
+
+    crate::fs::mkdir(&path).unwrap();
+    creator.copy_template(template.as_str(), &template).unwrap();
+
+    assert_eq!(
+        crate::fs::read(path.join("gleam.toml")).unwrap(),
+        "name = \"my_project\"\n"
+    );
+    assert_eq!(
+        crate::fs::read(path.join("src/my_project.gleam")).unwrap(),
+        "// Made with Gleam 1.0.0-gleam\n"
+    );
+    assert!(!path.join("gleam-template.toml").exists());
+    assert!(!path.join("docs").exists());
+    assert!(!path.join(".gitignore").exists());
+}
+
+#[test]
+fn template_directory_without_manifest() {
+    let tmp = tempfile::tempdir().unwrap();
+    let template = Utf8PathBuf::from_path_buf(tmp.path().join("template")).expect("Non Utf8 Path");
+    let path = Utf8PathBuf::from_path_buf(tmp.path().join("my_project")).expect("Non Utf8 Path");
+    crate::fs::mkdir(&template).unwrap();
+
+    let creator = super::Creator::new(
+        super::NewOptions {
+            project_root: path.to_string(),
+            template: super::Template::Git(template.to_string()),
+            name: None,
+            skip_git: true,
+            skip_github: true,
+        },
+        "1.0.0-gleam",
+    )
+    .unwrap();
+
+    assert!(matches!(
+        creator.copy_template(template.as_str(), &template),
+        Err(Error::ProjectTemplate { .. })
+    ));
+}
@@ -4,7 +4,10 @@ use gleam_core::{
     analyse::TargetSupport,
     build::{Codegen, Mode, Options, Package, Target},
     config::{PackageConfig, SpdxLicense},
-    hex, paths,
+    hex,
+    manifest::Manifest,
+    package_interface::{compatibility, PackageInterface},
+    paths,
     paths::ProjectPaths,
     requirement::Requirement,
     Error, Result,
@@ -16,11 +19,34 @@ use std::{io::Write, path::PathBuf, time::Instant};
 
 use crate::{build, cli, docs, fs, hex::ApiKeyCommand, http::HttpClient};
 
-pub fn command(replace: bool, yes: bool) -> Result<()> {
-    let command = PublishCommand::setup(replace, yes)?;
+#[derive(Debug)]
+pub struct PublishOptions {
+    pub replace: bool,
+    pub yes: bool,
+    pub check_semver: bool,
+    /// Whether to build the tarballs without publishing them.
+    pub dry_run: bool,
+    /// Where to write the package tarball of a dry run.
+    pub output: Option<Utf8PathBuf>,
+}
+
+pub fn command(options: PublishOptions) -> Result<()> {
+    let PublishOptions {
+        replace,
+        yes,
+        check_semver,
+        dry_run,
+        output,
+    } = options;
+    // A dry run publishes nothing, so there is nothing to confirm
+    let command = PublishCommand::setup(replace, yes || dry_run, check_semver)?;
 
     if let Some(mut command) = command {
-        command.run()?;
+        if dry_run {
+            command.dry_run(output.as_deref())?;
+        } else {
+            command.run()?;
+        }
     }
     Ok(())
 }
@@ -33,7 +59,7 @@ pub struct PublishCommand {
 }
 
 impl PublishCommand {
-    pub fn setup(replace: bool, i_am_sure: bool) -> Result<Option<Self>> {
+    pub fn setup(replace: bool, i_am_sure: bool, check_semver: bool) -> Result<Option<Self>> {
         let paths = crate::find_project_paths()?;
         let config = crate::config::root_config()?;
 
@@ -49,29 +75,38 @@ impl PublishCommand {
         let Tarball {
             mut compile_result,
             data: package_tarball,
+            checksum,
             src_files_added,
             generated_files_added,
+            manifest,
         } = do_build_hex_tarball(&paths, &config)?;
 
         check_for_name_squatting(&compile_result)?;
+        if check_semver {
+            check_for_breaking_changes(&config, &compile_result)?;
+        }
 
         // Build HTML documentation
-        let docs_tarball =
-            fs::create_tar_archive(docs::build_documentation(&config, &mut compile_result)?)?;
+        let docs_tarball = fs::create_tar_archive(docs::build_documentation(
+            &config,
+            &mut compile_result,
+            &manifest,
+        )?)?;
 
         // Ask user if this is correct
         if !generated_files_added.is_empty() {
             println!("\nGenerated files:");
-            for file in generated_files_added.iter().sorted() {
+            for file in generated_files_added.iter() {
                 println!("  - {}", file.0);
             }
         }
         println!("\nSource files:");
-        for file in src_files_added.iter().sorted() {
+        for file in src_files_added.iter() {
             println!("  - {}", file);
         }
         println!("\nName: {}", config.name);
         println!("Version: {}", config.version);
+        println!("Checksum: {checksum}");
 
         let should_publish = i_am_sure || cli::confirm("\nDo you wish to publish this package?")?;
         if !should_publish {
@@ -86,6 +121,27 @@ impl PublishCommand {
             replace,
         }))
     }
+
+    /// Writes the tarballs that would be published to Hex instead of
+    /// publishing them, so they can be inspected. The documentation tarball
+    /// is written next to the package tarball.
+    fn dry_run(&self, output: Option<&Utf8Path>) -> Result<()> {
+        if let Some(path) = output {
+            let docs_path = docs_tarball_path(path);
+            fs::write_bytes(path, &self.package_tarball)?;
+            fs::write_bytes(&docs_path, &self.docs_tarball)?;
+            println!("\nPackage tarball written to {path}");
+            println!("Documentation tarball written to {docs_path}");
+        }
+        println!("\nDry run, not publishing.");
+        Ok(())
+    }
+}
+
+/// The documentation tarball for `package.tar` is `package-docs.tar.gz`.
+fn docs_tarball_path(package_tarball: &Utf8Path) -> Utf8PathBuf {
+    let name = package_tarball.file_stem().unwrap_or("package");
+    package_tarball.with_file_name(format!("{name}-docs.tar.gz"))
 }
 
 fn check_for_name_squatting(package: &Package) -> Result<(), Error> {
@@ -118,6 +174,65 @@ fn check_for_name_squatting(package: &Package) -> Result<(), Error> {
     Ok(())
 }
 
+/// Refuses to publish a release that makes breaking changes to the API of the
+/// latest release before it, unless it is a new major version. The API of the
+/// previous release is read from the package interface published with its
+/// documentation, and the release is refused if that can't be downloaded.
+fn check_for_breaking_changes(config: &PackageConfig, package: &Package) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let Some(previous_version) = previous_release(runtime.handle(), config)? else {
+        println!("No earlier release of this package, so there are no breaking changes to check.");
+        return Ok(());
+    };
+
+    let url = format!(
+        "https://hexdocs.pm/{}/{previous_version}/package-interface.json",
+        config.name
+    );
+    let unavailable = |reason: String| Error::PreviousPackageInterfaceUnavailable {
+        version: previous_version.to_string(),
+        reason,
+    };
+    let json = docs::download_text(runtime.handle(), &url)?
+        .ok_or_else(|| unavailable(format!("it was not found at {url}")))?;
+    let previous = serde_json::from_str::<PackageInterface>(&json)
+        .map_err(|error| unavailable(format!("it could not be parsed: {error}")))?;
+
+    let changes =
+        compatibility::breaking_changes(&previous, &PackageInterface::from_package(package));
+    if changes.is_empty()
+        || compatibility::allows_breaking_changes(&previous_version, &config.version)
+    {
+        return Ok(());
+    }
+    Err(Error::BreakingChanges {
+        previous_version: previous_version.to_string(),
+        version: config.version.to_string(),
+        changes,
+    })
+}
+
+/// The latest release of the package that comes before the version being
+/// published, not counting pre-releases.
+fn previous_release(
+    handle: &tokio::runtime::Handle,
+    config: &PackageConfig,
+) -> Result<Option<Version>> {
+    let package = handle.block_on(hex::get_package(
+        &config.name,
+        &hexpm::Config::new(),
+        &HttpClient::new(),
+    ))?;
+    Ok(package.and_then(|package| {
+        package
+            .releases
+            .into_iter()
+            .map(|release| release.version)
+            .filter(|version| !version.is_pre() && *version < config.version)
+            .max()
+    }))
+}
+
 fn check_repo_url(config: &PackageConfig, i_am_sure: bool) -> Result<bool, Error> {
     let Some(url) = config.repository.url() else {
         return Ok(true);
@@ -233,8 +348,10 @@ HTML documentation will work:
 struct Tarball {
     compile_result: Package,
     data: Vec<u8>,
+    checksum: String,
     src_files_added: Vec<Utf8PathBuf>,
     generated_files_added: Vec<(Utf8PathBuf, String)>,
+    manifest: Manifest,
 }
 
 pub fn build_hex_tarball(paths: &ProjectPaths, config: &PackageConfig) -> Result<Vec<u8>> {
@@ -250,15 +367,17 @@ fn do_build_hex_tarball(paths: &ProjectPaths, config: &PackageConfig) -> Result<
     fs::delete_directory(&paths.build_directory_for_target(Mode::Prod, target))?;
 
     // Build the project to check that it is valid
+    let manifest = build::download_dependencies()?;
     let built = build::main(
         Options {
             root_target_support: TargetSupport::Enforced,
+            doc_tests: false,
             warnings_as_errors: false,
             mode: Mode::Prod,
             target: Some(target),
             codegen: Codegen::All,
         },
-        build::download_dependencies()?,
+        manifest.clone(),
     )?;
 
     // If any of the modules in the package contain a todo then refuse to
@@ -280,12 +399,15 @@ fn do_build_hex_tarball(paths: &ProjectPaths, config: &PackageConfig) -> Result<
     // We need to move aliases in to the type system first.
     // context: https://discord.com/channels/768594524158427167/768594524158427170/1227250677734969386
 
-    // Collect all the files we want to include in the tarball
-    let generated_files = match target {
+    // Collect all the files we want to include in the tarball. They are sorted
+    // so that the same project always produces the same tarball.
+    let mut generated_files = match target {
         Target::Erlang => generated_erlang_files(paths, &built.root_package)?,
         Target::JavaScript => vec![],
     };
-    let src_files = project_files()?;
+    generated_files.sort();
+    let mut src_files = project_files()?;
+    src_files.sort();
     let contents_tar_gz = contents_tarball(&src_files, &generated_files)?;
     let version = "3";
     let metadata = metadata_config(&built.root_package.config, &src_files, &generated_files)?;
@@ -312,8 +434,10 @@ fn do_build_hex_tarball(paths: &ProjectPaths, config: &PackageConfig) -> Result<
     Ok(Tarball {
         compile_result: built.root_package,
         data: tarball,
+        checksum,
         src_files_added: src_files,
         generated_files_added: generated_files,
+        manifest,
     })
 }
 
@@ -339,6 +463,7 @@ fn metadata_config<'a>(
     let requirements: Result<Vec<ReleaseRequirement<'a>>> = config
         .dependencies
         .iter()
+        .sorted_by_key(|(name, _)| *name)
         .map(|(name, requirement)| match requirement {
             Requirement::Hex { version } => Ok(ReleaseRequirement {
                 name,
@@ -378,6 +503,9 @@ fn contents_tarball(
     {
         let mut tarball =
             tar::Builder::new(GzEncoder::new(&mut contents_tar_gz, Compression::default()));
+        // Leave out the modification times and owners of the files, which
+        // would make the tarball differ each time it is built
+        tarball.mode(tar::HeaderMode::Deterministic);
         for path in files {
             add_path_to_tar(&mut tarball, path)?;
         }
@@ -686,6 +814,29 @@ fn prevent_publish_git_dependency() {
     );
 }
 
+#[test]
+fn metadata_requirements_are_sorted() {
+    let mut config = PackageConfig::default();
+    config.dependencies = [
+        ("wobble".into(), Requirement::hex("~> 1.0")),
+        ("wibble".into(), Requirement::hex("~> 2.0")),
+        ("gleam_stdlib".into(), Requirement::hex("~> 0.34")),
+    ]
+    .into();
+    let metadata = metadata_config(&config, &[], &[]).unwrap();
+    let position = |name: &str| metadata.find(&format!("<<\"{name}\">>")).unwrap();
+    assert!(position("gleam_stdlib") < position("wibble"));
+    assert!(position("wibble") < position("wobble"));
+}
+
+#[test]
+fn docs_tarball_path_is_next_to_package_tarball() {
+    assert_eq!(
+        docs_tarball_path(Utf8Path::new("out/package.tar")),
+        Utf8PathBuf::from("out/package-docs.tar.gz")
+    );
+}
+
 pub fn get_hostname() -> String {
     hostname::get()
         .expect("Looking up hostname")
@@ -2,10 +2,11 @@ use camino::{Utf8Path, Utf8PathBuf};
 
 use gleam_core::{
     error::{FileIoAction, FileKind},
+    manifest::{Manifest, ManifestPackage},
     Error, Result,
 };
 
-use crate::{cli, fs, UseManifest};
+use crate::{cli, fs, UseManifest, UseNetwork};
 
 pub fn command(packages: Vec<String>) -> Result<()> {
     // Read gleam.toml so we can remove deps from it
@@ -33,10 +34,88 @@ pub fn command(packages: Vec<String>) -> Result<()> {
     // Write the updated config
     fs::write(Utf8Path::new("gleam.toml"), &toml.to_string())?;
     let paths = crate::find_project_paths()?;
-    _ = crate::dependencies::download(&paths, cli::Reporter::new(), None, UseManifest::Yes)?;
-    for package_to_remove in packages {
-        cli::print_removed(&package_to_remove);
+    let previous_manifest = if paths.manifest().exists() {
+        Some(crate::dependencies::read_manifest_from_disc(&paths)?)
+    } else {
+        None
+    };
+    let manifest = crate::dependencies::download(
+        &paths,
+        cli::Reporter::new(),
+        None,
+        UseManifest::Yes,
+        UseNetwork::from_environment(),
+    )?;
+    for package_to_remove in &packages {
+        cli::print_removed(package_to_remove);
+    }
+
+    // Resolving the versions again drops the packages that were only needed by
+    // the removed ones from the manifest, so those are reported too
+    if let Some(previous_manifest) = previous_manifest {
+        for package in no_longer_needed(&previous_manifest, &manifest, &packages) {
+            cli::print_removed(&format!("{} v{}", package.name, package.version));
+        }
     }
 
     Ok(())
 }
+
+/// The packages of the previous manifest that are not in the new one, other
+/// than the ones that were removed by name.
+fn no_longer_needed<'a>(
+    previous: &'a Manifest,
+    current: &Manifest,
+    removed: &[String],
+) -> Vec<&'a ManifestPackage> {
+    previous
+        .packages
+        .iter()
+        .filter(|package| !removed.iter().any(|name| package.name == name.as_str()))
+        .filter(|package| {
+            !current
+                .packages
+                .iter()
+                .any(|current| current.name == package.name)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gleam_core::manifest::{Base16Checksum, ManifestPackageSource};
+
+    fn manifest(names: &[&str]) -> Manifest {
+        Manifest {
+            requirements: Default::default(),
+            packages: names
+                .iter()
+                .map(|name| ManifestPackage {
+                    name: (*name).into(),
+                    version: hexpm::version::Version::new(1, 0, 0),
+                    build_tools: vec!["gleam".into()],
+                    otp_app: None,
+                    requirements: vec![],
+                    source: ManifestPackageSource::Hex {
+                        outer_checksum: Base16Checksum(vec![]),
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn packages_no_longer_needed() {
+        let previous = manifest(&["gleam_stdlib", "lustre", "gleam_json", "thoas"]);
+        let current = manifest(&["gleam_stdlib", "lustre"]);
+        let removed = no_longer_needed(&previous, &current, &["gleam_json".into()]);
+        assert_eq!(
+            removed
+                .iter()
+                .map(|package| package.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["thoas"]
+        );
+    }
+}
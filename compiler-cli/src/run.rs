@@ -4,20 +4,46 @@ use camino::Utf8PathBuf;
 use ecow::EcoString;
 use gleam_core::{
     analyse::TargetSupport,
-    build::{Built, Codegen, Mode, Options, Runtime, Target},
+    build::{doc_tests, Built, Codegen, Mode, Options, Runtime, Target},
     config::{DenoFlag, PackageConfig},
     error::Error,
     io::{CommandExecutor, Stdio},
+    javascript,
     paths::ProjectPaths,
     type_::ModuleFunction,
 };
 
-use crate::{config::PackageKind, fs::ProjectIO};
+use itertools::Itertools;
 
-#[derive(Debug, Clone, Copy)]
+use crate::{
+    config::PackageKind,
+    fs::ProjectIO,
+    watch::{self, Snapshot, Watcher},
+};
+
+mod coverage;
+mod test_protocol;
+
+pub use test_protocol::{ReportFormat, TestOptions};
+
+#[derive(Debug, Clone)]
 pub enum Which {
-    Src,
-    Test,
+    /// Run a function of a module, `main` unless another is given.
+    Src {
+        function: String,
+    },
+    Test(TestOptions),
+}
+
+/// Whether the program is run once, or run again each time the files of the
+/// project change.
+#[derive(Debug, Clone, Copy)]
+enum Execution<'a> {
+    Once,
+    Watching {
+        watcher: &'a Watcher,
+        since: &'a Snapshot,
+    },
 }
 
 // TODO: test
@@ -27,7 +53,45 @@ pub fn command(
     runtime: Option<Runtime>,
     module: Option<String>,
     which: Which,
+    watch: bool,
 ) -> Result<(), Error> {
+    if !watch {
+        let status = run(arguments, target, runtime, module, which, Execution::Once)?;
+        std::process::exit(status.unwrap_or_default());
+    }
+
+    let paths = crate::find_project_paths()?;
+    let watcher = Watcher::new(&paths, &crate::config::root_config()?);
+
+    // The program is stopped along with the watcher on ctrl+c
+    ctrlc::set_handler(move || std::process::exit(130)).expect("Error setting Ctrl-C handler");
+
+    watch::watch(&watcher, |since| {
+        let execution = Execution::Watching {
+            watcher: &watcher,
+            since,
+        };
+        run(
+            arguments.clone(),
+            target,
+            runtime,
+            module.clone(),
+            which.clone(),
+            execution,
+        )
+    })
+}
+
+/// Builds and runs the program, returning its exit status, or `None` if it
+/// was stopped because the files of the project changed.
+fn run(
+    arguments: Vec<String>,
+    target: Option<Target>,
+    runtime: Option<Runtime>,
+    module: Option<String>,
+    which: Which,
+    execution: Execution<'_>,
+) -> Result<Option<i32>, Error> {
     let paths = crate::find_project_paths()?;
 
     // Validate the module path
@@ -39,6 +103,13 @@ pub fn command(
         }
     };
 
+    // Doc tests are run along with the tests of the project, but not when a
+    // test module is given or only some of the tests are selected.
+    let run_doc_tests = match &which {
+        Which::Src { .. } => false,
+        Which::Test(options) => module.is_none() && !options.selects_tests(),
+    };
+
     // Download dependencies
     let manifest = crate::build::download_dependencies()?;
 
@@ -57,12 +128,23 @@ pub fn command(
 
     // Determine which module to run
     let module = module.unwrap_or(match which {
-        Which::Src => root_config.name.to_string(),
-        Which::Test => format!("{}_test", &root_config.name),
+        Which::Src { .. } => root_config.name.to_string(),
+        Which::Test(_) => format!("{}_test", &root_config.name),
     });
+    let function = match &which {
+        Which::Src { function } => function.as_str(),
+        Which::Test(_) => "main",
+    };
 
     let target = target.unwrap_or(mod_config.target);
 
+    // Coverage is recorded by the Erlang virtual machine, so it can't be
+    // reported when the tests are run by a JavaScript runtime.
+    let with_coverage = matches!(&which, Which::Test(options) if options.coverage);
+    if with_coverage && (target != Target::Erlang || runtime.is_some()) {
+        return Err(Error::UnsupportedCoverageTarget { target, runtime });
+    }
+
     let options = Options {
         warnings_as_errors: false,
         codegen: Codegen::All,
@@ -76,51 +158,259 @@ pub fn command(
             // only care if the dependency can compile for the current target.
             PackageKind::Dependency => TargetSupport::NotEnforced,
         },
+        doc_tests: run_doc_tests,
     };
 
-    let built = crate::build::main(options, manifest)?;
+    // The typed AST of every module is needed to report their coverage, even
+    // for the ones that have been cached.
+    let built = if with_coverage {
+        crate::build::main_recompiling_root_package(options, manifest)?
+    } else {
+        crate::build::main(options, manifest)?
+    };
+    let doc_tests = doc_tests(&paths, &built);
+    let test_run = match &which {
+        Which::Src { .. } => None,
+        Which::Test(options) => Some(test_protocol::TestRun::prepare(&paths, &built, options)?),
+    };
+    let environment = test_run
+        .as_ref()
+        .map(|run| run.environment())
+        .unwrap_or_default();
+    let coverage_run = if with_coverage {
+        Some(coverage::CoverageRun::new(&paths, &built)?)
+    } else {
+        None
+    };
 
-    // A module can not be run if it does not exist or does not have a public main function.
-    let main_function = get_or_suggest_main_function(built, &module, target)?;
+    // A module can not be run if it does not exist or does not have the public function.
+    let main_function = get_or_suggest_main_function(built, &module, function, target)?;
 
     // Don't exit on ctrl+c as it is used by child erlang shell
-    ctrlc::set_handler(move || {}).expect("Error setting Ctrl-C handler");
-
-    crate::cli::print_running(&format!("{module}.main"));
+    if let Execution::Once = execution {
+        ctrlc::set_handler(move || {}).expect("Error setting Ctrl-C handler");
+    }
 
-    // Run the command
-    let status = match target {
-        Target::Erlang => match runtime {
-            Some(r) => Err(Error::InvalidRuntime {
+    let runtime = match (target, runtime) {
+        (Target::Erlang, Some(r)) => {
+            return Err(Error::InvalidRuntime {
                 target: Target::Erlang,
                 invalid_runtime: r,
-            }),
-            _ => run_erlang(&paths, &root_config.name, &module, arguments),
-        },
-        Target::JavaScript => match runtime.unwrap_or(mod_config.javascript.runtime) {
-            Runtime::Deno => run_javascript_deno(
-                &paths,
+            })
+        }
+        (Target::Erlang, None) => None,
+        (Target::JavaScript, runtime) => Some(runtime.unwrap_or(mod_config.javascript.runtime)),
+    };
+
+    // Run the doc tests before the tests, without stopping if they fail.
+    let doc_tests_status = if doc_tests.is_empty() {
+        Some(0)
+    } else {
+        crate::cli::print_running("doc tests");
+        match runtime {
+            None => {
+                let eval = erlang_doc_tests_eval(&doc_tests);
+                run_erlang(&paths, eval, vec![], &[], execution)?
+            }
+            Some(runtime) => {
+                let entrypoint =
+                    write_javascript_doc_tests_entrypoint(&paths, &root_config.name, &doc_tests)?;
+                run_javascript(&root_config, runtime, entrypoint, vec![], &[], execution)?
+            }
+        }
+    };
+    let Some(doc_tests_status) = doc_tests_status else {
+        return Ok(None);
+    };
+
+    // With fail fast the tests are not run if a doc test failed.
+    if doc_tests_status != 0 && test_run.as_ref().is_some_and(|run| run.fail_fast()) {
+        return Ok(Some(doc_tests_status));
+    }
+
+    // Run the command
+    let status = match runtime {
+        None => {
+            crate::cli::print_running(&format!("{module}.{function}"));
+            // gleam modules are separated by `/`. Erlang modules are separated by `@`.
+            let module = module.replace('/', "@");
+            let eval = format!(
+                "{}@@main:run({module}, '{function}', {})",
+                root_config.name, main_function.takes_arguments
+            );
+            // With coverage the modules are compiled for it before running.
+            let eval = match &coverage_run {
+                Some(coverage_run) => coverage_run.erlang_eval(&paths, &eval),
+                None => eval,
+            };
+            run_erlang(&paths, eval, arguments, &environment, execution)
+        }
+        Some(runtime) => {
+            crate::cli::print_running(&format!("{module}.{function}"));
+            let entrypoint =
+                write_javascript_entrypoint(&paths, &main_function, &module, function)?;
+            run_javascript(
                 &root_config,
-                &main_function.package,
-                &module,
+                runtime,
+                entrypoint,
                 arguments,
-            ),
-            Runtime::NodeJs => {
-                run_javascript_node(&paths, &main_function.package, &module, arguments)
-            }
-            Runtime::Bun => run_javascript_bun(&paths, &main_function.package, &module, arguments),
-        },
+                &environment,
+                execution,
+            )
+        }
     }?;
+    let Some(status) = status else {
+        return Ok(None);
+    };
 
-    std::process::exit(status);
+    if let Some(test_run) = test_run {
+        test_run.finish()?;
+    }
+    if let Some(coverage_run) = coverage_run {
+        coverage_run.report(&paths)?;
+    }
+
+    Ok(Some(if status == 0 {
+        doc_tests_status
+    } else {
+        status
+    }))
 }
 
-fn run_erlang(
+/// A module generated for a doc test, along with the location of the example
+/// it was generated from.
+struct DocTest {
+    module: EcoString,
+    location: String,
+}
+
+fn doc_tests(paths: &ProjectPaths, built: &Built) -> Vec<DocTest> {
+    built
+        .root_package
+        .modules
+        .iter()
+        .filter(|module| doc_tests::is_doc_test_module(&module.name))
+        .map(|module| {
+            let path = module
+                .input_path
+                .strip_prefix(paths.root())
+                .unwrap_or(&module.input_path);
+            let line = doc_tests::example_line(&module.name).unwrap_or_default();
+            DocTest {
+                module: module.name.clone(),
+                location: format!("{path}:{line}"),
+            }
+        })
+        .sorted_by(|a, b| a.module.cmp(&b.module))
+        .collect()
+}
+
+/// An Erlang expression that runs each doc test, printing the ones that
+/// fail, and halts with a non-zero status if any did.
+fn erlang_doc_tests_eval(doc_tests: &[DocTest]) -> String {
+    let tests = doc_tests
+        .iter()
+        .map(|test| {
+            let module = test.module.replace("/", "@");
+            let location = test.location.replace('\\', "\\\\").replace('"', "\\\"");
+            format!("{{'{module}', <<\"{location}\"/utf8>>}}")
+        })
+        .join(", ");
+    format!(
+        r#"Tests = [{tests}],
+Failures = lists:filter(fun({{Module, Location}}) ->
+    try Module:{function}(), false
+    catch _:Error ->
+        io:format("Doc test failed: ~ts~n~p~n~n", [Location, Error]),
+        true
+    end
+end, Tests),
+io:format("~p doc tests, ~p failed~n", [length(Tests), length(Failures)]),
+halt(case Failures of [] -> 0; _ -> 1 end)."#,
+        function = doc_tests::FUNCTION,
+    )
+}
+
+fn write_javascript_doc_tests_entrypoint(
     paths: &ProjectPaths,
     package: &str,
-    module: &str,
+    doc_tests: &[DocTest],
+) -> Result<Utf8PathBuf, Error> {
+    let path = paths
+        .build_directory_for_package(Mode::Dev, Target::JavaScript, package)
+        .join("gleam.doc_tests.mjs");
+    let tests = doc_tests
+        .iter()
+        .map(|test| {
+            let module = format!("./{}.mjs", test.module);
+            let test = (module.as_str(), test.location.as_str());
+            serde_json::to_string(&test).expect("doc test serialisation")
+        })
+        .join(",\n  ");
+    let module = format!(
+        r#"const tests = [
+  {tests}
+];
+let failures = 0;
+for (const [path, location] of tests) {{
+  try {{
+    const module = await import(path);
+    module.{function}();
+  }} catch (error) {{
+    failures++;
+    console.log(`Doc test failed: ${{location}}`);
+    console.log(error);
+    console.log();
+  }}
+}}
+console.log(`${{tests.length}} doc tests, ${{failures}} failed`);
+const exit = globalThis.Deno ? globalThis.Deno.exit : process.exit;
+exit(failures === 0 ? 0 : 1);
+"#,
+        function = doc_tests::FUNCTION,
+    );
+    crate::fs::write(&path, &module)?;
+    Ok(path)
+}
+
+fn run_javascript(
+    config: &PackageConfig,
+    runtime: Runtime,
+    entrypoint: Utf8PathBuf,
     arguments: Vec<String>,
-) -> Result<i32, Error> {
+    env: &[(&str, String)],
+    execution: Execution<'_>,
+) -> Result<Option<i32>, Error> {
+    match runtime {
+        Runtime::Deno => run_javascript_deno(config, entrypoint, arguments, env, execution),
+        Runtime::NodeJs => run_javascript_node(entrypoint, arguments, env, execution),
+        Runtime::Bun => run_javascript_bun(entrypoint, arguments, env, execution),
+    }
+}
+
+fn execute(
+    program: &str,
+    args: &[String],
+    env: &[(&str, String)],
+    execution: Execution<'_>,
+) -> Result<Option<i32>, Error> {
+    match execution {
+        Execution::Once => ProjectIO::new()
+            .exec(program, args, env, None, Stdio::Inherit)
+            .map(Some),
+        Execution::Watching { watcher, since } => {
+            watcher.run_until_changed(since, program, args, env)
+        }
+    }
+}
+
+fn run_erlang(
+    paths: &ProjectPaths,
+    eval: String,
+    arguments: Vec<String>,
+    env: &[(&str, String)],
+    execution: Execution<'_>,
+) -> Result<Option<i32>, Error> {
     let mut args = vec![];
 
     // Specify locations of Erlang applications
@@ -131,11 +421,8 @@ fn run_erlang(
         args.push(entry.path().join("ebin").into());
     }
 
-    // gleam modules are separated by `/`. Erlang modules are separated by `@`.
-    let module = module.replace('/', "@");
-
     args.push("-eval".into());
-    args.push(format!("{package}@@main:run({module})"));
+    args.push(eval);
 
     // Don't run the Erlang shell
     args.push("-noshell".into());
@@ -146,75 +433,84 @@ fn run_erlang(
         args.push(argument);
     }
 
-    ProjectIO::new().exec("erl", &args, &[], None, Stdio::Inherit)
+    execute("erl", &args, env, execution)
 }
 
 fn run_javascript_bun(
-    paths: &ProjectPaths,
-    package: &str,
-    module: &str,
+    entrypoint: Utf8PathBuf,
     arguments: Vec<String>,
-) -> Result<i32, Error> {
+    env: &[(&str, String)],
+    execution: Execution<'_>,
+) -> Result<Option<i32>, Error> {
     let mut args = vec!["run".to_string()];
-    let entry = write_javascript_entrypoint(paths, package, module)?;
 
-    args.push(entry.to_string());
+    args.push(entrypoint.to_string());
 
     for arg in arguments.into_iter() {
         args.push(arg);
     }
 
-    ProjectIO::new().exec("bun", &args, &[], None, Stdio::Inherit)
+    execute("bun", &args, env, execution)
 }
 
 fn run_javascript_node(
-    paths: &ProjectPaths,
-    package: &str,
-    module: &str,
+    entrypoint: Utf8PathBuf,
     arguments: Vec<String>,
-) -> Result<i32, Error> {
+    env: &[(&str, String)],
+    execution: Execution<'_>,
+) -> Result<Option<i32>, Error> {
     let mut args = vec![];
-    let entry = write_javascript_entrypoint(paths, package, module)?;
 
-    args.push(entry.to_string());
+    args.push(entrypoint.to_string());
 
     for argument in arguments.into_iter() {
         args.push(argument);
     }
 
-    ProjectIO::new().exec("node", &args, &[], None, Stdio::Inherit)
+    execute("node", &args, env, execution)
 }
 
 fn write_javascript_entrypoint(
     paths: &ProjectPaths,
-    package: &str,
+    main_function: &ModuleFunction,
     module: &str,
+    function: &str,
 ) -> Result<Utf8PathBuf, Error> {
     let path = paths
-        .build_directory_for_package(Mode::Dev, Target::JavaScript, package)
+        .build_directory_for_package(Mode::Dev, Target::JavaScript, &main_function.package)
         .to_path_buf()
         .join("gleam.main.mjs");
-    let module = format!(
-        r#"import {{ main }} from "./{module}.mjs";
-main();
-"#,
-    );
+    let module = javascript::entrypoint(module, function, main_function.takes_arguments);
     crate::fs::write(&path, &module)?;
     Ok(path)
 }
 
 fn run_javascript_deno(
-    paths: &ProjectPaths,
     config: &PackageConfig,
-    package: &str,
-    module: &str,
+    entrypoint: Utf8PathBuf,
     arguments: Vec<String>,
-) -> Result<i32, Error> {
+    env: &[(&str, String)],
+    execution: Execution<'_>,
+) -> Result<Option<i32>, Error> {
     let mut args = vec![];
 
     // Run the main function.
     args.push("run".into());
 
+    add_deno_permissions(&mut args, config);
+
+    args.push(entrypoint.to_string());
+
+    for argument in arguments.into_iter() {
+        args.push(argument);
+    }
+
+    execute("deno", &args, env, execution)
+}
+
+/// Adds the flags enabling the unstable features and permissions configured
+/// in `gleam.toml` to the arguments of `deno run`.
+pub(crate) fn add_deno_permissions(args: &mut Vec<String>, config: &PackageConfig) {
     // Enable unstable features and APIs
     if config.javascript.deno.unstable {
         args.push("--unstable".into())
@@ -231,7 +527,7 @@ fn run_javascript_deno(
         args.push("--allow-all".into())
     } else {
         // Allow env
-        add_deno_flag(&mut args, "--allow-env", &config.javascript.deno.allow_env);
+        add_deno_flag(args, "--allow-env", &config.javascript.deno.allow_env);
 
         // Allow sys
         if config.javascript.deno.allow_sys {
@@ -244,7 +540,7 @@ fn run_javascript_deno(
         }
 
         // Allow net
-        add_deno_flag(&mut args, "--allow-net", &config.javascript.deno.allow_net);
+        add_deno_flag(args, "--allow-net", &config.javascript.deno.allow_net);
 
         // Allow ffi
         if config.javascript.deno.allow_ffi {
@@ -252,31 +548,14 @@ fn run_javascript_deno(
         }
 
         // Allow read
-        add_deno_flag(
-            &mut args,
-            "--allow-read",
-            &config.javascript.deno.allow_read,
-        );
+        add_deno_flag(args, "--allow-read", &config.javascript.deno.allow_read);
 
         // Allow run
-        add_deno_flag(&mut args, "--allow-run", &config.javascript.deno.allow_run);
+        add_deno_flag(args, "--allow-run", &config.javascript.deno.allow_run);
 
         // Allow write
-        add_deno_flag(
-            &mut args,
-            "--allow-write",
-            &config.javascript.deno.allow_write,
-        );
+        add_deno_flag(args, "--allow-write", &config.javascript.deno.allow_write);
     }
-
-    let entrypoint = write_javascript_entrypoint(paths, package, module)?;
-    args.push(entrypoint.to_string());
-
-    for argument in arguments.into_iter() {
-        args.push(argument);
-    }
-
-    ProjectIO::new().exec("deno", &args, &[], None, Stdio::Inherit)
 }
 
 fn add_deno_flag(args: &mut Vec<String>, flag: &str, flags: &DenoFlag) {
@@ -310,10 +589,11 @@ fn is_gleam_module(module: &str) -> bool {
 fn get_or_suggest_main_function(
     built: Built,
     module: &str,
+    function: &str,
     target: Target,
 ) -> Result<ModuleFunction, Error> {
     // Check if the module exists
-    let error = match built.get_main_function(&module.into(), target) {
+    let error = match built.get_main_function(&module.into(), function, target) {
         Ok(main_fn) => return Ok(main_fn),
         Err(error) => error,
     };
@@ -324,7 +604,7 @@ fn get_or_suggest_main_function(
             Some(other) => other.into(),
             None => continue,
         };
-        if built.get_main_function(&other, target).is_ok() {
+        if built.get_main_function(&other, function, target).is_ok() {
             return Err(Error::ModuleDoesNotExist {
                 module: EcoString::from(module),
                 suggestion: Some(other),
@@ -0,0 +1,466 @@
+//! Line coverage for `gleam test --coverage`.
+//!
+//! The modules of the project are compiled for coverage analysis with OTP's
+//! `cover` application, and the tests are run by the project's test runner as
+//! they are without coverage. Test runners halt the virtual machine once they
+//! have finished, which would lose the analysis, so the modules that halt it
+//! are compiled again to write the analysis first. The number of times each
+//! line of the generated Erlang was run is written to a file, and is reported
+//! for the line of the Gleam code it was generated from.
+
+use std::collections::BTreeMap;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
+use gleam_core::{
+    build::{doc_tests, Built, Mode, Origin, Target},
+    erlang::{self, LineMapping},
+    error::Error,
+    line_numbers::LineNumbers,
+    paths::ProjectPaths,
+};
+use itertools::Itertools;
+
+/// A run of the tests with the modules of the project compiled for coverage
+/// analysis.
+#[derive(Debug)]
+pub struct CoverageRun {
+    modules: Vec<CoveredModule>,
+    data_path: Utf8PathBuf,
+}
+
+/// A module of the project, with the line of its Gleam code each line of its
+/// Erlang was generated from.
+#[derive(Debug)]
+struct CoveredModule {
+    name: EcoString,
+    path: Utf8PathBuf,
+    code: EcoString,
+    lines: LineMapping,
+}
+
+impl CoverageRun {
+    /// Requires the typed AST of every module of the root package, so the
+    /// package is to be compiled again even if it has been cached.
+    pub fn new(paths: &ProjectPaths, built: &Built) -> Result<Self, Error> {
+        let modules = built
+            .root_package
+            .modules
+            .iter()
+            .filter(|module| {
+                module.origin == Origin::Src && !doc_tests::is_doc_test_module(&module.name)
+            })
+            .map(|module| {
+                let line_numbers = LineNumbers::new(&module.code);
+                Ok(CoveredModule {
+                    name: module.name.clone(),
+                    path: module.input_path.clone(),
+                    code: module.code.clone(),
+                    lines: erlang::line_mapping(&module.ast, &line_numbers)?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?
+            .into_iter()
+            .sorted_by(|one, other| one.name.cmp(&other.name))
+            .collect();
+
+        // The analysis of the previous run is replaced by the one of this run.
+        let data_path = paths
+            .build_directory_for_mode(Mode::Dev)
+            .join("gleam-coverage.tsv");
+        crate::fs::delete_file(&data_path)?;
+
+        Ok(Self { modules, data_path })
+    }
+
+    /// An Erlang expression that compiles the modules for coverage analysis
+    /// and then evaluates `run`, which runs the tests. Modules that can't be
+    /// compiled for coverage are reported and left out of the analysis.
+    ///
+    /// The analysis is written when the virtual machine is halted, by
+    /// compiling the modules that halt it again with their calls to
+    /// `erlang:halt` replaced by calls to a function that writes it first.
+    pub fn erlang_eval(&self, paths: &ProjectPaths, run: &str) -> String {
+        let modules = self
+            .modules
+            .iter()
+            .map(|module| format!("'{}'", erlang_module(&module.name)))
+            .join(", ");
+        let packages = paths.build_directory_for_target(Mode::Dev, Target::Erlang);
+        format!(
+            r#"Data = {data},
+cover:start(),
+Covered = lists:filter(fun(Module) ->
+    case cover:compile_beam(Module) of
+        {{ok, _}} -> true;
+        Error ->
+            io:format(standard_error, "Module ~ts could not be compiled for coverage: ~p~n", [Module, Error]),
+            false
+    end
+end, [{modules}]),
+WriteAnalysis = fun() ->
+    Lines = [
+        io_lib:format("~ts\t~p\t~p~n", [Module, Line, Count])
+     || Module <- Covered,
+        {{ok, Calls}} <- [cover:analyse(Module, calls, line)],
+        {{{{_, Line}}, Count}} <- Calls,
+        Line > 0
+    ],
+    ok = file:write_file(Data, Lines)
+end,
+persistent_term:put({{gleam_coverage_halt, 0}}, fun() -> WriteAnalysis(), erlang:halt() end),
+persistent_term:put({{gleam_coverage_halt, 1}}, fun(Status) -> WriteAnalysis(), erlang:halt(Status) end),
+persistent_term:put({{gleam_coverage_halt, 2}}, fun(Status, Options) -> WriteAnalysis(), erlang:halt(Status, Options) end),
+Rewrite = fun
+    Rewrite({{call, Anno, {{remote, _, {{atom, _, erlang}}, {{atom, _, halt}}}}, Arguments}}) ->
+        Key = {{tuple, Anno, [{{atom, Anno, gleam_coverage_halt}}, {{integer, Anno, length(Arguments)}}]}},
+        Halt = {{call, Anno, {{remote, Anno, {{atom, Anno, persistent_term}}, {{atom, Anno, get}}}}, [Key]}},
+        {{call, Anno, Halt, Rewrite(Arguments)}};
+    Rewrite(Tuple) when is_tuple(Tuple) -> list_to_tuple(Rewrite(tuple_to_list(Tuple)));
+    Rewrite(List) when is_list(List) -> lists:map(Rewrite, List);
+    Rewrite(Term) -> Term
+end,
+lists:foreach(fun(Beam) ->
+    Module = list_to_atom(filename:basename(Beam, ".beam")),
+    Halts = case beam_lib:chunks(Beam, [imports]) of
+        {{ok, {{_, [{{imports, Imports}}]}}}} -> lists:any(fun(Import) -> element(1, Import) =:= erlang andalso element(2, Import) =:= halt end, Imports);
+        _ -> false
+    end,
+    case Halts andalso not lists:member(Module, Covered) of
+        false -> ok;
+        true ->
+            Rewritten = case beam_lib:chunks(Beam, [abstract_code]) of
+                {{ok, {{_, [{{abstract_code, {{raw_abstract_v1, Forms}}}}]}}}} -> compile:forms(Rewrite(Forms), [binary]);
+                _ -> error
+            end,
+            case Rewritten of
+                {{ok, _, Binary}} -> {{module, _}} = code:load_binary(Module, Beam, Binary);
+                _ -> io:format(standard_error, "Module ~ts could not be compiled to record coverage when it halts~n", [Module])
+            end
+    end
+end, [filename:join({packages}, Beam) || Beam <- filelib:wildcard("*/ebin/*.beam", {packages})]),
+{run}"#,
+            data = erlang_binary(self.data_path.as_str()),
+            packages = erlang_string(packages.as_str()),
+        )
+    }
+
+    /// Prints a summary of the coverage of each module, and writes the LCOV
+    /// and HTML reports to the `build/coverage` directory.
+    pub fn report(self, paths: &ProjectPaths) -> Result<(), Error> {
+        if !self.data_path.exists() {
+            return Err(Error::CoverageNotRecorded);
+        }
+        let data = crate::fs::read(&self.data_path)?;
+        let coverage = Coverage::new(self.modules, &data);
+
+        println!("\n{}", coverage.summary());
+
+        let directory = paths.build_directory().join("coverage");
+        crate::fs::delete_directory(&directory)?;
+        crate::fs::write(&directory.join("lcov.info"), &coverage.lcov(paths.root()))?;
+        for (path, html) in coverage.html() {
+            crate::fs::write(&directory.join("html").join(path), &html)?;
+        }
+        println!(
+            "\nCoverage reports written to {}",
+            directory.strip_prefix(paths.root()).unwrap_or(&directory)
+        );
+        Ok(())
+    }
+}
+
+/// The number of times each line of a module's Gleam code was run.
+#[derive(Debug, PartialEq, Eq)]
+struct ModuleCoverage {
+    module: EcoString,
+    path: Utf8PathBuf,
+    code: EcoString,
+    lines: BTreeMap<u32, u64>,
+}
+
+impl ModuleCoverage {
+    fn lines_hit(&self) -> usize {
+        self.lines.values().filter(|count| **count > 0).count()
+    }
+}
+
+#[derive(Debug)]
+struct Coverage {
+    modules: Vec<ModuleCoverage>,
+}
+
+impl Coverage {
+    /// Reads the analysis written by the Erlang expression, which has a line
+    /// for each line of the Erlang of a module that can be run, with the
+    /// Erlang name of the module, the line, and the number of times it was
+    /// run, separated by tabs. A Gleam line is counted as run as many times
+    /// as the Erlang line generated from it that was run the most.
+    fn new(modules: Vec<CoveredModule>, data: &str) -> Self {
+        let mut analysis: BTreeMap<&str, Vec<(u32, u64)>> = BTreeMap::new();
+        for line in data.lines() {
+            let Some((module, line, count)) = line.split('\t').collect_tuple() else {
+                continue;
+            };
+            let (Ok(line), Ok(count)) = (line.parse::<u32>(), count.parse::<u64>()) else {
+                continue;
+            };
+            analysis.entry(module).or_default().push((line, count));
+        }
+
+        let modules = modules
+            .into_iter()
+            .map(|module| {
+                let mut lines = BTreeMap::new();
+                let erlang_lines = analysis
+                    .get(erlang_module(&module.name).as_str())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                for (erlang_line, count) in erlang_lines {
+                    let Some(line) = module.lines.gleam_line(*erlang_line) else {
+                        continue;
+                    };
+                    let most = lines.entry(line).or_insert(0);
+                    *most = (*most).max(*count);
+                }
+                ModuleCoverage {
+                    module: module.name,
+                    path: module.path,
+                    code: module.code,
+                    lines,
+                }
+            })
+            .collect();
+
+        Self { modules }
+    }
+
+    fn totals(&self) -> (usize, usize) {
+        self.modules.iter().fold((0, 0), |(hit, found), module| {
+            (hit + module.lines_hit(), found + module.lines.len())
+        })
+    }
+
+    fn summary(&self) -> String {
+        let width = self
+            .modules
+            .iter()
+            .map(|module| module.module.len())
+            .chain(["Total".len()])
+            .max()
+            .unwrap_or_default();
+        let row = |name: &str, hit: usize, found: usize| {
+            format!(
+                "{name:<width$}  {:>6}  {hit:>5}/{found}",
+                percentage(hit, found)
+            )
+        };
+        let mut text = String::from("Line coverage:\n");
+        for module in &self.modules {
+            text.push_str(&row(&module.module, module.lines_hit(), module.lines.len()));
+            text.push('\n');
+        }
+        let (hit, found) = self.totals();
+        text.push_str(&row("Total", hit, found));
+        text
+    }
+
+    /// The coverage in the LCOV tracefile format, which CI services and
+    /// editors can display.
+    fn lcov(&self, root: &Utf8Path) -> String {
+        let mut text = String::new();
+        for module in &self.modules {
+            let path = module.path.strip_prefix(root).unwrap_or(&module.path);
+            text.push_str(&format!("TN:\nSF:{path}\n"));
+            for (line, count) in &module.lines {
+                text.push_str(&format!("DA:{line},{count}\n"));
+            }
+            text.push_str(&format!(
+                "LF:{}\nLH:{}\nend_of_record\n",
+                module.lines.len(),
+                module.lines_hit()
+            ));
+        }
+        text
+    }
+
+    /// An index page listing the coverage of each module, and a page for each
+    /// module showing its code with the lines that were and were not run
+    /// highlighted.
+    fn html(&self) -> Vec<(String, String)> {
+        let mut pages = vec![];
+        let mut rows = String::new();
+        for module in &self.modules {
+            let page = format!("{}.html", erlang_module(&module.module));
+            rows.push_str(&format!(
+                r#"<tr><td><a href="{page}">{name}</a></td><td>{percentage}</td><td>{hit}/{found}</td></tr>
+"#,
+                name = escape_html(&module.module),
+                percentage = percentage(module.lines_hit(), module.lines.len()),
+                hit = module.lines_hit(),
+                found = module.lines.len(),
+            ));
+
+            let lines = module
+                .code
+                .lines()
+                .enumerate()
+                .map(|(index, code)| {
+                    let number = index as u32 + 1;
+                    let (class, count) = match module.lines.get(&number) {
+                        Some(0) => ("missed", "0".to_string()),
+                        Some(count) => ("hit", count.to_string()),
+                        None => ("", String::new()),
+                    };
+                    format!(
+                        r#"<tr class="{class}"><td>{number}</td><td>{count}</td><td><pre>{}</pre></td></tr>"#,
+                        escape_html(code)
+                    )
+                })
+                .join("\n");
+            pages.push((
+                page,
+                html_page(&module.module, &format!("<table>\n{lines}\n</table>")),
+            ));
+        }
+
+        let (hit, found) = self.totals();
+        let index = format!(
+            r#"<table>
+<tr><th>Module</th><th>Coverage</th><th>Lines</th></tr>
+{rows}<tr><th>Total</th><th>{percentage}</th><th>{hit}/{found}</th></tr>
+</table>"#,
+            percentage = percentage(hit, found),
+        );
+        pages.push(("index.html".into(), html_page("Coverage", &index)));
+        pages
+    }
+}
+
+fn html_page(title: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; }}
+table {{ border-collapse: collapse; }}
+td, th {{ padding: 0 0.5em; text-align: left; }}
+pre {{ margin: 0; }}
+tr.hit {{ background: #dfd; }}
+tr.missed {{ background: #fdd; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+{body}
+</body>
+</html>
+"#,
+        title = escape_html(title),
+    )
+}
+
+fn percentage(hit: usize, found: usize) -> String {
+    if found == 0 {
+        return "-".into();
+    }
+    format!("{:.1}%", hit as f64 * 100.0 / found as f64)
+}
+
+fn erlang_module(module: &str) -> String {
+    module.replace('/', "@")
+}
+
+fn escape_erlang(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// An Erlang binary holding the text.
+fn erlang_binary(text: &str) -> String {
+    format!("<<\"{}\"/utf8>>", escape_erlang(text))
+}
+
+/// An Erlang string, which is a list of characters, holding the text.
+fn erlang_string(text: &str) -> String {
+    format!("\"{}\"", escape_erlang(text))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+fn example_coverage() -> Coverage {
+    Coverage::new(
+        vec![
+            CoveredModule {
+                name: "app".into(),
+                path: "/app/src/app.gleam".into(),
+                code: "import app/router\n\npub fn main() {\n  router.route()\n}\n".into(),
+                lines: BTreeMap::from([(6, 3), (7, 4)]).into(),
+            },
+            CoveredModule {
+                name: "app/router".into(),
+                path: "/app/src/app/router.gleam".into(),
+                code: "pub fn route() {\n  case 1 {\n    1 -> 2\n    _ -> 3\n  }\n}\n".into(),
+                lines: BTreeMap::from([(6, 2), (7, 3), (9, 4)]).into(),
+            },
+        ],
+        "app\t6\t2
+app\t7\t0
+app@router\t7\t1
+app@router\t8\t1
+app@router\t10\t0
+not_a_line
+other_module\t1\t1
+",
+    )
+}
+
+#[test]
+fn analysis_is_read_for_the_gleam_lines() {
+    let coverage = example_coverage();
+    assert_eq!(coverage.modules[1].module, "app/router");
+    // Erlang lines 7 and 8 are both generated from the third Gleam line
+    assert_eq!(coverage.modules[1].lines, BTreeMap::from([(3, 1), (4, 0)]));
+    assert_eq!(coverage.totals(), (2, 4));
+}
+
+#[test]
+fn summary_lists_each_module() {
+    assert_eq!(
+        example_coverage().summary(),
+        "Line coverage:
+app          50.0%      1/2
+app/router   50.0%      1/2
+Total        50.0%      2/4"
+    );
+}
+
+#[test]
+fn lcov_tracefile() {
+    assert_eq!(
+        example_coverage().lcov(Utf8Path::new("/app")),
+        "TN:
+SF:src/app.gleam
+DA:3,2
+DA:4,0
+LF:2
+LH:1
+end_of_record
+TN:
+SF:src/app/router.gleam
+DA:3,1
+DA:4,0
+LF:2
+LH:1
+end_of_record
+"
+    );
+}
@@ -0,0 +1,562 @@
+//! The protocol `gleam test` uses to select the tests a test runner runs, and
+//! to receive the result of each of them.
+//!
+//! When tests are selected the test runner is given the path of a file in the
+//! `GLEAM_TEST_SELECTION` environment variable. The file has the name of one
+//! selected test per line, written as `module.function`, for example
+//! `app/router_test.not_found_test`, and the runner is to run only those.
+//!
+//! The test runner is always given the path of a file in the
+//! `GLEAM_TEST_RESULTS` environment variable, and can append a JSON object to
+//! it for each test it runs, one per line:
+//!
+//! ```json
+//! {"module": "app/router_test", "function": "not_found_test", "outcome": "failed", "duration_ms": 3, "message": "..."}
+//! ```
+//!
+//! The `outcome` is one of `passed`, `failed` or `skipped`, and the
+//! `duration_ms` and `message` fields are optional.
+//!
+//! When `GLEAM_TEST_FAIL_FAST` is set to `true` the runner is to stop after
+//! the first test that fails, reporting the tests it didn't run as skipped.
+//!
+//! Test runners that don't support the protocol ignore these variables and
+//! run every test as before.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use camino::Utf8PathBuf;
+use clap::ValueEnum;
+use ecow::EcoString;
+use gleam_core::{
+    ast::Definition,
+    build::{doc_tests, Built, Mode, Module},
+    error::Error,
+    paths::ProjectPaths,
+};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString, VariantNames};
+
+pub const SELECTION_VARIABLE: &str = "GLEAM_TEST_SELECTION";
+pub const RESULTS_VARIABLE: &str = "GLEAM_TEST_RESULTS";
+pub const FAIL_FAST_VARIABLE: &str = "GLEAM_TEST_FAIL_FAST";
+
+/// The format of the report of the test results.
+#[derive(Debug, Display, EnumString, VariantNames, ValueEnum, Clone, Copy, Default)]
+#[strum(serialize_all = "kebab_case")]
+pub enum ReportFormat {
+    /// A JSON object with the counts of the outcomes and every result
+    #[default]
+    Json,
+    /// JUnit XML, with a test suite for each module
+    Junit,
+}
+
+/// Which of the project's tests to run, and where to report their results.
+#[derive(Debug, Clone, Default)]
+pub struct TestOptions {
+    /// Only run the tests with this text in their `module.function` name.
+    pub filter: Option<String>,
+    /// Only run the tests in these modules, or in the modules nested within
+    /// them.
+    pub modules: Vec<String>,
+    /// Only run the tests that failed the last time the tests were run.
+    pub failed: bool,
+    /// Where to write a report of the results.
+    pub report: Option<Utf8PathBuf>,
+    pub report_format: ReportFormat,
+    /// Whether to stop after the first test that fails.
+    pub fail_fast: bool,
+    /// Whether to report the line coverage of the project's modules.
+    pub coverage: bool,
+}
+
+impl TestOptions {
+    /// Whether only some of the tests are to be run.
+    pub fn selects_tests(&self) -> bool {
+        self.filter.is_some() || !self.modules.is_empty() || self.failed
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct TestName {
+    pub module: EcoString,
+    pub function: EcoString,
+}
+
+impl fmt::Display for TestName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.module, self.function)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// The result of a single test, as reported by the test runner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestResult {
+    #[serde(flatten)]
+    pub name: TestName,
+    pub outcome: Outcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Report<'a> {
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+    duration_ms: u128,
+    tests: &'a [TestResult],
+}
+
+/// A run of the project's tests, prepared before the test runner is started.
+#[derive(Debug)]
+pub struct TestRun {
+    results_path: Utf8PathBuf,
+    /// The file with the selected tests, if only some are to be run.
+    selection_path: Option<Utf8PathBuf>,
+    report: Option<Utf8PathBuf>,
+    report_format: ReportFormat,
+    fail_fast: bool,
+    started: Instant,
+}
+
+impl TestRun {
+    /// Selects the tests to run and writes the selection for the test runner.
+    /// This has to happen before the test runner is started, as the results
+    /// of the previous run are needed to select the tests that failed.
+    pub fn prepare(
+        paths: &ProjectPaths,
+        built: &Built,
+        options: &TestOptions,
+    ) -> Result<Self, Error> {
+        let directory = paths.build_directory_for_mode(Mode::Dev);
+        let results_path = directory.join("gleam-test-results.jsonl");
+        let selection_path = directory.join("gleam-test-selection.txt");
+
+        let previous_failures = if options.failed && results_path.exists() {
+            let results = parse_results(&crate::fs::read(&results_path)?);
+            failures(&results)
+                .map(|result| result.name.clone())
+                .collect()
+        } else {
+            vec![]
+        };
+
+        // The results of the previous run are replaced by the ones of this run.
+        crate::fs::delete_file(&results_path)?;
+        crate::fs::delete_file(&selection_path)?;
+
+        let selection_path = if options.selects_tests() {
+            let tests = select(
+                test_functions(&built.root_package.modules),
+                options,
+                &previous_failures,
+            );
+            if tests.is_empty() {
+                return Err(Error::NoTestsSelected);
+            }
+            let text = tests.iter().map(|test| format!("{test}\n")).join("");
+            crate::fs::write(&selection_path, &text)?;
+            Some(selection_path)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            results_path,
+            selection_path,
+            report: options.report.clone(),
+            report_format: options.report_format,
+            fail_fast: options.fail_fast,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn fail_fast(&self) -> bool {
+        self.fail_fast
+    }
+
+    /// The environment variables that tell the test runner about this run.
+    pub fn environment(&self) -> Vec<(&'static str, String)> {
+        let mut environment = vec![(RESULTS_VARIABLE, self.results_path.to_string())];
+        if let Some(path) = &self.selection_path {
+            environment.push((SELECTION_VARIABLE, path.to_string()));
+        }
+        if self.fail_fast {
+            environment.push((FAIL_FAST_VARIABLE, "true".into()));
+        }
+        environment
+    }
+
+    /// Prints a summary of the results reported by the test runner, and
+    /// writes the report if one was asked for.
+    pub fn finish(self) -> Result<(), Error> {
+        let duration = self.started.elapsed();
+        let results = if self.results_path.exists() {
+            parse_results(&crate::fs::read(&self.results_path)?)
+        } else {
+            vec![]
+        };
+
+        if results.is_empty() {
+            if self.selection_path.is_some() {
+                println!(
+                    "\nThe test runner did not report any results, so it may have run every \
+test rather than only the selected ones. Selecting tests requires a test runner \
+that supports the `{SELECTION_VARIABLE}` environment variable."
+                );
+            }
+        } else {
+            println!("\n{}", summary(&results, duration));
+        }
+
+        if let Some(path) = &self.report {
+            let report = match self.report_format {
+                ReportFormat::Json => report(&results, duration),
+                ReportFormat::Junit => junit_report(&results, duration),
+            };
+            crate::fs::write(path, &report)?;
+        }
+        Ok(())
+    }
+}
+
+/// The test functions of the project: the public functions of the test
+/// modules that take no arguments and have a name ending in `_test`.
+pub fn test_functions(modules: &[Module]) -> Vec<TestName> {
+    modules
+        .iter()
+        .filter(|module| module.is_test() && !doc_tests::is_doc_test_module(&module.name))
+        .flat_map(|module| {
+            module
+                .ast
+                .definitions
+                .iter()
+                .filter_map(move |definition| match definition {
+                    Definition::Function(function)
+                        if function.publicity.is_public()
+                            && function.arguments.is_empty()
+                            && function.name.ends_with("_test") =>
+                    {
+                        Some(TestName {
+                            module: module.name.clone(),
+                            function: function.name.clone(),
+                        })
+                    }
+                    _ => None,
+                })
+        })
+        .sorted()
+        .collect()
+}
+
+/// The tests that match all the given selection options.
+pub fn select(
+    tests: Vec<TestName>,
+    options: &TestOptions,
+    previous_failures: &[TestName],
+) -> Vec<TestName> {
+    tests
+        .into_iter()
+        .filter(|test| {
+            options.modules.is_empty()
+                || options.modules.iter().any(|module| {
+                    let module = module.trim_end_matches('/');
+                    test.module.as_str() == module
+                        || test
+                            .module
+                            .strip_prefix(module)
+                            .is_some_and(|rest| rest.starts_with('/'))
+                })
+        })
+        .filter(|test| match &options.filter {
+            Some(filter) => test.to_string().contains(filter.as_str()),
+            None => true,
+        })
+        .filter(|test| !options.failed || previous_failures.contains(test))
+        .collect()
+}
+
+/// Parses the results written by the test runner. Lines that are not valid
+/// results are ignored, so a runner being interrupted part way through writing
+/// a line doesn't lose the results of the other tests.
+pub fn parse_results(text: &str) -> Vec<TestResult> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(result) => Some(result),
+            Err(error) => {
+                tracing::debug!(line, error = %error, "invalid_test_result");
+                None
+            }
+        })
+        .collect()
+}
+
+fn failures(results: &[TestResult]) -> impl Iterator<Item = &TestResult> {
+    results
+        .iter()
+        .filter(|result| result.outcome == Outcome::Failed)
+}
+
+fn count(results: &[TestResult], outcome: Outcome) -> usize {
+    results
+        .iter()
+        .filter(|result| result.outcome == outcome)
+        .count()
+}
+
+fn summary(results: &[TestResult], duration: Duration) -> String {
+    let mut text = String::new();
+    let failures = failures(results).collect_vec();
+    if !failures.is_empty() {
+        text.push_str("Failed tests:\n");
+        for failure in failures {
+            text.push_str(&format!("  {}", failure.name));
+            if let Some(duration) = failure.duration_ms {
+                text.push_str(&format!(" ({duration}ms)"));
+            }
+            text.push('\n');
+            if let Some(message) = &failure.message {
+                for line in message.lines() {
+                    text.push_str(&format!("    {line}\n"));
+                }
+            }
+        }
+        text.push('\n');
+    }
+    text.push_str(&format!(
+        "{} passed, {} failed, {} skipped in {}",
+        count(results, Outcome::Passed),
+        count(results, Outcome::Failed),
+        count(results, Outcome::Skipped),
+        crate::cli::seconds(duration),
+    ));
+    text
+}
+
+fn report(results: &[TestResult], duration: Duration) -> String {
+    let report = Report {
+        passed: count(results, Outcome::Passed),
+        failed: count(results, Outcome::Failed),
+        skipped: count(results, Outcome::Skipped),
+        duration_ms: duration.as_millis(),
+        tests: results,
+    };
+    serde_json::to_string_pretty(&report).expect("test report serialisation")
+}
+
+/// A JUnit XML report, with a test suite for each module. Most CI services
+/// can show the results of tests from this format.
+fn junit_report(results: &[TestResult], duration: Duration) -> String {
+    let mut modules: BTreeMap<&EcoString, Vec<&TestResult>> = BTreeMap::new();
+    for result in results {
+        modules.entry(&result.name.module).or_default().push(result);
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+        results.len(),
+        count(results, Outcome::Failed),
+        count(results, Outcome::Skipped),
+        junit_time(duration.as_millis() as u64),
+    ));
+    for (module, results) in modules {
+        let failed = results
+            .iter()
+            .filter(|result| result.outcome == Outcome::Failed)
+            .count();
+        let skipped = results
+            .iter()
+            .filter(|result| result.outcome == Outcome::Skipped)
+            .count();
+        let time = results.iter().filter_map(|result| result.duration_ms).sum();
+        xml.push_str(&format!(
+            "  <testsuite name=\"{module}\" tests=\"{}\" failures=\"{failed}\" \
+skipped=\"{skipped}\" time=\"{}\">\n",
+            results.len(),
+            junit_time(time),
+            module = xml_escape(module),
+        ));
+        for result in results {
+            xml.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+                xml_escape(&result.name.function),
+                xml_escape(module),
+                junit_time(result.duration_ms.unwrap_or(0)),
+            ));
+            match (result.outcome, &result.message) {
+                (Outcome::Passed, _) => xml.push_str("/>\n"),
+                (Outcome::Skipped, _) => xml.push_str(">\n      <skipped/>\n    </testcase>\n"),
+                (Outcome::Failed, None) => xml.push_str(">\n      <failure/>\n    </testcase>\n"),
+                (Outcome::Failed, Some(message)) => xml.push_str(&format!(
+                    ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+                    xml_escape(message.lines().next().unwrap_or_default()),
+                    xml_escape(message),
+                )),
+            }
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn junit_time(milliseconds: u64) -> String {
+    format!("{:.3}", milliseconds as f64 / 1000.0)
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+fn test_name(module: &str, function: &str) -> TestName {
+    TestName {
+        module: module.into(),
+        function: function.into(),
+    }
+}
+
+#[test]
+fn select_by_module_includes_nested_modules() {
+    let tests = vec![
+        test_name("app/router_test", "home_test"),
+        test_name("app/router/params_test", "parse_test"),
+        test_name("app/router_extra_test", "extra_test"),
+        test_name("app_test", "main_test"),
+    ];
+    let options = TestOptions {
+        modules: vec!["app/router_test".into(), "app/router".into()],
+        ..Default::default()
+    };
+    assert_eq!(
+        select(tests, &options, &[]),
+        vec![
+            test_name("app/router_test", "home_test"),
+            test_name("app/router/params_test", "parse_test"),
+        ]
+    );
+}
+
+#[test]
+fn select_by_filter_and_previous_failures() {
+    let tests = vec![
+        test_name("app_test", "parse_int_test"),
+        test_name("app_test", "parse_float_test"),
+        test_name("app/parse_test", "empty_test"),
+        test_name("app_test", "render_test"),
+    ];
+    let options = TestOptions {
+        filter: Some("parse".into()),
+        failed: true,
+        ..Default::default()
+    };
+    let previous_failures = vec![
+        test_name("app_test", "parse_float_test"),
+        test_name("app_test", "render_test"),
+    ];
+    assert_eq!(
+        select(tests, &options, &previous_failures),
+        vec![test_name("app_test", "parse_float_test")]
+    );
+}
+
+#[test]
+fn results_are_parsed_ignoring_invalid_lines() {
+    let text = r#"{"module": "app_test", "function": "one_test", "outcome": "passed", "duration_ms": 2}
+{"module": "app_test", "function": "two_test", "outcome": "failed", "message": "1 != 2"}
+
+{"module": "app_test", "function": "thr
+{"module": "app_test", "function": "four_test", "outcome": "skipped"}
+"#;
+    assert_eq!(
+        parse_results(text),
+        vec![
+            TestResult {
+                name: test_name("app_test", "one_test"),
+                outcome: Outcome::Passed,
+                duration_ms: Some(2),
+                message: None,
+            },
+            TestResult {
+                name: test_name("app_test", "two_test"),
+                outcome: Outcome::Failed,
+                duration_ms: None,
+                message: Some("1 != 2".into()),
+            },
+            TestResult {
+                name: test_name("app_test", "four_test"),
+                outcome: Outcome::Skipped,
+                duration_ms: None,
+                message: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn summary_lists_failures() {
+    let results = parse_results(
+        r#"{"module": "app_test", "function": "one_test", "outcome": "passed"}
+{"module": "app_test", "function": "two_test", "outcome": "failed", "duration_ms": 3, "message": "Expected 1\nGot 2"}"#,
+    );
+    assert_eq!(
+        summary(&results, Duration::from_millis(1500)),
+        "Failed tests:
+  app_test.two_test (3ms)
+    Expected 1
+    Got 2
+
+1 passed, 1 failed, 0 skipped in 1.50s"
+    );
+}
+
+#[test]
+fn junit_report_groups_tests_by_module() {
+    let results = parse_results(
+        r#"{"module": "app_test", "function": "one_test", "outcome": "passed", "duration_ms": 12}
+{"module": "app/router_test", "function": "two_test", "outcome": "failed", "duration_ms": 3, "message": "Expected <1>\nGot 2"}
+{"module": "app_test", "function": "three_test", "outcome": "skipped"}"#,
+    );
+    assert_eq!(
+        junit_report(&results, Duration::from_millis(1500)),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="3" failures="1" skipped="1" time="1.500">
+  <testsuite name="app/router_test" tests="1" failures="1" skipped="0" time="0.003">
+    <testcase name="two_test" classname="app/router_test" time="0.003">
+      <failure message="Expected &lt;1&gt;">Expected &lt;1&gt;
+Got 2</failure>
+    </testcase>
+  </testsuite>
+  <testsuite name="app_test" tests="2" failures="0" skipped="1" time="0.012">
+    <testcase name="one_test" classname="app_test" time="0.012"/>
+    <testcase name="three_test" classname="app_test" time="0.000">
+      <skipped/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+    );
+}
@@ -1,47 +1,328 @@
+use camino::{Utf8Path, Utf8PathBuf};
 use gleam_core::{
     analyse::TargetSupport,
-    build::{Codegen, Mode, Options, Target},
-    error::Error,
+    build::{Codegen, Mode, Options, Runtime, Target},
+    config::PackageConfig,
+    error::{Error, StandardIoAction},
+    shell::{Compiled, Session, JAVASCRIPT_FFI, MODULE_DIRECTORY},
 };
-use std::process::Command;
+use std::{
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+/// The line the runtime prints once it has evaluated an input, followed by
+/// `ok` or `error`.
+const DONE: &str = "%gleam_shell_done%";
 
-pub fn command() -> Result<(), Error> {
+/// Starts a shell evaluating the Gleam entered at the prompt, with the
+/// project's modules available to import.
+pub fn command(target: Option<Target>, runtime: Option<Runtime>) -> Result<(), Error> {
     let paths = crate::find_project_paths()?;
+    let config = crate::config::root_config()?;
+    let target = target.unwrap_or(config.target);
+    let runtime = match (target, runtime) {
+        (Target::Erlang, Some(runtime)) => {
+            return Err(Error::InvalidRuntime {
+                target: Target::Erlang,
+                invalid_runtime: runtime,
+            })
+        }
+        (Target::Erlang, None) => None,
+        (Target::JavaScript, runtime) => Some(runtime.unwrap_or(config.javascript.runtime)),
+    };
 
     // Build project
-    let _ = crate::build::main(
+    let built = crate::build::main(
         Options {
             root_target_support: TargetSupport::Enforced,
+            doc_tests: false,
             warnings_as_errors: false,
             codegen: Codegen::All,
             mode: Mode::Dev,
-            target: Some(Target::Erlang),
+            target: Some(target),
         },
         crate::build::download_dependencies()?,
     )?;
 
-    // Don't exit on ctrl+c as it is used by child erlang shell
+    let package_directory = paths.build_directory_for_package(Mode::Dev, target, &config.name);
+    let mut evaluator = match runtime {
+        None => Evaluator::erlang(&paths)?,
+        Some(runtime) => Evaluator::javascript(&config, runtime, &package_directory)?,
+    };
+
+    // Don't exit on ctrl+c as it is used to stop the code being evaluated
     ctrlc::set_handler(move || {}).expect("Error setting Ctrl-C handler");
 
-    // Prepare the Erlang shell command
-    let mut command = Command::new("erl");
+    crate::cli::print_running("Gleam shell");
+    println!("Enter Gleam expressions, `let` assignments, or imports. Enter :quit to exit.");
 
-    // Print character lists as lists
-    let _ = command.arg("-stdlib").arg("shell_strings").arg("false");
+    let mut session = Session::new();
+    while let Some(input) = read_input()? {
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        if input == ":quit" {
+            break;
+        }
 
-    // Specify locations of .beam files
-    let packages = paths.build_directory_for_target(Mode::Dev, Target::Erlang);
-    for entry in crate::fs::read_dir(packages)?.filter_map(Result::ok) {
-        let _ = command.arg("-pa").arg(entry.path().join("ebin"));
+        let compiled = match session.compile(&built, &config, target, input) {
+            Ok(compiled) => compiled,
+            Err(error) => {
+                eprintln!("{}", error.pretty_string());
+                continue;
+            }
+        };
+        let path = module_path(&package_directory, target, &compiled);
+        crate::fs::write(&path, &compiled.code)?;
+        if evaluator.evaluate(&path)? {
+            session.ran(compiled);
+        }
     }
 
-    crate::cli::print_running("Erlang shell");
-
-    // Run the shell
-    tracing::info!("Running OS process {:?}", command);
-    let _ = command.status().map_err(|e| Error::ShellCommand {
-        program: "erl".into(),
-        err: Some(e.kind()),
-    })?;
+    evaluator.stop();
     Ok(())
 }
+
+/// Reads an input from the prompt, continuing on the next line while it is
+/// incomplete. Returns nothing once standard input has been closed.
+fn read_input() -> Result<Option<String>, Error> {
+    let mut input = String::new();
+    loop {
+        print!("{}", if input.is_empty() { "> " } else { "... " });
+        std::io::stdout().flush().expect("shell stdout flush");
+        let read = std::io::stdin()
+            .read_line(&mut input)
+            .map_err(|e| Error::StandardIo {
+                action: StandardIoAction::Read,
+                err: Some(e.kind()),
+            })?;
+        if read == 0 {
+            return Ok(if input.trim().is_empty() {
+                None
+            } else {
+                Some(input)
+            });
+        }
+        if !is_incomplete(&input) {
+            return Ok(Some(input));
+        }
+    }
+}
+
+/// Whether the input has brackets that are not closed yet, or ends with an
+/// operator, so the next line is part of it.
+fn is_incomplete(input: &str) -> bool {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for line in input.lines() {
+        let mut chars = line.chars().peekable();
+        while let Some(char) = chars.next() {
+            match char {
+                _ if escaped => escaped = false,
+                '\\' if in_string => escaped = true,
+                '"' => in_string = !in_string,
+                _ if in_string => (),
+                '/' if chars.peek() == Some(&'/') => break,
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                _ => (),
+            }
+        }
+    }
+    let end = input.trim_end();
+    in_string
+        || depth > 0
+        || ["|>", "=", "->", "<>", "&&", "||", ","]
+            .iter()
+            .any(|operator| end.ends_with(operator))
+}
+
+/// The file the code of a compiled input is written to, in the build of the
+/// root package.
+fn module_path(package_directory: &Utf8Path, target: Target, compiled: &Compiled) -> Utf8PathBuf {
+    match target {
+        Target::Erlang => package_directory
+            .join(MODULE_DIRECTORY)
+            .join(format!("{}.erl", compiled.module.replace("/", "@"))),
+        Target::JavaScript => package_directory.join(format!("{}.mjs", compiled.module)),
+    }
+}
+
+/// The runtime process the compiled inputs are evaluated in. It reads the
+/// path of a module from standard input, loads the module and prints the
+/// value returned by its `run` function.
+struct Evaluator {
+    program: &'static str,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Evaluator {
+    fn erlang(paths: &gleam_core::paths::ProjectPaths) -> Result<Self, Error> {
+        let mut command = Command::new("erl");
+
+        // Specify locations of .beam files
+        let packages = paths.build_directory_for_target(Mode::Dev, Target::Erlang);
+        for entry in crate::fs::read_dir(packages)?.filter_map(Result::ok) {
+            let _ = command.arg("-pa").arg(entry.path().join("ebin"));
+        }
+
+        let eval = format!(
+            r#"Inspect = case code:ensure_loaded('gleam@string') of
+    {{module, _}} -> fun(Value) -> 'gleam@string':inspect(Value) end;
+    _ -> fun(Value) -> io_lib:format("~tp", [Value]) end
+end,
+Loop = fun Loop() ->
+    case io:get_line("") of
+        eof -> halt(0);
+        Line ->
+            Path = string:trim(Line),
+            Status = try
+                {{ok, Module, Binary}} = compile:file(Path, [binary, report_errors]),
+                {{module, Module}} = code:load_binary(Module, Path, Binary),
+                io:format("~ts~n", [Inspect(Module:run())]),
+                ok
+            catch Class:Reason ->
+                io:format("~p: ~tp~n", [Class, Reason]),
+                error
+            end,
+            io:format("{DONE} ~p~n", [Status]),
+            Loop()
+    end
+end,
+Loop()."#
+        );
+        let _ = command.arg("-eval").arg(eval).arg("-noshell");
+        Self::spawn("erl", command)
+    }
+
+    fn javascript(
+        config: &PackageConfig,
+        runtime: Runtime,
+        package_directory: &Utf8Path,
+    ) -> Result<Self, Error> {
+        let directory = package_directory.join(MODULE_DIRECTORY);
+        crate::fs::write(&directory.join("ffi.mjs"), JAVASCRIPT_FFI)?;
+        let runner = directory.join("runner.mjs");
+        crate::fs::write(
+            &runner,
+            &format!(
+                r#"import process from "node:process";
+import {{ createInterface }} from "node:readline";
+import {{ pathToFileURL }} from "node:url";
+import {{ inspect }} from "../gleam.mjs";
+
+for await (const path of createInterface({{ input: process.stdin }})) {{
+  let status = "ok";
+  try {{
+    const module = await import(pathToFileURL(path).href);
+    console.log(inspect(module.run()));
+  }} catch (error) {{
+    status = "error";
+    console.log(error);
+  }}
+  console.log(`{DONE} ${{status}}`);
+}}
+"#
+            ),
+        )?;
+
+        let (program, mut command) = match runtime {
+            Runtime::NodeJs => ("node", Command::new("node")),
+            Runtime::Bun => {
+                let mut command = Command::new("bun");
+                let _ = command.arg("run");
+                ("bun", command)
+            }
+            Runtime::Deno => {
+                let mut args = vec!["run".into()];
+                crate::run::add_deno_permissions(&mut args, config);
+                let mut command = Command::new("deno");
+                let _ = command.args(args);
+                ("deno", command)
+            }
+        };
+        let _ = command.arg(runner.as_str());
+        Self::spawn(program, command)
+    }
+
+    fn spawn(program: &'static str, mut command: Command) -> Result<Self, Error> {
+        tracing::info!("Running OS process {:?}", command);
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::ShellCommand {
+                program: program.into(),
+                err: Some(e.kind()),
+            })?;
+        let stdin = child.stdin.take().expect("shell runtime stdin");
+        let stdout = BufReader::new(child.stdout.take().expect("shell runtime stdout"));
+        Ok(Self {
+            program,
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    /// Evaluates a compiled input, printing its output. Returns whether it
+    /// ran successfully.
+    fn evaluate(&mut self, path: &Utf8Path) -> Result<bool, Error> {
+        writeln!(self.stdin, "{path}")
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| self.error(Some(e.kind())))?;
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = self
+                .stdout
+                .read_line(&mut line)
+                .map_err(|e| self.error(Some(e.kind())))?;
+            if read == 0 {
+                return Err(self.error(None));
+            }
+            match line.trim_end().strip_prefix(DONE) {
+                Some(status) => return Ok(status.trim() == "ok"),
+                None => print!("{line}"),
+            }
+        }
+    }
+
+    fn error(&self, err: Option<std::io::ErrorKind>) -> Error {
+        Error::ShellCommand {
+            program: self.program.into(),
+            err,
+        }
+    }
+
+    fn stop(mut self) {
+        drop(self.stdin);
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn unclosed_brackets_continue_the_input() {
+    assert!(is_incomplete("let x = [1, 2"));
+    assert!(is_incomplete("fn(a) {\n  a"));
+    assert!(!is_incomplete("fn(a) {\n  a\n}"));
+}
+
+#[test]
+fn brackets_in_strings_and_comments_are_ignored() {
+    assert!(!is_incomplete("\"(\" // {"));
+    assert!(is_incomplete("\"\\\" ("));
+}
+
+#[test]
+fn trailing_operators_continue_the_input() {
+    assert!(is_incomplete("[1, 2]\n|>"));
+    assert!(is_incomplete("let x ="));
+    assert!(!is_incomplete("x |> f"));
+}
@@ -0,0 +1,189 @@
+use camino::Utf8Path;
+use clap::ValueEnum;
+use gleam_core::{
+    analyse::TargetSupport,
+    ast::TodoKind,
+    build::{Built, Codegen, Mode, Module, Options, Target, WorkItemKind},
+    line_numbers::LineNumbers,
+    type_::pretty::Printer,
+    Result,
+};
+use itertools::Itertools;
+use serde::Serialize;
+use strum::{Display, EnumString, VariantNames};
+
+use crate::build;
+
+#[derive(Debug, Display, EnumString, VariantNames, ValueEnum, Clone, Copy, Default)]
+#[strum(serialize_all = "kebab_case")]
+pub enum Sort {
+    /// Sort by file and position in the file
+    #[default]
+    File,
+    /// Group items of the same kind together
+    Kind,
+}
+
+/// Prints every `todo` in the given modules along with the type the compiler
+/// expects it to produce.
+pub fn print(modules: &[Module], root: &Utf8Path) {
+    let mut count = 0;
+
+    for module in modules.iter().sorted_by(|a, b| a.name.cmp(&b.name)) {
+        let path = relative_path(module, root);
+        let line_numbers = LineNumbers::new(&module.code);
+
+        for todo in module.todos() {
+            count += 1;
+            let position = line_numbers.line_and_column_number(todo.location.start);
+            let type_ = Printer::new().pretty_print(&todo.type_, 0);
+            let description = todo_description(&todo.kind);
+            let message = match &todo.message {
+                Some(message) => format!(" - {message}"),
+                None => String::new(),
+            };
+            println!(
+                "{path}:{line}:{column} {description}: {type_}{message}",
+                line = position.line,
+                column = position.column,
+            );
+        }
+    }
+
+    match count {
+        0 => println!("No todos found"),
+        1 => println!("1 todo found"),
+        _ => println!("{count} todos found"),
+    }
+}
+
+/// Lists every `todo`, `panic as` and use of a deprecated value in the
+/// project.
+pub fn command(target: Option<Target>, sort: Sort, json: bool) -> Result<()> {
+    let paths = crate::find_project_paths()?;
+    let built = build_all_modules(target)?;
+    let items = collect(&built, paths.root(), sort);
+
+    if json {
+        let json = serde_json::to_string_pretty(&items).expect("work items to json");
+        println!("{json}");
+        return Ok(());
+    }
+
+    for item in &items {
+        let message = match &item.message {
+            Some(message) => format!(" - {message}"),
+            None => String::new(),
+        };
+        println!(
+            "{path}:{line}:{column} {description}: {type_}{message}",
+            path = item.path,
+            line = item.line,
+            column = item.column,
+            description = item.description,
+            type_ = item.type_,
+        );
+    }
+
+    match items.len() {
+        0 => println!("No work items found"),
+        1 => println!("1 work item found"),
+        count => println!("{count} work items found"),
+    }
+    Ok(())
+}
+
+/// Builds the project, type checking every module of the root package even if
+/// it has been cached, so all of their todos can be found.
+pub fn build_all_modules(target: Option<Target>) -> Result<Built> {
+    build::main_recompiling_root_package(
+        Options {
+            root_target_support: TargetSupport::Enforced,
+            doc_tests: false,
+            warnings_as_errors: false,
+            codegen: Codegen::DepsOnly,
+            mode: Mode::Dev,
+            target,
+        },
+        build::download_dependencies()?,
+    )
+}
+
+#[derive(Debug, Serialize)]
+struct Item {
+    path: String,
+    line: u32,
+    column: u32,
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(rename = "type")]
+    type_: String,
+    message: Option<String>,
+    #[serde(skip)]
+    description: String,
+    #[serde(skip)]
+    kind_order: u8,
+}
+
+fn collect(built: &Built, root: &Utf8Path, sort: Sort) -> Vec<Item> {
+    let mut items = vec![];
+    for module in built
+        .root_package
+        .modules
+        .iter()
+        .sorted_by(|a, b| a.input_path.cmp(&b.input_path))
+    {
+        let path = relative_path(module, root);
+        let line_numbers = LineNumbers::new(&module.code);
+
+        for item in built.work_items(module) {
+            let position = line_numbers.line_and_column_number(item.location.start);
+            let (kind, kind_order, name) = match &item.kind {
+                WorkItemKind::Todo(TodoKind::Keyword) => ("todo", 0, None),
+                WorkItemKind::Todo(TodoKind::EmptyFunction) => ("empty-function", 1, None),
+                WorkItemKind::Todo(TodoKind::IncompleteUse) => ("incomplete-use", 2, None),
+                WorkItemKind::Panic => ("panic", 3, None),
+                WorkItemKind::Deprecated { name } => ("deprecated", 4, Some(name.to_string())),
+            };
+            let description = match (&item.kind, &name) {
+                (WorkItemKind::Todo(kind), _) => todo_description(kind).into(),
+                (_, Some(name)) => format!("deprecated `{name}`"),
+                (_, None) => kind.into(),
+            };
+            items.push(Item {
+                path: path.to_string(),
+                line: position.line,
+                column: position.column,
+                kind,
+                name,
+                type_: Printer::new().pretty_print(&item.type_, 0),
+                message: item.message.map(|message| message.to_string()),
+                description,
+                kind_order,
+            });
+        }
+    }
+
+    match sort {
+        // Items are already ordered by file and position.
+        Sort::File => {}
+        Sort::Kind => items.sort_by_key(|item| item.kind_order),
+    }
+    items
+}
+
+fn relative_path<'a>(module: &'a Module, root: &Utf8Path) -> &'a Utf8Path {
+    module
+        .input_path
+        .strip_prefix(root)
+        .unwrap_or(&module.input_path)
+}
+
+fn todo_description(kind: &TodoKind) -> &'static str {
+    match kind {
+        TodoKind::Keyword => "todo",
+        TodoKind::EmptyFunction => "empty function body",
+        TodoKind::IncompleteUse => "incomplete use expression",
+    }
+}
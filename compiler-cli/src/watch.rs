@@ -0,0 +1,189 @@
+//! Running a command again each time the files of the project change, for
+//! `gleam run --watch` and `gleam test --watch`.
+//!
+//! The files are polled for changes, as the modification times of the
+//! project's source files can be read quickly enough for the projects Gleam is
+//! used for, and this works the same way on every operating system.
+
+use std::{
+    collections::BTreeMap,
+    io::{self, IsTerminal},
+    process::Child,
+    time::{Duration, SystemTime},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use gleam_core::{config::PackageConfig, paths::ProjectPaths, requirement::Requirement, Error};
+
+/// How often files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long files have to be left unchanged before the command is run again,
+/// so saving several files at once runs it once.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// The modification time of every watched file.
+pub type Snapshot = BTreeMap<Utf8PathBuf, SystemTime>;
+
+/// The files of a project: its `gleam.toml` and the `src` and `test`
+/// directories of the project and of its path dependencies.
+#[derive(Debug)]
+pub struct Watcher {
+    files: Vec<Utf8PathBuf>,
+    directories: Vec<Utf8PathBuf>,
+}
+
+impl Watcher {
+    pub fn new(paths: &ProjectPaths, config: &PackageConfig) -> Self {
+        let path_dependencies = config
+            .dependencies
+            .values()
+            .chain(config.dev_dependencies.values())
+            .filter_map(|requirement| match requirement {
+                Requirement::Path { path } => Some(paths.root().join(path)),
+                Requirement::Hex { .. } | Requirement::Git { .. } => None,
+            });
+        let roots: Vec<_> = std::iter::once(paths.root().to_path_buf())
+            .chain(path_dependencies)
+            .collect();
+
+        Self {
+            files: roots.iter().map(|root| root.join("gleam.toml")).collect(),
+            directories: roots
+                .iter()
+                .flat_map(|root| [root.join("src"), root.join("test")])
+                .collect(),
+        }
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        let mut snapshot = Snapshot::new();
+        for file in &self.files {
+            add_file(&mut snapshot, file);
+        }
+        for directory in &self.directories {
+            for entry in walkdir::WalkDir::new(directory)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_file())
+            {
+                if let Ok(path) = Utf8PathBuf::from_path_buf(entry.into_path()) {
+                    add_file(&mut snapshot, &path);
+                }
+            }
+        }
+        snapshot
+    }
+
+    /// Waits until the files are different to the snapshot, and then until
+    /// they stop changing.
+    pub fn wait_for_change(&self, since: &Snapshot) {
+        let mut last = self.snapshot();
+        while last == *since {
+            std::thread::sleep(POLL_INTERVAL);
+            last = self.snapshot();
+        }
+        loop {
+            std::thread::sleep(DEBOUNCE);
+            let next = self.snapshot();
+            if next == last {
+                return;
+            }
+            last = next;
+        }
+    }
+
+    /// Runs a program until it exits, returning its exit status, or until the
+    /// files are different to the snapshot, in which case the program is
+    /// stopped and `None` is returned.
+    pub fn run_until_changed(
+        &self,
+        since: &Snapshot,
+        program: &str,
+        args: &[String],
+        env: &[(&str, String)],
+    ) -> Result<Option<i32>, Error> {
+        tracing::trace!(program=program, args=?args.join(" "), env=?env, "command_spawn");
+        let mut child = std::process::Command::new(program)
+            .args(args)
+            .envs(env.iter().map(|pair| (pair.0, &pair.1)))
+            .spawn()
+            .map_err(|error| command_error(program, error))?;
+
+        loop {
+            if let Some(status) = child
+                .try_wait()
+                .map_err(|error| command_error(program, error))?
+            {
+                return Ok(Some(status.code().unwrap_or_default()));
+            }
+            if self.snapshot() != *since {
+                stop(&mut child);
+                return Ok(None);
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// Runs the command, and runs it again each time the files of the project
+/// change. The command is given the snapshot of the files taken before it
+/// started, and returns `None` if it was stopped part way through because
+/// they changed. This only returns if the command can't be started.
+pub fn watch<F>(watcher: &Watcher, mut command: F) -> Result<(), Error>
+where
+    F: FnMut(&Snapshot) -> Result<Option<i32>, Error>,
+{
+    loop {
+        let snapshot = watcher.snapshot();
+        match command(&snapshot) {
+            Ok(None) => {
+                clear_screen();
+                continue;
+            }
+            Ok(Some(status)) => {
+                println!("\nExited with status {status}. Waiting for changes...");
+            }
+            Err(error) => {
+                let stderr = crate::cli::stderr_buffer_writer();
+                let mut buffer = stderr.buffer();
+                error.pretty(&mut buffer);
+                stderr.print(&buffer).expect("Watch error writing");
+                println!("Waiting for changes...");
+            }
+        }
+        watcher.wait_for_change(&snapshot);
+        clear_screen();
+    }
+}
+
+fn add_file(snapshot: &mut Snapshot, path: &Utf8Path) {
+    if let Ok(modified) = path.metadata().and_then(|metadata| metadata.modified()) {
+        let _ = snapshot.insert(path.to_path_buf(), modified);
+    }
+}
+
+fn stop(child: &mut Child) {
+    if let Err(error) = child.kill() {
+        tracing::debug!(error = %error, "stop_watched_program");
+    }
+    let _ = child.wait();
+}
+
+fn clear_screen() {
+    if io::stdout().is_terminal() {
+        print!("\x1b[2J\x1b[H");
+    }
+}
+
+fn command_error(program: &str, error: io::Error) -> Error {
+    match error.kind() {
+        io::ErrorKind::NotFound => Error::ShellProgramNotFound {
+            program: program.to_string(),
+        },
+        other => Error::ShellCommand {
+            program: program.to_string(),
+            err: Some(other),
+        },
+    }
+}
@@ -2825,6 +2825,33 @@ impl ::capnp::traits::HasTypeId for Publicity {
   fn type_id() -> u64 { 0xc549_d3c8_21e9_1c66u64 }
 }
 
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Purity {
+  Pure = 0,
+  Impure = 1,
+  Unknown = 2,
+}
+impl ::capnp::traits::FromU16 for Purity {
+  #[inline]
+  fn from_u16(value: u16) -> ::core::result::Result<Purity, ::capnp::NotInSchema> {
+    match value {
+      0 => ::core::result::Result::Ok(Purity::Pure),
+      1 => ::core::result::Result::Ok(Purity::Impure),
+      2 => ::core::result::Result::Ok(Purity::Unknown),
+      n => ::core::result::Result::Err(::capnp::NotInSchema(n)),
+    }
+  }
+}
+impl ::capnp::traits::ToU16 for Purity {
+  #[inline]
+  fn to_u16(self) -> u16 { self as u16 }
+}
+impl ::capnp::traits::HasTypeId for Purity {
+  #[inline]
+  fn type_id() -> u64 { 0xb7e2_4c91_0d5a_3f68u64 }
+}
+
 pub mod implementations {
   #[derive(Copy, Clone)]
   pub struct Owned(());
@@ -3134,6 +3161,8 @@ pub mod value_constructor_variant {
       self.builder.get_pointer_field(1).clear();
       self.builder.get_pointer_field(2).clear();
       self.builder.set_data_field::<u16>(1, 0u16);
+      self.builder.set_data_field::<u16>(2, 0u16);
+      self.builder.set_bool_field(48, false);
       self.builder.get_pointer_field(3).clear();
       self.builder.get_pointer_field(4).clear();
       self.builder.get_pointer_field(5).clear();
@@ -3536,6 +3565,14 @@ pub mod value_constructor_variant {
       pub fn has_implementations(&self) -> bool {
         !self.reader.get_pointer_field(5).is_null()
       }
+      #[inline]
+      pub fn get_purity(self) -> ::core::result::Result<crate::schema_capnp::Purity,::capnp::NotInSchema> {
+        ::capnp::traits::FromU16::from_u16(self.reader.get_data_field::<u16>(2))
+      }
+      #[inline]
+      pub fn get_must_use(self) -> bool {
+        self.reader.get_bool_field(48)
+      }
     }
 
     pub struct Builder<'a> { builder: ::capnp::private::layout::StructBuilder<'a> }
@@ -3690,6 +3727,22 @@ pub mod value_constructor_variant {
       pub fn has_implementations(&self) -> bool {
         !self.builder.get_pointer_field(5).is_null()
       }
+      #[inline]
+      pub fn get_purity(self) -> ::core::result::Result<crate::schema_capnp::Purity,::capnp::NotInSchema> {
+        ::capnp::traits::FromU16::from_u16(self.builder.get_data_field::<u16>(2))
+      }
+      #[inline]
+      pub fn set_purity(&mut self, value: crate::schema_capnp::Purity)  {
+        self.builder.set_data_field::<u16>(2, value as u16)
+      }
+      #[inline]
+      pub fn get_must_use(self) -> bool {
+        self.builder.get_bool_field(48)
+      }
+      #[inline]
+      pub fn set_must_use(&mut self, value: bool)  {
+        self.builder.set_bool_field(48, value);
+      }
     }
 
     pub struct Pipeline { _typeless: ::capnp::any_pointer::Pipeline }
@@ -6368,6 +6421,14 @@ pub mod line_numbers {
     pub fn get_length(self) -> u32 {
       self.reader.get_data_field::<u32>(0)
     }
+    #[inline]
+    pub fn get_multi_byte_characters(self) -> ::capnp::Result<::capnp::struct_list::Reader<'a,crate::schema_capnp::multi_byte_character::Owned>> {
+      ::capnp::traits::FromPointerReader::get_from_pointer(&self.reader.get_pointer_field(1), ::core::option::Option::None)
+    }
+    #[inline]
+    pub fn has_multi_byte_characters(&self) -> bool {
+      !self.reader.get_pointer_field(1).is_null()
+    }
   }
 
   pub struct Builder<'a> { builder: ::capnp::private::layout::StructBuilder<'a> }
@@ -6442,6 +6503,22 @@ pub mod line_numbers {
     pub fn set_length(&mut self, value: u32)  {
       self.builder.set_data_field::<u32>(0, value);
     }
+    #[inline]
+    pub fn get_multi_byte_characters(self) -> ::capnp::Result<::capnp::struct_list::Builder<'a,crate::schema_capnp::multi_byte_character::Owned>> {
+      ::capnp::traits::FromPointerBuilder::get_from_pointer(self.builder.get_pointer_field(1), ::core::option::Option::None)
+    }
+    #[inline]
+    pub fn set_multi_byte_characters(&mut self, value: ::capnp::struct_list::Reader<'a,crate::schema_capnp::multi_byte_character::Owned>) -> ::capnp::Result<()> {
+      ::capnp::traits::SetPointerBuilder::set_pointer_builder(self.builder.get_pointer_field(1), value, false)
+    }
+    #[inline]
+    pub fn init_multi_byte_characters(self, size: u32) -> ::capnp::struct_list::Builder<'a,crate::schema_capnp::multi_byte_character::Owned> {
+      ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(1), size)
+    }
+    #[inline]
+    pub fn has_multi_byte_characters(&self) -> bool {
+      !self.builder.get_pointer_field(1).is_null()
+    }
   }
 
   pub struct Pipeline { _typeless: ::capnp::any_pointer::Pipeline }
@@ -6454,7 +6531,144 @@ pub mod line_numbers {
   }
   mod _private {
     use capnp::private::layout;
-    pub const STRUCT_SIZE: layout::StructSize = layout::StructSize { data: 1, pointers: 1 };
+    pub const STRUCT_SIZE: layout::StructSize = layout::StructSize { data: 1, pointers: 2 };
     pub const TYPE_ID: u64 = 0xebf6_0b4e_3e31_2165;
   }
 }
+
+pub mod multi_byte_character {
+  #[derive(Copy, Clone)]
+  pub struct Owned(());
+  impl <'a> ::capnp::traits::Owned<'a> for Owned { type Reader = Reader<'a>; type Builder = Builder<'a>; }
+  impl <'a> ::capnp::traits::OwnedStruct<'a> for Owned { type Reader = Reader<'a>; type Builder = Builder<'a>; }
+  impl ::capnp::traits::Pipelined for Owned { type Pipeline = Pipeline; }
+
+  #[derive(Clone, Copy)]
+  pub struct Reader<'a> { reader: ::capnp::private::layout::StructReader<'a> }
+
+  impl <'a,> ::capnp::traits::HasTypeId for Reader<'a,>  {
+    #[inline]
+    fn type_id() -> u64 { _private::TYPE_ID }
+  }
+  impl <'a,> ::capnp::traits::FromStructReader<'a> for Reader<'a,>  {
+    fn new(reader: ::capnp::private::layout::StructReader<'a>) -> Reader<'a,> {
+      Reader { reader,  }
+    }
+  }
+
+  impl <'a,> ::capnp::traits::FromPointerReader<'a> for Reader<'a,>  {
+    fn get_from_pointer(reader: &::capnp::private::layout::PointerReader<'a>, default: ::core::option::Option<&'a [capnp::Word]>) -> ::capnp::Result<Reader<'a,>> {
+      ::core::result::Result::Ok(::capnp::traits::FromStructReader::new(reader.get_struct(default)?))
+    }
+  }
+
+  impl <'a,> ::capnp::traits::IntoInternalStructReader<'a> for Reader<'a,>  {
+    fn into_internal_struct_reader(self) -> ::capnp::private::layout::StructReader<'a> {
+      self.reader
+    }
+  }
+
+  impl <'a,> ::capnp::traits::Imbue<'a> for Reader<'a,>  {
+    fn imbue(&mut self, cap_table: &'a ::capnp::private::layout::CapTable) {
+      self.reader.imbue(::capnp::private::layout::CapTableReader::Plain(cap_table))
+    }
+  }
+
+  impl <'a,> Reader<'a,>  {
+    pub fn reborrow(&self) -> Reader<'_,> {
+      Reader { .. *self }
+    }
+
+    pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+      self.reader.total_size()
+    }
+    #[inline]
+    pub fn get_byte_index(self) -> u32 {
+      self.reader.get_data_field::<u32>(0)
+    }
+    #[inline]
+    pub fn get_length(self) -> u8 {
+      self.reader.get_data_field::<u8>(4)
+    }
+  }
+
+  pub struct Builder<'a> { builder: ::capnp::private::layout::StructBuilder<'a> }
+  impl <'a,> ::capnp::traits::HasStructSize for Builder<'a,>  {
+    #[inline]
+    fn struct_size() -> ::capnp::private::layout::StructSize { _private::STRUCT_SIZE }
+  }
+  impl <'a,> ::capnp::traits::HasTypeId for Builder<'a,>  {
+    #[inline]
+    fn type_id() -> u64 { _private::TYPE_ID }
+  }
+  impl <'a,> ::capnp::traits::FromStructBuilder<'a> for Builder<'a,>  {
+    fn new(builder: ::capnp::private::layout::StructBuilder<'a>) -> Builder<'a, > {
+      Builder { builder,  }
+    }
+  }
+
+  impl <'a,> ::capnp::traits::ImbueMut<'a> for Builder<'a,>  {
+    fn imbue_mut(&mut self, cap_table: &'a mut ::capnp::private::layout::CapTable) {
+      self.builder.imbue(::capnp::private::layout::CapTableBuilder::Plain(cap_table))
+    }
+  }
+
+  impl <'a,> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a,>  {
+    fn init_pointer(builder: ::capnp::private::layout::PointerBuilder<'a>, _size: u32) -> Builder<'a,> {
+      ::capnp::traits::FromStructBuilder::new(builder.init_struct(_private::STRUCT_SIZE))
+    }
+    fn get_from_pointer(builder: ::capnp::private::layout::PointerBuilder<'a>, default: ::core::option::Option<&'a [capnp::Word]>) -> ::capnp::Result<Builder<'a,>> {
+      ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(builder.get_struct(_private::STRUCT_SIZE, default)?))
+    }
+  }
+
+  impl <'a,> ::capnp::traits::SetPointerBuilder for Reader<'a,>  {
+    fn set_pointer_builder<'b>(pointer: ::capnp::private::layout::PointerBuilder<'b>, value: Reader<'a,>, canonicalize: bool) -> ::capnp::Result<()> { pointer.set_struct(&value.reader, canonicalize) }
+  }
+
+  impl <'a,> Builder<'a,>  {
+    pub fn into_reader(self) -> Reader<'a,> {
+      ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+    }
+    pub fn reborrow(&mut self) -> Builder<'_,> {
+      Builder { .. *self }
+    }
+    pub fn reborrow_as_reader(&self) -> Reader<'_,> {
+      ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+    }
+
+    pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+      self.builder.into_reader().total_size()
+    }
+    #[inline]
+    pub fn get_byte_index(self) -> u32 {
+      self.builder.get_data_field::<u32>(0)
+    }
+    #[inline]
+    pub fn set_byte_index(&mut self, value: u32)  {
+      self.builder.set_data_field::<u32>(0, value);
+    }
+    #[inline]
+    pub fn get_length(self) -> u8 {
+      self.builder.get_data_field::<u8>(4)
+    }
+    #[inline]
+    pub fn set_length(&mut self, value: u8)  {
+      self.builder.set_data_field::<u8>(4, value);
+    }
+  }
+
+  pub struct Pipeline { _typeless: ::capnp::any_pointer::Pipeline }
+  impl ::capnp::capability::FromTypelessPipeline for Pipeline {
+    fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
+      Pipeline { _typeless: typeless,  }
+    }
+  }
+  impl Pipeline  {
+  }
+  mod _private {
+    use capnp::private::layout;
+    pub const STRUCT_SIZE: layout::StructSize = layout::StructSize { data: 1, pointers: 0 };
+    pub const TYPE_ID: u64 = 0xd1c5_8a3e_f4b2_6790;
+  }
+}
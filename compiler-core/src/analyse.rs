@@ -23,6 +23,7 @@ use crate::{
         fields::{FieldMap, FieldMapBuilder},
         hydrator::Hydrator,
         prelude::*,
+        purity::{self, Purity},
         AccessorsMap, Deprecation, ModuleInterface, PatternConstructor, RecordAccessor, Type,
         TypeConstructor, TypeValueConstructor, TypeValueConstructorField, TypeVariantConstructors,
         ValueConstructor, ValueConstructorVariant,
@@ -127,11 +128,11 @@ pub fn infer_module<A>(
     line_numbers: LineNumbers,
     package_config: &PackageConfig,
     src_path: Utf8PathBuf,
-) -> Result<TypedModule, Error> {
+) -> Result<TypedModule, Vec1<Error>> {
     let name = module.name.clone();
     let documentation = std::mem::take(&mut module.documentation);
     let package = package_config.name.clone();
-    let env = Environment::new(
+    let mut env = Environment::new(
         ids.clone(),
         package_config.name.clone(),
         name.clone(),
@@ -140,6 +141,7 @@ pub fn infer_module<A>(
         warnings,
         target_support,
     );
+    env.warnings_config = package_config.warnings.clone();
     validate_module_name(&name)?;
 
     let mut type_names = HashMap::with_capacity(module.definitions.len());
@@ -216,31 +218,40 @@ pub fn infer_module<A>(
     // on those, etc.
     let definition_groups = into_dependency_order(statements.functions, statements.constants)?;
     let mut working_group = vec![];
+    // Errors in a definition don't stop the others from being inferred, so
+    // that all of them can be reported at once.
+    let mut errors = vec![];
 
     for group in definition_groups {
         // A group may have multiple functions that depend on each other through
         // mutual recursion.
 
         for definition in group {
-            match definition {
-                CallGraphNode::Function(f) => {
-                    let statement = infer_function(f, &mut env, &mut hydrators, &name)?;
-                    working_group.push(statement);
-                }
+            let result = match definition {
+                CallGraphNode::Function(f) => infer_function(f, &mut env, &mut hydrators, &name),
                 CallGraphNode::ModuleConstant(c) => {
-                    let statement = infer_module_constant(c, &mut env, &name)?;
-                    working_group.push(statement);
+                    infer_module_constant(c, &mut env, &name).map_err(Vec1::new)
                 }
+            };
+            match result {
+                Ok(statement) => working_group.push(statement),
+                Err(definition_errors) => errors.extend(definition_errors),
             }
         }
 
-        // Now that the entire group has been inferred, generalise their types.
+        // Now that the entire group has been inferred, generalise their types
+        // and record whether their functions have side effects.
+        let purities = purity::infer_group_purity(&working_group, &name);
         for inferred in working_group.drain(..) {
-            let statement = generalise_statement(inferred, &name, &mut env);
+            let statement = generalise_statement(inferred, &name, &purities, &mut env);
             typed_statements.push(statement);
         }
     }
 
+    if let Ok(errors) = Vec1::try_from_vec(errors) {
+        return Err(errors);
+    }
+
     // Generate warnings for unused items
     let unused_imports = env.convert_unused_to_warnings();
 
@@ -261,7 +272,8 @@ pub fn infer_module<A>(
             return Err(Error::PrivateTypeLeak {
                 location: value.variant.definition_location(),
                 leaked,
-            });
+            }
+            .into());
         }
 
         // We also want to make sure that no public type exposes internal ones
@@ -287,7 +299,7 @@ pub fn infer_module<A>(
 
     let is_internal = package_config.is_internal_module(name.as_str());
 
-    Ok(ast::Module {
+    let module = ast::Module {
         documentation,
         name: name.clone(),
         definitions: typed_statements,
@@ -305,7 +317,13 @@ pub fn infer_module<A>(
             line_numbers,
             src_path,
         },
-    })
+    };
+
+    for warning in crate::lint::check_module(&package_config.lints, &module) {
+        warnings.emit(type_::Warning::Lint { warning });
+    }
+
+    Ok(module)
 }
 
 fn validate_module_name(name: &EcoString) -> Result<(), Error> {
@@ -336,6 +354,7 @@ fn register_type_alias(
         alias: name,
         type_ast: resolved_type,
         deprecation,
+        since: _,
         type_: _,
         documentation: _,
     } = t;
@@ -372,7 +391,7 @@ fn register_type_alias(
 
     // Register the type for detection of dead code.
     if publicity.is_private() {
-        environment.init_usage(name.clone(), EntityKind::PrivateType, *location);
+        environment.init_usage(name.clone(), EntityKind::PrivateType(*location), *location);
     };
     Ok(())
 }
@@ -441,7 +460,11 @@ fn register_types_from_custom_type<'a>(
     }
 
     if publicity.is_private() {
-        environment.init_usage(name.clone(), EntityKind::PrivateType, *location);
+        environment.init_usage(
+            name.clone(),
+            EntityKind::PrivateType(t.full_location()),
+            *location,
+        );
     };
     Ok(())
 }
@@ -600,6 +623,8 @@ fn register_value_from_function(
         external_erlang,
         external_javascript,
         deprecation,
+        since: _,
+        must_use,
         end_position: _,
         body: _,
         return_type: _,
@@ -638,10 +663,16 @@ fn register_value_from_function(
         arity: args.len(),
         location: *location,
         implementations: *implementations,
+        purity: preregistered_purity(external_erlang, external_javascript),
+        must_use: *must_use,
     };
     environment.insert_variable(name.clone(), variant, typ, *publicity, deprecation.clone());
     if publicity.is_private() {
-        environment.init_usage(name.clone(), EntityKind::PrivateFunction, *location);
+        environment.init_usage(
+            name.clone(),
+            EntityKind::PrivateFunction(f.full_location()),
+            *location,
+        );
     };
     Ok(())
 }
@@ -688,7 +719,7 @@ fn infer_function(
     environment: &mut Environment<'_>,
     hydrators: &mut HashMap<EcoString, Hydrator>,
     module_name: &EcoString,
-) -> Result<TypedDefinition, Error> {
+) -> Result<TypedDefinition, Vec1<Error>> {
     let Function {
         documentation: doc,
         location,
@@ -699,6 +730,8 @@ fn infer_function(
         return_annotation,
         end_position: end_location,
         deprecation,
+        since,
+        must_use,
         external_erlang,
         external_javascript,
         return_type: (),
@@ -736,23 +769,24 @@ fn infer_function(
     };
 
     // Infer the type using the preregistered args + return types as a starting point
-    let (type_, args, body, implementations) = environment.in_new_scope(|environment| {
-        let args_types = arguments
-            .into_iter()
-            .zip(&args_types)
-            .map(|(a, t)| a.set_type(t.clone()))
-            .collect();
-        let mut expr_typer = ExprTyper::new(environment, definition);
-        expr_typer.hydrator = hydrators
-            .remove(&name)
-            .expect("Could not find hydrator for fn");
-
-        let (args, body) =
-            expr_typer.infer_fn_with_known_types(args_types, body, Some(return_type))?;
-        let args_types = args.iter().map(|a| a.type_.clone()).collect();
-        let typ = fn_(args_types, body.last().type_());
-        Ok((typ, args, body, expr_typer.implementations))
-    })?;
+    let (type_, args, body, implementations) =
+        environment.in_new_scope(|environment| -> Result<_, Vec1<Error>> {
+            let args_types = arguments
+                .into_iter()
+                .zip(&args_types)
+                .map(|(a, t)| a.set_type(t.clone()))
+                .collect();
+            let mut expr_typer = ExprTyper::new(environment, definition);
+            expr_typer.hydrator = hydrators
+                .remove(&name)
+                .expect("Could not find hydrator for fn");
+
+            let result = expr_typer.infer_fn_with_known_types(args_types, body, Some(return_type));
+            let (args, body) = expr_typer.with_recorded_errors(result)?;
+            let args_types = args.iter().map(|a| a.type_.clone()).collect();
+            let typ = fn_(args_types, body.last().type_());
+            Ok((typ, args, body, expr_typer.implementations))
+        })?;
 
     // Assert that the inferred type matches the type of any recursive call
     unify(preregistered_type, type_.clone()).map_err(|e| convert_unify_error(e, location))?;
@@ -768,7 +802,8 @@ fn infer_function(
             name: name.clone(),
             target,
             location,
-        });
+        }
+        .into());
     }
 
     let variant = ValueConstructorVariant::ModuleFn {
@@ -779,6 +814,8 @@ fn infer_function(
         arity: args.len(),
         location,
         implementations,
+        purity: preregistered_purity(&external_erlang, &external_javascript),
+        must_use,
     };
 
     environment.insert_variable(
@@ -795,6 +832,8 @@ fn infer_function(
         name,
         publicity,
         deprecation,
+        since,
+        must_use,
         arguments: args,
         end_position: end_location,
         return_annotation,
@@ -808,6 +847,19 @@ fn infer_function(
     }))
 }
 
+/// The purity a function is assumed to have until its whole group has been
+/// inferred. Functions with external implementations are always impure.
+fn preregistered_purity(
+    external_erlang: &Option<(EcoString, EcoString)>,
+    external_javascript: &Option<(EcoString, EcoString)>,
+) -> Purity {
+    if external_erlang.is_some() || external_javascript.is_some() {
+        Purity::Impure
+    } else {
+        Purity::Pure
+    }
+}
+
 /// Returns the module name and function name of the implementation of a
 /// function. If the function is implemented as a Gleam function then it is the
 /// same as the name of the module and function. If the function has an external
@@ -880,6 +932,7 @@ fn insert_type_alias(
         parameters: args,
         type_ast: resolved_type,
         deprecation,
+        since,
         ..
     } = t;
     let typ = environment
@@ -896,6 +949,7 @@ fn insert_type_alias(
         type_ast: resolved_type,
         type_: typ,
         deprecation,
+        since,
     }))
 }
 
@@ -913,6 +967,7 @@ fn infer_custom_type(
         parameters,
         constructors,
         deprecation,
+        since,
         ..
     } = t;
     let constructors = constructors
@@ -938,6 +993,7 @@ fn infer_custom_type(
                                     label,
                                     ast,
                                     location,
+                                    doc,
                                     ..
                                 },
                                 t,
@@ -947,7 +1003,7 @@ fn infer_custom_type(
                                     ast,
                                     location,
                                     type_: t.clone(),
-                                    doc: None,
+                                    doc,
                                 }
                             },
                         )
@@ -982,6 +1038,7 @@ fn infer_custom_type(
         constructors,
         typed_parameters,
         deprecation,
+        since,
     }))
 }
 
@@ -1010,6 +1067,7 @@ fn record_imported_items_for_use_detection<A>(
                 location,
                 name: module.clone(),
                 imported_modules: environment.imported_modules.keys().cloned().collect(),
+                suggestions: environment.module_suggestions(&module),
             })?;
 
     // Modules should belong to a package that is a direct dependency of the
@@ -1050,6 +1108,7 @@ fn infer_module_constant(
         publicity,
         value,
         deprecation,
+        since,
         ..
     } = c;
 
@@ -1088,7 +1147,12 @@ fn infer_module_constant(
     environment.insert_module_value(name.clone(), variant);
 
     if publicity.is_private() {
-        environment.init_usage(name.clone(), EntityKind::PrivateConstant, location);
+        let definition_location = SrcSpan::new(location.start, typed_expr.location().end);
+        environment.init_usage(
+            name.clone(),
+            EntityKind::PrivateConstant(definition_location),
+            location,
+        );
     }
 
     Ok(Definition::ModuleConstant(ModuleConstant {
@@ -1100,6 +1164,7 @@ fn infer_module_constant(
         value: Box::new(typed_expr),
         type_,
         deprecation,
+        since,
         implementations,
     }))
 }
@@ -1155,10 +1220,14 @@ where
 fn generalise_statement(
     s: TypedDefinition,
     module_name: &EcoString,
+    purities: &HashMap<EcoString, Purity>,
     environment: &mut Environment<'_>,
 ) -> TypedDefinition {
     match s {
-        Definition::Function(function) => generalise_function(function, environment, module_name),
+        Definition::Function(function) => {
+            let purity = purities.get(&function.name).copied().unwrap_or_default();
+            generalise_function(function, environment, module_name, purity)
+        }
         Definition::ModuleConstant(constant) => {
             generalise_module_constant(constant, environment, module_name)
         }
@@ -1182,6 +1251,7 @@ fn generalise_module_constant(
         value,
         type_,
         deprecation,
+        since,
         implementations,
     } = constant;
     let typ = type_.clone();
@@ -1220,6 +1290,7 @@ fn generalise_module_constant(
         value,
         type_,
         deprecation,
+        since,
         implementations,
     })
 }
@@ -1228,6 +1299,7 @@ fn generalise_function(
     function: Function<Arc<Type>, ast::TypedExpr>,
     environment: &mut Environment<'_>,
     module_name: &EcoString,
+    purity: Purity,
 ) -> TypedDefinition {
     let Function {
         documentation: doc,
@@ -1235,6 +1307,8 @@ fn generalise_function(
         name,
         publicity,
         deprecation,
+        since,
+        must_use,
         arguments: args,
         body,
         return_annotation,
@@ -1267,6 +1341,8 @@ fn generalise_function(
         arity: args.len(),
         location,
         implementations,
+        purity,
+        must_use,
     };
     environment.insert_variable(
         name.clone(),
@@ -1291,6 +1367,8 @@ fn generalise_function(
         name,
         publicity,
         deprecation,
+        since,
+        must_use,
         arguments: args,
         end_position: end_location,
         return_annotation,
@@ -1375,7 +1453,7 @@ fn custom_type_accessors<A>(
 
 /// Returns the fields that have the same label and type across all variants of
 /// the given type.
-fn get_compatible_record_fields<A>(
+pub(crate) fn get_compatible_record_fields<A>(
     constructors: &[RecordConstructor<A>],
 ) -> Vec<(usize, &EcoString, &TypeAst)> {
     let mut compatible = vec![];
@@ -47,6 +47,7 @@ impl<'a> Importer<'a> {
                 location,
                 name: imported_module_name.clone(),
                 imported_modules: self.environment.imported_modules.keys().cloned().collect(),
+                suggestions: self.environment.module_suggestions(&imported_module_name),
             })?;
 
         self.check_src_does_not_import_test(module_info, location, imported_module_name.clone())?;
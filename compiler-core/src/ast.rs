@@ -1,6 +1,7 @@
 mod constant;
 mod typed;
 mod untyped;
+pub mod visit;
 
 #[cfg(test)]
 mod tests;
@@ -405,6 +406,12 @@ pub struct Function<T, Expr> {
     pub body: Vec1<Statement<T, Expr>>,
     pub publicity: Publicity,
     pub deprecation: Deprecation,
+    /// The version given by a `@since` attribute: the version of the package
+    /// the definition was added in.
+    pub since: Option<EcoString>,
+    /// Whether the function was marked with `@must_use`, meaning its return
+    /// value should not be discarded.
+    pub must_use: bool,
     pub return_annotation: Option<TypeAst>,
     pub return_type: T,
     pub documentation: Option<EcoString>,
@@ -417,7 +424,9 @@ pub type TypedFunction = Function<Arc<Type>, TypedExpr>;
 pub type UntypedFunction = Function<(), UntypedExpr>;
 
 impl<T, E> Function<T, E> {
-    fn full_location(&self) -> SrcSpan {
+    /// The `location` field of a `Function` is only the location of its
+    /// head. This method returns a `SrcSpan` that includes its body.
+    pub fn full_location(&self) -> SrcSpan {
         SrcSpan::new(self.location.start, self.end_position)
     }
 }
@@ -459,6 +468,7 @@ impl<T> Import<T> {
     }
 }
 
+pub type TypedModuleConstant = ModuleConstant<Arc<Type>, EcoString>;
 pub type UntypedModuleConstant = ModuleConstant<(), ()>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -479,9 +489,13 @@ pub struct ModuleConstant<T, ConstantRecordTag> {
     pub value: Box<Constant<T, ConstantRecordTag>>,
     pub type_: T,
     pub deprecation: Deprecation,
+    /// The version given by a `@since` attribute: the version of the package
+    /// the definition was added in.
+    pub since: Option<EcoString>,
     pub implementations: Implementations,
 }
 
+pub type TypedCustomType = CustomType<Arc<Type>>;
 pub type UntypedCustomType = CustomType<()>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -508,6 +522,9 @@ pub struct CustomType<T> {
     pub constructors: Vec<RecordConstructor<T>>,
     pub documentation: Option<EcoString>,
     pub deprecation: Deprecation,
+    /// The version given by a `@since` attribute: the version of the package
+    /// the definition was added in.
+    pub since: Option<EcoString>,
     pub opaque: bool,
     /// The names of the type parameters.
     pub parameters: Vec<EcoString>,
@@ -525,6 +542,7 @@ impl<T> CustomType<T> {
     }
 }
 
+pub type TypedTypeAlias = TypeAlias<Arc<Type>>;
 pub type UntypedTypeAlias = TypeAlias<()>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -545,6 +563,9 @@ pub struct TypeAlias<T> {
     pub publicity: Publicity,
     pub documentation: Option<EcoString>,
     pub deprecation: Deprecation,
+    /// The version given by a `@since` attribute: the version of the package
+    /// the definition was added in.
+    pub since: Option<EcoString>,
 }
 
 pub type TypedDefinition = Definition<Arc<Type>, TypedExpr, EcoString, EcoString>;
@@ -854,12 +875,31 @@ pub struct CallArg<A> {
     // not, which is not permitted if the argument is given explicitly by the
     // programmer rather than implicitly by Gleam's syntactic sugar.
     pub implicit: bool,
+    /// Whether the argument was written with the label shorthand syntax,
+    /// `label:`, in which case its value is the variable named after the label.
+    pub label_shorthand: bool,
 }
 
 impl CallArg<TypedExpr> {
     pub fn find_node(&self, byte_index: u32) -> Option<Located<'_>> {
         self.value.find_node(byte_index)
     }
+
+    /// The label of the argument given in the call, if it is at the byte
+    /// index.
+    pub fn find_label<'a>(&'a self, byte_index: u32, call: &'a TypedExpr) -> Option<Located<'a>> {
+        let label = self.label.as_ref().filter(|_| !self.implicit)?;
+        let location = SrcSpan::new(
+            self.location.start,
+            self.location.start + label.len() as u32,
+        );
+        location.contains(byte_index).then(|| Located::Label {
+            label,
+            location,
+            type_: self.value.type_(),
+            of: call,
+        })
+    }
 }
 
 impl CallArg<TypedPattern> {
@@ -894,6 +934,9 @@ pub struct UntypedRecordUpdateArg {
     pub label: EcoString,
     pub location: SrcSpan,
     pub value: UntypedExpr,
+    /// Whether the argument was written with the label shorthand syntax,
+    /// `label:`.
+    pub label_shorthand: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -908,6 +951,21 @@ impl TypedRecordUpdateArg {
     pub fn find_node(&self, byte_index: u32) -> Option<Located<'_>> {
         self.value.find_node(byte_index)
     }
+
+    /// The label of the field given in the record update, if it is at the
+    /// byte index.
+    pub fn find_label<'a>(&'a self, byte_index: u32, update: &'a TypedExpr) -> Option<Located<'a>> {
+        let location = SrcSpan::new(
+            self.location.start,
+            self.location.start + self.label.len() as u32,
+        );
+        location.contains(byte_index).then(|| Located::Label {
+            label: &self.label,
+            location,
+            type_: self.value.type_(),
+            of: update,
+        })
+    }
 }
 
 pub type MultiPattern<Type> = Vec<Pattern<Type>>;
@@ -1152,7 +1210,7 @@ impl TypedClauseGuard {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Default, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct SrcSpan {
     pub start: u32,
     pub end: u32,
@@ -1212,6 +1270,17 @@ pub enum Pattern<Type> {
         type_: Type,
     },
 
+    /// A reference to a module constant, matching a value equal to the
+    /// literal the constant is defined as.
+    /// e.g. `case x { config.max_size -> ... }`
+    Constant {
+        location: SrcSpan,
+        module: EcoString,
+        name: EcoString,
+        constructor: Option<Box<ValueConstructor>>,
+        type_: Type,
+    },
+
     /// A name given to a sub-pattern using the `as` keyword.
     /// e.g. `assert #(1, [_, _] as the_list) = x`
     Assign {
@@ -1309,6 +1378,7 @@ impl<A> Pattern<A> {
             Pattern::Int { location, .. }
             | Pattern::Variable { location, .. }
             | Pattern::VarUsage { location, .. }
+            | Pattern::Constant { location, .. }
             | Pattern::List { location, .. }
             | Pattern::Float { location, .. }
             | Pattern::Discard { location, .. }
@@ -1326,6 +1396,73 @@ impl<A> Pattern<A> {
     pub fn is_discard(&self) -> bool {
         matches!(self, Self::Discard { .. })
     }
+
+    /// The names and locations of all the variables this pattern binds.
+    pub fn bound_variables(&self) -> Vec<(EcoString, SrcSpan)> {
+        let mut variables = vec![];
+        self.collect_bound_variables(&mut variables);
+        variables
+    }
+
+    fn collect_bound_variables(&self, variables: &mut Vec<(EcoString, SrcSpan)>) {
+        match self {
+            Pattern::Int { .. }
+            | Pattern::Float { .. }
+            | Pattern::String { .. }
+            | Pattern::VarUsage { .. }
+            | Pattern::Constant { .. }
+            | Pattern::Discard { .. } => (),
+
+            Pattern::Variable { name, location, .. } => variables.push((name.clone(), *location)),
+
+            Pattern::Assign {
+                name,
+                location,
+                pattern,
+            } => {
+                pattern.collect_bound_variables(variables);
+                variables.push((name.clone(), *location));
+            }
+
+            Pattern::List { elements, tail, .. } => {
+                for element in elements.iter().chain(tail.as_deref()) {
+                    element.collect_bound_variables(variables);
+                }
+            }
+
+            Pattern::Constructor { arguments, .. } => {
+                for argument in arguments {
+                    argument.value.collect_bound_variables(variables);
+                }
+            }
+
+            Pattern::Tuple { elems, .. } => {
+                for element in elems {
+                    element.collect_bound_variables(variables);
+                }
+            }
+
+            Pattern::BitArray { segments, .. } => {
+                for segment in segments {
+                    segment.value.collect_bound_variables(variables);
+                }
+            }
+
+            Pattern::StringPrefix {
+                left_side_assignment,
+                right_location,
+                right_side_assignment,
+                ..
+            } => {
+                if let Some((name, location)) = left_side_assignment {
+                    variables.push((name.clone(), *location));
+                }
+                if let AssignName::Variable(name) = right_side_assignment {
+                    variables.push((name.clone(), *right_location));
+                }
+            }
+        }
+    }
 }
 
 impl TypedPattern {
@@ -1344,6 +1481,10 @@ impl TypedPattern {
             | Pattern::StringPrefix { .. } => None,
 
             Pattern::Constructor { constructor, .. } => constructor.definition_location(),
+
+            Pattern::Constant { constructor, .. } => constructor
+                .as_ref()
+                .map(|constructor| constructor.definition_location()),
         }
     }
 
@@ -1362,6 +1503,10 @@ impl TypedPattern {
             | Pattern::StringPrefix { .. } => None,
 
             Pattern::Constructor { constructor, .. } => constructor.get_documentation(),
+
+            Pattern::Constant { constructor, .. } => constructor
+                .as_ref()
+                .and_then(|constructor| constructor.get_documentation()),
         }
     }
 
@@ -1376,6 +1521,7 @@ impl TypedPattern {
             Pattern::Variable { type_, .. }
             | Pattern::List { type_, .. }
             | Pattern::VarUsage { type_, .. }
+            | Pattern::Constant { type_, .. }
             | Pattern::Constructor { type_, .. } => type_.clone(),
 
             Pattern::Assign { pattern, .. } => pattern.type_(),
@@ -1404,6 +1550,7 @@ impl TypedPattern {
             | Pattern::String { .. }
             | Pattern::Variable { .. }
             | Pattern::VarUsage { .. }
+            | Pattern::Constant { .. }
             | Pattern::Assign { .. }
             | Pattern::Discard { .. }
             | Pattern::BitArray { .. }
@@ -1703,6 +1850,16 @@ impl UntypedStatement {
             Statement::Assignment(_) | Statement::Use(_) => false,
         }
     }
+
+    /// The expression the statement evaluates: the expression itself, the
+    /// value assigned, or the function called by a `use`.
+    pub fn value(&self) -> &UntypedExpr {
+        match self {
+            Statement::Expression(expression) => expression,
+            Statement::Assignment(assignment) => &assignment.value,
+            Statement::Use(use_) => &use_.call,
+        }
+    }
 }
 
 impl TypedStatement {
@@ -5,7 +5,7 @@ use crate::build::Target;
 use crate::config::PackageConfig;
 use crate::line_numbers::LineNumbers;
 use crate::type_::expression::FunctionDefinition;
-use crate::type_::{Deprecation, PRELUDE_MODULE_NAME};
+use crate::type_::{Deprecation, Purity, PRELUDE_MODULE_NAME};
 use crate::{
     ast::{SrcSpan, TypedExpr},
     build::Located,
@@ -349,6 +349,7 @@ fn find_node_module_select() {
             name: "function".into(),
             location: SrcSpan { start: 1, end: 55 },
             documentation: None,
+            purity: Purity::Pure,
         },
     };
 
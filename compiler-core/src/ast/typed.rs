@@ -116,6 +116,7 @@ pub enum TypedExpr {
     Todo {
         location: SrcSpan,
         message: Option<Box<Self>>,
+        kind: TodoKind,
         type_: Arc<Type>,
     },
 
@@ -125,6 +126,14 @@ pub enum TypedExpr {
         type_: Arc<Type>,
     },
 
+    /// Prints the value of the expression, evaluating to that same value.
+    /// When `echo` is used as a step of a pipeline the expression is the
+    /// variable holding the value of the previous step.
+    Echo {
+        location: SrcSpan,
+        expression: Box<Self>,
+    },
+
     BitArray {
         location: SrcSpan,
         typ: Arc<Type>,
@@ -147,6 +156,14 @@ pub enum TypedExpr {
         location: SrcSpan,
         value: Box<Self>,
     },
+
+    /// An expression that could not be type checked. The type checker uses
+    /// it in place of the erroneous expression so it can carry on looking
+    /// for further errors. It never reaches code generation.
+    Invalid {
+        location: SrcSpan,
+        typ: Arc<Type>,
+    },
 }
 
 impl TypedExpr {
@@ -171,6 +188,7 @@ impl TypedExpr {
             Self::Var { .. }
             | Self::Int { .. }
             | Self::Todo { .. }
+            | Self::Invalid { .. }
             | Self::Panic { .. }
             | Self::Float { .. }
             | Self::String { .. }
@@ -200,7 +218,11 @@ impl TypedExpr {
                 .find_map(|e| e.find_node(byte_index))
                 .or_else(|| self.self_if_contains_location(byte_index)),
 
-            Self::NegateBool { value, .. } | Self::NegateInt { value, .. } => value
+            Self::NegateBool { value, .. }
+            | Self::NegateInt { value, .. }
+            | Self::Echo {
+                expression: value, ..
+            } => value
                 .find_node(byte_index)
                 .or_else(|| self.self_if_contains_location(byte_index)),
 
@@ -212,7 +234,10 @@ impl TypedExpr {
 
             Self::Call { fun, args, .. } => args
                 .iter()
-                .find_map(|arg| arg.find_node(byte_index))
+                .find_map(|arg| {
+                    arg.find_label(byte_index, self)
+                        .or_else(|| arg.find_node(byte_index))
+                })
                 .or_else(|| fun.find_node(byte_index))
                 .or_else(|| self.self_if_contains_location(byte_index)),
 
@@ -244,7 +269,10 @@ impl TypedExpr {
 
             Self::RecordUpdate { spread, args, .. } => args
                 .iter()
-                .find_map(|arg| arg.find_node(byte_index))
+                .find_map(|arg| {
+                    arg.find_label(byte_index, self)
+                        .or_else(|| arg.find_node(byte_index))
+                })
                 .or_else(|| spread.find_node(byte_index))
                 .or_else(|| self.self_if_contains_location(byte_index)),
         }
@@ -277,6 +305,7 @@ impl TypedExpr {
             | Self::Int { location, .. }
             | Self::Var { location, .. }
             | Self::Todo { location, .. }
+            | Self::Invalid { location, .. }
             | Self::Case { location, .. }
             | Self::Call { location, .. }
             | Self::List { location, .. }
@@ -284,6 +313,7 @@ impl TypedExpr {
             | Self::BinOp { location, .. }
             | Self::Tuple { location, .. }
             | Self::Panic { location, .. }
+            | Self::Echo { location, .. }
             | Self::Block { location, .. }
             | Self::String { location, .. }
             | Self::NegateBool { location, .. }
@@ -303,6 +333,7 @@ impl TypedExpr {
             | Self::Int { location, .. }
             | Self::Var { location, .. }
             | Self::Todo { location, .. }
+            | Self::Invalid { location, .. }
             | Self::Case { location, .. }
             | Self::Call { location, .. }
             | Self::List { location, .. }
@@ -311,6 +342,7 @@ impl TypedExpr {
             | Self::Tuple { location, .. }
             | Self::String { location, .. }
             | Self::Panic { location, .. }
+            | Self::Echo { location, .. }
             | Self::NegateBool { location, .. }
             | Self::NegateInt { location, .. }
             | Self::Pipeline { location, .. }
@@ -331,7 +363,9 @@ impl TypedExpr {
             | TypedExpr::Call { .. }
             | TypedExpr::Case { .. }
             | TypedExpr::Todo { .. }
+            | TypedExpr::Invalid { .. }
             | TypedExpr::Panic { .. }
+            | TypedExpr::Echo { .. }
             | TypedExpr::BinOp { .. }
             | TypedExpr::Float { .. }
             | TypedExpr::Tuple { .. }
@@ -367,10 +401,12 @@ impl TypedExpr {
         match self {
             Self::NegateBool { .. } => bool(),
             Self::NegateInt { value, .. } => value.type_(),
+            Self::Echo { expression, .. } => expression.type_(),
             Self::Var { constructor, .. } => constructor.type_.clone(),
             Self::Fn { typ, .. }
             | Self::Int { typ, .. }
             | Self::Todo { type_: typ, .. }
+            | Self::Invalid { typ, .. }
             | Self::Case { typ, .. }
             | Self::List { typ, .. }
             | Self::Call { typ, .. }
@@ -401,6 +437,14 @@ impl TypedExpr {
         }
     }
 
+    /// Returns `true` if the typed expr is [`Invalid`].
+    ///
+    /// [`Invalid`]: TypedExpr::Invalid
+    #[must_use]
+    pub fn is_invalid(&self) -> bool {
+        matches!(self, Self::Invalid { .. })
+    }
+
     /// Returns `true` if the typed expr is [`Var`].
     ///
     /// [`Var`]: TypedExpr::Var
@@ -430,7 +474,9 @@ impl TypedExpr {
             | TypedExpr::Tuple { .. }
             | TypedExpr::TupleIndex { .. }
             | TypedExpr::Todo { .. }
+            | TypedExpr::Invalid { .. }
             | TypedExpr::Panic { .. }
+            | TypedExpr::Echo { .. }
             | TypedExpr::BitArray { .. }
             | TypedExpr::RecordUpdate { .. }
             | TypedExpr::RecordAccess { .. }
@@ -498,7 +544,11 @@ impl TypedExpr {
             // `panic` and `todo` are never considered pure value constructors,
             // we don't want to raise a warning for an unused value if it's one
             // of those two.
-            TypedExpr::Todo { .. } | TypedExpr::Panic { .. } => false,
+            TypedExpr::Todo { .. } | TypedExpr::Panic { .. } | TypedExpr::Invalid { .. } => false,
+
+            // `echo` prints its value, so it is not pure even if the value
+            // being printed is.
+            TypedExpr::Echo { .. } => false,
         }
     }
 
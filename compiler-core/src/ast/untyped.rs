@@ -102,6 +102,14 @@ pub enum UntypedExpr {
         message: Option<Box<Self>>,
     },
 
+    /// Prints the value of an expression along with where it is in the
+    /// source code, evaluating to that same value. The expression is `None`
+    /// when `echo` is used on its own as a step of a pipeline.
+    Echo {
+        location: SrcSpan,
+        expression: Option<Box<Self>>,
+    },
+
     BitArray {
         location: SrcSpan,
         segments: Vec<UntypedExprBitArraySegment>,
@@ -133,6 +141,13 @@ pub enum UntypedExpr {
     Placeholder {
         location: SrcSpan,
     },
+
+    /// Source code that could not be parsed. This is only produced when
+    /// parsing with error recovery, so that the rest of the module can still
+    /// be analysed.
+    Invalid {
+        location: SrcSpan,
+    },
 }
 
 impl UntypedExpr {
@@ -152,17 +167,83 @@ impl UntypedExpr {
             | Self::BinOp { location, .. }
             | Self::Tuple { location, .. }
             | Self::Panic { location, .. }
+            | Self::Echo { location, .. }
             | Self::String { location, .. }
             | Self::BitArray { location, .. }
             | Self::NegateInt { location, .. }
             | Self::NegateBool { location, .. }
             | Self::TupleIndex { location, .. }
             | Self::Placeholder { location, .. }
+            | Self::Invalid { location, .. }
             | Self::FieldAccess { location, .. }
             | Self::RecordUpdate { location, .. } => *location,
         }
     }
 
+    /// The expressions this one is made of, including the values of the
+    /// statements of blocks and anonymous functions. The expressions of
+    /// clause guards and of the options of bit array segments aren't included.
+    pub fn children(&self) -> Vec<&Self> {
+        match self {
+            Self::Call { fun, arguments, .. } => std::iter::once(fun.as_ref())
+                .chain(arguments.iter().map(|arg| &arg.value))
+                .collect(),
+
+            Self::Block { statements, .. }
+            | Self::Fn {
+                body: statements, ..
+            } => statements.iter().map(UntypedStatement::value).collect(),
+
+            Self::List { elements, tail, .. } => elements.iter().chain(tail.as_deref()).collect(),
+
+            Self::BinOp { left, right, .. } => vec![left.as_ref(), right.as_ref()],
+
+            Self::PipeLine { expressions } => expressions.iter().collect(),
+
+            Self::Case {
+                subjects, clauses, ..
+            } => subjects
+                .iter()
+                .chain(clauses.iter().map(|clause| &clause.then))
+                .collect(),
+
+            Self::Tuple { elems, .. } => elems.iter().collect(),
+
+            Self::FieldAccess { container, .. } => vec![container.as_ref()],
+            Self::TupleIndex { tuple, .. } => vec![tuple.as_ref()],
+            Self::NegateBool { value, .. } | Self::NegateInt { value, .. } => vec![value.as_ref()],
+
+            Self::Todo { message, .. }
+            | Self::Panic { message, .. }
+            | Self::Echo {
+                expression: message,
+                ..
+            } => message.as_deref().into_iter().collect(),
+
+            Self::BitArray { segments, .. } => segments
+                .iter()
+                .map(|segment| segment.value.as_ref())
+                .collect(),
+
+            Self::RecordUpdate {
+                constructor,
+                spread,
+                arguments,
+                ..
+            } => [constructor.as_ref(), spread.base.as_ref()]
+                .into_iter()
+                .chain(arguments.iter().map(|arg| &arg.value))
+                .collect(),
+
+            Self::Int { .. }
+            | Self::Float { .. }
+            | Self::String { .. }
+            | Self::Var { .. }
+            | Self::Placeholder { .. }
+            | Self::Invalid { .. } => vec![],
+        }
+    }
+
     pub fn start_byte_index(&self) -> u32 {
         match self {
             Self::Block { location, .. } => location.start,
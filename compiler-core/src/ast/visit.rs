@@ -0,0 +1,618 @@
+//! A visitor for the typed AST.
+//!
+//! Implement the `Visit` trait, overriding the methods for the nodes you are
+//! interested in. Each method has a default implementation that calls the
+//! matching free function in this module, which walks the children of the
+//! node. When overriding a method call the free function to continue walking
+//! into the node's children, or don't to skip them.
+//!
+//! ```ignore
+//! struct VarCounter(usize);
+//!
+//! impl<'ast> Visit<'ast> for VarCounter {
+//!     fn visit_typed_expr_var(
+//!         &mut self,
+//!         _location: &'ast SrcSpan,
+//!         _constructor: &'ast ValueConstructor,
+//!         _name: &'ast EcoString,
+//!     ) {
+//!         self.0 += 1;
+//!     }
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use ecow::EcoString;
+
+use crate::type_::{ModuleValueConstructor, Type, ValueConstructor};
+
+use super::{
+    BitArrayOption, CallArg, ClauseGuard, Constant, Definition, Pattern, SrcSpan, Statement,
+    TypeAst, TypedArg, TypedAssignment, TypedClause, TypedClauseGuard, TypedConstant,
+    TypedCustomType, TypedDefinition, TypedExpr, TypedExprBitArraySegment, TypedFunction,
+    TypedModule, TypedModuleConstant, TypedPattern, TypedRecordUpdateArg, TypedStatement,
+    TypedTypeAlias,
+};
+
+pub trait Visit<'ast> {
+    fn visit_typed_module(&mut self, module: &'ast TypedModule) {
+        visit_typed_module(self, module);
+    }
+
+    fn visit_typed_definition(&mut self, definition: &'ast TypedDefinition) {
+        visit_typed_definition(self, definition);
+    }
+
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        visit_typed_function(self, function);
+    }
+
+    fn visit_typed_module_constant(&mut self, constant: &'ast TypedModuleConstant) {
+        visit_typed_module_constant(self, constant);
+    }
+
+    fn visit_typed_custom_type(&mut self, custom_type: &'ast TypedCustomType) {
+        visit_typed_custom_type(self, custom_type);
+    }
+
+    fn visit_typed_type_alias(&mut self, type_alias: &'ast TypedTypeAlias) {
+        visit_typed_type_alias(self, type_alias);
+    }
+
+    fn visit_typed_arg(&mut self, arg: &'ast TypedArg) {
+        visit_typed_arg(self, arg);
+    }
+
+    fn visit_typed_statement(&mut self, statement: &'ast TypedStatement) {
+        visit_typed_statement(self, statement);
+    }
+
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        visit_typed_assignment(self, assignment);
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        visit_typed_expr(self, expr);
+    }
+
+    fn visit_typed_expr_var(
+        &mut self,
+        location: &'ast SrcSpan,
+        constructor: &'ast ValueConstructor,
+        name: &'ast EcoString,
+    ) {
+        visit_typed_expr_var(self, location, constructor, name);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_typed_expr_module_select(
+        &mut self,
+        location: &'ast SrcSpan,
+        typ: &'ast Arc<Type>,
+        label: &'ast EcoString,
+        module_name: &'ast EcoString,
+        module_alias: &'ast EcoString,
+        constructor: &'ast ModuleValueConstructor,
+    ) {
+        visit_typed_expr_module_select(
+            self,
+            location,
+            typ,
+            label,
+            module_name,
+            module_alias,
+            constructor,
+        );
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'ast SrcSpan,
+        typ: &'ast Arc<Type>,
+        is_capture: &'ast bool,
+        args: &'ast [TypedArg],
+        body: &'ast [TypedStatement],
+        return_annotation: &'ast Option<TypeAst>,
+    ) {
+        visit_typed_expr_fn(
+            self,
+            location,
+            typ,
+            is_capture,
+            args,
+            body,
+            return_annotation,
+        );
+    }
+
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'ast SrcSpan,
+        typ: &'ast Arc<Type>,
+        fun: &'ast TypedExpr,
+        args: &'ast [CallArg<TypedExpr>],
+    ) {
+        visit_typed_expr_call(self, location, typ, fun, args);
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'ast TypedClause) {
+        visit_typed_clause(self, clause);
+    }
+
+    fn visit_typed_clause_guard(&mut self, guard: &'ast TypedClauseGuard) {
+        visit_typed_clause_guard(self, guard);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        visit_typed_pattern(self, pattern);
+    }
+
+    fn visit_typed_constant(&mut self, constant: &'ast TypedConstant) {
+        visit_typed_constant(self, constant);
+    }
+
+    fn visit_type_ast(&mut self, type_ast: &'ast TypeAst) {
+        visit_type_ast(self, type_ast);
+    }
+}
+
+pub fn visit_typed_module<'a, V>(v: &mut V, module: &'a TypedModule)
+where
+    V: Visit<'a> + ?Sized,
+{
+    for definition in &module.definitions {
+        v.visit_typed_definition(definition);
+    }
+}
+
+pub fn visit_typed_definition<'a, V>(v: &mut V, definition: &'a TypedDefinition)
+where
+    V: Visit<'a> + ?Sized,
+{
+    match definition {
+        Definition::Function(function) => v.visit_typed_function(function),
+        Definition::ModuleConstant(constant) => v.visit_typed_module_constant(constant),
+        Definition::CustomType(custom_type) => v.visit_typed_custom_type(custom_type),
+        Definition::TypeAlias(type_alias) => v.visit_typed_type_alias(type_alias),
+        Definition::Import(_) => {}
+    }
+}
+
+pub fn visit_typed_function<'a, V>(v: &mut V, function: &'a TypedFunction)
+where
+    V: Visit<'a> + ?Sized,
+{
+    for arg in &function.arguments {
+        v.visit_typed_arg(arg);
+    }
+    if let Some(annotation) = &function.return_annotation {
+        v.visit_type_ast(annotation);
+    }
+    for statement in &function.body {
+        v.visit_typed_statement(statement);
+    }
+}
+
+pub fn visit_typed_module_constant<'a, V>(v: &mut V, constant: &'a TypedModuleConstant)
+where
+    V: Visit<'a> + ?Sized,
+{
+    if let Some(annotation) = &constant.annotation {
+        v.visit_type_ast(annotation);
+    }
+    v.visit_typed_constant(&constant.value);
+}
+
+pub fn visit_typed_custom_type<'a, V>(v: &mut V, custom_type: &'a TypedCustomType)
+where
+    V: Visit<'a> + ?Sized,
+{
+    for constructor in &custom_type.constructors {
+        for argument in &constructor.arguments {
+            v.visit_type_ast(&argument.ast);
+        }
+    }
+}
+
+pub fn visit_typed_type_alias<'a, V>(v: &mut V, type_alias: &'a TypedTypeAlias)
+where
+    V: Visit<'a> + ?Sized,
+{
+    v.visit_type_ast(&type_alias.type_ast);
+}
+
+pub fn visit_typed_arg<'a, V>(v: &mut V, arg: &'a TypedArg)
+where
+    V: Visit<'a> + ?Sized,
+{
+    if let Some(annotation) = &arg.annotation {
+        v.visit_type_ast(annotation);
+    }
+}
+
+pub fn visit_typed_statement<'a, V>(v: &mut V, statement: &'a TypedStatement)
+where
+    V: Visit<'a> + ?Sized,
+{
+    match statement {
+        Statement::Expression(expr) => v.visit_typed_expr(expr),
+        Statement::Assignment(assignment) => v.visit_typed_assignment(assignment),
+        // `use` expressions are desugared into function calls before type
+        // checking, so they never appear in the typed AST.
+        Statement::Use(_) => {}
+    }
+}
+
+pub fn visit_typed_assignment<'a, V>(v: &mut V, assignment: &'a TypedAssignment)
+where
+    V: Visit<'a> + ?Sized,
+{
+    v.visit_typed_expr(&assignment.value);
+    v.visit_typed_pattern(&assignment.pattern);
+    if let Some(annotation) = &assignment.annotation {
+        v.visit_type_ast(annotation);
+    }
+}
+
+pub fn visit_typed_expr<'a, V>(v: &mut V, expr: &'a TypedExpr)
+where
+    V: Visit<'a> + ?Sized,
+{
+    match expr {
+        TypedExpr::Int { .. }
+        | TypedExpr::Float { .. }
+        | TypedExpr::String { .. }
+        | TypedExpr::Invalid { .. } => {}
+
+        TypedExpr::Block { statements, .. } => {
+            for statement in statements {
+                v.visit_typed_statement(statement);
+            }
+        }
+
+        TypedExpr::Pipeline {
+            assignments,
+            finally,
+            ..
+        } => {
+            for assignment in assignments {
+                v.visit_typed_assignment(assignment);
+            }
+            v.visit_typed_expr(finally);
+        }
+
+        TypedExpr::Var {
+            location,
+            constructor,
+            name,
+        } => v.visit_typed_expr_var(location, constructor, name),
+
+        TypedExpr::Fn {
+            location,
+            typ,
+            is_capture,
+            args,
+            body,
+            return_annotation,
+        } => v.visit_typed_expr_fn(location, typ, is_capture, args, body, return_annotation),
+
+        TypedExpr::List { elements, tail, .. } => {
+            for element in elements {
+                v.visit_typed_expr(element);
+            }
+            if let Some(tail) = tail {
+                v.visit_typed_expr(tail);
+            }
+        }
+
+        TypedExpr::Call {
+            location,
+            typ,
+            fun,
+            args,
+        } => v.visit_typed_expr_call(location, typ, fun, args),
+
+        TypedExpr::BinOp { left, right, .. } => {
+            v.visit_typed_expr(left);
+            v.visit_typed_expr(right);
+        }
+
+        TypedExpr::Case {
+            subjects, clauses, ..
+        } => {
+            for subject in subjects {
+                v.visit_typed_expr(subject);
+            }
+            for clause in clauses {
+                v.visit_typed_clause(clause);
+            }
+        }
+
+        TypedExpr::RecordAccess { record, .. } => v.visit_typed_expr(record),
+
+        TypedExpr::ModuleSelect {
+            location,
+            typ,
+            label,
+            module_name,
+            module_alias,
+            constructor,
+        } => v.visit_typed_expr_module_select(
+            location,
+            typ,
+            label,
+            module_name,
+            module_alias,
+            constructor,
+        ),
+
+        TypedExpr::Tuple { elems, .. } => {
+            for elem in elems {
+                v.visit_typed_expr(elem);
+            }
+        }
+
+        TypedExpr::TupleIndex { tuple, .. } => v.visit_typed_expr(tuple),
+
+        TypedExpr::Todo { message, .. } | TypedExpr::Panic { message, .. } => {
+            if let Some(message) = message {
+                v.visit_typed_expr(message);
+            }
+        }
+
+        TypedExpr::BitArray { segments, .. } => {
+            for segment in segments {
+                visit_typed_expr_bit_array_segment(v, segment);
+            }
+        }
+
+        TypedExpr::RecordUpdate { spread, args, .. } => {
+            v.visit_typed_expr(spread);
+            for TypedRecordUpdateArg { value, .. } in args {
+                v.visit_typed_expr(value);
+            }
+        }
+
+        TypedExpr::NegateBool { value, .. }
+        | TypedExpr::NegateInt { value, .. }
+        | TypedExpr::Echo {
+            expression: value, ..
+        } => v.visit_typed_expr(value),
+    }
+}
+
+pub fn visit_typed_expr_var<'a, V>(
+    _v: &mut V,
+    _location: &'a SrcSpan,
+    _constructor: &'a ValueConstructor,
+    _name: &'a EcoString,
+) where
+    V: Visit<'a> + ?Sized,
+{
+}
+
+pub fn visit_typed_expr_module_select<'a, V>(
+    _v: &mut V,
+    _location: &'a SrcSpan,
+    _typ: &'a Arc<Type>,
+    _label: &'a EcoString,
+    _module_name: &'a EcoString,
+    _module_alias: &'a EcoString,
+    _constructor: &'a ModuleValueConstructor,
+) where
+    V: Visit<'a> + ?Sized,
+{
+}
+
+pub fn visit_typed_expr_fn<'a, V>(
+    v: &mut V,
+    _location: &'a SrcSpan,
+    _typ: &'a Arc<Type>,
+    _is_capture: &'a bool,
+    args: &'a [TypedArg],
+    body: &'a [TypedStatement],
+    return_annotation: &'a Option<TypeAst>,
+) where
+    V: Visit<'a> + ?Sized,
+{
+    for arg in args {
+        v.visit_typed_arg(arg);
+    }
+    if let Some(annotation) = return_annotation {
+        v.visit_type_ast(annotation);
+    }
+    for statement in body {
+        v.visit_typed_statement(statement);
+    }
+}
+
+pub fn visit_typed_expr_call<'a, V>(
+    v: &mut V,
+    _location: &'a SrcSpan,
+    _typ: &'a Arc<Type>,
+    fun: &'a TypedExpr,
+    args: &'a [CallArg<TypedExpr>],
+) where
+    V: Visit<'a> + ?Sized,
+{
+    v.visit_typed_expr(fun);
+    for arg in args {
+        v.visit_typed_expr(&arg.value);
+    }
+}
+
+fn visit_typed_expr_bit_array_segment<'a, V>(v: &mut V, segment: &'a TypedExprBitArraySegment)
+where
+    V: Visit<'a> + ?Sized,
+{
+    v.visit_typed_expr(&segment.value);
+    for option in &segment.options {
+        if let BitArrayOption::Size { value, .. } = option {
+            v.visit_typed_expr(value);
+        }
+    }
+}
+
+pub fn visit_typed_clause<'a, V>(v: &mut V, clause: &'a TypedClause)
+where
+    V: Visit<'a> + ?Sized,
+{
+    for pattern in std::iter::once(&clause.pattern).chain(&clause.alternative_patterns) {
+        for pattern in pattern {
+            v.visit_typed_pattern(pattern);
+        }
+    }
+    if let Some(guard) = &clause.guard {
+        v.visit_typed_clause_guard(guard);
+    }
+    v.visit_typed_expr(&clause.then);
+}
+
+pub fn visit_typed_clause_guard<'a, V>(v: &mut V, guard: &'a TypedClauseGuard)
+where
+    V: Visit<'a> + ?Sized,
+{
+    match guard {
+        ClauseGuard::Equals { left, right, .. }
+        | ClauseGuard::NotEquals { left, right, .. }
+        | ClauseGuard::GtInt { left, right, .. }
+        | ClauseGuard::GtEqInt { left, right, .. }
+        | ClauseGuard::LtInt { left, right, .. }
+        | ClauseGuard::LtEqInt { left, right, .. }
+        | ClauseGuard::GtFloat { left, right, .. }
+        | ClauseGuard::GtEqFloat { left, right, .. }
+        | ClauseGuard::LtFloat { left, right, .. }
+        | ClauseGuard::LtEqFloat { left, right, .. }
+        | ClauseGuard::Or { left, right, .. }
+        | ClauseGuard::And { left, right, .. } => {
+            v.visit_typed_clause_guard(left);
+            v.visit_typed_clause_guard(right);
+        }
+
+        ClauseGuard::Not { expression, .. } => v.visit_typed_clause_guard(expression),
+
+        ClauseGuard::Var { .. } => {}
+
+        ClauseGuard::TupleIndex { tuple, .. } => v.visit_typed_clause_guard(tuple),
+
+        ClauseGuard::FieldAccess { container, .. } => v.visit_typed_clause_guard(container),
+
+        ClauseGuard::ModuleSelect { literal, .. } => v.visit_typed_constant(literal),
+
+        ClauseGuard::Constant(constant) => v.visit_typed_constant(constant),
+    }
+}
+
+pub fn visit_typed_pattern<'a, V>(v: &mut V, pattern: &'a TypedPattern)
+where
+    V: Visit<'a> + ?Sized,
+{
+    match pattern {
+        Pattern::Int { .. }
+        | Pattern::Float { .. }
+        | Pattern::String { .. }
+        | Pattern::Variable { .. }
+        | Pattern::VarUsage { .. }
+        | Pattern::Constant { .. }
+        | Pattern::Discard { .. }
+        | Pattern::StringPrefix { .. } => {}
+
+        Pattern::Assign { pattern, .. } => v.visit_typed_pattern(pattern),
+
+        Pattern::List { elements, tail, .. } => {
+            for element in elements {
+                v.visit_typed_pattern(element);
+            }
+            if let Some(tail) = tail {
+                v.visit_typed_pattern(tail);
+            }
+        }
+
+        Pattern::Constructor { arguments, .. } => {
+            for argument in arguments {
+                v.visit_typed_pattern(&argument.value);
+            }
+        }
+
+        Pattern::Tuple { elems, .. } => {
+            for elem in elems {
+                v.visit_typed_pattern(elem);
+            }
+        }
+
+        Pattern::BitArray { segments, .. } => {
+            for segment in segments {
+                v.visit_typed_pattern(&segment.value);
+                for option in &segment.options {
+                    if let BitArrayOption::Size { value, .. } = option {
+                        v.visit_typed_pattern(value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn visit_typed_constant<'a, V>(v: &mut V, constant: &'a TypedConstant)
+where
+    V: Visit<'a> + ?Sized,
+{
+    match constant {
+        Constant::Int { .. }
+        | Constant::Float { .. }
+        | Constant::String { .. }
+        | Constant::Var { .. } => {}
+
+        Constant::Tuple { elements, .. } | Constant::List { elements, .. } => {
+            for element in elements {
+                v.visit_typed_constant(element);
+            }
+        }
+
+        Constant::Record { args, .. } => {
+            for arg in args {
+                v.visit_typed_constant(&arg.value);
+            }
+        }
+
+        Constant::BitArray { segments, .. } => {
+            for segment in segments {
+                v.visit_typed_constant(&segment.value);
+                for option in &segment.options {
+                    if let BitArrayOption::Size { value, .. } = option {
+                        v.visit_typed_constant(value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn visit_type_ast<'a, V>(v: &mut V, type_ast: &'a TypeAst)
+where
+    V: Visit<'a> + ?Sized,
+{
+    match type_ast {
+        TypeAst::Constructor(constructor) => {
+            for argument in &constructor.arguments {
+                v.visit_type_ast(argument);
+            }
+        }
+
+        TypeAst::Fn(function) => {
+            for argument in &function.arguments {
+                v.visit_type_ast(argument);
+            }
+            v.visit_type_ast(&function.return_);
+        }
+
+        TypeAst::Tuple(tuple) => {
+            for element in &tuple.elems {
+                v.visit_type_ast(element);
+            }
+        }
+
+        TypeAst::Var(_) | TypeAst::Hole(_) => {}
+    }
+}
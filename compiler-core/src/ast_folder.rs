@@ -303,6 +303,11 @@ pub trait UntypedExprFolder: TypeAstFolder + UntypedConstantFolder + PatternFold
 
             UntypedExpr::Panic { location, message } => self.fold_panic(location, message),
 
+            UntypedExpr::Echo {
+                location,
+                expression,
+            } => self.fold_echo(location, expression),
+
             UntypedExpr::BitArray { location, segments } => self.fold_bit_array(location, segments),
 
             UntypedExpr::RecordUpdate {
@@ -317,6 +322,8 @@ pub trait UntypedExprFolder: TypeAstFolder + UntypedConstantFolder + PatternFold
             UntypedExpr::NegateInt { location, value } => self.fold_negate_int(location, value),
 
             UntypedExpr::Placeholder { location } => self.fold_placeholder(location),
+
+            UntypedExpr::Invalid { location } => self.fold_invalid(location),
         }
     }
 
@@ -329,7 +336,8 @@ pub trait UntypedExprFolder: TypeAstFolder + UntypedConstantFolder + PatternFold
             | UntypedExpr::String { .. }
             | UntypedExpr::NegateInt { .. }
             | UntypedExpr::NegateBool { .. }
-            | UntypedExpr::Placeholder { .. } => e,
+            | UntypedExpr::Placeholder { .. }
+            | UntypedExpr::Invalid { .. } => e,
 
             UntypedExpr::Todo {
                 kind,
@@ -346,6 +354,14 @@ pub trait UntypedExprFolder: TypeAstFolder + UntypedConstantFolder + PatternFold
                 message: message.map(|msg_expr| Box::new(self.fold_expr(*msg_expr))),
             },
 
+            UntypedExpr::Echo {
+                location,
+                expression,
+            } => UntypedExpr::Echo {
+                location,
+                expression: expression.map(|expression| Box::new(self.fold_expr(*expression))),
+            },
+
             UntypedExpr::Block {
                 location,
                 statements,
@@ -770,6 +786,17 @@ pub trait UntypedExprFolder: TypeAstFolder + UntypedConstantFolder + PatternFold
         UntypedExpr::Panic { location, message }
     }
 
+    fn fold_echo(
+        &mut self,
+        location: SrcSpan,
+        expression: Option<Box<UntypedExpr>>,
+    ) -> UntypedExpr {
+        UntypedExpr::Echo {
+            location,
+            expression,
+        }
+    }
+
     fn fold_bit_array(
         &mut self,
         location: SrcSpan,
@@ -805,6 +832,10 @@ pub trait UntypedExprFolder: TypeAstFolder + UntypedConstantFolder + PatternFold
         UntypedExpr::Placeholder { location }
     }
 
+    fn fold_invalid(&mut self, location: SrcSpan) -> UntypedExpr {
+        UntypedExpr::Invalid { location }
+    }
+
     fn fold_assignment(&mut self, assignment: UntypedAssignment) -> UntypedAssignment {
         assignment
     }
@@ -1030,6 +1061,14 @@ pub trait PatternFolder {
                 type_: (),
             } => self.fold_pattern_var_usage(location, name),
 
+            Pattern::Constant {
+                location,
+                module,
+                name,
+                constructor: _,
+                type_: (),
+            } => self.fold_pattern_constant(location, module, name),
+
             Pattern::Assign {
                 name,
                 location,
@@ -1112,6 +1151,21 @@ pub trait PatternFolder {
         }
     }
 
+    fn fold_pattern_constant(
+        &mut self,
+        location: SrcSpan,
+        module: EcoString,
+        name: EcoString,
+    ) -> UntypedPattern {
+        Pattern::Constant {
+            location,
+            module,
+            name,
+            constructor: None,
+            type_: (),
+        }
+    }
+
     fn fold_pattern_assign(
         &mut self,
         name: EcoString,
@@ -1210,6 +1264,7 @@ pub trait PatternFolder {
             | Pattern::String { .. }
             | Pattern::Discard { .. }
             | Pattern::VarUsage { .. }
+            | Pattern::Constant { .. }
             | Pattern::StringPrefix { .. } => m,
 
             Pattern::Assign {
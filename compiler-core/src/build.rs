@@ -1,5 +1,9 @@
 #![allow(warnings)]
 
+mod cancellation;
+mod dead_code;
+pub mod doc_tests;
+mod echo;
 mod elixir_libraries;
 mod module_loader;
 mod native_file_copier;
@@ -7,14 +11,19 @@ pub mod package_compiler;
 mod package_loader;
 mod project_compiler;
 mod telemetry;
+pub mod timings;
+mod todos;
 
 #[cfg(test)]
 mod tests;
 
+pub use self::cancellation::CancellationToken;
 pub use self::package_compiler::PackageCompiler;
 pub use self::package_loader::StaleTracker;
 pub use self::project_compiler::{Built, Options, ProjectCompiler};
 pub use self::telemetry::{NullTelemetry, Telemetry};
+pub use self::timings::{Phase, Timings};
+pub use self::todos::{Todo, WorkItem, WorkItemKind};
 
 use crate::ast::{
     CustomType, DefinitionLocation, TypedArg, TypedDefinition, TypedExpr, TypedFunction,
@@ -34,7 +43,7 @@ use ecow::EcoString;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
-use std::{collections::HashMap, ffi::OsString, fs::DirEntry, iter::Peekable, process};
+use std::{collections::HashMap, ffi::OsString, fs::DirEntry, iter::Peekable, process, sync::Arc};
 use strum::{Display, EnumIter, EnumString, EnumVariantNames, VariantNames};
 
 #[derive(
@@ -124,6 +133,7 @@ impl Default for Runtime {
 pub enum TargetCodegenConfiguration {
     JavaScript {
         emit_typescript_definitions: bool,
+        emit_source_maps: bool,
         prelude_location: Utf8PathBuf,
     },
     Erlang {
@@ -296,6 +306,16 @@ pub enum Located<'a> {
     ModuleStatement(&'a TypedDefinition),
     FunctionBody(&'a TypedFunction),
     Arg(&'a TypedArg),
+    /// The label of an argument given to a function or record constructor, or
+    /// of a field given in a record update.
+    Label {
+        label: &'a EcoString,
+        location: SrcSpan,
+        /// The type of the value given for the label.
+        type_: Arc<type_::Type>,
+        /// The call or record update the label is in.
+        of: &'a TypedExpr,
+    },
 }
 
 impl<'a> Located<'a> {
@@ -309,7 +329,7 @@ impl<'a> Located<'a> {
                 module: None,
                 span: statement.location(),
             }),
-            Self::Arg(_) => None,
+            Self::Arg(_) | Self::Label { .. } => None,
         }
     }
 }
@@ -0,0 +1,36 @@
+//! Cancellation of a compilation that is no longer wanted, such as one of
+//! code the language server has been told has been edited again since.
+//!
+//! A token is shared by the compiler and whoever may cancel its work. The
+//! compiler checks it between modules and stops with `Error::Cancelled` once
+//! it has been cancelled. Cancelling doesn't undo anything that has been done
+//! already.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Clears the cancellation, returning whether there was one.
+    pub fn reset(&self) -> bool {
+        self.cancelled.swap(false, Ordering::SeqCst)
+    }
+}
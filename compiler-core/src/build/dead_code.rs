@@ -0,0 +1,540 @@
+//! Detection of public definitions that are never used by an application.
+//!
+//! Libraries are expected to expose public definitions that they don't use
+//! themselves, but an application is only ever entered through its `main`
+//! functions. Any public function, constant, type, or constructor that can't
+//! be reached from one of them is dead code.
+//!
+//! The internal modules of a library are not part of its API, so their public
+//! definitions are dead code if no other module of the library, or its
+//! tests, uses them.
+//!
+//! This works by walking the typed AST of every `src` module of the package,
+//! starting from the `main` functions and following every reference to
+//! another definition of the package. Anything that was not reached is
+//! reported as unused.
+//!
+//! Definitions that are only referenced from FFI code can't be seen by this
+//! analysis and so will be reported as unused.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use ecow::EcoString;
+
+use crate::{
+    analyse::Inferred,
+    ast::{
+        visit::{self, Visit},
+        Constant, Definition, Pattern, SrcSpan, TypedArg, TypedConstant, TypedCustomType,
+        TypedExpr, TypedFunction, TypedModuleConstant, TypedPattern, TypedRecordConstructor,
+    },
+    type_::{
+        error::PublicDefinitionKind, ModuleValueConstructor, Type, TypeVar, ValueConstructor,
+        ValueConstructorVariant,
+    },
+    Warning,
+};
+
+use super::{Module, Origin, Target};
+
+/// Returns a warning for each public definition of the package's `src`
+/// modules that can't be reached from any public `main` function.
+///
+/// If the package has no `main` function then it is not an application, and
+/// only the public definitions of its internal modules that are not used by
+/// any other module are reported.
+///
+pub(crate) fn unused_public_definitions(modules: &[Module], target: Target) -> Vec<Warning> {
+    let src_modules = modules
+        .iter()
+        .filter(|module| module.origin == Origin::Src)
+        .collect::<Vec<_>>();
+    let definitions = Definitions::new(&src_modules, target);
+
+    let entry_points = src_modules
+        .iter()
+        .filter(|module| {
+            matches!(
+                definitions.values.get(&(module.name.clone(), "main".into())),
+                Some(Value::Function(function)) if !function.publicity.is_private()
+            )
+        })
+        .map(|module| Entity::Value(module.name.clone(), "main".into()))
+        .collect::<Vec<_>>();
+
+    if entry_points.is_empty() {
+        return unused_internal_definitions(modules, target);
+    }
+
+    let reached = definitions.reachable_from(entry_points);
+    unused_definitions(
+        &src_modules,
+        &reached,
+        |location, name, kind, definition_location| crate::type_::Warning::UnusedPublicDefinition {
+            location,
+            name,
+            kind,
+            definition_location,
+        },
+    )
+}
+
+/// Returns a warning for each public definition of the package's internal
+/// modules that is not used by any of its other modules, including its tests.
+///
+/// Everything defined by the modules that are not internal is part of the
+/// API of the package, so it could be used by other packages.
+///
+fn unused_internal_definitions(modules: &[Module], target: Target) -> Vec<Warning> {
+    let modules = modules.iter().collect::<Vec<_>>();
+    let definitions = Definitions::new(&modules, target);
+
+    let is_internal =
+        |module: &Module| module.origin == Origin::Src && module.ast.type_info.is_internal;
+
+    let entry_points = modules
+        .iter()
+        .filter(|module| !is_internal(module))
+        .flat_map(|module| module_entities(module))
+        .collect::<Vec<_>>();
+
+    let reached = definitions.reachable_from(entry_points);
+    let internal_modules = modules
+        .into_iter()
+        .filter(|module| is_internal(module))
+        .collect::<Vec<_>>();
+    unused_definitions(
+        &internal_modules,
+        &reached,
+        |location, name, kind, definition_location| {
+            crate::type_::Warning::UnusedInternalDefinition {
+                location,
+                name,
+                kind,
+                definition_location,
+            }
+        },
+    )
+}
+
+/// Every function, constant, and constructor defined by the module.
+///
+fn module_entities(module: &Module) -> Vec<Entity> {
+    let mut entities = vec![];
+    for definition in &module.ast.definitions {
+        match definition {
+            Definition::Function(function) => {
+                entities.push(Entity::Value(module.name.clone(), function.name.clone()))
+            }
+            Definition::ModuleConstant(constant) => {
+                entities.push(Entity::Value(module.name.clone(), constant.name.clone()))
+            }
+            Definition::CustomType(custom_type) => {
+                entities.push(Entity::Type(module.name.clone(), custom_type.name.clone()));
+                entities.extend(custom_type.constructors.iter().map(|constructor| {
+                    Entity::Constructor(module.name.clone(), constructor.name.clone())
+                }));
+            }
+            Definition::TypeAlias(_) | Definition::Import(_) => (),
+        }
+    }
+    entities
+}
+
+/// Returns a warning for each public definition of the modules that was not
+/// reached.
+///
+fn unused_definitions(
+    modules: &[&Module],
+    reached: &HashSet<Entity>,
+    make_warning: impl Fn(SrcSpan, EcoString, PublicDefinitionKind, SrcSpan) -> crate::type_::Warning,
+) -> Vec<Warning> {
+    let mut warnings = vec![];
+    for module in modules {
+        let mut warn = |location: SrcSpan,
+                        name: &EcoString,
+                        kind: PublicDefinitionKind,
+                        definition_location: SrcSpan| {
+            warnings.push(Warning::Type {
+                path: module.input_path.clone(),
+                src: module.code.clone(),
+                warning: make_warning(location, name.clone(), kind, definition_location),
+            })
+        };
+        let is_reached = |entity: Entity| reached.contains(&entity);
+
+        for definition in &module.ast.definitions {
+            match definition {
+                Definition::Function(function)
+                    if !function.publicity.is_private()
+                        && !is_reached(Entity::Value(
+                            module.name.clone(),
+                            function.name.clone(),
+                        )) =>
+                {
+                    warn(
+                        function.location,
+                        &function.name,
+                        PublicDefinitionKind::Function,
+                        function.full_location(),
+                    )
+                }
+
+                Definition::ModuleConstant(constant)
+                    if !constant.publicity.is_private()
+                        && !is_reached(Entity::Value(
+                            module.name.clone(),
+                            constant.name.clone(),
+                        )) =>
+                {
+                    warn(
+                        constant.location,
+                        &constant.name,
+                        PublicDefinitionKind::Constant,
+                        SrcSpan::new(constant.location.start, constant.value.location().end),
+                    )
+                }
+
+                Definition::CustomType(custom_type) if !custom_type.publicity.is_private() => {
+                    // If the type itself is never used there's no point in
+                    // also reporting each of its constructors.
+                    if !is_reached(Entity::Type(module.name.clone(), custom_type.name.clone())) {
+                        warn(
+                            custom_type.location,
+                            &custom_type.name,
+                            PublicDefinitionKind::Type,
+                            custom_type.full_location(),
+                        );
+                        continue;
+                    }
+
+                    for constructor in &custom_type.constructors {
+                        let entity =
+                            Entity::Constructor(module.name.clone(), constructor.name.clone());
+                        if !is_reached(entity) {
+                            warn(
+                                constructor.location,
+                                &constructor.name,
+                                PublicDefinitionKind::Constructor,
+                                constructor.location,
+                            );
+                        }
+                    }
+                }
+
+                Definition::Function(_)
+                | Definition::ModuleConstant(_)
+                | Definition::CustomType(_)
+                | Definition::TypeAlias(_)
+                | Definition::Import(_) => (),
+            }
+        }
+    }
+    warnings
+}
+
+/// A definition of the package, identified by the name of the module it is
+/// defined in and its own name.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Entity {
+    Value(EcoString, EcoString),
+    Type(EcoString, EcoString),
+    Constructor(EcoString, EcoString),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Value<'a> {
+    Function(&'a TypedFunction),
+    Constant(&'a TypedModuleConstant),
+}
+
+#[derive(Debug, Default)]
+struct Definitions<'a> {
+    values: HashMap<(EcoString, EcoString), Value<'a>>,
+    constructors:
+        HashMap<(EcoString, EcoString), (&'a TypedCustomType, &'a TypedRecordConstructor)>,
+    /// Module constants referenced by a variable only record where they are
+    /// defined, not their name.
+    constants_by_location: HashMap<(EcoString, u32), EcoString>,
+    /// Functions referenced by a variable record the module and name of
+    /// their implementation for the current target, which is not the Gleam
+    /// definition for external functions.
+    externals: HashMap<(EcoString, EcoString), Vec<(EcoString, EcoString)>>,
+}
+
+impl<'a> Definitions<'a> {
+    fn new(modules: &[&'a Module], target: Target) -> Self {
+        let mut definitions = Self::default();
+        for module in modules {
+            let module_name = &module.name;
+            for definition in &module.ast.definitions {
+                match definition {
+                    Definition::Function(function) => {
+                        let key = (module_name.clone(), function.name.clone());
+                        let external = match target {
+                            Target::Erlang => &function.external_erlang,
+                            Target::JavaScript => &function.external_javascript,
+                        };
+                        if let Some(external) = external {
+                            definitions
+                                .externals
+                                .entry(external.clone())
+                                .or_default()
+                                .push(key.clone());
+                        }
+                        let _ = definitions.values.insert(key, Value::Function(function));
+                    }
+
+                    Definition::ModuleConstant(constant) => {
+                        let key = (module_name.clone(), constant.name.clone());
+                        let _ = definitions.constants_by_location.insert(
+                            (module_name.clone(), constant.location.start),
+                            constant.name.clone(),
+                        );
+                        let _ = definitions.values.insert(key, Value::Constant(constant));
+                    }
+
+                    Definition::CustomType(custom_type) => {
+                        for constructor in &custom_type.constructors {
+                            let key = (module_name.clone(), constructor.name.clone());
+                            let _ = definitions
+                                .constructors
+                                .insert(key, (custom_type, constructor));
+                        }
+                    }
+
+                    Definition::TypeAlias(_) | Definition::Import(_) => (),
+                }
+            }
+        }
+        definitions
+    }
+
+    fn reachable_from(&self, entry_points: Vec<Entity>) -> HashSet<Entity> {
+        let mut reached = HashSet::new();
+        let mut references = References {
+            definitions: self,
+            found: entry_points,
+        };
+
+        while let Some(entity) = references.found.pop() {
+            if reached.contains(&entity) {
+                continue;
+            }
+
+            match &entity {
+                Entity::Value(module, name) => {
+                    match self.values.get(&(module.clone(), name.clone())) {
+                        Some(Value::Function(function)) => {
+                            references.visit_typed_function(function)
+                        }
+                        Some(Value::Constant(constant)) => {
+                            references.visit_typed_module_constant(constant)
+                        }
+                        None => (),
+                    }
+                }
+
+                Entity::Constructor(module, name) => {
+                    let key = (module.clone(), name.clone());
+                    if let Some((custom_type, constructor)) = self.constructors.get(&key) {
+                        references
+                            .found
+                            .push(Entity::Type(module.clone(), custom_type.name.clone()));
+                        for argument in &constructor.arguments {
+                            references.register_type(&argument.type_);
+                        }
+                    }
+                }
+
+                // Types don't reference any code, only other types which have
+                // already been registered when the type was used.
+                Entity::Type(..) => (),
+            }
+
+            let _ = reached.insert(entity);
+        }
+
+        reached
+    }
+
+    /// The Gleam functions that could be referenced by a variable with the
+    /// given implementation module and function name.
+    fn functions(&self, module: &EcoString, name: &EcoString) -> Vec<Entity> {
+        let key = (module.clone(), name.clone());
+        let functions = match self.externals.get(&key) {
+            Some(functions) => functions.clone(),
+            None => vec![key],
+        };
+        functions
+            .into_iter()
+            .map(|(module, name)| Entity::Value(module, name))
+            .collect()
+    }
+
+    fn constant(&self, module: &EcoString, location: SrcSpan) -> Option<Entity> {
+        let name = self
+            .constants_by_location
+            .get(&(module.clone(), location.start))?;
+        Some(Entity::Value(module.clone(), name.clone()))
+    }
+}
+
+/// Collects all the definitions referenced by the visited nodes.
+///
+struct References<'a, 'b> {
+    definitions: &'b Definitions<'a>,
+    found: Vec<Entity>,
+}
+
+impl References<'_, '_> {
+    fn register_type(&mut self, type_: &Type) {
+        match type_ {
+            Type::Named {
+                module, name, args, ..
+            } => {
+                self.found.push(Entity::Type(module.clone(), name.clone()));
+                for argument in args {
+                    self.register_type(argument);
+                }
+            }
+
+            Type::Fn { args, retrn } => {
+                for argument in args {
+                    self.register_type(argument);
+                }
+                self.register_type(retrn);
+            }
+
+            Type::Tuple { elems } => {
+                for element in elems {
+                    self.register_type(element);
+                }
+            }
+
+            Type::Var { type_ } => {
+                if let TypeVar::Link { type_ } = &*type_.borrow() {
+                    self.register_type(type_);
+                }
+            }
+        }
+    }
+
+    fn register_value_constructor(&mut self, constructor: &ValueConstructor) {
+        self.register_type(&constructor.type_);
+        match &constructor.variant {
+            ValueConstructorVariant::ModuleFn { module, name, .. } => {
+                let functions = self.definitions.functions(module, name);
+                self.found.extend(functions);
+            }
+
+            ValueConstructorVariant::ModuleConstant {
+                module, location, ..
+            } => {
+                if let Some(entity) = self.definitions.constant(module, *location) {
+                    self.found.push(entity);
+                }
+            }
+
+            ValueConstructorVariant::Record { module, name, .. } => {
+                self.found
+                    .push(Entity::Constructor(module.clone(), name.clone()));
+            }
+
+            ValueConstructorVariant::LocalVariable { .. }
+            | ValueConstructorVariant::LocalConstant { .. } => (),
+        }
+    }
+
+    fn register_constructor_of_type(&mut self, type_: &Arc<Type>, name: &EcoString) {
+        let type_ = type_.return_type().unwrap_or_else(|| type_.clone());
+        if let Some((module, _)) = type_.named_type_name() {
+            self.found.push(Entity::Constructor(module, name.clone()));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for References<'_, '_> {
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        self.register_type(&function.return_type);
+        visit::visit_typed_function(self, function);
+    }
+
+    fn visit_typed_arg(&mut self, arg: &'ast TypedArg) {
+        self.register_type(&arg.type_);
+        visit::visit_typed_arg(self, arg);
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        self.register_type(&expr.type_());
+        visit::visit_typed_expr(self, expr);
+    }
+
+    fn visit_typed_expr_var(
+        &mut self,
+        _location: &'ast SrcSpan,
+        constructor: &'ast ValueConstructor,
+        _name: &'ast EcoString,
+    ) {
+        self.register_value_constructor(constructor);
+    }
+
+    fn visit_typed_expr_module_select(
+        &mut self,
+        _location: &'ast SrcSpan,
+        typ: &'ast Arc<Type>,
+        label: &'ast EcoString,
+        module_name: &'ast EcoString,
+        _module_alias: &'ast EcoString,
+        constructor: &'ast ModuleValueConstructor,
+    ) {
+        self.register_type(typ);
+        let entity = match constructor {
+            ModuleValueConstructor::Record { name, .. } => {
+                Entity::Constructor(module_name.clone(), name.clone())
+            }
+            ModuleValueConstructor::Fn { .. } | ModuleValueConstructor::Constant { .. } => {
+                Entity::Value(module_name.clone(), label.clone())
+            }
+        };
+        self.found.push(entity);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        if let Pattern::Constructor {
+            type_,
+            constructor: Inferred::Known(constructor),
+            ..
+        } = pattern
+        {
+            self.register_type(type_);
+            self.register_constructor_of_type(type_, &constructor.name);
+        }
+        if let Pattern::Constant {
+            constructor: Some(constructor),
+            ..
+        } = pattern
+        {
+            self.register_value_constructor(constructor);
+        }
+        visit::visit_typed_pattern(self, pattern);
+    }
+
+    fn visit_typed_constant(&mut self, constant: &'ast TypedConstant) {
+        self.register_type(&constant.type_());
+        match constant {
+            Constant::Record { typ, tag, .. } => self.register_constructor_of_type(typ, tag),
+            Constant::Var {
+                constructor: Some(constructor),
+                ..
+            } => self.register_value_constructor(constructor),
+            _ => (),
+        }
+        visit::visit_typed_constant(self, constant);
+    }
+}
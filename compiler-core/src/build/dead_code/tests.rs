@@ -0,0 +1,293 @@
+use crate::{
+    build::{tests::compile_test_package, Mode},
+    config::{PackageConfig, WarningsConfig},
+    io::{memory::InMemoryFileSystem, FileSystemWriter},
+    type_,
+    warning::WarningEmitter,
+    Warning,
+};
+use camino::Utf8PathBuf;
+use itertools::Itertools;
+
+fn compile(modules: Vec<(&str, &str)>) -> String {
+    let fs = InMemoryFileSystem::new();
+    for (name, src) in modules {
+        let path = match name.strip_prefix("test/") {
+            Some(name) => format!("/test/{name}.gleam"),
+            None => format!("/src/{name}.gleam"),
+        };
+        fs.write(&Utf8PathBuf::from(path), src)
+            .expect("should write module");
+    }
+
+    let mut config = PackageConfig::default();
+    config.name = "app".into();
+    config.warnings = WarningsConfig {
+        shadowing: false,
+        dead_code: true,
+        ..Default::default()
+    };
+
+    let (warnings, emitted) = WarningEmitter::vector();
+    let _ = compile_test_package(config, Mode::Dev, fs, &warnings, |compiler| {
+        compiler.detect_dead_code = true;
+    })
+    .expect("should compile");
+
+    emitted
+        .take()
+        .iter()
+        .filter(|warning| {
+            matches!(
+                warning,
+                Warning::Type {
+                    warning: type_::Warning::UnusedPublicDefinition { .. }
+                        | type_::Warning::UnusedInternalDefinition { .. },
+                    ..
+                }
+            )
+        })
+        .map(|warning| warning.to_pretty_string())
+        .sorted()
+        .join("\n")
+}
+
+macro_rules! assert_dead_code {
+    ($($name:expr => $src:expr),+ $(,)?) => {
+        let output = compile(vec![$(($name, $src)),+]);
+        insta::assert_snapshot!(insta::internals::AutoName, output);
+    };
+}
+
+macro_rules! assert_no_dead_code {
+    ($($name:expr => $src:expr),+ $(,)?) => {
+        let output = compile(vec![$(($name, $src)),+]);
+        assert_eq!(output, "");
+    };
+}
+
+#[test]
+fn unused_public_function() {
+    assert_dead_code!(
+        "app" => "
+pub fn main() {
+  Nil
+}
+
+pub fn unused() {
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn function_used_from_main_is_not_reported() {
+    assert_no_dead_code!(
+        "app" => "
+import app/other
+
+pub fn main() {
+  other.used()
+}
+",
+        "app/other" => "
+pub fn used() {
+  helper()
+}
+
+pub fn helper() {
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn function_used_only_by_unused_function_is_reported() {
+    assert_dead_code!(
+        "app" => "
+pub fn main() {
+  Nil
+}
+",
+        "app/other" => "
+pub fn unused() {
+  also_unused()
+}
+
+pub fn also_unused() {
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn unused_type_and_constant() {
+    assert_dead_code!(
+        "app" => "
+pub fn main() {
+  Nil
+}
+
+pub type Unused {
+  Unused
+}
+
+pub const unused = 1
+"
+    );
+}
+
+#[test]
+fn unused_constructor_of_used_type() {
+    assert_dead_code!(
+        "app" => "
+import app/other.{Used}
+
+pub fn main() {
+  Used
+}
+",
+        "app/other" => "
+pub type Thing {
+  Used
+  Unused
+}
+"
+    );
+}
+
+#[test]
+fn constructors_used_in_patterns_and_constants_are_not_reported() {
+    assert_no_dead_code!(
+        "app" => "
+import app/other.{type Thing, Wibble, Wobble}
+
+const thing = Wibble
+
+pub fn main() {
+  check(thing)
+}
+
+fn check(thing: Thing) {
+  case thing {
+    Wobble -> 1
+    Wibble -> 2
+  }
+}
+",
+        "app/other" => "
+pub type Thing {
+  Wibble
+  Wobble
+}
+"
+    );
+}
+
+#[test]
+fn external_function_used_from_main_is_not_reported() {
+    assert_no_dead_code!(
+        "app" => "
+pub fn main() {
+  wibble()
+}
+
+@external(erlang, \"ffi\", \"wibble\")
+@external(javascript, \"./ffi.mjs\", \"wibble\")
+pub fn wibble() -> Nil
+"
+    );
+}
+
+#[test]
+fn libraries_without_main_are_not_checked() {
+    assert_no_dead_code!(
+        "app" => "
+pub fn unused() {
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn private_definitions_are_not_reported() {
+    assert_no_dead_code!(
+        "app" => "
+pub fn main() {
+  Nil
+}
+
+fn unused() {
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn unused_definitions_of_internal_modules_are_reported() {
+    assert_dead_code!(
+        "app" => "
+import app/internal
+
+pub fn wibble() {
+  internal.used()
+}
+",
+        "app/internal" => "
+pub fn used() {
+  Nil
+}
+
+pub fn unused() {
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn internal_definitions_used_by_tests_are_not_reported() {
+    assert_no_dead_code!(
+        "app/internal" => "
+pub fn tested() {
+  Nil
+}
+",
+        "test/app_test" => "
+import app/internal
+
+pub fn tested_test() {
+  internal.tested()
+}
+"
+    );
+}
+
+#[test]
+fn internal_definitions_used_by_private_functions_are_not_reported() {
+    assert_no_dead_code!(
+        "app" => "
+import app/internal
+
+pub fn wibble() {
+  helper()
+}
+
+fn helper() {
+  internal.Wobble(internal.value)
+}
+",
+        "app/internal" => "
+pub type Wobble {
+  Wobble(Int)
+}
+
+pub const value = 1
+"
+    );
+}
@@ -0,0 +1,307 @@
+//! Doc tests are the ```` ```gleam ```` examples in the documentation of a
+//! package's modules. When testing they are each compiled as a hidden test
+//! module that imports the names it uses from the documented module, and run
+//! so that examples which no longer compile or which crash are reported.
+//!
+//! The code of an example is kept on the same line and column it has in the
+//! documented module, so errors in it can be reported at the location of the
+//! documentation.
+
+use std::collections::HashSet;
+
+use ecow::EcoString;
+use itertools::Itertools;
+
+use crate::ast::{Definition, SrcSpan, UntypedModule};
+
+#[cfg(test)]
+mod tests;
+
+/// The prefix of the names of the modules generated for doc tests. The rest
+/// of the name is the documented module followed by `line_` and the line of
+/// the example, such as `gleam_doc_test/app/line_12`.
+pub const MODULE_PREFIX: &str = "gleam_doc_test/";
+
+/// The name of the function each doc test module runs its example in.
+pub const FUNCTION: &str = "doc_test";
+
+/// Returns true if the module was generated for a doc test.
+pub fn is_doc_test_module(name: &str) -> bool {
+    name.starts_with(MODULE_PREFIX)
+}
+
+/// The line of the documented module that the example a doc test module was
+/// generated from starts on.
+pub fn example_line(name: &str) -> Option<u32> {
+    name.strip_prefix(MODULE_PREFIX)?
+        .rsplit_once("/line_")?
+        .1
+        .parse()
+        .ok()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DocTest {
+    /// The name of the generated module.
+    pub name: EcoString,
+    pub code: EcoString,
+}
+
+/// A line of a documentation comment.
+#[derive(Debug, Clone, Copy)]
+struct CommentLine<'a> {
+    /// The line number, starting from 1.
+    line: u32,
+    /// The column the content of the comment starts at, after the slashes.
+    column: usize,
+    content: &'a str,
+}
+
+/// Generates a doc test module for each example in the documentation of a
+/// module. Nothing is generated if the module can't be parsed, as the error
+/// is reported when the module itself is compiled.
+pub(crate) fn doc_tests(module_name: &str, code: &str) -> Vec<DocTest> {
+    let Ok(parsed) = crate::parse::parse_module(code) else {
+        return vec![];
+    };
+    let importable = importable_names(&parsed.module);
+
+    [&parsed.extra.module_comments, &parsed.extra.doc_comments]
+        .into_iter()
+        .flat_map(|comments| examples(comment_lines(code, comments)))
+        .sorted_by_key(|example| example.first().map(|line| line.line))
+        .filter_map(|example| doc_test(module_name, &importable, &example))
+        .collect()
+}
+
+fn comment_lines<'a>(code: &'a str, comments: &[SrcSpan]) -> Vec<CommentLine<'a>> {
+    comments
+        .iter()
+        .filter_map(|comment| {
+            let start = comment.start as usize;
+            let before = code.get(..start)?;
+            let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+            Some(CommentLine {
+                line: before.matches('\n').count() as u32 + 1,
+                column: start - line_start,
+                content: code
+                    .get(start..comment.end as usize)?
+                    .trim_end_matches('\r'),
+            })
+        })
+        .collect()
+}
+
+/// Finds the ```` ```gleam ```` code blocks in consecutive comment lines. Each
+/// example includes its opening and closing fences.
+fn examples(lines: Vec<CommentLine<'_>>) -> Vec<Vec<CommentLine<'_>>> {
+    let mut examples = vec![];
+    let mut example: Option<Vec<CommentLine<'_>>> = None;
+    let mut previous_line = 0;
+
+    for line in lines {
+        let is_consecutive = line.line == previous_line + 1;
+        previous_line = line.line;
+        let fence = line.content.trim().strip_prefix("```");
+
+        match example.as_mut() {
+            // The comment ended before the code block was closed.
+            Some(_) if !is_consecutive => example = None,
+            Some(lines) if fence.is_some_and(|info| info.trim().is_empty()) => {
+                lines.push(line);
+                examples.extend(example.take());
+                continue;
+            }
+            Some(lines) => {
+                lines.push(line);
+                continue;
+            }
+            None => (),
+        }
+
+        if fence.is_some_and(|info| info.trim() == "gleam") {
+            example = Some(vec![line]);
+        }
+    }
+    examples
+}
+
+fn doc_test(
+    module_name: &str,
+    importable: &[(EcoString, bool)],
+    example: &[CommentLine<'_>],
+) -> Option<DocTest> {
+    let (opening, rest) = example.split_first()?;
+    let (closing, body) = rest.split_last()?;
+
+    // Imports at the start of the example are kept at the top level of the
+    // module, and the rest of the example becomes the body of the doc test
+    // function.
+    let mut import_lines = 0;
+    let mut depth = 0;
+    for (index, line) in body.iter().enumerate() {
+        let trimmed = line.content.trim();
+        if depth > 0 || trimmed.starts_with("import ") {
+            depth += trimmed.matches('{').count() as isize;
+            depth -= trimmed.matches('}').count() as isize;
+            import_lines = index + 1;
+        } else if !trimmed.is_empty() && !trimmed.starts_with("//") {
+            break;
+        }
+    }
+    let (imports, code) = body.split_at(import_lines);
+    let first_code = code
+        .iter()
+        .map(|line| line.content.trim())
+        .find(|line| !line.is_empty() && !line.starts_with("//"))?;
+    let is_definition = ["pub ", "fn ", "type ", "const ", "@"]
+        .iter()
+        .any(|start| first_code.starts_with(start));
+
+    let mut lines = vec![String::new(); closing.line as usize];
+    let mut set_line = |line: &CommentLine<'_>, text: String| {
+        if let Some(slot) = lines.get_mut(line.line as usize - 1) {
+            *slot = text;
+        }
+    };
+    for line in body {
+        set_line(line, format!("{}{}", " ".repeat(line.column), line.content));
+    }
+
+    let mut opening_text = implicit_import(module_name, importable, imports, code);
+    if is_definition {
+        set_line(closing, format!("pub fn {FUNCTION}() {{ Nil }}"));
+    } else {
+        let function = format!("pub fn {FUNCTION}() {{");
+        match imports.last() {
+            Some(last) if !last.content.contains("//") => set_line(
+                last,
+                format!("{}{} {function}", " ".repeat(last.column), last.content),
+            ),
+            Some(_) => {
+                let first = code.first()?;
+                set_line(
+                    first,
+                    format!("{function}{}{}", " ".repeat(first.column), first.content),
+                );
+            }
+            None => opening_text = format!("{opening_text} {function}"),
+        }
+        set_line(closing, "}".into());
+    }
+    set_line(opening, opening_text);
+
+    Some(DocTest {
+        name: format!("{MODULE_PREFIX}{module_name}/line_{}", opening.line).into(),
+        code: lines.join("\n").into(),
+    })
+}
+
+/// The import of the documented module, with the names the example uses
+/// from it. The example can leave this out by importing the module itself.
+fn implicit_import(
+    module_name: &str,
+    importable: &[(EcoString, bool)],
+    imports: &[CommentLine<'_>],
+    code: &[CommentLine<'_>],
+) -> String {
+    let imports_module = imports.iter().any(|line| {
+        line.content
+            .trim()
+            .strip_prefix("import ")
+            .and_then(|import| import.trim_start().strip_prefix(module_name))
+            .is_some_and(|rest| !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+    });
+    if imports_module {
+        return String::new();
+    }
+
+    let code = code.iter().map(|line| line.content).join("\n");
+    let words = words(&code);
+    let alias = module_name.rsplit('/').next().unwrap_or(module_name);
+    let uses_module = words
+        .iter()
+        .any(|word| word.text == alias && word.is_qualifier);
+    let names: HashSet<_> = words
+        .iter()
+        .filter(|word| !word.is_qualified)
+        .map(|word| word.text)
+        .collect();
+    let unqualified = importable
+        .iter()
+        .filter(|(name, _)| names.contains(name.as_str()))
+        .map(|(name, is_type)| match is_type {
+            true => format!("type {name}"),
+            false => name.to_string(),
+        })
+        .join(", ");
+
+    match (unqualified.is_empty(), uses_module) {
+        (true, false) => String::new(),
+        (true, true) => format!("import {module_name}"),
+        (false, _) => format!("import {module_name}.{{{unqualified}}}"),
+    }
+}
+
+struct Word<'a> {
+    text: &'a str,
+    /// Whether the word comes after a `.`, as in `module.word`.
+    is_qualified: bool,
+    /// Whether the word comes before a `.`, as in `word.value`.
+    is_qualifier: bool,
+}
+
+/// The words of some code: the names of the modules, types, and values it
+/// uses, along with the keywords and anything else made of letters.
+fn words(code: &str) -> Vec<Word<'_>> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut words = vec![];
+    let mut rest = code;
+    while let Some(start) = rest.find(is_word_char) {
+        let (before, word_start) = rest.split_at(start);
+        let end = word_start
+            .find(|c| !is_word_char(c))
+            .unwrap_or(word_start.len());
+        let (text, after) = word_start.split_at(end);
+        words.push(Word {
+            text,
+            is_qualified: before.ends_with('.'),
+            is_qualifier: after.starts_with('.'),
+        });
+        rest = after;
+    }
+    words
+}
+
+/// The names of the types and values a module defines that can be imported,
+/// each with whether it is the name of a type.
+fn importable_names(module: &UntypedModule) -> Vec<(EcoString, bool)> {
+    let mut names = vec![];
+    for definition in &module.definitions {
+        match &definition.definition {
+            Definition::Function(function) if function.publicity.is_importable() => {
+                names.push((function.name.clone(), false))
+            }
+            Definition::ModuleConstant(constant) if constant.publicity.is_importable() => {
+                names.push((constant.name.clone(), false))
+            }
+            Definition::TypeAlias(alias) if alias.publicity.is_importable() => {
+                names.push((alias.alias.clone(), true))
+            }
+            Definition::CustomType(custom_type) if custom_type.publicity.is_importable() => {
+                names.push((custom_type.name.clone(), true));
+                if !custom_type.opaque {
+                    for constructor in &custom_type.constructors {
+                        names.push((constructor.name.clone(), false));
+                    }
+                }
+            }
+            Definition::Function(_)
+            | Definition::ModuleConstant(_)
+            | Definition::TypeAlias(_)
+            | Definition::CustomType(_)
+            | Definition::Import(_) => (),
+        }
+    }
+    names
+}
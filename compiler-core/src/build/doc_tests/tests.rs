@@ -0,0 +1,192 @@
+use crate::{
+    build::{tests::compile_test_package, Mode},
+    config::PackageConfig,
+    io::{memory::InMemoryFileSystem, FileSystemWriter},
+    warning::WarningEmitter,
+};
+use camino::Utf8PathBuf;
+use itertools::Itertools;
+
+fn generated(src: &str) -> String {
+    super::doc_tests("app/maths", src)
+        .into_iter()
+        .map(|test| format!("// {}\n{}", test.name, test.code))
+        .join("\n\n")
+}
+
+/// Compiles a package with its doc tests, returning the names of the
+/// compiled modules or the error.
+fn compile(src: &str) -> Result<Vec<String>, crate::Error> {
+    let fs = InMemoryFileSystem::new();
+    fs.write(&Utf8PathBuf::from("/src/app.gleam"), src)
+        .expect("should write module");
+
+    let mut config = PackageConfig::default();
+    config.name = "app".into();
+
+    let (warnings, _) = WarningEmitter::vector();
+    let (compiled, _) = compile_test_package(config, Mode::Dev, fs, &warnings, |compiler| {
+        compiler.doc_tests = true;
+    })?;
+    Ok(compiled
+        .iter()
+        .map(|module| module.name.to_string())
+        .sorted()
+        .collect())
+}
+
+#[test]
+fn expression_example() {
+    let src = r#"/// Doubles a number.
+///
+/// ```gleam
+/// double(2)
+/// |> should.equal(4)
+/// ```
+pub fn double(x: Int) -> Int {
+  x * 2
+}
+"#;
+    insta::assert_snapshot!(generated(src));
+}
+
+#[test]
+fn example_with_imports() {
+    let src = r#"/// ```gleam
+/// import gleam/int
+/// import gleam/list.{
+///   map,
+/// }
+///
+/// map([1], double) |> int.sum
+/// ```
+pub fn double(x: Int) -> Int {
+  x * 2
+}
+"#;
+    insta::assert_snapshot!(generated(src));
+}
+
+#[test]
+fn example_importing_the_module_itself() {
+    let src = r#"/// ```gleam
+/// import app/maths as m
+/// m.double(1)
+/// ```
+pub fn double(x: Int) -> Int {
+  x * 2
+}
+"#;
+    insta::assert_snapshot!(generated(src));
+}
+
+#[test]
+fn definition_example() {
+    let src = r#"/// ```gleam
+/// pub fn quadruple(x) {
+///   maths.double(double(x))
+/// }
+/// ```
+pub fn double(x: Int) -> Int {
+  x * 2
+}
+"#;
+    insta::assert_snapshot!(generated(src));
+}
+
+#[test]
+fn types_and_constructors_are_imported() {
+    let src = r#"//// ```gleam
+//// let shape: Shape = Circle(1.0)
+//// ```
+
+pub type Shape {
+  Circle(Float)
+  Square(Float)
+}
+
+pub opaque type Secret {
+  Secret
+}
+
+fn private() { Nil }
+"#;
+    insta::assert_snapshot!(generated(src));
+}
+
+#[test]
+fn only_gleam_code_blocks_are_examples() {
+    let src = r#"/// ```
+/// not gleam
+/// ```
+///
+/// ```erlang
+/// io:format("hello")
+/// ```
+pub fn double(x: Int) -> Int {
+  x * 2
+}
+"#;
+    assert_eq!(generated(src), "");
+}
+
+#[test]
+fn unclosed_example_is_ignored() {
+    let src = r#"/// ```gleam
+/// double(1)
+pub fn double(x: Int) -> Int {
+  x * 2
+}
+"#;
+    assert_eq!(generated(src), "");
+}
+
+#[test]
+fn example_line_of_module_name() {
+    assert_eq!(
+        super::example_line("gleam_doc_test/app/maths/line_12"),
+        Some(12)
+    );
+    assert_eq!(super::example_line("app/maths/line_12"), None);
+    assert_eq!(super::example_line("gleam_doc_test/app"), None);
+}
+
+#[test]
+fn doc_tests_are_compiled_as_test_modules() {
+    let src = r#"/// ```gleam
+/// let assert 4 = double(2)
+/// ```
+pub fn double(x: Int) -> Int {
+  x * 2
+}
+"#;
+    assert_eq!(
+        compile(src).expect("should compile"),
+        vec!["app", "gleam_doc_test/app/line_1"]
+    );
+}
+
+#[test]
+fn errors_in_doc_tests_point_at_the_documentation() {
+    let src = r#"/// Doubles a number.
+///
+/// ```gleam
+/// double("two")
+/// ```
+pub fn double(x: Int) -> Int {
+  x * 2
+}
+"#;
+    let error = compile(src).expect_err("should not compile");
+    let crate::Error::Type { path, src, errors } = error else {
+        panic!("expected a type error, got {error:?}");
+    };
+    assert_eq!(path, Utf8PathBuf::from("/src/app.gleam"));
+    let crate::type_::Error::CouldNotUnify { location, .. } = errors.first() else {
+        panic!("expected a type mismatch, got {errors:?}");
+    };
+    assert_eq!(
+        src.get(location.start as usize..location.end as usize),
+        Some("\"two\"")
+    );
+}
@@ -0,0 +1,66 @@
+//! Removal of `echo` expressions from modules compiled in production mode, so
+//! that debug printing left in the code doesn't end up in a release build.
+
+use vec1::Vec1;
+
+use crate::{
+    ast::{SrcSpan, UntypedExpr, UntypedModule},
+    ast_folder::{
+        PatternFolder, TypeAstFolder, UntypedConstantFolder, UntypedExprFolder, UntypedModuleFolder,
+    },
+};
+
+/// Replaces each `echo` in the module with the expression it prints. An
+/// `echo` used as a step of a pipeline is removed from the pipeline.
+///
+pub fn remove_echoes(module: UntypedModule) -> UntypedModule {
+    EchoRemover.fold_module(module)
+}
+
+struct EchoRemover;
+
+impl UntypedModuleFolder for EchoRemover {}
+impl TypeAstFolder for EchoRemover {}
+impl UntypedConstantFolder for EchoRemover {}
+impl PatternFolder for EchoRemover {}
+
+impl UntypedExprFolder for EchoRemover {
+    fn fold_echo(
+        &mut self,
+        location: SrcSpan,
+        expression: Option<Box<UntypedExpr>>,
+    ) -> UntypedExpr {
+        match expression {
+            Some(expression) => *expression,
+            // An `echo` with nothing to print outside of a pipeline is an
+            // error, so it is kept for the type checker to report.
+            None => UntypedExpr::Echo {
+                location,
+                expression: None,
+            },
+        }
+    }
+
+    fn fold_pipe_line(&mut self, expressions: Vec1<UntypedExpr>) -> UntypedExpr {
+        let (first, rest) = expressions.split_off_first();
+        let mut expressions = Vec1::new(first);
+        expressions.extend(rest.into_iter().filter(|expression| {
+            !matches!(
+                expression,
+                UntypedExpr::Echo {
+                    expression: None,
+                    ..
+                }
+            )
+        }));
+
+        if expressions.len() == 1 {
+            expressions.split_off_first().0
+        } else {
+            UntypedExpr::PipeLine { expressions }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
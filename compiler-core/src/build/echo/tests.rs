@@ -0,0 +1,70 @@
+use crate::{
+    build::{tests::compile_test_package, Mode},
+    config::PackageConfig,
+    io::{memory::InMemoryFileSystem, FileSystemReader, FileSystemWriter},
+    warning::WarningEmitter,
+};
+use camino::Utf8PathBuf;
+
+fn compile(src: &str, remove_echoes: bool) -> String {
+    let fs = InMemoryFileSystem::new();
+    fs.write(&Utf8PathBuf::from("/src/app.gleam"), src)
+        .expect("should write module");
+
+    let mut config = PackageConfig::default();
+    config.name = "app".into();
+
+    let (warnings, _) = WarningEmitter::vector();
+    let _ = compile_test_package(config, Mode::Prod, fs.clone(), &warnings, |compiler| {
+        compiler.perform_codegen = true;
+        compiler.remove_echoes = remove_echoes;
+    })
+    .expect("should compile");
+
+    fs.read(&Utf8PathBuf::from("/build/_gleam_artefacts/app.erl"))
+        .expect("should generate Erlang")
+}
+
+#[test]
+fn echo_is_removed() {
+    insta::assert_snapshot!(compile(
+        "
+pub fn main() {
+  let x = echo 1 + 2
+  echo x
+}
+",
+        true
+    ));
+}
+
+#[test]
+fn echo_in_pipeline_is_removed() {
+    insta::assert_snapshot!(compile(
+        "
+pub fn main() {
+  1
+  |> echo
+  |> add(2)
+  |> echo
+}
+
+fn add(a, b) {
+  a + b
+}
+",
+        true
+    ));
+}
+
+#[test]
+fn echo_is_kept_when_not_removing_echoes() {
+    insta::assert_snapshot!(compile(
+        "
+pub fn main() {
+  1 |> echo |> echo
+}
+",
+        false
+    ));
+}
@@ -16,6 +16,8 @@ use super::{
 use crate::{
     error::{FileIoAction, FileKind},
     io::{CommandExecutor, FileSystemReader, FileSystemWriter},
+    metadata::ModuleEncoder,
+    type_,
     warning::WarningEmitter,
     Error, Result,
 };
@@ -29,6 +31,25 @@ impl SourceFingerprint {
     }
 }
 
+/// A hash of the interface of a module, which the modules importing it are
+/// type checked against. A module is only type checked again when one of the
+/// modules it imports has changed if the interface of that module is
+/// different from the one the module was type checked against.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct InterfaceFingerprint(u64);
+
+impl InterfaceFingerprint {
+    pub(crate) fn new(interface: &type_::ModuleInterface) -> Result<Self> {
+        let bytes = ModuleEncoder::new(interface).encode()?;
+        Ok(Self::from_encoded(&bytes))
+    }
+
+    /// The fingerprint of an interface from the bytes it is cached as.
+    pub(crate) fn from_encoded(bytes: &[u8]) -> Self {
+        InterfaceFingerprint(xxhash_rust::xxh3::xxh3_64(bytes))
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct ModuleLoader<'a, IO> {
     pub io: IO,
@@ -52,8 +73,9 @@ where
     /// changed since then, load the precompiled data instead.
     ///
     /// Whether the module has changed or not is determined by comparing the
-    /// modification time of the source file with the value recorded in the
-    /// `.timestamp` file in the artefact directory.
+    /// hash of the source file with the one recorded in the `.cache_meta`
+    /// file in the artefact directory. The source is only read to be hashed
+    /// if its modification time is not the one recorded there.
     pub fn load(&self, path: Utf8PathBuf) -> Result<Input> {
         let name = module_name(self.source_directory, &path);
         let artefact = name.replace("/", "@");
@@ -61,7 +83,7 @@ where
 
         let read_source = |name| self.read_source(path, name, source_mtime);
 
-        let meta = match self.read_cache_metadata(&artefact)? {
+        let mut meta = match self.read_cache_metadata(&artefact)? {
             Some(meta) => meta,
             None => return read_source(name).map(Input::New),
         };
@@ -74,15 +96,23 @@ where
             return read_source(name).map(Input::New);
         }
 
-        // If the timestamp of the source is newer than the cache entry and
+        // If the timestamp of the source is not the one of the cache entry and
         // the hash of the source differs from the one in the cache entry,
-        // then we need to recompile.
-        if meta.mtime < source_mtime {
+        // then we need to recompile. Sources that have been touched without
+        // being changed, such as by a git checkout or by restoring a CI cache,
+        // keep using the cache.
+        if meta.mtime != source_mtime {
             let source_module = read_source(name.clone())?;
             if meta.fingerprint != SourceFingerprint::new(&source_module.code) {
                 tracing::debug!(?name, "cache_stale");
                 return Ok(Input::New(source_module));
             }
+
+            // The new timestamp is recorded so the source doesn't have to be
+            // read and hashed again the next time it is loaded.
+            tracing::debug!(?name, "cache_timestamp_updated");
+            meta.mtime = source_mtime;
+            self.write_cache_metadata(&artefact, &meta)?;
         }
 
         Ok(Input::Cached(self.cached(name, meta)))
@@ -91,10 +121,7 @@ where
     /// Read the timestamp file from the artefact directory for the given
     /// artefact slug. If the file does not exist, return `None`.
     fn read_cache_metadata(&self, artefact: &str) -> Result<Option<CacheMetadata>> {
-        let meta_path = self
-            .artefact_directory
-            .join(artefact)
-            .with_extension("cache_meta");
+        let meta_path = self.cache_metadata_path(artefact);
 
         if !self.io.is_file(&meta_path) {
             return Ok(None);
@@ -112,6 +139,17 @@ where
         Ok(Some(cache_metadata))
     }
 
+    fn write_cache_metadata(&self, artefact: &str, meta: &CacheMetadata) -> Result<()> {
+        let meta_path = self.cache_metadata_path(artefact);
+        self.io.write_bytes(&meta_path, &meta.to_binary())
+    }
+
+    fn cache_metadata_path(&self, artefact: &str) -> Utf8PathBuf {
+        self.artefact_directory
+            .join(artefact)
+            .with_extension("cache_meta")
+    }
+
     fn read_source(
         &self,
         path: Utf8PathBuf,
@@ -132,10 +170,12 @@ where
     fn cached(&self, name: EcoString, meta: CacheMetadata) -> CachedModule {
         CachedModule {
             dependencies: meta.dependencies,
+            dependency_interfaces: meta.dependency_interfaces,
             source_path: self.source_directory.join(format!("{}.gleam", name)),
             origin: self.origin,
             name,
             line_numbers: meta.line_numbers,
+            warnings: meta.warnings,
         }
     }
 }
@@ -153,7 +193,18 @@ where
     IO: FileSystemReader + FileSystemWriter + CommandExecutor + Clone,
 {
     let code: EcoString = io.read(&path)?.into();
+    parse_source(code, target, origin, path, name, package_name, mtime)
+}
 
+pub(crate) fn parse_source(
+    code: EcoString,
+    target: Target,
+    origin: Origin,
+    path: Utf8PathBuf,
+    name: EcoString,
+    package_name: EcoString,
+    mtime: SystemTime,
+) -> Result<UncompiledModule> {
     let parsed = crate::parse::parse_module(&code).map_err(|error| Error::Parse {
         path: path.clone(),
         src: code.clone(),
@@ -85,6 +85,48 @@ fn cache_present_and_stale_but_source_is_the_same() {
     assert!(result.is_cached());
 }
 
+#[test]
+fn cache_present_and_changed_with_older_source() {
+    let name = "package".into();
+    let src = Utf8Path::new("/src");
+    let artefact = Utf8Path::new("/artefact");
+    let fs = InMemoryFileSystem::new();
+    let warnings = WarningEmitter::null();
+    let loader = make_loader(&warnings, &name, &fs, src, artefact);
+
+    // The mtime of the source is older than that of the cache, but the source
+    // is different from the one that was cached
+    write_src(&fs, TEST_SOURCE_2, "/src/main.gleam", 0);
+    write_cache(&fs, TEST_SOURCE_1, "/artefact/main.cache_meta", 1, false);
+
+    let result = loader
+        .load(Utf8Path::new("/src/main.gleam").to_path_buf())
+        .unwrap();
+
+    assert!(result.is_new());
+}
+
+#[test]
+fn cache_timestamp_is_updated_when_source_is_the_same() {
+    let name = "package".into();
+    let src = Utf8Path::new("/src");
+    let artefact = Utf8Path::new("/artefact");
+    let fs = InMemoryFileSystem::new();
+    let warnings = WarningEmitter::null();
+    let loader = make_loader(&warnings, &name, &fs, src, artefact);
+
+    write_src(&fs, TEST_SOURCE_1, "/src/main.gleam", 2);
+    write_cache(&fs, TEST_SOURCE_1, "/artefact/main.cache_meta", 1, false);
+
+    let result = loader
+        .load(Utf8Path::new("/src/main.gleam").to_path_buf())
+        .unwrap();
+    assert!(result.is_cached());
+
+    let meta = loader.read_cache_metadata("main").unwrap().unwrap();
+    assert_eq!(meta.mtime, SystemTime::UNIX_EPOCH + Duration::from_secs(2));
+}
+
 #[test]
 fn cache_present_without_codegen_when_required() {
     let name = "package".into();
@@ -163,8 +205,10 @@ fn write_cache(
         mtime: SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
         codegen_performed,
         dependencies: vec![],
+        dependency_interfaces: vec![],
         fingerprint: SourceFingerprint::new(source),
         line_numbers,
+        warnings: vec![],
     };
     let path = Utf8Path::new(path);
     fs.write_bytes(&path, &cache_metadata.to_binary()).unwrap();
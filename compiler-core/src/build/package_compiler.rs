@@ -1,18 +1,27 @@
+#[cfg(test)]
+mod tests;
+
 use crate::analyse::TargetSupport;
 use crate::line_numbers::{self, LineNumbers};
 use crate::type_::PRELUDE_MODULE_NAME;
 use crate::{
     ast::{SrcSpan, TypedModule, UntypedModule},
     build::{
+        dead_code, echo,
         elixir_libraries::ElixirLibraries,
-        module_loader::SourceFingerprint,
+        module_loader::{read_source, InterfaceFingerprint, SourceFingerprint},
         native_file_copier::NativeFileCopier,
-        package_loader::{CodegenRequired, PackageLoader, StaleTracker},
-        Mode, Module, Origin, Package, Target,
+        package_loader::{
+            emit_cached_warnings, load_cached_interface, warning_to_cache, CodegenRequired,
+            PackageLoader, StaleTracker,
+        },
+        Mode, Module, Origin, Package, Phase, Target, Timings,
     },
-    codegen::{Erlang, ErlangApp, JavaScript, TypeScriptDeclarations},
-    config::PackageConfig,
-    dep_tree, error,
+    codegen::{Erlang, ErlangApp, JavaScript, SourceMaps, TypeScriptDeclarations},
+    config::{Dependencies, PackageConfig},
+    dep_tree,
+    diagnostic::Diagnostic,
+    error,
     io::{CommandExecutor, FileSystemReader, FileSystemWriter, Stdio},
     metadata::ModuleEncoder,
     parse::extra::ModuleExtra,
@@ -24,11 +33,17 @@ use crate::{
 use askama::Template;
 use ecow::EcoString;
 use std::collections::HashSet;
-use std::{collections::HashMap, fmt::write, time::SystemTime};
+use std::{
+    collections::HashMap,
+    fmt::write,
+    time::{Instant, SystemTime},
+};
 
 use camino::{Utf8Path, Utf8PathBuf};
 
-use super::{ErlangAppCodegenConfiguration, TargetCodegenConfiguration, Telemetry};
+use super::{
+    CancellationToken, ErlangAppCodegenConfiguration, TargetCodegenConfiguration, Telemetry,
+};
 
 #[derive(Debug)]
 pub struct PackageCompiler<'a, IO> {
@@ -47,6 +62,23 @@ pub struct PackageCompiler<'a, IO> {
     pub compile_beam_bytecode: bool,
     pub subprocess_stdio: Stdio,
     pub target_support: TargetSupport,
+    /// Whether to warn about public definitions that can't be reached from
+    /// any `main` function. This requires every module to be type checked,
+    /// so caches are not used when it is set.
+    pub detect_dead_code: bool,
+    /// Whether to type check every module again, even those with a valid
+    /// cache, for commands that need the typed AST of every module.
+    pub recompile_cached: bool,
+    /// Whether to remove `echo` expressions before type checking, so that
+    /// they don't print anything in a production build.
+    pub remove_echoes: bool,
+    /// Whether to compile the examples in the documentation of the package's
+    /// modules as test modules. See the `doc_tests` module.
+    pub doc_tests: bool,
+    /// Where the time taken by each phase of the compilation is recorded.
+    pub timings: Timings,
+    /// Stops the type checking of the modules when cancelled.
+    pub cancellation: CancellationToken,
 }
 
 impl<'a, IO> PackageCompiler<'a, IO>
@@ -79,6 +111,12 @@ where
             compile_beam_bytecode: true,
             subprocess_stdio: Stdio::Inherit,
             target_support: TargetSupport::NotEnforced,
+            detect_dead_code: false,
+            recompile_cached: false,
+            doc_tests: false,
+            remove_echoes: false,
+            timings: Timings::disabled(),
+            cancellation: CancellationToken::new(),
         }
     }
 
@@ -91,6 +129,7 @@ where
         warnings: &WarningEmitter,
         existing_modules: &mut im::HashMap<EcoString, type_::ModuleInterface>,
         already_defined_modules: &mut im::HashMap<EcoString, Utf8PathBuf>,
+        skipped_modules: &mut im::HashSet<EcoString>,
         stale_modules: &mut StaleTracker,
         telemetry: &dyn Telemetry,
     ) -> Result<Vec<Module>, Error> {
@@ -106,6 +145,7 @@ where
         } else {
             CodegenRequired::No
         };
+        let start = Instant::now();
         let loaded = PackageLoader::new(
             self.io.clone(),
             self.ids.clone(),
@@ -118,8 +158,14 @@ where
             &self.config.name,
             stale_modules,
             already_defined_modules,
+            self.detect_dead_code || self.recompile_cached,
+            self.doc_tests,
+            self.config.modules_for_other_targets(self.target.target()),
+            skipped_modules,
         )
         .run()?;
+        self.timings
+            .record(Phase::Parse, &self.config.name, None, start);
 
         // Load the cached modules that have previously been compiled
         for module in loaded.cached.into_iter() {
@@ -137,20 +183,42 @@ where
 
         // Type check the modules that are new or have changed
         tracing::info!(count=%loaded.to_compile.len(), "analysing_modules");
-        let modules = analyse(
+        let mut fingerprints = InterfaceFingerprints::default();
+        let start = Instant::now();
+        let (modules, module_warnings) = analyse(
+            &self.io,
+            &artefact_directory,
             &self.config,
             self.target.target(),
             self.mode,
             &self.ids,
             loaded.to_compile,
             existing_modules,
+            &mut fingerprints,
             warnings,
             self.target_support,
+            self.remove_echoes,
+            &self.timings,
+            &self.cancellation,
         )?;
+        self.timings
+            .record(Phase::Analysis, &self.config.name, None, start);
+
+        if self.detect_dead_code {
+            tracing::debug!("detecting_dead_code");
+            for warning in dead_code::unused_public_definitions(&modules, self.target.target()) {
+                warnings.emit(warning);
+            }
+        }
 
         tracing::debug!("performing_code_generation");
         self.perform_codegen(&modules)?;
-        self.encode_and_write_metadata(&modules)?;
+        self.encode_and_write_metadata(
+            &modules,
+            module_warnings,
+            existing_modules,
+            &mut fingerprints,
+        )?;
         Ok(modules)
     }
 
@@ -233,7 +301,13 @@ where
         Ok(())
     }
 
-    fn encode_and_write_metadata(&mut self, modules: &[Module]) -> Result<()> {
+    fn encode_and_write_metadata(
+        &mut self,
+        modules: &[Module],
+        mut module_warnings: HashMap<EcoString, Vec<Diagnostic>>,
+        module_types: &im::HashMap<EcoString, type_::ModuleInterface>,
+        fingerprints: &mut InterfaceFingerprints,
+    ) -> Result<()> {
         if !self.write_metadata {
             tracing::debug!("package_metadata_writing_disabled");
             return Ok(());
@@ -253,16 +327,28 @@ where
             let path = artefact_dir.join(name);
             let bytes = ModuleEncoder::new(&module.ast.type_info).encode()?;
             self.io.write_bytes(&path, &bytes)?;
+            fingerprints.insert(
+                module.name.clone(),
+                InterfaceFingerprint::from_encoded(&bytes),
+            );
 
             // Write cache info
             let name = format!("{}.cache_meta", &module_name);
             let path = artefact_dir.join(name);
+            let mut dependency_interfaces = vec![];
+            for dependency in module.dependencies_list() {
+                if let Some(fingerprint) = fingerprints.get(&dependency, module_types)? {
+                    dependency_interfaces.push((dependency, fingerprint));
+                }
+            }
             let info = CacheMetadata {
                 mtime: module.mtime,
                 codegen_performed: self.perform_codegen,
                 dependencies: module.dependencies_list(),
+                dependency_interfaces,
                 fingerprint: SourceFingerprint::new(&module.code),
                 line_numbers: module.ast.type_info.line_numbers.clone(),
+                warnings: module_warnings.remove(&module.name).unwrap_or_default(),
             };
             self.io.write_bytes(&path, &info.to_binary())?;
         }
@@ -278,10 +364,12 @@ where
         match self.target {
             TargetCodegenConfiguration::JavaScript {
                 emit_typescript_definitions,
+                emit_source_maps,
                 prelude_location,
             } => self.perform_javascript_codegen(
                 modules,
                 *emit_typescript_definitions,
+                *emit_source_maps,
                 prelude_location,
             ),
             TargetCodegenConfiguration::Erlang { app_file } => {
@@ -326,11 +414,17 @@ where
         // we overwrite any precompiled Erlang that was included in the Hex
         // package. Otherwise we will build the potentially outdated precompiled
         // version and not the newly compiled version.
+        let start = Instant::now();
         Erlang::new(&build_dir, &include_dir).render(io, modules)?;
+        self.timings
+            .record(Phase::Codegen, &self.config.name, None, start);
 
         if self.compile_beam_bytecode {
             written.extend(modules.iter().map(Module::compiled_erlang_path));
+            let start = Instant::now();
             self.compile_erlang_to_beam(&written)?;
+            self.timings
+                .record(Phase::ErlangCompile, &self.config.name, None, start);
         } else {
             tracing::debug!("skipping_erlang_bytecode_compilation");
         }
@@ -341,6 +435,7 @@ where
         &mut self,
         modules: &[Module],
         typescript: bool,
+        source_maps: bool,
         prelude_location: &Utf8Path,
     ) -> Result<(), Error> {
         let mut written = HashSet::new();
@@ -349,9 +444,23 @@ where
         } else {
             TypeScriptDeclarations::None
         };
+        let source_maps = if source_maps {
+            SourceMaps::Emit
+        } else {
+            SourceMaps::None
+        };
 
-        JavaScript::new(&self.out, typescript, prelude_location, self.target_support)
-            .render(&self.io, modules)?;
+        let start = Instant::now();
+        JavaScript::new(
+            &self.out,
+            typescript,
+            source_maps,
+            prelude_location,
+            self.target_support,
+        )
+        .render(&self.io, modules)?;
+        self.timings
+            .record(Phase::Codegen, &self.config.name, None, start);
 
         if self.copy_native_files {
             self.copy_project_native_files(&self.out, &mut written)?;
@@ -370,17 +479,19 @@ where
         let name = format!("{name}@@main.erl", name = self.config.name);
         let path = out.join(&name);
 
+        let template = ErlangEntrypointModule {
+            application: &self.config.name,
+        };
+        let module = template.render().expect("Erlang entrypoint rendering");
+
         // If the entrypoint module has already been created then we don't need
-        // to write and compile it again.
-        if self.io.is_file(&path) {
+        // to write and compile it again, unless it was created by a version of
+        // Gleam with a different entrypoint.
+        if self.io.read(&path).is_ok_and(|existing| existing == module) {
             tracing::debug!("erlang_entrypoint_already_exists");
             return Ok(());
         }
 
-        let template = ErlangEntrypointModule {
-            application: &self.config.name,
-        };
-        let module = template.render().expect("Erlang entrypoint rendering");
         self.io.write(&path, &module)?;
         let _ = modules_to_compile.insert(name.into());
         tracing::debug!("erlang_entrypoint_written");
@@ -388,17 +499,28 @@ where
     }
 }
 
-fn analyse(
+#[allow(clippy::too_many_arguments)]
+fn analyse<IO>(
+    io: &IO,
+    artefact_directory: &Utf8Path,
     package_config: &PackageConfig,
     target: Target,
     mode: Mode,
     ids: &UniqueIdGenerator,
-    mut parsed_modules: Vec<UncompiledModule>,
+    inputs: Vec<Input>,
     module_types: &mut im::HashMap<EcoString, type_::ModuleInterface>,
+    fingerprints: &mut InterfaceFingerprints,
     warnings: &WarningEmitter,
     target_support: TargetSupport,
-) -> Result<Vec<Module>, Error> {
-    let mut modules = Vec::with_capacity(parsed_modules.len() + 1);
+    remove_echoes: bool,
+    timings: &Timings,
+    cancellation: &CancellationToken,
+) -> Result<(Vec<Module>, HashMap<EcoString, Vec<Diagnostic>>), Error>
+where
+    IO: FileSystemReader + FileSystemWriter + CommandExecutor + Clone,
+{
+    let mut modules = Vec::with_capacity(inputs.len() + 1);
+    let mut module_warnings = HashMap::new();
     let direct_dependencies = package_config.dependencies_for(mode).expect("Package deps");
 
     // Insert the prelude
@@ -408,59 +530,287 @@ fn analyse(
     // place.
     let _ = module_types.insert(PRELUDE_MODULE_NAME.into(), type_::build_prelude(ids));
 
-    for UncompiledModule {
+    for group in import_depth_groups(inputs) {
+        if cancellation.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+
+        // A cached module that imports modules that were compiled again can
+        // still be loaded from the cache if the interfaces of those modules
+        // are the same as the ones it was type checked against.
+        let mut group_modules = Vec::with_capacity(group.len());
+        for input in group {
+            let cached = match input {
+                Input::New(module) => {
+                    group_modules.push(module);
+                    continue;
+                }
+                Input::Cached(cached) => cached,
+            };
+            if !fingerprints.unchanged(&cached.dependency_interfaces, module_types)? {
+                tracing::debug!(module = %cached.name, "dependency_interfaces_changed");
+                let mtime = io.modification_time(&cached.source_path)?;
+                group_modules.push(read_source(
+                    io.clone(),
+                    target,
+                    cached.origin,
+                    cached.source_path,
+                    cached.name,
+                    package_config.name.clone(),
+                    mtime,
+                )?);
+                continue;
+            }
+            tracing::debug!(module = %cached.name, "module_to_load_from_cache");
+            let (interface, fingerprint) =
+                load_cached_interface(io, artefact_directory, ids, &cached.name)?;
+            emit_cached_warnings(io, warnings, &cached.source_path, cached.warnings);
+            fingerprints.insert(cached.name.clone(), fingerprint);
+            let _ = module_types.insert(cached.name, interface);
+        }
+
+        // The modules of a group don't import each other, so they are type
+        // checked at the same time against the interfaces of the modules
+        // checked before them. The map of interfaces shares its contents, so
+        // copying it is cheap.
+        let interfaces = module_types.clone();
+        let analysed = in_parallel(group_modules, |module| {
+            let start = Instant::now();
+            let name = module.name.clone();
+            let analysed = analyse_module(
+                package_config,
+                target,
+                ids,
+                module,
+                &interfaces,
+                &direct_dependencies,
+                target_support,
+                remove_echoes,
+            );
+            timings.record(Phase::Analysis, &package_config.name, Some(&name), start);
+            analysed
+        });
+
+        // The results are used in the order the modules were loaded in, so the
+        // warnings and the error reported don't depend on which thread
+        // finished first.
+        for (result, emitted) in analysed {
+            let diagnostics = emitted
+                .iter()
+                .map(|warning| warning_to_cache(warning.to_diagnostic()))
+                .collect();
+            for warning in emitted {
+                warnings.emit(warning);
+            }
+            let module = result?;
+            let _ = module_warnings.insert(module.name.clone(), diagnostics);
+
+            // Register the types from this module so they can be imported into
+            // other modules.
+            let _ = module_types.insert(module.name.clone(), module.ast.type_info.clone());
+
+            // Register the successfully type checked module data so that it can be
+            // used for code generation
+            modules.push(module);
+        }
+    }
+
+    Ok((modules, module_warnings))
+}
+
+/// The fingerprints of the interfaces of modules, worked out the first time
+/// they are needed.
+#[derive(Debug, Default)]
+struct InterfaceFingerprints(HashMap<EcoString, InterfaceFingerprint>);
+
+impl InterfaceFingerprints {
+    fn insert(&mut self, module: EcoString, fingerprint: InterfaceFingerprint) {
+        let _ = self.0.insert(module, fingerprint);
+    }
+
+    fn get(
+        &mut self,
+        module: &EcoString,
+        module_types: &im::HashMap<EcoString, type_::ModuleInterface>,
+    ) -> Result<Option<InterfaceFingerprint>> {
+        if let Some(fingerprint) = self.0.get(module) {
+            return Ok(Some(*fingerprint));
+        }
+        let Some(interface) = module_types.get(module) else {
+            return Ok(None);
+        };
+        let fingerprint = InterfaceFingerprint::new(interface)?;
+        self.insert(module.clone(), fingerprint);
+        Ok(Some(fingerprint))
+    }
+
+    /// Whether the interfaces of the modules are the same as they were.
+    fn unchanged(
+        &mut self,
+        interfaces: &[(EcoString, InterfaceFingerprint)],
+        module_types: &im::HashMap<EcoString, type_::ModuleInterface>,
+    ) -> Result<bool> {
+        for (module, fingerprint) in interfaces {
+            if self.get(module, module_types)? != Some(*fingerprint) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Type checks a module, returning the warnings emitted for it along with it
+/// so they can be emitted once all the modules being checked at the same
+/// time are done.
+#[allow(clippy::too_many_arguments)]
+fn analyse_module(
+    package_config: &PackageConfig,
+    target: Target,
+    ids: &UniqueIdGenerator,
+    module: UncompiledModule,
+    module_types: &im::HashMap<EcoString, type_::ModuleInterface>,
+    direct_dependencies: &Dependencies,
+    target_support: TargetSupport,
+    remove_echoes: bool,
+) -> (Result<Module, Error>, Vec<Warning>) {
+    let UncompiledModule {
         name,
         code,
         ast,
         path,
         mtime,
         origin,
-        package,
+        package: _,
+        dependencies,
+        extra,
+    } = module;
+    tracing::debug!(module = ?name, "Type checking");
+
+    let ast = if remove_echoes {
+        echo::remove_echoes(ast)
+    } else {
+        ast
+    };
+
+    let (warnings, emitted) = WarningEmitter::vector();
+    let line_numbers = LineNumbers::new(&code);
+    let ast = crate::analyse::infer_module(
+        target,
+        ids,
+        ast,
+        origin,
+        module_types,
+        &TypeWarningEmitter::new(path.clone(), code.clone(), warnings),
+        direct_dependencies,
+        target_support,
+        line_numbers,
+        package_config,
+        path.clone(),
+    )
+    .map_err(|errors| Error::Type {
+        path: path.clone(),
+        src: code.clone(),
+        errors,
+    });
+
+    let module = ast.map(|ast| Module {
         dependencies,
+        origin,
         extra,
-    } in parsed_modules
-    {
-        tracing::debug!(module = ?name, "Type checking");
+        mtime,
+        name,
+        code,
+        ast,
+        input_path: path,
+    });
+    (module, emitted.take())
+}
 
-        let line_numbers = LineNumbers::new(&code);
-        let ast = crate::analyse::infer_module(
-            target,
-            ids,
-            ast,
-            origin,
-            module_types,
-            &TypeWarningEmitter::new(path.clone(), code.clone(), warnings.clone()),
-            &direct_dependencies,
-            target_support,
-            line_numbers,
-            package_config,
-            path.clone(),
-        )
-        .map_err(|error| Error::Type {
-            path: path.clone(),
-            src: code.clone(),
-            error,
-        })?;
-
-        // Register the types from this module so they can be imported into
-        // other modules.
-        let _ = module_types.insert(name.clone(), ast.type_info.clone());
-
-        // Register the successfully type checked module data so that it can be
-        // used for code generation
-        modules.push(Module {
-            dependencies,
-            origin,
-            extra,
-            mtime,
-            name,
-            code,
-            ast,
-            input_path: path,
-        });
+/// Groups modules, given in the order they import each other, by how deep
+/// they are in the import graph of the package. A module is one level deeper
+/// than the deepest module of the package it imports, so the modules of a
+/// group don't import each other.
+fn import_depth_groups(modules: Vec<Input>) -> Vec<Vec<Input>> {
+    let mut depths: HashMap<EcoString, usize> = HashMap::new();
+    let mut groups: Vec<Vec<Input>> = vec![];
+    for module in modules {
+        let depth = module
+            .dependencies()
+            .iter()
+            .filter_map(|dependency| depths.get(dependency))
+            .map(|depth| depth + 1)
+            .max()
+            .unwrap_or(0);
+        let _ = depths.insert(module.name().clone(), depth);
+        if groups.len() <= depth {
+            groups.resize_with(depth + 1, Vec::new);
+        }
+        if let Some(group) = groups.get_mut(depth) {
+            group.push(module);
+        }
+    }
+    groups
+}
+
+/// The stack size of the threads modules are type checked and generated on,
+/// which is the same as the main thread's on most platforms, as type checking
+/// and code generation are deeply recursive.
+const WORKER_THREAD_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Calls the function with each item on a thread for each of the available
+/// cores, returning the results in the order of the items. Where threads are
+/// not available, such as on WebAssembly, the items are done one by one.
+pub(crate) fn in_parallel<T: Send, R: Send>(
+    items: Vec<T>,
+    function: impl Fn(T) -> R + Sync,
+) -> Vec<R> {
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    in_threads(threads, items, function)
+}
+
+/// Calls the function with each item on up to the given number of threads,
+/// returning the results in the order of the items.
+fn in_threads<T: Send, R: Send>(
+    threads: usize,
+    items: Vec<T>,
+    function: impl Fn(T) -> R + Sync,
+) -> Vec<R> {
+    let threads = threads.min(items.len());
+    if threads <= 1 {
+        return items.into_iter().map(function).collect();
     }
 
-    Ok(modules)
+    let function = &function;
+    let queue = std::sync::Mutex::new(items.into_iter().enumerate());
+    let mut results: Vec<(usize, R)> = std::thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|_| {
+                std::thread::Builder::new()
+                    .stack_size(WORKER_THREAD_STACK_SIZE)
+                    .spawn_scoped(scope, || {
+                        let mut results = vec![];
+                        loop {
+                            let next = queue.lock().expect("Work queue lock poisoned").next();
+                            let Some((index, item)) = next else {
+                                return results;
+                            };
+                            results.push((index, function(item)));
+                        }
+                    })
+                    .expect("Worker thread spawning")
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
 }
 
 pub(crate) fn module_name(package_path: &Utf8Path, full_module_path: &Utf8Path) -> EcoString {
@@ -532,8 +882,10 @@ pub(crate) struct CachedModule {
     pub name: EcoString,
     pub origin: Origin,
     pub dependencies: Vec<EcoString>,
+    pub dependency_interfaces: Vec<(EcoString, InterfaceFingerprint)>,
     pub source_path: Utf8PathBuf,
     pub line_numbers: LineNumbers,
+    pub warnings: Vec<Diagnostic>,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -541,8 +893,14 @@ pub(crate) struct CacheMetadata {
     pub mtime: SystemTime,
     pub codegen_performed: bool,
     pub dependencies: Vec<EcoString>,
+    /// The fingerprints of the interfaces of the modules the module imports,
+    /// as they were when the module was type checked.
+    pub dependency_interfaces: Vec<(EcoString, InterfaceFingerprint)>,
     pub fingerprint: SourceFingerprint,
     pub line_numbers: LineNumbers,
+    /// The warnings emitted when the module was type checked, which are
+    /// emitted again when the module is loaded from the cache.
+    pub warnings: Vec<Diagnostic>,
 }
 
 impl CacheMetadata {
@@ -555,9 +913,11 @@ impl CacheMetadata {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default)]
 pub(crate) struct Loaded {
-    pub to_compile: Vec<UncompiledModule>,
+    /// The modules to compile, along with the cached modules that import
+    /// modules being compiled again, in the order they are to be compiled in.
+    pub to_compile: Vec<Input>,
     pub cached: Vec<type_::ModuleInterface>,
 }
 
@@ -0,0 +1,168 @@
+use super::*;
+use crate::{
+    build::tests::compile_test_package,
+    io::{memory::InMemoryFileSystem, FileSystemWriter},
+};
+use std::time::Duration;
+
+fn cached(name: &str, dependencies: &[&str]) -> Input {
+    Input::Cached(CachedModule {
+        name: name.into(),
+        origin: Origin::Src,
+        dependencies: dependencies.iter().map(|&name| name.into()).collect(),
+        dependency_interfaces: vec![],
+        source_path: Utf8PathBuf::from(format!("/src/{name}.gleam")),
+        line_numbers: LineNumbers::new(""),
+        warnings: vec![],
+    })
+}
+
+fn group_names(modules: Vec<Input>) -> Vec<Vec<EcoString>> {
+    let groups = import_depth_groups(modules);
+
+    // No module of a group imports another module of the same group.
+    for group in &groups {
+        for module in group {
+            let dependencies = module.dependencies();
+            assert!(
+                group
+                    .iter()
+                    .all(|other| !dependencies.contains(other.name())),
+                "{} imports a module of its own group",
+                module.name()
+            );
+        }
+    }
+
+    groups
+        .iter()
+        .map(|group| group.iter().map(|module| module.name().clone()).collect())
+        .collect()
+}
+
+#[test]
+fn import_depth_groups_of_chain() {
+    let modules = vec![cached("a", &[]), cached("b", &["a"]), cached("c", &["b"])];
+    assert_eq!(group_names(modules), vec![vec!["a"], vec!["b"], vec!["c"]]);
+}
+
+#[test]
+fn import_depth_groups_of_diamond() {
+    let modules = vec![
+        cached("a", &[]),
+        cached("b", &["a"]),
+        cached("c", &["a"]),
+        cached("d", &["b", "c"]),
+    ];
+    assert_eq!(
+        group_names(modules),
+        vec![vec!["a"], vec!["b", "c"], vec!["d"]]
+    );
+}
+
+#[test]
+fn import_depth_groups_use_deepest_import() {
+    let modules = vec![
+        cached("a", &[]),
+        cached("b", &["a"]),
+        cached("c", &["b"]),
+        cached("d", &["a", "c"]),
+        cached("e", &["a"]),
+    ];
+    assert_eq!(
+        group_names(modules),
+        vec![vec!["a"], vec!["b", "e"], vec!["c"], vec!["d"]]
+    );
+}
+
+#[test]
+fn import_depth_groups_ignore_modules_of_other_packages() {
+    let modules = vec![
+        cached("a", &["gleam/list"]),
+        cached("b", &["gleam/string"]),
+        cached("c", &["a", "gleam/list"]),
+    ];
+    assert_eq!(group_names(modules), vec![vec!["a", "b"], vec!["c"]]);
+}
+
+#[test]
+fn in_threads_returns_results_in_order_of_items() {
+    // Later items are quicker to do, so they finish first.
+    let items = (0..16).collect::<Vec<u64>>();
+    let results = in_threads(4, items, |item| {
+        std::thread::sleep(Duration::from_millis((16 - item) * 5));
+        item * 10
+    });
+    assert_eq!(results, (0..16).map(|item| item * 10).collect::<Vec<_>>());
+}
+
+#[test]
+fn in_threads_with_one_thread() {
+    let results = in_threads(1, vec![1, 2, 3], |item| item * 10);
+    assert_eq!(results, vec![10, 20, 30]);
+}
+
+#[test]
+fn in_parallel_returns_results_in_order_of_items() {
+    let results = in_parallel((0..100).collect(), |item: u64| item * 10);
+    assert_eq!(results, (0..100).map(|item| item * 10).collect::<Vec<_>>());
+}
+
+#[test]
+fn in_parallel_with_no_items() {
+    let results = in_parallel(Vec::<u64>::new(), |item| item);
+    assert_eq!(results, Vec::<u64>::new());
+}
+
+#[test]
+fn warnings_and_error_are_reported_in_load_order() {
+    // The modules don't import each other so they are type checked at the
+    // same time. The first modules are much bigger than the others so they
+    // take the longest to type check.
+    let padding = (0..300)
+        .map(|i| format!("pub fn function_{i}(x) {{ [x, x + {i}, x * {i}] }}\n"))
+        .collect::<String>();
+    let fs = InMemoryFileSystem::new();
+    let modules = [
+        ("a", true, false),
+        ("b", true, true),
+        ("c", false, true),
+        ("d", false, false),
+    ];
+    for (name, padded, error) in modules {
+        let mut src = "pub fn main() {\n  let unused = 1\n  Nil\n}\n".to_string();
+        if error {
+            src.push_str("pub fn error() {\n  1 + \"one\"\n}\n");
+        }
+        if padded {
+            src.push_str(&padding);
+        }
+        fs.write(&Utf8PathBuf::from(format!("/src/{name}.gleam")), &src)
+            .expect("should write module");
+    }
+    let mut config = PackageConfig::default();
+    config.name = "app".into();
+
+    let (warnings, emitted) = WarningEmitter::vector();
+    let result = compile_test_package(config, Mode::Dev, fs, &warnings, |_| {});
+
+    let warning_paths = emitted
+        .take()
+        .into_iter()
+        .map(|warning| match warning {
+            Warning::Type { path, .. } => path,
+            warning => panic!("unexpected warning {warning:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warning_paths,
+        vec![
+            Utf8PathBuf::from("/src/a.gleam"),
+            Utf8PathBuf::from("/src/b.gleam")
+        ]
+    );
+    match result {
+        Err(Error::Type { path, .. }) => assert_eq!(path, Utf8PathBuf::from("/src/b.gleam")),
+        result => panic!("expected a type error, got {:?}", result.map(|_| ())),
+    }
+}
@@ -8,8 +8,6 @@ use std::{
 
 use camino::{Utf8Path, Utf8PathBuf};
 
-// TODO: emit warnings for cached modules even if they are not compiled again.
-
 use ecow::EcoString;
 use itertools::Itertools;
 
@@ -17,6 +15,7 @@ use crate::{
     build::{module_loader::ModuleLoader, package_compiler::module_name, Module, Origin},
     config::PackageConfig,
     dep_tree,
+    diagnostic::Diagnostic,
     error::{FileIoAction, FileKind},
     io::{CommandExecutor, FileSystemReader, FileSystemWriter},
     metadata, type_,
@@ -26,7 +25,8 @@ use crate::{
 };
 
 use super::{
-    module_loader::read_source,
+    doc_tests,
+    module_loader::{parse_source, read_source, InterfaceFingerprint},
     package_compiler::{CacheMetadata, CachedModule, Input, Loaded, UncompiledModule},
     Mode, Target,
 };
@@ -60,6 +60,19 @@ pub struct PackageLoader<'a, IO> {
     target: Target,
     stale_modules: &'a mut StaleTracker,
     already_defined_modules: &'a mut im::HashMap<EcoString, Utf8PathBuf>,
+    /// When set every module is recompiled, even if it has a valid cache.
+    /// This is needed by analyses that need the typed AST of every module.
+    recompile_cached: bool,
+    /// When set, and tests are included, a test module is generated for each
+    /// example in the documentation of the package's modules.
+    doc_tests: bool,
+    /// Modules that `gleam.toml` limits to another target. They are skipped,
+    /// along with the modules nested within them.
+    other_target_modules: &'a [EcoString],
+    /// The modules that were skipped as they are not built for the target,
+    /// including those of packages compiled before this one, so imports of
+    /// them can be reported.
+    skipped_modules: &'a mut im::HashSet<EcoString>,
 }
 
 impl<'a, IO> PackageLoader<'a, IO>
@@ -78,6 +91,10 @@ where
         package_name: &'a EcoString,
         stale_modules: &'a mut StaleTracker,
         already_defined_modules: &'a mut im::HashMap<EcoString, Utf8PathBuf>,
+        recompile_cached: bool,
+        doc_tests: bool,
+        other_target_modules: &'a [EcoString],
+        skipped_modules: &'a mut im::HashSet<EcoString>,
     ) -> Self {
         Self {
             io,
@@ -91,6 +108,10 @@ where
             artefact_directory,
             stale_modules,
             already_defined_modules,
+            recompile_cached,
+            doc_tests,
+            other_target_modules,
+            skipped_modules,
         }
     }
 
@@ -99,6 +120,7 @@ where
         // will check the mtimes and hashes of sources and caches to determine
         // which should be loaded.
         let mut inputs = self.read_sources_and_caches()?;
+        self.check_imports_are_built(&inputs)?;
 
         // Determine order in which modules are to be processed
         let deps = inputs
@@ -126,17 +148,28 @@ where
                 Input::New(module) => {
                     tracing::debug!(module = %module.name, "module_to_be_compiled");
                     self.stale_modules.add(module.name.clone());
-                    loaded.to_compile.push(module);
+                    loaded.to_compile.push(Input::New(module));
                 }
 
-                // A cached module with dependencies that are stale must be
-                // recompiled as the changes in the dependencies may have affect
-                // the output, making the cache invalid.
-                Input::Cached(info) if self.stale_modules.includes_any(&info.dependencies) => {
+                // All cached modules are recompiled if we've been asked to
+                // recompile everything.
+                Input::Cached(info) if self.recompile_cached => {
                     tracing::debug!(module = %info.name, "module_to_be_compiled");
                     self.stale_modules.add(info.name.clone());
                     let module = self.load_and_parse(info)?;
-                    loaded.to_compile.push(module);
+                    loaded.to_compile.push(Input::New(module));
+                }
+
+                // A cached module with dependencies that are stale must be
+                // recompiled if the changes in the dependencies changed their
+                // interfaces, as that may affect the output, making the cache
+                // invalid. This can only be known once the dependencies have
+                // been compiled, so the module is checked when it is reached
+                // during analysis.
+                Input::Cached(info) if self.stale_modules.includes_any(&info.dependencies) => {
+                    tracing::debug!(module = %info.name, "module_to_be_checked");
+                    self.stale_modules.add(info.name.clone());
+                    loaded.to_compile.push(Input::Cached(info));
                 }
 
                 // A cached module with no stale dependencies can be used as-is
@@ -152,13 +185,30 @@ where
         Ok(loaded)
     }
 
+    /// Modules that are built for the target can't import modules that are
+    /// only built for another target.
+    fn check_imports_are_built(&self, inputs: &HashMap<EcoString, Input>) -> Result<()> {
+        for (name, input) in inputs.iter().sorted_by_key(|(name, _)| *name) {
+            let skipped = input
+                .dependencies()
+                .into_iter()
+                .find(|dependency| self.skipped_modules.contains(dependency));
+            if let Some(imported) = skipped {
+                return Err(Error::ImportNotBuiltForTarget {
+                    module: name.clone(),
+                    imported,
+                    target: self.target,
+                });
+            }
+        }
+        Ok(())
+    }
+
     fn load_cached_module(&self, info: CachedModule) -> Result<type_::ModuleInterface, Error> {
-        let path = self
-            .artefact_directory
-            .join(info.name.replace("/", "@").as_ref())
-            .with_extension("cache");
-        let bytes = self.io.read_bytes(&path)?;
-        metadata::ModuleDecoder::new(self.ids.clone()).read(bytes.as_slice())
+        let (interface, _) =
+            load_cached_interface(&self.io, self.artefact_directory, &self.ids, &info.name)?;
+        emit_cached_warnings(&self.io, self.warnings, &info.source_path, info.warnings);
+        Ok(interface)
     }
 
     pub fn is_gleam_path(&self, path: &Utf8Path, dir: &Utf8Path) -> bool {
@@ -181,7 +231,7 @@ where
         )
     }
 
-    fn read_sources_and_caches(&self) -> Result<HashMap<EcoString, Input>> {
+    fn read_sources_and_caches(&mut self) -> Result<HashMap<EcoString, Input>> {
         let span = tracing::info_span!("load");
         let _enter = span.enter();
 
@@ -206,10 +256,41 @@ where
                 self.warnings.emit(crate::Warning::InvalidSource { path });
                 continue;
             }
+            let name = module_name(&src, &path);
+            if skip_for_other_target(self.other_target_modules, self.skipped_modules, name) {
+                continue;
+            }
             let input = loader.load(path)?;
             inputs.insert(input)?;
         }
 
+        // Doc tests
+        if self.doc_tests && self.mode.includes_tests() {
+            for path in self.io.gleam_source_files(&src) {
+                if !self.is_gleam_path(&path, &src) {
+                    continue;
+                }
+                let name = module_name(&src, &path);
+                if self.skipped_modules.contains(&name) {
+                    continue;
+                }
+                let code = self.io.read(&path)?;
+                let mtime = self.io.modification_time(&path)?;
+                for doc_test in doc_tests::doc_tests(&name, &code) {
+                    let module = parse_source(
+                        doc_test.code,
+                        self.target,
+                        Origin::Test,
+                        path.clone(),
+                        doc_test.name,
+                        self.package_name.clone(),
+                        mtime,
+                    )?;
+                    inputs.insert(Input::New(module))?;
+                }
+            }
+        }
+
         // Test
         if self.mode.includes_tests() {
             let test = self.root.join("test");
@@ -221,6 +302,10 @@ where
                     self.warnings.emit(crate::Warning::InvalidSource { path });
                     continue;
                 }
+                let name = module_name(&test, &path);
+                if skip_for_other_target(self.other_target_modules, self.skipped_modules, name) {
+                    continue;
+                }
                 let input = loader.load(path)?;
                 inputs.insert(input)?;
             }
@@ -243,6 +328,85 @@ where
     }
 }
 
+/// Reads the interface of a module from the cache, along with its
+/// fingerprint.
+pub(crate) fn load_cached_interface<IO>(
+    io: &IO,
+    artefact_directory: &Utf8Path,
+    ids: &UniqueIdGenerator,
+    name: &EcoString,
+) -> Result<(type_::ModuleInterface, InterfaceFingerprint)>
+where
+    IO: FileSystemReader,
+{
+    let path = artefact_directory
+        .join(name.replace("/", "@").as_ref())
+        .with_extension("cache");
+    let bytes = io.read_bytes(&path)?;
+    let interface = metadata::ModuleDecoder::new(ids.clone()).read(bytes.as_slice())?;
+    Ok((interface, InterfaceFingerprint::from_encoded(&bytes)))
+}
+
+/// Emits the warnings a module loaded from the cache had when it was type
+/// checked.
+/// Warnings are cached without the source code of their module, as it is the
+/// same as the one the cache was written for. It is read again to show the
+/// warnings, and if that fails they are shown without their location.
+pub(crate) fn warning_to_cache(mut diagnostic: Diagnostic) -> Diagnostic {
+    if let Some(location) = &mut diagnostic.location {
+        location.src = EcoString::new();
+    }
+    diagnostic
+}
+
+pub(crate) fn emit_cached_warnings<IO: FileSystemReader>(
+    io: &IO,
+    warnings: &WarningEmitter,
+    source_path: &Utf8Path,
+    diagnostics: Vec<Diagnostic>,
+) {
+    if diagnostics.is_empty() {
+        return;
+    }
+    let src = io.read(source_path).ok().map(EcoString::from);
+    for mut diagnostic in diagnostics {
+        match &src {
+            Some(src) => {
+                if let Some(location) = &mut diagnostic.location {
+                    location.src = src.clone();
+                }
+            }
+            None => diagnostic.location = None,
+        }
+        warnings.emit(crate::Warning::Cached { diagnostic });
+    }
+}
+
+/// Whether a module is limited to another target in `gleam.toml`, in which
+/// case it is recorded as skipped.
+fn skip_for_other_target(
+    other_target_modules: &[EcoString],
+    skipped_modules: &mut im::HashSet<EcoString>,
+    name: EcoString,
+) -> bool {
+    let skip = other_target_modules
+        .iter()
+        .any(|module| is_nested_within(&name, module));
+    if skip {
+        tracing::debug!(module = %name, "skipping_module_for_other_target");
+        _ = skipped_modules.insert(name);
+    }
+    skip
+}
+
+/// Whether a module is the given module or is nested within it, such as
+/// `wibble/wobble` within `wibble`.
+fn is_nested_within(module: &str, parent: &str) -> bool {
+    module
+        .strip_prefix(parent)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
 fn convert_deps_tree_error(e: dep_tree::Error) -> Error {
     match e {
         dep_tree::Error::Cycle(modules) => Error::ImportCycle { modules },
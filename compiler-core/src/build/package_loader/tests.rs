@@ -35,8 +35,10 @@ fn write_cache(fs: &InMemoryFileSystem, name: &str, seconds: u64, deps: Vec<EcoS
         mtime,
         codegen_performed: true,
         dependencies: deps,
+        dependency_interfaces: vec![],
         fingerprint: SourceFingerprint::new(src),
         line_numbers: line_numbers.clone(),
+        warnings: vec![],
     };
     let path = Utf8Path::new("/artefact").join(format!("{name}.cache_meta"));
     fs.write_bytes(&path, &cache_metadata.to_binary()).unwrap();
@@ -64,6 +66,25 @@ fn write_cache(fs: &InMemoryFileSystem, name: &str, seconds: u64, deps: Vec<EcoS
 }
 
 fn run_loader(fs: InMemoryFileSystem, root: &Utf8Path, artefact: &Utf8Path) -> LoaderTestOutput {
+    run_loader_with_options(fs, root, artefact, false)
+}
+
+fn run_loader_with_options(
+    fs: InMemoryFileSystem,
+    root: &Utf8Path,
+    artefact: &Utf8Path,
+    recompile_cached: bool,
+) -> LoaderTestOutput {
+    load(fs, root, artefact, recompile_cached, &[]).unwrap()
+}
+
+fn load(
+    fs: InMemoryFileSystem,
+    root: &Utf8Path,
+    artefact: &Utf8Path,
+    recompile_cached: bool,
+    other_target_modules: &[EcoString],
+) -> Result<LoaderTestOutput> {
     let mut defined = im::HashMap::new();
     let ids = UniqueIdGenerator::new();
     let (emitter, warnings) = WarningEmitter::vector();
@@ -80,14 +101,22 @@ fn run_loader(fs: InMemoryFileSystem, root: &Utf8Path, artefact: &Utf8Path) -> L
         target: Target::JavaScript,
         stale_modules: &mut StaleTracker::default(),
         already_defined_modules: &mut defined,
+        recompile_cached,
+        doc_tests: false,
+        other_target_modules,
+        skipped_modules: &mut im::HashSet::new(),
     };
-    let loaded = loader.run().unwrap();
-
-    LoaderTestOutput {
-        to_compile: loaded.to_compile.into_iter().map(|m| m.name).collect(),
+    let loaded = loader.run()?;
+
+    Ok(LoaderTestOutput {
+        to_compile: loaded
+            .to_compile
+            .into_iter()
+            .map(|m| m.name().clone())
+            .collect(),
         cached: loaded.cached.into_iter().map(|m| m.name).collect(),
         warnings: warnings.take(),
-    }
+    })
 }
 
 #[test]
@@ -163,6 +192,20 @@ fn reading_cache() {
     assert_eq!(loaded.cached, vec![EcoString::from("one")]);
 }
 
+#[test]
+fn cache_is_ignored_when_recompiling_everything() {
+    let fs = InMemoryFileSystem::new();
+    let root = Utf8Path::new("/");
+    let artefact = Utf8Path::new("/artefact");
+
+    write_src(&fs, "/src/one.gleam", 0, TEST_SOURCE_1);
+    write_cache(&fs, "one", 0, vec![], TEST_SOURCE_1);
+
+    let loaded = run_loader_with_options(fs, root, artefact, true);
+    assert_eq!(loaded.to_compile, vec![EcoString::from("one")]);
+    assert!(loaded.cached.is_empty());
+}
+
 #[test]
 fn module_is_stale_if_cache_older() {
     let fs = InMemoryFileSystem::new();
@@ -177,6 +220,68 @@ fn module_is_stale_if_cache_older() {
     assert!(loaded.cached.is_empty());
 }
 
+#[test]
+fn warnings_of_cached_modules_are_emitted() {
+    let fs = InMemoryFileSystem::new();
+    let root = Utf8Path::new("/");
+    let artefact = Utf8Path::new("/artefact");
+
+    write_src(&fs, "/src/one.gleam", 0, TEST_SOURCE_1);
+    write_cache(&fs, "one", 0, vec![], TEST_SOURCE_1);
+
+    let diagnostic = Diagnostic {
+        title: "Unused variable".into(),
+        text: "".into(),
+        level: crate::diagnostic::Level::Warning,
+        location: None,
+        hint: None,
+        code: Some(crate::diagnostic::Code::Warning(100)),
+    };
+    let path = Utf8Path::new("/artefact/one.cache_meta");
+    let mut cache_metadata = CacheMetadata::from_binary(&fs.read_bytes(path).unwrap()).unwrap();
+    cache_metadata.warnings = vec![diagnostic.clone()];
+    fs.write_bytes(path, &cache_metadata.to_binary()).unwrap();
+
+    let loaded = run_loader(fs, root, artefact);
+    assert_eq!(loaded.cached, vec![EcoString::from("one")]);
+    assert_eq!(loaded.warnings, vec![Warning::Cached { diagnostic }]);
+}
+
+#[test]
+fn source_of_cached_warnings_is_read_again() {
+    let fs = InMemoryFileSystem::new();
+    let root = Utf8Path::new("/");
+    let artefact = Utf8Path::new("/artefact");
+
+    write_src(&fs, "/src/one.gleam", 0, TEST_SOURCE_1);
+    write_cache(&fs, "one", 0, vec![], TEST_SOURCE_1);
+
+    let location = crate::diagnostic::Location {
+        src: TEST_SOURCE_1.into(),
+        path: "/src/one.gleam".into(),
+        label: crate::diagnostic::Label {
+            text: None,
+            span: crate::ast::SrcSpan::new(6, 7),
+        },
+        extra_labels: vec![],
+    };
+    let diagnostic = Diagnostic {
+        title: "Unused variable".into(),
+        text: "".into(),
+        level: crate::diagnostic::Level::Warning,
+        location: Some(location),
+        hint: None,
+        code: Some(crate::diagnostic::Code::Warning(113)),
+    };
+    let path = Utf8Path::new("/artefact/one.cache_meta");
+    let mut cache_metadata = CacheMetadata::from_binary(&fs.read_bytes(path).unwrap()).unwrap();
+    cache_metadata.warnings = vec![warning_to_cache(diagnostic.clone())];
+    fs.write_bytes(path, &cache_metadata.to_binary()).unwrap();
+
+    let loaded = run_loader(fs, root, artefact);
+    assert_eq!(loaded.warnings, vec![Warning::Cached { diagnostic }]);
+}
+
 #[test]
 fn module_is_stale_if_deps_are_stale() {
     let fs = InMemoryFileSystem::new();
@@ -282,3 +387,41 @@ fn invalid_nested_module_name_in_test() {
         }],
     );
 }
+
+#[test]
+fn modules_for_other_targets_are_skipped() {
+    let fs = InMemoryFileSystem::new();
+    let root = Utf8Path::new("/");
+    let artefact = Utf8Path::new("/artefact");
+
+    write_src(&fs, "/src/main.gleam", 0, "const x = 1");
+    write_src(&fs, "/src/erl.gleam", 0, "const x = 1");
+    write_src(&fs, "/src/erl/ffi.gleam", 0, "const x = 1");
+    write_src(&fs, "/src/erlang.gleam", 0, "const x = 1");
+
+    let loaded = load(fs, root, artefact, false, &["erl".into()]).unwrap();
+    assert_eq!(
+        loaded.to_compile.into_iter().sorted().collect_vec(),
+        vec![EcoString::from("erlang"), EcoString::from("main")]
+    );
+}
+
+#[test]
+fn importing_a_module_for_another_target() {
+    let fs = InMemoryFileSystem::new();
+    let root = Utf8Path::new("/");
+    let artefact = Utf8Path::new("/artefact");
+
+    write_src(&fs, "/src/main.gleam", 0, "import erl/ffi");
+    write_src(&fs, "/src/erl/ffi.gleam", 0, "const x = 1");
+
+    let error = load(fs, root, artefact, false, &["erl".into()]).unwrap_err();
+    assert_eq!(
+        error,
+        Error::ImportNotBuiltForTarget {
+            module: "main".into(),
+            imported: "erl/ffi".into(),
+            target: Target::JavaScript,
+        }
+    );
+}
@@ -2,20 +2,22 @@ use crate::{
     analyse::TargetSupport,
     build::{
         package_compiler, package_compiler::PackageCompiler, package_loader::StaleTracker,
-        project_compiler, telemetry::Telemetry, Mode, Module, Origin, Package, Target,
+        project_compiler, telemetry::Telemetry, CancellationToken, Mode, Module, Origin, Package,
+        Phase, Target, Timings, WorkItem,
     },
     codegen::{self, ErlangApp},
-    config::PackageConfig,
+    config::{Dependencies, DependencyWarnings, PackageConfig},
     dep_tree,
     error::{FileIoAction, FileKind},
     io::{CommandExecutor, FileSystemReader, FileSystemWriter, Stdio},
+    line_numbers::LineNumbers,
     manifest::{ManifestPackage, ManifestPackageSource},
     metadata,
     paths::{self, ProjectPaths},
-    type_::{self, ModuleFunction},
+    type_::{self, ModuleFunction, PRELUDE_MODULE_NAME},
     uid::UniqueIdGenerator,
     version::COMPILER_VERSION,
-    warning::{self, WarningEmitter, WarningEmitterIO},
+    warning::{self, TypeWarningEmitter, WarningEmitter, WarningEmitterIO},
     Error, Result, Warning,
 };
 use ecow::EcoString;
@@ -51,6 +53,9 @@ pub struct Options {
     pub codegen: Codegen,
     pub warnings_as_errors: bool,
     pub root_target_support: TargetSupport,
+    /// Whether to compile the examples in the documentation of the root
+    /// package as test modules, so they can be run as doc tests.
+    pub doc_tests: bool,
 }
 
 #[derive(Debug)]
@@ -64,16 +69,29 @@ impl Built {
     pub fn get_main_function(
         &self,
         module: &EcoString,
+        function: &str,
         target: Target,
     ) -> Result<ModuleFunction, Error> {
         match self.module_interfaces.get(module) {
-            Some(module_data) => module_data.get_main_function(target),
+            Some(module_data) => module_data.get_main_function(function, target),
             None => Err(Error::ModuleDoesNotExist {
                 module: module.clone(),
                 suggestion: None,
             }),
         }
     }
+
+    /// Returns the work items in a module of the root package, see
+    /// `Module::work_items`.
+    pub fn work_items(&self, module: &Module) -> Vec<WorkItem> {
+        module.work_items(&self.module_interfaces)
+    }
+
+    /// The interfaces of every module that was compiled, which other modules
+    /// can be type checked against.
+    pub fn module_interfaces(&self) -> &im::HashMap<EcoString, type_::ModuleInterface> {
+        &self.module_interfaces
+    }
 }
 
 #[derive(Debug)]
@@ -83,6 +101,9 @@ pub struct ProjectCompiler<IO> {
     pub(crate) packages: HashMap<String, ManifestPackage>,
     importable_modules: im::HashMap<EcoString, type_::ModuleInterface>,
     defined_modules: im::HashMap<EcoString, Utf8PathBuf>,
+    /// Modules that are not built for the target, as `gleam.toml` limits them
+    /// or the package they belong to to another target.
+    skipped_modules: im::HashSet<EcoString>,
     stale_modules: StaleTracker,
     warnings: WarningEmitter,
     telemetry: Box<dyn Telemetry>,
@@ -93,6 +114,15 @@ pub struct ProjectCompiler<IO> {
     /// We may want to silence subprocess stdout if we are running in LSP mode.
     /// The language server talks over stdio so printing would break that.
     pub subprocess_stdio: Stdio,
+    /// Where the time taken by each phase of compiling each package is
+    /// recorded.
+    pub timings: Timings,
+    /// Whether to type check every module of the root package again, even
+    /// those with a valid cache, so the typed AST of all of them is returned.
+    pub recompile_root_package: bool,
+    /// Stops the compilation between modules when cancelled, for the
+    /// language server to drop a compilation of code that has been edited.
+    pub cancellation: CancellationToken,
 }
 
 // TODO: test that tests cannot be imported into src
@@ -119,10 +149,14 @@ where
         Self {
             importable_modules: im::HashMap::new(),
             defined_modules: im::HashMap::new(),
+            skipped_modules: im::HashSet::new(),
             stale_modules: StaleTracker::default(),
             ids: UniqueIdGenerator::new(),
             warnings: WarningEmitter::new(warning_emitter),
             subprocess_stdio: Stdio::Inherit,
+            timings: Timings::disabled(),
+            recompile_root_package: false,
+            cancellation: CancellationToken::new(),
             telemetry,
             packages,
             options,
@@ -169,6 +203,14 @@ where
 
         let root_package = self.compile_root_package()?;
 
+        // Warnings of categories set to `deny` fail the build even without
+        // the `--warnings-as-errors` flag.
+        if self.warnings.denied_count() > 0 {
+            return Err(Error::DeniedWarnings {
+                count: self.warnings.denied_count(),
+            });
+        }
+
         // TODO: test
         if self.options.warnings_as_errors && self.warnings.count() > 0 {
             return Err(Error::ForbiddenWarnings {
@@ -189,6 +231,115 @@ where
         Ok(Package { config, modules })
     }
 
+    /// Type checks a module of the root package that has syntax errors, using
+    /// whatever parts of it could be parsed. This lets the language server
+    /// carry on providing information about a module while it is being
+    /// edited.
+    ///
+    /// The module's dependencies must have already been compiled, and the
+    /// returned module must not be used for code generation.
+    ///
+    pub fn recover_module(&self, path: &Utf8Path, code: EcoString) -> Option<Module> {
+        let (origin, directory) = if path.starts_with(self.paths.test_directory()) {
+            (Origin::Test, self.paths.test_directory())
+        } else {
+            (Origin::Src, self.paths.src_directory())
+        };
+        let name = package_compiler::module_name(&directory, path);
+        let direct_dependencies = self.config.dependencies_for(self.mode()).ok()?;
+        self.analyse_module(
+            path,
+            code,
+            name,
+            origin,
+            &self.config,
+            &direct_dependencies,
+            self.options.root_target_support,
+        )
+    }
+
+    /// Type checks a module of a Gleam dependency package from its source, so
+    /// the language server can provide information about the dependency code
+    /// it navigates to. Compiled dependencies are loaded from the build cache,
+    /// which doesn't have their typed AST.
+    ///
+    /// Returns `None` if the path is not of a module in the `src` directory of
+    /// a dependency. As with `recover_module`, the dependencies must have
+    /// already been compiled and the module must not be used for code
+    /// generation.
+    ///
+    pub fn analyse_dependency_module(&self, path: &Utf8Path) -> Option<Module> {
+        let package_root = self
+            .packages
+            .values()
+            .filter_map(|package| self.package_root(package).ok())
+            .find(|root| path.starts_with(root.join("src")))?;
+        let config = PackageConfig::read(package_root.join("gleam.toml"), &self.io).ok()?;
+        let name = package_compiler::module_name(&package_root.join("src"), path);
+        let code: EcoString = self.io.read(path).ok()?.into();
+        let direct_dependencies = config.dependencies_for(Mode::Prod).ok()?;
+        self.analyse_module(
+            path,
+            code,
+            name,
+            Origin::Src,
+            &config,
+            &direct_dependencies,
+            TargetSupport::NotEnforced,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn analyse_module(
+        &self,
+        path: &Utf8Path,
+        code: EcoString,
+        name: EcoString,
+        origin: Origin,
+        config: &PackageConfig,
+        direct_dependencies: &Dependencies,
+        target_support: TargetSupport,
+    ) -> Option<Module> {
+        let (parsed, _errors) = crate::parse::parse_module_with_recovery(&code);
+        let mut ast = parsed.module;
+        ast.name = name.clone();
+        let target = self.target();
+        let dependencies = ast.dependencies(target);
+
+        // The prelude is only registered once a package has been analysed,
+        // which may not have happened yet if this is the first module.
+        let mut modules = self.importable_modules.clone();
+        if !modules.contains_key(PRELUDE_MODULE_NAME) {
+            let _ = modules.insert(PRELUDE_MODULE_NAME.into(), type_::build_prelude(&self.ids));
+        }
+
+        let ast = crate::analyse::infer_module(
+            target,
+            &self.ids,
+            ast,
+            origin,
+            &modules,
+            &TypeWarningEmitter::null(),
+            direct_dependencies,
+            target_support,
+            LineNumbers::new(&code),
+            config,
+            path.to_path_buf(),
+        )
+        .ok()?;
+
+        Some(Module {
+            mtime: self.io.modification_time(path).ok()?,
+            input_path: path.to_path_buf(),
+            extra: parsed.extra,
+            dependencies,
+            origin,
+            name,
+            code,
+            ast,
+        })
+    }
+
     /// Checks that version file found in the build directory matches the
     /// current version of gleam. If not, we will clear the build directory
     /// before continuing. This will ensure that upgrading gleam will not leave
@@ -226,6 +377,10 @@ where
         let mut modules = vec![];
 
         for name in sequence {
+            if !self.config.builds_dependency_for(&name, self.target()) {
+                self.skip_package(&name);
+                continue;
+            }
             let compiled = self.load_cache_or_compile_package(&name)?;
             modules.extend(compiled);
         }
@@ -233,6 +388,23 @@ where
         Ok(modules)
     }
 
+    /// Records the modules of a dependency that `gleam.toml` limits to
+    /// another target as skipped, so importing them from a module that is
+    /// built for this target can be reported.
+    fn skip_package(&mut self, name: &str) {
+        tracing::debug!(package = %name, "skipping_package_for_other_target");
+        let package = self.packages.get(name).expect("Missing package");
+        let Ok(package_root) = self.package_root(package) else {
+            return;
+        };
+        let src = package_root.join("src");
+        for path in self.io.gleam_source_files(&src) {
+            _ = self
+                .skipped_modules
+                .insert(package_compiler::module_name(&src, &path));
+        }
+    }
+
     fn write_prelude(&self) -> Result<()> {
         // Only the JavaScript target has a prelude to write.
         if !self.target().is_javascript() {
@@ -265,7 +437,9 @@ where
         // packages into their own classes and then only mutate self after we no
         // longer need to have the package borrowed from self.packages.
         let package = self.packages.get(name).expect("Missing package").clone();
-        let result = match usable_build_tools(&package)?.as_slice() {
+        let build_tools = usable_build_tools(&package)?;
+        let start = Instant::now();
+        let result = match build_tools.as_slice() {
             &[BuildTool::Gleam] => self.compile_gleam_dep_package(&package),
             &[BuildTool::Rebar3] => self.compile_rebar3_dep_package(&package).map(|_| vec![]),
             &[BuildTool::Mix] => self.compile_mix_dep_package(&package).map(|_| vec![]),
@@ -280,6 +454,11 @@ where
                 })
             }
         };
+        // The phases of compiling Gleam packages are recorded as they go.
+        if build_tools != [BuildTool::Gleam] {
+            self.timings
+                .record(Phase::BuildTool, &package.name, None, start);
+        }
 
         // TODO: test. This one is not covered by the integration tests.
         if result.is_err() {
@@ -469,6 +648,25 @@ where
         package: &ManifestPackage,
     ) -> Result<Vec<Module>, Error> {
         // TODO: Test
+        let package_root = self.package_root(package)?;
+        let config_path = package_root.join("gleam.toml");
+        let config = PackageConfig::read(config_path, &self.io)?;
+        self.compile_gleam_package(&config, false, package_root)
+    }
+
+    /// The directories of the dependency packages that are local paths rather
+    /// than downloaded, whose source can change while the project is being
+    /// worked on.
+    pub fn local_package_roots(&self) -> Vec<Utf8PathBuf> {
+        self.packages
+            .values()
+            .filter(|package| matches!(package.source, ManifestPackageSource::Local { .. }))
+            .filter_map(|package| self.package_root(package).ok())
+            .collect()
+    }
+
+    /// The directory the source of a dependency package is in.
+    fn package_root(&self, package: &ManifestPackage) -> Result<Utf8PathBuf, Error> {
         let package_root = match &package.source {
             // If the path is relative it is relative to the root of the
             // project, not to the current working directory. The language server
@@ -487,9 +685,7 @@ where
                 self.paths.build_packages_package(&package.name)
             }
         };
-        let config_path = package_root.join("gleam.toml");
-        let config = PackageConfig::read(config_path, &self.io)?;
-        self.compile_gleam_package(&config, false, package_root)
+        Ok(package_root)
     }
 
     fn compile_gleam_package(
@@ -525,6 +721,7 @@ where
 
             Target::JavaScript => super::TargetCodegenConfiguration::JavaScript {
                 emit_typescript_definitions: self.config.javascript.typescript_declarations,
+                emit_source_maps: self.config.javascript.source_maps,
                 // This path is relative to each package output directory
                 prelude_location: Utf8PathBuf::from("../prelude.mjs"),
             },
@@ -545,6 +742,13 @@ where
         compiler.perform_codegen = self.options.codegen.should_codegen(is_root);
         compiler.compile_beam_bytecode = self.options.codegen.should_codegen(is_root);
         compiler.subprocess_stdio = self.subprocess_stdio;
+        compiler.detect_dead_code = is_root && config.warnings.dead_code;
+        compiler.recompile_cached = is_root && self.recompile_root_package;
+        // Debug printing can be left out of production builds.
+        compiler.remove_echoes = config.remove_echo && self.mode() == Mode::Prod;
+        compiler.doc_tests = is_root && self.options.doc_tests;
+        compiler.timings = self.timings.clone();
+        compiler.cancellation = self.cancellation.clone();
         compiler.target_support = if is_root {
             // When compiling the root package it is context specific as to whether we need to
             // enforce that all functions have an implementation for the current target.
@@ -560,17 +764,54 @@ where
             TargetSupport::NotEnforced
         };
 
+        // Warnings from dependencies are collected separately unless they
+        // are to be shown, as the programmer can't do anything about them.
+        // The levels of the categories of warnings only apply to the root
+        // package.
+        let dependency_warnings = self.dependency_warnings();
+        let (warnings, collected_warnings) = if is_root {
+            (self.warnings.with_levels(config.warnings.clone()), None)
+        } else {
+            match dependency_warnings {
+                DependencyWarnings::Show => (self.warnings.clone(), None),
+                DependencyWarnings::Summarise | DependencyWarnings::Silence => {
+                    let (warnings, collected) = WarningEmitter::vector();
+                    (warnings, Some(collected))
+                }
+            }
+        };
+
         // Compile project to Erlang or JavaScript source code
         let compiled = compiler.compile(
-            &mut self.warnings,
+            &warnings,
             &mut self.importable_modules,
             &mut self.defined_modules,
+            &mut self.skipped_modules,
             &mut self.stale_modules,
             self.telemetry.as_ref(),
         )?;
 
+        let count = collected_warnings.map_or(0, |collected| collected.take().len());
+        if dependency_warnings == DependencyWarnings::Summarise && count > 0 {
+            self.warnings.emit(Warning::DependencyWarnings {
+                package: config.name.clone(),
+                count,
+            });
+        }
+
         Ok(compiled)
     }
+
+    /// How warnings from dependency packages are reported. Unless configured
+    /// otherwise the language server doesn't report them, as they can't be
+    /// fixed in the editor.
+    fn dependency_warnings(&self) -> DependencyWarnings {
+        match (self.config.warnings.dependencies, self.mode()) {
+            (Some(dependency_warnings), _) => dependency_warnings,
+            (None, Mode::Lsp) => DependencyWarnings::Silence,
+            (None, Mode::Dev | Mode::Prod) => DependencyWarnings::Show,
+        }
+    }
 }
 
 fn order_packages(packages: &HashMap<String, ManifestPackage>) -> Result<Vec<EcoString>, Error> {
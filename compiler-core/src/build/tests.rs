@@ -1,7 +1,56 @@
-use crate::{manifest::ManifestPackage, Error};
+use crate::{
+    build::{
+        CancellationToken, Mode, Module, NullTelemetry, PackageCompiler, StaleTracker,
+        TargetCodegenConfiguration,
+    },
+    config::PackageConfig,
+    io::{memory::InMemoryFileSystem, FileSystemWriter},
+    manifest::ManifestPackage,
+    type_,
+    uid::UniqueIdGenerator,
+    warning::WarningEmitter,
+    Error,
+};
+use camino::Utf8PathBuf;
+use ecow::EcoString;
 
 use super::project_compiler::{usable_build_tools, BuildTool};
 
+/// Compiles the package in the file system, rooted at `/` and built into
+/// `/build`, returning the compiled modules and the interfaces of all the
+/// modules. Metadata, code and native files are not written unless the
+/// compiler is configured to.
+pub(crate) fn compile_test_package(
+    config: PackageConfig,
+    mode: Mode,
+    fs: InMemoryFileSystem,
+    warnings: &WarningEmitter,
+    configure: impl FnOnce(&mut PackageCompiler<'_, InMemoryFileSystem>),
+) -> Result<(Vec<Module>, im::HashMap<EcoString, type_::ModuleInterface>), Error> {
+    let ids = UniqueIdGenerator::new();
+    let target = TargetCodegenConfiguration::Erlang { app_file: None };
+    let root = Utf8PathBuf::from("/");
+    let build = root.join("build");
+    let lib = root.join("lib");
+    let mut compiler = PackageCompiler::new(&config, mode, &root, &build, &lib, &target, ids, fs);
+    compiler.write_metadata = false;
+    compiler.perform_codegen = false;
+    compiler.compile_beam_bytecode = false;
+    compiler.copy_native_files = false;
+    configure(&mut compiler);
+
+    let mut interfaces = im::HashMap::new();
+    let compiled = compiler.compile(
+        warnings,
+        &mut interfaces,
+        &mut im::HashMap::new(),
+        &mut im::HashSet::new(),
+        &mut StaleTracker::default(),
+        &NullTelemetry,
+    )?;
+    Ok((compiled, interfaces))
+}
+
 #[test]
 fn usable_build_tool_unknown() {
     assert_eq!(
@@ -55,3 +104,20 @@ fn usable_build_tool_mix_then_rebar3() {
         Ok(vec![BuildTool::Mix, BuildTool::Rebar3])
     )
 }
+
+#[test]
+fn cancelled_compilation_stops_before_analysis() {
+    let fs = InMemoryFileSystem::new();
+    fs.write(&Utf8PathBuf::from("/src/app.gleam"), "pub fn main() { 1 }")
+        .expect("should write module");
+    let mut config = PackageConfig::default();
+    config.name = "app".into();
+    let cancellation = CancellationToken::new();
+    cancellation.cancel();
+
+    let (warnings, _) = WarningEmitter::vector();
+    let result = compile_test_package(config, Mode::Dev, fs, &warnings, |compiler| {
+        compiler.cancellation = cancellation
+    });
+    assert_eq!(result.map(|_| ()), Err(Error::Cancelled));
+}
@@ -0,0 +1,248 @@
+//! Recording how long each phase of a build takes, for each package and for
+//! the modules type checked, so `gleam build --timings` can show where the
+//! time building a project goes.
+//!
+//! The timings can be printed as a summary, or written as a trace in the
+//! Chrome trace event format to be explored with `chrome://tracing` or
+//! Perfetto. Modules are type checked in parallel, so the timings record
+//! which thread they were taken on to show them alongside each other.
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    sync::{Arc, Mutex},
+    thread::ThreadId,
+    time::{Duration, Instant},
+};
+
+use ecow::EcoString;
+use itertools::Itertools;
+
+/// How many packages and modules the summary lists.
+const SUMMARY_LENGTH: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Phase {
+    /// Downloading the dependency packages of the project.
+    Download,
+    /// Reading and parsing the modules of a package, or loading them from
+    /// the cache.
+    Parse,
+    /// Type checking the modules of a package.
+    Analysis,
+    /// Generating Erlang or JavaScript and writing it to the build directory.
+    Codegen,
+    /// Compiling the generated Erlang to BEAM bytecode.
+    ErlangCompile,
+    /// Building a package that uses rebar3 or Mix rather than Gleam.
+    BuildTool,
+}
+
+impl Phase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::Download => "download",
+            Phase::Parse => "parse",
+            Phase::Analysis => "analysis",
+            Phase::Codegen => "codegen",
+            Phase::ErlangCompile => "erlang compile",
+            Phase::BuildTool => "build tool",
+        }
+    }
+}
+
+/// The time taken by a phase of the build of a package, or of one of its
+/// modules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timing {
+    pub phase: Phase,
+    pub package: EcoString,
+    /// The module, if this is the time taken by a single module rather than
+    /// the whole package.
+    pub module: Option<EcoString>,
+    /// When the phase started, relative to when the recording started.
+    pub start: Duration,
+    pub duration: Duration,
+    /// The thread the phase ran on, numbered in the order they first
+    /// recorded a timing.
+    pub thread: usize,
+}
+
+/// Where the timings of a build are recorded. Timings are only recorded if
+/// they have been enabled, and clones record to the same place.
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    recording: Option<Arc<Recording>>,
+}
+
+#[derive(Debug)]
+struct Recording {
+    start: Instant,
+    state: Mutex<RecordingState>,
+}
+
+#[derive(Debug, Default)]
+struct RecordingState {
+    timings: Vec<Timing>,
+    threads: HashMap<ThreadId, usize>,
+}
+
+impl Timings {
+    pub fn enabled() -> Self {
+        Self {
+            recording: Some(Arc::new(Recording {
+                start: Instant::now(),
+                state: Mutex::new(RecordingState::default()),
+            })),
+        }
+    }
+
+    /// Timings that record nothing.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Records that the phase of the package, or of one of its modules, ran
+    /// from `start` until now.
+    pub fn record(&self, phase: Phase, package: &str, module: Option<&str>, start: Instant) {
+        let Some(recording) = &self.recording else {
+            return;
+        };
+        let duration = start.elapsed();
+        let mut state = recording.state.lock().expect("Timings lock poisoned");
+        let next_thread = state.threads.len();
+        let thread = *state
+            .threads
+            .entry(std::thread::current().id())
+            .or_insert(next_thread);
+        state.timings.push(Timing {
+            phase,
+            package: package.into(),
+            module: module.map(EcoString::from),
+            start: start.saturating_duration_since(recording.start),
+            duration,
+            thread,
+        });
+    }
+
+    /// The timings recorded so far, in the order they were recorded.
+    pub fn timings(&self) -> Vec<Timing> {
+        match &self.recording {
+            Some(recording) => recording
+                .state
+                .lock()
+                .expect("Timings lock poisoned")
+                .timings
+                .clone(),
+            None => vec![],
+        }
+    }
+}
+
+/// A table of the time taken by each phase, followed by the packages and the
+/// modules that took the longest.
+pub fn summary(timings: &[Timing]) -> String {
+    let (package_timings, module_timings): (Vec<_>, Vec<_>) =
+        timings.iter().partition(|timing| timing.module.is_none());
+
+    let phases = package_timings
+        .iter()
+        .into_grouping_map_by(|timing| timing.phase)
+        .fold(Duration::ZERO, |total, _, timing| total + timing.duration)
+        .into_iter()
+        .sorted()
+        .map(|(phase, total)| [phase.as_str().to_string(), seconds(total)])
+        .collect_vec();
+    let total: Duration = package_timings.iter().map(|timing| timing.duration).sum();
+
+    let packages = package_timings
+        .iter()
+        .into_grouping_map_by(|timing| timing.package.clone())
+        .fold(Duration::ZERO, |total, _, timing| total + timing.duration)
+        .into_iter()
+        .sorted_by(|(a_name, a), (b_name, b)| b.cmp(a).then_with(|| a_name.cmp(b_name)))
+        .take(SUMMARY_LENGTH)
+        .map(|(package, total)| [package.to_string(), seconds(total)])
+        .collect_vec();
+
+    let modules = module_timings
+        .iter()
+        .filter(|timing| timing.phase == Phase::Analysis)
+        .sorted_by(|a, b| {
+            b.duration
+                .cmp(&a.duration)
+                .then_with(|| a.module.cmp(&b.module))
+        })
+        .take(SUMMARY_LENGTH)
+        .map(|timing| {
+            let module = timing.module.clone().unwrap_or_default();
+            [module.to_string(), seconds(timing.duration)]
+        })
+        .collect_vec();
+
+    let mut summary = String::new();
+    let rows = phases
+        .into_iter()
+        .chain(std::iter::once(["total".into(), seconds(total)]));
+    write_table(&mut summary, ["Phase", "Time"], rows);
+    if !packages.is_empty() {
+        summary.push('\n');
+        write_table(&mut summary, ["Package", "Time"], packages);
+    }
+    if !modules.is_empty() {
+        summary.push('\n');
+        write_table(&mut summary, ["Module", "Analysis"], modules);
+    }
+    summary
+}
+
+/// The timings as a trace in the Chrome trace event format.
+pub fn chrome_trace(timings: &[Timing]) -> String {
+    let events = timings
+        .iter()
+        .map(|timing| {
+            let subject = timing.module.as_ref().unwrap_or(&timing.package);
+            serde_json::json!({
+                "name": format!("{} {subject}", timing.phase.as_str()),
+                "cat": timing.phase.as_str(),
+                "ph": "X",
+                "ts": timing.start.as_micros() as u64,
+                "dur": timing.duration.as_micros() as u64,
+                "pid": 0,
+                "tid": timing.thread,
+                "args": {
+                    "package": timing.package,
+                    "module": timing.module,
+                },
+            })
+        })
+        .collect_vec();
+    serde_json::json!({
+        "traceEvents": events,
+        "displayTimeUnit": "ms",
+    })
+    .to_string()
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.3}s", duration.as_secs_f64())
+}
+
+/// Writes the rows aligned in columns, with the times aligned to the right.
+fn write_table(out: &mut String, header: [&str; 2], rows: impl IntoIterator<Item = [String; 2]>) {
+    let rows = std::iter::once(header.map(String::from))
+        .chain(rows)
+        .collect_vec();
+    let name_width = rows.iter().map(|[name, _]| name.len()).max().unwrap_or(0);
+    let time_width = rows.iter().map(|[_, time]| time.len()).max().unwrap_or(0);
+    for [name, time] in rows {
+        let _ = writeln!(out, "{name:name_width$}  {time:>time_width$}");
+    }
+}
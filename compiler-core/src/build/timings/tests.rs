@@ -0,0 +1,125 @@
+use std::time::{Duration, Instant};
+
+use super::{chrome_trace, summary, Phase, Timing, Timings};
+
+fn timing(phase: Phase, package: &str, module: Option<&str>, start: u64, duration: u64) -> Timing {
+    Timing {
+        phase,
+        package: package.into(),
+        module: module.map(Into::into),
+        start: Duration::from_millis(start),
+        duration: Duration::from_millis(duration),
+        thread: 0,
+    }
+}
+
+#[test]
+fn disabled_timings_record_nothing() {
+    let timings = Timings::disabled();
+    timings.record(Phase::Parse, "wibble", None, Instant::now());
+    assert_eq!(timings.timings(), vec![]);
+}
+
+#[test]
+fn timings_are_recorded_by_clones() {
+    let timings = Timings::enabled();
+    timings.clone().record(
+        Phase::Analysis,
+        "wibble",
+        Some("wibble/wobble"),
+        Instant::now(),
+    );
+    let recorded = timings
+        .timings()
+        .into_iter()
+        .map(|timing| (timing.phase, timing.package, timing.module))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        recorded,
+        vec![(
+            Phase::Analysis,
+            "wibble".into(),
+            Some("wibble/wobble".into())
+        )]
+    );
+}
+
+#[test]
+fn summary_of_phases_packages_and_modules() {
+    let timings = [
+        timing(Phase::Download, "app", None, 0, 1200),
+        timing(Phase::Parse, "gleam_stdlib", None, 1200, 30),
+        timing(
+            Phase::Analysis,
+            "gleam_stdlib",
+            Some("gleam/list"),
+            1230,
+            250,
+        ),
+        timing(Phase::Analysis, "gleam_stdlib", Some("gleam/int"), 1230, 40),
+        timing(Phase::Analysis, "gleam_stdlib", None, 1230, 260),
+        timing(Phase::Codegen, "gleam_stdlib", None, 1490, 100),
+        timing(Phase::ErlangCompile, "gleam_stdlib", None, 1590, 2000),
+        timing(Phase::Parse, "app", None, 3590, 5),
+        timing(Phase::Analysis, "app", Some("app"), 3595, 10),
+        timing(Phase::Analysis, "app", None, 3595, 10),
+    ];
+    assert_eq!(
+        summary(&timings),
+        "\
+Phase             Time
+download        1.200s
+parse           0.035s
+analysis        0.270s
+codegen         0.100s
+erlang compile  2.000s
+total           3.605s
+
+Package         Time
+gleam_stdlib  2.390s
+app           1.215s
+
+Module      Analysis
+gleam/list    0.250s
+gleam/int     0.040s
+app           0.010s
+"
+    );
+}
+
+#[test]
+fn chrome_trace_events() {
+    let timings = [
+        timing(Phase::Analysis, "app", None, 1, 20),
+        timing(Phase::Analysis, "app", Some("app/wibble"), 2, 10),
+    ];
+    let trace: serde_json::Value = serde_json::from_str(&chrome_trace(&timings)).unwrap();
+    assert_eq!(
+        trace,
+        serde_json::json!({
+            "traceEvents": [
+                {
+                    "name": "analysis app",
+                    "cat": "analysis",
+                    "ph": "X",
+                    "ts": 1000,
+                    "dur": 20000,
+                    "pid": 0,
+                    "tid": 0,
+                    "args": { "package": "app", "module": null },
+                },
+                {
+                    "name": "analysis app/wibble",
+                    "cat": "analysis",
+                    "ph": "X",
+                    "ts": 2000,
+                    "dur": 10000,
+                    "pid": 0,
+                    "tid": 0,
+                    "args": { "package": "app", "module": "app/wibble" },
+                },
+            ],
+            "displayTimeUnit": "ms",
+        })
+    );
+}
@@ -0,0 +1,190 @@
+//! Listing of the `todo` expressions in a module along with the types the
+//! compiler expects them to have, so that they can be used as a list of the
+//! work that remains to be done.
+//!
+//! Work items extend this with the other markers of unfinished or outdated
+//! code: `panic`s given a message, and uses of deprecated values.
+
+use std::sync::Arc;
+
+use ecow::EcoString;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        SrcSpan, TodoKind, TypedExpr,
+    },
+    type_::{Deprecation, ModuleInterface, Type, ValueConstructor},
+};
+
+use super::Module;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Todo {
+    pub location: SrcSpan,
+    pub kind: TodoKind,
+    /// The type the surrounding code expects the `todo` to produce.
+    pub type_: Arc<Type>,
+    /// The message given with `as`, if it is a string literal.
+    pub message: Option<EcoString>,
+}
+
+/// A marker of unfinished or outdated code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkItem {
+    pub location: SrcSpan,
+    pub kind: WorkItemKind,
+    /// The type of the expression: the type the surrounding code expects for
+    /// a `todo` or `panic`, or the type of the deprecated value.
+    pub type_: Arc<Type>,
+    /// The message given with `as` if it is a string literal, or the
+    /// deprecation message.
+    pub message: Option<EcoString>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkItemKind {
+    Todo(TodoKind),
+    Panic,
+    Deprecated { name: EcoString },
+}
+
+impl Module {
+    /// Returns every `todo` in the module, in the order they appear in the
+    /// source.
+    ///
+    pub fn todos(&self) -> Vec<Todo> {
+        let mut todos = Todos { found: vec![] };
+        todos.visit_typed_module(&self.ast);
+        todos.found.sort_by_key(|todo| todo.location.start);
+        todos.found
+    }
+
+    /// Returns every `todo`, `panic` with a message, and use of a deprecated
+    /// value in the module, in the order they appear in the source. The
+    /// interfaces of the modules it imports are used to find out which
+    /// imported values are deprecated.
+    ///
+    pub fn work_items(&self, modules: &im::HashMap<EcoString, ModuleInterface>) -> Vec<WorkItem> {
+        let mut finder = WorkItems {
+            modules,
+            found: vec![],
+        };
+        finder.visit_typed_module(&self.ast);
+
+        let mut found = finder.found;
+        found.extend(self.todos().into_iter().map(|todo| WorkItem {
+            location: todo.location,
+            kind: WorkItemKind::Todo(todo.kind),
+            type_: todo.type_,
+            message: todo.message,
+        }));
+        found.sort_by_key(|item| item.location.start);
+        found
+    }
+}
+
+struct Todos {
+    found: Vec<Todo>,
+}
+
+impl<'ast> Visit<'ast> for Todos {
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        if let TypedExpr::Todo {
+            location,
+            message,
+            kind,
+            type_,
+        } = expr
+        {
+            let message = match message.as_deref() {
+                Some(TypedExpr::String { value, .. }) => Some(value.clone()),
+                _ => None,
+            };
+            self.found.push(Todo {
+                location: *location,
+                kind: *kind,
+                type_: type_.clone(),
+                message,
+            });
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+}
+
+struct WorkItems<'a> {
+    modules: &'a im::HashMap<EcoString, ModuleInterface>,
+    found: Vec<WorkItem>,
+}
+
+impl WorkItems<'_> {
+    fn deprecated_use(
+        &mut self,
+        location: SrcSpan,
+        name: &EcoString,
+        value: Option<&ValueConstructor>,
+        type_: &Arc<Type>,
+    ) {
+        if let Some(ValueConstructor {
+            deprecation: Deprecation::Deprecated { message },
+            ..
+        }) = value
+        {
+            self.found.push(WorkItem {
+                location,
+                kind: WorkItemKind::Deprecated { name: name.clone() },
+                type_: type_.clone(),
+                message: Some(message.clone()),
+            });
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for WorkItems<'_> {
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        match expr {
+            TypedExpr::Panic {
+                location,
+                message: Some(message),
+                type_,
+            } => {
+                let message = match message.as_ref() {
+                    TypedExpr::String { value, .. } => Some(value.clone()),
+                    _ => None,
+                };
+                self.found.push(WorkItem {
+                    location: *location,
+                    kind: WorkItemKind::Panic,
+                    type_: type_.clone(),
+                    message,
+                });
+            }
+
+            TypedExpr::Var {
+                location,
+                constructor,
+                name,
+            } => self.deprecated_use(*location, name, Some(constructor), &constructor.type_),
+
+            TypedExpr::ModuleSelect {
+                location,
+                typ,
+                label,
+                module_name,
+                ..
+            } => {
+                let value = self
+                    .modules
+                    .get(module_name)
+                    .and_then(|module| module.values.get(label));
+                self.deprecated_use(*location, label, value, typ);
+            }
+
+            _ => {}
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{
+    build::{tests::compile_test_package, Mode},
+    config::PackageConfig,
+    io::{memory::InMemoryFileSystem, FileSystemWriter},
+    type_::pretty::Printer,
+    warning::WarningEmitter,
+};
+use camino::Utf8PathBuf;
+use itertools::Itertools;
+
+fn work_items(modules: Vec<(&str, &str)>) -> String {
+    let fs = InMemoryFileSystem::new();
+    for (name, src) in modules {
+        fs.write(&Utf8PathBuf::from(format!("/src/{name}.gleam")), src)
+            .expect("should write module");
+    }
+
+    let mut config = PackageConfig::default();
+    config.name = "app".into();
+
+    let (warnings, _) = WarningEmitter::vector();
+    let (compiled, interfaces) =
+        compile_test_package(config, Mode::Dev, fs, &warnings, |_| {}).expect("should compile");
+
+    compiled
+        .iter()
+        .sorted_by(|one, other| one.name.cmp(&other.name))
+        .flat_map(|module| {
+            module.work_items(&interfaces).into_iter().map(move |item| {
+                format!(
+                    "{}:{} {:?}: {}{}",
+                    module.name,
+                    item.location.start,
+                    item.kind,
+                    Printer::new().pretty_print(&item.type_, 0),
+                    item.message
+                        .map(|message| format!(" - {message}"))
+                        .unwrap_or_default()
+                )
+            })
+        })
+        .join("\n")
+}
+
+#[test]
+fn todos_and_panics() {
+    let src = r#"
+pub fn main() {
+  let x: Int = todo as "make a number"
+  let y: String = panic as "not done"
+  panic
+}
+
+pub fn empty() -> Bool {}
+"#;
+    insta::assert_snapshot!(work_items(vec![("app", src)]));
+}
+
+#[test]
+fn deprecated_uses() {
+    let src = r#"
+import other.{old as unqualified}
+
+@deprecated("Use `new` instead")
+pub fn old_local() { 1 }
+
+pub fn main() {
+  #(old_local(), other.old(), unqualified())
+}
+"#;
+    let other = r#"
+@deprecated("Use `other.new` instead")
+pub fn old() { 1 }
+"#;
+    insta::assert_snapshot!(work_items(vec![("app", src), ("other", other)]));
+}
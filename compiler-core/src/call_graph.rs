@@ -155,7 +155,8 @@ impl<'a> CallGraphBuilder<'a> {
             UntypedExpr::Int { .. }
             | UntypedExpr::Float { .. }
             | UntypedExpr::String { .. }
-            | UntypedExpr::Placeholder { .. } => (),
+            | UntypedExpr::Placeholder { .. }
+            | UntypedExpr::Invalid { .. } => (),
 
             UntypedExpr::Todo { message, .. } => {
                 if let Some(msg_expr) = message {
@@ -169,6 +170,12 @@ impl<'a> CallGraphBuilder<'a> {
                 }
             }
 
+            UntypedExpr::Echo { expression, .. } => {
+                if let Some(expression) = expression {
+                    self.expression(expression)
+                }
+            }
+
             // Aha! A variable is being referenced.
             UntypedExpr::Var { name, .. } => {
                 self.referenced(name);
@@ -323,6 +330,8 @@ impl<'a> CallGraphBuilder<'a> {
                 self.referenced(name);
             }
 
+            Pattern::Constant { .. } => (),
+
             Pattern::Assign { name, pattern, .. } => {
                 self.define(name);
                 self.pattern(pattern);
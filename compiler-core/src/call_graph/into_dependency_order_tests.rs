@@ -32,6 +32,8 @@ fn parse_and_order(
             return_annotation: None,
             publicity: Publicity::Public,
             deprecation: Deprecation::NotDeprecated,
+            since: None,
+            must_use: false,
             end_position: src.len() as u32,
             return_type: (),
             documentation: None,
@@ -66,6 +68,7 @@ fn parse_and_order(
                 },
                 type_: (),
                 deprecation: Deprecation::NotDeprecated,
+                since: None,
             }
         })
         .collect_vec();
@@ -1,6 +1,6 @@
 use crate::{
     analyse::TargetSupport,
-    build::{ErlangAppCodegenConfiguration, Module},
+    build::{package_compiler::in_parallel, ErlangAppCodegenConfiguration, Module},
     config::PackageConfig,
     erlang,
     io::FileSystemWriter,
@@ -11,7 +11,7 @@ use crate::{
 use itertools::Itertools;
 use std::fmt::Debug;
 
-use camino::Utf8Path;
+use camino::{Utf8Path, Utf8PathBuf};
 
 /// A code generator that creates a .erl Erlang module and record header files
 /// for each Gleam module in the package.
@@ -34,40 +34,35 @@ impl<'a> Erlang<'a> {
         writer: Writer,
         modules: &[Module],
     ) -> Result<()> {
-        for module in modules {
+        // The code of the modules is generated in parallel, and then written
+        // in the order of the modules.
+        let generated = in_parallel(modules.iter().collect(), |module| {
             let erl_name = module.name.replace("/", "@");
-            self.erlang_module(&writer, module, &erl_name)?;
-            self.erlang_record_headers(&writer, module, &erl_name)?;
-        }
-        Ok(())
+            let mut files = vec![self.erlang_module(module, &erl_name)?];
+            files.extend(self.erlang_record_headers(module, &erl_name));
+            Ok(files)
+        });
+        write_generated_files(&writer, generated)
     }
 
-    fn erlang_module<Writer: FileSystemWriter>(
-        &self,
-        writer: &Writer,
-        module: &Module,
-        erl_name: &str,
-    ) -> Result<()> {
+    fn erlang_module(&self, module: &Module, erl_name: &str) -> Result<(Utf8PathBuf, String)> {
         let name = format!("{erl_name}.erl");
         let path = self.build_directory.join(&name);
         let line_numbers = LineNumbers::new(&module.code);
         let output = erlang::module(&module.ast, &line_numbers);
         tracing::debug!(name = ?name, "Generated Erlang module");
-        writer.write(&path, &output?)
+        Ok((path, output?))
     }
 
-    fn erlang_record_headers<Writer: FileSystemWriter>(
-        &self,
-        writer: &Writer,
-        module: &Module,
-        erl_name: &str,
-    ) -> Result<()> {
-        for (name, text) in erlang::records(&module.ast) {
-            let name = format!("{erl_name}_{name}.hrl");
-            tracing::debug!(name = ?name, "Generated Erlang header");
-            writer.write(&self.include_directory.join(name), &text)?;
-        }
-        Ok(())
+    fn erlang_record_headers(&self, module: &Module, erl_name: &str) -> Vec<(Utf8PathBuf, String)> {
+        erlang::records(&module.ast)
+            .into_iter()
+            .map(|(name, text)| {
+                let name = format!("{erl_name}_{name}.hrl");
+                tracing::debug!(name = ?name, "Generated Erlang header");
+                (self.include_directory.join(name), text)
+            })
+            .collect()
     }
 }
 
@@ -155,11 +150,18 @@ pub enum TypeScriptDeclarations {
     Emit,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceMaps {
+    None,
+    Emit,
+}
+
 #[derive(Debug)]
 pub struct JavaScript<'a> {
     output_directory: &'a Utf8Path,
     prelude_location: &'a Utf8Path,
     typescript: TypeScriptDeclarations,
+    source_maps: SourceMaps,
     target_support: TargetSupport,
 }
 
@@ -167,6 +169,7 @@ impl<'a> JavaScript<'a> {
     pub fn new(
         output_directory: &'a Utf8Path,
         typescript: TypeScriptDeclarations,
+        source_maps: SourceMaps,
         prelude_location: &'a Utf8Path,
         target_support: TargetSupport,
     ) -> Self {
@@ -175,17 +178,26 @@ impl<'a> JavaScript<'a> {
             output_directory,
             target_support,
             typescript,
+            source_maps,
         }
     }
 
     pub fn render(&self, writer: &impl FileSystemWriter, modules: &[Module]) -> Result<()> {
-        for module in modules {
+        // The code of the modules is generated in parallel, and then written
+        // in the order of the modules.
+        let generated = in_parallel(modules.iter().collect(), |module| {
             let js_name = module.name.clone();
+            let mut files = vec![];
             if self.typescript == TypeScriptDeclarations::Emit {
-                self.ts_declaration(writer, module, &js_name)?;
+                files.push(self.ts_declaration(module, &js_name)?);
             }
-            self.js_module(writer, module, &js_name)?
-        }
+            match self.source_maps {
+                SourceMaps::None => files.push(self.js_module(module, &js_name)?),
+                SourceMaps::Emit => files.extend(self.js_module_with_source_map(module, &js_name)?),
+            }
+            Ok(files)
+        });
+        write_generated_files(writer, generated)?;
         self.write_prelude(writer)?;
         Ok(())
     }
@@ -202,25 +214,15 @@ impl<'a> JavaScript<'a> {
         Ok(())
     }
 
-    fn ts_declaration(
-        &self,
-        writer: &impl FileSystemWriter,
-        module: &Module,
-        js_name: &str,
-    ) -> Result<()> {
+    fn ts_declaration(&self, module: &Module, js_name: &str) -> Result<(Utf8PathBuf, String)> {
         let name = format!("{js_name}.d.mts");
         let path = self.output_directory.join(name);
         let output = javascript::ts_declaration(&module.ast, &module.input_path, &module.code);
         tracing::debug!(name = ?js_name, "Generated TS declaration");
-        writer.write(&path, &output?)
+        Ok((path, output?))
     }
 
-    fn js_module(
-        &self,
-        writer: &impl FileSystemWriter,
-        module: &Module,
-        js_name: &str,
-    ) -> Result<()> {
+    fn js_module(&self, module: &Module, js_name: &str) -> Result<(Utf8PathBuf, String)> {
         let name = format!("{js_name}.mjs");
         let path = self.output_directory.join(name);
         let line_numbers = LineNumbers::new(&module.code);
@@ -233,6 +235,42 @@ impl<'a> JavaScript<'a> {
             self.typescript,
         );
         tracing::debug!(name = ?js_name, "Generated js module");
-        writer.write(&path, &output?)
+        Ok((path, output?))
+    }
+
+    fn js_module_with_source_map(
+        &self,
+        module: &Module,
+        js_name: &str,
+    ) -> Result<[(Utf8PathBuf, String); 2]> {
+        let name = format!("{js_name}.mjs");
+        let path = self.output_directory.join(name);
+        let line_numbers = LineNumbers::new(&module.code);
+        let (code, source_map) = javascript::module_with_source_map(
+            &module.ast,
+            &line_numbers,
+            &module.input_path,
+            &module.code,
+            self.target_support,
+            self.typescript,
+            &path,
+        )?;
+        tracing::debug!(name = ?js_name, "Generated js module with source map");
+        let source_map_path = self.output_directory.join(format!("{js_name}.mjs.map"));
+        Ok([(path, code), (source_map_path, source_map)])
+    }
+}
+
+/// Writes the files generated for each module, stopping at the first module
+/// that could not be generated.
+fn write_generated_files(
+    writer: &impl FileSystemWriter,
+    generated: Vec<Result<Vec<(Utf8PathBuf, String)>>>,
+) -> Result<()> {
+    for files in generated {
+        for (path, text) in files? {
+            writer.write(&path, &text)?;
+        }
     }
+    Ok(())
 }
@@ -1,8 +1,10 @@
 use crate::error::{FileIoAction, FileKind};
+use crate::format::FormatOptions;
 use crate::io::FileSystemReader;
 use crate::manifest::Manifest;
 use crate::requirement::Requirement;
 use crate::version::COMPILER_VERSION;
+use crate::warning::WarningCategory;
 use crate::{Error, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use ecow::EcoString;
@@ -97,6 +99,16 @@ pub struct PackageConfig {
     pub target: Target,
     #[serde(default)]
     pub internal_modules: Option<Vec<Glob>>,
+    #[serde(default)]
+    pub warnings: WarningsConfig,
+    #[serde(default)]
+    pub lints: LintsConfig,
+    #[serde(default)]
+    pub format: FormatConfig,
+    /// Whether to remove `echo` expressions when building in production
+    /// mode, so that they don't print anything in a release.
+    #[serde(default)]
+    pub remove_echo: bool,
 }
 
 impl PackageConfig {
@@ -119,6 +131,28 @@ impl PackageConfig {
         Ok(deps)
     }
 
+    /// The modules that the `[erlang]` and `[javascript]` sections limit to
+    /// a target other than the given one. These modules, and the modules
+    /// nested within them, are not built for the given target.
+    pub fn modules_for_other_targets(&self, target: Target) -> &[EcoString] {
+        match target {
+            Target::Erlang => &self.javascript.modules,
+            Target::JavaScript => &self.erlang.modules,
+        }
+    }
+
+    /// Whether a dependency is built for the target, which it is unless the
+    /// `[erlang]` or `[javascript]` section limits it to the other target.
+    pub fn builds_dependency_for(&self, package: &str, target: Target) -> bool {
+        let other_target_dependencies = match target {
+            Target::Erlang => &self.javascript.dependencies,
+            Target::JavaScript => &self.erlang.dependencies,
+        };
+        !other_target_dependencies
+            .iter()
+            .any(|dependency| dependency == package)
+    }
+
     pub fn read<FS: FileSystemReader, P: AsRef<Utf8Path>>(
         path: P,
         fs: &FS,
@@ -616,22 +650,219 @@ impl Default for PackageConfig {
             links: Default::default(),
             internal_modules: Default::default(),
             target: Target::Erlang,
+            warnings: Default::default(),
+            lints: Default::default(),
+            format: Default::default(),
+            remove_echo: false,
+        }
+    }
+}
+
+/// Formatter settings, configured in the `[format]` section of `gleam.toml`.
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone, Copy)]
+pub struct FormatConfig {
+    /// The width the formatter tries to keep lines within.
+    #[serde(default)]
+    pub line_width: Option<u16>,
+    /// The number of spaces each level of indentation is made of.
+    #[serde(default)]
+    pub indent_width: Option<u8>,
+    /// The most consecutive blank lines kept between the statements of a
+    /// function body or block.
+    #[serde(default)]
+    pub max_blank_lines_in_functions: Option<u8>,
+    /// The most consecutive blank lines kept between definitions.
+    #[serde(default)]
+    pub max_blank_lines_between_definitions: Option<u8>,
+    /// Whether line breaks written in chains of `|>`, `<>`, `&&` and `||`
+    /// are kept rather than the chains being laid out from scratch.
+    #[serde(default)]
+    pub keep_operator_line_breaks: bool,
+    /// Whether the digits of hexadecimal, octal, and binary integers are
+    /// separated into groups with underscores.
+    #[serde(default)]
+    pub group_non_decimal_digits: bool,
+}
+
+impl FormatConfig {
+    pub fn line_width(&self) -> isize {
+        self.line_width
+            .map(|width| width as isize)
+            .unwrap_or(crate::format::DEFAULT_LINE_WIDTH)
+    }
+
+    pub fn options(&self) -> FormatOptions {
+        let default = FormatOptions::default();
+        FormatOptions {
+            line_width: self.line_width(),
+            indent_width: self
+                .indent_width
+                .map_or(default.indent_width, |width| isize::from(width.max(1))),
+            max_blank_lines_in_functions: self
+                .max_blank_lines_in_functions
+                .map_or(default.max_blank_lines_in_functions, u32::from),
+            max_blank_lines_between_definitions: self
+                .max_blank_lines_between_definitions
+                .map_or(default.max_blank_lines_between_definitions, u32::from),
+            keep_operator_line_breaks: self.keep_operator_line_breaks,
+            group_non_decimal_digits: self.group_non_decimal_digits,
+        }
+    }
+}
+
+/// Opt-in warnings that are not emitted by default, and the levels of the
+/// categories of warnings, configured in the `[warnings]` section of
+/// `gleam.toml`.
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct WarningsConfig {
+    /// Warn when a variable shadows another variable defined in an enclosing
+    /// scope of the same function.
+    #[serde(default)]
+    pub shadowing: bool,
+    /// Warn about public definitions that can't be reached from any `main`
+    /// function of the package. Libraries are expected to expose definitions
+    /// that they don't use, so for a package without a `main` function only
+    /// the unused public definitions of its internal modules are reported.
+    #[serde(default)]
+    pub dead_code: bool,
+    /// How warnings from dependency packages are reported. When this is not
+    /// set the command line shows them and the language server doesn't.
+    #[serde(default)]
+    pub dependencies: Option<DependencyWarnings>,
+    /// The level of each category of warnings, set with the name of the
+    /// category such as `unused_imports = "deny"`.
+    #[serde(flatten)]
+    pub levels: HashMap<WarningCategory, WarningLevel>,
+}
+
+impl WarningsConfig {
+    /// The level warnings of the category are reported at. Categories that
+    /// are not configured are warned about.
+    pub fn level(&self, category: WarningCategory) -> WarningLevel {
+        self.levels
+            .get(&category)
+            .copied()
+            .unwrap_or(WarningLevel::Warn)
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum WarningLevel {
+    /// The warnings are not reported.
+    Allow,
+    /// The warnings are reported as warnings.
+    Warn,
+    /// The warnings are reported as errors, and the project fails to build
+    /// if there are any.
+    Deny,
+}
+
+/// How warnings from dependency packages are reported, configured with the
+/// `dependencies` key of the `[warnings]` section of `gleam.toml`.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyWarnings {
+    /// Every warning is reported.
+    Show,
+    /// A single warning is reported for each package with warnings, giving
+    /// the number of warnings it has.
+    Summarise,
+    /// No warnings are reported.
+    Silence,
+}
+
+/// The level of each lint, configured in the `[lints]` section of
+/// `gleam.toml` using the lint's name:
+///
+/// ```toml
+/// [lints]
+/// let_assert = "warn"
+/// ```
+///
+/// Lints that are not configured use their default level, and configuring a
+/// lint the compiler doesn't know about is an error.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct LintsConfig(HashMap<EcoString, LintLevel>);
+
+impl<'de> Deserialize<'de> for LintsConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let levels: HashMap<EcoString, LintLevel> = Deserialize::deserialize(deserializer)?;
+        let lints = crate::lint::all();
+        if let Some(unknown) = levels
+            .keys()
+            .find(|name| !lints.iter().any(|lint| lint.name() == name.as_str()))
+        {
+            let known = lints
+                .iter()
+                .map(|lint| lint.name())
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(serde::de::Error::custom(format!(
+                "unknown lint `{unknown}`, expected one of {known}"
+            )));
         }
+        Ok(Self(levels))
+    }
+}
+
+impl LintsConfig {
+    pub fn level(&self, lint: &str) -> Option<LintLevel> {
+        self.0.get(lint).copied()
+    }
+}
+
+impl<const N: usize> From<[(&str, LintLevel); N]> for LintsConfig {
+    fn from(levels: [(&str, LintLevel); N]) -> Self {
+        Self(
+            levels
+                .into_iter()
+                .map(|(lint, level)| (lint.into(), level))
+                .collect(),
+        )
     }
 }
 
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// The lint is not run.
+    Allow,
+    /// Problems found by the lint are reported as warnings.
+    Warn,
+}
+
 #[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
 pub struct ErlangConfig {
     #[serde(default)]
     pub application_start_module: Option<EcoString>,
     #[serde(default)]
     pub extra_applications: Vec<EcoString>,
+    /// Modules that are only built for Erlang, along with the modules nested
+    /// within them.
+    #[serde(default)]
+    pub modules: Vec<EcoString>,
+    /// Dependencies that are only built for Erlang.
+    #[serde(default)]
+    pub dependencies: Vec<EcoString>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Default, Clone)]
 pub struct JavaScriptConfig {
     #[serde(default)]
     pub typescript_declarations: bool,
+    #[serde(default)]
+    pub source_maps: bool,
+    /// Modules that are only built for JavaScript, along with the modules
+    /// nested within them.
+    #[serde(default)]
+    pub modules: Vec<EcoString>,
+    /// Dependencies that are only built for JavaScript.
+    #[serde(default)]
+    pub dependencies: Vec<EcoString>,
     #[serde(default = "default_javascript_runtime")]
     pub runtime: Runtime,
     #[serde(default, rename = "deno")]
@@ -782,6 +1013,64 @@ impl Default for Repository {
 pub struct Docs {
     #[serde(default)]
     pub pages: Vec<DocsPage>,
+    /// Groups of modules shown in the sidebar of the documentation, in the
+    /// order the groups and their modules are listed. Modules not in any
+    /// group are listed after them.
+    #[serde(default)]
+    pub module_groups: Vec<DocsModuleGroup>,
+    /// Glob patterns of modules that are left out of the documentation.
+    #[serde(default)]
+    pub hidden_modules: Vec<Glob>,
+    /// An image shown before the package's name in the header of each page.
+    /// It and the favicon are paths relative to the root of the project.
+    pub logo: Option<Utf8PathBuf>,
+    pub favicon: Option<Utf8PathBuf>,
+    /// The colour of the borders and highlights of the pages, as a CSS colour.
+    pub accent_colour: Option<String>,
+    /// The colour of links, as a CSS colour.
+    pub link_colour: Option<String>,
+    /// HTML added to the end of the `<head>` of each page, such as analytics
+    /// scripts or extra stylesheets.
+    pub head_html: Option<String>,
+    /// The URL the documentation of each release is published under, in a
+    /// directory named after the version, such as
+    /// `https://docs.example.com/app/1.2.0/`. When set, pages have a
+    /// dropdown for switching between the releases listed in the
+    /// `versions.json` manifest at this URL.
+    pub versions_url: Option<String>,
+}
+
+impl Docs {
+    /// Determines whether the given module has been hidden from the docs.
+    pub fn is_hidden_module(&self, module: &str) -> bool {
+        self.hidden_modules
+            .iter()
+            .any(|glob| glob.compile_matcher().is_match(module))
+    }
+
+    /// The group a module is listed in and its position in that group: the
+    /// index of the group and the index of the first of the group's patterns
+    /// that matches the module. A module is in the first group that has a
+    /// matching pattern.
+    pub fn module_group(&self, module: &str) -> Option<(usize, usize)> {
+        self.module_groups
+            .iter()
+            .enumerate()
+            .find_map(|(group_index, group)| {
+                let position = group
+                    .modules
+                    .iter()
+                    .position(|glob| glob.compile_matcher().is_match(module))?;
+                Some((group_index, position))
+            })
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct DocsModuleGroup {
+    pub title: String,
+    /// Glob patterns of the modules in the group.
+    pub modules: Vec<Glob>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -908,3 +1197,213 @@ name = "1"
         "Package names may only container lowercase letters, numbers, and underscores for key `name` at line 1 column 1"
     )
 }
+
+#[test]
+fn lint_levels() {
+    let input = r#"
+name = "wibble"
+
+[lints]
+let_assert = "warn"
+single_clause_case = "allow"
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("should parse config");
+    assert_eq!(config.lints.level("let_assert"), Some(LintLevel::Warn));
+    assert_eq!(
+        config.lints.level("single_clause_case"),
+        Some(LintLevel::Allow)
+    );
+    assert_eq!(config.lints.level("unconfigured"), None);
+}
+
+#[test]
+fn unknown_lint() {
+    let input = r#"
+name = "wibble"
+
+[lints]
+some_other_lint = "allow"
+"#;
+    assert!(toml::from_str::<PackageConfig>(input).is_err());
+}
+
+#[test]
+fn remove_echo() {
+    let config = toml::from_str::<PackageConfig>("name = \"wibble\"\nremove_echo = true")
+        .expect("should parse config");
+    assert!(config.remove_echo);
+    let config = toml::from_str::<PackageConfig>("name = \"wibble\"").expect("should parse config");
+    assert!(!config.remove_echo);
+}
+
+#[test]
+fn dependency_warnings() {
+    let input = r#"
+name = "wibble"
+
+[warnings]
+dependencies = "summarise"
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("should parse config");
+    assert_eq!(
+        config.warnings.dependencies,
+        Some(DependencyWarnings::Summarise)
+    );
+}
+
+#[test]
+fn warning_levels() {
+    let input = r#"
+name = "wibble"
+
+[warnings]
+shadowing = true
+unused_imports = "deny"
+todo = "allow"
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("should parse config");
+    assert!(config.warnings.shadowing);
+    assert_eq!(
+        config.warnings.level(WarningCategory::UnusedImports),
+        WarningLevel::Deny
+    );
+    assert_eq!(
+        config.warnings.level(WarningCategory::Todo),
+        WarningLevel::Allow
+    );
+    assert_eq!(
+        config.warnings.level(WarningCategory::Deprecated),
+        WarningLevel::Warn
+    );
+}
+
+#[test]
+fn unknown_warning_category() {
+    let input = r#"
+name = "wibble"
+
+[warnings]
+unused_wibbles = "deny"
+"#;
+    assert!(toml::from_str::<PackageConfig>(input).is_err());
+}
+
+#[test]
+fn format_line_width() {
+    let input = r#"
+name = "wibble"
+
+[format]
+line_width = 100
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("should parse config");
+    assert_eq!(config.format.line_width(), 100);
+}
+
+#[test]
+fn default_format_line_width() {
+    let config =
+        toml::from_str::<PackageConfig>(r#"name = "wibble""#).expect("should parse config");
+    assert_eq!(config.format.line_width(), 80);
+}
+
+#[test]
+fn format_keep_operator_line_breaks() {
+    let input = r#"
+name = "wibble"
+
+[format]
+keep_operator_line_breaks = true
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("should parse config");
+    assert!(config.format.options().keep_operator_line_breaks);
+}
+
+#[test]
+fn format_group_non_decimal_digits() {
+    let input = r#"
+name = "wibble"
+
+[format]
+group_non_decimal_digits = true
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("should parse config");
+    assert!(config.format.options().group_non_decimal_digits);
+}
+
+#[test]
+fn format_indent_width() {
+    let input = r#"
+name = "wibble"
+
+[format]
+indent_width = 4
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("should parse config");
+    assert_eq!(config.format.options().indent_width, 4);
+}
+
+#[test]
+fn docs_module_groups() {
+    let input = r#"
+name = "wibble"
+
+[documentation]
+hidden_modules = ["wibble/unstable/*"]
+
+[[documentation.module_groups]]
+title = "Getting started"
+modules = ["wibble", "wibble/router"]
+
+[[documentation.module_groups]]
+title = "Middleware"
+modules = ["wibble/middleware/*", "wibble/router"]
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("should parse config");
+    let docs = config.documentation;
+    assert_eq!(docs.module_group("wibble"), Some((0, 0)));
+    assert_eq!(docs.module_group("wibble/router"), Some((0, 1)));
+    assert_eq!(docs.module_group("wibble/middleware/cors"), Some((1, 0)));
+    assert_eq!(docs.module_group("wibble/other"), None);
+    assert!(docs.is_hidden_module("wibble/unstable/api"));
+    assert!(!docs.is_hidden_module("wibble/router"));
+}
+
+#[test]
+fn docs_branding() {
+    let input = r##"
+name = "wibble"
+
+[documentation]
+logo = "assets/logo.svg"
+favicon = "assets/favicon.png"
+accent_colour = "#0055ff"
+link_colour = "rebeccapurple"
+head_html = "<meta name=\"robots\" content=\"noindex\">"
+"##;
+    let config = toml::from_str::<PackageConfig>(input).expect("should parse config");
+    let docs = config.documentation;
+    assert_eq!(docs.logo, Some("assets/logo.svg".into()));
+    assert_eq!(docs.favicon, Some("assets/favicon.png".into()));
+    assert_eq!(docs.accent_colour.as_deref(), Some("#0055ff"));
+    assert_eq!(docs.link_colour.as_deref(), Some("rebeccapurple"));
+    assert_eq!(
+        docs.head_html.as_deref(),
+        Some(r#"<meta name="robots" content="noindex">"#)
+    );
+}
+
+#[test]
+fn docs_versions_url() {
+    let input = r#"
+name = "wibble"
+
+[documentation]
+versions_url = "https://docs.example.com/wibble"
+"#;
+    let config = toml::from_str::<PackageConfig>(input).expect("should parse config");
+    assert_eq!(
+        config.documentation.versions_url.as_deref(),
+        Some("https://docs.example.com/wibble")
+    );
+}
@@ -278,6 +278,34 @@ impl<'a> pubgrub::solver::DependencyProvider<PackageName, Version> for Dependenc
     }
 }
 
+/// The latest releases of a package, used to report the dependencies that
+/// could be updated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatestVersions {
+    /// The latest release satisfying the requirement for the package.
+    pub compatible: Option<Version>,
+    /// The latest release of the package, whatever its version.
+    pub latest: Option<Version>,
+}
+
+/// Finds the latest releases of a package. Pre-releases and retired releases
+/// are not considered, as they are not picked when resolving versions either.
+pub fn latest_versions(package: &hexpm::Package, requirement: &Range) -> LatestVersions {
+    let range = requirement.to_pubgrub().ok();
+    let releases = package
+        .releases
+        .iter()
+        .filter(|release| !release.version.is_pre() && !release.is_retired())
+        .map(|release| &release.version);
+    let compatible = releases
+        .clone()
+        .filter(|version| range.as_ref().is_some_and(|range| range.contains(version)))
+        .max()
+        .cloned();
+    let latest = releases.max().cloned();
+    LatestVersions { compatible, latest }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -697,4 +725,56 @@ mod tests {
         assert_eq!(parse_exact_version("~> 1.0.0"), None);
         assert_eq!(parse_exact_version(">= 1.0.0"), None);
     }
+
+    #[test]
+    fn latest_versions_of_package() {
+        let remote = make_remote();
+        let stdlib = remote.deps.get("gleam_stdlib").unwrap();
+        assert_eq!(
+            latest_versions(stdlib, &Range::new("~> 0.2.0".into())),
+            LatestVersions {
+                compatible: Some(Version::try_from("0.2.2").unwrap()),
+                latest: Some(Version::try_from("0.3.0").unwrap()),
+            }
+        );
+    }
+
+    #[test]
+    fn latest_versions_skip_pre_releases() {
+        let remote = make_remote();
+        let otp = remote.deps.get("gleam_otp").unwrap();
+        assert_eq!(
+            latest_versions(otp, &Range::new(">= 0.1.0".into())),
+            LatestVersions {
+                compatible: Some(Version::try_from("0.2.0").unwrap()),
+                latest: Some(Version::try_from("0.2.0").unwrap()),
+            }
+        );
+    }
+
+    #[test]
+    fn latest_versions_skip_retired_releases() {
+        let remote = make_remote();
+        let package = remote.deps.get("package_with_retired").unwrap();
+        assert_eq!(
+            latest_versions(package, &Range::new(">= 0.1.0".into())),
+            LatestVersions {
+                compatible: Some(Version::try_from("0.1.0").unwrap()),
+                latest: Some(Version::try_from("0.1.0").unwrap()),
+            }
+        );
+    }
+
+    #[test]
+    fn no_compatible_latest_version() {
+        let remote = make_remote();
+        let stdlib = remote.deps.get("gleam_stdlib").unwrap();
+        assert_eq!(
+            latest_versions(stdlib, &Range::new("~> 1.0".into())),
+            LatestVersions {
+                compatible: None,
+                latest: Some(Version::try_from("0.3.0").unwrap()),
+            }
+        );
+    }
 }
@@ -7,19 +7,23 @@ use termcolor::Buffer;
 
 use crate::ast::SrcSpan;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub mod json;
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum Level {
     Error,
     Warning,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Label {
     pub text: Option<String>,
     pub span: SrcSpan,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Location {
     pub src: EcoString,
     pub path: Utf8PathBuf,
@@ -33,14 +37,120 @@ impl Location {
     }
 }
 
-// TODO: split this into locationed diagnostics and locationless diagnostics
+/// A stable code identifying a kind of error or warning, such as `GE0042`.
+///
+/// Codes are grouped into blocks by where the problem is detected:
+///
+/// - `GE0001`..`GE0099`: errors of the build tool and the compiler itself.
+/// - `GE0100`..`GE0199`: syntax errors.
+/// - `GE0200`..`GE0399`: type errors.
+/// - `GW0001`..`GW0099`: warnings of the build tool.
+/// - `GW0100`..`GW0299`: warnings of the type checker.
+///
+/// Once assigned a code must never be reused for a different problem, so
+/// that it can be searched for and explained with `gleam explain`.
+///
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub enum Code {
+    Error(u16),
+    Warning(u16),
+}
+
+impl Code {
+    pub fn parse(code: &str) -> Option<Self> {
+        let code = code.trim().to_uppercase();
+        let number = code
+            .get(2..)
+            .filter(|number| number.len() == 4 && number.chars().all(|c| c.is_ascii_digit()))?
+            .parse()
+            .ok()?;
+        match code.get(..2)? {
+            "GE" => Some(Code::Error(number)),
+            "GW" => Some(Code::Warning(number)),
+            _ => None,
+        }
+    }
+
+    /// Whether this code has been assigned to an error or warning. Codes are
+    /// assigned in order within each block, so this has to be updated when a
+    /// new one is.
+    pub fn is_assigned(&self) -> bool {
+        match self {
+            Code::Error(number) => matches!(number, 1..=66 | 100..=143 | 200..=253),
+            Code::Warning(number) => matches!(number, 1..=2 | 100..=129),
+        }
+    }
+
+    /// An extended explanation of the problem identified by this code, with
+    /// examples of code causing it and how to fix it, written in Markdown.
+    pub fn explanation(&self) -> Option<&'static str> {
+        EXPLANATIONS
+            .iter()
+            .find(|(code, _)| code == self)
+            .map(|(_, explanation)| *explanation)
+    }
+}
+
+impl std::fmt::Display for Code {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Code::Error(number) => write!(f, "GE{number:04}"),
+            Code::Warning(number) => write!(f, "GW{number:04}"),
+        }
+    }
+}
+
+macro_rules! explanations {
+    ($($kind:ident($number:literal) => $file:literal,)*) => {
+        const EXPLANATIONS: &[(Code, &str)] = &[
+            $((Code::$kind($number), include_str!(concat!("diagnostic/explanations/", $file)))),*
+        ];
+    };
+}
+
+explanations! {
+    Error(203) => "GE0203.md",
+    Error(205) => "GE0205.md",
+    Error(211) => "GE0211.md",
+    Error(215) => "GE0215.md",
+    Error(216) => "GE0216.md",
+    Error(222) => "GE0222.md",
+    Error(247) => "GE0247.md",
+    Error(248) => "GE0248.md",
+    Warning(100) => "GW0100.md",
+    Warning(101) => "GW0101.md",
+    Warning(113) => "GW0113.md",
+    Warning(119) => "GW0119.md",
+    Warning(124) => "GW0124.md",
+    Warning(126) => "GW0126.md",
+}
+
+/// A change to the source of a module that fixes the problem a diagnostic
+/// reports, such as removing an unused import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub title: String,
+    pub edits: Vec<Edit>,
+}
+
+/// The replacement of a span of the source of a module.
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub span: SrcSpan,
+    pub replacement: String,
+}
+
+// TODO: split this into locationed diagnostics and locationless diagnostics
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Diagnostic {
     pub title: String,
     pub text: String,
     pub level: Level,
     pub location: Option<Location>,
     pub hint: Option<String>,
+    pub code: Option<Code>,
 }
 
 impl Diagnostic {
@@ -82,9 +192,12 @@ impl Diagnostic {
             Level::Warning => Severity::Warning,
         };
 
-        let diagnostic = codespan_reporting::diagnostic::Diagnostic::new(severity)
+        let mut diagnostic = codespan_reporting::diagnostic::Diagnostic::new(severity)
             .with_message(&self.title)
             .with_labels(labels);
+        if let Some(code) = self.code {
+            diagnostic = diagnostic.with_code(code.to_string());
+        }
         let config = codespan_reporting::term::Config::default();
         codespan_reporting::term::emit(buffer, &config, &file, &diagnostic)
             .expect("write_diagnostic");
@@ -101,6 +214,9 @@ impl Diagnostic {
             .set_color(ColorSpec::new().set_bold(true).set_fg(Some(colour)))
             .expect("write_title_color1");
         write!(buffer, "{kind}").expect("write_title_kind");
+        if let Some(code) = self.code {
+            write!(buffer, "[{code}]").expect("write_title_code");
+        }
         buffer
             .set_color(ColorSpec::new().set_bold(true))
             .expect("write_title_color2");
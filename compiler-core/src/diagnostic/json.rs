@@ -0,0 +1,159 @@
+//! Errors and warnings as JSON objects, printed one per line with
+//! `--message-format=json` for tools such as editor plugins and CI annotators
+//! to read rather than the text printed for people.
+
+use camino::Utf8PathBuf;
+use serde::Serialize;
+
+use super::{Diagnostic, Edit, Fix, Label, Level};
+use crate::{ast::SrcSpan, line_numbers::LineNumbers, Error, Warning};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonDiagnostic {
+    /// The stable code of the problem, such as `GW0109`.
+    pub code: Option<String>,
+    pub severity: JsonSeverity,
+    pub title: String,
+    pub text: String,
+    pub hint: Option<String>,
+    /// The file the problem is in, if it is in one.
+    pub path: Option<Utf8PathBuf>,
+    /// The code the problem is about, starting with the primary span and
+    /// followed by the related ones.
+    pub spans: Vec<JsonSpan>,
+    pub fixes: Vec<JsonFix>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonSeverity {
+    Error,
+    Warning,
+}
+
+/// A span of the source of a file, as byte offsets and as lines and columns.
+/// Lines and columns start at 1, and columns are counted in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct JsonRange {
+    pub start: u32,
+    pub end: u32,
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonSpan {
+    #[serde(flatten)]
+    pub range: JsonRange,
+    pub label: Option<String>,
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonFix {
+    pub title: String,
+    pub edits: Vec<JsonEdit>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonEdit {
+    #[serde(flatten)]
+    pub range: JsonRange,
+    pub replacement: String,
+}
+
+impl JsonDiagnostic {
+    pub fn new(diagnostic: Diagnostic, fixes: Vec<Fix>) -> Self {
+        let severity = match diagnostic.level {
+            Level::Error => JsonSeverity::Error,
+            Level::Warning => JsonSeverity::Warning,
+        };
+        let (path, spans, fixes) = match diagnostic.location {
+            Some(location) => {
+                let line_numbers = LineNumbers::new(&location.src);
+                let spans = std::iter::once((location.label, true))
+                    .chain(
+                        location
+                            .extra_labels
+                            .into_iter()
+                            .map(|label| (label, false)),
+                    )
+                    .map(|(Label { text, span }, primary)| JsonSpan {
+                        range: JsonRange::new(span, &line_numbers),
+                        label: text,
+                        primary,
+                    })
+                    .collect();
+                let fixes = fixes
+                    .into_iter()
+                    .map(|fix| JsonFix::new(fix, &line_numbers))
+                    .collect();
+                (Some(location.path), spans, fixes)
+            }
+            // Fixes can't be given without the source they change.
+            None => (None, vec![], vec![]),
+        };
+        Self {
+            code: diagnostic.code.map(|code| code.to_string()),
+            severity,
+            title: diagnostic.title,
+            text: diagnostic.text,
+            hint: diagnostic.hint,
+            path,
+            spans,
+            fixes,
+        }
+    }
+
+    pub fn from_warning(warning: &Warning) -> Self {
+        Self::new(warning.to_diagnostic(), warning.fixes())
+    }
+
+    /// The diagnostics for an error, of which there can be more than one
+    /// when type checking finds several errors in a module.
+    pub fn from_error(error: &Error) -> Vec<Self> {
+        error
+            .to_diagnostics()
+            .into_iter()
+            .map(|diagnostic| Self::new(diagnostic, vec![]))
+            .collect()
+    }
+
+    /// The diagnostic as JSON on a single line.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Diagnostic JSON serialisation")
+    }
+}
+
+impl JsonRange {
+    fn new(span: SrcSpan, line_numbers: &LineNumbers) -> Self {
+        let start = line_numbers.line_and_column_number(span.start);
+        let end = line_numbers.line_and_column_number(span.end);
+        Self {
+            start: span.start,
+            end: span.end,
+            start_line: start.line,
+            start_column: start.column,
+            end_line: end.line,
+            end_column: end.column,
+        }
+    }
+}
+
+impl JsonFix {
+    fn new(fix: Fix, line_numbers: &LineNumbers) -> Self {
+        Self {
+            title: fix.title,
+            edits: fix
+                .edits
+                .into_iter()
+                .map(|Edit { span, replacement }| JsonEdit {
+                    range: JsonRange::new(span, line_numbers),
+                    replacement,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use super::{
+    json::{JsonDiagnostic, JsonSeverity},
+    Code, EXPLANATIONS,
+};
+use crate::{ast::SrcSpan, type_, Warning};
+use camino::Utf8PathBuf;
+
+#[test]
+fn codes_are_printed_with_padding() {
+    assert_eq!(Code::Error(7).to_string(), "GE0007");
+    assert_eq!(Code::Error(215).to_string(), "GE0215");
+    assert_eq!(Code::Warning(113).to_string(), "GW0113");
+}
+
+#[test]
+fn codes_can_be_parsed() {
+    assert_eq!(Code::parse("GE0215"), Some(Code::Error(215)));
+    assert_eq!(Code::parse("gw0113"), Some(Code::Warning(113)));
+    assert_eq!(Code::parse(" GE0007\n"), Some(Code::Error(7)));
+}
+
+#[test]
+fn invalid_codes_are_not_parsed() {
+    assert_eq!(Code::parse(""), None);
+    assert_eq!(Code::parse("GE"), None);
+    assert_eq!(Code::parse("GE215"), None);
+    assert_eq!(Code::parse("GE02150"), None);
+    assert_eq!(Code::parse("GX0215"), None);
+    assert_eq!(Code::parse("GE-215"), None);
+    assert_eq!(Code::parse("E0215"), None);
+}
+
+#[test]
+fn assigned_codes() {
+    assert!(Code::Error(1).is_assigned());
+    assert!(Code::Error(215).is_assigned());
+    assert!(Code::Warning(113).is_assigned());
+    assert!(!Code::Error(0).is_assigned());
+    assert!(!Code::Error(99).is_assigned());
+    assert!(!Code::Error(400).is_assigned());
+    assert!(!Code::Warning(3).is_assigned());
+}
+
+#[test]
+fn explanations_start_with_their_code() {
+    for (code, explanation) in EXPLANATIONS {
+        assert!(code.is_assigned(), "explanation for unassigned code {code}");
+        assert!(
+            explanation.starts_with(&format!("# {code}: ")),
+            "explanation for {code} doesn't start with its code"
+        );
+        assert!(
+            explanation.contains("```gleam"),
+            "explanation for {code} has no example"
+        );
+    }
+}
+
+#[test]
+fn warning_as_json() {
+    let warning = Warning::Type {
+        path: Utf8PathBuf::from("src/wibble.gleam"),
+        src: "import gleam/io\nimport wobble\n".into(),
+        warning: type_::Warning::UnusedImportedModule {
+            location: SrcSpan::new(16, 29),
+            name: "wobble".into(),
+        },
+    };
+    let json: serde_json::Value =
+        serde_json::from_str(&JsonDiagnostic::from_warning(&warning).to_json()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "code": "GW0109",
+            "severity": "warning",
+            "title": "Unused imported module",
+            "text": "",
+            "hint": "You can safely remove it.",
+            "path": "src/wibble.gleam",
+            "spans": [{
+                "start": 16,
+                "end": 29,
+                "start_line": 2,
+                "start_column": 1,
+                "end_line": 2,
+                "end_column": 14,
+                "label": "This imported module is never used",
+                "primary": true,
+            }],
+            "fixes": [{
+                "title": "Remove unused import",
+                "edits": [{
+                    "start": 16,
+                    "end": 29,
+                    "start_line": 2,
+                    "start_column": 1,
+                    "end_line": 2,
+                    "end_column": 14,
+                    "replacement": "",
+                }],
+            }],
+        })
+    );
+}
+
+#[test]
+fn locationless_error_as_json() {
+    let error = crate::Error::ForbiddenWarnings { count: 2 };
+    let diagnostics = JsonDiagnostic::from_error(&error);
+    assert_eq!(diagnostics.len(), 1);
+
+    let diagnostic = diagnostics.first().unwrap();
+    assert_eq!(diagnostic.code.as_deref(), Some("GE0030"));
+    assert_eq!(diagnostic.severity, JsonSeverity::Error);
+    assert_eq!(diagnostic.path, None);
+    assert_eq!(diagnostic.spans, vec![]);
+}
@@ -1,10 +1,15 @@
+mod assets;
+mod dump;
+mod markdown;
+mod search;
 mod source_links;
 #[cfg(test)]
 mod tests;
+mod versions;
 
 use std::time::SystemTime;
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 
 use crate::{
     ast::{
@@ -12,17 +17,28 @@ use crate::{
     },
     build::{Module, Package},
     config::{DocsPage, PackageConfig},
-    docs::source_links::SourceLinker,
+    docs::{
+        assets::{AssetLinker, LocalAssets},
+        dump::{ModuleDump, PackageDump},
+        search::SearchData,
+        source_links::{source_page_path, SourceLinker},
+    },
     format,
     io::{Content, FileSystemReader, OutputFile},
+    manifest::{Manifest, ManifestPackageSource},
     package_interface::PackageInterface,
     paths::ProjectPaths,
     pretty,
-    type_::Deprecation,
+    type_::{
+        self,
+        pretty::{LINK_END, LINK_START, LINK_TEXT},
+        Deprecation,
+    },
     version::COMPILER_VERSION,
+    Result,
 };
 use askama::Template;
-use ecow::EcoString;
+use ecow::{eco_format, EcoString};
 use itertools::Itertools;
 use serde::Serialize;
 use serde_json::to_string as serde_to_string;
@@ -34,13 +50,11 @@ pub fn generate_html<IO: FileSystemReader>(
     config: &PackageConfig,
     analysed: &[Module],
     docs_pages: &[DocsPage],
+    manifest: &Manifest,
     fs: IO,
     rendering_timestamp: SystemTime,
-) -> Vec<OutputFile> {
-    let modules = analysed
-        .iter()
-        .filter(|module| !module.is_test())
-        .filter(|module| !config.is_internal_module(&module.name));
+) -> Result<Vec<OutputFile>> {
+    let modules = documented_modules(config, analysed);
 
     let rendering_timestamp = rendering_timestamp
         .duration_since(SystemTime::UNIX_EPOCH)
@@ -48,6 +62,18 @@ pub fn generate_html<IO: FileSystemReader>(
         .as_secs()
         .to_string();
 
+    // Types from Hex dependencies link to the documentation of the version
+    // of the dependency in the manifest.
+    let package_docs: im::HashMap<_, _> = manifest
+        .packages
+        .iter()
+        .filter(|package| matches!(package.source, ManifestPackageSource::Hex { .. }))
+        .map(|package| {
+            let url = eco_format!("https://hexdocs.pm/{}/{}/", package.name, package.version);
+            (package.name.clone(), url)
+        })
+        .collect();
+
     // Define user-supplied (or README) pages
     let pages: Vec<_> = docs_pages
         .iter()
@@ -76,25 +102,25 @@ pub fn generate_html<IO: FileSystemReader>(
 
     let mut files = vec![];
 
+    let mut assets = LocalAssets::new(paths, &fs, docs_pages);
+    let branding = branding(config, &mut assets)?;
+    let versions_manifest_url = versions_manifest_url(config).unwrap_or_default();
+
     let mut search_indexes = vec![];
+    let mut module_dumps = vec![];
+    let mut text_pages = vec![];
 
-    let modules_links: Vec<_> = modules
-        .clone()
-        .map(|m| {
-            let path = [&m.name, ".html"].concat();
-            Link {
-                path,
-                name: m.name.to_string(),
-            }
-        })
-        .sorted()
-        .collect();
+    let module_groups = module_groups(config, modules.clone());
 
     // Generate user-supplied (or README) pages
     for page in docs_pages {
         let content = fs.read(&page.source).unwrap_or_default();
-        let rendered_content = render_markdown(&content, MarkdownSource::Standalone);
         let unnest = page_unnest(&page.path);
+        let rendered_content = render_markdown(
+            &content,
+            MarkdownSource::Standalone,
+            &mut assets.page_linker(paths, page, &unnest),
+        );
 
         let page_path_without_ext = page.path.split('.').next().unwrap_or("");
         let page_title = match page_path_without_ext {
@@ -112,7 +138,9 @@ pub fn generate_html<IO: FileSystemReader>(
             gleam_version: COMPILER_VERSION,
             links: &links,
             pages: &pages,
-            modules: &modules_links,
+            module_groups: &module_groups,
+            branding: &branding,
+            versions_manifest_url: &versions_manifest_url,
             project_name: &config.name,
             page_title: &page_title,
             page_meta_description: &page_meta_description,
@@ -127,9 +155,15 @@ pub fn generate_html<IO: FileSystemReader>(
             content: Content::Text(temp.render().expect("Page template rendering")),
         });
 
+        // Results from extra pages such as guides are titled with the page, so
+        // they can be told apart from the README
+        let search_title = match page_path_without_ext {
+            "index" => config.name.to_string(),
+            _other => page.title.clone(),
+        };
         search_indexes.push(SearchIndex {
             doc: config.name.to_string(),
-            title: config.name.to_string(),
+            title: search_title,
             content,
             url: page.path.to_string(),
         })
@@ -143,36 +177,36 @@ pub fn generate_html<IO: FileSystemReader>(
         // Read module src & create line number lookup structure
         let source_links = SourceLinker::new(paths, config, module);
 
+        // Relative links in documentation comments are resolved from the
+        // root of the project.
+        let mut linker = assets.linker(Utf8Path::new(""), &unnest);
+
         let documentation_content = module.ast.documentation.iter().join("\n");
-        let rendered_documentation =
-            render_markdown(&documentation_content.clone(), MarkdownSource::Comment);
-
-        let functions: Vec<DocsFunction<'_>> = module
-            .ast
-            .definitions
-            .iter()
-            .filter(|statement| !statement.is_internal())
-            .flat_map(|statement| function(&source_links, statement))
-            .sorted()
-            .collect();
-
-        let types: Vec<Type<'_>> = module
-            .ast
-            .definitions
-            .iter()
-            .filter(|statement| !statement.is_internal())
-            .flat_map(|statement| type_(&source_links, statement))
-            .sorted()
-            .collect();
-
-        let constants: Vec<Constant<'_>> = module
-            .ast
-            .definitions
-            .iter()
-            .filter(|statement| !statement.is_internal())
-            .flat_map(|statement| constant(&source_links, statement))
-            .sorted()
-            .collect();
+        let rendered_documentation = render_markdown(
+            &documentation_content.clone(),
+            MarkdownSource::Comment,
+            &mut linker,
+        );
+
+        let members = ModuleMembers::new(module, &source_links, &package_docs, &mut linker);
+
+        let raw_module_documentation = raw_documentation(&documentation_content);
+        module_dumps.push(ModuleDump::new(
+            &module.name,
+            &raw_module_documentation,
+            &members,
+        ));
+        text_pages.push(markdown::module_page(
+            &module.name,
+            &raw_module_documentation,
+            &members,
+        ));
+
+        let ModuleMembers {
+            types,
+            constants,
+            functions,
+        } = members;
 
         types.iter().for_each(|typ| {
             let constructors = typ
@@ -247,7 +281,9 @@ pub fn generate_html<IO: FileSystemReader>(
             links: &links,
             pages: &pages,
             documentation: rendered_documentation,
-            modules: &modules_links,
+            module_groups: &module_groups,
+            branding: &branding,
+            versions_manifest_url: &versions_manifest_url,
             project_name: &config.name,
             page_title: &page_title,
             page_meta_description,
@@ -267,8 +303,67 @@ pub fn generate_html<IO: FileSystemReader>(
                     .expect("Module documentation template rendering"),
             ),
         });
+
+        let source_path = source_page_path(&module.name);
+        let source_page_title = format!(
+            "{}.gleam · {} · v{}",
+            module.name, config.name, config.version
+        );
+        let source_page = PageTemplate {
+            gleam_version: COMPILER_VERSION,
+            links: &links,
+            pages: &pages,
+            module_groups: &module_groups,
+            branding: &branding,
+            versions_manifest_url: &versions_manifest_url,
+            project_name: &config.name,
+            page_title: &source_page_title,
+            page_meta_description: "",
+            project_version: &config.version.to_string(),
+            content: render_source(&module.name, &module.code),
+            rendering_timestamp: &rendering_timestamp,
+            unnest: &page_unnest(&source_path),
+        };
+        files.push(OutputFile {
+            path: Utf8PathBuf::from(source_path),
+            content: Content::Text(
+                source_page
+                    .render()
+                    .expect("Source page template rendering"),
+            ),
+        });
     }
 
+    files.extend(assets.into_outputs());
+
+    // A dump of the documentation of every module for search services and
+    // other tools, as JSON and as plain text.
+
+    files.push(OutputFile {
+        path: Utf8PathBuf::from("documentation.json"),
+        content: Content::Text(
+            serde_json::to_string(&PackageDump {
+                name: &config.name,
+                version: config.version.to_string(),
+                modules: module_dumps,
+            })
+            .expect("documentation dump serialisation"),
+        ),
+    });
+
+    let mut text = format!("# {} v{}\n", config.name, config.version);
+    if !config.description.is_empty() {
+        text.push_str(&format!("\n{}\n", config.description));
+    }
+    for page in text_pages {
+        text.push('\n');
+        text.push_str(&page);
+    }
+    files.push(OutputFile {
+        path: Utf8PathBuf::from("documentation.txt"),
+        content: Content::Text(text),
+    });
+
     // Render static assets
 
     files.push(OutputFile {
@@ -334,18 +429,13 @@ pub fn generate_html<IO: FileSystemReader>(
         ),
     });
 
-    // lunr.min.js, search-data.js and index.js:
-
-    files.push(OutputFile {
-        path: Utf8PathBuf::from("js/lunr.min.js"),
-        content: Content::Text(std::include_str!("../templates/docs-js/lunr.min.js").to_string()),
-    });
+    // search-data.js and index.js:
 
     files.push(OutputFile {
         path: Utf8PathBuf::from("search-data.js"),
         content: Content::Text(format!(
             "window.Gleam.initSearch({});",
-            serde_to_string(&escape_html_contents(search_indexes))
+            serde_to_string(&SearchData::new(escape_html_contents(search_indexes)))
                 .expect("search index serialization")
         )),
     });
@@ -431,9 +521,208 @@ pub fn generate_html<IO: FileSystemReader>(
         ),
     });
 
+    Ok(files)
+}
+
+/// Renders the documentation as Markdown rather than HTML, for publishing
+/// with tools such as wikis, mdBook, and static site generators. Each module
+/// is written to a `.md` file with the same content as its HTML page, and the
+/// user-supplied pages are copied alongside them.
+pub fn generate_markdown<IO: FileSystemReader>(
+    paths: &ProjectPaths,
+    config: &PackageConfig,
+    analysed: &[Module],
+    docs_pages: &[DocsPage],
+    fs: IO,
+) -> Vec<OutputFile> {
+    let mut files = vec![];
+
+    for page in docs_pages {
+        let content = fs.read(&page.source).unwrap_or_default();
+        files.push(OutputFile {
+            path: Utf8PathBuf::from(&page.path).with_extension("md"),
+            content: Content::Text(content),
+        });
+    }
+
+    // Markdown can't have links inside code blocks, so types from
+    // dependencies are not linked. The Markdown is written as it is in the
+    // documentation comments, so its relative links are not rewritten either.
+    let package_docs = im::HashMap::new();
+    let mut assets = LocalAssets::new(paths, &fs, docs_pages);
+    for module in documented_modules(config, analysed) {
+        let source_links = SourceLinker::new(paths, config, module);
+        let mut linker = assets.linker(Utf8Path::new(""), &page_unnest(&module.name));
+        let members = ModuleMembers::new(module, &source_links, &package_docs, &mut linker);
+        let documentation = raw_documentation(&module.ast.documentation.iter().join("\n"));
+        files.push(OutputFile {
+            path: Utf8PathBuf::from(format!("{}.md", module.name)),
+            content: Content::Text(markdown::module_page(
+                &module.name,
+                &documentation,
+                &members,
+            )),
+        });
+    }
+
     files
 }
 
+/// The logo, favicon, colours, and extra HTML set in the `[documentation]`
+/// section of `gleam.toml`, shared by every page. The logo and favicon are
+/// copied into the `assets` directory of the documentation, along with the
+/// other files of the project the documentation uses.
+fn branding(config: &PackageConfig, assets: &mut LocalAssets<'_>) -> Result<Branding> {
+    let docs = &config.documentation;
+    let mut asset = |path: &Option<Utf8PathBuf>| match path {
+        Some(path) => assets.add(path),
+        None => Ok(String::new()),
+    };
+
+    let colours = [
+        ("--accent", &docs.accent_colour),
+        ("--links", &docs.link_colour),
+    ]
+    .into_iter()
+    .filter_map(|(property, colour)| Some(format!("{property}: {};", colour.as_ref()?)))
+    .join(" ");
+
+    Ok(Branding {
+        logo: asset(&docs.logo)?,
+        favicon: asset(&docs.favicon)?,
+        colours,
+        head_html: docs.head_html.clone().unwrap_or_default(),
+    })
+}
+
+/// The modules that documentation is generated for: those that are not test
+/// or internal modules.
+fn documented_modules<'a>(
+    config: &'a PackageConfig,
+    analysed: &'a [Module],
+) -> impl Iterator<Item = &'a Module> + Clone {
+    analysed
+        .iter()
+        .filter(|module| !module.is_test())
+        .filter(|module| !config.is_internal_module(&module.name))
+        .filter(|module| !config.documentation.is_hidden_module(&module.name))
+}
+
+/// The modules listed in the sidebar, in the groups set in `gleam.toml`.
+/// Modules not in a group are listed alphabetically after the groups, or on
+/// their own if there are no groups.
+fn module_groups<'a>(
+    config: &PackageConfig,
+    modules: impl Iterator<Item = &'a Module>,
+) -> Vec<ModuleGroup> {
+    let docs = &config.documentation;
+    let mut groups: Vec<_> = docs
+        .module_groups
+        .iter()
+        .map(|group| ModuleGroup {
+            title: group.title.clone(),
+            modules: vec![],
+        })
+        .collect();
+    let mut ungrouped = vec![];
+
+    for (group, module) in modules
+        .map(|module| (docs.module_group(&module.name), module))
+        .sorted_by(|(one_group, one), (other_group, other)| {
+            one_group
+                .map(|(_, position)| position)
+                .cmp(&other_group.map(|(_, position)| position))
+                .then_with(|| one.name.cmp(&other.name))
+        })
+    {
+        let link = Link {
+            path: [&module.name, ".html"].concat(),
+            name: module.name.to_string(),
+        };
+        match group.and_then(|(index, _)| groups.get_mut(index)) {
+            Some(group) => group.modules.push(link),
+            None => ungrouped.push(link),
+        }
+    }
+
+    groups.retain(|group| !group.modules.is_empty());
+    if groups.is_empty() {
+        groups.push(ModuleGroup {
+            title: "Modules".into(),
+            modules: ungrouped,
+        });
+    } else if !ungrouped.is_empty() {
+        groups.push(ModuleGroup {
+            title: "Other modules".into(),
+            modules: ungrouped,
+        });
+    }
+    groups
+}
+
+/// The documented definitions of a module, each sorted by name.
+struct ModuleMembers<'a> {
+    types: Vec<Type<'a>>,
+    constants: Vec<Constant<'a>>,
+    functions: Vec<DocsFunction<'a>>,
+}
+
+impl<'a> ModuleMembers<'a> {
+    fn new(
+        module: &'a Module,
+        source_links: &SourceLinker,
+        package_docs: &im::HashMap<EcoString, EcoString>,
+        linker: &mut AssetLinker<'_, '_>,
+    ) -> Self {
+        let definitions = || {
+            module
+                .ast
+                .definitions
+                .iter()
+                .filter(|statement| !statement.is_internal())
+        };
+        Self {
+            types: definitions()
+                .flat_map(|statement| type_(source_links, linker, statement))
+                .sorted()
+                .collect(),
+            constants: definitions()
+                .flat_map(|statement| constant(source_links, linker, statement))
+                .sorted()
+                .collect(),
+            functions: definitions()
+                .flat_map(|statement| function(source_links, package_docs, linker, statement))
+                .sorted()
+                .collect(),
+        }
+    }
+}
+
+/// The URL of the manifest listing the releases whose documentation is
+/// published under the `versions_url` set in `gleam.toml`, if one is set.
+pub fn versions_manifest_url(config: &PackageConfig) -> Option<String> {
+    let base_url = config.documentation.versions_url.as_deref()?;
+    Some(versions::manifest_url(base_url))
+}
+
+/// Generates the `versions.json` manifest of the releases whose documentation
+/// is published under the `versions_url` set in `gleam.toml`, by adding the
+/// current release to the manifest that has already been published. Nothing
+/// is generated if no `versions_url` is set.
+pub fn generate_versions_manifest(
+    config: &PackageConfig,
+    published: Option<&str>,
+) -> Option<OutputFile> {
+    let base_url = config.documentation.versions_url.as_deref()?;
+    let versions = versions::add_release(base_url, &config.version, published);
+    Some(OutputFile {
+        path: Utf8PathBuf::from("versions.json"),
+        content: Content::Text(
+            serde_json::to_string_pretty(&versions).expect("versions manifest serialisation"),
+        ),
+    })
+}
+
 pub fn generate_json_package_interface(path: Utf8PathBuf, package: &Package) -> OutputFile {
     OutputFile {
         path,
@@ -473,6 +762,24 @@ fn page_unnest_test() {
     assert_eq!(page_unnest("gleam/string/inspect"), "../..");
 }
 
+/// Renders the source code of a module for its source page. Each line number
+/// is an anchor, such as `#L12`, that the "View source" links of the module's
+/// definitions point to.
+fn render_source(module_name: &str, code: &str) -> String {
+    let line_numbers = (1..=code.lines().count().max(1))
+        .map(|line| format!("<a id=\"L{line}\" href=\"#L{line}\">{line}</a>"))
+        .join("\n");
+    format!(
+        r#"<h1 class="module-name"><a href="{unnest}/{module_name}.html">{module_name}</a>.gleam</h1>
+<div class="source-code">
+<pre class="source-line-numbers">{line_numbers}</pre>
+<pre><code class="hljs gleam">{code}</code></pre>
+</div>"#,
+        unnest = page_unnest(&source_page_path(module_name)),
+        code = escape_html_content(code.to_string()),
+    )
+}
+
 fn escape_html_content(it: String) -> String {
     it.replace('&', "&amp;")
         .replace('<', "&lt;")
@@ -499,9 +806,13 @@ fn import_synonyms(parent: &str, child: &str) -> String {
 
 fn function<'a>(
     source_links: &SourceLinker,
+    package_docs: &im::HashMap<EcoString, EcoString>,
+    linker: &mut AssetLinker<'_, '_>,
     statement: &'a TypedDefinition,
 ) -> Option<DocsFunction<'a>> {
     let mut formatter = format::Formatter::new();
+    let mut printer = type_::pretty::Printer::new();
+    printer.with_package_docs(package_docs.clone());
 
     match statement {
         Definition::Function(Function {
@@ -512,27 +823,39 @@ fn function<'a>(
             return_type: ret,
             location,
             deprecation,
+            since,
             ..
-        }) => Some(DocsFunction {
-            name,
-            documentation: markdown_documentation(doc),
-            text_documentation: text_documentation(doc),
-            signature: print(
+        }) => {
+            let signature = print(
                 formatter
-                    .docs_fn_signature(Publicity::Public, name, args, ret.clone())
+                    .docs_fn_signature(Publicity::Public, name, args, ret.clone(), &mut printer)
                     .group(),
-            ),
-            source_url: source_links.url(*location),
-            deprecation_message: match deprecation {
-                Deprecation::NotDeprecated => "".to_string(),
-                Deprecation::Deprecated { message } => message.to_string(),
-            },
-        }),
+            );
+            Some(DocsFunction {
+                name,
+                documentation: markdown_documentation(doc, linker),
+                raw_documentation: raw_documentation(doc.as_deref().unwrap_or_default()),
+                text_documentation: text_documentation(doc),
+                signature: without_links(&signature),
+                signature_html: linked_html(&signature),
+                source_url: source_links.url(*location),
+                source_page_url: source_links.page_url(*location),
+                deprecation_message: deprecation_message(deprecation),
+                since: since.as_deref().unwrap_or_default().to_string(),
+            })
+        }
 
         _ => None,
     }
 }
 
+fn deprecation_message(deprecation: &Deprecation) -> String {
+    match deprecation {
+        Deprecation::NotDeprecated => "".to_string(),
+        Deprecation::Deprecated { message } => message.to_string(),
+    }
+}
+
 fn text_documentation(doc: &Option<EcoString>) -> String {
     let raw_text = doc
         .as_ref()
@@ -543,9 +866,9 @@ fn text_documentation(doc: &Option<EcoString>) -> String {
     raw_text.replace("```gleam", "").replace("```", "")
 }
 
-fn markdown_documentation(doc: &Option<EcoString>) -> String {
+fn markdown_documentation(doc: &Option<EcoString>, linker: &mut AssetLinker<'_, '_>) -> String {
     doc.as_deref()
-        .map(|doc| render_markdown(doc, MarkdownSource::Comment))
+        .map(|doc| render_markdown(doc, MarkdownSource::Comment, linker))
         .unwrap_or_default()
 }
 
@@ -559,24 +882,63 @@ enum MarkdownSource {
     Standalone,
 }
 
-fn render_markdown(text: &str, source: MarkdownSource) -> String {
+/// The Markdown of a documentation comment, without the space each line
+/// starts with after the slashes.
+fn raw_documentation(doc: &str) -> String {
+    // Doc comments start with "///\s", which can confuse the markdown parser
+    // and prevent tables from rendering correctly, so remove that first space.
+    doc.split('\n')
+        .map(|s| s.strip_prefix(' ').unwrap_or(s))
+        .join("\n")
+}
+
+fn render_markdown(text: &str, source: MarkdownSource, linker: &mut AssetLinker<'_, '_>) -> String {
+    use pulldown_cmark::{CowStr, Event, Tag};
+
     let text = match source {
         MarkdownSource::Standalone => text.into(),
-        // Doc comments start with "///\s", which can confuse the markdown parser
-        // and prevent tables from rendering correctly, so remove that first space.
-        MarkdownSource::Comment => text
-            .split('\n')
-            .map(|s| s.strip_prefix(' ').unwrap_or(s))
-            .join("\n"),
+        MarkdownSource::Comment => raw_documentation(text),
     };
 
     let mut s = String::with_capacity(text.len() * 3 / 2);
-    let p = pulldown_cmark::Parser::new_ext(&text, pulldown_cmark::Options::all());
+    let p = pulldown_cmark::Parser::new_ext(&text, pulldown_cmark::Options::all()).map(|event| {
+        match event {
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => Event::Start(Tag::Link {
+                link_type,
+                dest_url: linker.link(&dest_url).map(CowStr::from).unwrap_or(dest_url),
+                title,
+                id,
+            }),
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => Event::Start(Tag::Image {
+                link_type,
+                dest_url: linker.link(&dest_url).map(CowStr::from).unwrap_or(dest_url),
+                title,
+                id,
+            }),
+            Event::Html(html) => Event::Html(linker.html(&html).into()),
+            Event::InlineHtml(html) => Event::InlineHtml(linker.html(&html).into()),
+            event => event,
+        }
+    });
     pulldown_cmark::html::push_html(&mut s, p);
     s
 }
 
-fn type_<'a>(source_links: &SourceLinker, statement: &'a TypedDefinition) -> Option<Type<'a>> {
+fn type_<'a>(
+    source_links: &SourceLinker,
+    linker: &mut AssetLinker<'_, '_>,
+    statement: &'a TypedDefinition,
+) -> Option<Type<'a>> {
     let mut formatter = format::Formatter::new();
 
     match statement {
@@ -586,18 +948,20 @@ fn type_<'a>(source_links: &SourceLinker, statement: &'a TypedDefinition) -> Opt
             // We are not interested in showing the exact implementation in the
             // documentation and we could add things like colours, etc.
             definition: print(formatter.custom_type(ct)),
-            documentation: markdown_documentation(&ct.documentation),
+            documentation: markdown_documentation(&ct.documentation, linker),
+            raw_documentation: raw_documentation(ct.documentation.as_deref().unwrap_or_default()),
             text_documentation: text_documentation(&ct.documentation),
-            deprecation_message: match &ct.deprecation {
-                Deprecation::NotDeprecated => "".to_string(),
-                Deprecation::Deprecated { message } => message.to_string(),
-            },
+            deprecation_message: deprecation_message(&ct.deprecation),
+            since: ct.since.as_deref().unwrap_or_default().to_string(),
             constructors: ct
                 .constructors
                 .iter()
                 .map(|constructor| TypeConstructor {
                     definition: print(formatter.record_constructor(constructor)),
-                    documentation: markdown_documentation(&constructor.documentation),
+                    documentation: markdown_documentation(&constructor.documentation, linker),
+                    raw_documentation: raw_documentation(
+                        constructor.documentation.as_deref().unwrap_or_default(),
+                    ),
                     text_documentation: text_documentation(&constructor.documentation),
                     arguments: constructor
                         .arguments
@@ -605,13 +969,15 @@ fn type_<'a>(source_links: &SourceLinker, statement: &'a TypedDefinition) -> Opt
                         .filter_map(|arg| arg.label.as_ref().map(|label| (arg, label)))
                         .map(|(argument, label)| TypeConstructorArg {
                             name: label.trim_end().to_string(),
-                            doc: markdown_documentation(&argument.doc),
+                            doc: markdown_documentation(&argument.doc, linker),
+                            raw_doc: raw_documentation(argument.doc.as_deref().unwrap_or_default()),
                         })
                         .filter(|arg| !arg.doc.is_empty())
                         .collect(),
                 })
                 .collect(),
             source_url: source_links.url(ct.location),
+            source_page_url: source_links.page_url(ct.location),
             opaque: ct.opaque,
         }),
 
@@ -623,6 +989,7 @@ fn type_<'a>(source_links: &SourceLinker, statement: &'a TypedDefinition) -> Opt
             documentation: doc,
             location,
             deprecation,
+            since,
             ..
         }) => Some(Type {
             name,
@@ -631,14 +998,14 @@ fn type_<'a>(source_links: &SourceLinker, statement: &'a TypedDefinition) -> Opt
                     .docs_opaque_custom_type(Publicity::Public, name, parameters, location)
                     .group(),
             ),
-            documentation: markdown_documentation(doc),
+            documentation: markdown_documentation(doc, linker),
+            raw_documentation: raw_documentation(doc.as_deref().unwrap_or_default()),
             text_documentation: text_documentation(doc),
             constructors: vec![],
             source_url: source_links.url(*location),
-            deprecation_message: match deprecation {
-                Deprecation::NotDeprecated => "".to_string(),
-                Deprecation::Deprecated { message } => message.to_string(),
-            },
+            source_page_url: source_links.page_url(*location),
+            deprecation_message: deprecation_message(deprecation),
+            since: since.as_deref().unwrap_or_default().to_string(),
             opaque: true,
         }),
 
@@ -650,6 +1017,7 @@ fn type_<'a>(source_links: &SourceLinker, statement: &'a TypedDefinition) -> Opt
             parameters: args,
             location,
             deprecation,
+            since,
             ..
         }) => Some(Type {
             name,
@@ -658,14 +1026,14 @@ fn type_<'a>(source_links: &SourceLinker, statement: &'a TypedDefinition) -> Opt
                     .type_alias(Publicity::Public, name, args, typ, deprecation)
                     .group(),
             ),
-            documentation: markdown_documentation(doc),
+            documentation: markdown_documentation(doc, linker),
+            raw_documentation: raw_documentation(doc.as_deref().unwrap_or_default()),
             text_documentation: text_documentation(doc),
             constructors: vec![],
             source_url: source_links.url(*location),
-            deprecation_message: match deprecation {
-                Deprecation::NotDeprecated => "".to_string(),
-                Deprecation::Deprecated { message } => message.to_string(),
-            },
+            source_page_url: source_links.page_url(*location),
+            deprecation_message: deprecation_message(deprecation),
+            since: since.as_deref().unwrap_or_default().to_string(),
             opaque: false,
         }),
 
@@ -675,6 +1043,7 @@ fn type_<'a>(source_links: &SourceLinker, statement: &'a TypedDefinition) -> Opt
 
 fn constant<'a>(
     source_links: &SourceLinker,
+    linker: &mut AssetLinker<'_, '_>,
     statement: &'a TypedDefinition,
 ) -> Option<Constant<'a>> {
     let mut formatter = format::Formatter::new();
@@ -685,13 +1054,19 @@ fn constant<'a>(
             name,
             value,
             location,
+            deprecation,
+            since,
             ..
         }) => Some(Constant {
             name,
             definition: print(formatter.docs_const_expr(Publicity::Public, name, value)),
-            documentation: markdown_documentation(doc),
+            documentation: markdown_documentation(doc, linker),
+            raw_documentation: raw_documentation(doc.as_deref().unwrap_or_default()),
             text_documentation: text_documentation(doc),
             source_url: source_links.url(*location),
+            source_page_url: source_links.page_url(*location),
+            deprecation_message: deprecation_message(deprecation),
+            since: since.as_deref().unwrap_or_default().to_string(),
         }),
 
         _ => None,
@@ -702,26 +1077,73 @@ fn print(doc: pretty::Document<'_>) -> String {
     doc.to_pretty_string(MAX_COLUMNS)
 }
 
+/// Escapes text printed by the type printer, turning the types it marked as
+/// links into links to their documentation.
+fn linked_html(printed: &str) -> String {
+    escape_html_content(printed.to_string())
+        .replace(LINK_START, "<a href=\"")
+        .replace(LINK_TEXT, "\">")
+        .replace(LINK_END, "</a>")
+}
+
+/// Removes the links marked by the type printer, leaving the plain text.
+fn without_links(printed: &str) -> String {
+    let mut text = String::with_capacity(printed.len());
+    let mut in_url = false;
+    for char in printed.chars() {
+        match char {
+            LINK_START => in_url = true,
+            LINK_TEXT => in_url = false,
+            LINK_END => (),
+            _ if in_url => (),
+            _ => text.push(char),
+        }
+    }
+    text
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone)]
 struct Link {
     name: String,
     path: String,
 }
 
+/// See the `branding` function. Each field is empty if it has not been set.
+struct Branding {
+    /// The path of the logo, relative to the root of the documentation.
+    logo: String,
+    /// The path of the favicon, relative to the root of the documentation.
+    favicon: String,
+    /// Declarations of the CSS variables for the colours of the theme.
+    colours: String,
+    head_html: String,
+}
+
+#[derive(PartialEq, Eq, Clone)]
+struct ModuleGroup {
+    title: String,
+    modules: Vec<Link>,
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord)]
 struct DocsFunction<'a> {
     name: &'a str,
     signature: String,
+    signature_html: String,
     documentation: String,
+    raw_documentation: String,
     text_documentation: String,
     source_url: String,
+    source_page_url: String,
     deprecation_message: String,
+    since: String,
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
 struct TypeConstructor {
     definition: String,
     documentation: String,
+    raw_documentation: String,
     text_documentation: String,
     arguments: Vec<TypeConstructorArg>,
 }
@@ -730,6 +1152,7 @@ struct TypeConstructor {
 struct TypeConstructorArg {
     name: String,
     doc: String,
+    raw_doc: String,
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -737,10 +1160,13 @@ struct Type<'a> {
     name: &'a str,
     definition: String,
     documentation: String,
+    raw_documentation: String,
     constructors: Vec<TypeConstructor>,
     text_documentation: String,
     source_url: String,
+    source_page_url: String,
     deprecation_message: String,
+    since: String,
     opaque: bool,
 }
 
@@ -749,8 +1175,12 @@ struct Constant<'a> {
     name: &'a str,
     definition: String,
     documentation: String,
+    raw_documentation: String,
     text_documentation: String,
     source_url: String,
+    source_page_url: String,
+    deprecation_message: String,
+    since: String,
 }
 
 #[derive(Template)]
@@ -764,7 +1194,9 @@ struct PageTemplate<'a> {
     project_version: &'a str,
     pages: &'a [Link],
     links: &'a [Link],
-    modules: &'a [Link],
+    module_groups: &'a [ModuleGroup],
+    branding: &'a Branding,
+    versions_manifest_url: &'a str,
     content: String,
     rendering_timestamp: &'a str,
 }
@@ -781,7 +1213,9 @@ struct ModuleTemplate<'a> {
     project_version: &'a str,
     pages: &'a [Link],
     links: &'a [Link],
-    modules: &'a [Link],
+    module_groups: &'a [ModuleGroup],
+    branding: &'a Branding,
+    versions_manifest_url: &'a str,
     functions: Vec<DocsFunction<'a>>,
     types: Vec<Type<'a>>,
     constants: Vec<Constant<'a>>,
@@ -789,7 +1223,7 @@ struct ModuleTemplate<'a> {
     rendering_timestamp: &'a str,
 }
 
-#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[derive(Serialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 struct SearchIndex {
     doc: String,
     title: String,
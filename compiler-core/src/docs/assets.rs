@@ -0,0 +1,224 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::OnceLock,
+};
+
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use regex::{Captures, Regex};
+
+use crate::{
+    config::DocsPage,
+    error::{FileIoAction, FileKind},
+    io::{Content, FileSystemReader, OutputFile},
+    paths::ProjectPaths,
+    Error, Result,
+};
+
+/// The files of the project that relative links and images in the README,
+/// the pages from `gleam.toml`, and documentation comments point to. These
+/// are copied into the `assets` directory of the documentation and the links
+/// are rewritten to point at the copies, so they still work once the
+/// documentation has been published.
+///
+/// Links to the source of a documentation page are rewritten to point at the
+/// page instead.
+pub(super) struct LocalAssets<'a> {
+    root: Utf8PathBuf,
+    fs: &'a dyn FileSystemReader,
+    /// The path of each documentation page, by the path of its source
+    /// relative to the root of the project.
+    pages: HashMap<Utf8PathBuf, String>,
+    /// The paths of the linked files, relative to the root of the project.
+    linked: BTreeSet<Utf8PathBuf>,
+}
+
+impl<'a> LocalAssets<'a> {
+    pub(super) fn new(
+        paths: &ProjectPaths,
+        fs: &'a dyn FileSystemReader,
+        docs_pages: &[DocsPage],
+    ) -> Self {
+        let pages = docs_pages
+            .iter()
+            .filter_map(|page| {
+                let source = relative_to_root(paths, &page.source);
+                Some((normalise(&source)?, page.path.clone()))
+            })
+            .collect();
+        Self {
+            root: paths.root().to_path_buf(),
+            fs,
+            pages,
+            linked: BTreeSet::new(),
+        }
+    }
+
+    /// A linker for the Markdown of a page nested `unnest` deep in the
+    /// documentation, with relative links resolved from `directory`.
+    pub(super) fn linker(&mut self, directory: &Utf8Path, unnest: &str) -> AssetLinker<'_, 'a> {
+        AssetLinker {
+            assets: self,
+            directory: directory.to_path_buf(),
+            unnest: unnest.to_string(),
+        }
+    }
+
+    /// The linker for the Markdown of a documentation page, which has its
+    /// relative links resolved from the directory of the page's source.
+    pub(super) fn page_linker(
+        &mut self,
+        paths: &ProjectPaths,
+        page: &DocsPage,
+        unnest: &str,
+    ) -> AssetLinker<'_, 'a> {
+        let source = relative_to_root(paths, &page.source);
+        let directory = source.parent().unwrap_or(Utf8Path::new(""));
+        self.linker(directory, unnest)
+    }
+
+    /// Adds a file of the project used by every page, such as the logo,
+    /// returning the path of its copy relative to the root of the
+    /// documentation. Unlike a linked file, it has to exist.
+    pub(super) fn add(&mut self, path: &Utf8Path) -> Result<String> {
+        let error = |reason: &str| Error::FileIo {
+            kind: FileKind::File,
+            action: FileIoAction::Read,
+            path: path.to_path_buf(),
+            err: Some(reason.into()),
+        };
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let relative =
+            normalise(relative).ok_or_else(|| error("The file is outside of the project"))?;
+        if !self.fs.is_file(&self.root.join(&relative)) {
+            return Err(error("The file does not exist"));
+        }
+        let link = format!("assets/{relative}");
+        let _ = self.linked.insert(relative);
+        Ok(link)
+    }
+
+    /// The copies of the linked files to add to the documentation.
+    pub(super) fn into_outputs(self) -> Vec<OutputFile> {
+        self.linked
+            .into_iter()
+            .filter_map(|path| {
+                let content = self.fs.read_bytes(&self.root.join(&path)).ok()?;
+                Some(OutputFile {
+                    path: Utf8PathBuf::from("assets").join(path),
+                    content: Content::Binary(content),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Rewrites the relative links of a piece of Markdown being rendered into a
+/// documentation page.
+pub(super) struct AssetLinker<'a, 'b> {
+    assets: &'a mut LocalAssets<'b>,
+    /// The directory relative links are resolved from, relative to the root of
+    /// the project.
+    directory: Utf8PathBuf,
+    unnest: String,
+}
+
+impl AssetLinker<'_, '_> {
+    /// The link to use in place of a link or image destination, if it is a
+    /// relative link to a documentation page or to a file of the project.
+    pub(super) fn link(&mut self, destination: &str) -> Option<String> {
+        if destination.is_empty()
+            || destination.starts_with(['/', '#', '?'])
+            || has_scheme(destination)
+        {
+            return None;
+        }
+
+        let end = destination.find(['?', '#']).unwrap_or(destination.len());
+        let (path, suffix) = destination.split_at(end);
+        let path = normalise(&self.directory.join(path))?;
+        let unnest = &self.unnest;
+
+        if let Some(page) = self.assets.pages.get(&path) {
+            return Some(format!("{unnest}/{page}{suffix}"));
+        }
+        if !self.assets.fs.is_file(&self.assets.root.join(&path)) {
+            return None;
+        }
+        let link = format!("{unnest}/assets/{path}{suffix}");
+        let _ = self.assets.linked.insert(path);
+        Some(link)
+    }
+
+    /// Rewrites the `src` and `href` attributes of HTML written in Markdown,
+    /// such as the `<img>` tags READMEs use to size their images.
+    pub(super) fn html(&mut self, html: &str) -> String {
+        static ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+        ATTRIBUTE
+            .get_or_init(|| Regex::new(r#"\b(src|href)="([^"]*)""#).expect("attribute regex"))
+            .replace_all(html, |captures: &Captures<'_>| {
+                let destination = &captures[2];
+                let link = self
+                    .link(destination)
+                    .unwrap_or_else(|| destination.to_string());
+                format!(r#"{}="{link}""#, &captures[1])
+            })
+            .into_owned()
+    }
+}
+
+/// Whether a link has a URL scheme, such as `https:` or `mailto:`, rather
+/// than being a path.
+fn has_scheme(destination: &str) -> bool {
+    match destination.split_once(':') {
+        Some((scheme, _)) => {
+            !scheme.is_empty()
+                && scheme
+                    .chars()
+                    .all(|char| char.is_ascii_alphanumeric() || matches!(char, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+fn relative_to_root(paths: &ProjectPaths, path: &Utf8Path) -> Utf8PathBuf {
+    path.strip_prefix(paths.root())
+        .unwrap_or(path)
+        .to_path_buf()
+}
+
+/// Removes the `.` and `..` components of a relative path, or returns nothing
+/// if the path is absolute or leaves the root of the project.
+fn normalise(path: &Utf8Path) -> Option<Utf8PathBuf> {
+    let mut normalised = Utf8PathBuf::new();
+    for component in path.components() {
+        match component {
+            Utf8Component::Normal(name) => normalised.push(name),
+            Utf8Component::CurDir => (),
+            Utf8Component::ParentDir => {
+                if !normalised.pop() {
+                    return None;
+                }
+            }
+            Utf8Component::RootDir | Utf8Component::Prefix(_) => return None,
+        }
+    }
+    Some(normalised)
+}
+
+#[test]
+fn paths_leaving_the_project_are_not_normalised() {
+    assert_eq!(
+        normalise(Utf8Path::new("docs/../images/./diagram.png")),
+        Some("images/diagram.png".into())
+    );
+    assert_eq!(normalise(Utf8Path::new("docs/../../secrets.txt")), None);
+    assert_eq!(normalise(Utf8Path::new("/etc/passwd")), None);
+}
+
+#[test]
+fn links_with_a_scheme_are_not_paths() {
+    assert!(has_scheme("https://gleam.run"));
+    assert!(has_scheme("mailto:hello@example.com"));
+    assert!(!has_scheme("images/diagram.png"));
+    assert!(!has_scheme("images/time:12.png"));
+}
@@ -0,0 +1,124 @@
+use ecow::EcoString;
+use serde::Serialize;
+
+use super::{Constant, DocsFunction, ModuleMembers, Type};
+
+/// The documentation of a package as a single JSON file, for search services
+/// and other tools to ingest rather than scraping the HTML pages.
+#[derive(Serialize, Debug)]
+pub(super) struct PackageDump<'a> {
+    pub(super) name: &'a str,
+    pub(super) version: String,
+    pub(super) modules: Vec<ModuleDump<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+pub(super) struct ModuleDump<'a> {
+    name: EcoString,
+    /// The path of the module's page, relative to the root of the
+    /// documentation.
+    url: String,
+    /// The module's documentation, as Markdown.
+    documentation: String,
+    types: Vec<DefinitionDump<'a>>,
+    constants: Vec<DefinitionDump<'a>>,
+    functions: Vec<DefinitionDump<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+struct DefinitionDump<'a> {
+    name: &'a str,
+    /// The definition of a type or constant, or the signature of a function.
+    definition: String,
+    /// The definition's documentation, as Markdown.
+    documentation: String,
+    /// The path of the definition on its module's page, relative to the root
+    /// of the documentation.
+    url: String,
+    deprecation: Option<String>,
+    since: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    constructors: Vec<ConstructorDump>,
+}
+
+#[derive(Serialize, Debug)]
+struct ConstructorDump {
+    definition: String,
+    documentation: String,
+}
+
+impl<'a> ModuleDump<'a> {
+    pub(super) fn new(name: &EcoString, documentation: &str, members: &ModuleMembers<'a>) -> Self {
+        let url = |member: &str| format!("{name}.html#{member}");
+        Self {
+            name: name.clone(),
+            url: format!("{name}.html"),
+            documentation: documentation.trim().to_string(),
+            types: members
+                .types
+                .iter()
+                .map(|typ| type_(typ, url(typ.name)))
+                .collect(),
+            constants: members
+                .constants
+                .iter()
+                .map(|constant| self::constant(constant, url(constant.name)))
+                .collect(),
+            functions: members
+                .functions
+                .iter()
+                .map(|function| self::function(function, url(function.name)))
+                .collect(),
+        }
+    }
+}
+
+fn type_<'a>(typ: &Type<'a>, url: String) -> DefinitionDump<'a> {
+    DefinitionDump {
+        name: typ.name,
+        definition: typ.definition.clone(),
+        documentation: typ.raw_documentation.trim().to_string(),
+        url,
+        deprecation: non_empty(&typ.deprecation_message),
+        since: non_empty(&typ.since),
+        constructors: typ
+            .constructors
+            .iter()
+            .map(|constructor| ConstructorDump {
+                definition: constructor.definition.clone(),
+                documentation: constructor.raw_documentation.trim().to_string(),
+            })
+            .collect(),
+    }
+}
+
+fn constant<'a>(constant: &Constant<'a>, url: String) -> DefinitionDump<'a> {
+    DefinitionDump {
+        name: constant.name,
+        definition: constant.definition.clone(),
+        documentation: constant.raw_documentation.trim().to_string(),
+        url,
+        deprecation: non_empty(&constant.deprecation_message),
+        since: non_empty(&constant.since),
+        constructors: vec![],
+    }
+}
+
+fn function<'a>(function: &DocsFunction<'a>, url: String) -> DefinitionDump<'a> {
+    DefinitionDump {
+        name: function.name,
+        definition: function.signature.clone(),
+        documentation: function.raw_documentation.trim().to_string(),
+        url,
+        deprecation: non_empty(&function.deprecation_message),
+        since: non_empty(&function.since),
+        constructors: vec![],
+    }
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    match text {
+        "" => None,
+        text => Some(text.to_string()),
+    }
+}
@@ -0,0 +1,123 @@
+use itertools::Itertools;
+
+use super::{Constant, DocsFunction, ModuleMembers, Type, TypeConstructor};
+
+/// Renders the documentation page of a module as Markdown, with the same
+/// sections as the HTML page: the module's documentation followed by its
+/// types, constants, and functions.
+pub(super) fn module_page(name: &str, documentation: &str, members: &ModuleMembers<'_>) -> String {
+    let mut sections = vec![format!("# {name}"), documentation.trim().to_string()];
+
+    if !members.types.is_empty() {
+        sections.push("## Types".into());
+        sections.extend(members.types.iter().map(type_));
+    }
+    if !members.constants.is_empty() {
+        sections.push("## Constants".into());
+        sections.extend(members.constants.iter().map(constant));
+    }
+    if !members.functions.is_empty() {
+        sections.push("## Functions".into());
+        sections.extend(members.functions.iter().map(function));
+    }
+
+    let mut page = join(sections);
+    page.push('\n');
+    page
+}
+
+fn type_(typ: &Type<'_>) -> String {
+    let heading = match typ.opaque {
+        true => format!("### `{}` (opaque)", typ.name),
+        false => format!("### `{}`", typ.name),
+    };
+    let mut parts = vec![
+        heading,
+        source_link(&typ.source_url),
+        since(&typ.since),
+        deprecation(&typ.deprecation_message),
+        typ.raw_documentation.trim().to_string(),
+        code_block(&typ.definition),
+    ];
+    if !typ.constructors.is_empty() {
+        parts.push("#### Constructors".into());
+        parts.extend(typ.constructors.iter().map(constructor));
+    }
+    join(parts)
+}
+
+fn constructor(constructor: &TypeConstructor) -> String {
+    let mut parts = vec![
+        code_block(&constructor.definition),
+        constructor.raw_documentation.trim().to_string(),
+    ];
+    if !constructor.arguments.is_empty() {
+        parts.push("Arguments:".into());
+        parts.push(
+            constructor
+                .arguments
+                .iter()
+                .map(|argument| {
+                    let doc = argument.raw_doc.trim().replace('\n', "\n  ");
+                    format!("- `{}`: {doc}", argument.name)
+                })
+                .join("\n"),
+        );
+    }
+    join(parts)
+}
+
+fn constant(constant: &Constant<'_>) -> String {
+    join(vec![
+        format!("### `{}`", constant.name),
+        source_link(&constant.source_url),
+        code_block(&constant.definition),
+        since(&constant.since),
+        deprecation(&constant.deprecation_message),
+        constant.raw_documentation.trim().to_string(),
+    ])
+}
+
+fn function(function: &DocsFunction<'_>) -> String {
+    join(vec![
+        format!("### `{}`", function.name),
+        source_link(&function.source_url),
+        code_block(&function.signature),
+        since(&function.since),
+        deprecation(&function.deprecation_message),
+        function.raw_documentation.trim().to_string(),
+    ])
+}
+
+fn code_block(code: &str) -> String {
+    format!("```gleam\n{code}\n```")
+}
+
+fn source_link(url: &str) -> String {
+    match url {
+        "" => String::new(),
+        url => format!("[View source]({url})"),
+    }
+}
+
+fn since(version: &str) -> String {
+    match version {
+        "" => String::new(),
+        version => format!("**Since:** {version}"),
+    }
+}
+
+fn deprecation(message: &str) -> String {
+    match message {
+        "" => String::new(),
+        message => format!("**Deprecated:** {message}"),
+    }
+}
+
+/// Joins the parts of a section with blank lines, leaving out empty ones.
+fn join(parts: Vec<String>) -> String {
+    parts
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .join("\n\n")
+}
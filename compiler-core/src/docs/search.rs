@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::SearchIndex;
+
+/// The search data written to `search-data.js`. Along with the pages that can
+/// be found it holds an index of every word in their titles and content, built
+/// here so that the browser doesn't have to build one each time the
+/// documentation is opened.
+///
+/// The positions of words are given in UTF-16 code units, as that is how
+/// JavaScript indexes strings.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub(super) struct SearchData {
+    pub(super) docs: Vec<SearchIndex>,
+    pub(super) terms: BTreeMap<String, Vec<Posting>>,
+}
+
+/// Where a term is found in one of the pages: the index of the page, the field
+/// it is in, and the start and length of each occurrence.
+pub(super) type Posting = (usize, SearchField, Vec<(usize, usize)>);
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum SearchField {
+    Title,
+    Content,
+}
+
+impl SearchData {
+    pub(super) fn new(docs: Vec<SearchIndex>) -> Self {
+        let mut terms: BTreeMap<String, Vec<Posting>> = BTreeMap::new();
+        for (doc, index) in docs.iter().enumerate() {
+            for (field, text) in [
+                (SearchField::Title, &index.title),
+                (SearchField::Content, &index.content),
+            ] {
+                let mut positions: BTreeMap<String, Vec<(usize, usize)>> = BTreeMap::new();
+                for (term, position) in words(text) {
+                    positions.entry(term).or_default().push(position);
+                }
+                for (term, positions) in positions {
+                    terms.entry(term).or_default().push((doc, field, positions));
+                }
+            }
+        }
+        Self { docs, terms }
+    }
+}
+
+/// Splits text into lowercase words made of letters, digits, and underscores,
+/// so `fn map(list: List(a))` is found when searching for `map` or `list`.
+/// HTML entities such as `&lt;` are not words.
+fn words(text: &str) -> Vec<(String, (usize, usize))> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut start = 0;
+    let mut offset = 0;
+    let mut previous = None;
+    let mut chars = text.chars().peekable();
+    while let Some(char) = chars.next() {
+        if is_word_char(char) {
+            if word.is_empty() {
+                start = offset;
+            }
+            word.extend(char.to_lowercase());
+            offset += char.len_utf16();
+            if !chars.peek().is_some_and(|next| is_word_char(*next)) {
+                let is_entity = previous == Some('&') && chars.peek() == Some(&';');
+                if !is_entity {
+                    words.push((std::mem::take(&mut word), (start, offset - start)));
+                }
+                word.clear();
+            }
+        } else {
+            previous = Some(char);
+            offset += char.len_utf16();
+        }
+    }
+    words
+}
+
+fn is_word_char(char: char) -> bool {
+    char.is_alphanumeric() || char == '_'
+}
@@ -11,6 +11,9 @@ use camino::{Utf8Component, Utf8Path};
 pub struct SourceLinker {
     line_numbers: LineNumbers,
     url_pattern: Option<(String, String)>,
+    /// The path of the module's source page in the generated documentation,
+    /// relative to the module's documentation page.
+    page_path: String,
 }
 
 impl SourceLinker {
@@ -76,8 +79,20 @@ impl SourceLinker {
         SourceLinker {
             line_numbers: LineNumbers::new(&module.code),
             url_pattern,
+            page_path: format!(
+                "{}/{}",
+                super::page_unnest(&module.name),
+                source_page_path(&module.name)
+            ),
         }
     }
+
+    /// The URL of the line a definition starts on in the source page
+    /// generated for its module.
+    pub fn page_url(&self, span: SrcSpan) -> String {
+        let line = self.line_numbers.line_number(span.start);
+        format!("{}#L{line}", self.page_path)
+    }
     pub fn url(&self, span: SrcSpan) -> String {
         match &self.url_pattern {
             Some((base, line_sep)) => {
@@ -91,6 +106,12 @@ impl SourceLinker {
     }
 }
 
+/// The path of the page showing the source code of a module, relative to the
+/// root of the documentation.
+pub fn source_page_path(module_name: &str) -> String {
+    format!("src/{module_name}.html")
+}
+
 fn to_url_path(path: &Utf8Path) -> Option<String> {
     let mut buf = String::new();
     for c in path.components() {
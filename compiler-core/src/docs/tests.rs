@@ -1,16 +1,23 @@
-use std::time::SystemTime;
+use std::{collections::HashMap, time::SystemTime};
 
+use super::search::{SearchData, SearchField};
 use crate::{
-    build::{Mode, NullTelemetry, PackageCompiler, StaleTracker, TargetCodegenConfiguration},
+    build::{
+        Mode, Module, NullTelemetry, PackageCompiler, StaleTracker, TargetCodegenConfiguration,
+    },
     config::{DocsPage, PackageConfig},
+    error::{FileIoAction, FileKind},
     io::{memory::InMemoryFileSystem, FileSystemWriter},
+    manifest::{Manifest, ManifestPackage, ManifestPackageSource},
     paths::ProjectPaths,
     uid::UniqueIdGenerator,
     version::COMPILER_VERSION,
     warning::WarningEmitter,
+    Error,
 };
 use camino::Utf8PathBuf;
 use ecow::EcoString;
+use hexpm::version::Version;
 use itertools::Itertools;
 
 fn compile_with_markdown_pages(
@@ -18,17 +25,74 @@ fn compile_with_markdown_pages(
     modules: Vec<(&str, &str)>,
     markdown_pages: Vec<(&str, &str)>,
 ) -> EcoString {
-    let fs = InMemoryFileSystem::new();
-    for (name, src) in modules {
-        fs.write(&Utf8PathBuf::from(format!("/src/{name}")), src)
-            .unwrap();
-    }
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![],
+    };
+    compile_with_dependencies(config, modules, markdown_pages, vec![], manifest)
+}
+
+fn compile_with_dependencies(
+    config: PackageConfig,
+    modules: Vec<(&str, &str)>,
+    markdown_pages: Vec<(&str, &str)>,
+    dependencies: Vec<(&str, Vec<(&str, &str)>)>,
+    manifest: Manifest,
+) -> EcoString {
+    let modules = compile_modules(&config, modules, dependencies);
+    let (pages_fs, docs_pages) = pages(markdown_pages);
+    let paths = ProjectPaths::new(Utf8PathBuf::from("/"));
+
+    super::generate_html(
+        &paths,
+        &config,
+        &modules,
+        &docs_pages,
+        &manifest,
+        pages_fs,
+        SystemTime::UNIX_EPOCH,
+    )
+    .expect("should generate documentation")
+    .into_iter()
+    .filter(|file| file.path.extension() == Some("html"))
+    .filter(|file| !file.path.starts_with("src"))
+    .sorted_by(|a, b| a.path.cmp(&b.path))
+    .flat_map(|file| {
+        Some(format!(
+            "//// {}\n\n{}\n\n",
+            file.path.as_str(),
+            file.content
+                .text()?
+                .replace(COMPILER_VERSION, "GLEAM_VERSION_HERE")
+        ))
+    })
+    .collect::<String>()
+    .chars()
+    .collect()
+}
 
-    // We're saving the pages under a different `InMemoryFileSystem` for these
-    // tests so we don't have to juggle with borrows and lifetimes.
-    // The package compiler is going to take ownership of `fs` but later
-    // `generate_html` also needs a `FileSystemReader` to go and read the
-    // markdown pages' content.
+fn compile_markdown(
+    config: PackageConfig,
+    modules: Vec<(&str, &str)>,
+    markdown_pages: Vec<(&str, &str)>,
+) -> String {
+    let modules = compile_modules(&config, modules, vec![]);
+    let (pages_fs, docs_pages) = pages(markdown_pages);
+    let paths = ProjectPaths::new(Utf8PathBuf::from("/"));
+
+    super::generate_markdown(&paths, &config, &modules, &docs_pages, pages_fs)
+        .into_iter()
+        .sorted_by(|a, b| a.path.cmp(&b.path))
+        .flat_map(|file| Some(format!("//// {}\n\n{}\n", file.path, file.content.text()?)))
+        .collect()
+}
+
+/// The pages are saved under a different `InMemoryFileSystem` to the modules
+/// so we don't have to juggle with borrows and lifetimes. The package
+/// compiler is going to take ownership of its file system but later the
+/// documentation generator also needs a `FileSystemReader` to go and read the
+/// markdown pages' content.
+fn pages(markdown_pages: Vec<(&str, &str)>) -> (InMemoryFileSystem, Vec<DocsPage>) {
     let pages_fs = InMemoryFileSystem::new();
     for (title, src) in markdown_pages.iter() {
         pages_fs
@@ -36,6 +100,28 @@ fn compile_with_markdown_pages(
             .unwrap();
     }
 
+    let docs_pages = markdown_pages
+        .into_iter()
+        .map(|(title, _)| DocsPage {
+            title: (*title).into(),
+            path: format!("{title}.html"),
+            source: format!("{title}.md").into(),
+        })
+        .collect_vec();
+    (pages_fs, docs_pages)
+}
+
+fn compile_modules(
+    config: &PackageConfig,
+    modules: Vec<(&str, &str)>,
+    dependencies: Vec<(&str, Vec<(&str, &str)>)>,
+) -> Vec<Module> {
+    let fs = InMemoryFileSystem::new();
+    for (name, src) in modules {
+        fs.write(&Utf8PathBuf::from(format!("/src/{name}")), src)
+            .unwrap();
+    }
+
     let ids = UniqueIdGenerator::new();
     let mut type_manifests = im::HashMap::new();
     let mut defined_modules = im::HashMap::new();
@@ -45,9 +131,44 @@ fn compile_with_markdown_pages(
     let root = Utf8PathBuf::from("/");
     let build = root.join("build");
     let lib = root.join("lib");
-    let paths = ProjectPaths::new(root.clone());
+
+    for (package, modules) in dependencies {
+        let fs = InMemoryFileSystem::new();
+        let package_root = lib.join(package);
+        for (name, src) in modules {
+            fs.write(&package_root.join("src").join(name), src)
+                .expect("write dependency module");
+        }
+        let package_build = build.join(package);
+        let config = PackageConfig {
+            name: package.into(),
+            ..Default::default()
+        };
+        let mut compiler = PackageCompiler::new(
+            &config,
+            Mode::Dev,
+            &package_root,
+            &package_build,
+            &lib,
+            &target,
+            ids.clone(),
+            fs,
+        );
+        compiler.write_entrypoint = false;
+        compiler.write_metadata = false;
+        let _ = compiler
+            .compile(
+                &warnings,
+                &mut type_manifests,
+                &mut defined_modules,
+                &mut im::HashSet::new(),
+                &mut StaleTracker::default(),
+                &NullTelemetry,
+            )
+            .expect("compile dependency");
+    }
     let mut compiler =
-        PackageCompiler::new(&config, Mode::Dev, &root, &build, &lib, &target, ids, fs);
+        PackageCompiler::new(config, Mode::Dev, &root, &build, &lib, &target, ids, fs);
     compiler.write_entrypoint = false;
     compiler.write_metadata = false;
     compiler.compile_beam_bytecode = true;
@@ -56,6 +177,7 @@ fn compile_with_markdown_pages(
             &warnings,
             &mut type_manifests,
             &mut defined_modules,
+            &mut im::HashSet::new(),
             &mut StaleTracker::default(),
             &NullTelemetry,
         )
@@ -64,39 +186,7 @@ fn compile_with_markdown_pages(
     for module in &mut modules {
         module.attach_doc_and_module_comments();
     }
-
-    let docs_pages = markdown_pages
-        .into_iter()
-        .map(|(title, _)| DocsPage {
-            title: (*title).into(),
-            path: format!("{title}.html"),
-            source: format!("{title}.md").into(),
-        })
-        .collect_vec();
-
-    super::generate_html(
-        &paths,
-        &config,
-        &modules,
-        &docs_pages,
-        pages_fs,
-        SystemTime::UNIX_EPOCH,
-    )
-    .into_iter()
-    .filter(|file| file.path.extension() == Some("html"))
-    .sorted_by(|a, b| a.path.cmp(&b.path))
-    .flat_map(|file| {
-        Some(format!(
-            "//// {}\n\n{}\n\n",
-            file.path.as_str(),
-            file.content
-                .text()?
-                .replace(COMPILER_VERSION, "GLEAM_VERSION_HERE")
-        ))
-    })
-    .collect::<String>()
-    .chars()
-    .collect()
+    modules
 }
 
 pub fn compile(config: PackageConfig, modules: Vec<(&str, &str)>) -> EcoString {
@@ -267,3 +357,691 @@ fn markdown_code_from_module_comment_is_trimmed() {
     )];
     insta::assert_snapshot!(compile(config, modules));
 }
+
+fn search_index(title: &str, content: &str) -> super::SearchIndex {
+    super::SearchIndex {
+        doc: "app".into(),
+        title: title.into(),
+        content: content.into(),
+        url: "app.html".into(),
+    }
+}
+
+#[test]
+fn search_index_includes_words_from_signatures_and_docs() {
+    let data = SearchData::new(vec![search_index(
+        "map",
+        "pub fn map(list: List(a), with fun: fn(a) -&gt; b) -&gt; List(b)\n Maps a list.",
+    )]);
+    assert_eq!(
+        data.terms.keys().collect_vec(),
+        vec!["a", "b", "fn", "fun", "list", "map", "maps", "pub", "with"]
+    );
+    assert_eq!(
+        data.terms.get("list"),
+        Some(&vec![(
+            0,
+            SearchField::Content,
+            vec![(11, 4), (17, 4), (57, 4), (73, 4)]
+        )])
+    );
+    assert_eq!(
+        data.terms.get("map"),
+        Some(&vec![
+            (0, SearchField::Title, vec![(0, 3)]),
+            (0, SearchField::Content, vec![(7, 3)])
+        ])
+    );
+}
+
+#[test]
+fn search_index_includes_extra_pages_with_their_titles() {
+    let mut config = PackageConfig::default();
+    config.name = "app".into();
+    let modules = compile_modules(&config, vec![], vec![]);
+    let (fs, docs_pages) = pages(vec![("guide", "# Routing\n\nRoutes are matched in order.")]);
+    let paths = ProjectPaths::new(Utf8PathBuf::from("/"));
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![],
+    };
+    let files = super::generate_html(
+        &paths,
+        &config,
+        &modules,
+        &docs_pages,
+        &manifest,
+        fs,
+        SystemTime::UNIX_EPOCH,
+    )
+    .expect("should generate documentation");
+    let search_data = files
+        .iter()
+        .find(|file| file.path == "search-data.js")
+        .and_then(|file| file.content.text())
+        .expect("search data");
+
+    assert!(search_data.contains(r#"{"doc":"app","title":"guide","#));
+    assert!(search_data.contains(r#""url":"guide.html"}"#));
+    assert!(search_data.contains(r#""routes":"#));
+}
+
+#[test]
+fn search_index_positions_are_utf16_offsets() {
+    let data = SearchData::new(vec![search_index("wibble", "🌸 café wobble")]);
+    assert_eq!(
+        data.terms.get("wobble"),
+        Some(&vec![(0, SearchField::Content, vec![(8, 6)])])
+    );
+    assert_eq!(
+        data.terms.get("café"),
+        Some(&vec![(0, SearchField::Content, vec![(3, 4)])])
+    );
+}
+
+#[test]
+fn types_from_dependencies_link_to_their_documentation() {
+    let config = PackageConfig::default();
+    let modules = vec![(
+        "app.gleam",
+        "
+import gleam/dict.{type Dict}
+import local/thing.{type Thing}
+
+pub fn from_dict(dict: Dict(String, Int)) -> Thing {
+  todo
+}
+",
+    )];
+    let dependencies = vec![
+        (
+            "gleam_stdlib",
+            vec![("gleam/dict.gleam", "pub type Dict(k, v)")],
+        ),
+        ("local_thing", vec![("local/thing.gleam", "pub type Thing")]),
+    ];
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![
+            ManifestPackage {
+                name: "gleam_stdlib".into(),
+                version: Version::new(0, 34, 0),
+                ..Default::default()
+            },
+            ManifestPackage {
+                name: "local_thing".into(),
+                source: ManifestPackageSource::Local {
+                    path: "../local_thing".into(),
+                },
+                ..Default::default()
+            },
+        ],
+    };
+    insta::assert_snapshot!(compile_with_dependencies(
+        config,
+        modules,
+        vec![],
+        dependencies,
+        manifest
+    ));
+}
+
+#[test]
+fn markdown_docs() {
+    let config = PackageConfig::default();
+    let modules = vec![(
+        "app.gleam",
+        r#"
+//// The app module.
+
+/// A shape.
+pub type Shape {
+  /// A circle.
+  Circle(
+    /// The radius of the circle.
+    radius: Float,
+  )
+  Square(Float)
+}
+
+/// A secret.
+pub opaque type Secret {
+  Secret
+}
+
+/// The answer.
+pub const answer = 42
+
+/// Turns a shape into a string.
+///
+/// ```gleam
+/// to_string(Circle(1.0))
+/// ```
+@deprecated("Use `describe` instead")
+pub fn to_string(shape: Shape) -> String {
+  todo
+}
+"#,
+    )];
+    let pages = vec![("guide", "# A guide\n\nSome text.")];
+    insta::assert_snapshot!(compile_markdown(config, modules, pages));
+}
+
+#[test]
+fn source_pages_have_line_anchors() {
+    let config = PackageConfig::default();
+    let modules = vec![(
+        "app/shapes.gleam",
+        r#"/// A shape.
+pub type Shape {
+  Circle(Float)
+}
+
+pub fn area(shape: Shape) -> Float {
+  case shape {
+    Circle(radius) -> radius *. radius *. 3.14
+  }
+}
+"#,
+    )];
+    let modules = compile_modules(&config, modules, vec![]);
+    let paths = ProjectPaths::new(Utf8PathBuf::from("/"));
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![],
+    };
+    let files = super::generate_html(
+        &paths,
+        &config,
+        &modules,
+        &[],
+        &manifest,
+        InMemoryFileSystem::new(),
+        SystemTime::UNIX_EPOCH,
+    )
+    .expect("should generate documentation");
+    let text = |path: &str| {
+        files
+            .iter()
+            .find(|file| file.path == path)
+            .and_then(|file| file.content.text())
+            .expect("documentation file")
+    };
+
+    let module_page = text("app/shapes.html");
+    assert!(module_page.contains(r#"href="../src/app/shapes.html#L2">View source</a>"#));
+    assert!(module_page.contains(r#"href="../src/app/shapes.html#L6">View source</a>"#));
+
+    let source_page = text("src/app/shapes.html");
+    let content = source_page
+        .split_once(r#"<main class="content">"#)
+        .and_then(|(_, rest)| rest.split_once("</main>"))
+        .map(|(content, _)| content.trim())
+        .expect("page content");
+    insta::assert_snapshot!(content);
+}
+
+#[test]
+fn modules_are_listed_in_the_groups_from_the_config() {
+    let config = toml::from_str::<PackageConfig>(
+        r#"
+name = "app"
+
+[documentation]
+hidden_modules = ["app/unstable"]
+
+[[documentation.module_groups]]
+title = "Getting started"
+modules = ["app/router", "app"]
+
+[[documentation.module_groups]]
+title = "Middleware"
+modules = ["app/middleware/*"]
+
+[[documentation.module_groups]]
+title = "Nothing"
+modules = ["app/missing"]
+"#,
+    )
+    .expect("should parse config");
+    let modules = vec![
+        ("app.gleam", "pub fn main() { Nil }"),
+        ("app/router.gleam", "pub fn route() { Nil }"),
+        ("app/middleware/logging.gleam", "pub fn log() { Nil }"),
+        ("app/middleware/cors.gleam", "pub fn cors() { Nil }"),
+        ("app/helpers.gleam", "pub fn help() { Nil }"),
+        ("app/unstable.gleam", "pub fn experiment() { Nil }"),
+    ];
+    let modules = compile_modules(&config, modules, vec![]);
+    let paths = ProjectPaths::new(Utf8PathBuf::from("/"));
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![],
+    };
+    let files = super::generate_html(
+        &paths,
+        &config,
+        &modules,
+        &[],
+        &manifest,
+        InMemoryFileSystem::new(),
+        SystemTime::UNIX_EPOCH,
+    )
+    .expect("should generate documentation");
+
+    assert!(!files.iter().any(|file| file.path == "app/unstable.html"));
+    let sidebar = files
+        .iter()
+        .find(|file| file.path == "app.html")
+        .and_then(|file| file.content.text())
+        .and_then(|page| page.split_once(r#"<nav class="sidebar">"#))
+        .and_then(|(_, rest)| rest.split_once("</nav>"))
+        .map(|(sidebar, _)| {
+            sidebar
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .join("\n")
+        })
+        .expect("sidebar");
+    insta::assert_snapshot!(sidebar);
+}
+
+#[test]
+fn branding_from_the_config_is_added_to_every_page() {
+    let config = toml::from_str::<PackageConfig>(
+        r##"
+name = "app"
+
+[documentation]
+logo = "brand/logo.svg"
+favicon = "favicon.png"
+accent_colour = "#0055ff"
+link_colour = "rebeccapurple"
+head_html = "<meta name=\"robots\" content=\"noindex\">"
+"##,
+    )
+    .expect("should parse config");
+    let modules = compile_modules(
+        &config,
+        vec![("app/router.gleam", "pub fn route() { Nil }")],
+        vec![],
+    );
+    let (fs, pages) = pages(vec![("README", "# App\n\n![Logo](images/logo.svg)")]);
+    fs.write(&Utf8PathBuf::from("/brand/logo.svg"), "<svg></svg>")
+        .expect("write logo");
+    fs.write(&Utf8PathBuf::from("/images/logo.svg"), "<svg/>")
+        .expect("write image");
+    fs.write_bytes(&Utf8PathBuf::from("/favicon.png"), &[137, 80, 78, 71])
+        .expect("write favicon");
+    let paths = ProjectPaths::new(Utf8PathBuf::from("/"));
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![],
+    };
+    let files = super::generate_html(
+        &paths,
+        &config,
+        &modules,
+        &pages,
+        &manifest,
+        fs,
+        SystemTime::UNIX_EPOCH,
+    )
+    .expect("should generate documentation");
+
+    // Files with the same name in different directories are both kept
+    let assets = files
+        .iter()
+        .filter(|file| file.path.starts_with("assets"))
+        .map(|file| file.path.as_str())
+        .sorted()
+        .collect_vec();
+    assert_eq!(
+        assets,
+        vec![
+            "assets/brand/logo.svg",
+            "assets/favicon.png",
+            "assets/images/logo.svg"
+        ]
+    );
+    for path in ["README.html", "app/router.html", "src/app/router.html"] {
+        let page = files
+            .iter()
+            .find(|file| file.path == path)
+            .and_then(|file| file.content.text())
+            .expect("page");
+        let unnest = super::page_unnest(path);
+        assert!(page.contains(&format!(
+            r#"<link rel="icon" href="{unnest}/assets/favicon.png"/>"#
+        )));
+        assert!(page.contains(&format!(
+            r#"<img class="project-logo" src="{unnest}/assets/brand/logo.svg" alt=""/>app</a>"#
+        )));
+        assert!(
+            page.contains("body, body.theme-dark { --accent: #0055ff; --links: rebeccapurple; }")
+        );
+        assert!(page.contains(r#"<meta name="robots" content="noindex">"#));
+    }
+}
+
+#[test]
+fn missing_branding_file_is_an_error() {
+    let config = toml::from_str::<PackageConfig>(
+        r#"
+name = "app"
+
+[documentation]
+favicon = "favicon.png"
+"#,
+    )
+    .expect("should parse config");
+    let (fs, pages) = pages(vec![("README", "# App")]);
+    let paths = ProjectPaths::new(Utf8PathBuf::from("/"));
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![],
+    };
+    let result = super::generate_html(
+        &paths,
+        &config,
+        &[],
+        &pages,
+        &manifest,
+        fs,
+        SystemTime::UNIX_EPOCH,
+    );
+
+    assert_eq!(
+        result.map(|_| ()),
+        Err(Error::FileIo {
+            kind: FileKind::File,
+            action: FileIoAction::Read,
+            path: "favicon.png".into(),
+            err: Some("The file does not exist".into()),
+        })
+    );
+}
+
+#[test]
+fn versions_manifest_adds_the_current_release() {
+    let config = toml::from_str::<PackageConfig>(
+        r#"
+name = "app"
+version = "1.2.0"
+
+[documentation]
+versions_url = "https://docs.example.com/app/"
+"#,
+    )
+    .expect("should parse config");
+    let published = r#"[
+  {"version": "v1.10.0", "url": "https://docs.example.com/app/1.10.0/"},
+  {"version": "v1.2.0", "url": "https://old.example.com/app/1.2.0/"},
+  {"version": "v1.1.0", "url": "https://docs.example.com/app/1.1.0/"},
+  {"version": "nightly", "url": "https://docs.example.com/app/nightly/"}
+]"#;
+
+    assert_eq!(
+        super::versions_manifest_url(&config).as_deref(),
+        Some("https://docs.example.com/app/versions.json")
+    );
+    let manifest =
+        super::generate_versions_manifest(&config, Some(published)).expect("versions manifest");
+    assert_eq!(manifest.path, "versions.json");
+    insta::assert_snapshot!(manifest.content.text().expect("text manifest"));
+}
+
+#[test]
+fn versions_manifest_without_a_published_manifest() {
+    let config = toml::from_str::<PackageConfig>(
+        r#"
+name = "app"
+version = "1.2.0"
+
+[documentation]
+versions_url = "https://docs.example.com/app"
+"#,
+    )
+    .expect("should parse config");
+    let manifest = super::generate_versions_manifest(&config, Some("<html>Not found</html>"))
+        .expect("versions manifest");
+    insta::assert_snapshot!(manifest.content.text().expect("text manifest"));
+}
+
+#[test]
+fn no_versions_manifest_without_a_versions_url() {
+    let config = PackageConfig::default();
+    assert_eq!(super::versions_manifest_url(&config), None);
+    assert!(super::generate_versions_manifest(&config, None).is_none());
+}
+
+#[test]
+fn relative_links_to_project_files_are_copied_into_the_documentation() {
+    let config = PackageConfig::default();
+    let modules = compile_modules(
+        &config,
+        vec![(
+            "app/router.gleam",
+            "//// ![Routing](images/routing.svg)
+
+/// See [the guide](docs/guide.md#routes).
+pub fn route() { Nil }",
+        )],
+        vec![],
+    );
+    let (fs, mut pages) = pages(vec![(
+        "README",
+        r#"# App
+
+![Screenshot](./images/screenshot.png)
+<img src="images/logo.svg" width="100">
+
+[Guide](docs/guide.md), [missing](images/missing.png), [Gleam](https://gleam.run)"#,
+    )]);
+    pages.push(DocsPage {
+        title: "Guide".into(),
+        path: "guide.html".into(),
+        source: "docs/guide.md".into(),
+    });
+    fs.write(
+        &Utf8PathBuf::from("docs/guide.md"),
+        "![Logo](../images/logo.svg)",
+    )
+    .expect("write guide");
+    for image in ["screenshot.png", "logo.svg", "routing.svg"] {
+        fs.write_bytes(&Utf8PathBuf::from("/images").join(image), &[1, 2, 3])
+            .expect("write image");
+    }
+    let paths = ProjectPaths::new(Utf8PathBuf::from("/"));
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![],
+    };
+    let files = super::generate_html(
+        &paths,
+        &config,
+        &modules,
+        &pages,
+        &manifest,
+        fs,
+        SystemTime::UNIX_EPOCH,
+    )
+    .expect("should generate documentation");
+    let page = |path: &str| {
+        files
+            .iter()
+            .find(|file| file.path == path)
+            .and_then(|file| file.content.text())
+            .expect("page")
+    };
+
+    let assets = files
+        .iter()
+        .filter(|file| file.path.starts_with("assets"))
+        .map(|file| file.path.as_str())
+        .sorted()
+        .collect_vec();
+    assert_eq!(
+        assets,
+        vec![
+            "assets/images/logo.svg",
+            "assets/images/routing.svg",
+            "assets/images/screenshot.png"
+        ]
+    );
+
+    let readme = page("README.html");
+    assert!(readme.contains(r#"<img src="./assets/images/screenshot.png" alt="Screenshot" />"#));
+    assert!(readme.contains(r#"<img src="./assets/images/logo.svg" width="100">"#));
+    assert!(readme.contains(r#"<a href="./guide.html">Guide</a>"#));
+    assert!(readme.contains(r#"<a href="images/missing.png">missing</a>"#));
+    assert!(readme.contains(r#"<a href="https://gleam.run">Gleam</a>"#));
+
+    assert!(page("guide.html").contains(r#"<img src="./assets/images/logo.svg" alt="Logo" />"#));
+
+    let module = page("app/router.html");
+    assert!(module.contains(r#"<img src="../assets/images/routing.svg" alt="Routing" />"#));
+    assert!(module.contains(r#"<a href="../guide.html#routes">the guide</a>"#));
+}
+
+#[test]
+fn since_and_deprecated_badges_are_shown() {
+    let config = PackageConfig::default();
+    let modules = vec![(
+        "app.gleam",
+        r#"
+@since("2.1.0")
+pub type Shape {
+  Circle
+}
+
+@since("2.2.0")
+@deprecated("Use `tau` instead")
+pub const pi = 3.14
+
+@since("2.3.0")
+pub fn area(shape: Shape) -> Float {
+  todo
+}
+"#,
+    )];
+    let html = compile_with_markdown_pages(config.clone(), modules.clone(), vec![]);
+    assert!(html.contains(r#"<span class="visibility-tag">since 2.1.0</span>"#));
+    assert!(html.contains(r#"<span class="visibility-tag">since 2.2.0</span>"#));
+    assert!(html.contains(r#"<span class="visibility-tag">since 2.3.0</span>"#));
+    assert!(html.contains(r#"<span class="visibility-tag">deprecated</span>"#));
+    assert!(html.contains("<b>Deprecated:</b> Use `tau` instead"));
+
+    let markdown = compile_markdown(config, modules, vec![]);
+    assert!(markdown.contains("**Since:** 2.1.0"));
+    assert!(markdown.contains("**Since:** 2.2.0\n\n**Deprecated:** Use `tau` instead"));
+    assert!(markdown.contains("**Since:** 2.3.0"));
+}
+
+#[test]
+fn documentation_dump_is_generated_alongside_the_html() {
+    let config = toml::from_str::<PackageConfig>(
+        r#"
+name = "app"
+version = "1.2.0"
+description = "An app"
+"#,
+    )
+    .expect("should parse config");
+    let modules = compile_modules(
+        &config,
+        vec![(
+            "app/shapes.gleam",
+            r#"//// Shapes and their areas.
+
+/// A shape.
+pub type Shape {
+  /// A circle.
+  Circle(radius: Float)
+}
+
+/// The ratio of a circle's circumference to its diameter.
+@deprecated("Use `tau` instead")
+pub const pi = 3.14
+
+/// The area of a shape.
+@since("1.1.0")
+pub fn area(shape: Shape) -> Float {
+  todo
+}
+"#,
+        )],
+        vec![],
+    );
+    let (fs, pages) = pages(vec![]);
+    let paths = ProjectPaths::new(Utf8PathBuf::from("/"));
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![],
+    };
+    let files = super::generate_html(
+        &paths,
+        &config,
+        &modules,
+        &pages,
+        &manifest,
+        fs,
+        SystemTime::UNIX_EPOCH,
+    )
+    .expect("should generate documentation");
+    let file = |path: &str| {
+        files
+            .iter()
+            .find(|file| file.path == path)
+            .and_then(|file| file.content.text())
+            .expect("file")
+    };
+
+    let dump: serde_json::Value =
+        serde_json::from_str(file("documentation.json")).expect("dump should be JSON");
+    assert_eq!(
+        dump,
+        serde_json::json!({
+            "name": "app",
+            "version": "1.2.0",
+            "modules": [{
+                "name": "app/shapes",
+                "url": "app/shapes.html",
+                "documentation": "Shapes and their areas.",
+                "types": [{
+                    "name": "Shape",
+                    "definition": "pub type Shape {\n  Circle(radius: Float)\n}",
+                    "documentation": "A shape.",
+                    "url": "app/shapes.html#Shape",
+                    "deprecation": null,
+                    "since": null,
+                    "constructors": [{
+                        "definition": "Circle(radius: Float)",
+                        "documentation": "A circle.",
+                    }],
+                }],
+                "constants": [{
+                    "name": "pi",
+                    "definition": "pub const pi: Float",
+                    "documentation": "The ratio of a circle's circumference to its diameter.",
+                    "url": "app/shapes.html#pi",
+                    "deprecation": "Use `tau` instead",
+                    "since": null,
+                }],
+                "functions": [{
+                    "name": "area",
+                    "definition": "pub fn area(shape: Shape) -> Float",
+                    "documentation": "The area of a shape.",
+                    "url": "app/shapes.html#area",
+                    "deprecation": null,
+                    "since": "1.1.0",
+                }],
+            }],
+        })
+    );
+
+    let text = file("documentation.txt");
+    assert!(text.starts_with("# app v1.2.0\n\nAn app\n\n# app/shapes\n\nShapes and their areas."));
+    assert!(text.contains("### `area`\n\n```gleam\npub fn area(shape: Shape) -> Float\n```"));
+}
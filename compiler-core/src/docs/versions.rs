@@ -0,0 +1,45 @@
+use std::cmp::Reverse;
+
+use hexpm::version::Version;
+use serde::{Deserialize, Serialize};
+
+/// A release listed in the `versions.json` manifest, in the format the
+/// version dropdown of the documentation expects.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub(super) struct DocsVersion {
+    /// The version, prefixed with `v`.
+    pub(super) version: String,
+    pub(super) url: String,
+}
+
+/// The URL of the manifest listing the releases published under a base URL.
+pub(super) fn manifest_url(base_url: &str) -> String {
+    format!("{}/versions.json", base_url.trim_end_matches('/'))
+}
+
+/// Adds a release to the releases of a manifest that has already been
+/// published, replacing any earlier entry for the same version. The newest
+/// versions come first. A published manifest that can't be parsed is treated
+/// as empty, so the new manifest lists only this release.
+pub(super) fn add_release(
+    base_url: &str,
+    version: &Version,
+    published: Option<&str>,
+) -> Vec<DocsVersion> {
+    let mut versions: Vec<DocsVersion> = published
+        .and_then(|published| serde_json::from_str(published).ok())
+        .unwrap_or_default();
+
+    let release = DocsVersion {
+        version: format!("v{version}"),
+        url: format!("{}/{version}/", base_url.trim_end_matches('/')),
+    };
+    versions.retain(|existing| existing.version != release.version);
+    versions.push(release);
+
+    // Versions that can't be parsed are kept, after the ones that can.
+    versions.sort_by_key(|release| {
+        Reverse(Version::parse(release.version.trim_start_matches('v')).ok())
+    });
+    versions
+}
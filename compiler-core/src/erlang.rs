@@ -25,7 +25,13 @@ use itertools::Itertools;
 use pattern::{pattern, requires_guard};
 use regex::{Captures, Regex};
 use std::sync::OnceLock;
-use std::{char, collections::HashMap, ops::Deref, str::FromStr, sync::Arc};
+use std::{
+    char,
+    collections::{BTreeMap, HashMap},
+    ops::Deref,
+    str::FromStr,
+    sync::Arc,
+};
 use vec1::Vec1;
 
 const INDENT: isize = 4;
@@ -148,6 +154,47 @@ pub fn module<'a>(module: &'a TypedModule, line_numbers: &'a LineNumbers) -> Res
     Ok(module_document(module, line_numbers)?.to_pretty_string(MAX_COLUMNS))
 }
 
+/// The line of the Gleam code each line of the Erlang a module is compiled to
+/// was generated from, so the lines of the Erlang reported by the runtime can
+/// be reported as those of the Gleam code.
+pub fn line_mapping(module: &TypedModule, line_numbers: &LineNumbers) -> Result<LineMapping> {
+    let (_, mappings) =
+        module_document(module, line_numbers)?.to_pretty_string_with_source_mappings(MAX_COLUMNS);
+    let mut lines = BTreeMap::new();
+    for mapping in mappings {
+        // The line of the first statement starting on an Erlang line is the
+        // one it is generated from.
+        let _ = lines
+            .entry(mapping.line + 1)
+            .or_insert_with(|| line_numbers.line_number(mapping.byte_index));
+    }
+    Ok(LineMapping { lines })
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineMapping {
+    /// The Erlang lines that statements start on, with the Gleam lines of the
+    /// statements. Lines are counted from 1.
+    lines: BTreeMap<u32, u32>,
+}
+
+impl LineMapping {
+    /// The Gleam line an Erlang line was generated from, which is the one of
+    /// the last statement started on or before it.
+    pub fn gleam_line(&self, erlang_line: u32) -> Option<u32> {
+        self.lines
+            .range(..=erlang_line)
+            .next_back()
+            .map(|(_, line)| *line)
+    }
+}
+
+impl From<BTreeMap<u32, u32>> for LineMapping {
+    fn from(lines: BTreeMap<u32, u32>) -> Self {
+        Self { lines }
+    }
+}
+
 fn module_document<'a>(
     module: &'a TypedModule,
     line_numbers: &'a LineNumbers,
@@ -265,7 +312,7 @@ fn register_imports(
                 .filter(|&id| !constructor_var_usages.contains_key(id))
                 .sorted()
                 .map(|&id| Type::Var {
-                    type_: Arc::new(std::cell::RefCell::new(TypeVar::Generic { id })),
+                    type_: Arc::new(crate::type_::TypeVarCell::new(TypeVar::Generic { id })),
                 })
                 .collect();
             let phantom_vars_constructor = if !phantom_vars.is_empty() {
@@ -381,7 +428,8 @@ fn module_function<'a>(
         .map(|(module, function)| docvec![atom(module), ":", atom(function), arguments.clone()])
         .unwrap_or_else(|| statement_sequence(&function.body, &mut env));
 
-    let doc = spec
+    let doc = source_location(function.location.start)
+        .append(spec)
         .append(atom_string(function.name.to_string()))
         .append(arguments)
         .append(" ->")
@@ -732,6 +780,7 @@ fn statement_sequence<'a>(statements: &'a [TypedStatement], env: &mut Env<'a>) -
     let count = statements.len();
     let mut documents = Vec::with_capacity(count * 3);
     for (i, expression) in statements.iter().enumerate() {
+        documents.push(source_location(expression.location().start));
         documents.push(statement(expression, env).group());
 
         if i + 1 < count {
@@ -1101,7 +1150,7 @@ fn clause<'a>(clause: &'a TypedClause, env: &mut Env<'a>) -> Document<'a> {
 fn clause_consequence<'a>(consequence: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
     match consequence {
         TypedExpr::Block { statements, .. } => statement_sequence(statements, env),
-        _ => expr(consequence, env),
+        _ => source_location(consequence.location().start).append(expr(consequence, env)),
     }
 }
 
@@ -1459,10 +1508,12 @@ fn needs_begin_end_wrapping(expression: &TypedExpr) -> bool {
         | TypedExpr::TupleIndex { .. }
         | TypedExpr::Todo { .. }
         | TypedExpr::Panic { .. }
+        | TypedExpr::Echo { .. }
         | TypedExpr::BitArray { .. }
         | TypedExpr::RecordUpdate { .. }
         | TypedExpr::NegateBool { .. }
-        | TypedExpr::NegateInt { .. } => false,
+        | TypedExpr::NegateInt { .. }
+        | TypedExpr::Invalid { .. } => false,
     }
 }
 
@@ -1482,6 +1533,34 @@ fn panic<'a>(location: SrcSpan, message: Option<&'a TypedExpr>, env: &mut Env<'a
     erlang_error("panic", &message, location, vec![], env)
 }
 
+/// Prints the value to standard error along with the module and line it is
+/// printed from, evaluating to the value.
+fn echo<'a>(expression: &'a TypedExpr, location: SrcSpan, env: &mut Env<'a>) -> Document<'a> {
+    let value = maybe_block_expr(expression, env);
+    let name = env.next_local_var_name("echo");
+    let line_number = env.line_numbers.line_number(location.start);
+    let print = docvec![
+        "io:format(standard_error, \"~ts:~p~n~tp~n\", [",
+        env.module.to_doc().surround("<<\"", "\"/utf8>>"),
+        ", ",
+        line_number,
+        ", ",
+        name.clone(),
+        "])"
+    ];
+    begin_end(docvec![
+        name.clone(),
+        " = ",
+        value,
+        ",",
+        line(),
+        print,
+        ",",
+        line(),
+        name
+    ])
+}
+
 fn erlang_error<'a>(
     name: &'a str,
     message: &Document<'a>,
@@ -1534,10 +1613,19 @@ fn expr<'a>(expression: &'a TypedExpr, env: &mut Env<'a>) -> Document<'a> {
             ..
         } => todo(label.as_deref(), *location, env),
 
+        TypedExpr::Invalid { .. } => {
+            unreachable!("Invalid expressions must not be present for Erlang generation")
+        }
+
         TypedExpr::Panic {
             location, message, ..
         } => panic(*location, message.as_deref(), env),
 
+        TypedExpr::Echo {
+            location,
+            expression,
+        } => echo(expression, *location, env),
+
         TypedExpr::Int { value, .. } => int(value),
         TypedExpr::Float { value, .. } => float(value),
         TypedExpr::String { value, .. } => string(value),
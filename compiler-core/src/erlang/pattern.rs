@@ -56,6 +56,14 @@ fn print<'a>(
             }
         }
 
+        Pattern::Constant { constructor, .. } => const_inline(
+            constructor
+                .as_ref()
+                .and_then(|constructor| constructor.pattern_literal())
+                .expect("Constant pattern must have a literal value"),
+            env,
+        ),
+
         Pattern::Variable { name, .. } if define_variables => {
             vars.push(name);
             env.next_local_var_name(name)
@@ -2,6 +2,7 @@ use crate::analyse::TargetSupport;
 use crate::config::PackageConfig;
 use crate::type_::PRELUDE_MODULE_NAME;
 use crate::{
+    ast::TypedModule,
     build::{Origin, Target},
     erlang::module,
     line_numbers::LineNumbers,
@@ -14,10 +15,12 @@ mod case;
 mod conditional_compilation;
 mod consts;
 mod custom_types;
+mod echo;
 mod external_fn;
 mod functions;
 mod guards;
 mod let_assert;
+mod line_mapping;
 mod numbers;
 mod panic;
 mod patterns;
@@ -30,6 +33,13 @@ mod use_;
 mod variables;
 
 pub fn compile_test_project(src: &str, dep: Option<(&str, &str, &str)>) -> String {
+    let ast = infer_test_project(src, dep);
+    let line_numbers = LineNumbers::new(src);
+    module(&ast, &line_numbers).unwrap()
+}
+
+/// Type checks the module, named `my/mod`, along with the given dependency.
+pub fn infer_test_project(src: &str, dep: Option<(&str, &str, &str)>) -> TypedModule {
     let mut modules = im::HashMap::new();
     let ids = UniqueIdGenerator::new();
     // DUPE: preludeinsertion
@@ -71,7 +81,7 @@ pub fn compile_test_project(src: &str, dep: Option<(&str, &str, &str)>) -> Strin
     let mut ast = parsed.module;
     ast.name = "my/mod".into();
     let line_numbers = LineNumbers::new(src);
-    let ast = crate::analyse::infer_module::<()>(
+    crate::analyse::infer_module::<()>(
         Target::Erlang,
         &ids,
         ast,
@@ -84,9 +94,7 @@ pub fn compile_test_project(src: &str, dep: Option<(&str, &str, &str)>) -> Strin
         &config,
         "".into(),
     )
-    .expect("should successfully infer root Erlang");
-    let line_numbers = LineNumbers::new(src);
-    module(&ast, &line_numbers).unwrap()
+    .expect("should successfully infer root Erlang")
 }
 
 #[macro_export]
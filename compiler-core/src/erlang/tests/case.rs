@@ -101,3 +101,43 @@ pub fn main() {
 "#,
     );
 }
+
+#[test]
+fn constant_pattern() {
+    assert_erl!(
+        (
+            "package",
+            "other",
+            r#"pub const answer = 42
+pub const greeting = "Hello""#
+        ),
+        r#"
+import other as values
+
+pub fn main(x, y) {
+  case x, y {
+    values.answer, _ -> 1
+    _, values.greeting -> 2
+    _, _ -> 3
+  }
+}
+"#
+    )
+}
+
+#[test]
+fn qualified_constant_pattern() {
+    assert_erl!(
+        ("package", "other", "pub const pi = 3.14"),
+        r#"
+import other
+
+pub fn main(x) {
+  case x {
+    other.pi -> True
+    _ -> False
+  }
+}
+"#
+    )
+}
@@ -0,0 +1,83 @@
+use crate::assert_erl;
+
+#[test]
+fn echo() {
+    assert_erl!(
+        r#"
+pub fn main() {
+  echo 1
+}
+"#
+    );
+}
+
+#[test]
+fn echo_of_expression() {
+    assert_erl!(
+        r#"
+pub fn main() {
+  let x = echo 1 + 2
+  x
+}
+"#
+    );
+}
+
+#[test]
+fn echo_in_pipeline() {
+    assert_erl!(
+        r#"
+pub fn main() {
+  [1, 2, 3]
+  |> echo
+  |> wibble
+}
+
+fn wibble(x) {
+  x
+}
+"#
+    );
+}
+
+#[test]
+fn echo_ending_pipeline_followed_by_statement() {
+    assert_erl!(
+        r#"
+pub fn main() {
+  1 |> echo
+  Nil
+}
+"#
+    );
+}
+
+#[test]
+fn echo_of_pipeline() {
+    assert_erl!(
+        r#"
+pub fn main() {
+  echo 1 |> wibble
+}
+
+fn wibble(x) {
+  x
+}
+"#
+    );
+}
+
+#[test]
+fn echo_as_function_argument() {
+    assert_erl!(
+        r#"
+pub fn main() {
+  wibble(echo 1, 2)
+}
+
+fn wibble(x, y) {
+  x + y
+}
+"#
+    );
+}
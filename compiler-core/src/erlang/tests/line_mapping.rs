@@ -0,0 +1,47 @@
+use itertools::Itertools;
+
+use crate::{
+    erlang::{line_mapping, module, tests::infer_test_project},
+    line_numbers::LineNumbers,
+};
+
+/// The generated Erlang, with each line preceded by the number of the Gleam
+/// line it was generated from.
+fn mapped_erlang(src: &str) -> String {
+    let ast = infer_test_project(src, None);
+    let line_numbers = LineNumbers::new(src);
+    let erlang = module(&ast, &line_numbers).expect("should generate Erlang");
+    let mapping = line_mapping(&ast, &line_numbers).expect("should map lines");
+    erlang
+        .lines()
+        .enumerate()
+        .map(|(index, code)| {
+            let line = mapping
+                .gleam_line(index as u32 + 1)
+                .map(|line| line.to_string())
+                .unwrap_or_default();
+            format!("{line:>2} | {code}").trim_end().to_string()
+        })
+        .join("\n")
+}
+
+#[test]
+fn statements_are_mapped_to_their_gleam_lines() {
+    let src = r#"
+pub fn main(x) {
+  let y = x + 1
+  case y {
+    1 -> 2
+    _ -> {
+      let z = y * 2
+      z
+    }
+  }
+}
+
+fn other() {
+  Nil
+}
+"#;
+    insta::assert_snapshot!(insta::internals::AutoName, mapped_erlang(src), src);
+}
@@ -1,8 +1,9 @@
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 use crate::build::{Runtime, Target};
-use crate::diagnostic::{Diagnostic, Label, Location};
+use crate::diagnostic::{Code, Diagnostic, Label, Location};
+use crate::package_interface::compatibility::BreakingChange;
 use crate::type_::error::RecordVariants;
-use crate::type_::error::{MissingAnnotation, UnknownTypeHint};
+use crate::type_::error::{MissingAnnotation, ModuleSuggestion, TypeConstraint, UnknownTypeHint};
 use crate::type_::{error::PatternMatchKind, FieldAccessUsage};
 use crate::{ast::BinOp, parse::error::ParseErrorType, type_::Type};
 use crate::{
@@ -20,6 +21,7 @@ use std::fmt::Debug;
 use std::path::PathBuf;
 use termcolor::Buffer;
 use thiserror::Error;
+use vec1::Vec1;
 
 use camino::{Utf8Path, Utf8PathBuf};
 
@@ -55,7 +57,7 @@ pub enum Error {
     Type {
         path: Utf8PathBuf,
         src: EcoString,
-        error: crate::type_::Error,
+        errors: Vec1<crate::type_::Error>,
     },
 
     #[error("unknown import {import}")]
@@ -104,6 +106,12 @@ pub enum Error {
     #[error("source code incorrectly formatted")]
     Format { problem_files: Vec<Unformatted> },
 
+    #[error("formatter verification failed for {path}")]
+    FormatVerification {
+        path: Utf8PathBuf,
+        problem: FormatVerificationProblem,
+    },
+
     #[error("Hex error: {0}")]
     Hex(String),
 
@@ -143,14 +151,25 @@ pub enum Error {
         suggestion: Option<EcoString>,
     },
 
-    #[error("{module} does not have a main function")]
-    ModuleDoesNotHaveMainFunction { module: EcoString },
+    #[error("{module} does not have a {function} function")]
+    ModuleDoesNotHaveMainFunction {
+        module: EcoString,
+        function: EcoString,
+    },
 
-    #[error("{module}'s main function has the wrong arity so it can not be run")]
-    MainFunctionHasWrongArity { module: EcoString, arity: usize },
+    #[error("{module}'s {function} function has the wrong arity so it can not be run")]
+    MainFunctionHasWrongArity {
+        module: EcoString,
+        function: EcoString,
+        arity: usize,
+    },
 
-    #[error("{module}'s main function does not support the current target")]
-    MainFunctionDoesNotSupportTarget { module: EcoString, target: Target },
+    #[error("{module}'s {function} function does not support the current target")]
+    MainFunctionDoesNotSupportTarget {
+        module: EcoString,
+        function: EcoString,
+        target: Target,
+    },
 
     #[error("{input} is not a valid version. {error}")]
     InvalidVersionFormat { input: String, error: String },
@@ -174,6 +193,29 @@ file_names.iter().map(|x| x.as_str()).join(", "))]
     #[error("warnings are not permitted")]
     ForbiddenWarnings { count: usize },
 
+    #[error("denied warnings were emitted")]
+    DeniedWarnings { count: usize },
+
+    #[error("the release makes breaking changes without a new major version")]
+    BreakingChanges {
+        previous_version: String,
+        version: String,
+        changes: Vec<BreakingChange>,
+    },
+
+    #[error("module {module} imports {imported}, which is not built for {target}")]
+    ImportNotBuiltForTarget {
+        module: EcoString,
+        imported: EcoString,
+        target: Target,
+    },
+
+    #[error("project template {template} could not be used: {error}")]
+    ProjectTemplate { template: String, error: String },
+
+    #[error("javascript module {path} could not be bundled: {reason}")]
+    JavaScriptBundle { path: Utf8PathBuf, reason: String },
+
     #[error("javascript codegen failed")]
     JavaScript {
         path: Utf8PathBuf,
@@ -200,6 +242,12 @@ file_names.iter().map(|x| x.as_str()).join(", "))]
     #[error("Git dependencies are currently unsupported")]
     GitDependencyUnsupported,
 
+    #[error("Dependency versions cannot be resolved offline")]
+    OfflineResolutionRequired,
+
+    #[error("The packages {packages:?} are not in the local cache")]
+    PackagesNotCached { packages: Vec<EcoString> },
+
     #[error("Failed to create canonical path for package {0}")]
     DependencyCanonicalizationFailed(String),
 
@@ -241,6 +289,24 @@ file_names.iter().map(|x| x.as_str()).join(", "))]
     #[error("Opening docs at {path} failed: {error}")]
     FailedToOpenDocs { path: Utf8PathBuf, error: String },
 
+    #[error("Failed to serve docs at {address}")]
+    FailedToServeDocs { address: String, error: String },
+
+    #[error("No tests matched the selection")]
+    NoTestsSelected,
+
+    #[error("Coverage is not supported for the {target} target")]
+    UnsupportedCoverageTarget {
+        target: Target,
+        runtime: Option<Runtime>,
+    },
+
+    #[error("The coverage of the tests was not recorded")]
+    CoverageNotRecorded,
+
+    #[error("the package interface of version {version} could not be downloaded")]
+    PreviousPackageInterfaceUnavailable { version: String, reason: String },
+
     #[error("The package {package} requires a Gleam version satisfying {required_version} and you are using v{gleam_version}")]
     IncompatibleCompilerVersion {
         package: String,
@@ -262,6 +328,15 @@ file_names.iter().map(|x| x.as_str()).join(", "))]
 
     #[error("Corrupt manifest.toml")]
     CorruptManifest,
+
+    #[error("unknown code {code}")]
+    UnknownDiagnosticCode { code: String },
+
+    #[error("no explanation for code {code}")]
+    NoDiagnosticExplanation { code: Code },
+
+    #[error("compilation cancelled")]
+    Cancelled,
 }
 
 impl Error {
@@ -578,6 +653,46 @@ fn edit_distance_with_substrings(a: &str, b: &str, limit: usize) -> Option<usize
     (score <= limit).then_some(score)
 }
 
+/// Labels pointing at the places that caused the types of a type mismatch
+/// to be inferred. Constraints within the error's own span are not shown as
+/// they would only repeat what the main label already points at.
+fn type_constraint_labels(
+    location: &crate::ast::SrcSpan,
+    trace: &[TypeConstraint],
+    printer: &mut Printer,
+) -> Vec<Label> {
+    trace
+        .iter()
+        .filter(|constraint| {
+            constraint.location.start < location.start || constraint.location.end > location.end
+        })
+        .map(|constraint| Label {
+            text: Some(format!(
+                "`{}` was inferred from this {}",
+                printer.pretty_print(&constraint.type_, 0),
+                constraint.kind.description()
+            )),
+            span: constraint.location,
+        })
+        .collect()
+}
+
+fn import_suggestions_hint(suggestions: &[ModuleSuggestion]) -> Option<String> {
+    let imports = suggestions
+        .iter()
+        .filter(|s| matches!(s, ModuleSuggestion::Import { .. }))
+        .map(|s| s.suggestion())
+        .collect_vec();
+    match imports.as_slice() {
+        [] => None,
+        [one] => Some(format!("Did you mean to `{one}`?")),
+        many => Some(format!(
+            "Did you mean to import one of these modules?\n\n    {}",
+            many.join("\n    ")
+        )),
+    }
+}
+
 fn did_you_mean(name: &str, options: &[EcoString]) -> Option<String> {
     // If only one option is given, return that option.
     // This seems to solve the `unknown_variable_3` test.
@@ -615,15 +730,110 @@ fn did_you_mean(name: &str, options: &[EcoString]) -> Option<String> {
 
 impl Error {
     pub fn pretty_string(&self) -> String {
-        self.to_diagnostic().pretty_string()
+        self.to_diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.pretty_string())
+            .join("\n")
     }
 
     pub fn pretty(&self, buffer: &mut Buffer) {
-        self.to_diagnostic().write(buffer)
+        use std::io::Write;
+        for (i, diagnostic) in self.to_diagnostics().iter().enumerate() {
+            if i > 0 {
+                buffer.write_all(b"\n").expect("write new line");
+            }
+            diagnostic.write(buffer);
+        }
+    }
+
+    /// All the diagnostics for this error. Type checking can find more than
+    /// one error in a module, all of which are reported at once.
+    pub fn to_diagnostics(&self) -> Vec<Diagnostic> {
+        match self {
+            Error::Type { path, src, errors } => errors
+                .iter()
+                .map(|error| type_error_diagnostic(path, src, error))
+                .collect(),
+            _ => vec![self.to_diagnostic()],
+        }
+    }
+
+    /// The stable code identifying this kind of error.
+    pub fn code(&self) -> Code {
+        match self {
+            Error::Parse { error, .. } => error.error.code(),
+            Error::Type { errors, .. } => errors.first().code(),
+            Error::UnknownImport { .. } => Code::Error(1),
+            Error::DuplicateModule { .. } => Code::Error(2),
+            Error::DuplicateSourceFile { .. } => Code::Error(3),
+            Error::ImportCycle { .. } => Code::Error(4),
+            Error::PackageCycle { .. } => Code::Error(5),
+            Error::FileIo { .. } => Code::Error(6),
+            Error::NonUtf8Path { .. } => Code::Error(7),
+            Error::GitInitialization { .. } => Code::Error(8),
+            Error::StandardIo { .. } => Code::Error(9),
+            Error::Format { .. } => Code::Error(10),
+            Error::Hex(..) => Code::Error(11),
+            Error::ExpandTar { .. } => Code::Error(12),
+            Error::AddTar { .. } => Code::Error(13),
+            Error::TarFinish(..) => Code::Error(14),
+            Error::Gzip(..) => Code::Error(15),
+            Error::ShellProgramNotFound { .. } => Code::Error(16),
+            Error::ShellCommand { .. } => Code::Error(17),
+            Error::InvalidProjectName { .. } => Code::Error(18),
+            Error::InvalidModuleName { .. } => Code::Error(19),
+            Error::ModuleDoesNotExist { .. } => Code::Error(20),
+            Error::ModuleDoesNotHaveMainFunction { .. } => Code::Error(21),
+            Error::MainFunctionHasWrongArity { .. } => Code::Error(22),
+            Error::MainFunctionDoesNotSupportTarget { .. } => Code::Error(23),
+            Error::InvalidVersionFormat { .. } => Code::Error(24),
+            Error::ProjectRootAlreadyExist { .. } => Code::Error(25),
+            Error::OutputFilesAlreadyExist { .. } => Code::Error(26),
+            Error::UnableToFindProjectRoot { .. } => Code::Error(27),
+            Error::VersionDoesNotMatch { .. } => Code::Error(28),
+            Error::MetadataDecodeError { .. } => Code::Error(29),
+            Error::ForbiddenWarnings { .. } => Code::Error(30),
+            Error::JavaScript { .. } => Code::Error(31),
+            Error::InvalidRuntime { .. } => Code::Error(32),
+            Error::DownloadPackageError { .. } => Code::Error(33),
+            Error::Http(..) => Code::Error(34),
+            Error::GitDependencyUnsupported => Code::Error(35),
+            Error::DependencyCanonicalizationFailed(..) => Code::Error(36),
+            Error::DependencyResolutionFailed(..) => Code::Error(37),
+            Error::DuplicateDependency(..) => Code::Error(38),
+            Error::WrongDependencyProvided { .. } => Code::Error(39),
+            Error::ProvidedDependencyConflict { .. } => Code::Error(40),
+            Error::MissingHexPublishFields { .. } => Code::Error(41),
+            Error::PublishNonHexDependencies { .. } => Code::Error(42),
+            Error::UnsupportedBuildTool { .. } => Code::Error(43),
+            Error::FailedToOpenDocs { .. } => Code::Error(44),
+            Error::IncompatibleCompilerVersion { .. } => Code::Error(45),
+            Error::JavaScriptPreludeRequired => Code::Error(46),
+            Error::CannotPublishTodo { .. } => Code::Error(47),
+            Error::CannotPublishLeakedInternalType { .. } => Code::Error(48),
+            Error::HexPackageSquatting => Code::Error(49),
+            Error::CorruptManifest => Code::Error(50),
+            Error::UnknownDiagnosticCode { .. } => Code::Error(51),
+            Error::FormatVerification { .. } => Code::Error(52),
+            Error::FailedToServeDocs { .. } => Code::Error(53),
+            Error::NoTestsSelected => Code::Error(54),
+            Error::UnsupportedCoverageTarget { .. } => Code::Error(55),
+            Error::OfflineResolutionRequired => Code::Error(56),
+            Error::PackagesNotCached { .. } => Code::Error(57),
+            Error::DeniedWarnings { .. } => Code::Error(58),
+            Error::BreakingChanges { .. } => Code::Error(59),
+            Error::ImportNotBuiltForTarget { .. } => Code::Error(60),
+            Error::ProjectTemplate { .. } => Code::Error(61),
+            Error::JavaScriptBundle { .. } => Code::Error(62),
+            Error::NoDiagnosticExplanation { .. } => Code::Error(63),
+            Error::Cancelled => Code::Error(64),
+            Error::CoverageNotRecorded => Code::Error(65),
+            Error::PreviousPackageInterfaceUnavailable { .. } => Code::Error(66),
+        }
     }
 
     pub fn to_diagnostic(&self) -> Diagnostic {
-        use crate::type_::Error as TypeError;
+        let code = Some(self.code());
         match self {
             Error::HexPackageSquatting => {
                 let text =
@@ -637,6 +847,7 @@ package deletion or account suspension.
                     title: "Invalid Hex package".into(),
                     text,
                     level: Level::Error,
+                    code,
                     location: None,
                     hint: None,
                 }
@@ -655,6 +866,7 @@ of the Gleam dependency modules."
                     title: "Failed to decode module metadata".into(),
                     text,
                     level: Level::Error,
+                    code,
                     location: None,
                     hint: None,
                 }
@@ -690,6 +902,7 @@ This prefix is intended for official Gleam packages only.",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -702,6 +915,7 @@ Module names can only contain lowercase letters, underscore, and
 forward slash and must not end with a slash."
                 ),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: None,
             },
@@ -715,49 +929,65 @@ forward slash and must not end with a slash."
                     title: "Module does not exist".into(),
                     text: format!("Module `{module}` was not found."),
                     level: Level::Error,
+                    code,
                     location: None,
                     hint: Some(hint),
                 }
             }
 
-            Error::ModuleDoesNotHaveMainFunction { module } => Diagnostic {
-                title: "Module does not have a main function".into(),
+            Error::ModuleDoesNotHaveMainFunction { module, function } => Diagnostic {
+                title: format!("Module does not have a {function} function"),
                 text: format!(
-                    "`{module}` does not have a main function so the module can not be run."
+                    "`{module}` does not have a {function} function so the module can not be run."
                 ),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: Some(format!(
-                    "Add a public `main` function to \
+                    "Add a public `{function}` function to \
 to `src/{module}.gleam`."
                 )),
             },
 
-            Error::MainFunctionDoesNotSupportTarget { module, target } => Diagnostic {
+            Error::MainFunctionDoesNotSupportTarget {
+                module,
+                function,
+                target,
+            } => Diagnostic {
                 title: "Target not supported".into(),
                 text: wrap_format!(
-                    "`{module}` has a main function, but it does not support the {target} \
+                    "`{module}` has a {function} function, but it does not support the {target} \
 target, so it cannot be run."
                 ),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: None,
             },
 
-            Error::MainFunctionHasWrongArity { module, arity } => Diagnostic {
+            Error::MainFunctionHasWrongArity {
+                module,
+                function,
+                arity,
+            } => Diagnostic {
                 title: "Main function has wrong arity".into(),
-                text: format!(
-                    "`{module}:main` should have an arity of 0 to be run but its arity is {arity}."
+                text: wrap_format!(
+                    "`{module}.{function}` should take no arguments, or a `List(String)` of \
+the command line arguments, to be run but its arity is {arity}."
                 ),
                 level: Level::Error,
+                code,
                 location: None,
-                hint: Some("Change the function signature of main to `pub fn main() {}`.".into()),
+                hint: Some(format!(
+                    "Change the function signature of {function} to `pub fn {function}() {{}}`."
+                )),
             },
 
             Error::ProjectRootAlreadyExist { path } => Diagnostic {
                 title: "Project folder already exists".into(),
                 text: format!("Project folder root:\n\n  {path}"),
                 level: Level::Error,
+                code,
                 hint: None,
                 location: None,
             },
@@ -782,6 +1012,7 @@ If you want to overwrite these files, delete them and run the command again.
                         .join("\n")
                 ),
                 level: Level::Error,
+                code,
                 hint: None,
                 location: None,
             },
@@ -801,6 +1032,7 @@ Please remove them and try again.
                         .join("\n")
                 ),
                 level: Level::Error,
+                code,
                 hint: None,
                 location: None,
             },
@@ -820,6 +1052,7 @@ Please make sure internal types do not appear in public functions and try again.
                         .join("\n")
                 ),
                 level: Level::Error,
+                code,
                 hint: None,
                 location: None,
             },
@@ -835,6 +1068,7 @@ We searched in {path} and all parent directories."
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -849,6 +1083,7 @@ your app.src file \"{app_ver}\"."
                     hint: None,
                     text,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -887,6 +1122,7 @@ You can also install rebar3 via homebrew using \"brew install rebar3\"",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -902,6 +1138,7 @@ You can also install rebar3 via homebrew using \"brew install rebar3\"",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -924,6 +1161,7 @@ The error from the shell command library was:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -941,6 +1179,7 @@ This was error from the gzip library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -959,6 +1198,7 @@ This was error from the tar library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -976,6 +1216,7 @@ This was error from the tar library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -993,6 +1234,7 @@ This was error from the tar library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -1010,6 +1252,7 @@ This was error from the Hex client library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -1031,6 +1274,7 @@ Second: {second}"
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -1040,6 +1284,7 @@ Second: {second}"
                 text: format!("The file `{file}` is defined multiple times."),
                 hint: None,
                 level: Level::Error,
+                code,
                 location: None,
             },
 
@@ -1070,6 +1315,7 @@ Second: {second}"
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -1083,6 +1329,7 @@ Second: {second}"
                     title: "Non UTF-8 Path Encountered".into(),
                     text,
                     level: Level::Error,
+                    code,
                     location: None,
                     hint: None,
                 }
@@ -1099,768 +1346,311 @@ Second: {second}"
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
 
-            Error::Type { path, src, error } => match error {
-                TypeError::SrcImportingTest {
-                    location,
-                    src_module,
-                    test_module,
-                } => {
-                    let text = wrap_format!(
-                        "The application module `{src_module}` is importing the test module `{test_module}`.
-
-Test modules are not included in production builds so test \
-modules cannot import them. Perhaps move the `{test_module}` module to the src directory.",
-                    );
-
-                    Diagnostic {
-                        title: "App importing test module".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some("Imported here".into()),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
+            Error::Type { path, src, errors } => type_error_diagnostic(path, src, errors.first()),
 
-                TypeError::UnknownLabels {
-                    unknown,
-                    valid,
-                    supplied,
-                } => {
-                    let other_labels: Vec<_> = valid
-                        .iter()
-                        .filter(|label| !supplied.contains(label))
-                        .cloned()
-                        .collect();
+            Error::Parse { path, src, error } => {
+                let (label, extra) = error.details();
+                let text = extra.join("\n");
 
-                    let title = if unknown.len() > 1 {
-                        "Unknown labels"
-                    } else {
-                        "Unknown label"
+                let adjusted_location = if error.error == ParseErrorType::UnexpectedEof {
+                    crate::ast::SrcSpan {
+                        start: (src.len() - 1) as u32,
+                        end: (src.len() - 1) as u32,
                     }
-                    .into();
+                } else {
+                    error.location
+                };
 
-                    let mut labels = unknown.iter().map(|(label, location)| {
-                        let text = did_you_mean(label, &other_labels)
-                            .unwrap_or_else(|| "Unexpected label".into());
-                        Label {
-                            text: Some(text),
-                            span: *location,
-                        }
-                    });
-                    let label = labels.next().expect("Unknown labels first label");
-                    let extra_labels = labels.collect();
-                    let text = if valid.is_empty() {
-                        "This constructor does not accept any labelled arguments.".into()
-                    } else if other_labels.is_empty() {
-                        "You have already supplied all the labelled arguments that this
-constructor accepts."
-                            .into()
-                    } else {
-                        let mut label_text = String::from("It accepts these labels:\n");
-                        for label in other_labels.iter().sorted() {
-                            label_text.push_str("\n    ");
-                            label_text.push_str(label);
-                        }
-                        label_text
-                    };
-                    Diagnostic {
-                        title,
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label,
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels,
-                        }),
-                    }
+                Diagnostic {
+                    title: "Syntax error".into(),
+                    text,
+                    hint: None,
+                    level: Level::Error,
+                    code,
+                    location: Some(Location {
+                        label: Label {
+                            text: Some(label.to_string()),
+                            span: adjusted_location,
+                        },
+                        path: path.clone(),
+                        src: src.clone(),
+                        extra_labels: vec![],
+                    }),
                 }
+            }
 
-                TypeError::UnexpectedLabelledArg { location, label } => {
-                    let text = format!(
-                        "This argument has been given a label but the constructor does
-not expect any. Please remove the label `{label}`."
-                    );
-                    Diagnostic {
-                        title: "Unexpected labelled argument".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+            Error::ImportCycle { modules } => {
+                let mut text = "The import statements for these modules form a cycle:
+"
+                .into();
+                write_cycle(&mut text, modules);
+                text.push_str(
+                    "Gleam doesn't support dependency cycles like these, please break the
+cycle to continue.",
+                );
+                Diagnostic {
+                    title: "Import cycle".into(),
+                    text,
+                    hint: None,
+                    level: Level::Error,
+                    code,
+                    location: None,
                 }
+            }
 
-                TypeError::PositionalArgumentAfterLabelled { location } => {
-                    let text =
-                        "This unlabeled argument has been supplied after a labelled argument.
-Once a labelled argument has been supplied all following arguments must
-also be labelled."
-                            .into();
-                    Diagnostic {
-                        title: "Unexpected positional argument".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+            Error::PackageCycle { packages } => {
+                let mut text = "The dependencies for these packages form a cycle:
+"
+                .into();
+                write_cycle(&mut text, packages);
+                text.push_str(
+                    "Gleam doesn't support dependency cycles like these, please break the
+cycle to continue.",
+                );
+                Diagnostic {
+                    title: "Dependency cycle".into(),
+                    text,
+                    hint: None,
+                    level: Level::Error,
+                    code,
+                    location: None,
                 }
+            }
 
-                TypeError::DuplicateImport {
+            Error::UnknownImport { import, details } => {
+                let UnknownImportDetails {
+                    module,
                     location,
-                    previous_location,
-                    name,
-                } => {
-                    let text = format!(
-                        "`{name}` has been imported multiple times.
-Names in a Gleam module must be unique so one will need to be renamed."
-                    );
-                    Diagnostic {
-                        title: "Duplicate import".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some("Reimported here".into()),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![Label {
-                                text: Some("First imported here".into()),
-                                span: *previous_location,
-                            }],
-                        }),
-                    }
+                    path,
+                    src,
+                    modules,
+                } = details.as_ref();
+                let text = wrap(&format!(
+                    "The module `{module}` is trying to import the module `{import}`, \
+but it cannot be found."
+                ));
+                Diagnostic {
+                    title: "Unknown import".into(),
+                    text,
+                    hint: None,
+                    level: Level::Error,
+                    code,
+                    location: Some(Location {
+                        label: Label {
+                            text: did_you_mean(import, modules),
+                            span: *location,
+                        },
+                        path: path.clone(),
+                        src: src.clone(),
+                        extra_labels: vec![],
+                    }),
                 }
+            }
 
-                TypeError::DuplicateName {
-                    location_a,
-                    location_b,
-                    name,
-                    ..
-                } => {
-                    let (first_location, second_location) = if location_a.start < location_b.start {
-                        (location_a, location_b)
-                    } else {
-                        (location_b, location_a)
-                    };
-                    let text = format!(
-                        "`{name}` has been defined multiple times.
-Names in a Gleam module must be unique so one will need to be renamed."
-                    );
-                    Diagnostic {
-                        title: "Duplicate definition".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some("Redefined here".into()),
-                                span: *second_location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![Label {
-                                text: Some("First defined here".into()),
-                                span: *first_location,
-                            }],
-                        }),
-                    }
-                }
+            Error::StandardIo { action, err } => {
+                let err = match err {
+                    Some(e) => format!(
+                        "\nThe error message from the stdio library was:\n\n    {}\n",
+                        std_io_error_kind_text(e)
+                    ),
+                    None => "".into(),
+                };
+                Diagnostic {
+                    title: "Standard IO failure".into(),
+                    text: format!(
+                        "An error occurred while trying to {}:
 
-                TypeError::DuplicateTypeName {
-                    name,
-                    location,
-                    previous_location,
-                    ..
-                } => {
-                    let text = format!(
-                        "The type `{name}` has been defined multiple times.
-Names in a Gleam module must be unique so one will need to be renamed."
-                    );
-                    Diagnostic {
-                        title: "Duplicate type definition".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some("Redefined here".into()),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![Label {
-                                text: Some("First defined here".into()),
-                                span: *previous_location,
-                            }],
-                        }),
-                    }
+{}",
+                        action.text(),
+                        err,
+                    ),
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                    code,
                 }
+            }
 
-                TypeError::DuplicateField { location, label } => {
-                    let text =
-                        format!("The field `{label}` has already been defined. Rename this field.");
-                    Diagnostic {
-                        title: "Duplicate field".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+            Error::Format { problem_files } => {
+                let files: Vec<_> = problem_files
+                    .iter()
+                    .map(|formatted| formatted.source.as_str())
+                    .map(|p| format!("  - {p}"))
+                    .sorted()
+                    .collect();
+                let mut text = files.iter().join("\n");
+                text.push('\n');
+                Diagnostic {
+                    title: "These files have not been formatted".into(),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                    code,
                 }
+            }
 
-                TypeError::DuplicateArgument { location, label } => {
-                    let text =
-                        format!("The labelled argument `{label}` has already been supplied.");
-                    Diagnostic {
-                        title: "Duplicate argument".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
+            Error::FormatVerification { path, problem } => {
+                let problem = match problem {
+                    FormatVerificationProblem::InvalidOutput => {
+                        "The formatted code does not parse."
+                    }
+                    FormatVerificationProblem::ChangedSyntaxTree => {
+                        "The formatted code means something different to the original code."
                     }
+                    FormatVerificationProblem::NotIdempotent => {
+                        "Formatting the formatted code again changes it."
+                    }
+                };
+                Diagnostic {
+                    title: "The formatter failed to verify a file".into(),
+                    text: format!("{problem}\nThe file {path} has been left unchanged."),
+                    hint: Some(
+                        "This is a bug in the formatter. Please report it along with the file."
+                            .into(),
+                    ),
+                    location: None,
+                    level: Level::Error,
+                    code,
                 }
+            }
 
-                TypeError::RecursiveType { location } => {
-                    let text = "I don't know how to work out what type this value has. It seems
-to be defined in terms of itself.
-
-Hint: Add some type annotations and try again."
-                        .into();
-                    Diagnostic {
-                        title: "Recursive type".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+            Error::ForbiddenWarnings { count } => {
+                let word_warning = match count {
+                    1 => "warning",
+                    _ => "warnings",
+                };
+                let text = "Your project was compiled with the `--warnings-as-errors` flag.
+Fix the warnings and try again."
+                    .into();
+                Diagnostic {
+                    title: format!("{count} {word_warning} generated."),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                    code,
                 }
+            }
 
-                TypeError::NotFn { location, typ } => {
-                    let mut printer = Printer::new();
-                    let text = format!(
-                        "This value is being called as a function but its type is:\n\n{}",
-                        printer.pretty_print(typ, 4)
-                    );
-                    Diagnostic {
-                        title: "Type mismatch".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+            Error::DeniedWarnings { count } => {
+                let word_warning = match count {
+                    1 => "warning",
+                    _ => "warnings",
+                };
+                Diagnostic {
+                    title: format!("{count} denied {word_warning} generated."),
+                    text: wrap(
+                        "The `[warnings]` section of gleam.toml sets the \
+categories of these warnings to `deny`. Fix the warnings and try again.",
+                    ),
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                    code,
                 }
+            }
 
-                TypeError::UnknownRecordField {
-                    usage,
-                    location,
-                    typ,
-                    label,
-                    fields,
-                    variants,
-                } => {
-                    let mut printer = Printer::new();
-
-                    // Give a hint about what type this value has.
-                    let mut text = format!(
-                        "The value being accessed has this type:\n\n{}\n",
-                        printer.pretty_print(typ, 4)
-                    );
-
-                    // Give a hint about what record fields this value has, if any.
-                    if fields.is_empty() {
-                        text.push_str("\nIt does not have any fields.");
-                    } else {
-                        text.push_str("\nIt has these fields:\n");
-                    }
-                    for field in fields.iter().sorted() {
-                        text.push_str("\n    .");
-                        text.push_str(field);
-                    }
-
-                    match variants {
-                        RecordVariants::HasVariants => {
-                            let msg = wrap(
-                                "Note: The field you are trying to \
-access might not be consistently present or positioned across the custom \
-type's variants, preventing reliable access. Ensure the field exists in the \
-same position and has the same type in all variants to enable direct accessor syntax.",
-                            );
-                            text.push_str("\n\n");
-                            text.push_str(&msg);
-                        }
-                        RecordVariants::NoVariants => (),
-                    }
-
-                    // Give a hint about Gleam not having OOP methods if it
-                    // looks like they might be trying to call one.
-                    match usage {
-                        FieldAccessUsage::MethodCall => {
-                            let msg = wrap(
-                                "Gleam is not object oriented, so if you are trying \
-to call a method on this value you may want to use the function syntax instead.",
-                            );
-                            text.push_str("\n\n");
-                            text.push_str(&msg);
-                            text.push_str("\n\n    ");
-                            text.push_str(label);
-                            text.push_str("(value)");
-                        }
-                        FieldAccessUsage::Other => (),
-                    }
-
-                    let label = did_you_mean(label, fields)
-                        .unwrap_or_else(|| "This field does not exist".into());
-                    Diagnostic {
-                        title: "Unknown record field".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some(label),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::CouldNotUnify {
-                    location,
-                    expected,
-                    given,
-                    situation: Some(UnifyErrorSituation::Operator(op)),
-                    rigid_type_names: annotated_names,
-                } => {
-                    let mut printer = Printer::new();
-                    printer.with_names(annotated_names.clone());
-                    let mut text = format!(
-                        "The {op} operator expects arguments of this type:
-
-{expected}
-
-But this argument has this type:
-
-{given}\n",
-                        op = op.name(),
-                        expected = printer.pretty_print(expected, 4),
-                        given = printer.pretty_print(given, 4),
-                    );
-                    if let Some(hint) = hint_alternative_operator(op, given) {
-                        text.push('\n');
-                        text.push_str("Hint: ");
-                        text.push_str(&hint);
-                    }
-                    Diagnostic {
-                        title: "Type mismatch".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::CouldNotUnify {
-                    location,
-                    expected,
-                    given,
-                    situation: Some(UnifyErrorSituation::PipeTypeMismatch),
-                    rigid_type_names: annotated_names,
-                } => {
-                    // Remap the pipe function type into just the type expected by the pipe.
-                    let expected = expected
-                        .fn_types()
-                        .and_then(|(args, _)| args.first().cloned());
-
-                    // Remap the argument as well, if it's a function.
-                    let given = given
-                        .fn_types()
-                        .and_then(|(args, _)| args.first().cloned())
-                        .unwrap_or_else(|| given.clone());
-
-                    let mut printer = Printer::new();
-                    printer.with_names(annotated_names.clone());
-                    let text = format!(
-                        "The argument is:
-
-{given}
-
-But function expects:
-
-{expected}",
-                        expected = expected
-                            .map(|v| printer.pretty_print(&v, 4))
-                            .unwrap_or_else(|| "    No arguments".into()),
-                        given = printer.pretty_print(&given, 4)
-                    );
-
-                    Diagnostic {
-                        title: "Type mismatch".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some("This function does not accept the piped type".into()),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::CouldNotUnify {
-                    location,
-                    expected,
-                    given,
-                    situation,
-                    rigid_type_names: annotated_names,
-                } => {
-                    let mut printer = Printer::new();
-                    printer.with_names(annotated_names.clone());
-                    let mut text =
-                        if let Some(description) = situation.and_then(|s| s.description()) {
-                            let mut text = description.to_string();
-                            text.push('\n');
-                            text.push('\n');
-                            text
-                        } else {
-                            "".into()
-                        };
-                    text.push_str("Expected type:\n\n");
-                    text.push_str(&printer.pretty_print(expected, 4));
-                    text.push_str("\n\nFound type:\n\n");
-                    text.push_str(&printer.pretty_print(given, 4));
-                    Diagnostic {
-                        title: "Type mismatch".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::IncorrectTypeArity {
-                    location,
-                    expected,
-                    given,
-                    ..
-                } => {
-                    let text = "Functions and constructors have to be called with their expected
-number of arguments."
-                        .into();
-                    let expected = match expected {
-                        0 => "no arguments".into(),
-                        1 => "1 argument".into(),
-                        _ => format!("{expected} arguments"),
-                    };
-                    Diagnostic {
-                        title: "Incorrect arity".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some(format!("Expected {expected}, got {given}")),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::IncorrectArity {
-                    labels,
-                    location,
-                    expected,
-                    given,
-                } => {
-                    let text = if labels.is_empty() {
-                        "".into()
-                    } else {
-                        let labels = labels
-                            .iter()
-                            .map(|p| format!("  - {p}"))
-                            .sorted()
-                            .join("\n");
-                        format!(
-                            "This call accepts these additional labelled arguments:\n\n{labels}",
-                        )
-                    };
-                    let expected = match expected {
-                        0 => "no arguments".into(),
-                        1 => "1 argument".into(),
-                        _ => format!("{expected} arguments"),
-                    };
-                    let label = format!("Expected {expected}, got {given}");
-                    Diagnostic {
-                        title: "Incorrect arity".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some(label),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::UnnecessarySpreadOperator { location, arity } => {
-                    let text = wrap_format!(
-                        "This record has {arity} fields and you have already \
-assigned variables to all of them."
-                    );
-                    Diagnostic {
-                        title: "Unnecessary spread operator".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+            Error::BreakingChanges {
+                previous_version,
+                version,
+                changes,
+            } => {
+                let changes = changes
+                    .iter()
+                    .map(|change| format!("  - {}", change.description()))
+                    .join("\n");
+                let text = format!(
+                    "{}\n\n{changes}\n",
+                    wrap(&format!(
+                        "Version {version} is not a new major version, but it makes \
+these breaking changes to the API of version {previous_version}:"
+                    ))
+                );
+                Diagnostic {
+                    title: "Breaking changes in a release".into(),
+                    text,
+                    hint: Some(
+                        "Publish the release as a new major version, or publish it \
+without `--check-semver` if the changes are intended."
+                            .into(),
+                    ),
+                    location: None,
+                    level: Level::Error,
+                    code,
                 }
+            }
 
-                TypeError::UpdateMultiConstructorType { location } => {
-                    let text = "This type has multiple constructors so it cannot be safely updated.
-If this value was one of the other variants then the update would be
-produce incorrect results.
-
-Consider pattern matching on it with a case expression and then
-constructing a new record with its values."
-                        .into();
-
-                    Diagnostic {
-                        title: "Unsafe record update".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some(
-                                    "I can't tell this is always the right constructor".into(),
-                                ),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+            Error::ImportNotBuiltForTarget {
+                module,
+                imported,
+                target,
+            } => {
+                let text = wrap(&format!(
+                    "The module `{module}` imports `{imported}`, but `{imported}` is \
+only built for another target, so it can't be imported when building for {target}."
+                ));
+                Diagnostic {
+                    title: "Import of a module not built for this target".into(),
+                    text,
+                    hint: Some(format!(
+                        "Limit `{module}` to the same target in the `[erlang]` or \
+`[javascript]` section of `gleam.toml`."
+                    )),
+                    location: None,
+                    level: Level::Error,
+                    code,
                 }
+            }
 
-                TypeError::UnknownType {
-                    location,
-                    name,
-                    hint,
-                } => {
-                    let label_text = match hint {
-                        UnknownTypeHint::AlternativeTypes(types) => did_you_mean(name, types),
-                        UnknownTypeHint::ValueInScopeWithSameName => None,
-                    };
-
-                    let mut text = wrap_format!(
-                        "The type `{name}` is not defined or imported in this module."
-                    );
-
-                    match hint {
-                        UnknownTypeHint::ValueInScopeWithSameName => {
-                            let hint = wrap_format!(
-                                "There is a value in scope with the name `{name}`, but no type in scope with that name."
-                            );
-                            text.push('\n');
-                            text.push_str(hint.as_str());
-                        }
-                        UnknownTypeHint::AlternativeTypes(_) => {}
-                    };
-
-                    Diagnostic {
-                        title: "Unknown type".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: label_text,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
+            Error::ProjectTemplate { template, error } => {
+                let text = format!(
+                    "The project could not be created from the template `{template}`:
 
-                TypeError::UnknownVariable {
-                    location,
-                    variables,
-                    name,
-                    type_with_name_in_scope,
-                } => {
-                    let text = if *type_with_name_in_scope {
-                        wrap_format!("`{name}` is a type, it cannot be used as a value.")
-                    } else {
-                        wrap_format!("The name `{name}` is not in scope here.")
-                    };
-                    Diagnostic {
-                        title: "Unknown variable".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: did_you_mean(name, variables),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+    {error}"
+                );
+                Diagnostic {
+                    title: "Failed to use project template".into(),
+                    text,
+                    hint: Some(
+                        "A template is a git repository with a `gleam-template.toml` \
+file at its root."
+                            .into(),
+                    ),
+                    location: None,
+                    level: Level::Error,
+                    code,
                 }
+            }
 
-                TypeError::PrivateTypeLeak { location, leaked } => {
-                    let mut printer = Printer::new();
-
-                    // TODO: be more precise.
-                    // - is being returned by this public function
-                    // - is taken as an argument by this public function
-                    // - is taken as an argument by this public enum constructor
-                    // etc
-                    let text = format!(
-                        "The following type is private, but is being used by this public export.
-
-{}
-
-Private types can only be used within the module that defines them.",
-                        printer.pretty_print(leaked, 4),
-                    );
-                    Diagnostic {
-                        title: "Private type used in public interface".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
+            Error::JavaScriptBundle { path, reason } => Diagnostic {
+                title: "Module can't be bundled".into(),
+                text: format!("The JavaScript module `{path}` can't be bundled because {reason}."),
+                hint: Some(
+                    "Modules that can't be bundled can still be bundled with a \
+JavaScript bundler such as esbuild."
+                        .into(),
+                ),
+                location: None,
+                level: Level::Error,
+                code,
+            },
 
-                TypeError::UnknownModule {
-                    location,
-                    name,
-                    imported_modules,
-                } => Diagnostic {
-                    title: "Unknown module".into(),
-                    text: format!("No module has been found with the name `{name}`."),
+            Error::JavaScript { src, path, error } => match error {
+                javascript::Error::Unsupported { feature, location } => Diagnostic {
+                    title: "Unsupported feature for compilation target".into(),
+                    text: format!("{feature} is not supported for JavaScript compilation."),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
-                            text: did_you_mean(name, imported_modules),
+                            text: None,
                             span: *location,
                         },
                         path: path.clone(),
@@ -1868,1296 +1658,2167 @@ Private types can only be used within the module that defines them.",
                         extra_labels: vec![],
                     }),
                 },
+            },
 
-                TypeError::UnknownModuleType {
-                    location,
-                    name,
-                    module_name,
-                    type_constructors,
-                } => {
-                    let text =
-                        format!("The module `{module_name}` does not have a `{name}` type.",);
-                    Diagnostic {
-                        title: "Unknown module type".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: did_you_mean(name, type_constructors),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::UnknownModuleValue {
-                    location,
-                    name,
-                    module_name,
-                    value_constructors,
-                } => {
-                    let text =
-                        format!("The module `{module_name}` does not have a `{name}` value.",);
-                    Diagnostic {
-                        title: "Unknown module field".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: did_you_mean(name, value_constructors),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
+            Error::DownloadPackageError {
+                package_name,
+                package_version,
+                error,
+            } => {
+                let text = format!(
+                    "A problem was encountered when downloading `{package_name}` {package_version}.
+The error from the package manager client was:
 
-                TypeError::UnknownModuleField {
-                    location,
-                    name,
-                    module_name,
-                    type_constructors,
-                    value_constructors,
-                } => {
-                    let options: Vec<_> = type_constructors
-                        .iter()
-                        .chain(value_constructors)
-                        .cloned()
-                        .collect();
-                    let text =
-                        format!("The module `{module_name}` does not have a `{name}` field.",);
-                    Diagnostic {
-                        title: "Unknown module field".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: did_you_mean(name, &options),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+    {error}"
+                );
+                Diagnostic {
+                    title: "Failed to download package".into(),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                    code,
                 }
+            }
 
-                TypeError::IncorrectNumClausePatterns {
-                    location,
-                    expected,
-                    given,
-                } => {
-                    let text = wrap_format!(
-                        "This case expression has {expected} subjects, but this pattern matches {given}.
-Each clause must have a pattern for every subject value.",
-                    );
-                    Diagnostic {
-                        title: "Incorrect number of patterns".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some(format!("Expected {expected} patterns, got {given}")),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
+            Error::Http(error) => {
+                let text = format!(
+                    "A HTTP request failed.
+The error from the HTTP client was:
 
-                TypeError::NonLocalClauseGuardVariable { location, name } => {
-                    let text = wrap_format!(
-                        "Variables used in guards must be either defined in the \
-function, or be an argument to the function. The variable `{name}` is not defined locally.",
-                    );
-                    Diagnostic {
-                        title: "Invalid guard variable".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some("Is not locally defined".into()),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+    {error}"
+                );
+                Diagnostic {
+                    title: "HTTP error".into(),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                    code,
                 }
+            }
 
-                TypeError::ExtraVarInAlternativePattern { location, name } => {
-                    let text = wrap_format!(
-"All alternative patterns must define the same variables as the initial pattern. \
-This variable `{name}` has not been previously defined.",
-                    );
-                    Diagnostic {
-                        title: "Extra alternative pattern variable".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some("Has not been previously defined".into()),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
+            Error::InvalidVersionFormat { input, error } => {
+                let text = format!(
+                    "I was unable to parse the version \"{input}\".
+The error from the parser was:
 
-                TypeError::MissingVarInAlternativePattern { location, name } => {
-                    let text = wrap_format!(
-                        "All alternative patterns must define the same variables \
-as the initial pattern, but the `{name}` variable is missing.",
-                    );
-                    Diagnostic {
-                        title: "Missing alternative pattern variable".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some("This does not define all required variables".into()),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+    {error}"
+                );
+                Diagnostic {
+                    title: "Invalid version format".into(),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                    code,
                 }
+            }
 
-                TypeError::DuplicateVarInPattern { location, name } => {
-                    let text = wrap_format!(
-                        "Variables can only be used once per pattern. This \
-variable `{name}` appears multiple times.
-If you used the same variable twice deliberately in order to check for equality \
-please use a guard clause instead.
-e.g. (x, y) if x == y -> ...",
-                    );
-                    Diagnostic {
-                        title: "Duplicate variable in pattern".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some("This has already been used".into()),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
+            Error::DependencyCanonicalizationFailed(package) => {
+                let text = format!("Local package `{package}` has no canonical path");
 
-                TypeError::OutOfBoundsTupleIndex {
-                    location, size: 0, ..
-                } => Diagnostic {
-                    title: "Out of bounds tuple index".into(),
-                    text: "This tuple has no elements so it cannot be indexed at all.".into(),
+                Diagnostic {
+                    title: "Failed to create canonical path".into(),
+                    text,
                     hint: None,
+                    location: None,
                     level: Level::Error,
-                    location: Some(Location {
-                        label: Label {
-                            text: None,
-                            span: *location,
-                        },
-                        path: path.clone(),
-                        src: src.clone(),
-                        extra_labels: vec![],
-                    }),
-                },
-
-                TypeError::OutOfBoundsTupleIndex {
-                    location,
-                    index,
-                    size,
-                } => {
-                    let text = wrap_format!(
-                        "The index being accessed for this tuple is {}, but this \
-tuple has {} elements so the highest valid index is {}.",
-                        index,
-                        size,
-                        size - 1,
-                    );
-                    Diagnostic {
-                        title: "Out of bounds tuple index".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some("This index is too large".into()),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+                    code,
                 }
+            }
 
-                TypeError::NotATuple { location, given } => {
-                    let mut printer = Printer::new();
-                    let text = format!(
-                        "To index into this value it needs to be a tuple, however it has this type:
+            Error::DependencyResolutionFailed(error) => {
+                let text = format!(
+                    "An error occurred while determining what dependency packages and
+versions should be downloaded.
+The error from the version resolver library was:
 
 {}",
-                        printer.pretty_print(given, 4),
-                    );
-                    Diagnostic {
-                        title: "Type mismatch".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some("This is not a tuple".into()),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::NotATupleUnbound { location } => {
-                    let text = "To index into a tuple we need to know it size, but we don't know
-anything about this type yet. Please add some type annotations so
-we can continue."
-                        .into();
-                    Diagnostic {
-                        title: "Type mismatch".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some("What type is this?".into()),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+                    wrap(error)
+                );
+                Diagnostic {
+                    title: "Dependency resolution failed".into(),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                    code,
                 }
+            }
 
-                TypeError::RecordAccessUnknownType { location } => {
-                    let text = "In order to access a record field we need to know what type it is,
-but I can't tell the type here. Try adding type annotations to your
-function and try again."
-                        .into();
-                    Diagnostic {
-                        title: "Unknown type for record access".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some("I don't know what type this is".into()),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::BitArraySegmentError { error, location } => {
-                    let (label, mut extra) = match error {
-                        bit_array::ErrorType::ConflictingTypeOptions { existing_type } => (
-                            "This is an extra type specifier",
-                            vec![format!("Hint: This segment already has the type {existing_type}.")],
-                        ),
-
-                        bit_array::ErrorType::ConflictingSignednessOptions {
-                            existing_signed
-                        } => (
-                            "This is an extra signedness specifier",
-                            vec![format!(
-                                "Hint: This segment already has a signedness of {existing_signed}."
-                            )],
-                        ),
-
-                        bit_array::ErrorType::ConflictingEndiannessOptions {
-                            existing_endianness
-                        } => (
-                            "This is an extra endianness specifier",
-                            vec![format!(
-                                "Hint: This segment already has an endianness of {existing_endianness}."
-                            )],
-                        ),
-
-                        bit_array::ErrorType::ConflictingSizeOptions => (
-                            "This is an extra size specifier",
-                            vec!["Hint: This segment already has a size.".into()],
-                        ),
-
-                        bit_array::ErrorType::ConflictingUnitOptions => (
-                            "This is an extra unit specifier",
-                            vec!["Hint: A BitArray segment can have at most 1 unit.".into()],
-                        ),
-
-                        bit_array::ErrorType::FloatWithSize => (
-                            "Invalid float size",
-                            vec!["Hint: floats have an exact size of 16/32/64 bits.".into()],
-                        ),
-
-                        bit_array::ErrorType::InvalidEndianness => (
-                            "This option is invalid here",
-                                vec![wrap("Hint: signed and unsigned can only be used with \
-int, float, utf16 and utf32 types.")],
-                        ),
-
-                        bit_array::ErrorType::OptionNotAllowedInValue => (
-                            "This option is only allowed in BitArray patterns",
-                            vec!["Hint: This option has no effect in BitArray values.".into()],
-                        ),
-
-                        bit_array::ErrorType::SignednessUsedOnNonInt { typ } => (
-                            "Signedness is only valid with int types",
-                            vec![format!("Hint: This segment has a type of {typ}")],
-                        ),
-                        bit_array::ErrorType::TypeDoesNotAllowSize { typ } => (
-                            "Size cannot be specified here",
-                            vec![format!("Hint: {typ} segments have an automatic size.")],
-                        ),
-                        bit_array::ErrorType::TypeDoesNotAllowUnit { typ } => (
-                            "Unit cannot be specified here",
-                            vec![wrap(&format!("Hint: {typ} segments are sized based on their value \
-and cannot have a unit."))],
-                        ),
-                        bit_array::ErrorType::VariableUtfSegmentInPattern => (
-                            "This cannot be a variable",
-                            vec![wrap("Hint: in patterns utf8, utf16, and utf32  must be an exact string.")],
-                        ),
-                        bit_array::ErrorType::SegmentMustHaveSize => (
-                            "This segment has no size",
-                            vec![wrap("Hint: Bit array segments without a size are only \
-allowed at the end of a bin pattern.")],
-                        ),
-                        bit_array::ErrorType::UnitMustHaveSize => (
-                            "This needs an explicit size",
-                            vec!["Hint: If you specify unit() you must also specify size().".into()],
-                        ),
-                    };
-                    extra.push("See: https://tour.gleam.run/data-types/bit-arrays/".into());
-                    let text = extra.join("\n");
-                    Diagnostic {
-                        title: "Invalid bit array segment".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: Some(label.into()),
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::RecordUpdateInvalidConstructor { location } => Diagnostic {
-                    title: "Invalid record constructor".into(),
-                    text: "Only record constructors can be used with the update syntax.".into(),
-                    hint: None,
-                    level: Level::Error,
-                    location: Some(Location {
-                        label: Label {
-                            text: Some("This is not a record constructor".into()),
-                            span: *location,
-                        },
-                        path: path.clone(),
-                        src: src.clone(),
-                        extra_labels: vec![],
-                    }),
-                },
-
-                TypeError::UnexpectedTypeHole { location } => Diagnostic {
-                    title: "Unexpected type hole".into(),
-                    text: "We need to know the exact type here so type holes cannot be used."
-                        .into(),
-                    hint: None,
-                    level: Level::Error,
-                    location: Some(Location {
-                        label: Label {
-                            text: Some("I need to know what this is".into()),
-                            span: *location,
-                        },
-                        path: path.clone(),
-                        src: src.clone(),
-                        extra_labels: vec![],
-                    }),
-                },
-
-                TypeError::ReservedModuleName { name } => {
-                    let text = format!(
-                        "The module name `{name}` is reserved.
-Try a different name for this module."
-                    );
-                    Diagnostic {
-                        title: "Reserved module name".into(),
-                        text,
-                        hint: None,
-                        location: None,
-                        level: Level::Error,
-                    }
-                }
+            Error::GitDependencyUnsupported => Diagnostic {
+                title: "Git dependencies are not currently supported".into(),
+                text: "Please remove all git dependencies from the gleam.toml file".into(),
+                hint: None,
+                location: None,
+                level: Level::Error,
+                code,
+            },
 
-                TypeError::KeywordInModuleName { name, keyword } => {
-                    let text = wrap(&format!(
-                        "The module name `{name}` contains the keyword `{keyword}`, so importing \
-it would be a syntax error.
-Try a different name for this module."
-                    ));
-                    Diagnostic {
-                        title: "Invalid module name".into(),
-                        text,
-                        hint: None,
-                        location: None,
-                        level: Level::Error,
-                    }
-                }
+            Error::OfflineResolutionRequired => Diagnostic {
+                title: "Dependencies cannot be resolved offline".into(),
+                text: wrap(
+                    "The versions of the dependencies need to be resolved, \
+as the manifest.toml file is missing or the dependencies in gleam.toml have \
+changed since it was written. Resolving them looks up their releases on Hex, \
+which is not used offline.",
+                ),
+                hint: Some(
+                    "Resolve the dependencies while online with `gleam deps download`.".into(),
+                ),
+                location: None,
+                level: Level::Error,
+                code,
+            },
 
-                TypeError::NotExhaustivePatternMatch {
-                    location,
-                    unmatched,
-                    kind,
-                } => {
-                    let mut text = match kind {
-                        PatternMatchKind::Case => {
-                            "This case expression does not match all possibilities.
-Each constructor must have a pattern that matches it or
-else it could crash."
-                        }
-                        PatternMatchKind::Assignment => {
-                            "This assignment does not match all possibilities.
-Either use a case expression with patterns for each possible
-value, or use `let assert` rather than `let`."
-                        }
-                    }
+            Error::PackagesNotCached { packages } => {
+                let mut text = "These packages are not in the local cache, so they \
+cannot be added to the project offline:\n"
                     .to_string();
-
-                    text.push_str("\n\nThese values are not matched:\n\n");
-                    for unmatched in unmatched {
-                        text.push_str("  - ");
-                        text.push_str(unmatched);
-                        text.push('\n');
-                    }
-                    Diagnostic {
-                        title: "Not exhaustive pattern match".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
+                for package in packages {
+                    text.push_str(&format!("\n- {package}"));
                 }
-
-                TypeError::ArgumentNameAlreadyUsed { location, name } => Diagnostic {
-                    title: "Argument name already used".into(),
-                    text: format!("Two `{name}` arguments have been defined for this function."),
-                    hint: None,
-                    level: Level::Error,
-                    location: Some(Location {
-                        label: Label {
-                            text: None,
-                            span: *location,
-                        },
-                        path: path.clone(),
-                        src: src.clone(),
-                        extra_labels: vec![],
-                    }),
-                },
-
-                TypeError::UnlabelledAfterlabelled { location } => Diagnostic {
-                    title: "Unlabelled argument after labelled argument".into(),
-                    text: wrap("All unlabelled arguments must come before any labelled arguments."),
-                    hint: None,
+                Diagnostic {
+                    title: "Packages not cached".into(),
+                    text,
+                    hint: Some(
+                        "Download the packages while online with `gleam deps download`.".into(),
+                    ),
+                    location: None,
                     level: Level::Error,
-                    location: Some(Location {
-                        label: Label {
-                            text: None,
-                            span: *location,
-                        },
-                        path: path.clone(),
-                        src: src.clone(),
-                        extra_labels: vec![],
-                    }),
-                },
-
-                TypeError::RecursiveTypeAlias { location, cycle } => {
-                    let mut text = "This type alias is defined in terms of itself.\n".into();
-                    write_cycle(&mut text, cycle);
-                    text.push_str(
-                        "If we tried to compile this recursive type it would expand
-forever in a loop, and we'd never get the final type.",
-                    );
-                    Diagnostic {
-                        title: "Type cycle".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::ExternalMissingAnnotation { location, kind } => {
-                    let kind = match kind {
-                        MissingAnnotation::Parameter => "parameter",
-                        MissingAnnotation::Return => "return",
-                    };
-                    let text = format!(
-                        "A {kind} annotation is missing from this function.
-
-Functions with external implementations must have type annotations
-so we can tell what type of values they accept and return.",
-                    );
-                    Diagnostic {
-                        title: "Missing type annotation".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::NoImplementation { location } => {
-                    let text = "We can't compile this function as it doesn't have an
-implementation. Add a body or an external implementation
-using the `@external` attribute."
-                        .into();
-                    Diagnostic {
-                        title: "Function without an implementation".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::InvalidExternalJavascriptModule {
-                    location,
-                    name,
-                    module,
-                } => {
-                    let text = wrap_format!(
-                        "The function `{name}` has an external JavaScript \
-implementation but the module path `{module}` is not valid."
-                    );
-                    Diagnostic {
-                        title: "Invalid JavaScript module".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::InvalidExternalJavascriptFunction {
-                    location,
-                    name,
-                    function,
-                } => {
-                    let text = wrap_format!(
-                        "The function `{name}` has an external JavaScript \
-implementation but the function name `{function}` is not valid."
-                    );
-                    Diagnostic {
-                        title: "Invalid JavaScript function".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            path: path.clone(),
-                            src: src.clone(),
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::InexhaustiveLetAssignment { location, missing } => {
-                    let mut text: String =
-                        "This assignment uses a pattern that does not match all possible
-values. If one of the other values is used then the assignment
-will crash.
-
-The missing patterns are:\n"
-                            .into();
-                    for missing in missing {
-                        text.push_str("\n    ");
-                        text.push_str(missing);
-                    }
-                    text.push('\n');
-
-                    Diagnostic {
-                        title: "Inexhaustive pattern".into(),
-                        text,
-                        hint: Some(
-                            "Use a more general pattern or use `let assert` instead.".into(),
-                        ),
-                        level: Level::Error,
-                        location: Some(Location {
-                            src: src.clone(),
-                            path: path.to_path_buf(),
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            extra_labels: Vec::new(),
-                        }),
-                    }
-                }
-
-                TypeError::InexhaustiveCaseExpression { location, missing } => {
-                    let mut text: String =
-                        "This case expression does not have a pattern for all possible values.
-If it is run on one of the values without a pattern then it will crash.
-
-The missing patterns are:\n"
-                            .into();
-                    for missing in missing {
-                        text.push_str("\n    ");
-                        text.push_str(missing);
-                    }
-                    Diagnostic {
-                        title: "Inexhaustive patterns".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            src: src.clone(),
-                            path: path.to_path_buf(),
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            extra_labels: Vec::new(),
-                        }),
-                    }
-                }
-
-                TypeError::UnsupportedExpressionTarget {
-                    location,
-                    target: current_target,
-                } => {
-                    let text = wrap_format!(
-                        "This value is not available as it is defined using externals, \
-and there is no implementation for the {} target.\n",
-                        match current_target {
-                            Target::Erlang => "Erlang",
-                            Target::JavaScript => "JavaScript",
-                        }
-                    );
-                    let hint = wrap("Did you mean to build for a different target?");
-                    Diagnostic {
-                        title: "Unsupported target".into(),
-                        text,
-                        hint: Some(hint),
-                        level: Level::Error,
-                        location: Some(Location {
-                            path: path.clone(),
-                            src: src.clone(),
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::UnsupportedPublicFunctionTarget {
-                    location,
-                    name,
-                    target,
-                } => {
-                    let target = match target {
-                        Target::Erlang => "Erlang",
-                        Target::JavaScript => "JavaScript",
-                    };
-                    let text = wrap_format!(
-                        "The `{name}` function is public but doesn't have an \
-implementation for the {target} target. All public functions of a package \
-must be able to compile for a module to be valid."
-                    );
-                    Diagnostic {
-                        title: "Unsupported target".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            path: path.clone(),
-                            src: src.clone(),
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::UnusedTypeAliasParameter { location, name } => {
-                    let text = wrap_format!(
-                        "The type variable `{name}` is unused. It can be safely removed.",
-                    );
-                    Diagnostic {
-                        title: "Unused type parameter".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            path: path.clone(),
-                            src: src.clone(),
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            extra_labels: vec![],
-                        }),
-                    }
-                }
-
-                TypeError::DuplicateTypeParameter { location, name } => {
-                    let text = wrap_format!(
-                        "This definition has multiple type parameters named `{name}`.
-Rename or remove one of them.",
-                    );
-                    Diagnostic {
-                        title: "Duplicate type parameter".into(),
-                        text,
-                        hint: None,
-                        level: Level::Error,
-                        location: Some(Location {
-                            path: path.clone(),
-                            src: src.clone(),
-                            label: Label {
-                                text: None,
-                                span: *location,
-                            },
-                            extra_labels: vec![],
-                        }),
-                    }
+                    code,
                 }
-            },
-
-            Error::Parse { path, src, error } => {
-                let (label, extra) = error.details();
-                let text = extra.join("\n");
+            }
 
-                let adjusted_location = if error.error == ParseErrorType::UnexpectedEof {
-                    crate::ast::SrcSpan {
-                        start: (src.len() - 1) as u32,
-                        end: (src.len() - 1) as u32,
-                    }
-                } else {
-                    error.location
-                };
+            Error::WrongDependencyProvided {
+                path,
+                expected,
+                found,
+            } => {
+                let text = format!(
+                    "Expected package `{expected}` at path `{path}` but found `{found}` instead.",
+                );
 
                 Diagnostic {
-                    title: "Syntax error".into(),
+                    title: "Wrong dependency provided".into(),
                     text,
                     hint: None,
+                    location: None,
                     level: Level::Error,
-                    location: Some(Location {
-                        label: Label {
-                            text: Some(label.to_string()),
-                            span: adjusted_location,
-                        },
-                        path: path.clone(),
-                        src: src.clone(),
-                        extra_labels: vec![],
-                    }),
+                    code,
                 }
             }
 
-            Error::ImportCycle { modules } => {
-                let mut text = "The import statements for these modules form a cycle:
-"
-                .into();
-                write_cycle(&mut text, modules);
-                text.push_str(
-                    "Gleam doesn't support dependency cycles like these, please break the
-cycle to continue.",
+            Error::ProvidedDependencyConflict {
+                package,
+                source_1,
+                source_2,
+            } => {
+                let text = format!(
+                    "The package `{package}` is provided as both `{source_1}` and `{source_2}`.",
                 );
+
                 Diagnostic {
-                    title: "Import cycle".into(),
+                    title: "Conflicting provided dependencies".into(),
                     text,
                     hint: None,
-                    level: Level::Error,
                     location: None,
+                    level: Level::Error,
+                    code,
                 }
             }
 
-            Error::PackageCycle { packages } => {
-                let mut text = "The dependencies for these packages form a cycle:
-"
-                .into();
-                write_cycle(&mut text, packages);
-                text.push_str(
-                    "Gleam doesn't support dependency cycles like these, please break the
-cycle to continue.",
+            Error::DuplicateDependency(name) => {
+                let text = format!(
+                    "The package `{name}` is specified in both the dependencies and
+dev-dependencies sections of the gleam.toml file."
                 );
                 Diagnostic {
-                    title: "Dependency cycle".into(),
+                    title: "Dependency duplicated".into(),
                     text,
                     hint: None,
-                    level: Level::Error,
                     location: None,
+                    level: Level::Error,
+                    code,
                 }
             }
 
-            Error::UnknownImport { import, details } => {
-                let UnknownImportDetails {
-                    module,
-                    location,
-                    path,
-                    src,
-                    modules,
-                } = details.as_ref();
-                let text = wrap(&format!(
-                    "The module `{module}` is trying to import the module `{import}`, \
-but it cannot be found."
-                ));
-                Diagnostic {
-                    title: "Unknown import".into(),
-                    text,
-                    hint: None,
-                    level: Level::Error,
-                    location: Some(Location {
-                        label: Label {
-                            text: did_you_mean(import, modules),
-                            span: *location,
-                        },
-                        path: path.clone(),
-                        src: src.clone(),
-                        extra_labels: vec![],
-                    }),
-                }
-            }
-
-            Error::StandardIo { action, err } => {
-                let err = match err {
-                    Some(e) => format!(
-                        "\nThe error message from the stdio library was:\n\n    {}\n",
-                        std_io_error_kind_text(e)
-                    ),
-                    None => "".into(),
-                };
-                Diagnostic {
-                    title: "Standard IO failure".into(),
-                    text: format!(
-                        "An error occurred while trying to {}:
+            Error::MissingHexPublishFields {
+                description_missing,
+                licence_missing,
+            } => {
+                let mut text =
+                    "Licence information and package description are required to publish a
+package to Hex.\n"
+                        .to_string();
+                text.push_str(if *description_missing && *licence_missing {
+                    r#"Add the licences and description fields to your gleam.toml file.
 
-{}",
-                        action.text(),
-                        err,
-                    ),
-                    hint: None,
-                    location: None,
-                    level: Level::Error,
-                }
-            }
+description = ""
+licences = ["Apache-2.0"]"#
+                } else if *description_missing {
+                    r#"Add the description field to your gleam.toml file.
 
-            Error::Format { problem_files } => {
-                let files: Vec<_> = problem_files
-                    .iter()
-                    .map(|formatted| formatted.source.as_str())
-                    .map(|p| format!("  - {p}"))
-                    .sorted()
-                    .collect();
-                let mut text = files.iter().join("\n");
-                text.push('\n');
-                Diagnostic {
-                    title: "These files have not been formatted".into(),
-                    text,
-                    hint: None,
-                    location: None,
-                    level: Level::Error,
-                }
-            }
+description = """#
+                } else {
+                    r#"Add the licences field to your gleam.toml file.
 
-            Error::ForbiddenWarnings { count } => {
-                let word_warning = match count {
-                    1 => "warning",
-                    _ => "warnings",
-                };
-                let text = "Your project was compiled with the `--warnings-as-errors` flag.
-Fix the warnings and try again."
-                    .into();
+licences = ["Apache-2.0"]"#
+                });
                 Diagnostic {
-                    title: format!("{count} {word_warning} generated."),
+                    title: "Missing required package fields".into(),
                     text,
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
-            Error::JavaScript { src, path, error } => match error {
-                javascript::Error::Unsupported { feature, location } => Diagnostic {
-                    title: "Unsupported feature for compilation target".into(),
-                    text: format!("{feature} is not supported for JavaScript compilation."),
-                    hint: None,
-                    level: Level::Error,
-                    location: Some(Location {
-                        label: Label {
-                            text: None,
-                            span: *location,
-                        },
-                        path: path.clone(),
-                        src: src.clone(),
-                        extra_labels: vec![],
-                    }),
-                },
+            Error::PublishNonHexDependencies { package } => Diagnostic {
+                title: "Unblished dependencies".into(),
+                text: wrap_format!(
+                    "The package cannot be published to Hex \
+because dependency `{package}` is not a Hex dependency.",
+                ),
+                hint: None,
+                location: None,
+                level: Level::Error,
+                code,
             },
 
-            Error::DownloadPackageError {
-                package_name,
-                package_version,
-                error,
+            Error::UnsupportedBuildTool {
+                package,
+                build_tools,
             } => {
-                let text = format!(
-                    "A problem was encountered when downloading `{package_name}` {package_version}.
-The error from the package manager client was:
+                let text = wrap_format!(
+                    "The package `{}` cannot be built as it does not use \
+a build tool supported by Gleam. It uses {:?}.
 
-    {error}"
+If you would like us to support this package please let us know by opening an \
+issue in our tracker: https://github.com/gleam-lang/gleam/issues",
+                    package,
+                    build_tools
                 );
                 Diagnostic {
-                    title: "Failed to download package".into(),
+                    title: "Unsupported build tool".into(),
                     text,
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
-            Error::Http(error) => {
+            Error::FailedToOpenDocs { path, error } => {
+                let error = format!("\nThe error message from the library was:\n\n    {error}\n");
                 let text = format!(
-                    "A HTTP request failed.
-The error from the HTTP client was:
+                    "An error occurred while trying to open the docs:
 
-    {error}"
+    {path}
+{error}",
                 );
                 Diagnostic {
-                    title: "HTTP error".into(),
+                    title: "Failed to open docs".into(),
                     text,
                     hint: None,
-                    location: None,
                     level: Level::Error,
+                    code,
+                    location: None,
                 }
             }
 
-            Error::InvalidVersionFormat { input, error } => {
+            Error::FailedToServeDocs { address, error } => {
                 let text = format!(
-                    "I was unable to parse the version \"{input}\".
-The error from the parser was:
+                    "The docs could not be served at {address}.
+The error from the operating system was:
 
     {error}"
                 );
                 Diagnostic {
-                    title: "Invalid version format".into(),
+                    title: "Failed to serve docs".into(),
                     text,
-                    hint: None,
-                    location: None,
+                    hint: Some(
+                        "Is another program using the port? A different one can be chosen with \
+the `--port` flag."
+                            .into(),
+                    ),
                     level: Level::Error,
+                    code,
+                    location: None,
                 }
             }
 
-            Error::DependencyCanonicalizationFailed(package) => {
-                let text = format!("Local package `{package}` has no canonical path");
+            Error::NoTestsSelected => Diagnostic {
+                title: "No tests selected".into(),
+                text: "None of the tests of the project matched the given `--filter`, \
+`--module` and `--failed` options, so there are no tests to run."
+                    .into(),
+                hint: Some(
+                    "Test functions are the public functions of the modules in the `test` \
+directory that have a name ending in `_test`."
+                        .into(),
+                ),
+                level: Level::Error,
+                code,
+                location: None,
+            },
+
+            Error::UnsupportedCoverageTarget { target, runtime } => Diagnostic {
+                title: "Coverage not supported".into(),
+                text: match runtime {
+                    Some(runtime) => format!(
+                        "Test coverage can not be reported when running the tests with \
+{runtime}, it is only supported on the Erlang target."
+                    ),
+                    None => format!(
+                        "Test coverage can not be reported when running the tests on the \
+{target} target, it is only supported on the Erlang target."
+                    ),
+                },
+                hint: Some("Run the tests with `--target erlang`.".into()),
+                level: Level::Error,
+                code,
+                location: None,
+            },
+
+            Error::CoverageNotRecorded => Diagnostic {
+                title: "Coverage not recorded".into(),
+                text: "The tests were stopped before their coverage could be recorded. \
+Coverage is recorded when the test runner halts the Erlang virtual machine, \
+which it did not do, or did from a module that could not be compiled again \
+to record it first."
+                    .into(),
+                hint: None,
+                level: Level::Error,
+                code,
+                location: None,
+            },
 
+            Error::PreviousPackageInterfaceUnavailable { version, reason } => Diagnostic {
+                title: "Previous API unavailable".into(),
+                text: wrap(&format!(
+                    "The package interface of version {version} is needed to check \
+the release for breaking changes, but {reason}."
+                )),
+                hint: Some(
+                    "Publish without `--check-semver` to skip the check for \
+breaking changes."
+                        .into(),
+                ),
+                level: Level::Error,
+                code,
+                location: None,
+            },
+
+            Error::IncompatibleCompilerVersion {
+                package,
+                required_version,
+                gleam_version,
+            } => {
+                let text = format!(
+                    "The package `{package}` requires a Gleam version satisfying {required_version} \
+but you are using v{gleam_version}.",
+                );
                 Diagnostic {
-                    title: "Failed to create canonical path".into(),
+                    title: "Incompatible Gleam version".into(),
                     text,
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
-            Error::DependencyResolutionFailed(error) => {
-                let text = format!(
-                    "An error occurred while determining what dependency packages and
-versions should be downloaded.
-The error from the version resolver library was:
+            Error::InvalidRuntime {
+                target,
+                invalid_runtime,
+            } => {
+                let text = format!("Invalid runtime for {target} target: {invalid_runtime}");
+
+                let hint = match target {
+                    Target::JavaScript => {
+                        Some("available runtimes for JavaScript are: node, deno.".into())
+                    }
+                    Target::Erlang => Some(
+                        "You can not set a runtime for Erlang. Did you mean to target JavaScript?"
+                            .into(),
+                    ),
+                };
 
-{}",
-                    wrap(error)
-                );
                 Diagnostic {
-                    title: "Dependency resolution failed".into(),
+                    title: format!("Invalid runtime for {target}"),
                     text,
-                    hint: None,
+                    hint,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
-            Error::GitDependencyUnsupported => Diagnostic {
-                title: "Git dependencies are not currently supported".into(),
-                text: "Please remove all git dependencies from the gleam.toml file".into(),
+            Error::JavaScriptPreludeRequired => Diagnostic {
+                title: "JavaScript prelude required".into(),
+                text: "The --javascript-prelude flag must be given when compiling to JavaScript."
+                    .into(),
+                level: Level::Error,
+                code,
+                location: None,
+                hint: None,
+            },
+            Error::CorruptManifest => Diagnostic {
+                title: "Corrupt manifest.toml".into(),
+                text: "The `manifest.toml` file is corrupt.".into(),
+                level: Level::Error,
+                code,
+                location: None,
+                hint: Some("Please run `gleam update` to fix it.".into()),
+            },
+
+            Error::UnknownDiagnosticCode { code: given } => Diagnostic {
+                title: "Unknown code".into(),
+                text: format!("`{given}` is not the code of any error or warning."),
+                level: Level::Error,
+                code,
+                location: None,
+                hint: Some(
+                    "Codes are printed next to errors and warnings, for example `error[GE0215]`."
+                        .into(),
+                ),
+            },
+
+            Error::NoDiagnosticExplanation { code: given } => Diagnostic {
+                title: "No explanation available".into(),
+                text: format!("There is no extended explanation for the code `{given}` yet."),
+                level: Level::Error,
+                code,
+                location: None,
+                hint: None,
+            },
+
+            Error::Cancelled => Diagnostic {
+                title: "Compilation cancelled".into(),
+                text: "The compilation was cancelled before it finished.".into(),
+                level: Level::Error,
+                code,
+                location: None,
+                hint: None,
+            },
+        }
+    }
+}
+
+fn type_error_diagnostic(
+    path: &Utf8PathBuf,
+    src: &EcoString,
+    error: &crate::type_::Error,
+) -> Diagnostic {
+    use crate::type_::Error as TypeError;
+    let code = Some(error.code());
+    match error {
+        TypeError::SrcImportingTest {
+            location,
+            src_module,
+            test_module,
+        } => {
+            let text = wrap_format!(
+                "The application module `{src_module}` is importing the test module `{test_module}`.
+
+Test modules are not included in production builds so test \
+modules cannot import them. Perhaps move the `{test_module}` module to the src directory.",
+            );
+
+            Diagnostic {
+                title: "App importing test module".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("Imported here".into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::UnknownLabels {
+            unknown,
+            valid,
+            supplied,
+        } => {
+            let other_labels: Vec<_> = valid
+                .iter()
+                .filter(|label| !supplied.contains(label))
+                .cloned()
+                .collect();
+
+            let title = if unknown.len() > 1 {
+                "Unknown labels"
+            } else {
+                "Unknown label"
+            }
+            .into();
+
+            let mut labels = unknown.iter().map(|(label, location)| {
+                let text =
+                    did_you_mean(label, &other_labels).unwrap_or_else(|| "Unexpected label".into());
+                Label {
+                    text: Some(text),
+                    span: *location,
+                }
+            });
+            let label = labels.next().expect("Unknown labels first label");
+            let extra_labels = labels.collect();
+            let text = if valid.is_empty() {
+                "This constructor does not accept any labelled arguments.".into()
+            } else if other_labels.is_empty() {
+                "You have already supplied all the labelled arguments that this
+constructor accepts."
+                    .into()
+            } else {
+                let mut label_text = String::from("It accepts these labels:\n");
+                for label in other_labels.iter().sorted() {
+                    label_text.push_str("\n    ");
+                    label_text.push_str(label);
+                }
+                label_text
+            };
+            Diagnostic {
+                title,
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label,
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels,
+                }),
+            }
+        }
+
+        TypeError::UnexpectedLabelledArg { location, label } => {
+            let text = format!(
+                "This argument has been given a label but the constructor does
+not expect any. Please remove the label `{label}`."
+            );
+            Diagnostic {
+                title: "Unexpected labelled argument".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::PositionalArgumentAfterLabelled { location } => {
+            let text = "This unlabeled argument has been supplied after a labelled argument.
+Once a labelled argument has been supplied all following arguments must
+also be labelled."
+                .into();
+            Diagnostic {
+                title: "Unexpected positional argument".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::DuplicateImport {
+            location,
+            previous_location,
+            name,
+        } => {
+            let text = format!(
+                "`{name}` has been imported multiple times.
+Names in a Gleam module must be unique so one will need to be renamed."
+            );
+            Diagnostic {
+                title: "Duplicate import".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("Reimported here".into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![Label {
+                        text: Some("First imported here".into()),
+                        span: *previous_location,
+                    }],
+                }),
+            }
+        }
+
+        TypeError::DuplicateName {
+            location_a,
+            location_b,
+            name,
+            ..
+        } => {
+            let (first_location, second_location) = if location_a.start < location_b.start {
+                (location_a, location_b)
+            } else {
+                (location_b, location_a)
+            };
+            let text = format!(
+                "`{name}` has been defined multiple times.
+Names in a Gleam module must be unique so one will need to be renamed."
+            );
+            Diagnostic {
+                title: "Duplicate definition".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("Redefined here".into()),
+                        span: *second_location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![Label {
+                        text: Some("First defined here".into()),
+                        span: *first_location,
+                    }],
+                }),
+            }
+        }
+
+        TypeError::DuplicateTypeName {
+            name,
+            location,
+            previous_location,
+            ..
+        } => {
+            let text = format!(
+                "The type `{name}` has been defined multiple times.
+Names in a Gleam module must be unique so one will need to be renamed."
+            );
+            Diagnostic {
+                title: "Duplicate type definition".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("Redefined here".into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![Label {
+                        text: Some("First defined here".into()),
+                        span: *previous_location,
+                    }],
+                }),
+            }
+        }
+
+        TypeError::DuplicateField { location, label } => {
+            let text = format!("The field `{label}` has already been defined. Rename this field.");
+            Diagnostic {
+                title: "Duplicate field".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::DuplicateArgument { location, label } => {
+            let text = format!("The labelled argument `{label}` has already been supplied.");
+            Diagnostic {
+                title: "Duplicate argument".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::RecursiveType { location } => {
+            let text = "I don't know how to work out what type this value has. It seems
+to be defined in terms of itself.
+
+Hint: Add some type annotations and try again."
+                .into();
+            Diagnostic {
+                title: "Recursive type".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::NotFn { location, typ } => {
+            let mut printer = Printer::new();
+            let text = format!(
+                "This value is being called as a function but its type is:\n\n{}",
+                printer.pretty_print(typ, 4)
+            );
+            Diagnostic {
+                title: "Type mismatch".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::UnknownRecordField {
+            usage,
+            location,
+            typ,
+            label,
+            fields,
+            variants,
+        } => {
+            let mut printer = Printer::new();
+
+            // Give a hint about what type this value has.
+            let mut text = format!(
+                "The value being accessed has this type:\n\n{}\n",
+                printer.pretty_print(typ, 4)
+            );
+
+            // Give a hint about what record fields this value has, if any.
+            if fields.is_empty() {
+                text.push_str("\nIt does not have any fields.");
+            } else {
+                text.push_str("\nIt has these fields:\n");
+            }
+            for field in fields.iter().sorted() {
+                text.push_str("\n    .");
+                text.push_str(field);
+            }
+
+            match variants {
+                RecordVariants::HasVariants => {
+                    let msg = wrap(
+                        "Note: The field you are trying to \
+access might not be consistently present or positioned across the custom \
+type's variants, preventing reliable access. Ensure the field exists in the \
+same position and has the same type in all variants to enable direct accessor syntax.",
+                    );
+                    text.push_str("\n\n");
+                    text.push_str(&msg);
+                }
+                RecordVariants::NoVariants => (),
+            }
+
+            // Give a hint about Gleam not having OOP methods if it
+            // looks like they might be trying to call one.
+            match usage {
+                FieldAccessUsage::MethodCall => {
+                    let msg = wrap(
+                        "Gleam is not object oriented, so if you are trying \
+to call a method on this value you may want to use the function syntax instead.",
+                    );
+                    text.push_str("\n\n");
+                    text.push_str(&msg);
+                    text.push_str("\n\n    ");
+                    text.push_str(label);
+                    text.push_str("(value)");
+                }
+                FieldAccessUsage::Other => (),
+            }
+
+            let label =
+                did_you_mean(label, fields).unwrap_or_else(|| "This field does not exist".into());
+            Diagnostic {
+                title: "Unknown record field".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some(label),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::CouldNotUnify {
+            location,
+            expected,
+            given,
+            situation: Some(UnifyErrorSituation::Operator(op)),
+            rigid_type_names: annotated_names,
+            trace,
+        } => {
+            let mut printer = Printer::new();
+            printer.with_names(annotated_names.clone());
+            let mut text = format!(
+                "The {op} operator expects arguments of this type:
+
+{expected}
+
+But this argument has this type:
+
+{given}\n",
+                op = op.name(),
+                expected = printer.pretty_print(expected, 4),
+                given = printer.pretty_print(given, 4),
+            );
+            if let Some(hint) = hint_alternative_operator(op, given) {
+                text.push('\n');
+                text.push_str("Hint: ");
+                text.push_str(&hint);
+            }
+            Diagnostic {
+                title: "Type mismatch".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: type_constraint_labels(location, trace, &mut printer),
+                }),
+            }
+        }
+
+        TypeError::CouldNotUnify {
+            location,
+            expected,
+            given,
+            situation: Some(UnifyErrorSituation::PipeTypeMismatch),
+            rigid_type_names: annotated_names,
+            trace,
+        } => {
+            // Remap the pipe function type into just the type expected by the pipe.
+            let expected = expected
+                .fn_types()
+                .and_then(|(args, _)| args.first().cloned());
+
+            // Remap the argument as well, if it's a function.
+            let given = given
+                .fn_types()
+                .and_then(|(args, _)| args.first().cloned())
+                .unwrap_or_else(|| given.clone());
+
+            let mut printer = Printer::new();
+            printer.with_names(annotated_names.clone());
+            let text = format!(
+                "The argument is:
+
+{given}
+
+But function expects:
+
+{expected}",
+                expected = expected
+                    .map(|v| printer.pretty_print(&v, 4))
+                    .unwrap_or_else(|| "    No arguments".into()),
+                given = printer.pretty_print(&given, 4)
+            );
+
+            Diagnostic {
+                title: "Type mismatch".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("This function does not accept the piped type".into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: type_constraint_labels(location, trace, &mut printer),
+                }),
+            }
+        }
+
+        TypeError::CouldNotUnify {
+            location,
+            expected,
+            given,
+            situation,
+            rigid_type_names: annotated_names,
+            trace,
+        } => {
+            let mut printer = Printer::new();
+            printer.with_names(annotated_names.clone());
+            let mut text = if let Some(description) = situation.and_then(|s| s.description()) {
+                let mut text = description.to_string();
+                text.push('\n');
+                text.push('\n');
+                text
+            } else {
+                "".into()
+            };
+            text.push_str("Expected type:\n\n");
+            text.push_str(&printer.pretty_print(expected, 4));
+            text.push_str("\n\nFound type:\n\n");
+            text.push_str(&printer.pretty_print(given, 4));
+            Diagnostic {
+                title: "Type mismatch".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: type_constraint_labels(location, trace, &mut printer),
+                }),
+            }
+        }
+
+        TypeError::IncorrectTypeArity {
+            location,
+            expected,
+            given,
+            ..
+        } => {
+            let text = "Functions and constructors have to be called with their expected
+number of arguments."
+                .into();
+            let expected = match expected {
+                0 => "no arguments".into(),
+                1 => "1 argument".into(),
+                _ => format!("{expected} arguments"),
+            };
+            Diagnostic {
+                title: "Incorrect arity".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some(format!("Expected {expected}, got {given}")),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::IncorrectArity {
+            labels,
+            location,
+            expected,
+            given,
+        } => {
+            let text = if labels.is_empty() {
+                "".into()
+            } else {
+                let labels = labels
+                    .iter()
+                    .map(|p| format!("  - {p}"))
+                    .sorted()
+                    .join("\n");
+                format!("This call accepts these additional labelled arguments:\n\n{labels}",)
+            };
+            let expected = match expected {
+                0 => "no arguments".into(),
+                1 => "1 argument".into(),
+                _ => format!("{expected} arguments"),
+            };
+            let label = format!("Expected {expected}, got {given}");
+            Diagnostic {
+                title: "Incorrect arity".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some(label),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::UnnecessarySpreadOperator { location, arity } => {
+            let text = wrap_format!(
+                "This record has {arity} fields and you have already \
+assigned variables to all of them."
+            );
+            Diagnostic {
+                title: "Unnecessary spread operator".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::UpdateMultiConstructorType { location } => {
+            let text = "This type has multiple constructors so it cannot be safely updated.
+If this value was one of the other variants then the update would be
+produce incorrect results.
+
+Consider pattern matching on it with a case expression and then
+constructing a new record with its values."
+                .into();
+
+            Diagnostic {
+                title: "Unsafe record update".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("I can't tell this is always the right constructor".into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::UnknownType {
+            location,
+            name,
+            hint,
+        } => {
+            let label_text = match hint {
+                UnknownTypeHint::AlternativeTypes(types) => did_you_mean(name, types),
+                UnknownTypeHint::ValueInScopeWithSameName => None,
+            };
+
+            let mut text =
+                wrap_format!("The type `{name}` is not defined or imported in this module.");
+
+            match hint {
+                UnknownTypeHint::ValueInScopeWithSameName => {
+                    let hint = wrap_format!(
+                        "There is a value in scope with the name `{name}`, but no type in scope with that name."
+                    );
+                    text.push('\n');
+                    text.push_str(hint.as_str());
+                }
+                UnknownTypeHint::AlternativeTypes(_) => {}
+            };
+
+            Diagnostic {
+                title: "Unknown type".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: label_text,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::UnknownVariable {
+            location,
+            variables,
+            name,
+            type_with_name_in_scope,
+            suggestions,
+        } => {
+            let text = if *type_with_name_in_scope {
+                wrap_format!("`{name}` is a type, it cannot be used as a value.")
+            } else {
+                wrap_format!("The name `{name}` is not in scope here.")
+            };
+            let label = did_you_mean(name, variables).or_else(|| {
+                let qualified = suggestions
+                    .iter()
+                    .filter(|s| matches!(s, ModuleSuggestion::UseQualified { .. }))
+                    .map(|s| format!("`{}`", s.suggestion()))
+                    .collect_vec();
+                match qualified.as_slice() {
+                    [] => None,
+                    [one] => Some(format!("Did you mean {one}?")),
+                    many => Some(format!("Did you mean one of {}?", many.join(", "))),
+                }
+            });
+            Diagnostic {
+                title: "Unknown variable".into(),
+                text,
+                hint: import_suggestions_hint(suggestions),
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: label,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::PrivateTypeLeak { location, leaked } => {
+            let mut printer = Printer::new();
+
+            // TODO: be more precise.
+            // - is being returned by this public function
+            // - is taken as an argument by this public function
+            // - is taken as an argument by this public enum constructor
+            // etc
+            let text = format!(
+                "The following type is private, but is being used by this public export.
+
+{}
+
+Private types can only be used within the module that defines them.",
+                printer.pretty_print(leaked, 4),
+            );
+            Diagnostic {
+                title: "Private type used in public interface".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::UnknownModule {
+            location,
+            name,
+            imported_modules,
+            suggestions,
+        } => Diagnostic {
+            title: "Unknown module".into(),
+            text: format!("No module has been found with the name `{name}`."),
+            hint: import_suggestions_hint(suggestions),
+            level: Level::Error,
+            code,
+            location: Some(Location {
+                label: Label {
+                    text: did_you_mean(name, imported_modules),
+                    span: *location,
+                },
+                path: path.clone(),
+                src: src.clone(),
+                extra_labels: vec![],
+            }),
+        },
+
+        TypeError::UnknownModuleType {
+            location,
+            name,
+            module_name,
+            type_constructors,
+        } => {
+            let text = format!("The module `{module_name}` does not have a `{name}` type.",);
+            Diagnostic {
+                title: "Unknown module type".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: did_you_mean(name, type_constructors),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::UnknownModuleValue {
+            location,
+            name,
+            module_name,
+            value_constructors,
+        } => {
+            let text = format!("The module `{module_name}` does not have a `{name}` value.",);
+            Diagnostic {
+                title: "Unknown module field".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: did_you_mean(name, value_constructors),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::UnknownModuleField {
+            location,
+            name,
+            module_name,
+            type_constructors,
+            value_constructors,
+        } => {
+            let options: Vec<_> = type_constructors
+                .iter()
+                .chain(value_constructors)
+                .cloned()
+                .collect();
+            let text = format!("The module `{module_name}` does not have a `{name}` field.",);
+            Diagnostic {
+                title: "Unknown module field".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: did_you_mean(name, &options),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::IncorrectNumClausePatterns {
+            location,
+            expected,
+            given,
+        } => {
+            let text = wrap_format!(
+                "This case expression has {expected} subjects, but this pattern matches {given}.
+Each clause must have a pattern for every subject value.",
+            );
+            Diagnostic {
+                title: "Incorrect number of patterns".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some(format!("Expected {expected} patterns, got {given}")),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::NonLocalClauseGuardVariable { location, name } => {
+            let text = wrap_format!(
+                "Variables used in guards must be either defined in the \
+function, or be an argument to the function. The variable `{name}` is not defined locally.",
+            );
+            Diagnostic {
+                title: "Invalid guard variable".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("Is not locally defined".into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::ExtraVarInAlternativePattern { location, name } => {
+            let text = wrap_format!(
+                "All alternative patterns must define the same variables as the initial pattern. \
+This variable `{name}` has not been previously defined.",
+            );
+            Diagnostic {
+                title: "Extra alternative pattern variable".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("Has not been previously defined".into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::MissingVarInAlternativePattern { location, name } => {
+            let text = wrap_format!(
+                "All alternative patterns must define the same variables \
+as the initial pattern, but the `{name}` variable is missing.",
+            );
+            Diagnostic {
+                title: "Missing alternative pattern variable".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("This does not define all required variables".into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::DuplicateVarInPattern { location, name } => {
+            let text = wrap_format!(
+                "Variables can only be used once per pattern. This \
+variable `{name}` appears multiple times.
+If you used the same variable twice deliberately in order to check for equality \
+please use a guard clause instead.
+e.g. (x, y) if x == y -> ...",
+            );
+            Diagnostic {
+                title: "Duplicate variable in pattern".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("This has already been used".into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::OutOfBoundsTupleIndex {
+            location, size: 0, ..
+        } => Diagnostic {
+            title: "Out of bounds tuple index".into(),
+            text: "This tuple has no elements so it cannot be indexed at all.".into(),
+            hint: None,
+            level: Level::Error,
+            code,
+            location: Some(Location {
+                label: Label {
+                    text: None,
+                    span: *location,
+                },
+                path: path.clone(),
+                src: src.clone(),
+                extra_labels: vec![],
+            }),
+        },
+
+        TypeError::OutOfBoundsTupleIndex {
+            location,
+            index,
+            size,
+        } => {
+            let text = wrap_format!(
+                "The index being accessed for this tuple is {}, but this \
+tuple has {} elements so the highest valid index is {}.",
+                index,
+                size,
+                size - 1,
+            );
+            Diagnostic {
+                title: "Out of bounds tuple index".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("This index is too large".into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::NotATuple { location, given } => {
+            let mut printer = Printer::new();
+            let text = format!(
+                "To index into this value it needs to be a tuple, however it has this type:
+
+{}",
+                printer.pretty_print(given, 4),
+            );
+            Diagnostic {
+                title: "Type mismatch".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("This is not a tuple".into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::NotATupleUnbound { location } => {
+            let text = "To index into a tuple we need to know it size, but we don't know
+anything about this type yet. Please add some type annotations so
+we can continue."
+                .into();
+            Diagnostic {
+                title: "Type mismatch".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("What type is this?".into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::RecordAccessUnknownType { location } => {
+            let text = "In order to access a record field we need to know what type it is,
+but I can't tell the type here. Try adding type annotations to your
+function and try again."
+                .into();
+            Diagnostic {
+                title: "Unknown type for record access".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some("I don't know what type this is".into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::BitArraySegmentError { error, location } => {
+            let (label, mut extra) = match error {
+                bit_array::ErrorType::ConflictingTypeOptions { existing_type } => (
+                    "This is an extra type specifier",
+                    vec![format!(
+                        "Hint: This segment already has the type {existing_type}."
+                    )],
+                ),
+
+                bit_array::ErrorType::ConflictingSignednessOptions { existing_signed } => (
+                    "This is an extra signedness specifier",
+                    vec![format!(
+                        "Hint: This segment already has a signedness of {existing_signed}."
+                    )],
+                ),
+
+                bit_array::ErrorType::ConflictingEndiannessOptions {
+                    existing_endianness,
+                } => (
+                    "This is an extra endianness specifier",
+                    vec![format!(
+                        "Hint: This segment already has an endianness of {existing_endianness}."
+                    )],
+                ),
+
+                bit_array::ErrorType::ConflictingSizeOptions => (
+                    "This is an extra size specifier",
+                    vec!["Hint: This segment already has a size.".into()],
+                ),
+
+                bit_array::ErrorType::ConflictingUnitOptions => (
+                    "This is an extra unit specifier",
+                    vec!["Hint: A BitArray segment can have at most 1 unit.".into()],
+                ),
+
+                bit_array::ErrorType::FloatWithSize => (
+                    "Invalid float size",
+                    vec!["Hint: floats have an exact size of 16/32/64 bits.".into()],
+                ),
+
+                bit_array::ErrorType::InvalidEndianness => (
+                    "This option is invalid here",
+                    vec![wrap(
+                        "Hint: signed and unsigned can only be used with \
+int, float, utf16 and utf32 types.",
+                    )],
+                ),
+
+                bit_array::ErrorType::OptionNotAllowedInValue => (
+                    "This option is only allowed in BitArray patterns",
+                    vec!["Hint: This option has no effect in BitArray values.".into()],
+                ),
+
+                bit_array::ErrorType::SignednessUsedOnNonInt { typ } => (
+                    "Signedness is only valid with int types",
+                    vec![format!("Hint: This segment has a type of {typ}")],
+                ),
+                bit_array::ErrorType::TypeDoesNotAllowSize { typ } => (
+                    "Size cannot be specified here",
+                    vec![format!("Hint: {typ} segments have an automatic size.")],
+                ),
+                bit_array::ErrorType::TypeDoesNotAllowUnit { typ } => (
+                    "Unit cannot be specified here",
+                    vec![wrap(&format!(
+                        "Hint: {typ} segments are sized based on their value \
+and cannot have a unit."
+                    ))],
+                ),
+                bit_array::ErrorType::VariableUtfSegmentInPattern => (
+                    "This cannot be a variable",
+                    vec![wrap(
+                        "Hint: in patterns utf8, utf16, and utf32  must be an exact string.",
+                    )],
+                ),
+                bit_array::ErrorType::SegmentMustHaveSize => (
+                    "This segment has no size",
+                    vec![wrap(
+                        "Hint: Bit array segments without a size are only \
+allowed at the end of a bin pattern.",
+                    )],
+                ),
+                bit_array::ErrorType::UnitMustHaveSize => (
+                    "This needs an explicit size",
+                    vec!["Hint: If you specify unit() you must also specify size().".into()],
+                ),
+            };
+            extra.push("See: https://tour.gleam.run/data-types/bit-arrays/".into());
+            let text = extra.join("\n");
+            Diagnostic {
+                title: "Invalid bit array segment".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some(label.into()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::RecordUpdateInvalidConstructor { location } => Diagnostic {
+            title: "Invalid record constructor".into(),
+            text: "Only record constructors can be used with the update syntax.".into(),
+            hint: None,
+            level: Level::Error,
+            code,
+            location: Some(Location {
+                label: Label {
+                    text: Some("This is not a record constructor".into()),
+                    span: *location,
+                },
+                path: path.clone(),
+                src: src.clone(),
+                extra_labels: vec![],
+            }),
+        },
+
+        TypeError::UnexpectedTypeHole { location } => Diagnostic {
+            title: "Unexpected type hole".into(),
+            text: "We need to know the exact type here so type holes cannot be used.".into(),
+            hint: None,
+            level: Level::Error,
+            code,
+            location: Some(Location {
+                label: Label {
+                    text: Some("I need to know what this is".into()),
+                    span: *location,
+                },
+                path: path.clone(),
+                src: src.clone(),
+                extra_labels: vec![],
+            }),
+        },
+
+        TypeError::EchoWithNoFollowingExpression { location } => Diagnostic {
+            title: "Invalid echo use".into(),
+            text: "The `echo` keyword should be followed by a value to print,
+or used as a step of a pipeline."
+                .into(),
+            hint: None,
+            level: Level::Error,
+            code,
+            location: Some(Location {
+                label: Label {
+                    text: Some("I was expecting a value after this".into()),
+                    span: *location,
+                },
+                path: path.clone(),
+                src: src.clone(),
+                extra_labels: vec![],
+            }),
+        },
+
+        TypeError::NonLiteralConstantPattern { location, name } => Diagnostic {
+            title: "Invalid constant pattern".into(),
+            text: format!(
+                "The constant `{name}` can't be used in a pattern as its value is
+not an int, float, or string literal."
+            ),
+            hint: Some("Use a guard to compare against it instead.".into()),
+            level: Level::Error,
+            code,
+            location: Some(Location {
+                label: Label {
+                    text: None,
+                    span: *location,
+                },
+                path: path.clone(),
+                src: src.clone(),
+                extra_labels: vec![],
+            }),
+        },
+
+        TypeError::ReservedModuleName { name } => {
+            let text = format!(
+                "The module name `{name}` is reserved.
+Try a different name for this module."
+            );
+            Diagnostic {
+                title: "Reserved module name".into(),
+                text,
+                hint: None,
+                location: None,
+                level: Level::Error,
+                code,
+            }
+        }
+
+        TypeError::KeywordInModuleName { name, keyword } => {
+            let text = wrap(&format!(
+                "The module name `{name}` contains the keyword `{keyword}`, so importing \
+it would be a syntax error.
+Try a different name for this module."
+            ));
+            Diagnostic {
+                title: "Invalid module name".into(),
+                text,
                 hint: None,
                 location: None,
                 level: Level::Error,
-            },
-
-            Error::WrongDependencyProvided {
-                path,
-                expected,
-                found,
-            } => {
-                let text = format!(
-                    "Expected package `{expected}` at path `{path}` but found `{found}` instead.",
-                );
-
-                Diagnostic {
-                    title: "Wrong dependency provided".into(),
-                    text,
-                    hint: None,
-                    location: None,
-                    level: Level::Error,
-                }
+                code,
             }
+        }
 
-            Error::ProvidedDependencyConflict {
-                package,
-                source_1,
-                source_2,
-            } => {
-                let text = format!(
-                    "The package `{package}` is provided as both `{source_1}` and `{source_2}`.",
-                );
-
-                Diagnostic {
-                    title: "Conflicting provided dependencies".into(),
-                    text,
-                    hint: None,
-                    location: None,
-                    level: Level::Error,
+        TypeError::NotExhaustivePatternMatch {
+            location,
+            unmatched,
+            kind,
+        } => {
+            let mut text = match kind {
+                PatternMatchKind::Case => {
+                    "This case expression does not match all possibilities.
+Each constructor must have a pattern that matches it or
+else it could crash."
                 }
-            }
-
-            Error::DuplicateDependency(name) => {
-                let text = format!(
-                    "The package `{name}` is specified in both the dependencies and
-dev-dependencies sections of the gleam.toml file."
-                );
-                Diagnostic {
-                    title: "Dependency duplicated".into(),
-                    text,
-                    hint: None,
-                    location: None,
-                    level: Level::Error,
+                PatternMatchKind::Assignment => {
+                    "This assignment does not match all possibilities.
+Either use a case expression with patterns for each possible
+value, or use `let assert` rather than `let`."
                 }
             }
+            .to_string();
 
-            Error::MissingHexPublishFields {
-                description_missing,
-                licence_missing,
-            } => {
-                let mut text =
-                    "Licence information and package description are required to publish a
-package to Hex.\n"
-                        .to_string();
-                text.push_str(if *description_missing && *licence_missing {
-                    r#"Add the licences and description fields to your gleam.toml file.
+            text.push_str("\n\nThese values are not matched:\n\n");
+            for unmatched in unmatched {
+                text.push_str("  - ");
+                text.push_str(unmatched);
+                text.push('\n');
+            }
+            Diagnostic {
+                title: "Not exhaustive pattern match".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
 
-description = ""
-licences = ["Apache-2.0"]"#
-                } else if *description_missing {
-                    r#"Add the description field to your gleam.toml file.
+        TypeError::ArgumentNameAlreadyUsed { location, name } => Diagnostic {
+            title: "Argument name already used".into(),
+            text: format!("Two `{name}` arguments have been defined for this function."),
+            hint: None,
+            level: Level::Error,
+            code,
+            location: Some(Location {
+                label: Label {
+                    text: None,
+                    span: *location,
+                },
+                path: path.clone(),
+                src: src.clone(),
+                extra_labels: vec![],
+            }),
+        },
+
+        TypeError::UnlabelledAfterlabelled { location } => Diagnostic {
+            title: "Unlabelled argument after labelled argument".into(),
+            text: wrap("All unlabelled arguments must come before any labelled arguments."),
+            hint: None,
+            level: Level::Error,
+            code,
+            location: Some(Location {
+                label: Label {
+                    text: None,
+                    span: *location,
+                },
+                path: path.clone(),
+                src: src.clone(),
+                extra_labels: vec![],
+            }),
+        },
+
+        TypeError::RecursiveTypeAlias { location, cycle } => {
+            let mut text = "This type alias is defined in terms of itself.\n".into();
+            write_cycle(&mut text, cycle);
+            text.push_str(
+                "If we tried to compile this recursive type it would expand
+forever in a loop, and we'd never get the final type.",
+            );
+            Diagnostic {
+                title: "Type cycle".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
 
-description = """#
-                } else {
-                    r#"Add the licences field to your gleam.toml file.
+        TypeError::ExternalMissingAnnotation { location, kind } => {
+            let kind = match kind {
+                MissingAnnotation::Parameter => "parameter",
+                MissingAnnotation::Return => "return",
+            };
+            let text = format!(
+                "A {kind} annotation is missing from this function.
 
-licences = ["Apache-2.0"]"#
-                });
-                Diagnostic {
-                    title: "Missing required package fields".into(),
-                    text,
-                    hint: None,
-                    location: None,
-                    level: Level::Error,
-                }
+Functions with external implementations must have type annotations
+so we can tell what type of values they accept and return.",
+            );
+            Diagnostic {
+                title: "Missing type annotation".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
             }
+        }
 
-            Error::PublishNonHexDependencies { package } => Diagnostic {
-                title: "Unblished dependencies".into(),
-                text: wrap_format!(
-                    "The package cannot be published to Hex \
-because dependency `{package}` is not a Hex dependency.",
-                ),
+        TypeError::NoImplementation { location } => {
+            let text = "We can't compile this function as it doesn't have an
+implementation. Add a body or an external implementation
+using the `@external` attribute."
+                .into();
+            Diagnostic {
+                title: "Function without an implementation".into(),
+                text,
                 hint: None,
-                location: None,
                 level: Level::Error,
-            },
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
 
-            Error::UnsupportedBuildTool {
-                package,
-                build_tools,
-            } => {
-                let text = wrap_format!(
-                    "The package `{}` cannot be built as it does not use \
-a build tool supported by Gleam. It uses {:?}.
+        TypeError::InvalidExternalJavascriptModule {
+            location,
+            name,
+            module,
+        } => {
+            let text = wrap_format!(
+                "The function `{name}` has an external JavaScript \
+implementation but the module path `{module}` is not valid."
+            );
+            Diagnostic {
+                title: "Invalid JavaScript module".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            }
+        }
 
-If you would like us to support this package please let us know by opening an \
-issue in our tracker: https://github.com/gleam-lang/gleam/issues",
-                    package,
-                    build_tools
-                );
-                Diagnostic {
-                    title: "Unsupported build tool".into(),
-                    text,
-                    hint: None,
-                    location: None,
-                    level: Level::Error,
-                }
+        TypeError::InvalidExternalJavascriptFunction {
+            location,
+            name,
+            function,
+        } => {
+            let text = wrap_format!(
+                "The function `{name}` has an external JavaScript \
+implementation but the function name `{function}` is not valid."
+            );
+            Diagnostic {
+                title: "Invalid JavaScript function".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
             }
+        }
 
-            Error::FailedToOpenDocs { path, error } => {
-                let error = format!("\nThe error message from the library was:\n\n    {error}\n");
-                let text = format!(
-                    "An error occurred while trying to open the docs:
+        TypeError::InexhaustiveLetAssignment { location, missing } => {
+            let mut text: String = "This assignment uses a pattern that does not match all possible
+values. If one of the other values is used then the assignment
+will crash.
 
-    {path}
-{error}",
-                );
-                Diagnostic {
-                    title: "Failed to open docs".into(),
-                    text,
-                    hint: None,
-                    level: Level::Error,
-                    location: None,
-                }
+The missing patterns are:\n"
+                .into();
+            for missing in missing {
+                text.push_str("\n    ");
+                text.push_str(&missing.to_string());
             }
+            text.push('\n');
 
-            Error::IncompatibleCompilerVersion {
-                package,
-                required_version,
-                gleam_version,
-            } => {
-                let text = format!(
-                    "The package `{package}` requires a Gleam version satisfying {required_version} \
-but you are using v{gleam_version}.",
-                );
-                Diagnostic {
-                    title: "Incompatible Gleam version".into(),
-                    text,
-                    hint: None,
-                    location: None,
-                    level: Level::Error,
-                }
+            Diagnostic {
+                title: "Inexhaustive pattern".into(),
+                text,
+                hint: Some("Use a more general pattern or use `let assert` instead.".into()),
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    src: src.clone(),
+                    path: path.to_path_buf(),
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    extra_labels: Vec::new(),
+                }),
             }
+        }
 
-            Error::InvalidRuntime {
-                target,
-                invalid_runtime,
-            } => {
-                let text = format!("Invalid runtime for {target} target: {invalid_runtime}");
+        TypeError::InexhaustiveCaseExpression { location, missing } => {
+            let mut text: String =
+                "This case expression does not have a pattern for all possible values.
+If it is run on one of the values without a pattern then it will crash.
 
-                let hint = match target {
-                    Target::JavaScript => {
-                        Some("available runtimes for JavaScript are: node, deno.".into())
-                    }
-                    Target::Erlang => Some(
-                        "You can not set a runtime for Erlang. Did you mean to target JavaScript?"
-                            .into(),
-                    ),
-                };
+The missing patterns are:\n"
+                    .into();
+            for missing in missing {
+                text.push_str("\n    ");
+                text.push_str(&missing.to_string());
+            }
+            Diagnostic {
+                title: "Inexhaustive patterns".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    src: src.clone(),
+                    path: path.to_path_buf(),
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    extra_labels: Vec::new(),
+                }),
+            }
+        }
 
-                Diagnostic {
-                    title: format!("Invalid runtime for {target}"),
-                    text,
-                    hint,
-                    location: None,
-                    level: Level::Error,
-                }
+        TypeError::UnsupportedExpressionTarget {
+            location,
+            target: current_target,
+        } => {
+            let text = wrap_format!(
+                "This value is not available as it is defined using externals, \
+and there is no implementation for the {} target.\n",
+                match current_target {
+                    Target::Erlang => "Erlang",
+                    Target::JavaScript => "JavaScript",
+                }
+            );
+            let hint = wrap("Did you mean to build for a different target?");
+            Diagnostic {
+                title: "Unsupported target".into(),
+                text,
+                hint: Some(hint),
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    path: path.clone(),
+                    src: src.clone(),
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::UnsupportedPublicFunctionTarget {
+            location,
+            name,
+            target,
+        } => {
+            let target = match target {
+                Target::Erlang => "Erlang",
+                Target::JavaScript => "JavaScript",
+            };
+            let text = wrap_format!(
+                "The `{name}` function is public but doesn't have an \
+implementation for the {target} target. All public functions of a package \
+must be able to compile for a module to be valid."
+            );
+            Diagnostic {
+                title: "Unsupported target".into(),
+                text,
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    path: path.clone(),
+                    src: src.clone(),
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    extra_labels: vec![],
+                }),
             }
+        }
 
-            Error::JavaScriptPreludeRequired => Diagnostic {
-                title: "JavaScript prelude required".into(),
-                text: "The --javascript-prelude flag must be given when compiling to JavaScript."
-                    .into(),
+        TypeError::UnusedTypeAliasParameter { location, name } => {
+            let text =
+                wrap_format!("The type variable `{name}` is unused. It can be safely removed.",);
+            Diagnostic {
+                title: "Unused type parameter".into(),
+                text,
+                hint: None,
                 level: Level::Error,
-                location: None,
+                code,
+                location: Some(Location {
+                    path: path.clone(),
+                    src: src.clone(),
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    extra_labels: vec![],
+                }),
+            }
+        }
+
+        TypeError::DuplicateTypeParameter { location, name } => {
+            let text = wrap_format!(
+                "This definition has multiple type parameters named `{name}`.
+Rename or remove one of them.",
+            );
+            Diagnostic {
+                title: "Duplicate type parameter".into(),
+                text,
                 hint: None,
-            },
-            Error::CorruptManifest => Diagnostic {
-                title: "Corrupt manifest.toml".into(),
-                text: "The `manifest.toml` file is corrupt.".into(),
                 level: Level::Error,
-                location: None,
-                hint: Some("Please run `gleam update` to fix it.".into()),
-            },
+                code,
+                location: Some(Location {
+                    path: path.clone(),
+                    src: src.clone(),
+                    label: Label {
+                        text: None,
+                        span: *location,
+                    },
+                    extra_labels: vec![],
+                }),
+            }
         }
     }
 }
@@ -3242,6 +3903,14 @@ functions from the `gleam/string` module.",
     )
 }
 
+/// Why formatting a file with `gleam format --verify` failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatVerificationProblem {
+    InvalidOutput,
+    ChangedSyntaxTree,
+    NotIdempotent,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Unformatted {
     pub source: Utf8PathBuf,
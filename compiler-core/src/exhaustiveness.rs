@@ -6,9 +6,7 @@
 //!
 //! Note that while this produces a decision tree, this tree is not suitable for
 //! use in code generation yet as it is incomplete. The tree is not correctly
-//! formed for:
-//! - Bit strings
-//! - String prefixes
+//! formed for string prefixes.
 //!
 //! These were not implemented as they are more complex and I've not worked out
 //! a good way to do them yet. The tricky bit is that unlike the others they are
@@ -27,25 +25,33 @@
 //! The decision tree needs to take into account that the first pattern is a
 //! super-pattern of the second, and the second is a super-pattern of the third.
 //!
+//! Bit array patterns can overlap in the same way. Rather than switching on a
+//! constructor they are tested one at a time, with each test having a branch
+//! for when the pattern matches and one for when it doesn't. See the
+//! `bit_array` module for how patterns are compared to each other.
+//!
 
+mod bit_array;
 mod missing_patterns;
 mod pattern;
 #[cfg(test)]
 mod pattern_tests;
 
+use self::bit_array::BitArrayTest;
 use self::pattern::{Constructor, Pattern, PatternId};
 use crate::{
     ast::AssignName,
     type_::{
         collapse_links, error::UnknownTypeConstructorError, is_prelude_module, Environment, Type,
-        TypeValueConstructor, TypeValueConstructorField, TypeVar,
+        TypeValueConstructor, TypeValueConstructorField, TypeVar, TypeVarCell,
     },
 };
 use ecow::EcoString;
 use id_arena::Arena;
 use itertools::Itertools;
-use std::{cell::RefCell, collections::HashMap, sync::Arc, u64};
+use std::{collections::HashMap, sync::Arc, u64};
 
+pub use self::missing_patterns::MissingPattern;
 pub use self::pattern::PatternArena;
 
 /// The body of code to evaluate in case of a match.
@@ -185,6 +191,22 @@ pub enum Decision {
     /// 3. A fallback decision to take, in case none of the cases matched.
     Switch(Variable, Vec<Case>, Option<Box<Decision>>),
 
+    /// Checks if a bit array matches a pattern.
+    BitArray {
+        /// The variable to test.
+        variable: Variable,
+        /// The pattern to test the variable against.
+        test: BitArrayTest,
+        /// The decision to take if the pattern matches.
+        matched: Box<Decision>,
+        /// The decision to take if the pattern doesn't match.
+        unmatched: Box<Decision>,
+    },
+
+    /// No value can reach this point, as the bit array patterns that were
+    /// tested on the way here contradict each other.
+    Unreachable,
+
     /// Checks if a list is empty or non-empty.
     List {
         /// The variable to test.
@@ -227,7 +249,7 @@ impl Match {
         self.diagnostics.reachable.contains(&(clause as u16))
     }
 
-    pub fn missing_patterns(&self, environment: &Environment<'_>) -> Vec<EcoString> {
+    pub fn missing_patterns(&self, environment: &Environment<'_>) -> Vec<MissingPattern> {
         missing_patterns::missing_patterns(self, environment)
     }
 }
@@ -239,6 +261,10 @@ pub struct Compiler<'a> {
     diagnostics: Diagnostics,
     patterns: Arena<Pattern>,
     environment: &'a Environment<'a>,
+    /// The bit array patterns tested on the way to the part of the tree being
+    /// compiled, with the id of the variable they were tested against and
+    /// whether they matched.
+    bit_array_tests: Vec<(usize, BitArrayTest, bool)>,
 }
 
 impl<'a> Compiler<'a> {
@@ -246,6 +272,7 @@ impl<'a> Compiler<'a> {
         Self {
             environment,
             patterns,
+            bit_array_tests: Vec::new(),
             variable_id: 0,
             diagnostics: Diagnostics {
                 missing: false,
@@ -261,6 +288,36 @@ impl<'a> Compiler<'a> {
         }
     }
 
+    /// Compiles a case expression without any clauses, which matches none of
+    /// the values of its subject. The values of a custom type are split by
+    /// their constructors, so each of them is reported as missing rather than
+    /// a single discard pattern.
+    pub fn compile_without_rows(mut self, variable: Variable) -> Match {
+        self.diagnostics.missing = true;
+        let tree = match self.branch_mode_for(variable) {
+            // External types have no constructors, so any value of them is
+            // missing.
+            BranchMode::NamedType {
+                variable,
+                constructors,
+            } if !constructors.is_empty() => {
+                let cases = self
+                    .constructor_cases(&variable, &constructors)
+                    .into_iter()
+                    .map(|(constructor, variables, _)| {
+                        Case::new(constructor, variables, Decision::Failure)
+                    })
+                    .collect();
+                Decision::Switch(variable, cases, None)
+            }
+            _ => Decision::Failure,
+        };
+        Match {
+            tree,
+            diagnostics: self.diagnostics,
+        }
+    }
+
     pub fn set_pattern_arena(&mut self, arena: Arena<Pattern>) {
         self.patterns = arena;
     }
@@ -331,34 +388,45 @@ impl<'a> Compiler<'a> {
                 element_type,
             } => self.compile_list_cases(rows, variable, element_type),
 
+            BranchMode::BitArray { variable } => self.compile_bit_array_cases(rows, variable),
+
             BranchMode::NamedType {
                 variable,
                 constructors,
             } => {
-                let cases = constructors
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, constructor)| {
-                        let variant = Constructor::Variant {
-                            type_: variable.type_.clone(),
-                            index: idx as u16,
-                        };
-                        // Make new variables for each of the fields of the variant,
-                        // so they can be used in the sub tree.
-                        let new_variables = constructor
-                            .parameters
-                            .iter()
-                            .map(|p| self.new_variable(p.type_.clone()))
-                            .collect_vec();
-                        (variant, new_variables, Vec::new())
-                    })
-                    .collect();
+                let cases = self.constructor_cases(&variable, &constructors);
                 let cases = self.compile_constructor_cases(rows, variable.clone(), cases);
                 Decision::Switch(variable, cases, None)
             }
         }
     }
 
+    /// A case for each of the constructors of a custom type, without any rows.
+    fn constructor_cases(
+        &mut self,
+        variable: &Variable,
+        constructors: &[TypeValueConstructor],
+    ) -> Vec<(Constructor, Vec<Variable>, Vec<Row>)> {
+        constructors
+            .iter()
+            .enumerate()
+            .map(|(idx, constructor)| {
+                let variant = Constructor::Variant {
+                    type_: variable.type_.clone(),
+                    index: idx as u16,
+                };
+                // Make new variables for each of the fields of the variant,
+                // so they can be used in the sub tree.
+                let new_variables = constructor
+                    .parameters
+                    .iter()
+                    .map(|p| self.new_variable(p.type_.clone()))
+                    .collect_vec();
+                (variant, new_variables, Vec::new())
+            })
+            .collect()
+    }
+
     /// String, ints and floats have an infinite number of constructors, so we
     /// specialise the compilation of their patterns with this function.
     fn compile_infinite_cases(
@@ -401,10 +469,6 @@ impl<'a> Compiler<'a> {
                         Constructor::Float(value.clone()),
                     ),
 
-                    Pattern::BitArray { value } => {
-                        (TestKey::Exact(value.clone()), Constructor::BitArray)
-                    }
-
                     Pattern::String { value } => (
                         TestKey::Exact(value.clone()),
                         Constructor::String(value.clone()),
@@ -431,6 +495,7 @@ impl<'a> Compiler<'a> {
                     | Pattern::Discard
                     | Pattern::EmptyList
                     | Pattern::List { .. }
+                    | Pattern::BitArray { .. }
                     | Pattern::Or { .. }) => panic!("Unexpected pattern {:?}", pattern),
                 };
 
@@ -620,6 +685,122 @@ impl<'a> Compiler<'a> {
         }
     }
 
+    /// Bit array patterns can overlap, so rather than a case for each
+    /// pattern the bit array is tested against the pattern of the first row,
+    /// with one branch for when it matches and one for when it doesn't.
+    ///
+    /// Rows with a pattern that is sure to match when the tested pattern does
+    /// have their column removed in the matching branch, and rows with a
+    /// pattern that can't match go only in the other branch. Branches that
+    /// no bit array could reach are not compiled.
+    ///
+    fn compile_bit_array_cases(&mut self, rows: Vec<Row>, branch_var: Variable) -> Decision {
+        let rows = rows
+            .into_iter()
+            .flat_map(|row| {
+                let pattern = row
+                    .columns
+                    .iter()
+                    .find(|column| column.variable.id == branch_var.id)
+                    .map(|column| column.pattern);
+                match pattern {
+                    Some(pattern) => self
+                        .flatten_or(pattern, row)
+                        .into_iter()
+                        .map(|(pattern, mut row)| {
+                            for column in &mut row.columns {
+                                if column.variable.id == branch_var.id {
+                                    column.pattern = pattern;
+                                }
+                            }
+                            row
+                        })
+                        .collect_vec(),
+                    None => vec![row],
+                }
+            })
+            .collect_vec();
+
+        let test = rows
+            .first()
+            .and_then(|row| self.bit_array_test(row, &branch_var))
+            .expect("The first row must test the bit array")
+            .clone();
+
+        let mut matched_rows = vec![];
+        let mut unmatched_rows = vec![];
+        for row in rows {
+            let Some(row_test) = self.bit_array_test(&row, &branch_var) else {
+                matched_rows.push(row.clone());
+                unmatched_rows.push(row);
+                continue;
+            };
+
+            if test.implies(row_test) {
+                let mut row = row.clone();
+                let _ = row.remove_column(branch_var.id);
+                matched_rows.push(row);
+            } else if !test.is_disjoint_from(row_test) {
+                matched_rows.push(row.clone());
+            }
+
+            if !row_test.implies(&test) {
+                unmatched_rows.push(row);
+            }
+        }
+
+        Decision::BitArray {
+            variable: branch_var.clone(),
+            matched: Box::new(self.compile_bit_array_branch(
+                &branch_var,
+                test.clone(),
+                true,
+                matched_rows,
+            )),
+            unmatched: Box::new(self.compile_bit_array_branch(
+                &branch_var,
+                test.clone(),
+                false,
+                unmatched_rows,
+            )),
+            test,
+        }
+    }
+
+    fn compile_bit_array_branch(
+        &mut self,
+        branch_var: &Variable,
+        test: BitArrayTest,
+        matched: bool,
+        rows: Vec<Row>,
+    ) -> Decision {
+        self.bit_array_tests.push((branch_var.id, test, matched));
+        let tests = self
+            .bit_array_tests
+            .iter()
+            .filter(|(variable_id, _, _)| *variable_id == branch_var.id)
+            .map(|(_, test, matched)| (test, *matched));
+        let decision = if bit_array::is_satisfiable(tests) {
+            self.compile_rows(rows)
+        } else {
+            Decision::Unreachable
+        };
+        let _ = self.bit_array_tests.pop();
+        decision
+    }
+
+    /// The bit array pattern a row tests the given variable against, if any.
+    fn bit_array_test(&self, row: &Row, variable: &Variable) -> Option<&BitArrayTest> {
+        let column = row
+            .columns
+            .iter()
+            .find(|column| column.variable.id == variable.id)?;
+        match self.pattern(column.pattern) {
+            Pattern::BitArray { test } => Some(test),
+            pattern => panic!("Unexpected non-bit-array pattern {:?}", pattern),
+        }
+    }
+
     /// Moves variable-only patterns/tests into the right-hand side/body of a
     /// case.
     ///
@@ -656,6 +837,12 @@ impl<'a> Compiler<'a> {
                     next = iterator.next();
                 }
 
+                // A bit array pattern that matches any bit array, such as
+                // `<<_:bits>>`, doesn't need testing.
+                Pattern::BitArray { test } if test.is_irrefutable() => {
+                    next = iterator.next();
+                }
+
                 Pattern::Variable { name: bind } => {
                     next = iterator.next();
                     bindings.push((bind.clone(), column.variable));
@@ -717,19 +904,25 @@ impl<'a> Compiler<'a> {
             .max_by_key(|var| counts.get(&var.id).copied().unwrap_or(0))
             .expect("The first row must have at least one column");
 
+        self.branch_mode_for(variable)
+    }
+
+    /// How to branch on the values of a variable, based on its type.
+    fn branch_mode_for(&self, variable: Variable) -> BranchMode {
         match collapse_links(variable.type_.clone()).as_ref() {
             Type::Fn { .. } | Type::Var { .. } => BranchMode::Infinite { variable },
 
             Type::Named { module, name, .. }
                 if is_prelude_module(module)
-                    && (name == "Int"
-                        || name == "Float"
-                        || name == "String"
-                        || name == "BitArray") =>
+                    && (name == "Int" || name == "Float" || name == "String") =>
             {
                 BranchMode::Infinite { variable }
             }
 
+            Type::Named { module, name, .. } if is_prelude_module(module) && name == "BitArray" => {
+                BranchMode::BitArray { variable }
+            }
+
             Type::Named {
                 module, name, args, ..
             } if is_prelude_module(module) && name == "List" => BranchMode::List {
@@ -817,6 +1010,9 @@ enum BranchMode {
         variable: Variable,
         element_type: Arc<Type>,
     },
+    BitArray {
+        variable: Variable,
+    },
     NamedType {
         variable: Variable,
         /// The constructors for this type. For example, `Result` has `Ok` and `Error`.
@@ -874,7 +1070,7 @@ impl ConstructorSpecialiser {
             },
 
             Type::Var { type_ } => Type::Var {
-                type_: Arc::new(RefCell::new(self.specialise_var(type_))),
+                type_: Arc::new(TypeVarCell::new(self.specialise_var(type_))),
             },
 
             Type::Tuple { elems } => Type::Tuple {
@@ -883,7 +1079,7 @@ impl ConstructorSpecialiser {
         })
     }
 
-    fn specialise_var(&self, type_: &RefCell<TypeVar>) -> TypeVar {
+    fn specialise_var(&self, type_: &TypeVarCell) -> TypeVar {
         match &*type_.borrow() {
             TypeVar::Unbound { id } => TypeVar::Unbound { id: *id },
 
@@ -0,0 +1,430 @@
+//! A simplified description of bit array patterns, used by the exhaustiveness
+//! checker to tell when one bit array pattern covers another, when two bit
+//! array patterns can never match the same value, and when no value can pass
+//! a combination of bit array patterns that did and did not match.
+//!
+//! A bit array pattern is described by the sizes of the bit arrays it can
+//! match and the values of the bits it checks. Segments that can't be
+//! described this way, such as floats, utf8 codepoints, or segments with a
+//! size given by a variable, make the whole pattern unknown. An unknown
+//! pattern is only ever known to cover itself, so it is handled the same way
+//! as all bit array patterns were before this analysis existed.
+//!
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    ast::{BitArrayOption, Pattern, SrcSpan, TypedPattern, TypedPatternBitArraySegment},
+    parse::parse_int_value,
+};
+
+/// The largest number of bits that are enumerated when checking if a set of
+/// patterns leaves any value possible. Past this the check gives up and
+/// assumes there is one.
+const MAXIMUM_ENUMERATED_BITS: usize = 12;
+
+/// The largest period of bit array sizes that is checked when looking for a
+/// size that is accepted by a set of patterns.
+const MAXIMUM_SIZE_PERIOD: u64 = 1024;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum BitArrayTest {
+    Known {
+        size: SizeTest,
+        /// The value of each of the bits checked by the pattern, by their
+        /// offset from the start of the bit array.
+        bits: BTreeMap<u64, bool>,
+    },
+
+    /// A pattern using segments that are not analysed. The location of the
+    /// pattern is used to tell these apart.
+    Unknown { location: SrcSpan },
+}
+
+/// The sizes, in bits, of the bit arrays a pattern can match.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum SizeTest {
+    /// Matches bit arrays with exactly this many bits.
+    Exact(u64),
+
+    /// Matches bit arrays with at least `minimum` bits, with the bits after
+    /// those coming in multiples of `unit`. This is the size of a pattern
+    /// ending with an unsized `bits` or `bytes` segment.
+    AtLeast { minimum: u64, unit: u64 },
+}
+
+impl SizeTest {
+    fn admits(&self, size: u64) -> bool {
+        match self {
+            SizeTest::Exact(exact) => size == *exact,
+            SizeTest::AtLeast { minimum, unit } => {
+                size >= *minimum && (size - minimum).is_multiple_of(*unit)
+            }
+        }
+    }
+
+    fn minimum(&self) -> u64 {
+        match self {
+            SizeTest::Exact(minimum) | SizeTest::AtLeast { minimum, .. } => *minimum,
+        }
+    }
+
+    fn unit(&self) -> u64 {
+        match self {
+            SizeTest::Exact(_) => 1,
+            SizeTest::AtLeast { unit, .. } => *unit,
+        }
+    }
+
+    fn is_subset_of(&self, other: &SizeTest) -> bool {
+        match (self, other) {
+            (SizeTest::Exact(size), _) => other.admits(*size),
+            (SizeTest::AtLeast { .. }, SizeTest::Exact(_)) => false,
+            (
+                SizeTest::AtLeast { minimum, unit },
+                SizeTest::AtLeast {
+                    unit: other_unit, ..
+                },
+            ) => other.admits(*minimum) && unit % other_unit == 0,
+        }
+    }
+
+    fn is_disjoint_from(&self, other: &SizeTest) -> bool {
+        let start = self.minimum().max(other.minimum());
+        let period = self.unit() * other.unit();
+        !(start..=start + period).any(|size| self.admits(size) && other.admits(size))
+    }
+}
+
+impl BitArrayTest {
+    pub fn from_segments(location: SrcSpan, segments: &[TypedPatternBitArraySegment]) -> Self {
+        match known_test(segments) {
+            Some((size, bits)) => BitArrayTest::Known { size, bits },
+            None => BitArrayTest::Unknown { location },
+        }
+    }
+
+    /// Whether the pattern matches any bit array, like `<<_:bits>>` does.
+    pub fn is_irrefutable(&self) -> bool {
+        match self {
+            BitArrayTest::Known {
+                size:
+                    SizeTest::AtLeast {
+                        minimum: 0,
+                        unit: 1,
+                    },
+                bits,
+            } => bits.is_empty(),
+            BitArrayTest::Known { .. } | BitArrayTest::Unknown { .. } => false,
+        }
+    }
+
+    /// Whether every value matched by this pattern is also matched by the
+    /// other one.
+    pub fn implies(&self, other: &BitArrayTest) -> bool {
+        match (self, other) {
+            (
+                BitArrayTest::Known { size, bits },
+                BitArrayTest::Known {
+                    size: other_size,
+                    bits: other_bits,
+                },
+            ) => {
+                size.is_subset_of(other_size)
+                    && other_bits
+                        .iter()
+                        .all(|(offset, value)| bits.get(offset) == Some(value))
+            }
+            (BitArrayTest::Known { .. } | BitArrayTest::Unknown { .. }, _) => self == other,
+        }
+    }
+
+    /// Whether there is no value matched by both this pattern and the other
+    /// one.
+    pub fn is_disjoint_from(&self, other: &BitArrayTest) -> bool {
+        match (self, other) {
+            (
+                BitArrayTest::Known { size, bits },
+                BitArrayTest::Known {
+                    size: other_size,
+                    bits: other_bits,
+                },
+            ) => {
+                size.is_disjoint_from(other_size)
+                    || other_bits
+                        .iter()
+                        .any(|(offset, value)| bits.get(offset).is_some_and(|bit| bit != value))
+            }
+            (BitArrayTest::Known { .. } | BitArrayTest::Unknown { .. }, _) => false,
+        }
+    }
+}
+
+/// Whether there is a bit array that matches all the patterns that are
+/// paired with `true` and none of those paired with `false`.
+///
+/// Unknown patterns are ignored, and if there are too many possible values to
+/// check this gives up and returns `true`, so this can only be relied on when
+/// it says that there is no such value.
+///
+pub fn is_satisfiable<'a>(checks: impl IntoIterator<Item = (&'a BitArrayTest, bool)>) -> bool {
+    let mut positives = vec![];
+    let mut negatives = vec![];
+    for (test, matched) in checks {
+        let BitArrayTest::Known { size, bits } = test else {
+            continue;
+        };
+        if matched {
+            positives.push((size, bits));
+        } else {
+            negatives.push((size, bits));
+        }
+    }
+
+    // All the bits checked by the patterns that did match must agree.
+    let mut fixed = BTreeMap::new();
+    for (_, bits) in &positives {
+        for (offset, value) in bits.iter() {
+            if *fixed.entry(*offset).or_insert(*value) != *value {
+                return false;
+            }
+        }
+    }
+
+    // Past the largest size mentioned by any of the patterns whether a size
+    // is accepted repeats with a period of the units of the patterns, so only
+    // the sizes up to there need checking.
+    let mut largest = 0;
+    let mut period = 1;
+    for (size, bits) in positives.iter().chain(&negatives) {
+        let last_bit = bits.keys().next_back().map_or(0, |offset| offset + 1);
+        largest = largest.max(size.minimum()).max(last_bit);
+        period = lcm(period, size.unit());
+        if period > MAXIMUM_SIZE_PERIOD {
+            return true;
+        }
+    }
+
+    (0..=largest + period).any(|length| {
+        if !positives.iter().all(|(size, _)| size.admits(length)) {
+            return false;
+        }
+
+        // The patterns that didn't match and could have matched a value of
+        // this size.
+        let negatives = negatives
+            .iter()
+            .filter(|(size, bits)| {
+                size.admits(length)
+                    && bits
+                        .iter()
+                        .all(|(offset, value)| fixed.get(offset).is_none_or(|bit| bit == value))
+            })
+            .map(|(_, bits)| *bits)
+            .collect::<Vec<_>>();
+
+        let free_bits = negatives
+            .iter()
+            .flat_map(|bits| bits.keys())
+            .filter(|offset| !fixed.contains_key(offset))
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        if free_bits.len() > MAXIMUM_ENUMERATED_BITS {
+            return true;
+        }
+
+        // Look for values of the bits checked by the patterns that didn't
+        // match which none of those patterns match.
+        (0..1_u32 << free_bits.len()).any(|assignment| {
+            let bit_value = |offset: &u64| match fixed.get(offset) {
+                Some(value) => *value,
+                None => {
+                    let index = free_bits
+                        .iter()
+                        .position(|free| free == offset)
+                        .expect("Bit must be fixed or free");
+                    assignment & (1 << index) != 0
+                }
+            };
+            !negatives.iter().any(|bits| {
+                bits.iter()
+                    .all(|(offset, value)| bit_value(offset) == *value)
+            })
+        })
+    })
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    a / x * b
+}
+
+enum Segment {
+    /// A segment with a known size, and the value of its bits if it is a
+    /// literal.
+    Sized { size: u64, bits: Option<Vec<bool>> },
+
+    /// An unsized `bits` or `bytes` segment, which matches the rest of the bit
+    /// array.
+    Rest { unit: u64 },
+}
+
+fn known_test(segments: &[TypedPatternBitArraySegment]) -> Option<(SizeTest, BTreeMap<u64, bool>)> {
+    let mut offset = 0;
+    let mut checked_bits = BTreeMap::new();
+    for (index, segment) in segments.iter().enumerate() {
+        match known_segment(segment)? {
+            Segment::Sized { size, bits } => {
+                for (bit_offset, value) in bits.into_iter().flatten().enumerate() {
+                    let _ = checked_bits.insert(offset + bit_offset as u64, value);
+                }
+                offset += size;
+            }
+
+            Segment::Rest { unit } if index == segments.len() - 1 => {
+                return Some((
+                    SizeTest::AtLeast {
+                        minimum: offset,
+                        unit,
+                    },
+                    checked_bits,
+                ));
+            }
+
+            Segment::Rest { .. } => return None,
+        }
+    }
+    Some((SizeTest::Exact(offset), checked_bits))
+}
+
+fn known_segment(segment: &TypedPatternBitArraySegment) -> Option<Segment> {
+    enum Kind {
+        Int,
+        Bits { default_unit: u64 },
+        Utf8,
+    }
+
+    let mut kind = Kind::Int;
+    let mut signed = false;
+    let mut little_endian = false;
+    let mut size = None;
+    let mut unit = None;
+    for option in &segment.options {
+        match option {
+            BitArrayOption::Int { .. }
+            | BitArrayOption::Unsigned { .. }
+            | BitArrayOption::Big { .. } => (),
+            BitArrayOption::Bytes { .. } => kind = Kind::Bits { default_unit: 8 },
+            BitArrayOption::Bits { .. } => kind = Kind::Bits { default_unit: 1 },
+            BitArrayOption::Utf8 { .. } => kind = Kind::Utf8,
+            BitArrayOption::Signed { .. } => signed = true,
+            BitArrayOption::Little { .. } => little_endian = true,
+            BitArrayOption::Size { value, .. } => size = Some(literal_int(value)?),
+            BitArrayOption::Unit { value, .. } => unit = Some(u64::from(*value)),
+            BitArrayOption::Float { .. }
+            | BitArrayOption::Utf16 { .. }
+            | BitArrayOption::Utf32 { .. }
+            | BitArrayOption::Utf8Codepoint { .. }
+            | BitArrayOption::Utf16Codepoint { .. }
+            | BitArrayOption::Utf32Codepoint { .. }
+            | BitArrayOption::Native { .. } => return None,
+        }
+    }
+
+    match kind {
+        Kind::Utf8 => match segment.value.as_ref() {
+            Pattern::String { value, .. } => Some(Segment::Sized {
+                size: value.len() as u64 * 8,
+                bits: Some(
+                    value
+                        .bytes()
+                        .flat_map(|byte| (0..8).rev().map(move |bit| byte & (1 << bit) != 0))
+                        .collect(),
+                ),
+            }),
+            _ => None,
+        },
+
+        Kind::Bits { default_unit } => {
+            if !is_irrefutable(&segment.value) {
+                return None;
+            }
+            let unit = unit.unwrap_or(default_unit);
+            match size {
+                Some(size) => Some(Segment::Sized {
+                    size: size * unit,
+                    bits: None,
+                }),
+                None if unit == 0 => None,
+                None => Some(Segment::Rest { unit }),
+            }
+        }
+
+        Kind::Int => {
+            let size = size.unwrap_or(8) * unit.unwrap_or(1);
+            match segment.value.as_ref() {
+                Pattern::Int { value, .. } => Some(Segment::Sized {
+                    size,
+                    bits: Some(int_bits(
+                        parse_int_value(value)?,
+                        size,
+                        signed,
+                        little_endian,
+                    )?),
+                }),
+                value if is_irrefutable(value) => Some(Segment::Sized { size, bits: None }),
+                _ => None,
+            }
+        }
+    }
+}
+
+fn is_irrefutable(pattern: &TypedPattern) -> bool {
+    match pattern {
+        Pattern::Variable { .. } | Pattern::Discard { .. } => true,
+        Pattern::Assign { pattern, .. } => is_irrefutable(pattern),
+        _ => false,
+    }
+}
+
+fn literal_int(pattern: &TypedPattern) -> Option<u64> {
+    match pattern {
+        Pattern::Int { value, .. } => u64::try_from(parse_int_value(value)?).ok(),
+        _ => None,
+    }
+}
+
+/// The bits of an int segment, most significant first. Returns `None` if the
+/// int doesn't fit in the segment, as the segment can then never match.
+fn int_bits(value: i128, size: u64, signed: bool, little_endian: bool) -> Option<Vec<bool>> {
+    if size > 64 {
+        return None;
+    }
+    let (minimum, maximum) = if signed && size > 0 {
+        (-(1_i128 << (size - 1)), 1_i128 << (size - 1))
+    } else {
+        (0, 1_i128 << size)
+    };
+    if value < minimum || value >= maximum {
+        return None;
+    }
+
+    let value = value.rem_euclid(1_i128 << size);
+    let bits = (0..size)
+        .rev()
+        .map(|bit| value & (1 << bit) != 0)
+        .collect::<Vec<_>>();
+
+    if !little_endian {
+        Some(bits)
+    } else if size.is_multiple_of(8) {
+        Some(bits.chunks(8).rev().flatten().copied().collect())
+    } else {
+        None
+    }
+}
@@ -3,30 +3,95 @@ use crate::type_::environment::Environment;
 use ecow::EcoString;
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// A pattern that is not matched by a case expression or let assignment.
+///
+/// These are produced by the exhaustiveness checker as witnesses of the
+/// values that would cause the program to crash, and can be printed as
+/// Gleam code using their `Display` implementation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MissingPattern {
+    /// Any value, printed as `_`. Used for values that cannot be enumerated
+    /// such as ints and strings, or for sub-patterns that were not inspected.
+    Discard,
+
+    /// A custom type constructor, such as `Ok(_)` or `Nil`.
+    Constructor {
+        /// The module the custom type is defined in.
+        module: EcoString,
+        name: EcoString,
+        arguments: Vec<MissingPattern>,
+    },
+
+    Tuple {
+        elements: Vec<MissingPattern>,
+    },
+
+    /// A list with a known prefix, such as `[]`, `[_]` or `[_, ..]`.
+    List {
+        elements: Vec<MissingPattern>,
+        /// Whether the list pattern ends with a `..` tail.
+        tail: bool,
+    },
+}
+
+impl fmt::Display for MissingPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MissingPattern::Discard => write!(f, "_"),
+
+            MissingPattern::Constructor {
+                name, arguments, ..
+            } if arguments.is_empty() => write!(f, "{name}"),
+
+            MissingPattern::Constructor {
+                name, arguments, ..
+            } => write!(f, "{name}({})", arguments.iter().join(", ")),
+
+            MissingPattern::Tuple { elements } => write!(f, "#({})", elements.iter().join(", ")),
+
+            MissingPattern::List { elements, tail } => {
+                write!(f, "[{}", elements.iter().join(", "))?;
+                match (elements.is_empty(), tail) {
+                    (_, false) => (),
+                    (true, true) => write!(f, "..")?,
+                    (false, true) => write!(f, ", ..")?,
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
 
 /// Returns a list of patterns not covered by the match expression.
-pub fn missing_patterns(matches: &Match, environment: &Environment<'_>) -> Vec<EcoString> {
-    let mut names = HashSet::new();
+pub fn missing_patterns(matches: &Match, environment: &Environment<'_>) -> Vec<MissingPattern> {
+    let mut patterns = HashSet::new();
     let mut steps = Vec::new();
 
-    add_missing_patterns(&matches.tree, &mut steps, &mut names, environment);
-
-    let mut missing: Vec<EcoString> = names.into_iter().collect();
+    add_missing_patterns(&matches.tree, &mut steps, &mut patterns, environment);
 
     // Sorting isn't necessary, but it makes it a bit easier to write tests.
-    missing.sort();
-    missing
+    patterns
+        .into_iter()
+        .sorted_by_cached_key(|pattern| pattern.to_string())
+        .collect()
 }
 
 /// Information about a single constructor/value (aka term) being tested, used
-/// to build a list of names of missing patterns.
+/// to build a list of missing patterns.
 #[derive(Debug)]
 enum Term {
     Variant {
         variable: Variable,
+        module: EcoString,
         name: EcoString,
         arguments: Vec<Variable>,
     },
+    Tuple {
+        variable: Variable,
+        elements: Vec<Variable>,
+    },
     Infinite {
         variable: Variable,
     },
@@ -44,89 +109,100 @@ impl Term {
     fn variable(&self) -> &Variable {
         match self {
             Term::Variant { variable, .. } => variable,
+            Term::Tuple { variable, .. } => variable,
             Term::Infinite { variable } => variable,
             Term::EmptyList { variable } => variable,
             Term::List { variable, .. } => variable,
         }
     }
 
-    fn pattern_string(&self, terms: &[Term], mapping: &HashMap<usize, usize>) -> EcoString {
+    fn pattern(&self, terms: &[Term], mapping: &HashMap<usize, usize>) -> MissingPattern {
         match self {
             Term::Variant {
-                name, arguments, ..
-            } => {
-                if arguments.is_empty() {
-                    return name.clone();
-                }
-                let args = arguments
-                    .iter()
-                    .map(|variable| {
-                        mapping
-                            .get(&variable.id)
-                            .map(|&idx| {
-                                terms
-                                    .get(idx)
-                                    .expect("Term must exist")
-                                    .pattern_string(terms, mapping)
-                            })
-                            .unwrap_or_else(|| "_".into())
-                    })
-                    .join(", ");
-                format!("{}({})", name, args).into()
-            }
+                module,
+                name,
+                arguments,
+                ..
+            } => MissingPattern::Constructor {
+                module: module.clone(),
+                name: name.clone(),
+                arguments: variable_patterns(arguments, terms, mapping),
+            },
 
-            Term::Infinite { .. } => "_".into(),
+            Term::Tuple { elements, .. } => MissingPattern::Tuple {
+                elements: variable_patterns(elements, terms, mapping),
+            },
 
-            Term::EmptyList { .. } => "[]".into(),
+            Term::Infinite { .. } => MissingPattern::Discard,
 
-            Term::List { .. } => format!("[{}]", self.list_pattern_string(terms, mapping)).into(),
+            Term::EmptyList { .. } | Term::List { .. } => {
+                let mut elements = vec![];
+                let tail = self.collect_list_elements(terms, mapping, &mut elements);
+                MissingPattern::List { elements, tail }
+            }
         }
     }
 
-    fn list_pattern_string(&self, terms: &[Term], mapping: &HashMap<usize, usize>) -> EcoString {
+    /// Pushes the patterns for the elements of a list term, returning whether
+    /// the list has a tail that was not inspected any further.
+    fn collect_list_elements(
+        &self,
+        terms: &[Term],
+        mapping: &HashMap<usize, usize>,
+        elements: &mut Vec<MissingPattern>,
+    ) -> bool {
         match self {
-            Term::Infinite { .. } | Term::Variant { .. } => "_".into(),
+            Term::Infinite { .. } | Term::Variant { .. } | Term::Tuple { .. } => true,
 
-            Term::EmptyList { .. } => "".into(),
+            Term::EmptyList { .. } => false,
 
             Term::List { first, rest, .. } => {
-                let first = mapping
-                    .get(&first.id)
-                    .map(|&idx| {
-                        terms
-                            .get(idx)
-                            .expect("Term must exist")
-                            .pattern_string(terms, mapping)
-                    })
-                    .unwrap_or_else(|| "_".into());
-                let rest = mapping
-                    .get(&rest.id)
-                    .map(|&idx| {
-                        terms
-                            .get(idx)
-                            .expect("Term must exist")
-                            .list_pattern_string(terms, mapping)
-                    })
-                    .unwrap_or_else(|| "_".into());
-
-                match rest.as_str() {
-                    "" => first,
-                    "_" => format!("{first}, ..").into(),
-                    _ => format!("{first}, {rest}").into(),
+                elements.push(variable_pattern(first, terms, mapping));
+                match mapping.get(&rest.id) {
+                    Some(&index) => terms
+                        .get(index)
+                        .expect("Term must exist")
+                        .collect_list_elements(terms, mapping, elements),
+                    None => true,
                 }
             }
         }
     }
 }
 
+fn variable_pattern(
+    variable: &Variable,
+    terms: &[Term],
+    mapping: &HashMap<usize, usize>,
+) -> MissingPattern {
+    match mapping.get(&variable.id) {
+        Some(&index) => terms
+            .get(index)
+            .expect("Term must exist")
+            .pattern(terms, mapping),
+        None => MissingPattern::Discard,
+    }
+}
+
+fn variable_patterns(
+    variables: &[Variable],
+    terms: &[Term],
+    mapping: &HashMap<usize, usize>,
+) -> Vec<MissingPattern> {
+    variables
+        .iter()
+        .map(|variable| variable_pattern(variable, terms, mapping))
+        .collect()
+}
+
 fn add_missing_patterns(
     node: &Decision,
     terms: &mut Vec<Term>,
-    missing: &mut HashSet<EcoString>,
+    missing: &mut HashSet<MissingPattern>,
     environment: &Environment<'_>,
 ) {
     match node {
-        Decision::Success(_) => {}
+        Decision::Success(_) | Decision::Unreachable => {}
 
         Decision::Failure => {
             let mut mapping = HashMap::new();
@@ -139,7 +215,7 @@ fn add_missing_patterns(
             // appear in order in the term stack.
             //
             // This mapping is then used when (recursively) generating a
-            // pattern name.
+            // pattern.
             //
             // This approach could probably be done more efficiently, so if
             // you're reading this and happen to know of a way, please
@@ -148,12 +224,12 @@ fn add_missing_patterns(
                 _ = mapping.insert(step.variable().id, index);
             }
 
-            let name = terms
+            let pattern = terms
                 .first()
-                .map(|term| term.pattern_string(terms, &mapping))
-                .unwrap_or_else(|| "_".into());
+                .map(|term| term.pattern(terms, &mapping))
+                .unwrap_or(MissingPattern::Discard);
 
-            _ = missing.insert(name);
+            _ = missing.insert(pattern);
         }
 
         Decision::Guard(_, _, fallback) => {
@@ -166,7 +242,6 @@ fn add_missing_patterns(
                     Constructor::Int(_)
                     | Constructor::Float(_)
                     | Constructor::String(_)
-                    | Constructor::BitArray
                     | Constructor::StringPrefix => {
                         terms.push(Term::Infinite {
                             variable: variable.clone(),
@@ -174,11 +249,9 @@ fn add_missing_patterns(
                     }
 
                     Constructor::Tuple(_) => {
-                        let arguments = case.arguments.clone();
-                        terms.push(Term::Variant {
+                        terms.push(Term::Tuple {
                             variable: variable.clone(),
-                            name: "#".into(),
-                            arguments,
+                            elements: case.arguments.clone(),
                         });
                     }
 
@@ -195,6 +268,7 @@ fn add_missing_patterns(
                             .clone();
                         terms.push(Term::Variant {
                             variable: variable.clone(),
+                            module,
                             name,
                             arguments: case.arguments.clone(),
                         });
@@ -210,6 +284,21 @@ fn add_missing_patterns(
             }
         }
 
+        Decision::BitArray {
+            variable,
+            matched,
+            unmatched,
+            ..
+        } => {
+            for decision in [matched, unmatched] {
+                terms.push(Term::Infinite {
+                    variable: variable.clone(),
+                });
+                add_missing_patterns(decision, terms, missing, environment);
+                _ = terms.pop();
+            }
+        }
+
         Decision::List {
             variable,
             empty,
@@ -1,5 +1,6 @@
+use super::bit_array::BitArrayTest;
 use crate::{
-    ast::{AssignName, TypedPattern},
+    ast::{AssignName, Constant, TypedPattern},
     type_::Type,
 };
 use ecow::EcoString;
@@ -51,9 +52,8 @@ pub enum Pattern {
         rest: PatternId,
     },
     EmptyList,
-    // TODO: Compile the matching within the bit strings
     BitArray {
-        value: EcoString,
+        test: BitArrayTest,
     },
 }
 
@@ -66,8 +66,6 @@ pub enum Constructor {
     String(EcoString),
     Variant { type_: Arc<Type>, index: u16 },
     // TODO: Generate a decision tree for this
-    BitArray,
-    // TODO: Generate a decision tree for this
     StringPrefix,
 }
 
@@ -79,7 +77,6 @@ impl Constructor {
             | Constructor::Float(_)
             | Constructor::Tuple(_)
             | Constructor::String(_)
-            | Constructor::BitArray
             | Constructor::StringPrefix => 0,
 
             Constructor::Variant { index, .. } => *index,
@@ -169,15 +166,9 @@ impl PatternArena {
                 })
             }
 
-            TypedPattern::BitArray { location, .. } => {
-                // TODO: in future support bit strings fully and check the
-                // exhaustiveness of their segment patterns.
-                // For now we use the location to give each bit string a pattern
-                // a unique value.
-                self.insert(Pattern::BitArray {
-                    value: format!("{}:{}", location.start, location.end).into(),
-                })
-            }
+            TypedPattern::BitArray { location, segments } => self.insert(Pattern::BitArray {
+                test: BitArrayTest::from_segments(*location, segments),
+            }),
 
             TypedPattern::StringPrefix {
                 left_side_string,
@@ -189,6 +180,25 @@ impl PatternArena {
                 self.insert(Pattern::StringPrefix { prefix, rest })
             }
 
+            TypedPattern::Constant { constructor, .. } => {
+                let literal = constructor
+                    .as_ref()
+                    .and_then(|constructor| constructor.pattern_literal())
+                    .expect("Constant pattern must have a literal value");
+                match literal {
+                    Constant::Int { value, .. } => self.insert(Pattern::Int {
+                        value: value.clone(),
+                    }),
+                    Constant::Float { value, .. } => self.insert(Pattern::Float {
+                        value: value.clone(),
+                    }),
+                    Constant::String { value, .. } => self.insert(Pattern::String {
+                        value: value.clone(),
+                    }),
+                    _ => unreachable!("Constant pattern with a non literal value"),
+                }
+            }
+
             TypedPattern::VarUsage { .. } => {
                 unreachable!("Cannot convert VarUsage to exhaustiveness pattern")
             }
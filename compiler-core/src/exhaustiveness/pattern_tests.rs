@@ -3,7 +3,9 @@ use crate::{
     type_,
 };
 
+use super::bit_array::{BitArrayTest, SizeTest};
 use super::pattern::*;
+use std::collections::BTreeMap;
 
 #[test]
 fn register_int() {
@@ -64,7 +66,10 @@ fn register_bit_string() {
     assert_eq!(
         patterns.get(id),
         Some(&Pattern::BitArray {
-            value: "123:456".into()
+            test: BitArrayTest::Known {
+                size: SizeTest::Exact(0),
+                bits: BTreeMap::new(),
+            }
         })
     )
 }
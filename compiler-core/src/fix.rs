@@ -1,28 +1,223 @@
-use crate::{
-    format::{Formatter, Intermediate},
-    Error, Result,
-};
+//! Fixes are changes to the source code of a module that can be made without
+//! a person checking them, such as removing unused imports, replacing uses of
+//! deprecated functions, or the rewrites suggested by lints. The language
+//! server offers them as quick fixes, and `gleam fix` applies them to every
+//! module of a project.
+
+use std::sync::Arc;
+
 use camino::Utf8Path;
 use ecow::EcoString;
 
-pub fn parse_fix_and_format(src: &EcoString, path: &Utf8Path) -> Result<String> {
-    // Parse
-    let parsed = crate::parse::parse_module(src).map_err(|error| Error::Parse {
-        path: path.to_path_buf(),
-        src: src.clone(),
-        error,
-    })?;
-    let intermediate = Intermediate::from_extra(&parsed.extra, src);
-    let module = parsed.module;
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        SrcSpan, TypedModule,
+    },
+    config::LintsConfig,
+    format::FormatOptions,
+    lint::{LintEdit, LintFix, Replacement},
+    type_::{self, Deprecation, ModuleValueConstructor, Type},
+    Result,
+};
 
-    // Fix
-    // let module = some_fixer_module::Fixer::fix(module);
+#[cfg(test)]
+mod tests;
 
-    // Format
+/// Parses and formats a module, which migrates any syntax that is still
+/// accepted by the parser but has been replaced to the current syntax.
+pub fn parse_fix_and_format(
+    src: &EcoString,
+    path: &Utf8Path,
+    options: FormatOptions,
+) -> Result<String> {
     let mut buffer = String::new();
-    Formatter::with_comments(&intermediate)
-        .module(&module)
-        .pretty_print(80, &mut buffer)?;
-
+    crate::format::pretty_with_options(&mut buffer, src, path, options)?;
     Ok(buffer)
 }
+
+/// The fix removing the imports, and import aliases, of the module that are
+/// not used.
+pub fn remove_unused_imports(module: &TypedModule) -> Option<LintFix> {
+    let unused = &module.type_info.unused_imports;
+    if unused.is_empty() {
+        return None;
+    }
+    let mut edits = unused
+        .iter()
+        .map(|location| LintEdit {
+            location: *location,
+            replacement: vec![],
+        })
+        .collect::<Vec<_>>();
+    edits.sort_by_key(|edit| edit.location.start);
+    Some(LintFix {
+        title: "Remove unused imports".into(),
+        edits,
+    })
+}
+
+/// The fixes replacing uses of deprecated functions and constants of other
+/// modules with the value their deprecation message says to use instead,
+/// when it is in the same module. The message has to be of the form
+/// "Use `replacement` instead", the name being written either on its own or
+/// qualified with the module.
+pub fn replace_deprecated_values(
+    module: &TypedModule,
+    interfaces: &im::HashMap<EcoString, type_::ModuleInterface>,
+) -> Vec<LintFix> {
+    let mut finder = DeprecatedValueFinder {
+        interfaces,
+        fixes: vec![],
+    };
+    finder.visit_typed_module(module);
+    finder.fixes
+}
+
+struct DeprecatedValueFinder<'a> {
+    interfaces: &'a im::HashMap<EcoString, type_::ModuleInterface>,
+    fixes: Vec<LintFix>,
+}
+
+impl<'ast> Visit<'ast> for DeprecatedValueFinder<'_> {
+    fn visit_typed_expr_module_select(
+        &mut self,
+        location: &'ast SrcSpan,
+        typ: &'ast Arc<Type>,
+        label: &'ast EcoString,
+        module_name: &'ast EcoString,
+        module_alias: &'ast EcoString,
+        constructor: &'ast ModuleValueConstructor,
+    ) {
+        let replacement = self
+            .interfaces
+            .get(module_name)
+            .and_then(|interface| interface.values.get(label))
+            .and_then(|value| match &value.deprecation {
+                Deprecation::Deprecated { message } => {
+                    deprecation_replacement(message, module_name)
+                }
+                Deprecation::NotDeprecated => None,
+            });
+        if let Some(replacement) = replacement {
+            // The location of a module select is that of the `.label` after
+            // the module, so the module alias used is left as it is.
+            self.fixes.push(LintFix {
+                title: format!("Use `{module_alias}.{replacement}`").into(),
+                edits: vec![LintEdit {
+                    location: *location,
+                    replacement: vec![Replacement::Text(format!(".{replacement}").into())],
+                }],
+            });
+        }
+        visit::visit_typed_expr_module_select(
+            self,
+            location,
+            typ,
+            label,
+            module_name,
+            module_alias,
+            constructor,
+        );
+    }
+}
+
+/// The name of the value a deprecation message of the form "Use `name`
+/// instead" says to use, if it is in the module the deprecated value is in.
+fn deprecation_replacement<'a>(message: &'a str, module_name: &str) -> Option<&'a str> {
+    let name = message
+        .trim()
+        .trim_end_matches('.')
+        .strip_prefix("Use ")?
+        .strip_suffix(" instead")?
+        .trim_matches('`');
+    let name = match name.rsplit_once('.') {
+        Some((module, name)) => {
+            let module_end = module_name.rsplit('/').next().unwrap_or(module_name);
+            if module != module_name && module != module_end {
+                return None;
+            }
+            name
+        }
+        None => name,
+    };
+    let mut characters = name.chars();
+    let is_value_name = characters
+        .next()
+        .is_some_and(|character| character.is_ascii_lowercase())
+        && characters.all(|character| {
+            character.is_ascii_lowercase() || character.is_ascii_digit() || character == '_'
+        });
+    is_value_name.then_some(name)
+}
+
+/// All the fixes for the module: removing its unused imports, replacing uses
+/// of deprecated values, and the fixes of the lints enabled by the
+/// configuration.
+pub fn module_fixes(
+    module: &TypedModule,
+    interfaces: &im::HashMap<EcoString, type_::ModuleInterface>,
+    lints: &LintsConfig,
+) -> Vec<LintFix> {
+    remove_unused_imports(module)
+        .into_iter()
+        .chain(replace_deprecated_values(module, interfaces))
+        .chain(
+            crate::lint::check_module(lints, module)
+                .into_iter()
+                .filter_map(|warning| warning.fix),
+        )
+        .collect()
+}
+
+/// The source code of a module with fixes applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixed {
+    pub src: String,
+    /// The titles of the fixes that were applied.
+    pub applied: Vec<EcoString>,
+    /// The number of fixes that were not applied as they change code already
+    /// changed by another fix. They can be applied by fixing the result again.
+    pub skipped: usize,
+}
+
+/// Applies the fixes to the source code they were found in, in order. A fix
+/// that overlaps one that has already been applied is skipped.
+pub fn apply(src: &str, fixes: &[LintFix]) -> Fixed {
+    let mut applied = vec![];
+    let mut skipped = 0;
+    let mut edits: Vec<(&LintEdit, String)> = vec![];
+
+    for fix in fixes {
+        let overlaps = fix.edits.iter().any(|edit| {
+            edits.iter().any(|(other, _)| {
+                edit.location.start < other.location.end && other.location.start < edit.location.end
+            })
+        });
+        if overlaps {
+            skipped += 1;
+            continue;
+        }
+        // The replacements are made from the original source code, so the
+        // text of each edit is worked out before any are applied.
+        edits.extend(fix.edits.iter().map(|edit| (edit, edit.new_text(src))));
+        applied.push(fix.title.clone());
+    }
+
+    edits.sort_by_key(|(edit, _)| edit.location.start);
+    let mut fixed = String::with_capacity(src.len());
+    let mut position = 0;
+    for (edit, text) in edits {
+        let start = edit.location.start as usize;
+        fixed.push_str(src.get(position..start).unwrap_or_default());
+        fixed.push_str(&text);
+        position = edit.location.end as usize;
+    }
+    fixed.push_str(src.get(position..).unwrap_or_default());
+
+    Fixed {
+        src: fixed,
+        applied,
+        skipped,
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{
+    ast::SrcSpan,
+    config::{LintLevel, LintsConfig},
+    lint::{LintEdit, LintFix, Replacement},
+    type_::tests::compile_module,
+};
+
+const WIBBLE: &str = "pub fn wibble() { 1 }";
+
+const WOBBLE: &str = "pub fn wobble() { 2 }
+
+@deprecated(\"Use `wobble` instead\")
+pub fn old_wobble() { 2 }
+
+@deprecated(\"Use wobble.wobble instead.\")
+pub fn older_wobble() { 2 }
+
+@deprecated(\"Use wibble.wibble instead\")
+pub fn other_wobble() { 1 }
+
+@deprecated(\"Don't use this\")
+pub fn unfixable_wobble() { 2 }
+";
+
+fn fix(config: &LintsConfig, src: &str) -> String {
+    let module = compile_module(
+        "test_module",
+        src,
+        None,
+        vec![
+            ("thepackage", "wibble", WIBBLE),
+            ("thepackage", "wobble", WOBBLE),
+        ],
+    )
+    .expect("should compile");
+    let interfaces = im::hashmap! {
+        "wibble".into() => compile_module("wibble", WIBBLE, None, vec![]).expect("should compile").type_info,
+        "wobble".into() => compile_module("wobble", WOBBLE, None, vec![]).expect("should compile").type_info,
+    };
+    super::apply(src, &super::module_fixes(&module, &interfaces, config)).src
+}
+
+#[test]
+fn unused_imports_are_removed() {
+    let src = "import wibble
+import wobble
+
+pub fn main() {
+  wobble.wobble()
+}
+";
+    assert_eq!(
+        fix(&LintsConfig::default(), src),
+        "
+import wobble
+
+pub fn main() {
+  wobble.wobble()
+}
+"
+    );
+}
+
+#[test]
+fn lint_fixes_are_applied_when_the_lint_is_enabled() {
+    let src = "pub fn main(pair) {
+  case pair {
+    #(first, second) -> first + second
+  }
+}
+";
+    assert_eq!(fix(&LintsConfig::default(), src), src);
+
+    let config = LintsConfig::from([("single_clause_case", LintLevel::Warn)]);
+    assert_eq!(
+        fix(&config, src),
+        "pub fn main(pair) {
+  let #(first, second) = pair
+  first + second
+}
+"
+    );
+}
+
+#[test]
+fn deprecated_values_are_replaced() {
+    let src = "import wobble as w
+
+pub fn main() {
+  #(w.old_wobble(), w.older_wobble, w.other_wobble(), w.unfixable_wobble())
+}
+";
+    assert_eq!(
+        fix(&LintsConfig::default(), src),
+        "import wobble as w
+
+pub fn main() {
+  #(w.wobble(), w.wobble, w.other_wobble(), w.unfixable_wobble())
+}
+"
+    );
+}
+
+#[test]
+fn deprecation_replacements() {
+    assert_eq!(
+        super::deprecation_replacement("Use `wobble` instead", "wibble"),
+        Some("wobble")
+    );
+    assert_eq!(
+        super::deprecation_replacement("Use list.map instead.", "gleam/list"),
+        Some("map")
+    );
+    assert_eq!(
+        super::deprecation_replacement("Use string.map instead", "gleam/list"),
+        None
+    );
+    assert_eq!(
+        super::deprecation_replacement("Use Wobble instead", "wibble"),
+        None
+    );
+    assert_eq!(
+        super::deprecation_replacement("Use `wobble(1)` instead", "wibble"),
+        None
+    );
+}
+
+fn replace(title: &str, start: u32, end: u32, text: &str) -> LintFix {
+    LintFix {
+        title: title.into(),
+        edits: vec![LintEdit {
+            location: SrcSpan::new(start, end),
+            replacement: vec![Replacement::Text(text.into())],
+        }],
+    }
+}
+
+#[test]
+fn overlapping_fixes_are_skipped() {
+    let fixed = super::apply(
+        "one two three",
+        &[
+            replace("first", 4, 7, "2"),
+            replace("overlapping", 0, 5, "1"),
+            replace("last", 8, 13, "3"),
+        ],
+    );
+    assert_eq!(fixed.src, "one 2 3");
+    assert_eq!(fixed.applied, vec!["first", "last"]);
+    assert_eq!(fixed.skipped, 1);
+}
@@ -1,5 +1,12 @@
+mod cursor;
+mod range;
 #[cfg(test)]
 mod tests;
+mod verify;
+
+pub use cursor::formatted_cursor_position;
+pub use range::{apply_edits, pretty_range, FormatEdit};
+pub use verify::pretty_verified;
 
 use crate::{
     ast::{
@@ -24,19 +31,94 @@ use camino::Utf8Path;
 
 const INDENT: isize = 2;
 
+/// The width the formatter tries to keep lines within when the project
+/// doesn't configure one.
+pub const DEFAULT_LINE_WIDTH: isize = 80;
+
+/// Settings for the formatter, configured in the `[format]` section of
+/// `gleam.toml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// The width the formatter tries to keep lines within.
+    pub line_width: isize,
+    /// The number of spaces each level of indentation is made of.
+    pub indent_width: isize,
+    /// The most consecutive blank lines kept between the statements of a
+    /// function body or block.
+    pub max_blank_lines_in_functions: u32,
+    /// The most consecutive blank lines kept between definitions. There is
+    /// always at least one.
+    pub max_blank_lines_between_definitions: u32,
+    /// Whether the line breaks written in chains of `|>`, `<>`, `&&` and `||`
+    /// are kept. Operators written on the same line as the code before them
+    /// are still laid out by the formatter.
+    pub keep_operator_line_breaks: bool,
+    /// Whether the digits of hexadecimal, octal, and binary integers are
+    /// separated into groups with underscores, as they are for decimal
+    /// integers.
+    pub group_non_decimal_digits: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            line_width: DEFAULT_LINE_WIDTH,
+            indent_width: INDENT,
+            max_blank_lines_in_functions: 1,
+            max_blank_lines_between_definitions: 1,
+            keep_operator_line_breaks: false,
+            group_non_decimal_digits: false,
+        }
+    }
+}
+
 pub fn pretty(writer: &mut impl Utf8Writer, src: &EcoString, path: &Utf8Path) -> Result<()> {
+    pretty_with_options(writer, src, path, FormatOptions::default())
+}
+
+pub fn pretty_with_options(
+    writer: &mut impl Utf8Writer,
+    src: &EcoString,
+    path: &Utf8Path,
+    options: FormatOptions,
+) -> Result<()> {
     let parsed = crate::parse::parse_module(src).map_err(|error| Error::Parse {
         path: path.to_path_buf(),
         src: src.clone(),
         error,
     })?;
     let intermediate = Intermediate::from_extra(&parsed.extra, src);
-    Formatter::with_comments(&intermediate)
-        .module(&parsed.module)
-        .pretty_print(80, writer)
+    let document = Formatter::with_options(&intermediate, options).module(&parsed.module);
+    let document = if options.indent_width == INDENT {
+        document
+    } else {
+        document.scale_nesting(INDENT, options.indent_width)
+    };
+    document.pretty_print(options.line_width, writer)
+}
+
+/// Formats statements parsed from part of the source of a module on their
+/// own, such as when code is rewritten by a language server code action. The
+/// locations of the statements and of the comments and empty lines in `extra`
+/// are relative to the start of `src`.
+pub(crate) fn statements(
+    statements: &Vec1<UntypedStatement>,
+    src: &EcoString,
+    extra: &ModuleExtra,
+    options: FormatOptions,
+) -> String {
+    let intermediate = Intermediate::from_extra(extra, src);
+    let document = Formatter::with_options(&intermediate, options).statements(statements);
+    let document = if options.indent_width == INDENT {
+        document
+    } else {
+        document.scale_nesting(INDENT, options.indent_width)
+    };
+    document.to_pretty_string(options.line_width)
 }
 
 pub(crate) struct Intermediate<'a> {
+    src: &'a str,
     comments: Vec<Comment<'a>>,
     doc_comments: Vec<Comment<'a>>,
     module_comments: Vec<Comment<'a>>,
@@ -46,6 +128,7 @@ pub(crate) struct Intermediate<'a> {
 impl<'a> Intermediate<'a> {
     pub fn from_extra(extra: &'a ModuleExtra, src: &'a EcoString) -> Intermediate<'a> {
         Intermediate {
+            src,
             comments: extra
                 .comments
                 .iter()
@@ -73,6 +156,8 @@ pub struct Formatter<'a> {
     doc_comments: &'a [Comment<'a>],
     module_comments: &'a [Comment<'a>],
     empty_lines: &'a [u32],
+    src: &'a str,
+    options: FormatOptions,
 }
 
 impl<'comments> Formatter<'comments> {
@@ -80,12 +165,17 @@ impl<'comments> Formatter<'comments> {
         Default::default()
     }
 
-    pub(crate) fn with_comments(extra: &'comments Intermediate<'comments>) -> Self {
+    pub(crate) fn with_options(
+        extra: &'comments Intermediate<'comments>,
+        options: FormatOptions,
+    ) -> Self {
         Self {
             comments: &extra.comments,
             doc_comments: &extra.doc_comments,
             module_comments: &extra.module_comments,
             empty_lines: extra.empty_lines,
+            src: extra.src,
+            options,
         }
     }
 
@@ -119,6 +209,25 @@ impl<'comments> Formatter<'comments> {
         popped
     }
 
+    /// Remove the comment following the code ending at `end` if it is on the
+    /// same line, returning it to be printed after that code. Only the comma
+    /// separating the code from what comes next can be between them.
+    fn pop_trailing_comment<'a>(&mut self, end: u32) -> Option<Document<'a>> {
+        let (comment, rest) = self.comments.split_first()?;
+        let between = self
+            .src
+            .get(end as usize..comment.start as usize)?
+            .strip_suffix("//")?;
+        if !between.chars().all(|c| c == ',' || c == ' ' || c == '\t') {
+            return None;
+        }
+        self.comments = rest;
+        Some(docvec![
+            " //",
+            Document::String(comment.content.to_string())
+        ])
+    }
+
     /// Remove between 0 and `limit` empty lines following the current position,
     /// returning true if any empty lines were removed.
     fn pop_empty_lines(&mut self, limit: u32) -> bool {
@@ -137,6 +246,37 @@ impl<'comments> Formatter<'comments> {
         end != 0
     }
 
+    /// Remove the empty lines before `limit` like `pop_empty_lines`, returning
+    /// how many blank lines there were in the last run of them, up to `max`.
+    fn pop_blank_lines(&mut self, limit: u32, max: u32) -> u32 {
+        let last = self
+            .empty_lines
+            .iter()
+            .take_while(|position| **position <= limit)
+            .last()
+            .copied();
+        let _ = self.pop_empty_lines(limit);
+        match last {
+            // The empty line token starts one character into the whitespace.
+            Some(position) => self.blank_lines_from(position.saturating_sub(1)).min(max),
+            None => 0,
+        }
+    }
+
+    /// The number of blank lines in the whitespace starting at the given
+    /// position of the source, where `\r\n` and a lone `\r` both end a line.
+    fn blank_lines_from(&self, position: u32) -> u32 {
+        let rest = self.src.get(position as usize..).unwrap_or_default();
+        let whitespace = rest
+            .get(..rest.len() - rest.trim_start().len())
+            .unwrap_or_default();
+        let newlines = whitespace
+            .replace("\r\n", "\n")
+            .matches(['\n', '\r'])
+            .count();
+        newlines.saturating_sub(1) as u32
+    }
+
     fn targeted_definition<'a>(&mut self, definition: &'a TargetedDefinition) -> Document<'a> {
         let target = definition.target;
         let definition = &definition.definition;
@@ -154,6 +294,7 @@ impl<'comments> Formatter<'comments> {
     pub(crate) fn module<'a>(&mut self, module: &'a UntypedModule) -> Document<'a> {
         let mut documents = vec![];
         let mut previous_was_a_definition = false;
+        let mut previous_end = 0;
 
         // Here we take consecutive groups of imports so that they can be sorted
         // alphabetically.
@@ -166,13 +307,23 @@ impl<'comments> Formatter<'comments> {
                 if previous_was_a_definition {
                     documents.push(lines(2));
                 }
-                documents.append(&mut self.imports(definitions.collect_vec()));
+                let imports = definitions.collect_vec();
+                previous_end = imports
+                    .iter()
+                    .map(|i| range::definition_end(i))
+                    .max()
+                    .unwrap_or(0);
+                documents.append(&mut self.imports(imports));
                 previous_was_a_definition = false;
             } else {
                 for definition in definitions {
                     if !documents.is_empty() {
-                        documents.push(lines(2));
+                        let blank_lines = self
+                            .blank_lines_from(previous_end)
+                            .clamp(1, self.options.max_blank_lines_between_definitions.max(1));
+                        documents.push(lines(blank_lines as usize + 1));
                     }
+                    previous_end = range::definition_end(definition);
                     documents.push(self.targeted_definition(definition));
                 }
                 previous_was_a_definition = true;
@@ -312,7 +463,9 @@ impl<'comments> Formatter<'comments> {
 
     fn definition<'a>(&mut self, statement: &'a UntypedDefinition) -> Document<'a> {
         match statement {
-            Definition::Function(function) => self.statement_fn(function),
+            Definition::Function(function) => self
+                .since_attr(&function.since)
+                .append(self.statement_fn(function)),
 
             Definition::TypeAlias(TypeAlias {
                 alias,
@@ -320,10 +473,17 @@ impl<'comments> Formatter<'comments> {
                 type_ast: resolved_type,
                 publicity,
                 deprecation,
+                since,
                 ..
-            }) => self.type_alias(*publicity, alias, args, resolved_type, deprecation),
+            }) => self.since_attr(since).append(self.type_alias(
+                *publicity,
+                alias,
+                args,
+                resolved_type,
+                deprecation,
+            )),
 
-            Definition::CustomType(ct) => self.custom_type(ct),
+            Definition::CustomType(ct) => self.since_attr(&ct.since).append(self.custom_type(ct)),
 
             Definition::Import(Import {
                 module,
@@ -381,9 +541,14 @@ impl<'comments> Formatter<'comments> {
                 name,
                 annotation,
                 value,
+                since,
                 ..
             }) => {
-                let head = pub_(*publicity).append("const ").append(name.as_str());
+                let head = self
+                    .since_attr(since)
+                    .append(pub_(*publicity))
+                    .append("const ")
+                    .append(name.as_str());
                 let head = match annotation {
                     None => head,
                     Some(t) => head.append(": ").append(self.type_ast(t)),
@@ -615,6 +780,13 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
+    fn since_attr<'a>(&mut self, since: &'a Option<EcoString>) -> Document<'a> {
+        match since {
+            None => nil(),
+            Some(version) => docvec!["@since(\"", version, "\")", line()],
+        }
+    }
+
     fn fn_arg<'a, A>(&mut self, arg: &'a Arg<A>) -> Document<'a> {
         let comments = self.pop_comments(arg.location.start);
         let doc = match &arg.annotation {
@@ -629,6 +801,13 @@ impl<'comments> Formatter<'comments> {
         // @deprecated attribute
         let attributes = self.deprecation_attr(&function.deprecation);
 
+        // @must_use attribute
+        let attributes = if function.must_use {
+            attributes.append("@must_use").append(line())
+        } else {
+            attributes
+        };
+
         // @external attribute
         let external = |t: &'static str, m: &'a str, f: &'a str| {
             docvec!["@external(", t, ", \"", m, "\", \"", f, "\")", line()]
@@ -718,11 +897,12 @@ impl<'comments> Formatter<'comments> {
         let count = statements.len();
         let mut documents = Vec::with_capacity(count * 2);
         for (i, statement) in statements.iter().enumerate() {
-            let preceding_newline = self.pop_empty_lines(previous_position + 1);
-            if i != 0 && preceding_newline {
-                documents.push(lines(2));
-            } else if i != 0 {
-                documents.push(lines(1));
+            let blank_lines = self.pop_blank_lines(
+                previous_position + 1,
+                self.options.max_blank_lines_in_functions,
+            );
+            if i != 0 {
+                documents.push(lines(blank_lines as usize + 1));
             }
             previous_position = statement.location().end;
             documents.push(self.statement(statement).group());
@@ -770,6 +950,7 @@ impl<'comments> Formatter<'comments> {
 
         let document = match expr {
             UntypedExpr::Placeholder { .. } => panic!("Placeholders should not be formatted"),
+            UntypedExpr::Invalid { .. } => panic!("Invalid expressions should not be formatted"),
 
             UntypedExpr::Panic {
                 message: Some(m), ..
@@ -783,6 +964,15 @@ impl<'comments> Formatter<'comments> {
                 message: Some(l), ..
             } => docvec!["todo as ", self.expr(l)],
 
+            UntypedExpr::Echo {
+                expression: Some(expression),
+                ..
+            } => docvec!["echo ", self.expr(expression)],
+
+            UntypedExpr::Echo {
+                expression: None, ..
+            } => "echo".to_doc(),
+
             UntypedExpr::PipeLine { expressions, .. } => self.pipeline(expressions, false),
 
             UntypedExpr::Int { value, .. } => self.int(value),
@@ -936,8 +1126,18 @@ impl<'comments> Formatter<'comments> {
     }
 
     fn int<'a>(&self, value: &'a str) -> Document<'a> {
-        if value.starts_with("0x") || value.starts_with("0b") || value.starts_with("0o") {
-            return value.to_doc();
+        let base = value.get(..2).and_then(|prefix| match prefix {
+            "0x" | "0b" => Some(4),
+            "0o" => Some(3),
+            _ => None,
+        });
+        if let Some(group_size) = base {
+            if !self.options.group_non_decimal_digits {
+                return value.to_doc();
+            }
+            let (prefix, digits) = value.split_at(2);
+            let digits = group_digits(digits, group_size);
+            return EcoString::from(format!("{prefix}{digits}")).to_doc();
         }
 
         self.underscore_integer_string(value)
@@ -1023,6 +1223,7 @@ impl<'comments> Formatter<'comments> {
     ) -> Document<'a> {
         let expr = match fun {
             UntypedExpr::Placeholder { .. } => panic!("Placeholders should not be formatted"),
+            UntypedExpr::Invalid { .. } => panic!("Invalid expressions should not be formatted"),
 
             UntypedExpr::PipeLine { .. } => break_block(self.expr(fun)),
 
@@ -1041,6 +1242,7 @@ impl<'comments> Formatter<'comments> {
             | UntypedExpr::TupleIndex { .. }
             | UntypedExpr::Todo { .. }
             | UntypedExpr::Panic { .. }
+            | UntypedExpr::Echo { .. }
             | UntypedExpr::BitArray { .. }
             | UntypedExpr::RecordUpdate { .. }
             | UntypedExpr::NegateBool { .. }
@@ -1174,21 +1376,52 @@ impl<'comments> Formatter<'comments> {
         right: &'a UntypedExpr,
         nest_steps: bool,
     ) -> Document<'a> {
-        let left_side = self.bin_op_side(name, left, nest_steps);
+        let (doc, kept_line_break) = self.bin_op_chain(name, left, right, nest_steps);
+        if kept_line_break {
+            // The chain is only broken where a line break was kept, but the
+            // code around it is laid out as if the whole chain was broken.
+            doc.group().force_break()
+        } else {
+            doc
+        }
+    }
+
+    /// Pretty prints a chain of binary operations that can be grouped
+    /// together, also returning whether a line break written in the chain
+    /// has been kept.
+    fn bin_op_chain<'a>(
+        &mut self,
+        name: &'a BinOp,
+        left: &'a UntypedExpr,
+        right: &'a UntypedExpr,
+        nest_steps: bool,
+    ) -> (Document<'a>, bool) {
+        let (left_side, left_kept_line_break) = self.bin_op_side(name, left, nest_steps);
 
         let comments = self.pop_comments(right.start_byte_index());
-        let name_doc = break_("", " ").append(commented(name.to_doc(), comments));
+        let kept_line_break = matches!(name, BinOp::And | BinOp::Or | BinOp::Concatenate)
+            && self.kept_line_break(left.location().end, right.start_byte_index());
+        let operator_break = if kept_line_break {
+            line()
+        } else {
+            break_("", " ")
+        };
+        let name_doc = operator_break.append(commented(name.to_doc(), comments));
 
-        let right_side = self.bin_op_side(name, right, nest_steps);
+        let (right_side, right_kept_line_break) = self.bin_op_side(name, right, nest_steps);
 
-        left_side
+        let doc = left_side
             .append(if nest_steps {
                 name_doc.nest(INDENT)
             } else {
                 name_doc
             })
             .append(" ")
-            .append(right_side)
+            .append(right_side);
+        (
+            doc,
+            left_kept_line_break || kept_line_break || right_kept_line_break,
+        )
     }
 
     fn bin_op_side<'a>(
@@ -1196,30 +1429,37 @@ impl<'comments> Formatter<'comments> {
         operator: &'a BinOp,
         side: &'a UntypedExpr,
         nest_steps: bool,
-    ) -> Document<'a> {
-        let side_doc = match side {
-            UntypedExpr::String { value, .. } => self.bin_op_string(value),
-            UntypedExpr::BinOp {
-                name, left, right, ..
-            } => self.bin_op(name, left, right, nest_steps),
-            _ => self.expr(side),
-        };
-        match side.bin_op_name() {
+    ) -> (Document<'a>, bool) {
+        match side {
             // In case the other side is a binary operation as well and it can
             // be grouped together with the current binary operation, the two
             // docs are simply concatenated, so that they will end up in the
             // same group and the formatter will try to keep those on a single
             // line.
-            Some(side_name) if side_name.can_be_grouped_with(operator) => side_doc,
+            UntypedExpr::BinOp {
+                name, left, right, ..
+            } if name.can_be_grouped_with(operator) => {
+                self.bin_op_chain(name, left, right, nest_steps)
+            }
             // In case the binary operations cannot be grouped together the
             // other side is treated as a group on its own so that it can be
             // broken independently of other pieces of the binary operations
             // chain.
-            _ => self.operator_side(
-                side_doc.group(),
-                operator.precedence(),
-                side.bin_op_precedence(),
-            ),
+            _ => {
+                let side_doc = match side {
+                    UntypedExpr::String { value, .. } => self.bin_op_string(value),
+                    UntypedExpr::BinOp {
+                        name, left, right, ..
+                    } => self.bin_op(name, left, right, nest_steps),
+                    _ => self.expr(side),
+                };
+                let doc = self.operator_side(
+                    side_doc.group(),
+                    operator.precedence(),
+                    side.bin_op_precedence(),
+                );
+                (doc, false)
+            }
         }
     }
 
@@ -1242,7 +1482,18 @@ impl<'comments> Formatter<'comments> {
         let first = self.expr(first).group();
         docs.push(self.operator_side(first, 5, first_precedence));
 
+        let keep_line_breaks = self.options.keep_operator_line_breaks;
+        let mut previous_end = expressions.first().location().end;
+        let mut kept_any_line_break = false;
         for expr in expressions.iter().skip(1) {
+            let kept_line_break = self.kept_line_break(previous_end, expr.location().start);
+            kept_any_line_break |= kept_line_break;
+            let pipe_break = if !keep_line_breaks || kept_line_break {
+                line()
+            } else {
+                break_("", " ")
+            };
+            previous_end = expr.location().end;
             let comments = self.pop_comments(expr.location().start);
             let doc = match expr {
                 UntypedExpr::Fn {
@@ -1262,13 +1513,30 @@ impl<'comments> Formatter<'comments> {
                 _ => self.expr(expr),
             };
 
-            let pipe = line().append(commented("|> ".to_doc(), comments));
+            let pipe = pipe_break.append(commented("|> ".to_doc(), comments));
             let pipe = if nest_pipe { pipe.nest(INDENT) } else { pipe };
             docs.push(pipe);
             docs.push(self.operator_side(doc, 4, expr.bin_op_precedence()));
         }
 
-        docs.to_doc().force_break()
+        if keep_line_breaks && !kept_any_line_break {
+            docs.to_doc().group()
+        } else if keep_line_breaks {
+            docs.to_doc().group().force_break()
+        } else {
+            docs.to_doc().force_break()
+        }
+    }
+
+    /// Whether there is a line break between the two positions of the source
+    /// that should be kept, as the formatter has been told to keep the line
+    /// breaks in operator chains.
+    fn kept_line_break(&self, start: u32, end: u32) -> bool {
+        self.options.keep_operator_line_breaks
+            && self
+                .src
+                .get(start as usize..end as usize)
+                .is_some_and(|between| between.contains(['\n', '\r']))
     }
 
     fn pipe_capture_right_hand_side<'a>(&mut self, fun: &'a UntypedExpr) -> Document<'a> {
@@ -1353,25 +1621,32 @@ impl<'comments> Formatter<'comments> {
                 .name
                 .as_str()
                 .to_doc()
-                .append(wrap_args(constructor.arguments.iter().map(
-                    |RecordConstructorArg {
-                         label,
-                         ast,
-                         location,
-                         ..
-                     }| {
-                        let arg_comments = self.pop_comments(location.start);
-                        let arg = match label {
-                            Some(l) => l.to_doc().append(": ").append(self.type_ast(ast)),
-                            None => self.type_ast(ast),
-                        };
-
-                        commented(
-                            self.doc_comments(location.start).append(arg).group(),
-                            arg_comments,
+                .append(wrap_commented_args(
+                    constructor
+                        .arguments
+                        .iter()
+                        .map(
+                            |RecordConstructorArg {
+                                 label,
+                                 ast,
+                                 location,
+                                 ..
+                             }| {
+                                let arg_comments = self.pop_comments(location.start);
+                                let arg = match label {
+                                    Some(l) => l.to_doc().append(": ").append(self.type_ast(ast)),
+                                    None => self.type_ast(ast),
+                                };
+
+                                let arg = commented(
+                                    self.doc_comments(location.start).append(arg).group(),
+                                    arg_comments,
+                                );
+                                (arg, self.pop_trailing_comment(ast.location().end))
+                            },
                         )
-                    },
-                )))
+                        .collect_vec(),
+                ))
                 .group()
         };
 
@@ -1446,13 +1721,12 @@ impl<'comments> Formatter<'comments> {
         name: &'a str,
         args: &'a [TypedArg],
         return_type: Arc<Type>,
+        printer: &mut type_::pretty::Printer,
     ) -> Document<'a> {
-        let mut printer = type_::pretty::Printer::new();
-
         pub_(publicity)
             .append("fn ")
             .append(name)
-            .append(self.docs_fn_args(args, &mut printer))
+            .append(self.docs_fn_args(args, printer))
             .append(" -> ".to_doc())
             .append(printer.print(&return_type))
     }
@@ -1485,17 +1759,25 @@ impl<'comments> Formatter<'comments> {
 
     fn call_arg<'a>(&mut self, arg: &'a CallArg<UntypedExpr>, arity: usize) -> Document<'a> {
         match &arg.label {
+            Some(s) if arg.label_shorthand => commented(
+                s.to_doc().append(":"),
+                self.pop_comments(arg.location.start),
+            ),
             Some(s) => commented(
                 s.to_doc().append(": "),
                 self.pop_comments(arg.location.start),
-            ),
-            None => nil(),
+            )
+            .append(self.comma_separated_item(&arg.value, arity)),
+            None => self.comma_separated_item(&arg.value, arity),
         }
-        .append(self.comma_separated_item(&arg.value, arity))
     }
 
     fn record_update_arg<'a>(&mut self, arg: &'a UntypedRecordUpdateArg) -> Document<'a> {
         let comments = self.pop_comments(arg.location.start);
+        if arg.label_shorthand {
+            return commented(arg.label.as_str().to_doc().append(":"), comments);
+        }
+
         let doc = arg
             .label
             .as_str()
@@ -1598,6 +1880,7 @@ impl<'comments> Formatter<'comments> {
         .group()
         .append("->")
         .append(self.case_clause_value(&clause.then))
+        .append(self.pop_trailing_comment(clause.location.end))
     }
 
     fn alternative_patterns<'a>(&mut self, clause: &'a UntypedClause) -> Document<'a> {
@@ -1705,13 +1988,15 @@ impl<'comments> Formatter<'comments> {
                 None => 0,
             };
 
-        let elements = join(
-            elements
-                .iter()
-                .map(|e| self.comma_separated_item(e, list_size)),
-            comma,
-        )
-        .next_break_fits(NextBreakFitsMode::Disabled);
+        let elements = elements
+            .iter()
+            .map(|e| {
+                let element = self.comma_separated_item(e, list_size);
+                (element, self.pop_trailing_comment(e.location().end))
+            })
+            .collect_vec();
+        let (elements, last_comment) = join_commented(elements, comma);
+        let elements = elements.next_break_fits(NextBreakFitsMode::Disabled);
 
         let doc = break_("[", "[").append(elements);
         // We need to keep the last break aside and do not add it immediately
@@ -1719,15 +2004,20 @@ impl<'comments> Formatter<'comments> {
         // bracket we want to add indentation (to just that break). Otherwise,
         // the final comment would be less indented than list's elements.
         let (doc, last_break) = match tail {
-            None => (doc.nest(INDENT), break_(",", "")),
+            None => (doc.nest(INDENT), commented_break(",", "", last_comment)),
 
             Some(tail) => {
                 let comments = self.pop_comments(tail.location().start);
+                let tail_end = tail.location().end;
                 let tail = commented(docvec!["..", self.expr(tail)], comments);
-                (
-                    doc.append(break_(",", ", ")).append(tail).nest(INDENT),
-                    break_("", ""),
-                )
+                let doc = doc
+                    .append(commented_break(",", ", ", last_comment))
+                    .append(tail)
+                    .nest(INDENT);
+                match self.pop_trailing_comment(tail_end) {
+                    Some(comment) => (doc.force_break(), commented_break("", "", Some(comment))),
+                    None => (doc, break_("", "")),
+                }
             }
         };
 
@@ -1792,6 +2082,8 @@ impl<'comments> Formatter<'comments> {
 
             Pattern::VarUsage { name, .. } => name.to_doc(),
 
+            Pattern::Constant { module, name, .. } => docvec![module, ".", name],
+
             Pattern::Assign { name, pattern, .. } => {
                 self.pattern(pattern).append(" as ").append(name.as_str())
             }
@@ -1879,11 +2171,14 @@ impl<'comments> Formatter<'comments> {
     }
 
     fn pattern_call_arg<'a>(&mut self, arg: &'a CallArg<UntypedPattern>) -> Document<'a> {
-        arg.label
-            .as_ref()
-            .map(|s| s.to_doc().append(": "))
-            .unwrap_or_else(nil)
-            .append(self.pattern(&arg.value))
+        match &arg.label {
+            Some(s) if arg.label_shorthand => commented(
+                s.to_doc().append(":"),
+                self.pop_comments(arg.location.start),
+            ),
+            Some(s) => s.to_doc().append(": ").append(self.pattern(&arg.value)),
+            None => self.pattern(&arg.value),
+        }
     }
 
     pub fn clause_guard_bin_op<'a>(
@@ -1993,6 +2288,7 @@ impl<'comments> Formatter<'comments> {
     fn constant_call_arg<'a, A, B>(&mut self, arg: &'a CallArg<Constant<A, B>>) -> Document<'a> {
         match &arg.label {
             None => self.const_expr(&arg.value),
+            Some(s) if arg.label_shorthand => s.to_doc().append(":"),
             Some(s) => s.to_doc().append(": ").append(self.const_expr(&arg.value)),
         }
     }
@@ -2089,6 +2385,7 @@ impl<'comments> Formatter<'comments> {
     fn bit_array_segment_expr<'a>(&mut self, expr: &'a UntypedExpr) -> Document<'a> {
         match expr {
             UntypedExpr::Placeholder { .. } => panic!("Placeholders should not be formatted"),
+            UntypedExpr::Invalid { .. } => panic!("Invalid expressions should not be formatted"),
 
             UntypedExpr::BinOp { .. } => wrap_block(self.expr(expr)),
 
@@ -2106,6 +2403,7 @@ impl<'comments> Formatter<'comments> {
             | UntypedExpr::TupleIndex { .. }
             | UntypedExpr::Todo { .. }
             | UntypedExpr::Panic { .. }
+            | UntypedExpr::Echo { .. }
             | UntypedExpr::BitArray { .. }
             | UntypedExpr::RecordUpdate { .. }
             | UntypedExpr::NegateBool { .. }
@@ -2280,6 +2578,82 @@ where
         .append(")")
 }
 
+/// Separates digits into groups of the given size with underscores, counting
+/// from the right. Digits that fit in a single group are left ungrouped.
+fn group_digits(digits: &str, group_size: usize) -> String {
+    let digits = digits.chars().filter(|c| *c != '_').collect_vec();
+    if digits.len() <= group_size {
+        return digits.into_iter().collect();
+    }
+    let first_group_size = match digits.len() % group_size {
+        0 => group_size,
+        size => size,
+    };
+    let (first, rest) = digits.split_at(first_group_size);
+    std::iter::once(first)
+        .chain(rest.chunks(group_size))
+        .map(|group| group.iter().collect::<String>())
+        .join("_")
+}
+
+/// Like `wrap_args`, but each argument comes with the comment that followed
+/// it on the same line, if there was one.
+fn wrap_commented_args<'a>(args: Vec<(Document<'a>, Option<Document<'a>>)>) -> Document<'a> {
+    if args.is_empty() {
+        return "()".to_doc();
+    }
+    let (args, last_comment) = join_commented(args, break_(",", ", "));
+    break_("(", "(")
+        .append(args.next_break_fits(NextBreakFitsMode::Disabled))
+        .nest(INDENT)
+        .append(commented_break(",", "", last_comment))
+        .append(")")
+}
+
+/// Joins comma separated items, each with the comment that followed it on
+/// the same line, if there was one. The comment of an item is printed after
+/// the separator following it, except for the last item whose comment is
+/// returned for the caller to print after the final separator.
+///
+/// As a comment runs to the end of its line the items are always broken
+/// onto multiple lines if any of them has one.
+fn join_commented<'a>(
+    items: Vec<(Document<'a>, Option<Document<'a>>)>,
+    separator: Document<'a>,
+) -> (Document<'a>, Option<Document<'a>>) {
+    let mut docs = Vec::with_capacity(items.len() * 2);
+    let mut last_comment = None;
+    let mut any_comments = false;
+    for (item, comment) in items {
+        if let Some(previous_comment) = last_comment.take() {
+            docs.push(commented_break(",", ", ", Some(previous_comment)));
+        } else if !docs.is_empty() {
+            docs.push(separator.clone());
+        }
+        docs.push(item);
+        any_comments |= comment.is_some();
+        last_comment = comment;
+    }
+    if any_comments {
+        (concat(docs).force_break(), last_comment)
+    } else {
+        (concat(docs), last_comment)
+    }
+}
+
+/// A break that is followed by a comment when there is one, in which case
+/// it is always broken.
+fn commented_break<'a>(
+    broken: &'a str,
+    unbroken: &'a str,
+    comment: Option<Document<'a>>,
+) -> Document<'a> {
+    match comment {
+        Some(comment) => docvec![broken, comment, line()],
+        None => break_(broken, unbroken),
+    }
+}
+
 pub fn wrap_args_with_spread<'a, I>(args: I) -> Document<'a>
 where
     I: IntoIterator<Item = Document<'a>>,
@@ -0,0 +1,58 @@
+/// Works out where a cursor at the given byte offset of the source ends up
+/// once the source has been formatted, so editors can keep the cursor in the
+/// same place in the code.
+///
+/// Formatting mostly changes whitespace, so the non-whitespace characters of
+/// the two sources are matched up, allowing for the odd character such as a
+/// trailing comma being added or removed.
+pub fn formatted_cursor_position(src: &str, formatted: &str, cursor: u32) -> u32 {
+    let cursor = (cursor as usize).min(src.len());
+    let before = src.get(..cursor).unwrap_or(src);
+    let after = src.get(cursor..).unwrap_or_default();
+
+    // A cursor in whitespace before some code stays before that code rather
+    // than after the code preceding it.
+    let stick_to_next = before.ends_with(char::is_whitespace) || before.is_empty();
+    let target = before.chars().filter(|c| !c.is_whitespace()).count();
+    if after.trim().is_empty() && stick_to_next {
+        return formatted.trim_end().len() as u32;
+    }
+
+    let old = non_whitespace(src);
+    let new = non_whitespace(formatted);
+    let (mut i, mut j) = (0, 0);
+
+    while i < target {
+        let (Some((_, old_char)), Some((_, new_char))) = (old.get(i), new.get(j)) else {
+            break;
+        };
+        if old_char == new_char {
+            i += 1;
+            j += 1;
+        } else if new.get(j + 1).is_some_and(|(_, c)| c == old_char) {
+            // A character was added by the formatter.
+            j += 1;
+        } else if old.get(i + 1).is_some_and(|(_, c)| c == new_char) {
+            // A character was removed by the formatter.
+            i += 1;
+        } else {
+            i += 1;
+            j += 1;
+        }
+    }
+
+    let position = if stick_to_next {
+        new.get(j).map(|(index, _)| *index)
+    } else {
+        j.checked_sub(1)
+            .and_then(|previous| new.get(previous))
+            .map(|(index, c)| index + c.len_utf8())
+    };
+    position.unwrap_or(formatted.len()) as u32
+}
+
+fn non_whitespace(src: &str) -> Vec<(usize, char)> {
+    src.char_indices()
+        .filter(|(_, c)| !c.is_whitespace())
+        .collect()
+}
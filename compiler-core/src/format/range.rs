@@ -0,0 +1,141 @@
+use camino::Utf8Path;
+use ecow::EcoString;
+use itertools::Itertools;
+
+use crate::{
+    ast::{Definition, SrcSpan, TargetedDefinition, UntypedModule},
+    Error, Result,
+};
+
+use super::FormatOptions;
+
+/// A part of a module's source to be replaced with its formatted version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatEdit {
+    pub location: SrcSpan,
+    pub new_text: String,
+}
+
+/// Formats only the top-level definitions of a module that overlap the given
+/// range of its source, returning the edits to make to the source.
+///
+/// Consecutive imports are sorted together by the formatter, so they are
+/// formatted together too.
+pub fn pretty_range(
+    src: &EcoString,
+    path: &Utf8Path,
+    options: FormatOptions,
+    range: SrcSpan,
+) -> Result<Vec<FormatEdit>> {
+    let parsed = crate::parse::parse_module(src).map_err(|error| Error::Parse {
+        path: path.to_path_buf(),
+        src: src.clone(),
+        error,
+    })?;
+    let mut formatted = String::new();
+    super::pretty_with_options(&mut formatted, src, path, options)?;
+    let formatted_module = crate::parse::parse_module(&formatted)
+        .expect("Formatted module should parse")
+        .module;
+
+    let chunks = definition_chunks(&parsed.module, src);
+    let formatted_chunks = definition_chunks(&formatted_module, &formatted);
+
+    // The formatter keeps definitions in the same order, but if the two
+    // modules somehow don't line up the whole module is replaced.
+    if chunks.len() != formatted_chunks.len() {
+        return Ok(if src.as_str() == formatted {
+            vec![]
+        } else {
+            vec![FormatEdit {
+                location: SrcSpan::new(0, src.len() as u32),
+                new_text: formatted,
+            }]
+        });
+    }
+
+    let edits = chunks
+        .into_iter()
+        .zip(formatted_chunks)
+        .filter(|(chunk, _)| overlaps(*chunk, range))
+        .filter_map(|(chunk, formatted_chunk)| {
+            let text = slice(src, chunk);
+            let new_text = slice(&formatted, formatted_chunk);
+            (text != new_text).then(|| FormatEdit {
+                location: chunk,
+                new_text: new_text.to_string(),
+            })
+        })
+        .collect();
+    Ok(edits)
+}
+
+/// Applies edits that don't overlap each other to the source.
+pub fn apply_edits(src: &str, edits: &[FormatEdit]) -> String {
+    let mut result = String::with_capacity(src.len());
+    let mut position = 0;
+    for edit in edits.iter().sorted_by_key(|edit| edit.location.start) {
+        result.push_str(slice(src, SrcSpan::new(position, edit.location.start)));
+        result.push_str(&edit.new_text);
+        position = edit.location.end;
+    }
+    result.push_str(slice(src, SrcSpan::new(position, src.len() as u32)));
+    result
+}
+
+/// The spans of source taken by each definition of the module along with the
+/// comments, doc comments, and attributes before it, with each group of
+/// consecutive imports taken as one.
+fn definition_chunks(module: &UntypedModule, src: &str) -> Vec<SrcSpan> {
+    let mut ends = vec![];
+    for (is_import_group, definitions) in &module
+        .definitions
+        .iter()
+        .group_by(|definition| definition.definition.is_import())
+    {
+        if is_import_group {
+            ends.extend(definitions.map(definition_end).max());
+        } else {
+            ends.extend(definitions.map(definition_end));
+        }
+    }
+
+    let mut previous_end = 0;
+    ends.into_iter()
+        .map(|end| {
+            let start = previous_end + leading_whitespace(src, previous_end);
+            previous_end = end;
+            SrcSpan::new(start, end)
+        })
+        .collect()
+}
+
+/// The end of the source of a definition. The `location` of some definitions
+/// only covers their head.
+pub(super) fn definition_end(definition: &TargetedDefinition) -> u32 {
+    match &definition.definition {
+        Definition::Function(function) => function.location.end.max(function.end_position),
+        Definition::CustomType(custom_type) => custom_type.full_location().end,
+        Definition::ModuleConstant(constant) => {
+            constant.location.end.max(constant.value.location().end)
+        }
+        Definition::TypeAlias(alias) => alias.location.end,
+        Definition::Import(import) => import.location.end,
+    }
+}
+
+/// Whether the chunk overlaps the range. An empty range overlaps the chunk it
+/// is in.
+fn overlaps(chunk: SrcSpan, range: SrcSpan) -> bool {
+    chunk.start < range.end.max(range.start + 1) && range.start < chunk.end
+}
+
+fn leading_whitespace(src: &str, start: u32) -> u32 {
+    let rest = src.get(start as usize..).unwrap_or_default();
+    (rest.len() - rest.trim_start().len()) as u32
+}
+
+fn slice(src: &str, span: SrcSpan) -> &str {
+    src.get(span.start as usize..span.end as usize)
+        .expect("Chunk should be in source")
+}
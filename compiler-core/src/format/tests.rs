@@ -1,19 +1,25 @@
 use pretty_assertions::assert_eq;
 
+use crate::format::FormatOptions;
+
 mod asignments;
 mod binary_operators;
 mod bit_array;
 mod blocks;
 mod cases;
 mod conditional_compilation;
+mod cursor;
 mod external_fn;
 mod external_types;
 mod function;
 mod guards;
 mod imports;
+mod label_shorthand;
+mod range;
 mod record_update;
 mod tuple;
 mod use_;
+mod verify;
 
 #[macro_export]
 macro_rules! assert_format {
@@ -25,6 +31,21 @@ macro_rules! assert_format {
     };
 }
 
+#[macro_export]
+macro_rules! assert_format_with_options {
+    ($options:expr, $src:expr $(,)?) => {
+        let mut writer = String::new();
+        $crate::format::pretty_with_options(
+            &mut writer,
+            &$src.into(),
+            camino::Utf8Path::new("<stdin>"),
+            $options,
+        )
+        .unwrap();
+        assert_eq!($src, writer);
+    };
+}
+
 #[macro_export]
 macro_rules! assert_format_rewrite {
     ($src:expr, $output:expr  $(,)?) => {
@@ -35,6 +56,21 @@ macro_rules! assert_format_rewrite {
     };
 }
 
+#[macro_export]
+macro_rules! assert_format_rewrite_with_options {
+    ($options:expr, $src:expr, $output:expr $(,)?) => {
+        let mut writer = String::new();
+        $crate::format::pretty_with_options(
+            &mut writer,
+            &$src.into(),
+            camino::Utf8Path::new("<stdin>"),
+            $options,
+        )
+        .unwrap();
+        assert_eq!(writer, $output);
+    };
+}
+
 #[test]
 fn imports() {
     assert_format!("\n");
@@ -3213,6 +3249,50 @@ fn expr_todo_as_value() {
     );
 }
 
+#[test]
+fn expr_echo() {
+    assert_format!(
+        r#"fn main() {
+  echo 1 + 2
+}
+"#
+    );
+}
+
+#[test]
+fn expr_echo_in_pipeline() {
+    assert_format!(
+        r#"fn main() {
+  [1, 2, 3]
+  |> echo
+  |> list.map(fn(x) { x + 1 })
+  |> echo
+}
+"#
+    );
+}
+
+#[test]
+fn expr_echo_of_pipeline() {
+    assert_format!(
+        r#"fn main() {
+  echo [1, 2, 3]
+  |> list.reverse
+}
+"#
+    );
+}
+
+#[test]
+fn expr_echo_in_block() {
+    assert_format!(
+        r#"fn main() {
+  { echo 1 } + 2
+}
+"#
+    );
+}
+
 #[test]
 fn expr_todo() {
     assert_format!(
@@ -5352,6 +5432,28 @@ fn comment_at_end_of_type() {
     );
 }
 
+#[test]
+fn must_use_function() {
+    assert_format!(
+        r#"@must_use
+pub fn new() -> Int {
+  1
+}
+"#
+    );
+}
+
+#[test]
+fn deprecated_must_use_external_function() {
+    assert_format!(
+        r#"@deprecated("Use new instead")
+@must_use
+@external(erlang, "wibble", "wobble")
+pub fn old() -> Int
+"#
+    );
+}
+
 #[test]
 fn deprecated_custom_type() {
     assert_format!(
@@ -5373,6 +5475,47 @@ pub type Tiger =
     );
 }
 
+#[test]
+fn since_function() {
+    assert_format!(
+        r#"@since("1.2.0")
+@deprecated("Use new instead")
+@external(erlang, "wibble", "wobble")
+pub fn old() -> Int
+"#
+    );
+}
+
+#[test]
+fn since_custom_type() {
+    assert_format!(
+        r#"@since("1.2.0")
+pub type One {
+  One
+}
+"#
+    );
+}
+
+#[test]
+fn since_type_alias() {
+    assert_format!(
+        r#"@since("1.2.0")
+pub type Tiger =
+  Nil
+"#
+    );
+}
+
+#[test]
+fn since_constant() {
+    assert_format!(
+        r#"@since("1.2.0")
+pub const one = 1
+"#
+    );
+}
+
 // https://github.com/gleam-lang/gleam/issues/2423
 #[test]
 fn prefix_as() {
@@ -5949,3 +6092,612 @@ pub const wibble = 1
 "#
     );
 }
+
+#[test]
+fn constant_patterns() {
+    assert_format!(
+        r#"import config
+
+pub fn main(x) {
+  case x {
+    config.max_size -> True
+    answer -> True
+    _ -> False
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn narrow_line_width() {
+    assert_format_with_options!(
+        FormatOptions {
+            line_width: 30,
+            ..Default::default()
+        },
+        r#"pub fn main() {
+  wibble(
+    "one",
+    "two",
+    "three",
+  )
+}
+"#
+    );
+}
+
+#[test]
+fn wide_line_width() {
+    assert_format_with_options!(
+        FormatOptions {
+            line_width: 120,
+            ..Default::default()
+        },
+        r#"pub fn main() {
+  wibble("one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven")
+}
+"#
+    );
+}
+
+#[test]
+fn blank_lines_in_functions_are_collapsed_by_default() {
+    assert_format_rewrite!(
+        "pub fn main() {
+  let x = 1
+
+
+
+  x
+}
+",
+        "pub fn main() {
+  let x = 1
+
+  x
+}
+"
+    );
+}
+
+#[test]
+fn more_blank_lines_in_functions() {
+    let options = FormatOptions {
+        max_blank_lines_in_functions: 2,
+        ..Default::default()
+    };
+    assert_format_with_options!(
+        options,
+        "pub fn main() {
+  let x = 1
+
+
+  let y = 2
+
+  x + y
+}
+"
+    );
+    assert_format_rewrite_with_options!(
+        options,
+        "pub fn main() {
+  let x = 1
+
+
+
+
+  x
+}
+",
+        "pub fn main() {
+  let x = 1
+
+
+  x
+}
+"
+    );
+}
+
+#[test]
+fn no_blank_lines_in_functions() {
+    assert_format_rewrite_with_options!(
+        FormatOptions {
+            max_blank_lines_in_functions: 0,
+            ..Default::default()
+        },
+        "pub fn main() {
+  let x = 1
+
+  x
+}
+",
+        "pub fn main() {
+  let x = 1
+  x
+}
+"
+    );
+}
+
+#[test]
+fn blank_lines_between_definitions_are_collapsed_by_default() {
+    assert_format_rewrite!(
+        "pub fn one() {
+  1
+}
+
+
+
+pub fn two() {
+  2
+}
+",
+        "pub fn one() {
+  1
+}
+
+pub fn two() {
+  2
+}
+"
+    );
+}
+
+#[test]
+fn more_blank_lines_between_definitions() {
+    let options = FormatOptions {
+        max_blank_lines_between_definitions: 2,
+        ..Default::default()
+    };
+    assert_format_with_options!(
+        options,
+        "import wibble
+
+
+pub fn one() {
+  1
+}
+
+pub fn two() {
+  2
+}
+
+
+// A comment
+pub const three = 3
+"
+    );
+    assert_format_rewrite_with_options!(
+        options,
+        "pub fn one() {
+  1
+}
+pub fn two() {
+  2
+}
+
+
+
+
+pub fn three() {
+  3
+}
+",
+        "pub fn one() {
+  1
+}
+
+pub fn two() {
+  2
+}
+
+
+pub fn three() {
+  3
+}
+"
+    );
+}
+
+#[test]
+fn trailing_comments_on_case_clauses_stay_on_their_line() {
+    assert_format!(
+        r#"pub fn main() {
+  case x {
+    1 -> one // The first
+    2 -> two(x) // The second
+    _ -> other
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn trailing_comments_on_list_elements_stay_on_their_line() {
+    assert_format!(
+        r#"pub fn main() {
+  [
+    wibble, // One
+    wobble,
+    wubble, // Three
+  ]
+}
+"#
+    );
+}
+
+#[test]
+fn trailing_comments_on_list_elements_break_the_list() {
+    assert_format_rewrite!(
+        r#"pub fn main() {
+  [wibble, // One
+  wobble]
+}
+"#,
+        r#"pub fn main() {
+  [
+    wibble, // One
+    wobble,
+  ]
+}
+"#
+    );
+}
+
+#[test]
+fn trailing_comments_on_list_of_literals_stay_on_their_line() {
+    assert_format!(
+        r#"pub fn main() {
+  [
+    1, 2, 3, // Three
+    4, 5,
+  ]
+}
+"#
+    );
+}
+
+#[test]
+fn trailing_comment_on_list_tail_stays_on_its_line() {
+    assert_format!(
+        r#"pub fn main() {
+  [
+    wibble, // One
+    ..wobble // The rest
+  ]
+}
+"#
+    );
+}
+
+#[test]
+fn trailing_comments_on_record_constructor_arguments_stay_on_their_line() {
+    assert_format!(
+        r#"pub type Wibble {
+  Wibble(
+    name: String, // The name
+    Int,
+    age: Int, // The age
+  )
+}
+"#
+    );
+}
+
+#[test]
+fn trailing_comment_on_last_argument_without_comma() {
+    assert_format_rewrite!(
+        r#"pub type Wibble {
+  Wibble(
+    name: String,
+    age: Int // The age
+  )
+}
+"#,
+        r#"pub type Wibble {
+  Wibble(
+    name: String,
+    age: Int, // The age
+  )
+}
+"#
+    );
+}
+
+#[test]
+fn pipelines_are_broken_by_default() {
+    assert_format_rewrite!(
+        "pub fn main() {
+  wibble |> wobble |> wubble
+}
+",
+        "pub fn main() {
+  wibble
+  |> wobble
+  |> wubble
+}
+"
+    );
+}
+
+#[test]
+fn kept_operator_line_breaks_in_pipeline() {
+    assert_format_with_options!(
+        FormatOptions {
+            keep_operator_line_breaks: true,
+            ..Default::default()
+        },
+        "pub fn main() {
+  wibble |> wobble
+  |> wubble |> wabble
+}
+"
+    );
+}
+
+#[test]
+fn pipeline_without_line_breaks_is_kept_on_one_line() {
+    assert_format_with_options!(
+        FormatOptions {
+            keep_operator_line_breaks: true,
+            ..Default::default()
+        },
+        "pub fn main() {
+  wibble |> wobble |> wubble
+}
+"
+    );
+}
+
+#[test]
+fn long_pipeline_without_line_breaks_is_broken() {
+    assert_format_rewrite_with_options!(
+        FormatOptions {
+            keep_operator_line_breaks: true,
+            ..Default::default()
+        },
+        "pub fn main() {
+  wibble_wobble_wubble_wabble |> wibble_wobble_wubble_wabble |> wibble_wobble_wubble_wabble
+}
+",
+        "pub fn main() {
+  wibble_wobble_wubble_wabble
+  |> wibble_wobble_wubble_wabble
+  |> wibble_wobble_wubble_wabble
+}
+"
+    );
+}
+
+#[test]
+fn kept_operator_line_breaks_in_assigned_pipeline() {
+    assert_format_rewrite_with_options!(
+        FormatOptions {
+            keep_operator_line_breaks: true,
+            ..Default::default()
+        },
+        "pub fn main() {
+  let x = wibble |> wobble
+    |> wubble
+  x
+}
+",
+        "pub fn main() {
+  let x =
+    wibble |> wobble
+    |> wubble
+  x
+}
+"
+    );
+}
+
+#[test]
+fn kept_operator_line_breaks_in_string_concatenation() {
+    assert_format_with_options!(
+        FormatOptions {
+            keep_operator_line_breaks: true,
+            ..Default::default()
+        },
+        r#"pub fn main() {
+  "Hello, " <> name
+  <> "! How are you?" <> " I'm fine."
+}
+"#
+    );
+}
+
+#[test]
+fn kept_operator_line_breaks_in_boolean_chain() {
+    assert_format_rewrite_with_options!(
+        FormatOptions {
+            keep_operator_line_breaks: true,
+            ..Default::default()
+        },
+        "pub fn main() {
+  let x = wibble && wobble
+    || wubble
+  x
+}
+",
+        "pub fn main() {
+  let x =
+    wibble && wobble
+    || wubble
+  x
+}
+"
+    );
+}
+
+#[test]
+fn line_breaks_in_other_operator_chains_are_not_kept() {
+    assert_format_rewrite_with_options!(
+        FormatOptions {
+            keep_operator_line_breaks: true,
+            ..Default::default()
+        },
+        "pub fn main() {
+  1 + 2
+  + 3
+}
+",
+        "pub fn main() {
+  1 + 2 + 3
+}
+"
+    );
+}
+
+#[test]
+fn operator_line_breaks_are_not_kept_by_default() {
+    assert_format_rewrite!(
+        r#"pub fn main() {
+  "Hello, " <> name
+  <> "!"
+}
+"#,
+        r#"pub fn main() {
+  "Hello, " <> name <> "!"
+}
+"#
+    );
+}
+
+#[test]
+fn non_decimal_digits_are_not_grouped_by_default() {
+    assert_format!(
+        "pub fn main() {
+  #(0xFFFFFF, 0b101010101010, 0o1234567)
+}
+"
+    );
+}
+
+#[test]
+fn grouped_hexadecimal_digits() {
+    assert_format_rewrite_with_options!(
+        FormatOptions {
+            group_non_decimal_digits: true,
+            ..Default::default()
+        },
+        "const colour = 0xFFAA00
+
+pub fn main() {
+  #(0xDEADBEEF, 0xFFFF, 0xF_F_F_F_F)
+}
+",
+        "const colour = 0xFF_AA00
+
+pub fn main() {
+  #(0xDEAD_BEEF, 0xFFFF, 0xF_FFFF)
+}
+"
+    );
+}
+
+#[test]
+fn grouped_binary_and_octal_digits() {
+    assert_format_rewrite_with_options!(
+        FormatOptions {
+            group_non_decimal_digits: true,
+            ..Default::default()
+        },
+        "pub fn main() {
+  #(0b101010101010, 0b101, 0o1234567, 0o777)
+}
+",
+        "pub fn main() {
+  #(0b1010_1010_1010, 0b101, 0o1_234_567, 0o777)
+}
+"
+    );
+}
+
+#[test]
+fn grouped_digits_in_patterns() {
+    assert_format_rewrite_with_options!(
+        FormatOptions {
+            group_non_decimal_digits: true,
+            ..Default::default()
+        },
+        "pub fn main() {
+  case x {
+    0xCAFEBABE -> 1
+    _ -> 0
+  }
+}
+",
+        "pub fn main() {
+  case x {
+    0xCAFE_BABE -> 1
+    _ -> 0
+  }
+}
+"
+    );
+}
+
+#[test]
+fn four_space_indentation() {
+    assert_format_rewrite_with_options!(
+        FormatOptions {
+            indent_width: 4,
+            ..Default::default()
+        },
+        r#"pub type Wibble {
+  Wibble
+  Wobble(Int)
+}
+
+pub fn main() {
+  case x {
+    Wibble -> {
+      io.println("Wibble")
+      [1, 2, 3]
+      |> list.map(fn(x) { x + 1 })
+    }
+    Wobble(_) -> Nil
+  }
+}
+"#,
+        r#"pub type Wibble {
+    Wibble
+    Wobble(Int)
+}
+
+pub fn main() {
+    case x {
+        Wibble -> {
+            io.println("Wibble")
+            [1, 2, 3]
+            |> list.map(fn(x) { x + 1 })
+        }
+        Wobble(_) -> Nil
+    }
+}
+"#
+    );
+}
+
+#[test]
+fn four_space_indentation_of_broken_arguments() {
+    assert_format_with_options!(
+        FormatOptions {
+            indent_width: 4,
+            ..Default::default()
+        },
+        r#"pub fn main() {
+    wibble_wobble_wubble(
+        "aaaaaaaaaaaaaaaaaaaaaaa",
+        "bbbbbbbbbbbbbbbbbbbbbbbbbb",
+        // A comment
+        "cccccccccccccccccccccc",
+    )
+}
+"#
+    );
+}
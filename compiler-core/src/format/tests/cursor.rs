@@ -0,0 +1,105 @@
+use camino::Utf8Path;
+
+use crate::format::{formatted_cursor_position, pretty};
+
+/// Formats the source with the cursor at the `|` in it, returning the
+/// formatted source with a `|` at the new cursor position.
+fn format_with_cursor(src: &str) -> String {
+    let cursor = src.find('|').expect("cursor");
+    let src = src.replacen('|', "", 1);
+    let mut formatted = String::new();
+    pretty(
+        &mut formatted,
+        &src.as_str().into(),
+        Utf8Path::new("<stdin>"),
+    )
+    .expect("should format");
+    let position = formatted_cursor_position(&src, &formatted, cursor as u32) as usize;
+    format!(
+        "{}|{}",
+        formatted.get(..position).expect("before cursor"),
+        formatted.get(position..).expect("after cursor")
+    )
+}
+
+#[test]
+fn cursor_in_a_name() {
+    assert_eq!(
+        format_with_cursor("pub fn main() {   wib|ble(1,2) }"),
+        "pub fn main() {
+  wib|ble(1, 2)
+}
+"
+    );
+}
+
+#[test]
+fn cursor_after_code() {
+    assert_eq!(
+        format_with_cursor("pub fn main() {   wibble(1,2)| }"),
+        "pub fn main() {
+  wibble(1, 2)|
+}
+"
+    );
+}
+
+#[test]
+fn cursor_in_whitespace_before_code() {
+    assert_eq!(
+        format_with_cursor("pub fn main() {  |  wibble(1,2) }"),
+        "pub fn main() {
+  |wibble(1, 2)
+}
+"
+    );
+}
+
+#[test]
+fn cursor_after_added_trailing_comma() {
+    assert_eq!(
+        format_with_cursor(
+            "pub fn main() {
+  wibble(\"one one one one one\", \"two two two two two\", \"three three three three\")|
+}"
+        ),
+        "pub fn main() {
+  wibble(
+    \"one one one one one\",
+    \"two two two two two\",
+    \"three three three three\",
+  )|
+}
+"
+    );
+}
+
+#[test]
+fn cursor_after_removed_trailing_comma() {
+    assert_eq!(
+        format_with_cursor("pub fn main() { wibble(1, 2,) |wobble() }"),
+        "pub fn main() {
+  wibble(1, 2)
+  |wobble()
+}
+"
+    );
+}
+
+#[test]
+fn cursor_at_start_and_end() {
+    assert_eq!(
+        format_with_cursor("|pub fn main() {   1 }"),
+        "|pub fn main() {
+  1
+}
+"
+    );
+    assert_eq!(
+        format_with_cursor("pub fn main() {   1 }\n\n|"),
+        "pub fn main() {
+  1
+}|
+"
+    );
+}
@@ -0,0 +1,101 @@
+use crate::assert_format;
+
+#[test]
+fn label_shorthand_in_call() {
+    assert_format!(
+        r#"pub fn main() {
+  let name = "Lucy"
+  wibble(name:, age: 10)
+}
+"#
+    );
+}
+
+#[test]
+fn label_shorthand_in_constructor() {
+    assert_format!(
+        r#"pub fn main() {
+  let name = "Lucy"
+  let age = 10
+  Person(name:, age:)
+}
+"#
+    );
+}
+
+#[test]
+fn label_shorthand_in_long_call() {
+    assert_format!(
+        r#"pub fn main() {
+  wibble(
+    a_very_long_argument_name:,
+    another_very_long_argument_name:,
+    yet_another_argument_name:,
+  )
+}
+"#
+    );
+}
+
+#[test]
+fn label_shorthand_in_pattern() {
+    assert_format!(
+        r#"pub fn main() {
+  let Person(name:, age: _) = person
+  name
+}
+"#
+    );
+}
+
+#[test]
+fn label_shorthand_in_record_update() {
+    assert_format!(
+        r#"pub fn main() {
+  Person(..person, name:)
+}
+"#
+    );
+}
+
+#[test]
+fn label_shorthand_in_constant() {
+    assert_format!(
+        r#"const person = Person(name:, age: 10)
+"#
+    );
+}
+
+#[test]
+fn label_shorthand_in_function_capture() {
+    assert_format!(
+        r#"pub fn main() {
+  wibble(name:, age: _)
+}
+"#
+    );
+}
+
+#[test]
+fn comment_before_label_shorthand() {
+    assert_format!(
+        r#"pub fn main() {
+  wibble(
+    // A comment
+    name:,
+    age: 10,
+  )
+}
+"#
+    );
+}
+
+#[test]
+fn label_with_same_name_value_is_not_rewritten() {
+    assert_format!(
+        r#"pub fn main() {
+  wibble(name: name)
+}
+"#
+    );
+}
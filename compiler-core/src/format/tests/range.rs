@@ -0,0 +1,151 @@
+use camino::Utf8Path;
+
+use crate::{
+    ast::SrcSpan,
+    format::{apply_edits, pretty_range, FormatOptions},
+};
+
+/// Formats the definitions overlapping the part of the source between the
+/// first and last occurrence of `selection`.
+fn format_range(src: &str, selection: &str) -> String {
+    let start = src.find(selection).expect("selection start") as u32;
+    let end = (src.rfind(selection).expect("selection end") + selection.len()) as u32;
+    let edits = pretty_range(
+        &src.into(),
+        Utf8Path::new("<stdin>"),
+        FormatOptions::default(),
+        SrcSpan::new(start, end),
+    )
+    .expect("should format");
+    apply_edits(src, &edits)
+}
+
+#[test]
+fn only_the_selected_definition_is_formatted() {
+    let src = "pub fn one() {   1 }
+
+pub fn two() {   2 }
+
+pub fn three() {   3 }
+";
+    assert_eq!(
+        format_range(src, "2"),
+        "pub fn one() {   1 }
+
+pub fn two() {
+  2
+}
+
+pub fn three() {   3 }
+"
+    );
+}
+
+#[test]
+fn several_definitions_can_be_selected() {
+    let src = "pub fn one() {   1 }
+
+pub fn two() {   2 }
+
+pub fn three() {   3 }
+";
+    assert_eq!(
+        format_range(
+            src,
+            "two() {   2 }
+
+pub fn three"
+        ),
+        "pub fn one() {   1 }
+
+pub fn two() {
+  2
+}
+
+pub fn three() {
+  3
+}
+"
+    );
+}
+
+#[test]
+fn comments_and_attributes_are_formatted_with_their_definition() {
+    let src = "pub fn one() {   1 }
+
+// A comment
+/// Some documentation
+@deprecated(\"Use one\")
+pub   fn two() {   2 }
+";
+    assert_eq!(
+        format_range(src, "Use one"),
+        "pub fn one() {   1 }
+
+// A comment
+/// Some documentation
+@deprecated(\"Use one\")
+pub fn two() {
+  2
+}
+"
+    );
+}
+
+#[test]
+fn imports_are_formatted_together() {
+    let src = "import wobble
+import   wibble
+
+pub fn one() {   1 }
+";
+    assert_eq!(
+        format_range(src, "wobble"),
+        "import wibble
+import wobble
+
+pub fn one() {   1 }
+"
+    );
+}
+
+#[test]
+fn cursor_in_definition() {
+    let src = "pub fn one() {   1 }
+
+pub const two = [  2  ]
+";
+    let start = src.find('2').expect("cursor") as u32;
+    let edits = pretty_range(
+        &src.into(),
+        Utf8Path::new("<stdin>"),
+        FormatOptions::default(),
+        SrcSpan::new(start, start),
+    )
+    .expect("should format");
+    assert_eq!(
+        apply_edits(src, &edits),
+        "pub fn one() {   1 }
+
+pub const two = [2]
+"
+    );
+}
+
+#[test]
+fn formatted_definitions_have_no_edits() {
+    let src = "pub fn one() {
+  1
+}
+
+pub fn two() {   2 }
+";
+    let edits = pretty_range(
+        &src.into(),
+        Utf8Path::new("<stdin>"),
+        FormatOptions::default(),
+        SrcSpan::new(0, 10),
+    )
+    .expect("should format");
+    assert_eq!(edits, vec![]);
+}
@@ -0,0 +1,77 @@
+use camino::Utf8Path;
+
+use crate::format::{pretty_verified, verify::syntax_tree, FormatOptions};
+
+fn format_verified(src: &str) -> String {
+    pretty_verified(
+        &src.into(),
+        Utf8Path::new("<stdin>"),
+        FormatOptions::default(),
+    )
+    .expect("should verify")
+}
+
+fn same_syntax_tree(one: &str, other: &str) -> bool {
+    let parse = |src: &str| {
+        crate::parse::parse_module(src)
+            .expect("should parse")
+            .module
+    };
+    syntax_tree(parse(one)) == syntax_tree(parse(other))
+}
+
+#[test]
+fn verified_output_is_formatted() {
+    let src = "pub fn main() {   1 }\n";
+    assert_eq!(format_verified(src), "pub fn main() {\n  1\n}\n");
+}
+
+#[test]
+fn sorted_imports_are_verified() {
+    let src = "import wibble.{b, a}\nimport wobble\nimport gleam/io\n";
+    assert_eq!(
+        format_verified(src),
+        "import gleam/io\nimport wibble.{a, b}\nimport wobble\n"
+    );
+}
+
+#[test]
+fn rewritten_numbers_are_verified() {
+    let src = "const x = 1000000\n\npub fn main() {\n  let y = 1.500\n  y\n}\n";
+    assert_eq!(
+        format_verified(src),
+        "const x = 1_000_000\n\npub fn main() {\n  let y = 1.5\n  y\n}\n"
+    );
+}
+
+#[test]
+fn syntax_tree_ignores_positions() {
+    assert!(same_syntax_tree(
+        "pub fn main() { wibble(1, 2) }",
+        "pub fn main() {\n  wibble(\n    1,\n    2,\n  )\n}\n"
+    ));
+}
+
+#[test]
+fn syntax_tree_ignores_blocks_around_single_expressions() {
+    assert!(same_syntax_tree(
+        "pub fn main() { tuple.0.1 }",
+        "pub fn main() { { tuple.0 }.1 }"
+    ));
+}
+
+#[test]
+fn syntax_tree_keeps_changed_code() {
+    assert!(!same_syntax_tree(
+        "pub fn main() { wibble(1, 2) }",
+        "pub fn main() { wibble(2, 1) }"
+    ));
+    assert!(!same_syntax_tree(
+        "pub fn main() { 1.5 }",
+        "pub fn main() { 1.05 }"
+    ));
+    assert!(!same_syntax_tree(
+        "import a\n\npub fn main() { 1 }",
+        "pub fn main() { 1 }\n\nimport a"
+    ));
+}
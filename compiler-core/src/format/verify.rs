@@ -0,0 +1,167 @@
+use std::sync::OnceLock;
+
+use camino::Utf8Path;
+use ecow::EcoString;
+use itertools::Itertools;
+use regex::Regex;
+use vec1::Vec1;
+
+use crate::{
+    ast::{
+        Definition, SrcSpan, Statement, UntypedConstant, UntypedExpr, UntypedModule,
+        UntypedPattern, UntypedStatement,
+    },
+    ast_folder::{
+        PatternFolder, TypeAstFolder, UntypedConstantFolder, UntypedExprFolder, UntypedModuleFolder,
+    },
+    error::FormatVerificationProblem,
+    Error, Result,
+};
+
+use super::FormatOptions;
+
+/// Formats a module, checking that the formatter has not changed what the
+/// code means. The formatted code must parse to the same syntax tree as the
+/// original code, ignoring positions, and formatting it again must not change
+/// it.
+pub fn pretty_verified(src: &EcoString, path: &Utf8Path, options: FormatOptions) -> Result<String> {
+    let mut formatted = String::new();
+    super::pretty_with_options(&mut formatted, src, path, options)?;
+
+    let failure = |problem| Error::FormatVerification {
+        path: path.to_path_buf(),
+        problem,
+    };
+
+    let original = crate::parse::parse_module(src)
+        .expect("Module should parse as it has been formatted")
+        .module;
+    let formatted_src = EcoString::from(formatted.as_str());
+    let Ok(parsed) = crate::parse::parse_module(&formatted_src) else {
+        return Err(failure(FormatVerificationProblem::InvalidOutput));
+    };
+    if syntax_tree(original) != syntax_tree(parsed.module) {
+        return Err(failure(FormatVerificationProblem::ChangedSyntaxTree));
+    }
+
+    let mut reformatted = String::new();
+    super::pretty_with_options(&mut reformatted, &formatted_src, path, options)?;
+    if reformatted != formatted {
+        return Err(failure(FormatVerificationProblem::NotIdempotent));
+    }
+
+    Ok(formatted)
+}
+
+/// A printed version of the module's syntax tree without anything the
+/// formatter is allowed to change: positions, the order of imports, blocks
+/// around single expressions, and the way numbers are written.
+pub(super) fn syntax_tree(module: UntypedModule) -> Vec<String> {
+    let module = Normaliser.fold_module(module);
+    let mut definitions = vec![];
+    for (is_import_group, group) in &module
+        .definitions
+        .into_iter()
+        .group_by(|definition| definition.definition.is_import())
+    {
+        let group = group.map(|mut definition| {
+            if let Definition::Import(import) = &mut definition.definition {
+                import
+                    .unqualified_values
+                    .sort_by(|a, b| a.name.cmp(&b.name));
+                import.unqualified_types.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+            without_positions(&format!("{definition:?}"))
+        });
+        if is_import_group {
+            definitions.extend(group.sorted());
+        } else {
+            definitions.extend(group);
+        }
+    }
+    definitions
+}
+
+fn without_positions(syntax_tree: &str) -> String {
+    static POSITION: OnceLock<Regex> = OnceLock::new();
+    POSITION
+        .get_or_init(|| {
+            Regex::new(r"SrcSpan \{ start: \d+, end: \d+ \}|end_position: \d+")
+                .expect("POSITION regex")
+        })
+        .replace_all(syntax_tree, "_")
+        .into_owned()
+}
+
+/// Rewrites the parts of a module the formatter is allowed to write
+/// differently into a single form.
+struct Normaliser;
+
+impl UntypedModuleFolder for Normaliser {}
+impl TypeAstFolder for Normaliser {}
+
+impl UntypedExprFolder for Normaliser {
+    fn fold_int(&mut self, location: SrcSpan, value: EcoString) -> UntypedExpr {
+        let value = normalise_int(&value);
+        UntypedExpr::Int { location, value }
+    }
+
+    fn fold_float(&mut self, location: SrcSpan, value: EcoString) -> UntypedExpr {
+        let value = normalise_float(&value);
+        UntypedExpr::Float { location, value }
+    }
+
+    /// The formatter wraps some expressions in a block to keep them from
+    /// being parsed differently, such as `{ tuple.0 }.1`.
+    fn fold_block(&mut self, location: SrcSpan, statements: Vec1<UntypedStatement>) -> UntypedExpr {
+        match statements.split_off_first() {
+            (Statement::Expression(expression), rest) if rest.is_empty() => {
+                self.update_expr(expression)
+            }
+            (first, rest) => {
+                let mut statements = Vec1::new(first);
+                statements.extend(rest);
+                UntypedExpr::Block {
+                    location,
+                    statements,
+                }
+            }
+        }
+    }
+}
+
+impl UntypedConstantFolder for Normaliser {
+    fn fold_constant_int(&mut self, location: SrcSpan, value: EcoString) -> UntypedConstant {
+        let value = normalise_int(&value);
+        UntypedConstant::Int { location, value }
+    }
+
+    fn fold_constant_float(&mut self, location: SrcSpan, value: EcoString) -> UntypedConstant {
+        let value = normalise_float(&value);
+        UntypedConstant::Float { location, value }
+    }
+}
+
+impl PatternFolder for Normaliser {
+    fn fold_pattern_int(&mut self, location: SrcSpan, value: EcoString) -> UntypedPattern {
+        let value = normalise_int(&value);
+        UntypedPattern::Int { location, value }
+    }
+
+    fn fold_pattern_float(&mut self, location: SrcSpan, value: EcoString) -> UntypedPattern {
+        let value = normalise_float(&value);
+        UntypedPattern::Float { location, value }
+    }
+}
+
+fn normalise_int(value: &str) -> EcoString {
+    value.replace('_', "").into()
+}
+
+fn normalise_float(value: &str) -> EcoString {
+    let value = value.replace('_', "");
+    match value.parse::<f64>() {
+        Ok(float) => format!("{float:?}").into(),
+        Err(_) => value.into(),
+    }
+}
@@ -286,6 +286,23 @@ pub async fn publish_documentation<Http: HttpClient>(
     hexpm::publish_docs_response(response).map_err(Error::hex)
 }
 
+/// The package with the name in the Hex repository, or `None` if it has not
+/// been published.
+pub async fn get_package<Http: HttpClient>(
+    name: &str,
+    config: &hexpm::Config,
+    http: &Http,
+) -> Result<Option<hexpm::Package>> {
+    tracing::info!(name = name, "looking_up_package");
+    let request = hexpm::get_package_request(name, None, config);
+    let response = http.send(request).await?;
+    match hexpm::get_package_response(response, HEXPM_PUBLIC_KEY) {
+        Ok(package) => Ok(Some(package)),
+        Err(hexpm::ApiError::NotFound) => Ok(None),
+        Err(error) => Err(Error::hex(error)),
+    }
+}
+
 pub async fn get_package_release<Http: HttpClient>(
     name: &str,
     version: &Version,
@@ -49,6 +49,11 @@ pub trait Utf8Writer: std::fmt::Write {
     }
 
     fn convert_err<E: std::error::Error>(&self, err: E) -> Error;
+
+    /// Called when the code written next was generated from the source code
+    /// at this byte index. Writers building source maps record it, others
+    /// have no use for it.
+    fn source_location(&mut self, _byte_index: u32) {}
 }
 
 impl Utf8Writer for String {
@@ -1,6 +1,8 @@
+pub mod bundle;
 mod expression;
 mod import;
 mod pattern;
+mod source_map;
 #[cfg(test)]
 mod tests;
 mod typescript;
@@ -169,6 +171,10 @@ impl<'a> Generator<'a> {
             self.register_prelude_usage(&mut imports, "float64Bits", None);
         };
 
+        if self.tracker.echo_used {
+            self.register_prelude_usage(&mut imports, "echo", None);
+        };
+
         // Put it all together
 
         if imports.is_empty() && statements.is_empty() {
@@ -217,11 +223,12 @@ impl<'a> Generator<'a> {
             Definition::CustomType(CustomType { .. }) => None,
 
             Definition::ModuleConstant(ModuleConstant {
+                location,
                 publicity,
                 name,
                 value,
                 ..
-            }) => Some(self.module_constant(*publicity, name, value)),
+            }) => Some(self.module_constant(*location, *publicity, name, value)),
 
             Definition::Function(function) => {
                 // If there's an external JavaScript implementation then it will be imported,
@@ -455,6 +462,7 @@ impl<'a> Generator<'a> {
 
     fn module_constant(
         &mut self,
+        location: SrcSpan,
         publicity: Publicity,
         name: &'a str,
         value: &'a TypedConstant,
@@ -465,6 +473,7 @@ impl<'a> Generator<'a> {
             "export const "
         };
         Ok(docvec![
+            source_location(location.start),
             head,
             maybe_escape_identifier_doc(name),
             " = ",
@@ -485,6 +494,7 @@ impl<'a> Generator<'a> {
             .collect();
         let mut generator = expression::Generator::new(
             self.module.name.clone(),
+            &self.module.type_info.values,
             self.line_numbers,
             function.name.clone(),
             argument_names,
@@ -513,6 +523,7 @@ impl<'a> Generator<'a> {
         };
 
         let document = docvec![
+            source_location(function.location.start),
             head,
             maybe_escape_identifier_doc(function.name.as_str()),
             fun_args(function.arguments.as_slice(), generator.tail_recursion_used),
@@ -562,6 +573,43 @@ pub fn module(
     Ok(document.to_pretty_string(80))
 }
 
+/// Generates the code of a module that is to be written to `output_path`,
+/// along with a source map mapping it back to the Gleam code it was generated
+/// from. The code links to the source map, which is to be written next to it
+/// with the `.map` extension.
+pub fn module_with_source_map(
+    module: &TypedModule,
+    line_numbers: &LineNumbers,
+    path: &Utf8Path,
+    src: &EcoString,
+    target_support: TargetSupport,
+    typescript: TypeScriptDeclarations,
+    output_path: &Utf8Path,
+) -> Result<(String, String), crate::Error> {
+    let document = Generator::new(line_numbers, module, target_support, typescript)
+        .compile()
+        .map_err(|error| crate::Error::JavaScript {
+            path: path.to_path_buf(),
+            src: src.clone(),
+            error,
+        })?;
+    let (mut code, mappings) = document.to_pretty_string_with_source_mappings(80);
+
+    let file = output_path.file_name().unwrap_or_default();
+    code.push_str(&format!("//# sourceMappingURL={file}.map\n"));
+
+    // The source is referenced relative to the generated code, with the `/`
+    // separators of a URL.
+    let source = output_path
+        .parent()
+        .and_then(|directory| pathdiff::diff_utf8_paths(path, directory))
+        .unwrap_or_else(|| path.to_path_buf());
+    let source = source.as_str().replace('\\', "/");
+
+    let source_map = source_map::source_map(file, &source, src, line_numbers, &mappings);
+    Ok((code, source_map))
+}
+
 pub fn ts_declaration(
     module: &TypedModule,
     path: &Utf8Path,
@@ -577,6 +625,28 @@ pub fn ts_declaration(
     Ok(document.to_pretty_string(80))
 }
 
+/// A JavaScript module calling a public function of a module of the same
+/// package, used by `gleam run` to run the function. A function taking the
+/// command line arguments is given them as a Gleam list, without the
+/// runtime and script the arguments of the process start with.
+pub fn entrypoint(module: &str, function: &str, takes_arguments: bool) -> String {
+    let function = maybe_escape_identifier_string(function);
+    if !takes_arguments {
+        return format!(
+            r#"import {{ {function} }} from "./{module}.mjs";
+{function}();
+"#
+        );
+    }
+    format!(
+        r#"import {{ toList }} from "./gleam.mjs";
+import {{ {function} }} from "./{module}.mjs";
+const args = globalThis.Deno ? Deno.args : process.argv.slice(2);
+{function}(toList(args));
+"#
+    )
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
     Unsupported { feature: String, location: SrcSpan },
@@ -762,4 +832,5 @@ pub(crate) struct UsageTracker {
     pub string_bit_array_segment_used: bool,
     pub codepoint_bit_array_segment_used: bool,
     pub float_bit_array_segment_used: bool,
+    pub echo_used: bool,
 }
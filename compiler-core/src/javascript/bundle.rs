@@ -0,0 +1,422 @@
+//! Bundling the JavaScript generated for a module, along with every module
+//! it imports, into a single ES module that can be loaded without a bundler.
+//!
+//! Each module is evaluated in a function of its own so the names it defines
+//! don't clash with those of other modules, and its imports and exports are
+//! rewritten to refer to the objects these functions return. Modules come in
+//! the bundle after the modules they import, so each is evaluated after its
+//! imports have been. Packages imported by name rather than by path, such as
+//! `node:fs` or those installed with npm, are still imported by the bundle.
+//!
+//! The imports and exports are found line by line rather than by parsing the
+//! JavaScript, which works for the code generated by the compiler and for
+//! typical FFI modules. Anything else is reported as unsupported.
+
+#[cfg(test)]
+mod tests;
+
+use std::{collections::HashMap, sync::OnceLock};
+
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
+use regex::{Captures, Regex};
+
+use crate::{io::FileSystemReader, Error, Result};
+
+/// The bundle of the module at the entrypoint path, exporting everything the
+/// module exports. The paths of the bundled modules are written in comments
+/// relative to the directory.
+pub fn bundle(
+    io: &impl FileSystemReader,
+    directory: &Utf8Path,
+    entrypoint: &Utf8Path,
+) -> Result<String> {
+    let mut bundler = Bundler {
+        io,
+        directory,
+        modules: vec![],
+        indices: HashMap::new(),
+        packages: vec![],
+        side_effect_packages: vec![],
+    };
+    let entrypoint = bundler.include(entrypoint)?;
+
+    let mut bundle = String::new();
+    for (index, package) in bundler.packages.iter().enumerate() {
+        bundle.push_str(&format!(
+            "import * as {} from \"{package}\";\n",
+            package_variable(index)
+        ));
+    }
+    for package in &bundler.side_effect_packages {
+        bundle.push_str(&format!("import \"{package}\";\n"));
+    }
+    if !bundler.packages.is_empty() || !bundler.side_effect_packages.is_empty() {
+        bundle.push('\n');
+    }
+    for module in &bundler.modules {
+        bundle.push_str(&module.code);
+        bundle.push('\n');
+    }
+    let names = bundler
+        .modules
+        .get(entrypoint)
+        .map(|module| module.names.iter().join(", "))
+        .unwrap_or_default();
+    if !names.is_empty() {
+        bundle.push_str(&format!(
+            "export const {{ {names} }} = {};\n",
+            module_variable(entrypoint)
+        ));
+    }
+    Ok(bundle)
+}
+
+struct Bundler<'a, IO> {
+    io: &'a IO,
+    directory: &'a Utf8Path,
+    /// The modules in the bundle, in the order they are evaluated.
+    modules: Vec<BundledModule>,
+    /// The index of each module in the bundle, or `None` for the modules
+    /// still being bundled.
+    indices: HashMap<Utf8PathBuf, Option<usize>>,
+    /// The packages imported by name, in the order they are first imported.
+    packages: Vec<String>,
+    /// The packages imported only to be evaluated.
+    side_effect_packages: Vec<String>,
+}
+
+struct BundledModule {
+    code: String,
+    /// The names the module exports.
+    names: Vec<String>,
+}
+
+/// Where the names a module imports, or exports from another module, come
+/// from.
+#[derive(Debug, Clone, Copy)]
+enum Source {
+    Module(usize),
+    Package(usize),
+}
+
+impl Source {
+    fn variable(self) -> String {
+        match self {
+            Source::Module(index) => module_variable(index),
+            Source::Package(index) => package_variable(index),
+        }
+    }
+}
+
+fn module_variable(index: usize) -> String {
+    format!("$$module_{index}")
+}
+
+fn package_variable(index: usize) -> String {
+    format!("$$package_{index}")
+}
+
+impl<IO: FileSystemReader> Bundler<'_, IO> {
+    /// Adds the module at the path to the bundle, after the modules it
+    /// imports, returning its index.
+    fn include(&mut self, path: &Utf8Path) -> Result<usize> {
+        match self.indices.get(path) {
+            Some(Some(index)) => return Ok(*index),
+            Some(None) => {
+                return Err(unsupported(
+                    path,
+                    "it is imported by a module it imports".into(),
+                ))
+            }
+            None => (),
+        }
+        _ = self.indices.insert(path.to_path_buf(), None);
+
+        let code = self.io.read(path)?;
+        let module = self.module(path, &code)?;
+        let index = self.modules.len();
+        let name = path.strip_prefix(self.directory).unwrap_or(path);
+        self.modules.push(BundledModule {
+            code: format!(
+                "// {name}\nconst {} = (() => {{\n{}}})();\n",
+                module_variable(index),
+                module.code
+            ),
+            names: module.names,
+        });
+        _ = self.indices.insert(path.to_path_buf(), Some(index));
+        Ok(index)
+    }
+
+    /// The code of the module with its imports and exports rewritten, ending
+    /// by returning an object of everything it exports.
+    fn module(&mut self, path: &Utf8Path, code: &str) -> Result<BundledModule> {
+        let mut body = String::new();
+        // The modules all of whose exports are exported again
+        let mut exported_modules = vec![];
+        // The names the module exports, and the value of each of them
+        let mut exports: Vec<(String, String)> = vec![];
+        let mut names = vec![];
+
+        let mut lines = code.lines();
+        while let Some(line) = lines.next() {
+            if let Some(declaration) = export_declaration().captures(line) {
+                let name = ["function", "class", "variable"]
+                    .into_iter()
+                    .find_map(|group| declaration.name(group))
+                    .map(|name| name.as_str().to_string())
+                    .unwrap_or_default();
+                body.push_str(line.trim_start_matches("export").trim_start());
+                body.push('\n');
+                exports.push((name.clone(), name));
+                continue;
+            }
+
+            let (complete, is_import) = if import_start().is_match(line) {
+                (import(), true)
+            } else if export_list_start().is_match(line) {
+                (export_list(), false)
+            } else if line.starts_with("export ") {
+                return Err(unsupported_statement(path, line));
+            } else {
+                body.push_str(line);
+                body.push('\n');
+                continue;
+            };
+
+            // The statement may continue over several lines
+            let mut statement = line.to_string();
+            while !complete.is_match(&statement) {
+                let next = lines
+                    .next()
+                    .ok_or_else(|| unsupported_statement(path, line))?;
+                statement.push('\n');
+                statement.push_str(next);
+            }
+            let captures = complete
+                .captures(&statement)
+                .expect("Complete JavaScript statement");
+
+            if is_import {
+                body.push_str(&self.import(path, &captures)?);
+                continue;
+            }
+
+            let source = match captures.name("from") {
+                Some(from) => Some(self.source(path, from.as_str())?),
+                None => None,
+            };
+            match (source, captures.name("members"), captures.name("namespace")) {
+                (_, Some(members), _) => {
+                    for (name, alias) in members_of(members.as_str()) {
+                        let value = match source {
+                            Some(source) => format!("{}.{name}", source.variable()),
+                            None => name.clone(),
+                        };
+                        exports.push((alias.unwrap_or(name), value));
+                    }
+                }
+                (Some(source), None, Some(namespace)) => {
+                    exports.push((namespace.as_str().into(), source.variable()));
+                }
+                (Some(source), None, None) => {
+                    exported_modules.push(format!("...{}", source.variable()));
+                    if let Source::Module(index) = source {
+                        if let Some(module) = self.modules.get(index) {
+                            names.extend(module.names.iter().cloned());
+                        }
+                    }
+                }
+                (None, None, _) => return Err(unsupported_statement(path, &statement)),
+            }
+        }
+
+        names.extend(exports.iter().map(|(name, _)| name.clone()));
+        let properties = exported_modules
+            .into_iter()
+            .chain(exports.into_iter().map(|(name, value)| {
+                if name == value {
+                    name
+                } else {
+                    format!("{name}: {value}")
+                }
+            }))
+            .join(", ");
+        if properties.is_empty() {
+            body.push_str("return {};\n");
+        } else {
+            body.push_str(&format!("return {{ {properties} }};\n"));
+        }
+        Ok(BundledModule {
+            code: body,
+            names: names.into_iter().unique().collect(),
+        })
+    }
+
+    /// The declarations of the names an import statement imports.
+    fn import(&mut self, path: &Utf8Path, statement: &Captures<'_>) -> Result<String> {
+        let from = statement.name("from").map_or("", |from| from.as_str());
+        let Some(clause) = statement
+            .name("clause")
+            .map(|clause| clause.as_str().trim())
+            .filter(|clause| !clause.is_empty())
+        else {
+            // The module is imported only to be evaluated
+            if is_path(from) {
+                _ = self.include(&resolve(path, from))?;
+            } else if !self
+                .side_effect_packages
+                .iter()
+                .any(|package| package == from)
+            {
+                self.side_effect_packages.push(from.into());
+            }
+            return Ok(String::new());
+        };
+
+        let variable = self.source(path, from)?.variable();
+        let (default, rest) = if clause.starts_with('{') || clause.starts_with('*') {
+            (None, clause)
+        } else {
+            match clause.split_once(',') {
+                Some((default, rest)) => (Some(default.trim()), rest.trim()),
+                None => (Some(clause), ""),
+            }
+        };
+
+        let mut declarations = String::new();
+        if let Some(default) = default {
+            declarations.push_str(&format!("const {default} = {variable}.default;\n"));
+        }
+        if let Some(namespace) = rest.strip_prefix('*') {
+            let namespace = namespace.trim().trim_start_matches("as").trim();
+            declarations.push_str(&format!("const {namespace} = {variable};\n"));
+        } else if let Some(members) = rest
+            .strip_prefix('{')
+            .and_then(|rest| rest.strip_suffix('}'))
+        {
+            let members = members_of(members)
+                .into_iter()
+                .map(|(name, alias)| match alias {
+                    Some(alias) => format!("{name}: {alias}"),
+                    None => name,
+                })
+                .join(", ");
+            if !members.is_empty() {
+                declarations.push_str(&format!("const {{ {members} }} = {variable};\n"));
+            }
+        } else if !rest.is_empty() {
+            return Err(unsupported_statement(
+                path,
+                statement.get(0).map_or("", |m| m.as_str()),
+            ));
+        }
+        Ok(declarations)
+    }
+
+    /// The module or package imported from.
+    fn source(&mut self, path: &Utf8Path, from: &str) -> Result<Source> {
+        if is_path(from) {
+            return Ok(Source::Module(self.include(&resolve(path, from))?));
+        }
+        let index = match self.packages.iter().position(|package| package == from) {
+            Some(index) => index,
+            None => {
+                self.packages.push(from.into());
+                self.packages.len() - 1
+            }
+        };
+        Ok(Source::Package(index))
+    }
+}
+
+fn unsupported(path: &Utf8Path, reason: String) -> Error {
+    Error::JavaScriptBundle {
+        path: path.to_path_buf(),
+        reason,
+    }
+}
+
+fn unsupported_statement(path: &Utf8Path, statement: &str) -> Error {
+    let statement = statement.lines().next().unwrap_or_default().trim();
+    unsupported(
+        path,
+        format!("its statement `{statement}` is not supported"),
+    )
+}
+
+/// Whether a module is imported by its path, rather than by the name of a
+/// package.
+fn is_path(from: &str) -> bool {
+    from.starts_with("./") || from.starts_with("../") || from.starts_with('/')
+}
+
+/// The path of a module imported from the module at the path.
+fn resolve(path: &Utf8Path, from: &str) -> Utf8PathBuf {
+    let mut resolved = path.parent().map(Utf8Path::to_path_buf).unwrap_or_default();
+    for component in Utf8Path::new(from).components() {
+        match component {
+            Utf8Component::CurDir => (),
+            Utf8Component::ParentDir => _ = resolved.pop(),
+            component => resolved.push(component),
+        }
+    }
+    resolved
+}
+
+/// The names in a list of imported or exported names such as `a, b as c`,
+/// along with their aliases.
+fn members_of(members: &str) -> Vec<(String, Option<String>)> {
+    members
+        .split(',')
+        .map(str::trim)
+        .filter(|member| !member.is_empty())
+        .map(|member| match member.split_once(" as ") {
+            Some((name, alias)) => (name.trim().into(), Some(alias.trim().into())),
+            None => (member.into(), None),
+        })
+        .collect()
+}
+
+fn import_start() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r#"^import(\s*[{*"']|\s+[A-Za-z_$])"#).expect("import start regex")
+    })
+}
+
+fn import() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(
+            r#"(?s)^import\s*(?:(?P<clause>[^"']*?)\s*from\s*)?["'](?P<from>[^"']+)["']\s*;?\s*$"#,
+        )
+        .expect("import regex")
+    })
+}
+
+fn export_list_start() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^export\s*[{*]").expect("export list start regex"))
+}
+
+fn export_list() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(
+            r#"(?s)^export\s*(?:\{(?P<members>[^}]*)\}|\*(?:\s*as\s+(?P<namespace>[\w$]+))?)\s*(?:from\s*["'](?P<from>[^"']+)["'])?\s*;?\s*$"#,
+        )
+        .expect("export list regex")
+    })
+}
+
+fn export_declaration() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(
+            r"^export\s+(?:(?:async\s+)?function\s*\*?\s*(?P<function>[\w$]+)|class\s+(?P<class>[\w$]+)|(?:const|let|var)\s+(?P<variable>[\w$]+))",
+        )
+        .expect("export declaration regex")
+    })
+}
@@ -0,0 +1,200 @@
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::{
+    io::{memory::InMemoryFileSystem, FileSystemWriter},
+    Error,
+};
+
+use super::bundle;
+
+fn bundle_files(files: &[(&str, &str)]) -> Result<String, Error> {
+    let io = InMemoryFileSystem::new();
+    for (path, code) in files {
+        io.write(Utf8Path::new(path), code).unwrap();
+    }
+    let (entrypoint, _) = files.first().unwrap();
+    bundle(&io, Utf8Path::new("/build"), Utf8Path::new(entrypoint))
+}
+
+#[test]
+fn modules_come_after_their_imports() {
+    let bundled = bundle_files(&[
+        (
+            "/build/app/app.mjs",
+            r#"import * as $list from "../gleam_stdlib/gleam/list.mjs";
+import { Ok, toList } from "./gleam.mjs";
+import { read } from "node:fs";
+
+export function main() {
+  return $list.reverse(toList([1]));
+}
+"#,
+        ),
+        ("/build/app/gleam.mjs", r#"export * from "../prelude.mjs";"#),
+        (
+            "/build/prelude.mjs",
+            "export class Ok {}
+export function toList(array) {
+  return array;
+}
+",
+        ),
+        (
+            "/build/gleam_stdlib/gleam/list.mjs",
+            "export function reverse(list) {
+  return list;
+}
+",
+        ),
+    ])
+    .unwrap();
+    assert_eq!(
+        bundled,
+        r#"import * as $$package_0 from "node:fs";
+
+// gleam_stdlib/gleam/list.mjs
+const $$module_0 = (() => {
+function reverse(list) {
+  return list;
+}
+return { reverse };
+})();
+
+// prelude.mjs
+const $$module_1 = (() => {
+class Ok {}
+function toList(array) {
+  return array;
+}
+return { Ok, toList };
+})();
+
+// app/gleam.mjs
+const $$module_2 = (() => {
+return { ...$$module_1 };
+})();
+
+// app/app.mjs
+const $$module_3 = (() => {
+const $list = $$module_0;
+const { Ok, toList } = $$module_2;
+const { read } = $$package_0;
+
+function main() {
+  return $list.reverse(toList([1]));
+}
+return { main };
+})();
+
+export const { main } = $$module_3;
+"#
+    );
+}
+
+#[test]
+fn export_lists() {
+    let bundled = bundle_files(&[
+        (
+            "/build/app.mjs",
+            r#"import {
+  wibble,
+} from "./wibble.mjs";
+
+const wobble = wibble + 1;
+
+export { wobble, wibble as wubble };
+export { wibble as default_wibble } from "./wibble.mjs";
+export * as w from "./wibble.mjs";
+"#,
+        ),
+        ("/build/wibble.mjs", "export const wibble = 1;"),
+    ])
+    .unwrap();
+    assert_eq!(
+        bundled,
+        r#"// wibble.mjs
+const $$module_0 = (() => {
+const wibble = 1;
+return { wibble };
+})();
+
+// app.mjs
+const $$module_1 = (() => {
+const { wibble } = $$module_0;
+
+const wobble = wibble + 1;
+
+return { wobble, wubble: wibble, default_wibble: $$module_0.wibble, w: $$module_0 };
+})();
+
+export const { wobble, wubble, default_wibble, w } = $$module_1;
+"#
+    );
+}
+
+#[test]
+fn default_and_side_effect_imports() {
+    let bundled = bundle_files(&[
+        (
+            "/build/app.mjs",
+            r#"import "./setup.mjs";
+import "node:process";
+import wibble, { wobble } from "wibble";
+export const x = wibble(wobble);
+"#,
+        ),
+        ("/build/setup.mjs", "globalThis.ready = true;"),
+    ])
+    .unwrap();
+    assert_eq!(
+        bundled,
+        r#"import * as $$package_0 from "wibble";
+import "node:process";
+
+// setup.mjs
+const $$module_0 = (() => {
+globalThis.ready = true;
+return {};
+})();
+
+// app.mjs
+const $$module_1 = (() => {
+const wibble = $$package_0.default;
+const { wobble } = $$package_0;
+const x = wibble(wobble);
+return { x };
+})();
+
+export const { x } = $$module_1;
+"#
+    );
+}
+
+#[test]
+fn import_cycles_are_not_supported() {
+    let error = bundle_files(&[
+        ("/build/wibble.mjs", r#"import { b } from "./wobble.mjs";"#),
+        ("/build/wobble.mjs", r#"import { a } from "./wibble.mjs";"#),
+    ])
+    .unwrap_err();
+    assert_eq!(
+        error,
+        Error::JavaScriptBundle {
+            path: Utf8PathBuf::from("/build/wibble.mjs"),
+            reason: "it is imported by a module it imports".into(),
+        }
+    );
+}
+
+#[test]
+fn default_exports_are_not_supported() {
+    let error =
+        bundle_files(&[("/build/app.mjs", "const x = 1;\nexport default x;\n")]).unwrap_err();
+    assert_eq!(
+        error,
+        Error::JavaScriptBundle {
+            path: Utf8PathBuf::from("/build/app.mjs"),
+            reason: "its statement `export default x;` is not supported".into(),
+        }
+    );
+}
@@ -10,7 +10,7 @@ use crate::{
     pretty::*,
     type_::{ModuleValueConstructor, Type, ValueConstructor, ValueConstructorVariant},
 };
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 #[derive(Debug, Clone, Copy)]
 pub enum Position {
@@ -31,6 +31,9 @@ impl Position {
 #[derive(Debug)]
 pub(crate) struct Generator<'module> {
     module_name: EcoString,
+    // The values defined in the module, used to look up the final purity of
+    // its functions.
+    module_values: &'module HashMap<EcoString, ValueConstructor>,
     line_numbers: &'module LineNumbers,
     function_name: Option<EcoString>,
     function_arguments: Vec<Option<&'module EcoString>>,
@@ -50,6 +53,7 @@ impl<'module> Generator<'module> {
     #[allow(clippy::too_many_arguments)] // TODO: FIXME
     pub fn new(
         module_name: EcoString,
+        module_values: &'module HashMap<EcoString, ValueConstructor>,
         line_numbers: &'module LineNumbers,
         function_name: EcoString,
         function_arguments: Vec<Option<&'module EcoString>>,
@@ -70,6 +74,7 @@ impl<'module> Generator<'module> {
         Self {
             tracker,
             module_name,
+            module_values,
             line_numbers,
             function_name,
             function_arguments,
@@ -138,6 +143,10 @@ impl<'module> Generator<'module> {
         let document = match expression {
             TypedExpr::String { value, .. } => Ok(string(value)),
 
+            TypedExpr::Invalid { .. } => {
+                unreachable!("Invalid expressions must not be present for JavaScript generation")
+            }
+
             TypedExpr::Int { value, .. } => Ok(int(value)),
             TypedExpr::Float { value, .. } => Ok(float(value)),
 
@@ -190,6 +199,11 @@ impl<'module> Generator<'module> {
                 location, message, ..
             } => self.panic(location, message.as_ref().map(|m| &**m)),
 
+            TypedExpr::Echo {
+                location,
+                expression,
+            } => self.echo(expression, location),
+
             TypedExpr::BitArray { segments, .. } => self.bit_array(segments),
 
             TypedExpr::ModuleSelect {
@@ -469,8 +483,9 @@ impl<'module> Generator<'module> {
 
     fn statements<'a>(&mut self, statements: &'a [TypedStatement]) -> Output<'a> {
         let count = statements.len();
-        let mut documents = Vec::with_capacity(count * 3);
+        let mut documents = Vec::with_capacity(count * 4);
         for (i, statement) in statements.iter().enumerate() {
+            documents.push(source_location(statement.location().start));
             if i + 1 < count {
                 documents.push(self.not_in_tail_position(|gen| gen.statement(statement))?);
                 if requires_semicolon(statement) {
@@ -757,18 +772,67 @@ impl<'module> Generator<'module> {
             }
 
             _ => {
+                // Calls to functions without side effects are annotated so
+                // that bundlers can remove them when their result is unused.
+                let annotation = if self.is_pure_function(fun) {
+                    "/* @__PURE__ */ ".to_doc()
+                } else {
+                    nil()
+                };
+                // An annotated call being called is wrapped in parentheses so
+                // that the annotation can't be taken to cover the outer call.
+                let needs_parens = match fun {
+                    TypedExpr::Fn { .. } => true,
+                    TypedExpr::Call { fun, .. } => self.is_pure_function(fun),
+                    _ => false,
+                };
                 let fun = self.not_in_tail_position(|gen| {
-                    let is_fn_literal = matches!(fun, TypedExpr::Fn { .. });
                     let fun = gen.wrap_expression(fun)?;
-                    if is_fn_literal {
+                    if needs_parens {
                         Ok(docvec!("(", fun, ")"))
                     } else {
                         Ok(fun)
                     }
                 })?;
                 let arguments = call_arguments(arguments.into_iter().map(Ok))?;
-                Ok(self.wrap_return(docvec![fun, arguments]))
+                Ok(self.wrap_return(docvec![annotation, fun, arguments]))
+            }
+        }
+    }
+
+    fn is_pure_function(&self, fun: &TypedExpr) -> bool {
+        match fun {
+            TypedExpr::Var {
+                constructor:
+                    ValueConstructor {
+                        variant:
+                            ValueConstructorVariant::ModuleFn {
+                                name,
+                                module,
+                                purity,
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                // Calls between mutually recursive functions are typed before
+                // the purity of the group is known, so the purity of this
+                // module's functions is taken from the finished module instead.
+                if module == &self.module_name {
+                    self.module_values
+                        .get(name)
+                        .and_then(|value| value.variant.purity())
+                        .is_some_and(|purity| purity.is_pure())
+                } else {
+                    purity.is_pure()
+                }
             }
+            TypedExpr::ModuleSelect {
+                constructor: ModuleValueConstructor::Fn { purity, .. },
+                ..
+            } => purity.is_pure(),
+            _ => false,
         }
     }
 
@@ -974,6 +1038,14 @@ impl<'module> Generator<'module> {
         Ok(doc)
     }
 
+    fn echo<'a>(&mut self, expression: &'a TypedExpr, location: &'a SrcSpan) -> Output<'a> {
+        self.tracker.echo_used = true;
+        let value = self.not_in_tail_position(|gen| gen.wrap_expression(expression))?;
+        let module = self.module_name.clone().to_doc().surround('"', '"');
+        let line = self.line_numbers.line_number(location.start).to_doc();
+        Ok(docvec!["echo", wrap_args([value, module, line])])
+    }
+
     fn throw_error<'a, Fields>(
         &mut self,
         error_name: &'a str,
@@ -1456,7 +1528,9 @@ impl TypedExpr {
             | TypedExpr::BitArray { .. }
             | TypedExpr::RecordUpdate { .. }
             | TypedExpr::NegateBool { .. }
-            | TypedExpr::NegateInt { .. } => false,
+            | TypedExpr::NegateInt { .. }
+            | TypedExpr::Echo { .. }
+            | TypedExpr::Invalid { .. } => false,
         }
     }
 }
@@ -1513,7 +1587,9 @@ fn requires_semicolon(statement: &TypedStatement) -> bool {
             | TypedExpr::RecordUpdate { .. }
             | TypedExpr::RecordAccess { .. }
             | TypedExpr::ModuleSelect { .. }
-            | TypedExpr::Block { .. },
+            | TypedExpr::Block { .. }
+            | TypedExpr::Echo { .. }
+            | TypedExpr::Invalid { .. },
         ) => true,
 
         Statement::Expression(
@@ -322,6 +322,22 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
                 Ok(())
             }
 
+            Pattern::Constant { constructor, .. } => {
+                let value = match constructor
+                    .as_ref()
+                    .and_then(|constructor| constructor.pattern_literal())
+                {
+                    Some(Constant::Int { value, .. }) => expression::int(value),
+                    Some(Constant::Float { value, .. }) => expression::float(value),
+                    Some(Constant::String { value, .. }) => expression::string(value),
+                    _ => {
+                        panic!("JavaScript generation performed with non literal constant pattern")
+                    }
+                };
+                self.push_equality_check(subject.clone(), value);
+                Ok(())
+            }
+
             Pattern::Discard { .. } => Ok(()),
 
             Pattern::Variable { name, .. } => {
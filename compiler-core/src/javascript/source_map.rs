@@ -0,0 +1,117 @@
+//! Source maps for the generated JavaScript, so that stack traces and
+//! debuggers can point to the Gleam code the JavaScript was generated from.
+//!
+//! The format is described by the [source map specification][0]. Each
+//! module has a source map of its own, with the Gleam module as its only
+//! source.
+//!
+//! [0]: https://tc39.es/source-map/
+
+use itertools::Itertools;
+
+use crate::{
+    line_numbers::{LineNumbers, PositionEncoding},
+    pretty::SourceMapping,
+};
+
+#[cfg(test)]
+mod tests;
+
+const BASE64_DIGITS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// The JSON of the source map for the code generated into `file` from the
+/// Gleam code `src`, which can be found at `source`.
+pub fn source_map(
+    file: &str,
+    source: &str,
+    src: &str,
+    line_numbers: &LineNumbers,
+    mappings: &[SourceMapping],
+) -> String {
+    serde_json::json!({
+        "version": 3,
+        "file": file,
+        "sources": [source],
+        "sourcesContent": [src],
+        "names": [],
+        "mappings": encode_mappings(line_numbers, mappings),
+    })
+    .to_string()
+}
+
+/// The mappings are written as a segment for each mapping, with the
+/// segments of each line of the generated code separated by commas and the
+/// lines separated by semicolons. Each number of a segment is relative to the
+/// one of the segment before it, except the generated column which starts
+/// again from 0 on each line.
+fn encode_mappings(line_numbers: &LineNumbers, mappings: &[SourceMapping]) -> String {
+    let mut encoded = String::new();
+    let mut line = 0;
+    let mut first_segment_of_line = true;
+    let mut previous_column = 0;
+    let mut previous_source_line = 0;
+    let mut previous_source_column = 0;
+
+    // When several locations start at the same place in the generated code
+    // the last one is the most precise, as it is nested in the others.
+    let mappings = mappings.iter().coalesce(|previous, next| {
+        if (previous.line, previous.column) == (next.line, next.column) {
+            Ok(next)
+        } else {
+            Err((previous, next))
+        }
+    });
+
+    for mapping in mappings {
+        while line < mapping.line {
+            encoded.push(';');
+            line += 1;
+            first_segment_of_line = true;
+            previous_column = 0;
+        }
+        if !first_segment_of_line {
+            encoded.push(',');
+        }
+        first_segment_of_line = false;
+
+        let position =
+            line_numbers.line_and_column_number_in(mapping.byte_index, PositionEncoding::Utf16);
+        let source_line = i64::from(position.line) - 1;
+        let source_column = i64::from(position.column) - 1;
+        let column = i64::from(mapping.column);
+
+        encode_vlq(&mut encoded, column - previous_column);
+        // The index of the source, which is always the Gleam module.
+        encode_vlq(&mut encoded, 0);
+        encode_vlq(&mut encoded, source_line - previous_source_line);
+        encode_vlq(&mut encoded, source_column - previous_source_column);
+
+        previous_column = column;
+        previous_source_line = source_line;
+        previous_source_column = source_column;
+    }
+    encoded
+}
+
+/// Appends a number in the base 64 variable length quantity encoding of
+/// source maps: five bits at a time starting with the least significant,
+/// with the sign in the lowest bit of the first digit and the sixth bit of
+/// each digit set when there are more digits to follow.
+fn encode_vlq(encoded: &mut String, number: i64) {
+    let mut value = (number.unsigned_abs() << 1) | u64::from(number < 0);
+    loop {
+        let mut digit = value & 0b11111;
+        value >>= 5;
+        if value > 0 {
+            digit |= 0b100000;
+        }
+        let digit = BASE64_DIGITS
+            .get(digit as usize)
+            .expect("VLQ digit is less than 64");
+        encoded.push(char::from(*digit));
+        if value == 0 {
+            return;
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use camino::Utf8Path;
+
+use crate::{
+    analyse::TargetSupport,
+    javascript::{module_with_source_map, tests::compile, TypeScriptDeclarations},
+    line_numbers::LineNumbers,
+};
+
+use super::encode_vlq;
+
+fn vlq(number: i64) -> String {
+    let mut encoded = String::new();
+    encode_vlq(&mut encoded, number);
+    encoded
+}
+
+fn compile_with_source_map(src: &str) -> (String, serde_json::Value) {
+    let module = compile(src, vec![]);
+    let line_numbers = LineNumbers::new(src);
+    let (code, source_map) = module_with_source_map(
+        &module,
+        &line_numbers,
+        Utf8Path::new("/project/src/my/mod.gleam"),
+        &src.into(),
+        TargetSupport::NotEnforced,
+        TypeScriptDeclarations::None,
+        Utf8Path::new("/project/build/my/mod.mjs"),
+    )
+    .unwrap();
+    (code, serde_json::from_str(&source_map).unwrap())
+}
+
+#[test]
+fn vlq_encoding() {
+    assert_eq!(vlq(0), "A");
+    assert_eq!(vlq(1), "C");
+    assert_eq!(vlq(-1), "D");
+    assert_eq!(vlq(15), "e");
+    assert_eq!(vlq(16), "gB");
+    assert_eq!(vlq(123), "2H");
+}
+
+#[test]
+fn module_links_to_its_source_map() {
+    let (code, _) = compile_with_source_map("fn main() {\n  1\n}\n");
+    assert_eq!(
+        code,
+        "function main() {\n  return 1;\n}\n//# sourceMappingURL=mod.mjs.map\n"
+    );
+}
+
+#[test]
+fn source_map_maps_functions_and_statements() {
+    let src = "fn main() {\n  1\n}\n";
+    let (_, source_map) = compile_with_source_map(src);
+    assert_eq!(
+        source_map,
+        serde_json::json!({
+            "version": 3,
+            "file": "mod.mjs",
+            "sources": ["../../src/my/mod.gleam"],
+            "sourcesContent": [src],
+            "names": [],
+            "mappings": "AAAA;EACE",
+        })
+    );
+}
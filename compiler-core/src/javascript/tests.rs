@@ -15,6 +15,8 @@ mod bools;
 mod case;
 mod case_clause_guards;
 mod custom_types;
+mod echo;
+mod entrypoint;
 mod externals;
 mod functions;
 mod generics;
@@ -23,6 +25,7 @@ mod modules;
 mod numbers;
 mod panic;
 mod prelude;
+mod purity;
 mod records;
 mod recursion;
 mod results;
@@ -0,0 +1,82 @@
+use crate::assert_js;
+
+#[test]
+fn echo() {
+    assert_js!(
+        r#"
+pub fn main() {
+  echo 1
+}
+"#,
+    );
+}
+
+#[test]
+fn echo_of_expression() {
+    assert_js!(
+        r#"
+pub fn main() {
+  let x = echo 1 + 2
+  x
+}
+"#,
+    );
+}
+
+#[test]
+fn echo_in_pipeline() {
+    assert_js!(
+        r#"
+pub fn main() {
+  [1, 2, 3]
+  |> echo
+  |> wibble
+}
+
+fn wibble(x) {
+  x
+}
+"#,
+    );
+}
+
+#[test]
+fn echo_ending_pipeline_followed_by_statement() {
+    assert_js!(
+        r#"
+pub fn main() {
+  1 |> echo
+  Nil
+}
+"#,
+    );
+}
+
+#[test]
+fn echo_of_case() {
+    assert_js!(
+        r#"
+pub fn main(x) {
+  echo case x {
+    1 -> "one"
+    _ -> "other"
+  }
+}
+"#,
+    );
+}
+
+#[test]
+fn echo_as_function_argument() {
+    assert_js!(
+        r#"
+pub fn main() {
+  wibble(echo 1, 2)
+}
+
+fn wibble(x, y) {
+  x + y
+}
+"#,
+    );
+}
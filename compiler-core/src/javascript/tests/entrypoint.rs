@@ -0,0 +1,33 @@
+use crate::javascript::entrypoint;
+
+#[test]
+fn entrypoint_calling_main() {
+    assert_eq!(
+        entrypoint("app", "main", false),
+        r#"import { main } from "./app.mjs";
+main();
+"#
+    );
+}
+
+#[test]
+fn entrypoint_passing_arguments() {
+    assert_eq!(
+        entrypoint("app/cli", "run", true),
+        r#"import { toList } from "./gleam.mjs";
+import { run } from "./app/cli.mjs";
+const args = globalThis.Deno ? Deno.args : process.argv.slice(2);
+run(toList(args));
+"#
+    );
+}
+
+#[test]
+fn entrypoint_escapes_function_name() {
+    assert_eq!(
+        entrypoint("app", "delete", false),
+        r#"import { delete$ } from "./app.mjs";
+delete$();
+"#
+    );
+}
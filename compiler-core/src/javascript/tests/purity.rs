@@ -0,0 +1,115 @@
+use crate::{assert_js, javascript::tests::CURRENT_PACKAGE};
+
+#[test]
+fn pure_function_call_is_annotated() {
+    assert_js!(
+        r#"
+fn add(x, y) {
+  x + y
+}
+
+pub fn main() {
+  add(1, 2)
+}
+"#
+    );
+}
+
+#[test]
+fn imported_pure_function_call_is_annotated() {
+    assert_js!(
+        (CURRENT_PACKAGE, "maths", r#"pub fn double(x) { x * 2 }"#),
+        r#"
+import maths.{double}
+
+pub fn main() {
+  maths.double(1)
+  double(2)
+}
+"#
+    );
+}
+
+#[test]
+fn panicking_function_call_is_not_annotated() {
+    assert_js!(
+        r#"
+fn fail() {
+  panic
+}
+
+pub fn main() {
+  fail()
+}
+"#
+    );
+}
+
+#[test]
+fn external_function_call_is_not_annotated() {
+    assert_js!(
+        r#"
+@external(javascript, "./ffi.mjs", "log")
+fn log(x: Int) -> Nil
+
+pub fn main() {
+  log(1)
+}
+"#
+    );
+}
+
+#[test]
+fn function_calling_argument_is_not_annotated() {
+    assert_js!(
+        r#"
+fn apply(f, x) {
+  f(x)
+}
+
+pub fn main() {
+  apply(fn(x) { x }, 1)
+}
+"#
+    );
+}
+
+#[test]
+fn mutually_recursive_impure_function_calls_are_not_annotated() {
+    assert_js!(
+        r#"
+fn wibble(x) {
+  case x {
+    0 -> panic
+    _ -> wobble(x - 1)
+  }
+}
+
+fn wobble(x) {
+  wibble(x)
+}
+
+pub fn main() {
+  wibble(1)
+}
+"#
+    );
+}
+
+#[test]
+fn annotated_call_being_called_is_wrapped_in_parentheses() {
+    assert_js!(
+        r#"
+@external(javascript, "./ffi.mjs", "log")
+fn log(x: Int) -> Nil
+
+fn logger() {
+  log
+}
+
+pub fn main() {
+  logger()(1)
+}
+"#
+    );
+}
@@ -1,12 +1,33 @@
+mod call_hierarchy;
 mod code_action;
+mod code_lens;
+mod compiled_code;
 mod compiler;
+mod configuration;
+mod diagnostics;
+mod document;
+mod document_link;
 mod engine;
+mod external;
+mod extract;
 mod feedback;
 mod files;
+mod fill_labels;
+mod folding_range;
+mod formatting;
+mod generate_function;
+mod inlay_hints;
 mod messages;
 mod progress;
+mod reference;
+mod rename;
 mod router;
+mod semantic_tokens;
 mod server;
+mod signature_help;
+mod type_definition;
+mod use_callback;
+mod workspace_symbol;
 
 #[cfg(test)]
 mod tests;
@@ -14,10 +35,14 @@ mod tests;
 pub use server::LanguageServer;
 
 use crate::{
-    ast::SrcSpan, build::Target, line_numbers::LineNumbers, manifest::Manifest,
-    paths::ProjectPaths, Result,
+    ast::SrcSpan,
+    build::Target,
+    line_numbers::{LineNumbers, PositionEncoding},
+    manifest::Manifest,
+    paths::ProjectPaths,
+    Result,
 };
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use lsp_types::{Position, Range, Url};
 use std::any::Any;
 
@@ -36,9 +61,19 @@ pub trait DownloadDependencies {
     fn download_dependencies(&self, paths: &ProjectPaths) -> Result<Manifest>;
 }
 
-pub fn src_span_to_lsp_range(location: SrcSpan, line_numbers: &LineNumbers) -> Range {
-    let start = line_numbers.line_and_column_number(location.start);
-    let end = line_numbers.line_and_column_number(location.end);
+pub trait RunGleamCommand {
+    /// Runs `gleam` with the arguments in the root of the project, without
+    /// waiting for it to finish.
+    fn run_gleam_command(&self, paths: &ProjectPaths, arguments: Vec<String>) -> Result<()>;
+}
+
+pub fn src_span_to_lsp_range(
+    location: SrcSpan,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
+) -> Range {
+    let start = line_numbers.line_and_column_number_in(location.start, encoding);
+    let end = line_numbers.line_and_column_number_in(location.end, encoding);
 
     Range::new(
         Position::new(start.line - 1, start.column - 1),
@@ -55,3 +90,12 @@ fn path(uri: &Url) -> Utf8PathBuf {
     #[cfg(not(any(unix, windows, target_os = "redox", target_os = "wasi")))]
     return Utf8PathBuf::from_path_buf(uri.path().into()).expect("Non Utf8 Path");
 }
+
+fn url(path: &Utf8Path) -> Option<Url> {
+    // The from_file_path method is available on these platforms
+    #[cfg(any(unix, windows, target_os = "redox", target_os = "wasi"))]
+    return Url::from_file_path(path).ok();
+
+    #[cfg(not(any(unix, windows, target_os = "redox", target_os = "wasi")))]
+    return Url::parse(&format!("file://{path}")).ok();
+}
@@ -0,0 +1,139 @@
+//! The call hierarchy of the functions of the root package: the functions
+//! that call a function and the functions it calls, across the modules of the
+//! package. Calls are found with the index of references of each module, so a
+//! function referred to without being called, such as one given as an
+//! argument, counts as called where it is referred to.
+
+use std::collections::HashMap;
+
+use ecow::EcoString;
+use itertools::Itertools;
+
+use crate::{
+    ast::{Definition, SrcSpan, TypedFunction},
+    build::Module,
+};
+
+use super::reference::{ReferenceKind, Referenced, References};
+
+/// A function of the root package, and where it calls or is called by
+/// another function.
+pub struct Calls<'a> {
+    pub module: &'a Module,
+    pub function: &'a TypedFunction,
+    pub locations: Vec<SrcSpan>,
+}
+
+/// The module and definition of the function referred to, if it is defined
+/// in a module of the root package.
+pub fn function<'a>(
+    modules: &'a HashMap<EcoString, Module>,
+    referenced: &Referenced,
+) -> Option<(&'a Module, &'a TypedFunction)> {
+    let Referenced::Value { module, name } = referenced else {
+        return None;
+    };
+    let module = modules.get(module)?;
+    let function = module
+        .ast
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Function(function) if function.name == *name => Some(function),
+            _ => None,
+        })?;
+    Some((module, function))
+}
+
+/// The location of the whole of a function, from its head to the end of its
+/// body.
+pub fn function_location(function: &TypedFunction) -> SrcSpan {
+    SrcSpan::new(function.location.start, function.end_position)
+}
+
+/// The functions of the root package calling the function, ordered by module
+/// and by where they are defined.
+pub fn incoming<'a>(
+    modules: &'a HashMap<EcoString, Module>,
+    references: &HashMap<EcoString, References>,
+    referenced: &Referenced,
+) -> Vec<Calls<'a>> {
+    let mut calls: Vec<Calls<'a>> = vec![];
+    for module in modules.values().sorted_by_key(|module| &module.name) {
+        let Some(references) = references.get(&module.name) else {
+            continue;
+        };
+        let usages = references
+            .get(referenced)
+            .iter()
+            .filter(|reference| reference.kind == ReferenceKind::Usage);
+        // The references are ordered by location, so the calls from the same
+        // function follow each other.
+        for usage in usages {
+            // Constants can refer to functions too, but they don't call them
+            let Some(caller) = enclosing_function(module, usage.location) else {
+                continue;
+            };
+            match calls.last_mut() {
+                Some(last) if std::ptr::eq(last.function, caller) => {
+                    last.locations.push(usage.location)
+                }
+                _ => calls.push(Calls {
+                    module,
+                    function: caller,
+                    locations: vec![usage.location],
+                }),
+            }
+        }
+    }
+    calls
+}
+
+/// The functions of the root package the function calls, ordered by where
+/// they are first called.
+pub fn outgoing<'a>(
+    modules: &'a HashMap<EcoString, Module>,
+    references: &References,
+    function: &TypedFunction,
+) -> Vec<Calls<'a>> {
+    let location = function_location(function);
+    references
+        .iter()
+        .filter_map(|(referenced, references)| {
+            let locations = references
+                .iter()
+                .filter(|reference| {
+                    reference.kind == ReferenceKind::Usage
+                        && location.start <= reference.location.start
+                        && reference.location.end <= location.end
+                })
+                .map(|reference| reference.location)
+                .collect_vec();
+            if locations.is_empty() {
+                return None;
+            }
+            let (module, function) = self::function(modules, referenced)?;
+            Some(Calls {
+                module,
+                function,
+                locations,
+            })
+        })
+        .sorted_by_key(|calls| calls.locations.first().map(|location| location.start))
+        .collect()
+}
+
+fn enclosing_function(module: &Module, location: SrcSpan) -> Option<&TypedFunction> {
+    module
+        .ast
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Function(function) => {
+                let function_location = function_location(function);
+                (function_location.start <= location.start && location.end <= function_location.end)
+                    .then_some(function)
+            }
+            _ => None,
+        })
+}
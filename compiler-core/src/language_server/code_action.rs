@@ -1,4 +1,26 @@
-use lsp_types::{CodeAction, Url};
+use std::collections::HashSet;
+
+use ecow::EcoString;
+use lsp_types::{self as lsp, CodeAction, Url};
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        AssignName, Definition, Import, Pattern, SrcSpan, TypeAst, TypedArg, TypedFunction,
+        TypedModule, TypedPattern,
+    },
+    build::Module,
+    line_numbers::{LineNumbers, PositionEncoding},
+    type_::{
+        pretty::{Printer, TypeNames},
+        Type, TypeVar,
+    },
+};
+
+use super::{
+    reference::{Names, ReferenceKind, Referenced, References},
+    src_span_to_lsp_range, type_definition,
+};
 
 #[derive(Debug)]
 pub struct CodeActionBuilder {
@@ -36,6 +58,11 @@ impl CodeActionBuilder {
         self
     }
 
+    pub fn diagnostics(mut self, diagnostics: Vec<lsp_types::Diagnostic>) -> Self {
+        self.action.diagnostics = Some(diagnostics);
+        self
+    }
+
     pub fn preferred(mut self, is_preferred: bool) -> Self {
         self.action.is_preferred = Some(is_preferred);
         self
@@ -45,3 +72,448 @@ impl CodeActionBuilder {
         actions.push(self.action);
     }
 }
+
+/// Code actions to switch between using a value imported from another module
+/// qualified with the module, such as `result.map`, and importing it
+/// unqualified to use it by its name alone, such as `map`. Every use of the
+/// value in the module is changed along with the import of the module.
+pub struct QualifiedValue<'a> {
+    module: &'a Module,
+    references: &'a References,
+    params: &'a lsp::CodeActionParams,
+    line_numbers: &'a LineNumbers,
+    encoding: PositionEncoding,
+}
+
+impl<'a> QualifiedValue<'a> {
+    pub fn new(
+        module: &'a Module,
+        references: &'a References,
+        params: &'a lsp::CodeActionParams,
+        line_numbers: &'a LineNumbers,
+        encoding: PositionEncoding,
+    ) -> Self {
+        Self {
+            module,
+            references,
+            params,
+            line_numbers,
+            encoding,
+        }
+    }
+
+    pub fn code_actions(self) -> Vec<CodeAction> {
+        let byte_index = self.line_numbers.byte_index(
+            self.params.range.start.line,
+            self.params.range.start.character,
+            self.encoding,
+        );
+        let Some(referenced) = self.references.at(byte_index) else {
+            return vec![];
+        };
+        let Referenced::Value { module, name } = referenced else {
+            return vec![];
+        };
+        if *module == self.module.name {
+            return vec![];
+        }
+        let Some(import) = self.import(module) else {
+            return vec![];
+        };
+
+        let mut actions = vec![];
+        let at_qualified = self.references.get(referenced).iter().any(|reference| {
+            reference.location.contains(byte_index)
+                && self.qualifier(reference.location.start).is_some()
+        });
+        if at_qualified {
+            if let Some(edits) = self.unqualify(import, referenced, name) {
+                CodeActionBuilder::new(&format!("Import `{name}` unqualified"))
+                    .kind(lsp::CodeActionKind::REFACTOR_REWRITE)
+                    .changes(self.params.text_document.uri.clone(), edits)
+                    .push_to(&mut actions);
+            }
+        } else if let Some(edits) = self.qualify(import, referenced, name) {
+            CodeActionBuilder::new(&format!("Qualify `{name}` with its module"))
+                .kind(lsp::CodeActionKind::REFACTOR_REWRITE)
+                .changes(self.params.text_document.uri.clone(), edits)
+                .push_to(&mut actions);
+        }
+        actions
+    }
+
+    /// Removes the module alias from every qualified use of the value, and
+    /// adds the value to the unqualified imports of the module. Nothing is
+    /// done if the name of the value is already used by something else.
+    fn unqualify(
+        &self,
+        import: &Import<EcoString>,
+        referenced: &Referenced,
+        name: &EcoString,
+    ) -> Option<Vec<lsp::TextEdit>> {
+        let is_imported = match Names::new(&self.module.ast).value(None, name) {
+            Some(other) if other == *referenced => true,
+            Some(_) => return None,
+            None => false,
+        };
+        if local_variables(&self.module.ast).contains(name) {
+            return None;
+        }
+
+        let mut edits = vec![];
+        for reference in self.references.get(referenced) {
+            if reference.kind != ReferenceKind::Usage {
+                continue;
+            }
+            if let Some(start) = self.qualifier(reference.location.start) {
+                edits.push(self.edit(SrcSpan::new(start, reference.location.start), ""));
+            }
+        }
+        if !is_imported {
+            let items = import
+                .unqualified_values
+                .iter()
+                .chain(&import.unqualified_types)
+                .map(|item| item.location.end)
+                .max();
+            match items {
+                Some(end) => edits.push(self.edit(SrcSpan::new(end, end), &format!(", {name}"))),
+                None => {
+                    let module_end = self.module_path_end(import)?;
+                    let after = self.module.code.get(module_end as usize..)?;
+                    let (position, text) = if after.starts_with(".{") {
+                        (module_end + 2, name.to_string())
+                    } else {
+                        (module_end, format!(".{{{name}}}"))
+                    };
+                    edits.push(self.edit(SrcSpan::new(position, position), &text));
+                }
+            }
+        }
+        Some(sorted(edits))
+    }
+
+    /// Qualifies every unqualified use of the value with the alias of its
+    /// module, and removes the value from the unqualified imports of it.
+    fn qualify(
+        &self,
+        import: &Import<EcoString>,
+        referenced: &Referenced,
+        name: &EcoString,
+    ) -> Option<Vec<lsp::TextEdit>> {
+        let alias = import.used_name()?;
+        let mut items = import
+            .unqualified_values
+            .iter()
+            .chain(&import.unqualified_types)
+            .map(|item| item.location)
+            .collect::<Vec<_>>();
+        items.sort_by_key(|location| location.start);
+        let item = import
+            .unqualified_values
+            .iter()
+            .find(|value| &value.name == name)?;
+
+        let code = &self.module.code;
+        let mut edits = vec![];
+        for reference in self.references.get(referenced) {
+            let location = reference.location;
+            if reference.kind != ReferenceKind::Usage || self.qualifier(location.start).is_some() {
+                continue;
+            }
+            // A label shorthand such as `name:` keeps its label
+            let is_shorthand = code
+                .get(location.end as usize..)
+                .is_some_and(|after| after.starts_with(':'));
+            if is_shorthand {
+                let label = code.get(location.start as usize..location.end as usize)?;
+                let location = SrcSpan::new(location.start, location.end + 1);
+                edits.push(self.edit(location, &format!("{label}: {alias}.{name}")));
+            } else {
+                edits.push(self.edit(location, &format!("{alias}.{name}")));
+            }
+        }
+
+        // The item is removed along with the comma separating it from the
+        // others, or with the braces if it is the only one.
+        let index = items
+            .iter()
+            .position(|location| *location == item.location)?;
+        let removed = match (index.checked_sub(1), items.get(index + 1)) {
+            (_, Some(next)) => SrcSpan::new(item.location.start, next.start),
+            (Some(previous), None) => SrcSpan::new(items.get(previous)?.end, item.location.end),
+            (None, None) => {
+                let module_end = self.module_path_end(import)?;
+                let after = code.get(module_end as usize..import.location.end as usize)?;
+                SrcSpan::new(module_end, module_end + after.find('}')? as u32 + 1)
+            }
+        };
+        edits.push(self.edit(removed, ""));
+        Some(sorted(edits))
+    }
+
+    fn import(&self, module: &str) -> Option<&'a Import<EcoString>> {
+        self.module
+            .ast
+            .definitions
+            .iter()
+            .find_map(|definition| match definition {
+                Definition::Import(import) if import.module == module => Some(import),
+                _ => None,
+            })
+    }
+
+    /// The start of the module alias a name at the position is qualified
+    /// with, such as `result` in `result.map`.
+    fn qualifier(&self, name_start: u32) -> Option<u32> {
+        let before = self
+            .module
+            .code
+            .get(..name_start as usize)?
+            .strip_suffix('.')?;
+        let start = before
+            .trim_end_matches(|char: char| char.is_ascii_alphanumeric() || char == '_')
+            .len();
+        (start < before.len()).then_some(start as u32)
+    }
+
+    /// The end of the path of the module in an import, such as
+    /// `import gleam/result`.
+    fn module_path_end(&self, import: &Import<EcoString>) -> Option<u32> {
+        let location = import.location;
+        let text = self
+            .module
+            .code
+            .get(location.start as usize..location.end as usize)?;
+        let start = text.find(import.module.as_str())?;
+        Some(location.start + (start + import.module.len()) as u32)
+    }
+
+    fn edit(&self, location: SrcSpan, new_text: &str) -> lsp::TextEdit {
+        lsp::TextEdit {
+            range: src_span_to_lsp_range(location, self.line_numbers, self.encoding),
+            new_text: new_text.into(),
+        }
+    }
+}
+
+/// Code actions adding the types the compiler inferred for the arguments and
+/// return value of a function as annotations to its head, either for the
+/// function at the cursor or for every public function of the module.
+pub struct AddAnnotations<'a> {
+    module: &'a Module,
+    params: &'a lsp::CodeActionParams,
+    line_numbers: &'a LineNumbers,
+    encoding: PositionEncoding,
+}
+
+impl<'a> AddAnnotations<'a> {
+    pub fn new(
+        module: &'a Module,
+        params: &'a lsp::CodeActionParams,
+        line_numbers: &'a LineNumbers,
+        encoding: PositionEncoding,
+    ) -> Self {
+        Self {
+            module,
+            params,
+            line_numbers,
+            encoding,
+        }
+    }
+
+    pub fn code_actions(self) -> Vec<CodeAction> {
+        let byte_index = self.line_numbers.byte_index(
+            self.params.range.start.line,
+            self.params.range.start.character,
+            self.encoding,
+        );
+        let functions = self
+            .module
+            .ast
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Function(function) => Some(function),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let Some(function) = functions
+            .iter()
+            .find(|function| function.location.contains(byte_index))
+        else {
+            return vec![];
+        };
+
+        let names = TypeNames::new(&self.module.ast);
+        let mut actions = vec![];
+        if let Some(edits) = self.edits(function, &names) {
+            CodeActionBuilder::new("Add type annotations")
+                .kind(lsp::CodeActionKind::REFACTOR_REWRITE)
+                .changes(self.params.text_document.uri.clone(), edits)
+                .push_to(&mut actions);
+        }
+
+        let public = functions
+            .iter()
+            .filter(|function| function.publicity.is_public())
+            .filter_map(|function| self.edits(function, &names))
+            .collect::<Vec<_>>();
+        if public.len() > 1 {
+            CodeActionBuilder::new("Add type annotations to all public functions")
+                .kind(lsp::CodeActionKind::REFACTOR_REWRITE)
+                .changes(
+                    self.params.text_document.uri.clone(),
+                    public.into_iter().flatten().collect(),
+                )
+                .push_to(&mut actions);
+        }
+        actions
+    }
+
+    /// The edits annotating the arguments and return type of a function that
+    /// aren't annotated, if there are any and every type they need can be
+    /// referred to in the module.
+    fn edits(&self, function: &TypedFunction, names: &TypeNames) -> Option<Vec<lsp::TextEdit>> {
+        let unannotated = function
+            .arguments
+            .iter()
+            .filter(|arg| arg.annotation.is_none())
+            .collect::<Vec<_>>();
+        let return_unannotated = function.return_annotation.is_none();
+        if unannotated.is_empty() && !return_unannotated {
+            return None;
+        }
+
+        let types = unannotated
+            .iter()
+            .map(|arg| &arg.type_)
+            .chain(return_unannotated.then_some(&function.return_type));
+        for type_ in types {
+            let is_in_scope = type_definition::named_types(type_)
+                .iter()
+                .all(|(module, name)| names.is_in_scope(module, name));
+            if !is_in_scope {
+                return None;
+            }
+        }
+
+        // The type variables already named in annotations keep their names
+        let mut type_variables = im::HashMap::new();
+        for arg in &function.arguments {
+            if let Some(annotation) = &arg.annotation {
+                type_variable_names(annotation, &arg.type_, &mut type_variables);
+            }
+        }
+        if let Some(annotation) = &function.return_annotation {
+            type_variable_names(annotation, &function.return_type, &mut type_variables);
+        }
+        let mut printer = Printer::new();
+        printer.with_names(type_variables);
+        printer.with_type_names(names.clone());
+
+        let mut edits = vec![];
+        for arg in unannotated {
+            let end = arg.location.end;
+            let type_ = printer.pretty_print(&arg.type_, 0);
+            edits.push(self.edit(SrcSpan::new(end, end), &format!(": {type_}")));
+        }
+        if return_unannotated {
+            let end = function.location.end;
+            let type_ = printer.pretty_print(&function.return_type, 0);
+            edits.push(self.edit(SrcSpan::new(end, end), &format!(" -> {type_}")));
+        }
+        Some(edits)
+    }
+
+    fn edit(&self, location: SrcSpan, new_text: &str) -> lsp::TextEdit {
+        lsp::TextEdit {
+            range: src_span_to_lsp_range(location, self.line_numbers, self.encoding),
+            new_text: new_text.into(),
+        }
+    }
+}
+
+/// Finds the names the type variables of a type are given in an annotation
+/// of it.
+fn type_variable_names(
+    annotation: &TypeAst,
+    type_: &Type,
+    names: &mut im::HashMap<u64, EcoString>,
+) {
+    match (annotation, type_) {
+        (_, Type::Var { type_ }) => match &*type_.borrow() {
+            TypeVar::Link { type_ } => type_variable_names(annotation, type_, names),
+            TypeVar::Unbound { id } | TypeVar::Generic { id } => {
+                if let TypeAst::Var(var) = annotation {
+                    _ = names.insert(*id, var.name.clone());
+                }
+            }
+        },
+        (TypeAst::Constructor(constructor), Type::Named { args, .. }) => {
+            for (annotation, type_) in constructor.arguments.iter().zip(args) {
+                type_variable_names(annotation, type_, names);
+            }
+        }
+        (TypeAst::Fn(fn_), Type::Fn { args, retrn }) => {
+            for (annotation, type_) in fn_.arguments.iter().zip(args) {
+                type_variable_names(annotation, type_, names);
+            }
+            type_variable_names(&fn_.return_, retrn, names);
+        }
+        (TypeAst::Tuple(tuple), Type::Tuple { elems }) => {
+            for (annotation, type_) in tuple.elems.iter().zip(elems) {
+                type_variable_names(annotation, type_, names);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn sorted(mut edits: Vec<lsp::TextEdit>) -> Vec<lsp::TextEdit> {
+    edits.sort_by_key(|edit| edit.range.start);
+    edits
+}
+
+/// The names of every local variable defined in the module.
+pub fn local_variables(module: &TypedModule) -> HashSet<EcoString> {
+    let mut finder = LocalVariables::default();
+    finder.visit_typed_module(module);
+    finder.names
+}
+
+#[derive(Default)]
+struct LocalVariables {
+    names: HashSet<EcoString>,
+}
+
+impl<'ast> Visit<'ast> for LocalVariables {
+    fn visit_typed_arg(&mut self, arg: &'ast TypedArg) {
+        if let Some(name) = arg.names.get_variable_name() {
+            _ = self.names.insert(name.clone());
+        }
+        visit::visit_typed_arg(self, arg);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        match pattern {
+            Pattern::Variable { name, .. } | Pattern::Assign { name, .. } => {
+                _ = self.names.insert(name.clone());
+            }
+            Pattern::StringPrefix {
+                left_side_assignment,
+                right_side_assignment,
+                ..
+            } => {
+                if let Some((name, _)) = left_side_assignment {
+                    _ = self.names.insert(name.clone());
+                }
+                if let AssignName::Variable(name) = right_side_assignment {
+                    _ = self.names.insert(name.clone());
+                }
+            }
+            _ => (),
+        }
+        visit::visit_typed_pattern(self, pattern);
+    }
+}
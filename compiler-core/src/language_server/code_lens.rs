@@ -0,0 +1,105 @@
+//! Code lenses to run code from the editor: a "Run test" lens above each
+//! function of a test module, and a "Run" lens above a `main` function.
+//!
+//! The lenses run the `gleam.run` command, which the server executes by
+//! running `gleam run` or `gleam test` for the project the module is in. Only
+//! public functions without arguments are given a lens, as nothing else can
+//! be run.
+
+use ecow::EcoString;
+use lsp_types as lsp;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast::Definition,
+    build::{Module, Origin},
+    line_numbers::{LineNumbers, PositionEncoding},
+};
+
+use super::src_span_to_lsp_range;
+
+/// The command the lenses run.
+pub const RUN_COMMAND: &str = "gleam.run";
+
+/// The argument of the `gleam.run` command: the function to run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunParams {
+    /// The module the function is defined in, used to find its project.
+    pub uri: lsp::Url,
+    pub module: EcoString,
+    pub function: EcoString,
+    /// Whether the function is a test, which is run with `gleam test` rather
+    /// than `gleam run`.
+    pub test: bool,
+}
+
+impl RunParams {
+    /// The function to run given to the `gleam.run` command, if the command
+    /// is that one.
+    pub fn from_command(params: &lsp::ExecuteCommandParams) -> Option<Self> {
+        if params.command != RUN_COMMAND {
+            return None;
+        }
+        serde_json::from_value(params.arguments.first()?.clone()).ok()
+    }
+
+    /// The arguments to give the `gleam` command line to run the function.
+    pub fn arguments(&self) -> Vec<String> {
+        if self.test {
+            vec![
+                "test".into(),
+                "--module".into(),
+                self.module.to_string(),
+                "--filter".into(),
+                format!("{}.{}", self.module, self.function),
+            ]
+        } else {
+            vec!["run".into(), "--module".into(), self.module.to_string()]
+        }
+    }
+}
+
+/// The lenses for the functions of the module that can be run.
+pub fn code_lenses(
+    module: &Module,
+    uri: &lsp::Url,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
+) -> Vec<lsp::CodeLens> {
+    module
+        .ast
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Function(function)
+                if function.publicity.is_public() && function.arguments.is_empty() =>
+            {
+                Some(function)
+            }
+            _ => None,
+        })
+        .filter_map(|function| {
+            let (title, test) = match (&module.origin, function.name.as_str()) {
+                (_, "main") => ("Run", false),
+                (Origin::Test, _) => ("Run test", true),
+                (Origin::Src, _) => return None,
+            };
+            let params = RunParams {
+                uri: uri.clone(),
+                module: module.name.clone(),
+                function: function.name.clone(),
+                test,
+            };
+            Some(lsp::CodeLens {
+                range: src_span_to_lsp_range(function.location, line_numbers, encoding),
+                command: Some(lsp::Command {
+                    title: title.into(),
+                    command: RUN_COMMAND.into(),
+                    arguments: Some(vec![serde_json::to_value(params).ok()?]),
+                }),
+                data: None,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,75 @@
+//! The `gleam/showCompiledCode` request, which isn't part of the language
+//! server protocol. It gives the Erlang or JavaScript a module of the root
+//! package is compiled to, for editors to show as a preview of the compiled
+//! output next to the module.
+//!
+//! The language server doesn't generate code when it compiles the project,
+//! so the code of the module is generated from its typed AST when asked for.
+
+use lsp_types as lsp;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analyse::TargetSupport,
+    build::{Module, Target},
+    codegen::TypeScriptDeclarations,
+    erlang, javascript,
+    line_numbers::LineNumbers,
+    Result,
+};
+
+#[derive(Debug)]
+pub enum ShowCompiledCode {}
+
+impl lsp::request::Request for ShowCompiledCode {
+    type Params = ShowCompiledCodeParams;
+    type Result = Option<CompiledCode>;
+    const METHOD: &'static str = "gleam/showCompiledCode";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShowCompiledCodeParams {
+    pub text_document: lsp::TextDocumentIdentifier,
+    /// The target to compile the module to. The target of the project is
+    /// used if there is none.
+    #[serde(default)]
+    pub target: Option<Target>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompiledCode {
+    pub target: Target,
+    /// The identifier of the language of the code, for editors to highlight
+    /// it with.
+    pub language_id: String,
+    pub code: String,
+}
+
+/// The code the module is compiled to for the target.
+pub fn compiled_code(
+    module: &Module,
+    line_numbers: &LineNumbers,
+    target: Target,
+) -> Result<CompiledCode> {
+    let (language_id, code) = match target {
+        Target::Erlang => ("erlang", erlang::module(&module.ast, line_numbers)?),
+        Target::JavaScript => (
+            "javascript",
+            javascript::module(
+                &module.ast,
+                line_numbers,
+                &module.input_path,
+                &module.code,
+                TargetSupport::NotEnforced,
+                TypeScriptDeclarations::None,
+            )?,
+        ),
+    };
+    Ok(CompiledCode {
+        target,
+        language_id: language_id.into(),
+        code,
+    })
+}
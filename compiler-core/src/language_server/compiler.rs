@@ -1,13 +1,12 @@
 use debug_ignore::DebugIgnore;
 use ecow::EcoString;
-use itertools::Itertools;
 
 use crate::{
     analyse::TargetSupport,
     build::{self, Mode, Module, NullTelemetry, ProjectCompiler},
     config::PackageConfig,
     io::{CommandExecutor, FileSystemReader, FileSystemWriter, Stdio},
-    language_server::Locker,
+    language_server::{reference::References, Locker},
     line_numbers::LineNumbers,
     manifest::Manifest,
     paths::ProjectPaths,
@@ -17,7 +16,7 @@ use crate::{
 };
 use std::{collections::HashMap, sync::Arc};
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 
 /// A wrapper around the project compiler which makes it possible to repeatedly
 /// recompile the top level package, reusing the information about the already
@@ -31,9 +30,20 @@ pub struct LspProjectCompiler<IO> {
     pub modules: HashMap<EcoString, Module>,
     pub sources: HashMap<EcoString, ModuleSourceInformation>,
 
+    /// Where each compiled module refers to the things defined at the top
+    /// level of modules.
+    pub references: HashMap<EcoString, References>,
+
     /// The storage for the warning emitter.
     pub warnings: Arc<VectorWarningEmitterIO>,
 
+    /// The error the root package last failed to compile with, if it did.
+    pub error: Option<Error>,
+
+    /// Modules of dependency packages analysed from their source, by path,
+    /// for the language server features to work in dependency code.
+    pub dependency_modules: HashMap<Utf8PathBuf, Module>,
+
     /// A lock to ensure that multiple instances of the LSP don't try and use
     /// build directory at the same time.
     pub locker: DebugIgnore<Box<dyn Locker>>,
@@ -71,6 +81,7 @@ where
             target: None,
             codegen: build::Codegen::None,
             root_target_support: TargetSupport::Enforced,
+            doc_tests: false,
         };
         let mut project_compiler = ProjectCompiler::new(
             config,
@@ -92,6 +103,9 @@ where
             project_compiler,
             modules: HashMap::new(),
             sources: HashMap::new(),
+            references: HashMap::new(),
+            error: None,
+            dependency_modules: HashMap::new(),
         })
     }
 
@@ -104,14 +118,22 @@ where
         // directory as the cache files may be in a different format.
         self.project_compiler.check_gleam_version()?;
 
-        let compiled_dependencies = self.project_compiler.compile_dependencies()?;
-
-        // Store the compiled dependency module information
-        for module in &compiled_dependencies {
+        // Store the compiled dependency module information. The typed AST of
+        // a dependency module is only needed once it is opened, so the
+        // modules are dropped here rather than kept around while the root
+        // package is compiled. Any dependency modules analysed before may be
+        // out of date if the dependencies had to be compiled again.
+        let mut compiled_modules = vec![];
+        let dependency_modules = self.project_compiler.compile_dependencies()?;
+        if !dependency_modules.is_empty() {
+            self.dependency_modules.clear();
+        }
+        for module in dependency_modules {
             let path = module.input_path.as_os_str().to_string_lossy().to_string();
             let line_numbers = LineNumbers::new(&module.code);
             let source = ModuleSourceInformation { path, line_numbers };
-            _ = self.sources.insert(module.name.clone(), source);
+            _ = self.sources.insert(module.name, source);
+            compiled_modules.push(module.input_path);
         }
 
         // Since cached modules are not recompiled we need to manually add them
@@ -129,23 +151,32 @@ where
             _ = self.sources.insert(name.clone(), source);
         }
 
-        // Warnings from dependencies are not fixable by the programmer so
-        // we don't bother them with diagnostics for them.
-        let _ = self.take_warnings();
-
         // Do that there compilation. We don't use `?` to return early in the
         // event of an error because we _always_ want to do the restoration of
         // state afterwards.
         let result = self.project_compiler.compile_root_package();
 
-        // Return any error
-        let package = result?;
+        // If a module has syntax errors we type check whatever parts of it
+        // could be parsed, so that information about the rest of the module
+        // is still available while it is being edited.
+        if let Err(Error::Parse { path, src, .. }) = &result {
+            if let Some(module) = self.project_compiler.recover_module(path, src.clone()) {
+                let path = module.input_path.as_os_str().to_string_lossy().to_string();
+                let line_numbers = LineNumbers::new(&module.code);
+                let source = ModuleSourceInformation { path, line_numbers };
+                _ = self.sources.insert(module.name.clone(), source);
+                let references = References::new(&module.ast, &module.code);
+                _ = self.references.insert(module.name.clone(), references);
+                _ = self.modules.insert(module.name.clone(), module);
+            }
+        }
 
-        // Record the compiled dependency modules
-        let mut compiled_modules = compiled_dependencies
-            .into_iter()
-            .map(|m| m.input_path)
-            .collect_vec();
+        // Return any error. A cancelled compilation says nothing about the
+        // code, so the error from before is kept.
+        if !matches!(result, Err(Error::Cancelled)) {
+            self.error = result.as_ref().err().cloned();
+        }
+        let package = result?;
 
         // Store the compiled module information
         for module in package.modules {
@@ -154,6 +185,8 @@ where
             let source = ModuleSourceInformation { path, line_numbers };
             compiled_modules.push(module.input_path.clone());
             _ = self.sources.insert(module.name.clone(), source);
+            let references = References::new(&module.ast, &module.code);
+            _ = self.references.insert(module.name.clone(), references);
             _ = self.modules.insert(module.name.clone(), module);
         }
 
@@ -163,6 +196,17 @@ where
     pub fn get_module_inferface(&self, name: &str) -> Option<&ModuleInterface> {
         self.project_compiler.get_importable_modules().get(name)
     }
+
+    /// Analyses the module of a dependency package at the path, unless it
+    /// has been already or the path isn't of a dependency module.
+    pub fn load_dependency_module(&mut self, path: &Utf8Path) {
+        if self.dependency_modules.contains_key(path) {
+            return;
+        }
+        if let Some(module) = self.project_compiler.analyse_dependency_module(path) {
+            _ = self.dependency_modules.insert(path.to_path_buf(), module);
+        }
+    }
 }
 
 impl<IO> LspProjectCompiler<IO> {
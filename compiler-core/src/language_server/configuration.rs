@@ -0,0 +1,100 @@
+//! The settings the client can change the behaviour of the language server
+//! with. They are given as the `initializationOptions` when the connection is
+//! initialised, and again with `workspace/didChangeConfiguration` whenever
+//! they change. Settings the client leaves out keep their default values.
+//!
+//! The configuration is shared by the server and each of its engines, so
+//! handlers always use the latest settings.
+
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use serde::de::DeserializeOwned;
+
+use crate::build::Target;
+
+pub type SharedConfiguration = Arc<RwLock<Configuration>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Configuration {
+    /// Whether hovering over a value imported from a Hex package shows a link
+    /// to its documentation on `HexDocs`.
+    pub hexdocs_links: bool,
+
+    pub inlay_hints: InlayHintsConfiguration,
+
+    /// The target to analyse projects for, instead of the one set in their
+    /// `gleam.toml`.
+    pub target: Option<Target>,
+
+    /// How long to wait in milliseconds for the programmer to stop typing
+    /// before compiling.
+    pub compile_debounce: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlayHintsConfiguration {
+    /// Whether inlay hints are shown at all.
+    pub enabled: bool,
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Self {
+            hexdocs_links: true,
+            inlay_hints: InlayHintsConfiguration::default(),
+            target: None,
+            compile_debounce: 100,
+        }
+    }
+}
+
+impl Default for InlayHintsConfiguration {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+impl Configuration {
+    /// Reads the settings sent by the client, which may be nested under a
+    /// `gleam` section as they are in the settings of most editors. Each
+    /// setting is read on its own, so one that can't be read keeps its default
+    /// value without affecting the others.
+    pub fn from_json(settings: &serde_json::Value) -> Self {
+        let settings = settings.get("gleam").unwrap_or(settings);
+        let mut configuration = Self::default();
+        read_setting(settings, "hexdocsLinks", &mut configuration.hexdocs_links);
+        if let Some(inlay_hints) = settings.get("inlayHints") {
+            read_setting(
+                inlay_hints,
+                "enabled",
+                &mut configuration.inlay_hints.enabled,
+            );
+        }
+        read_setting(settings, "target", &mut configuration.target);
+        read_setting(
+            settings,
+            "compileDebounce",
+            &mut configuration.compile_debounce,
+        );
+        configuration
+    }
+
+    pub fn compile_debounce(&self) -> Duration {
+        Duration::from_millis(self.compile_debounce)
+    }
+}
+
+/// Replaces a value with the setting of the given name, if the client set it
+/// to something that can be read.
+fn read_setting<T: DeserializeOwned>(settings: &serde_json::Value, name: &str, value: &mut T) {
+    let Some(setting) = settings.get(name) else {
+        return;
+    };
+    match T::deserialize(setting) {
+        Ok(setting) => *value = setting,
+        Err(error) => tracing::warn!(setting = name, %error, "invalid_language_server_setting"),
+    }
+}
@@ -0,0 +1,161 @@
+//! The conversion of the errors and warnings of the compiler to language
+//! server diagnostics, both for the diagnostics published to the client after
+//! compiling and for the ones it pulls with `textDocument/diagnostic`.
+//!
+//! The other locations a diagnostic points to, such as the first definition of
+//! a name defined twice, are given as its related information. The fixes for
+//! a warning are attached as the data of its diagnostic, so code actions can
+//! offer them from the diagnostics the client sends back, as warnings aren't
+//! kept around once they have been reported.
+
+use camino::Utf8Path;
+use lsp_types as lsp;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    diagnostic::{Diagnostic, Fix, Level},
+    line_numbers::{LineNumbers, PositionEncoding},
+    Warning,
+};
+
+use super::src_span_to_lsp_range;
+
+/// The data of a diagnostic, with the fixes for the problem it reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticData {
+    pub fixes: Vec<DiagnosticFix>,
+}
+
+/// A change to the module the diagnostic is for, offered as a quick fix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticFix {
+    pub title: String,
+    pub edits: Vec<lsp::TextEdit>,
+}
+
+impl DiagnosticData {
+    /// The data of a diagnostic sent back by the client, if it has any.
+    pub fn from_diagnostic(diagnostic: &lsp::Diagnostic) -> Option<Self> {
+        serde_json::from_value(diagnostic.data.clone()?).ok()
+    }
+}
+
+pub fn to_lsp(diagnostic: Diagnostic, encoding: PositionEncoding) -> Vec<lsp::Diagnostic> {
+    let severity = match diagnostic.level {
+        Level::Error => lsp::DiagnosticSeverity::ERROR,
+        Level::Warning => lsp::DiagnosticSeverity::WARNING,
+    };
+    let hint = diagnostic.hint;
+    let mut text = diagnostic.title;
+
+    if let Some(label) = diagnostic
+        .location
+        .as_ref()
+        .and_then(|location| location.label.text.as_deref())
+    {
+        text.push_str("\n\n");
+        text.push_str(label);
+        if !label.ends_with(['.', '?']) {
+            text.push('.');
+        }
+    }
+
+    if !diagnostic.text.is_empty() {
+        text.push_str("\n\n");
+        text.push_str(&diagnostic.text);
+    }
+
+    // TODO: Redesign the diagnostic type so that we can be sure there is always
+    // a location. Locationless diagnostics would be handled separately.
+    let location = diagnostic
+        .location
+        .expect("Diagnostic given to LSP without location");
+    let line_numbers = LineNumbers::new(&location.src);
+
+    // The extra labels are in the same module as the main one
+    let related_information = super::url(&location.path).map(|uri| {
+        location
+            .extra_labels
+            .iter()
+            .filter_map(|label| {
+                Some(lsp::DiagnosticRelatedInformation {
+                    location: lsp::Location {
+                        uri: uri.clone(),
+                        range: src_span_to_lsp_range(label.span, &line_numbers, encoding),
+                    },
+                    message: label.text.clone()?,
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let main = lsp::Diagnostic {
+        range: src_span_to_lsp_range(location.label.span, &line_numbers, encoding),
+        severity: Some(severity),
+        code: diagnostic
+            .code
+            .map(|code| lsp::NumberOrString::String(code.to_string())),
+        code_description: None,
+        source: None,
+        message: text,
+        related_information: related_information.filter(|related| !related.is_empty()),
+        tags: None,
+        data: None,
+    };
+
+    match hint {
+        Some(hint) => {
+            let hint = lsp::Diagnostic {
+                severity: Some(lsp::DiagnosticSeverity::HINT),
+                message: hint,
+                related_information: None,
+                ..main.clone()
+            };
+            vec![main, hint]
+        }
+        None => vec![main],
+    }
+}
+
+/// The diagnostics for a warning, with the fixes for it as the data of the
+/// main diagnostic.
+pub fn warning_to_lsp(warning: &Warning, encoding: PositionEncoding) -> Vec<lsp::Diagnostic> {
+    let diagnostic = warning.to_diagnostic();
+    let fixes = match &diagnostic.location {
+        Some(location) => fixes_to_lsp(warning.fixes(), &location.src, encoding),
+        None => vec![],
+    };
+    let mut diagnostics = to_lsp(diagnostic, encoding);
+    if let Some(main) = diagnostics.first_mut().filter(|_| !fixes.is_empty()) {
+        main.data = serde_json::to_value(DiagnosticData { fixes }).ok();
+    }
+    diagnostics
+}
+
+fn fixes_to_lsp(fixes: Vec<Fix>, src: &str, encoding: PositionEncoding) -> Vec<DiagnosticFix> {
+    let line_numbers = LineNumbers::new(src);
+    fixes
+        .into_iter()
+        .map(|fix| DiagnosticFix {
+            title: fix.title,
+            edits: fix
+                .edits
+                .into_iter()
+                .map(|edit| lsp::TextEdit {
+                    range: src_span_to_lsp_range(edit.span, &line_numbers, encoding),
+                    new_text: edit.replacement,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Whether the diagnostic is for the module at the path.
+pub fn is_for_module(diagnostic: &Diagnostic, path: &Utf8Path) -> bool {
+    diagnostic
+        .location
+        .as_ref()
+        .is_some_and(|location| location.path == path)
+}
@@ -0,0 +1,54 @@
+use lsp_types::TextDocumentContentChangeEvent;
+
+use crate::line_numbers::{LineNumbers, PositionEncoding};
+
+/// The text of a file that is open in the editor.
+///
+/// The editor only sends the parts of the file that changed with each edit,
+/// so the line numbers of the text are kept alongside it and updated with
+/// each change rather than being worked out again for the whole file.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    text: String,
+    line_numbers: LineNumbers,
+}
+
+impl Document {
+    pub fn new(text: String) -> Self {
+        Self {
+            line_numbers: LineNumbers::new(&text),
+            text,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Applies the changes in the order the editor sent them. A change
+    /// without a range replaces the whole text.
+    pub fn apply_changes(
+        &mut self,
+        changes: Vec<TextDocumentContentChangeEvent>,
+        encoding: PositionEncoding,
+    ) {
+        for change in changes {
+            let Some(range) = change.range else {
+                *self = Document::new(change.text);
+                continue;
+            };
+
+            let start =
+                self.line_numbers
+                    .byte_index(range.start.line, range.start.character, encoding);
+            let end = self
+                .line_numbers
+                .byte_index(range.end.line, range.end.character, encoding)
+                .max(start);
+            self.text
+                .replace_range(start as usize..end as usize, &change.text);
+            self.line_numbers.edit(start, end, &change.text);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Document links, which make the name of the module of each import a link:
+//! to the documentation of the module on `HexDocs` for modules of Hex packages,
+//! and to the source of the module for any other module.
+
+use std::collections::HashSet;
+
+use ecow::EcoString;
+use lsp_types as lsp;
+
+use crate::{
+    ast::{Definition, Import, SrcSpan},
+    build::Module,
+    line_numbers::{LineNumbers, PositionEncoding},
+};
+
+use super::src_span_to_lsp_range;
+
+/// The links of the imports of a module. `source` gives the URL of the source
+/// of a module that isn't in a Hex package, if there is one.
+pub fn document_links<F>(
+    module: &Module,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
+    hex_deps: &HashSet<EcoString>,
+    source: F,
+) -> Vec<lsp::DocumentLink>
+where
+    F: Fn(&str) -> Option<lsp::Url>,
+{
+    module
+        .ast
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Import(import) => Some(import),
+            _ => None,
+        })
+        .filter_map(|import| {
+            let (target, tooltip) = if hex_deps.contains(&import.package) {
+                let link = hexdocs_link(&import.package, &import.module)?;
+                (link, Some("View on HexDocs".into()))
+            } else {
+                (source(&import.module)?, None)
+            };
+            let location = module_name_location(&module.code, import)?;
+            Some(lsp::DocumentLink {
+                range: src_span_to_lsp_range(location, line_numbers, encoding),
+                target: Some(target),
+                tooltip,
+                data: None,
+            })
+        })
+        .collect()
+}
+
+fn hexdocs_link(package: &str, module: &str) -> Option<lsp::Url> {
+    lsp::Url::parse(&format!("https://hexdocs.pm/{package}/{module}.html")).ok()
+}
+
+/// The location of the name of the imported module, such as `gleam/list` in
+/// `import gleam/list.{map} as l`.
+fn module_name_location(code: &str, import: &Import<EcoString>) -> Option<SrcSpan> {
+    // The name is looked for after the `import` keyword, as it could contain
+    // the name of the module.
+    let after_keyword = import.location.start + "import".len() as u32;
+    let source = code.get(after_keyword as usize..import.location.end as usize)?;
+    let start = after_keyword + source.find(import.module.as_str())? as u32;
+    Some(SrcSpan::new(start, start + import.module.len() as u32))
+}
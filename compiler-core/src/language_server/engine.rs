@@ -1,28 +1,47 @@
 use crate::{
+    analyse::get_compatible_record_fields,
     ast::{
-        Arg, Definition, Function, Import, ModuleConstant, Publicity, TypedDefinition, TypedExpr,
-        TypedPattern,
+        Arg, Definition, Function, Import, ModuleConstant, Publicity, SrcSpan, TypedDefinition,
+        TypedExpr, TypedFunction, TypedPattern,
     },
-    build::{Located, Module},
-    config::PackageConfig,
+    build::{CancellationToken, Located, Module},
+    config::{LintsConfig, PackageConfig},
+    format::FormatOptions,
     io::{CommandExecutor, FileSystemReader, FileSystemWriter},
     language_server::{
-        compiler::LspProjectCompiler, files::FileSystemProxy, progress::ProgressReporter,
+        call_hierarchy, code_lens,
+        compiled_code::{self, CompiledCode, ShowCompiledCodeParams},
+        compiler::LspProjectCompiler,
+        document_link, external, extract,
+        files::FileSystemProxy,
+        fill_labels, generate_function, inlay_hints,
+        progress::ProgressReporter,
+        reference::{self, Names, ReferenceKind, Referenced},
+        rename::{self, CannotRename, Renamed},
+        semantic_tokens, signature_help, type_definition, use_callback, workspace_symbol,
     },
-    line_numbers::LineNumbers,
+    line_numbers::{LineNumbers, PositionEncoding},
     paths::ProjectPaths,
-    type_::{pretty::Printer, PreludeType, Type, ValueConstructorVariant},
+    type_::{
+        collapse_links, pretty::Printer, Error as TypeError, ModuleInterface,
+        ModuleValueConstructor, PreludeType, Purity, Type, ValueConstructorVariant,
+        PRELUDE_MODULE_NAME,
+    },
     Error, Result, Warning,
 };
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use ecow::EcoString;
 use lsp::CodeAction;
 use lsp_types::{self as lsp, Hover, HoverContents, MarkedString, Url};
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use strum::IntoEnumIterator;
+use vec1::Vec1;
 
 use super::{
-    code_action::CodeActionBuilder, src_span_to_lsp_range, DownloadDependencies, MakeLocker,
+    code_action::{AddAnnotations, CodeActionBuilder, QualifiedValue},
+    configuration::{Configuration, SharedConfiguration},
+    diagnostics::{self, DiagnosticData},
+    src_span_to_lsp_range, DownloadDependencies, MakeLocker,
 };
 
 #[derive(Debug, PartialEq, Eq)]
@@ -60,9 +79,25 @@ pub struct LanguageServerEngine<IO, Reporter> {
     /// Used to know if to show the "View on HexDocs" link
     /// when hovering on an imported value
     hex_deps: std::collections::HashSet<EcoString>,
+
+    /// The unit that the client counts the columns of positions in, as agreed
+    /// when the connection was initialised.
+    position_encoding: PositionEncoding,
+
+    /// The warnings of each module from when it was last compiled, for the
+    /// clients that pull diagnostics rather than have them published.
+    warnings: HashMap<Utf8PathBuf, Vec<Warning>>,
+
+    /// Cancels the compilation in progress when the code is edited. It is
+    /// shared with the server, which cancels it.
+    cancellation: CancellationToken,
+
+    /// The settings of the client, shared with the server so they are always
+    /// the latest ones.
+    pub(crate) configuration: SharedConfiguration,
 }
 
-impl<'a, IO, Reporter> LanguageServerEngine<IO, Reporter>
+impl<IO, Reporter> LanguageServerEngine<IO, Reporter>
 where
     // IO to be supplied from outside of gleam-core
     IO: FileSystemReader
@@ -72,14 +107,24 @@ where
         + MakeLocker
         + Clone,
     // IO to be supplied from inside of gleam-core
-    Reporter: ProgressReporter + Clone + 'a,
+    Reporter: ProgressReporter + Clone,
 {
     pub fn new(
         config: PackageConfig,
         progress_reporter: Reporter,
         io: FileSystemProxy<IO>,
         paths: ProjectPaths,
+        position_encoding: PositionEncoding,
+        configuration: SharedConfiguration,
+        cancellation: CancellationToken,
     ) -> Result<Self> {
+        // The client can have projects analysed for another target than the
+        // one in their config.
+        let mut config = config;
+        if let Some(target) = configuration.read().expect("configuration lock").target {
+            config.target = target;
+        }
+
         let locker = io.inner().make_locker(&paths, config.target)?;
 
         // Download dependencies to ensure they are up-to-date for this new
@@ -91,8 +136,9 @@ where
         // NOTE: This must come after the progress reporter has finished!
         let manifest = manifest?;
 
-        let compiler =
+        let mut compiler =
             LspProjectCompiler::new(manifest, config, paths.clone(), io.clone(), locker)?;
+        compiler.project_compiler.cancellation = cancellation.clone();
 
         let hex_deps = compiler
             .project_compiler
@@ -114,21 +160,72 @@ where
             compiler,
             paths,
             hex_deps,
+            position_encoding,
+            warnings: HashMap::new(),
+            cancellation,
+            configuration,
         })
     }
 
+    /// The settings of the client as they are now.
+    fn configuration(&self) -> Configuration {
+        self.configuration
+            .read()
+            .expect("configuration lock")
+            .clone()
+    }
+
+    /// Whether the last compilation was cancelled by an edit made while it
+    /// was in progress, in which case it is to be done again.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Analyses the module at the path if it is of a dependency package, so
+    /// the language server features work in dependency code opened from the
+    /// definition of something used by the project. This can only be done
+    /// once the dependencies have been compiled.
+    pub fn load_dependency_module(&mut self, path: &Utf8Path) {
+        if path.starts_with(self.paths.src_directory())
+            || path.starts_with(self.paths.test_directory())
+        {
+            return;
+        }
+        self.compiler.load_dependency_module(path)
+    }
+
+    /// Whether the file is in one of the path dependencies of the project, in
+    /// which case the project is to be compiled again when it changes.
+    pub fn depends_on_path(&self, path: &Utf8Path) -> bool {
+        self.compiler
+            .project_compiler
+            .local_package_roots()
+            .iter()
+            .any(|root| path.starts_with(root))
+    }
+
     pub fn compile_please(&mut self) -> Response<()> {
         self.respond(Self::compile)
     }
 
     /// Compile the project if we are in one. Otherwise do nothing.
     fn compile(&mut self) -> Result<(), Error> {
-        self.compiled_since_last_feedback = true;
+        // The edits made before now are in the code about to be compiled
+        _ = self.cancellation.reset();
 
         self.progress_reporter.compilation_started();
         let result = self.compiler.compile();
         self.progress_reporter.compilation_finished();
 
+        // A cancelled compilation is stale, so it is dropped without being
+        // reported as compiled, along with the warnings it has emitted. The
+        // project is compiled again once the edit has been handled.
+        if let Err(Error::Cancelled) = result {
+            _ = self.take_warnings();
+            return Ok(());
+        }
+
+        self.compiled_since_last_feedback = true;
         let modules = result?;
         self.modules_compiled_since_last_feedback.extend(modules);
 
@@ -149,18 +246,33 @@ where
     ) -> Response<Option<lsp::Location>> {
         self.respond(|this| {
             let params = params.text_document_position_params;
+            if let Some(location) = this.external_definition(&params) {
+                return Ok(Some(location));
+            }
+
             let (line_numbers, node) = match this.node_at_position(&params) {
                 Some(location) => location,
                 None => return Ok(None),
             };
 
+            // The definition of a label is in the function or custom type
+            // it is for, which is looked up in the module defining it.
+            if let Located::Label { label, of, .. } = &node {
+                let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                    return Ok(None);
+                };
+                return Ok(this.label_definition(module, label, of).and_then(
+                    |(defining, location, _)| this.source_location(&defining.name, location),
+                ));
+            }
+
             let location = match node.definition_location() {
                 Some(location) => location,
                 None => return Ok(None),
             };
 
             let (uri, line_numbers) = match location.module {
-                None => (params.text_document.uri, &line_numbers),
+                None => (params.text_document.uri, line_numbers),
                 Some(name) => {
                     let module = match this.compiler.get_source(name) {
                         Some(module) => module,
@@ -171,18 +283,124 @@ where
                     (url, &module.line_numbers)
                 }
             };
-            let range = src_span_to_lsp_range(location.span, line_numbers);
+            let range = src_span_to_lsp_range(location.span, line_numbers, this.position_encoding);
 
             Ok(Some(lsp::Location { uri, range }))
         })
     }
 
+    /// Finds the definitions of the types of the value at the position, such
+    /// as `Pet` for a value of type `List(Pet)`.
+    pub fn goto_type_definition(
+        &mut self,
+        params: lsp::GotoDefinitionParams,
+    ) -> Response<Option<Vec<lsp::Location>>> {
+        self.respond(|this| {
+            let params = params.text_document_position_params;
+            let Some((_, node)) = this.node_at_position(&params) else {
+                return Ok(None);
+            };
+            let type_ = match node {
+                Located::Expression(expression) => expression.type_(),
+                Located::Pattern(pattern) => pattern.type_(),
+                Located::Statement(statement) => statement.type_(),
+                Located::Arg(arg) => arg.type_.clone(),
+                Located::Label { type_, .. } => type_,
+                Located::ModuleStatement(_) | Located::FunctionBody(_) => return Ok(None),
+            };
+
+            let modules = this.compiler.project_compiler.get_importable_modules();
+            let locations = type_definition::named_types(&type_)
+                .into_iter()
+                .filter_map(|(module, name)| {
+                    let type_ = modules.get(&module)?.types.get(&name)?;
+                    this.source_location(&module, type_.origin)
+                })
+                .collect();
+            Ok(Some(locations))
+        })
+    }
+
+    /// Finds the constructors of the type written in the annotation at the
+    /// position, or of the custom type defined at the position.
+    pub fn goto_implementation(
+        &mut self,
+        params: lsp::GotoDefinitionParams,
+    ) -> Response<Option<Vec<lsp::Location>>> {
+        self.respond(|this| {
+            let params = params.text_document_position_params;
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let Some(line_numbers) = this.module_line_numbers(module) else {
+                return Ok(None);
+            };
+            let byte_index = line_numbers.byte_index(
+                params.position.line,
+                params.position.character,
+                this.position_encoding,
+            );
+            let type_ = match type_definition::annotation_at(&module.ast, byte_index) {
+                Some(annotation) => {
+                    Names::new(&module.ast).type_(annotation.module.as_ref(), &annotation.name)
+                }
+                None => match module.find_node(byte_index) {
+                    Some(Located::ModuleStatement(Definition::CustomType(custom_type))) => {
+                        Some(Referenced::Type {
+                            module: module.name.clone(),
+                            name: custom_type.name.clone(),
+                        })
+                    }
+                    _ => None,
+                },
+            };
+            let Some(Referenced::Type {
+                module: type_module,
+                name,
+            }) = type_
+            else {
+                return Ok(None);
+            };
+
+            let modules = this.compiler.project_compiler.get_importable_modules();
+            let Some(interface) = modules.get(&type_module) else {
+                return Ok(None);
+            };
+            let Some(constructors) = interface.types_value_constructors.get(&name) else {
+                return Ok(None);
+            };
+            let locations = constructors
+                .variants
+                .iter()
+                .filter_map(
+                    |variant| match &interface.values.get(&variant.name)?.variant {
+                        ValueConstructorVariant::Record { location, .. } => {
+                            this.source_location(&type_module, *location)
+                        }
+                        _ => None,
+                    },
+                )
+                .collect();
+            Ok(Some(locations))
+        })
+    }
+
     pub fn completion(
         &mut self,
         params: lsp::TextDocumentPositionParams,
         src: EcoString,
     ) -> Response<Option<Vec<lsp::CompletionItem>>> {
         self.respond(|this| {
+            // A case expression without clauses doesn't type check, so its
+            // clauses are completed from the error rather than the module.
+            if let Some(completion) = completion_case_clauses(
+                this.compiler.error.as_ref(),
+                &params,
+                this.position_encoding,
+            ) {
+                return Ok(Some(vec![completion]));
+            }
+
             let module = match this.module_for_uri(&params.text_document.uri) {
                 Some(m) => m,
                 None => return Ok(None),
@@ -191,13 +409,23 @@ where
             // Check current filercontents if the user is writing an import
             // and handle separately from the rest of the completion flow
             // Check if an import is being written
+            let prefix = this.completion_prefix(&src, params.position);
             if let Some(value) = this.import_completions(src, &params, module) {
                 return value;
             }
 
-            let line_numbers = LineNumbers::new(&module.code);
-            let byte_index =
-                line_numbers.byte_index(params.position.line, params.position.character);
+            let Some(line_numbers) = this.module_line_numbers(module) else {
+                return Ok(None);
+            };
+            let byte_index = line_numbers.byte_index(
+                params.position.line,
+                params.position.character,
+                this.position_encoding,
+            );
+
+            if let Some(completions) = this.completion_fields(module, byte_index) {
+                return Ok(Some(completions));
+            }
 
             let Some(found) = module.find_node(byte_index) else {
                 return Ok(None);
@@ -206,18 +434,21 @@ where
             let completions = match found {
                 Located::Pattern(_pattern) => None,
 
-                Located::Statement(_) | Located::Expression(_) => {
-                    Some(this.completion_values(module))
+                Located::Statement(_)
+                | Located::Expression(_)
+                | Located::FunctionBody(_)
+                | Located::Label { .. } => {
+                    let mut completions = this.completion_values(module);
+                    completions.extend(this.completion_unimported_values(module, &prefix));
+                    Some(completions)
                 }
 
-                Located::ModuleStatement(Definition::Function(_)) => {
-                    Some(this.completion_types(module))
-                }
-
-                Located::FunctionBody(_) => Some(this.completion_values(module)),
-
-                Located::ModuleStatement(Definition::TypeAlias(_) | Definition::CustomType(_)) => {
-                    Some(this.completion_types(module))
+                Located::ModuleStatement(
+                    Definition::Function(_) | Definition::TypeAlias(_) | Definition::CustomType(_),
+                ) => {
+                    let mut completions = this.completion_types(module);
+                    completions.extend(this.completion_unimported_types(module, &prefix));
+                    Some(completions)
                 }
 
                 Located::ModuleStatement(Definition::Import(_) | Definition::ModuleConstant(_)) => {
@@ -234,11 +465,102 @@ where
     pub fn action(&mut self, params: lsp::CodeActionParams) -> Response<Option<Vec<CodeAction>>> {
         self.respond(|this| {
             let mut actions = vec![];
-            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+
+            code_action_diagnostic_fixes(&params, &mut actions);
+
+            // A module with a type error isn't compiled, so these don't need
+            // the module.
+            code_action_inexhaustive_case(
+                this.compiler.error.as_ref(),
+                &params,
+                this.position_encoding,
+                &mut actions,
+            );
+            code_action_generate_function(
+                this.compiler.error.as_ref(),
+                &params,
+                this.position_encoding,
+                this.compiler.project_compiler.config.format.options(),
+                &mut actions,
+            );
+            code_action_missing_labels(
+                this.compiler.error.as_ref(),
+                &params,
+                this.position_encoding,
+                &mut actions,
+            );
+
+            // Dependency code can't be changed, so it has no code actions.
+            let Some(module) = this
+                .module_for_uri(&params.text_document.uri)
+                .filter(|module| !this.is_dependency_module(module))
+            else {
+                return Ok(if actions.is_empty() {
+                    None
+                } else {
+                    Some(actions)
+                });
+            };
+
+            let Some(line_numbers) = this.module_line_numbers(module) else {
                 return Ok(None);
             };
 
-            code_action_unused_imports(module, &params, &mut actions);
+            code_action_unused_imports(
+                module,
+                &params,
+                line_numbers,
+                this.position_encoding,
+                &mut actions,
+            );
+            code_action_lint_fixes(
+                module,
+                &this.compiler.project_compiler.config.lints,
+                &params,
+                line_numbers,
+                this.position_encoding,
+                &mut actions,
+            );
+            code_action_extract(
+                module,
+                &params,
+                line_numbers,
+                this.position_encoding,
+                this.compiler.project_compiler.config.format.options(),
+                &mut actions,
+            );
+            code_action_label_arguments(
+                module,
+                this.compiler.project_compiler.get_importable_modules(),
+                &params,
+                line_numbers,
+                this.position_encoding,
+                &mut actions,
+            );
+            code_action_use_callback(
+                module,
+                &params,
+                line_numbers,
+                this.position_encoding,
+                this.compiler.project_compiler.config.format.options(),
+                &mut actions,
+            );
+            actions.extend(
+                AddAnnotations::new(module, &params, line_numbers, this.position_encoding)
+                    .code_actions(),
+            );
+            if let Some(references) = this.compiler.references.get(&module.name) {
+                actions.extend(
+                    QualifiedValue::new(
+                        module,
+                        references,
+                        &params,
+                        line_numbers,
+                        this.position_encoding,
+                    )
+                    .code_actions(),
+                );
+            }
 
             Ok(if actions.is_empty() {
                 None
@@ -248,6 +570,552 @@ where
         })
     }
 
+    /// Shows the types inferred for `let` bindings without an annotation and
+    /// for `todo` expressions, and the labels of arguments given without them.
+    pub fn inlay_hints(
+        &mut self,
+        params: lsp::InlayHintParams,
+    ) -> Response<Option<Vec<lsp::InlayHint>>> {
+        self.respond(|this| {
+            if !this.configuration().inlay_hints.enabled {
+                return Ok(None);
+            }
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let Some(line_numbers) = this.module_line_numbers(module) else {
+                return Ok(None);
+            };
+
+            let modules = this.compiler.project_compiler.get_importable_modules();
+            let hints = inlay_hints::hints(module, modules)
+                .into_iter()
+                .filter_map(|hint| {
+                    let location = SrcSpan::new(hint.position, hint.position);
+                    let position =
+                        src_span_to_lsp_range(location, line_numbers, this.position_encoding).start;
+                    if position < params.range.start || position > params.range.end {
+                        return None;
+                    }
+                    Some(lsp::InlayHint {
+                        position,
+                        label: lsp::InlayHintLabel::String(hint.label),
+                        kind: Some(hint.kind),
+                        text_edits: None,
+                        tooltip: None,
+                        padding_left: None,
+                        padding_right: None,
+                        data: None,
+                    })
+                })
+                .collect();
+
+            Ok(Some(hints))
+        })
+    }
+
+    /// Lenses to run the tests of a test module and the `main` function of a
+    /// module. Dependency code can't be run, so it has no lenses.
+    pub fn code_lens(
+        &mut self,
+        params: lsp::CodeLensParams,
+    ) -> Response<Option<Vec<lsp::CodeLens>>> {
+        self.respond(|this| {
+            let uri = &params.text_document.uri;
+            let Some(module) = this
+                .module_for_uri(uri)
+                .filter(|module| !this.is_dependency_module(module))
+            else {
+                return Ok(None);
+            };
+            let Some(line_numbers) = this.module_line_numbers(module) else {
+                return Ok(None);
+            };
+            Ok(Some(code_lens::code_lenses(
+                module,
+                uri,
+                line_numbers,
+                this.position_encoding,
+            )))
+        })
+    }
+
+    /// Links the name of each imported module to its documentation on
+    /// `HexDocs`, or to its source if it isn't in a Hex package.
+    pub fn document_link(
+        &mut self,
+        params: lsp::DocumentLinkParams,
+    ) -> Response<Option<Vec<lsp::DocumentLink>>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let Some(line_numbers) = this.module_line_numbers(module) else {
+                return Ok(None);
+            };
+            Ok(Some(document_link::document_links(
+                module,
+                line_numbers,
+                this.position_encoding,
+                &this.hex_deps,
+                |name| {
+                    let source = this.compiler.get_source(name)?;
+                    super::url(Utf8Path::new(&source.path))
+                },
+            )))
+        })
+    }
+
+    /// Checks what is at the position can be renamed before the editor asks
+    /// for a new name, responding with the name that would be renamed or
+    /// with why it can't be renamed.
+    pub fn prepare_rename(
+        &mut self,
+        params: lsp::TextDocumentPositionParams,
+    ) -> Response<std::result::Result<lsp::PrepareRenameResponse, CannotRename>> {
+        self.respond(|this| {
+            let (module, location) = match this.renamed_at(&params) {
+                Some((module, Ok((_, location)))) => (module, location),
+                Some((_, Err(error))) => return Ok(Err(error)),
+                None => return Ok(Err(CannotRename::Other)),
+            };
+            let Some(line_numbers) = this.module_line_numbers(module) else {
+                return Ok(Err(CannotRename::Other));
+            };
+            let placeholder = module
+                .code
+                .get(location.start as usize..location.end as usize)
+                .unwrap_or_default()
+                .into();
+            Ok(Ok(lsp::PrepareRenameResponse::RangeWithPlaceholder {
+                range: src_span_to_lsp_range(location, line_numbers, this.position_encoding),
+                placeholder,
+            }))
+        })
+    }
+
+    /// The module at the position and what renaming at the position renames.
+    fn renamed_at(
+        &self,
+        position: &lsp::TextDocumentPositionParams,
+    ) -> Option<(&Module, rename::Prepared)> {
+        let module = self
+            .module_for_uri(&position.text_document.uri)
+            .filter(|module| !self.is_dependency_module(module))?;
+        let line_numbers = self.module_line_numbers(module)?;
+        let byte_index = line_numbers.byte_index(
+            position.position.line,
+            position.position.character,
+            self.position_encoding,
+        );
+        let renamed = rename::prepare(&module.ast, &module.code, byte_index).and_then(
+            |(renamed, location)| match renamed.module() {
+                // Values defined in dependencies can't be renamed as their
+                // code can't be changed.
+                Some(defined_in) if !self.compiler.modules.contains_key(defined_in) => {
+                    Err(CannotRename::Dependency {
+                        module: defined_in.clone(),
+                        name: match &renamed {
+                            Renamed::Label { function, .. } => function.clone(),
+                            _ => renamed.name().clone(),
+                        },
+                    })
+                }
+                _ => Ok((renamed, location)),
+            },
+        );
+        Some((module, renamed))
+    }
+
+    /// Renames the variable, function, constant, or label at the position,
+    /// along with every use of it in the modules of the root package.
+    pub fn rename(&mut self, params: lsp::RenameParams) -> Response<Option<lsp::WorkspaceEdit>> {
+        self.respond(|this| {
+            let position = params.text_document_position;
+            if !rename::is_valid_name(&params.new_name) {
+                return Ok(None);
+            }
+            let Some((module, Ok((renamed, _)))) = this.renamed_at(&position) else {
+                return Ok(None);
+            };
+
+            let others = this
+                .compiler
+                .modules
+                .values()
+                .filter(|other| other.name != module.name && renamed.is_used_by(&other.ast));
+            let mut changes = HashMap::new();
+            for changed in std::iter::once(module).chain(others) {
+                let edits = rename::edits(&changed.ast, &changed.code, &renamed, &params.new_name);
+                if edits.is_empty() {
+                    continue;
+                }
+                let Some(line_numbers) = this.module_line_numbers(changed) else {
+                    continue;
+                };
+                let uri = if changed.name == module.name {
+                    position.text_document.uri.clone()
+                } else {
+                    match super::url(&changed.input_path) {
+                        Some(uri) => uri,
+                        None => continue,
+                    }
+                };
+                let edits = edits
+                    .into_iter()
+                    .map(|edit| lsp::TextEdit {
+                        range: src_span_to_lsp_range(
+                            edit.location,
+                            line_numbers,
+                            this.position_encoding,
+                        ),
+                        new_text: edit.new_text,
+                    })
+                    .collect();
+                _ = changes.insert(uri, edits);
+            }
+
+            Ok(Some(lsp::WorkspaceEdit {
+                changes: Some(changes),
+                document_changes: None,
+                change_annotations: None,
+            }))
+        })
+    }
+
+    /// Highlights every occurrence in the module of the variable, function,
+    /// or constant at the position. The place a variable is defined is
+    /// highlighted as a write, and the places it is used as reads.
+    pub fn document_highlight(
+        &mut self,
+        params: lsp::DocumentHighlightParams,
+    ) -> Response<Option<Vec<lsp::DocumentHighlight>>> {
+        self.respond(|this| {
+            let position = params.text_document_position_params;
+            let Some(module) = this.module_for_uri(&position.text_document.uri) else {
+                return Ok(None);
+            };
+            let Some(line_numbers) = this.module_line_numbers(module) else {
+                return Ok(None);
+            };
+            let byte_index = line_numbers.byte_index(
+                position.position.line,
+                position.position.character,
+                this.position_encoding,
+            );
+            let Some(highlighted) = module
+                .find_node(byte_index)
+                .and_then(|node| Renamed::at(&module.ast, node, byte_index))
+            else {
+                return Ok(None);
+            };
+
+            let highlights = rename::occurrences(&module.ast, &module.code, &highlighted)
+                .into_iter()
+                .map(|location| {
+                    let kind = match &highlighted {
+                        Renamed::Local { definition, .. }
+                            if definition.contains(location.start) =>
+                        {
+                            lsp::DocumentHighlightKind::WRITE
+                        }
+                        Renamed::Local { .. } => lsp::DocumentHighlightKind::READ,
+                        Renamed::ModuleValue { .. } | Renamed::Label { .. } => {
+                            lsp::DocumentHighlightKind::TEXT
+                        }
+                    };
+                    lsp::DocumentHighlight {
+                        range: src_span_to_lsp_range(
+                            location,
+                            line_numbers,
+                            this.position_encoding,
+                        ),
+                        kind: Some(kind),
+                    }
+                })
+                .collect();
+            Ok(Some(highlights))
+        })
+    }
+
+    /// Finds where the function, constant, record constructor, or type at the
+    /// position is referred to in the modules of the project.
+    pub fn references(
+        &mut self,
+        params: lsp::ReferenceParams,
+    ) -> Response<Option<Vec<lsp::Location>>> {
+        self.respond(|this| {
+            let position = params.text_document_position;
+            let Some(module) = this.module_for_uri(&position.text_document.uri) else {
+                return Ok(None);
+            };
+            let Some(line_numbers) = this.module_line_numbers(module) else {
+                return Ok(None);
+            };
+            let byte_index = line_numbers.byte_index(
+                position.position.line,
+                position.position.character,
+                this.position_encoding,
+            );
+            let Some(referenced) = this
+                .compiler
+                .references
+                .get(&module.name)
+                .and_then(|references| references.at(byte_index))
+            else {
+                return Ok(None);
+            };
+
+            let mut modules = this.compiler.modules.values().collect::<Vec<_>>();
+            modules.sort_by_key(|module| &module.name);
+            let mut locations = vec![];
+            for referring in modules {
+                let (Some(references), Some(line_numbers)) = (
+                    this.compiler.references.get(&referring.name),
+                    this.module_line_numbers(referring),
+                ) else {
+                    continue;
+                };
+                let uri = if referring.name == module.name {
+                    position.text_document.uri.clone()
+                } else {
+                    match super::url(&referring.input_path) {
+                        Some(uri) => uri,
+                        None => continue,
+                    }
+                };
+                let references = references.get(referenced).iter().filter(|reference| {
+                    params.context.include_declaration
+                        || reference.kind != ReferenceKind::Definition
+                });
+                for reference in references {
+                    locations.push(lsp::Location {
+                        uri: uri.clone(),
+                        range: src_span_to_lsp_range(
+                            reference.location,
+                            line_numbers,
+                            this.position_encoding,
+                        ),
+                    });
+                }
+            }
+
+            Ok(Some(locations))
+        })
+    }
+
+    /// The function at the position, for its callers and the functions it
+    /// calls to be found from.
+    pub fn prepare_call_hierarchy(
+        &mut self,
+        params: lsp::CallHierarchyPrepareParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyItem>>> {
+        self.respond(|this| {
+            let position = params.text_document_position_params;
+            let Some((module, function)) =
+                this.called_function(&position.text_document.uri, position.position)
+            else {
+                return Ok(None);
+            };
+            Ok(this
+                .call_hierarchy_item(module, function)
+                .map(|item| vec![item]))
+        })
+    }
+
+    /// The functions of the project calling the function of the item, with
+    /// where they call it.
+    pub fn incoming_calls(
+        &mut self,
+        params: lsp::CallHierarchyIncomingCallsParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyIncomingCall>>> {
+        self.respond(|this| {
+            let item = params.item;
+            let Some(referenced) = this.referenced_at(&item.uri, item.selection_range.start) else {
+                return Ok(None);
+            };
+
+            let calls = call_hierarchy::incoming(
+                &this.compiler.modules,
+                &this.compiler.references,
+                referenced,
+            )
+            .into_iter()
+            .filter_map(|calls| {
+                Some(lsp::CallHierarchyIncomingCall {
+                    from: this.call_hierarchy_item(calls.module, calls.function)?,
+                    from_ranges: this.call_ranges(calls.module, &calls.locations)?,
+                })
+            })
+            .collect();
+            Ok(Some(calls))
+        })
+    }
+
+    /// The functions of the project the function of the item calls, with
+    /// where it calls them.
+    pub fn outgoing_calls(
+        &mut self,
+        params: lsp::CallHierarchyOutgoingCallsParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyOutgoingCall>>> {
+        self.respond(|this| {
+            let item = params.item;
+            let Some((module, function)) =
+                this.called_function(&item.uri, item.selection_range.start)
+            else {
+                return Ok(None);
+            };
+            let Some(references) = this.compiler.references.get(&module.name) else {
+                return Ok(None);
+            };
+
+            let calls = call_hierarchy::outgoing(&this.compiler.modules, references, function)
+                .into_iter()
+                .filter_map(|calls| {
+                    Some(lsp::CallHierarchyOutgoingCall {
+                        to: this.call_hierarchy_item(calls.module, calls.function)?,
+                        from_ranges: this.call_ranges(module, &calls.locations)?,
+                    })
+                })
+                .collect();
+            Ok(Some(calls))
+        })
+    }
+
+    /// The signature of the function being called at the position, while
+    /// the arguments of the call are written.
+    pub fn signature_help(
+        &mut self,
+        params: lsp::SignatureHelpParams,
+    ) -> Response<Option<lsp::SignatureHelp>> {
+        self.respond(|this| {
+            let params = params.text_document_position_params;
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let Some(line_numbers) = this.module_line_numbers(module) else {
+                return Ok(None);
+            };
+            let byte_index = line_numbers.byte_index(
+                params.position.line,
+                params.position.character,
+                this.position_encoding,
+            );
+            let Some(call) = signature_help::find_call(&module.ast, byte_index) else {
+                return Ok(None);
+            };
+
+            let modules = this.compiler.project_compiler.get_importable_modules();
+            let field_map = signature_help::field_map(call.fun, modules);
+            Ok(signature_help::signature_help(call, field_map, byte_index))
+        })
+    }
+
+    /// The Erlang or JavaScript the module is compiled to, for the target
+    /// asked for or else the target of the project. There is none while the
+    /// module fails to compile.
+    pub fn compiled_code(
+        &mut self,
+        params: ShowCompiledCodeParams,
+    ) -> Response<Option<CompiledCode>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let Some(line_numbers) = this.module_line_numbers(module) else {
+                return Ok(None);
+            };
+            // A module with errors may only have been partially analysed
+            if let Some(Error::Parse { path, .. } | Error::Type { path, .. }) = &this.compiler.error
+            {
+                if *path == module.input_path {
+                    return Ok(None);
+                }
+            }
+
+            let target = params
+                .target
+                .unwrap_or(this.compiler.project_compiler.config.target);
+            compiled_code::compiled_code(module, line_numbers, target).map(Some)
+        })
+    }
+
+    /// The semantic tokens of a module, for editors to highlight it with.
+    pub fn semantic_tokens_full(
+        &mut self,
+        params: lsp::SemanticTokensParams,
+    ) -> Response<Option<lsp::SemanticTokensResult>> {
+        self.respond(|this| {
+            let tokens = this.semantic_tokens(&params.text_document.uri, None);
+            Ok(tokens.map(lsp::SemanticTokensResult::Tokens))
+        })
+    }
+
+    /// The semantic tokens of part of a module, for editors to highlight the
+    /// part that is visible before the whole module.
+    pub fn semantic_tokens_range(
+        &mut self,
+        params: lsp::SemanticTokensRangeParams,
+    ) -> Response<Option<lsp::SemanticTokensRangeResult>> {
+        self.respond(|this| {
+            let tokens = this.semantic_tokens(&params.text_document.uri, Some(params.range));
+            Ok(tokens.map(lsp::SemanticTokensRangeResult::Tokens))
+        })
+    }
+
+    fn semantic_tokens(&self, uri: &Url, range: Option<lsp::Range>) -> Option<lsp::SemanticTokens> {
+        let module = self.module_for_uri(uri)?;
+        let line_numbers = self.module_line_numbers(module)?;
+        let modules = self.compiler.project_compiler.get_importable_modules();
+        let mut tokens = semantic_tokens::tokens(&module.ast, &module.code, modules);
+        if let Some(range) = range {
+            let encoding = self.position_encoding;
+            let start = line_numbers.byte_index(range.start.line, range.start.character, encoding);
+            let end = line_numbers.byte_index(range.end.line, range.end.character, encoding);
+            tokens.retain(|token| token.location.start >= start && token.location.end <= end);
+        }
+        Some(lsp::SemanticTokens {
+            result_id: None,
+            data: semantic_tokens::encode(&tokens, line_numbers, self.position_encoding),
+        })
+    }
+
+    /// The definitions of the modules of the root package that match a
+    /// query, along with how well they match it, lower being better.
+    pub fn workspace_symbols(&self, query: &str) -> Vec<(usize, lsp::WorkspaceSymbol)> {
+        let mut found = vec![];
+        for module in self.compiler.modules.values() {
+            let (Some(line_numbers), Some(uri)) = (
+                self.module_line_numbers(module),
+                super::url(&module.input_path),
+            ) else {
+                continue;
+            };
+            for symbol in workspace_symbol::symbols(&module.ast, &module.code) {
+                let Some(score) = workspace_symbol::fuzzy_score(query, &symbol.name) else {
+                    continue;
+                };
+                let range =
+                    src_span_to_lsp_range(symbol.location, line_numbers, self.position_encoding);
+                found.push((
+                    score,
+                    lsp::WorkspaceSymbol {
+                        name: symbol.name.to_string(),
+                        kind: symbol.kind,
+                        tags: symbol.deprecated.then(|| vec![lsp::SymbolTag::DEPRECATED]),
+                        container_name: Some(module.name.to_string()),
+                        location: lsp::OneOf::Left(lsp::Location {
+                            uri: uri.clone(),
+                            range,
+                        }),
+                        data: None,
+                    },
+                ));
+            }
+        }
+        found
+    }
+
     fn respond<T>(&mut self, handler: impl FnOnce(&mut Self) -> Result<T>) -> Response<T> {
         let result = handler(self);
         let warnings = self.take_warnings();
@@ -259,6 +1127,7 @@ where
         } else {
             Compilation::No
         };
+        self.store_warnings(&compilation, &warnings);
         Response {
             result,
             warnings,
@@ -266,6 +1135,68 @@ where
         }
     }
 
+    /// Replaces the warnings of the modules that have been compiled again.
+    /// Modules compiled without warnings have theirs removed.
+    fn store_warnings(&mut self, compilation: &Compilation, warnings: &[Warning]) {
+        if let Compilation::Yes(modules) = compilation {
+            for path in modules {
+                _ = self.warnings.remove(path);
+            }
+        }
+        let mut new_warnings: HashMap<Utf8PathBuf, Vec<Warning>> = HashMap::new();
+        for warning in warnings {
+            let diagnostic = warning.to_diagnostic();
+            if let Some(location) = diagnostic.location {
+                new_warnings
+                    .entry(location.path)
+                    .or_default()
+                    .push(warning.clone());
+            }
+        }
+        self.warnings.extend(new_warnings);
+    }
+
+    /// The diagnostics of a module, for the clients that pull them rather
+    /// than have them published once the project has been compiled.
+    pub fn diagnostic(
+        &mut self,
+        params: lsp::DocumentDiagnosticParams,
+    ) -> Response<lsp::DocumentDiagnosticReportResult> {
+        self.respond(|this| {
+            let path = super::path(&params.text_document.uri);
+            let encoding = this.position_encoding;
+
+            let mut items = vec![];
+            if let Some(error) = &this.compiler.error {
+                items.extend(
+                    error
+                        .to_diagnostics()
+                        .into_iter()
+                        .filter(|diagnostic| diagnostics::is_for_module(diagnostic, &path))
+                        .flat_map(|diagnostic| diagnostics::to_lsp(diagnostic, encoding)),
+                );
+            }
+            items.extend(
+                this.warnings
+                    .get(&path)
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|warning| diagnostics::warning_to_lsp(warning, encoding)),
+            );
+
+            let report = lsp::RelatedFullDocumentDiagnosticReport {
+                related_documents: None,
+                full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                    result_id: None,
+                    items,
+                },
+            };
+            Ok(lsp::DocumentDiagnosticReportResult::Report(
+                lsp::DocumentDiagnosticReport::Full(report),
+            ))
+        })
+    }
+
     pub fn hover(&mut self, params: lsp::HoverParams) -> Response<Option<Hover>> {
         self.respond(|this| {
             let params = params.text_document_position_params;
@@ -274,51 +1205,288 @@ where
                 Some(value) => value,
                 None => return Ok(None),
             };
+            let encoding = this.position_encoding;
 
             Ok(match found {
                 Located::Statement(_) => None, // TODO: hover for statement
                 Located::ModuleStatement(Definition::Function(fun)) => {
-                    Some(hover_for_function_head(fun, lines))
+                    let purity = this
+                        .module_for_uri(&params.text_document.uri)
+                        .and_then(|module| module.ast.type_info.values.get(&fun.name))
+                        .and_then(|value| value.variant.purity());
+                    Some(hover_for_function_head(fun, purity, lines, encoding))
                 }
                 Located::ModuleStatement(Definition::ModuleConstant(constant)) => {
-                    Some(hover_for_module_constant(constant, lines))
+                    Some(hover_for_module_constant(constant, lines, encoding))
                 }
                 Located::ModuleStatement(_) => None,
-                Located::Pattern(pattern) => Some(hover_for_pattern(pattern, lines)),
+                Located::Pattern(pattern) => Some(hover_for_pattern(pattern, lines, encoding)),
                 Located::Expression(expression) => {
-                    let module = this.module_for_uri(&params.text_document.uri);
+                    // The module is only needed for the link to HexDocs.
+                    let module = this
+                        .module_for_uri(&params.text_document.uri)
+                        .filter(|_| this.configuration().hexdocs_links);
 
                     Some(hover_for_expression(
                         expression,
                         lines,
+                        encoding,
                         module,
                         &this.hex_deps,
                     ))
                 }
-                Located::Arg(arg) => Some(hover_for_function_argument(arg, lines)),
+                Located::Arg(arg) => Some(hover_for_function_argument(arg, lines, encoding)),
                 Located::FunctionBody(_) => None,
+                Located::Label {
+                    label,
+                    location,
+                    type_,
+                    of,
+                } => {
+                    let documentation = this
+                        .module_for_uri(&params.text_document.uri)
+                        .and_then(|module| this.label_definition(module, label, of))
+                        .and_then(|(_, _, documentation)| documentation);
+                    Some(hover_for_label(
+                        label,
+                        location,
+                        &type_,
+                        documentation,
+                        lines,
+                        encoding,
+                    ))
+                }
             })
         })
     }
 
-    fn module_node_at_position(
-        &self,
-        params: &lsp::TextDocumentPositionParams,
-        module: &'a Module,
-    ) -> Option<(LineNumbers, Located<'a>)> {
-        let line_numbers = LineNumbers::new(&module.code);
-        let byte_index = line_numbers.byte_index(params.position.line, params.position.character);
-        let node = module.find_node(byte_index);
-        let node = node?;
-        Some((line_numbers, node))
+    /// The definition of a label given in a call or record update: the
+    /// argument of the function or the field of the record constructor it is
+    /// for, along with the module it is in and the documentation of the field.
+    /// Labels can only be found in the modules that have been analysed.
+    fn label_definition<'b>(
+        &'b self,
+        module: &'b Module,
+        label: &EcoString,
+        of: &TypedExpr,
+    ) -> Option<(&'b Module, SrcSpan, Option<&'b EcoString>)> {
+        let module_named = |name: &str| {
+            if name == module.name {
+                return Some(module);
+            }
+            self.compiler.modules.get(name).or_else(|| {
+                self.compiler
+                    .dependency_modules
+                    .values()
+                    .find(|module| module.name == name)
+            })
+        };
+
+        // The fields given in a record update can be of any of the
+        // constructors of the type with the label.
+        if let TypedExpr::RecordUpdate { typ, .. } = of {
+            let (module_name, type_name) = typ.named_type_name()?;
+            let defining = module_named(&module_name)?;
+            return defining
+                .ast
+                .definitions
+                .iter()
+                .find_map(|definition| match definition {
+                    Definition::CustomType(custom_type) if custom_type.name == type_name => {
+                        custom_type
+                            .constructors
+                            .iter()
+                            .flat_map(|constructor| &constructor.arguments)
+                            .find(|argument| argument.label.as_ref() == Some(label))
+                    }
+                    _ => None,
+                })
+                .map(|argument| (defining, argument.location, argument.doc.as_ref()));
+        }
+
+        let TypedExpr::Call { fun, .. } = of else {
+            return None;
+        };
+        let location = fun.definition_location()?;
+        let defining = match location.module {
+            Some(name) => module_named(name)?,
+            None => module,
+        };
+        defining
+            .ast
+            .definitions
+            .iter()
+            .find_map(|definition| match definition {
+                Definition::Function(function) if function.location == location.span => function
+                    .arguments
+                    .iter()
+                    .find(|argument| argument.names.get_label() == Some(label))
+                    .map(|argument| (argument.location, None)),
+                Definition::CustomType(custom_type) => custom_type
+                    .constructors
+                    .iter()
+                    .find(|constructor| constructor.location == location.span)?
+                    .arguments
+                    .iter()
+                    .find(|argument| argument.label.as_ref() == Some(label))
+                    .map(|argument| (argument.location, argument.doc.as_ref())),
+                _ => None,
+            })
+            .map(|(location, documentation)| (defining, location, documentation))
+    }
+
+    fn module_node_at_position<'b>(
+        &'b self,
+        params: &lsp::TextDocumentPositionParams,
+        module: &'b Module,
+    ) -> Option<(&'b LineNumbers, Located<'b>)> {
+        let line_numbers = self.module_line_numbers(module)?;
+        let byte_index = line_numbers.byte_index(
+            params.position.line,
+            params.position.character,
+            self.position_encoding,
+        );
+        let node = module.find_node(byte_index);
+        let node = node?;
+        Some((line_numbers, node))
+    }
+
+    fn node_at_position(
+        &self,
+        params: &lsp::TextDocumentPositionParams,
+    ) -> Option<(&LineNumbers, Located<'_>)> {
+        let module = self.module_for_uri(&params.text_document.uri)?;
+        self.module_node_at_position(params, module)
+    }
+
+    /// The location of a span of the source of a module of the project or of
+    /// its dependencies.
+    fn source_location(&self, module: &str, span: SrcSpan) -> Option<lsp::Location> {
+        let source = self.compiler.get_source(module)?;
+        Some(lsp::Location {
+            uri: super::url(Utf8Path::new(&source.path))?,
+            range: src_span_to_lsp_range(span, &source.line_numbers, self.position_encoding),
+        })
+    }
+
+    /// The definition of the Erlang or JavaScript function referenced by the
+    /// string of an `@external` attribute at the position.
+    fn external_definition(
+        &self,
+        params: &lsp::TextDocumentPositionParams,
+    ) -> Option<lsp::Location> {
+        let module = self.module_for_uri(&params.text_document.uri)?;
+        let line_numbers = self.module_line_numbers(module)?;
+        let byte_index = line_numbers.byte_index(
+            params.position.line,
+            params.position.character,
+            self.position_encoding,
+        );
+        let external = external::external_at(&module.code, byte_index)?;
+
+        let path = match external.target.as_str() {
+            "erlang" => {
+                // Gleam modules are compiled to Erlang modules with their
+                // slashes replaced by `@`, so they may be referenced too.
+                let gleam_module = external.module.replace("@", "/");
+                if let Some(interface) = self
+                    .compiler
+                    .project_compiler
+                    .get_importable_modules()
+                    .get(gleam_module.as_str())
+                {
+                    let value = interface.values.get(&external.function)?;
+                    return self
+                        .source_location(&gleam_module, value.variant.definition_location());
+                }
+
+                let directories = [
+                    external::package_source_directory(module),
+                    Some(self.paths.src_directory()),
+                    Some(self.paths.test_directory()),
+                    Some(self.paths.build_packages_directory()),
+                ];
+                let directories = directories.into_iter().flatten().collect::<Vec<_>>();
+                external::erlang_file(
+                    &self.compiler.project_compiler.io,
+                    &directories,
+                    &external.module,
+                )?
+            }
+            "javascript" => external::javascript_file(module, &external.module)?,
+            _ => return None,
+        };
+
+        let code = self.compiler.project_compiler.io.read(&path).ok()?;
+        let span = external::function_location(&code, &external.target, &external.function)
+            .unwrap_or_default();
+        Some(lsp::Location {
+            uri: super::url(&path)?,
+            range: src_span_to_lsp_range(span, &LineNumbers::new(&code), self.position_encoding),
+        })
+    }
+
+    /// The line numbers for the code of a module, which are worked out each
+    /// time the module is compiled rather than for every request.
+    /// What is referred to by the name at the position in the module.
+    fn referenced_at(&self, uri: &Url, position: lsp::Position) -> Option<&Referenced> {
+        let module = self.module_for_uri(uri)?;
+        let line_numbers = self.module_line_numbers(module)?;
+        let byte_index =
+            line_numbers.byte_index(position.line, position.character, self.position_encoding);
+        self.compiler.references.get(&module.name)?.at(byte_index)
+    }
+
+    /// The function of the root package the name at the position refers to.
+    fn called_function(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+    ) -> Option<(&Module, &TypedFunction)> {
+        let referenced = self.referenced_at(uri, position)?;
+        call_hierarchy::function(&self.compiler.modules, referenced)
+    }
+
+    fn call_hierarchy_item(
+        &self,
+        module: &Module,
+        function: &TypedFunction,
+    ) -> Option<lsp::CallHierarchyItem> {
+        let line_numbers = self.module_line_numbers(module)?;
+        let name = reference::name_in(&module.code, function.location, &function.name)?;
+        Some(lsp::CallHierarchyItem {
+            name: function.name.to_string(),
+            kind: lsp::SymbolKind::FUNCTION,
+            tags: None,
+            detail: Some(module.name.to_string()),
+            uri: super::url(&module.input_path)?,
+            range: src_span_to_lsp_range(
+                call_hierarchy::function_location(function),
+                line_numbers,
+                self.position_encoding,
+            ),
+            selection_range: src_span_to_lsp_range(name, line_numbers, self.position_encoding),
+            data: None,
+        })
+    }
+
+    fn call_ranges(&self, module: &Module, locations: &[SrcSpan]) -> Option<Vec<lsp::Range>> {
+        let line_numbers = self.module_line_numbers(module)?;
+        Some(
+            locations
+                .iter()
+                .map(|location| {
+                    src_span_to_lsp_range(*location, line_numbers, self.position_encoding)
+                })
+                .collect(),
+        )
     }
 
-    fn node_at_position(
-        &self,
-        params: &lsp::TextDocumentPositionParams,
-    ) -> Option<(LineNumbers, Located<'_>)> {
-        let module = self.module_for_uri(&params.text_document.uri)?;
-        self.module_node_at_position(params, module)
+    fn module_line_numbers(&self, module: &Module) -> Option<&LineNumbers> {
+        self.compiler
+            .sources
+            .get(&module.name)
+            .map(|source| &source.line_numbers)
     }
 
     fn module_for_uri(&self, uri: &Url) -> Option<&Module> {
@@ -331,9 +1499,10 @@ where
         #[cfg(not(any(unix, windows, target_os = "redox", target_os = "wasi")))]
         let path: Utf8PathBuf = uri.path().into();
 
+        let Ok(path) = path.strip_prefix(self.paths.root()) else {
+            return self.compiler.dependency_modules.get(&super::path(uri));
+        };
         let components = path
-            .strip_prefix(self.paths.root())
-            .ok()?
             .components()
             .skip(1)
             .map(|c| c.as_os_str().to_string_lossy());
@@ -342,7 +1511,18 @@ where
             .strip_suffix(".gleam")?
             .into();
 
-        self.compiler.modules.get(&module_name)
+        self.compiler
+            .modules
+            .get(&module_name)
+            .or_else(|| self.compiler.dependency_modules.get(&super::path(uri)))
+    }
+
+    /// Whether the module is of a dependency package, which can be looked at
+    /// but not changed.
+    fn is_dependency_module(&self, module: &Module) -> bool {
+        self.compiler
+            .dependency_modules
+            .contains_key(&module.input_path)
     }
 
     fn completion_types<'b>(&'b self, module: &'b Module) -> Vec<lsp::CompletionItem> {
@@ -460,6 +1640,232 @@ where
         completions
     }
 
+    /// The fields of the record before the dot the position is after, as in
+    /// `wibble.` or `wibble.wob`, for the field being accessed to be completed.
+    fn completion_fields(
+        &self,
+        module: &Module,
+        byte_index: u32,
+    ) -> Option<Vec<lsp::CompletionItem>> {
+        let before = module.code.get(..byte_index as usize)?;
+        let dot = before
+            .trim_end_matches(|char: char| char.is_ascii_alphanumeric() || char == '_')
+            .strip_suffix('.')?
+            .len() as u32;
+        let Located::Expression(record) = module.find_node(dot.checked_sub(1)?)? else {
+            return None;
+        };
+        if record.location().end != dot {
+            return None;
+        }
+        let type_ = collapse_links(record.type_());
+        let Type::Named {
+            module: type_module,
+            name,
+            ..
+        } = type_.as_ref()
+        else {
+            return None;
+        };
+
+        let mut fields = if *type_module == module.name {
+            // Only the fields of the public types of a module are kept in its
+            // interface, so they are found in its definitions instead.
+            let custom_type =
+                module
+                    .ast
+                    .definitions
+                    .iter()
+                    .find_map(|definition| match definition {
+                        Definition::CustomType(custom_type) if custom_type.name == *name => {
+                            Some(custom_type)
+                        }
+                        _ => None,
+                    })?;
+            let constructor = custom_type.constructors.first()?;
+            get_compatible_record_fields(&custom_type.constructors)
+                .into_iter()
+                .filter_map(|(index, label, _)| {
+                    let argument = constructor.arguments.get(index)?;
+                    Some((index as u64, label.clone(), argument.type_.clone()))
+                })
+                .collect::<Vec<_>>()
+        } else {
+            let accessors = self
+                .compiler
+                .get_module_inferface(type_module)?
+                .accessors
+                .get(name)?;
+            accessors
+                .accessors
+                .values()
+                .map(|accessor| {
+                    (
+                        accessor.index,
+                        accessor.label.clone(),
+                        accessor.type_.clone(),
+                    )
+                })
+                .collect()
+        };
+        fields.sort_by_key(|(index, _, _)| *index);
+
+        let completions = fields
+            .into_iter()
+            .map(|(_, label, type_)| lsp::CompletionItem {
+                label: label.to_string(),
+                kind: Some(lsp::CompletionItemKind::FIELD),
+                detail: Some(Printer::new().pretty_print(&type_, 0)),
+                ..Default::default()
+            })
+            .collect();
+        Some(completions)
+    }
+
+    /// The name being written before the position, which the values and
+    /// types of modules that aren't imported are completed for. There is none
+    /// after a dot, where the name is of a field or of a value of a module.
+    fn completion_prefix(&self, src: &str, position: lsp::Position) -> String {
+        let line_numbers = LineNumbers::new(src);
+        let byte_index =
+            line_numbers.byte_index(position.line, position.character, self.position_encoding);
+        let Some(before) = src.get(..byte_index as usize) else {
+            return String::new();
+        };
+        let rest =
+            before.trim_end_matches(|char: char| char.is_ascii_alphanumeric() || char == '_');
+        if rest.ends_with('.') {
+            return String::new();
+        }
+        before.get(rest.len()..).unwrap_or_default().to_string()
+    }
+
+    /// The public values of the modules that could be imported by the module
+    /// but aren't, starting with the name being written. Each adds the import
+    /// of its module when it is picked.
+    fn completion_unimported_values<'b>(
+        &'b self,
+        module: &'b Module,
+        prefix: &str,
+    ) -> Vec<lsp::CompletionItem> {
+        let mut completions = vec![];
+        for (name, interface, import) in self.unimported_modules(module, prefix) {
+            let alias = name.rsplit('/').next().unwrap_or(name);
+            for (value_name, value) in &interface.values {
+                if value_name.starts_with(prefix) && self.is_completable(interface, value.publicity)
+                {
+                    completions.push(unimported_completion(
+                        value_name,
+                        value_completion(Some(alias), value_name, value),
+                        &import,
+                    ));
+                }
+            }
+        }
+        completions.sort_by(|one, other| one.label.cmp(&other.label));
+        completions
+    }
+
+    /// The public types of the modules that could be imported by the module
+    /// but aren't, starting with the name being written. Each adds the import
+    /// of its module when it is picked.
+    fn completion_unimported_types<'b>(
+        &'b self,
+        module: &'b Module,
+        prefix: &str,
+    ) -> Vec<lsp::CompletionItem> {
+        let mut completions = vec![];
+        for (name, interface, import) in self.unimported_modules(module, prefix) {
+            let alias: EcoString = name.rsplit('/').next().unwrap_or(name).into();
+            for (type_name, type_) in &interface.types {
+                if type_name.starts_with(prefix) && self.is_completable(interface, type_.publicity)
+                {
+                    completions.push(unimported_completion(
+                        type_name,
+                        type_completion(Some(&alias), type_name, type_),
+                        &import,
+                    ));
+                }
+            }
+        }
+        completions.sort_by(|one, other| one.label.cmp(&other.label));
+        completions
+    }
+
+    /// The modules that could be imported by the module but aren't, with the
+    /// edit importing each. There are none until the start of a name has been
+    /// written, as there would be too many to be useful.
+    fn unimported_modules<'b>(
+        &'b self,
+        module: &'b Module,
+        prefix: &str,
+    ) -> Vec<(&'b EcoString, &'b ModuleInterface, lsp::TextEdit)> {
+        if prefix.is_empty() {
+            return vec![];
+        }
+        let Some(line_numbers) = self.module_line_numbers(module) else {
+            return vec![];
+        };
+
+        // An import is added after the last one, or else at the start of the
+        // module after its module comments.
+        let last_import = module
+            .ast
+            .definitions
+            .iter()
+            .filter_map(get_import)
+            .map(|import| import.location.end)
+            .max();
+        let (line, separator) = match last_import {
+            Some(end) => (line_numbers.line_number(end), "\n"),
+            None => {
+                let comments_end = module
+                    .extra
+                    .module_comments
+                    .iter()
+                    .map(|comment| line_numbers.line_number(comment.end))
+                    .max();
+                (comments_end.unwrap_or(0), "\n\n")
+            }
+        };
+        let position = lsp::Position::new(line, 0);
+
+        // A module can't be used by a name another import already uses
+        let used_names: std::collections::HashSet<EcoString> = module
+            .ast
+            .definitions
+            .iter()
+            .filter_map(get_import)
+            .filter_map(|import| import.used_name())
+            .collect();
+
+        self.importable_modules(module)
+            .filter(|(name, _)| name.as_str() != PRELUDE_MODULE_NAME)
+            .filter(|(name, _)| {
+                let alias = name.rsplit('/').next().unwrap_or(name);
+                !used_names.contains(alias)
+            })
+            .map(|(name, interface)| {
+                let edit = lsp::TextEdit {
+                    range: lsp::Range::new(position, position),
+                    new_text: format!("import {name}{separator}"),
+                };
+                (name, interface, edit)
+            })
+            .collect()
+    }
+
+    /// Whether a value or type of a module can be completed in another one.
+    fn is_completable(&self, module: &ModuleInterface, publicity: Publicity) -> bool {
+        match publicity {
+            Publicity::Private => false,
+            // Internal values and types of other packages aren't meant to be
+            // used
+            Publicity::Internal => module.package == self.root_package_name(),
+            Publicity::Public => true,
+        }
+    }
+
     fn import_completions<'b>(
         &'b self,
         src: EcoString,
@@ -467,8 +1873,8 @@ where
         module: &'b Module,
     ) -> Option<Result<Option<Vec<lsp::CompletionItem>>>> {
         let line_num = LineNumbers::new(src.as_str());
-        let start_of_line = line_num.byte_index(params.position.line, 0);
-        let end_of_line = line_num.byte_index(params.position.line + 1, 0);
+        let start_of_line = line_num.byte_index(params.position.line, 0, self.position_encoding);
+        let end_of_line = line_num.byte_index(params.position.line + 1, 0, self.position_encoding);
 
         // Drop all lines before the line the cursor is on
         let src = &src.get(start_of_line as usize..)?;
@@ -479,8 +1885,8 @@ where
         }
 
         // Find where to start and end the import completion
-        let start = line_num.line_and_column_number(start_of_line);
-        let end = line_num.line_and_column_number(end_of_line - 1);
+        let start = line_num.line_and_column_number_in(start_of_line, self.position_encoding);
+        let end = line_num.line_and_column_number_in(end_of_line - 1, self.position_encoding);
         let start = lsp::Position::new(start.line - 1, start.column + 6);
         let end = lsp::Position::new(end.line - 1, end.column);
         let completions = self.complete_modules_for_import(module, start, end);
@@ -494,6 +1900,28 @@ where
         start: lsp::Position,
         end: lsp::Position,
     ) -> Vec<lsp::CompletionItem> {
+        self.importable_modules(current_module)
+            //
+            // Everything else we suggest as a completion
+            .map(|(name, _)| lsp::CompletionItem {
+                label: name.to_string(),
+                kind: Some(lsp::CompletionItemKind::MODULE),
+                text_edit: {
+                    Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+                        range: lsp::Range { start, end },
+                        new_text: name.to_string(),
+                    }))
+                },
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// The modules the module can import and hasn't imported yet.
+    fn importable_modules<'b>(
+        &'b self,
+        current_module: &'b Module,
+    ) -> impl Iterator<Item = (&'b EcoString, &'b ModuleInterface)> {
         let mut direct_dep_packages: std::collections::HashSet<&EcoString> =
             std::collections::HashSet::from_iter(
                 self.compiler.project_compiler.config.dependencies.keys(),
@@ -518,38 +1946,26 @@ where
             //
             // It is possible to import modules from dependencies of dependencies
             // but it's not recommended so we don't include them in completions
-            .filter(|(_, module)| {
+            .filter(move |(_, module)| {
                 let is_root_or_prelude =
                     module.package == self.root_package_name() || module.package.is_empty();
                 is_root_or_prelude || direct_dep_packages.contains(&module.package)
             })
             //
             // src/ cannot import test/
-            .filter(|(_, module)| module.origin.is_src() || !current_module.origin.is_src())
+            .filter(move |(_, module)| module.origin.is_src() || !current_module.origin.is_src())
             //
             // It is possible to import internal modules from other packages,
             // but it's not recommended so we don't include them in completions
-            .filter(|(_, module)| module.package == self.root_package_name() || !module.is_internal)
+            .filter(move |(_, module)| {
+                module.package == self.root_package_name() || !module.is_internal
+            })
             //
             // You cannot import a module twice
-            .filter(|(name, _)| !already_imported.contains(*name))
+            .filter(move |(name, _)| !already_imported.contains(*name))
             //
             // You cannot import yourself
-            .filter(|(name, _)| *name != &current_module.name)
-            //
-            // Everything else we suggest as a completion
-            .map(|(name, _)| lsp::CompletionItem {
-                label: name.to_string(),
-                kind: Some(lsp::CompletionItemKind::MODULE),
-                text_edit: {
-                    Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
-                        range: lsp::Range { start, end },
-                        new_text: name.to_string(),
-                    }))
-                },
-                ..Default::default()
-            })
-            .collect()
+            .filter(move |(name, _)| *name != &current_module.name)
     }
 
     fn root_package_name(&self) -> &str {
@@ -618,6 +2034,24 @@ fn value_completion(
     }
 }
 
+/// The completion of a value or type of a module that isn't imported, which
+/// adds the import of the module when it is picked. It is matched against the
+/// name being written without the module, and listed after the values and
+/// types already in scope.
+fn unimported_completion(
+    name: &str,
+    completion: lsp::CompletionItem,
+    import: &lsp::TextEdit,
+) -> lsp::CompletionItem {
+    lsp::CompletionItem {
+        filter_text: Some(name.into()),
+        // Editors order completions by their sort text, or else by their label
+        sort_text: Some(format!("~{}", completion.label)),
+        additional_text_edits: Some(vec![import.clone()]),
+        ..completion
+    }
+}
+
 fn get_import(statement: &TypedDefinition) -> Option<&Import<EcoString>> {
     match statement {
         Definition::Import(import) => Some(import),
@@ -625,7 +2059,11 @@ fn get_import(statement: &TypedDefinition) -> Option<&Import<EcoString>> {
     }
 }
 
-fn hover_for_pattern(pattern: &TypedPattern, line_numbers: LineNumbers) -> Hover {
+fn hover_for_pattern(
+    pattern: &TypedPattern,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
+) -> Hover {
     let documentation = pattern.get_documentation().unwrap_or_default();
 
     // Show the type of the hovered node to the user
@@ -638,13 +2076,19 @@ fn hover_for_pattern(pattern: &TypedPattern, line_numbers: LineNumbers) -> Hover
     );
     Hover {
         contents: HoverContents::Scalar(MarkedString::String(contents)),
-        range: Some(src_span_to_lsp_range(pattern.location(), &line_numbers)),
+        range: Some(src_span_to_lsp_range(
+            pattern.location(),
+            line_numbers,
+            encoding,
+        )),
     }
 }
 
 fn hover_for_function_head(
     fun: &Function<Arc<Type>, TypedExpr>,
-    line_numbers: LineNumbers,
+    purity: Option<Purity>,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
 ) -> Hover {
     let empty_str = EcoString::from("");
     let documentation = fun.documentation.as_ref().unwrap_or(&empty_str);
@@ -653,30 +2097,57 @@ fn hover_for_function_head(
         retrn: fun.return_type.clone(),
     };
     let formatted_type = Printer::new().pretty_print(&function_type, 0);
+    let effects = effects_section(purity);
     let contents = format!(
         "```gleam
 {formatted_type}
 ```
-{documentation}"
+{effects}{documentation}"
     );
     Hover {
         contents: HoverContents::Scalar(MarkedString::String(contents)),
-        range: Some(src_span_to_lsp_range(fun.location, &line_numbers)),
+        range: Some(src_span_to_lsp_range(fun.location, line_numbers, encoding)),
     }
 }
 
-fn hover_for_function_argument(argument: &Arg<Arc<Type>>, line_numbers: LineNumbers) -> Hover {
+fn hover_for_function_argument(
+    argument: &Arg<Arc<Type>>,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
+) -> Hover {
     let type_ = Printer::new().pretty_print(&argument.type_, 0);
     let contents = format!("```gleam\n{type_}\n```");
     Hover {
         contents: HoverContents::Scalar(MarkedString::String(contents)),
-        range: Some(src_span_to_lsp_range(argument.location, &line_numbers)),
+        range: Some(src_span_to_lsp_range(
+            argument.location,
+            line_numbers,
+            encoding,
+        )),
+    }
+}
+
+fn hover_for_label(
+    label: &EcoString,
+    location: SrcSpan,
+    type_: &Type,
+    documentation: Option<&EcoString>,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
+) -> Hover {
+    let type_ = Printer::new().pretty_print(type_, 0);
+    let documentation = documentation.map(EcoString::as_str).unwrap_or_default();
+    let contents = format!("```gleam\n{label}: {type_}\n```\n{documentation}");
+    Hover {
+        contents: HoverContents::Scalar(MarkedString::String(contents)),
+        range: Some(src_span_to_lsp_range(location, line_numbers, encoding)),
     }
 }
 
 fn hover_for_module_constant(
     constant: &ModuleConstant<Arc<Type>, EcoString>,
-    line_numbers: LineNumbers,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
 ) -> Hover {
     let empty_str = EcoString::from("");
     let type_ = Printer::new().pretty_print(&constant.type_, 0);
@@ -684,13 +2155,18 @@ fn hover_for_module_constant(
     let contents = format!("```gleam\n{type_}\n```\n{documentation}");
     Hover {
         contents: HoverContents::Scalar(MarkedString::String(contents)),
-        range: Some(src_span_to_lsp_range(constant.location, &line_numbers)),
+        range: Some(src_span_to_lsp_range(
+            constant.location,
+            line_numbers,
+            encoding,
+        )),
     }
 }
 
 fn hover_for_expression(
     expression: &TypedExpr,
-    line_numbers: LineNumbers,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
     module: Option<&Module>,
     hex_deps: &std::collections::HashSet<EcoString>,
 ) -> Hover {
@@ -705,15 +2181,42 @@ fn hover_for_expression(
 
     // Show the type of the hovered node to the user
     let type_ = Printer::new().pretty_print(expression.type_().as_ref(), 0);
+    let effects = effects_section(expression_purity(expression));
     let contents = format!(
         "```gleam
 {type_}
 ```
-{documentation}{link_section}"
+{effects}{documentation}{link_section}"
     );
     Hover {
         contents: HoverContents::Scalar(MarkedString::String(contents)),
-        range: Some(src_span_to_lsp_range(expression.location(), &line_numbers)),
+        range: Some(src_span_to_lsp_range(
+            expression.location(),
+            line_numbers,
+            encoding,
+        )),
+    }
+}
+
+/// The purity of the function an expression refers to, if it refers to a
+/// module function.
+fn expression_purity(expression: &TypedExpr) -> Option<Purity> {
+    match expression {
+        TypedExpr::Var { constructor, .. } => constructor.variant.purity(),
+        TypedExpr::ModuleSelect {
+            constructor: ModuleValueConstructor::Fn { purity, .. },
+            ..
+        } => Some(*purity),
+        _ => None,
+    }
+}
+
+/// Points out functions that may have side effects, so the places where
+/// effects happen stand out.
+fn effects_section(purity: Option<Purity>) -> &'static str {
+    match purity {
+        Some(Purity::Impure) => "This function may have side effects.\n\n",
+        Some(Purity::Pure | Purity::Unknown) | None => "",
     }
 }
 
@@ -726,28 +2229,27 @@ fn range_includes(outer: &lsp_types::Range, inner: &lsp_types::Range) -> bool {
 fn code_action_unused_imports(
     module: &Module,
     params: &lsp::CodeActionParams,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
     actions: &mut Vec<CodeAction>,
 ) {
     let uri = &params.text_document.uri;
-    let unused = &module.ast.type_info.unused_imports;
-
-    if unused.is_empty() {
+    let Some(fix) = crate::fix::remove_unused_imports(&module.ast) else {
         return;
-    }
+    };
 
     // Convert src spans to lsp range
-    let line_numbers = LineNumbers::new(&module.code);
     let mut hovered = false;
-    let mut edits = Vec::with_capacity(unused.len());
+    let mut edits = Vec::with_capacity(fix.edits.len());
 
-    for unused in unused {
-        let range = src_span_to_lsp_range(*unused, &line_numbers);
+    for edit in &fix.edits {
+        let range = src_span_to_lsp_range(edit.location, line_numbers, encoding);
         // Keep track of whether any unused import has is where the cursor is
         hovered = hovered || range_includes(&params.range, &range);
 
         edits.push(lsp_types::TextEdit {
             range,
-            new_text: "".into(),
+            new_text: edit.new_text(&module.code),
         });
     }
 
@@ -755,15 +2257,441 @@ fn code_action_unused_imports(
     if !hovered {
         return;
     }
-    edits.sort_by_key(|edit| edit.range.start);
 
-    CodeActionBuilder::new("Remove unused imports")
+    CodeActionBuilder::new(&fix.title)
         .kind(lsp_types::CodeActionKind::QUICKFIX)
         .changes(uri.clone(), edits)
         .preferred(true)
         .push_to(actions);
 }
 
+/// The fixes attached as data to the diagnostics the code actions are
+/// requested for. Warnings aren't kept once they have been reported, so this
+/// is how they are fixed.
+fn code_action_diagnostic_fixes(params: &lsp::CodeActionParams, actions: &mut Vec<CodeAction>) {
+    for diagnostic in &params.context.diagnostics {
+        let Some(data) = DiagnosticData::from_diagnostic(diagnostic) else {
+            continue;
+        };
+        for fix in data.fixes {
+            CodeActionBuilder::new(&fix.title)
+                .kind(lsp_types::CodeActionKind::QUICKFIX)
+                .diagnostics(vec![diagnostic.clone()])
+                .changes(params.text_document.uri.clone(), fix.edits)
+                .push_to(actions);
+        }
+    }
+}
+
+/// The source of the module at the URI and the type errors it failed to
+/// compile with, if it did.
+fn type_errors<'a>(
+    error: Option<&'a Error>,
+    uri: &Url,
+) -> Option<(&'a EcoString, &'a Vec1<TypeError>)> {
+    match error {
+        Some(Error::Type { path, src, errors }) if *path == super::path(uri) => Some((src, errors)),
+        _ => None,
+    }
+}
+
+fn code_action_generate_function(
+    error: Option<&Error>,
+    params: &lsp::CodeActionParams,
+    encoding: PositionEncoding,
+    options: FormatOptions,
+    actions: &mut Vec<CodeAction>,
+) {
+    let Some((src, errors)) = type_errors(error, &params.text_document.uri) else {
+        return;
+    };
+    let line_numbers = LineNumbers::new(src);
+
+    for error in errors {
+        let TypeError::UnknownVariable { location, name, .. } = error else {
+            continue;
+        };
+        if !rename::is_valid_name(name) {
+            continue;
+        }
+
+        // Only offer the fix when the selection touches the name of the
+        // function
+        let range = src_span_to_lsp_range(*location, &line_numbers, encoding);
+        if params.range.start > range.end || params.range.end < range.start {
+            continue;
+        }
+        let Some(definition) = generate_function::definition(
+            src,
+            name,
+            *location,
+            options.indent_width.unsigned_abs(),
+        ) else {
+            continue;
+        };
+
+        // The function goes at the end of the module, after a blank line
+        let end = src.len() as u32;
+        let new_text = if src.ends_with('\n') {
+            format!("\n{definition}")
+        } else {
+            format!("\n\n{definition}")
+        };
+        let edit = lsp_types::TextEdit {
+            range: src_span_to_lsp_range(SrcSpan::new(end, end), &line_numbers, encoding),
+            new_text,
+        };
+        CodeActionBuilder::new(&format!("Generate function `{name}`"))
+            .kind(lsp_types::CodeActionKind::QUICKFIX)
+            .changes(params.text_document.uri.clone(), vec![edit])
+            .preferred(true)
+            .push_to(actions);
+    }
+}
+
+fn code_action_missing_labels(
+    error: Option<&Error>,
+    params: &lsp::CodeActionParams,
+    encoding: PositionEncoding,
+    actions: &mut Vec<CodeAction>,
+) {
+    let Some((src, errors)) = type_errors(error, &params.text_document.uri) else {
+        return;
+    };
+    let line_numbers = LineNumbers::new(src);
+
+    for error in errors {
+        let TypeError::IncorrectArity {
+            location,
+            expected,
+            given,
+            labels,
+        } = error
+        else {
+            continue;
+        };
+        // The arguments given without labels are for the first parameters, so
+        // the missing ones are the last, and they can only be added if they
+        // all have labels.
+        let Some(missing) = expected
+            .checked_sub(*given)
+            .filter(|missing| *missing > 0)
+            .and_then(|missing| labels.get(labels.len().checked_sub(missing)?..))
+        else {
+            continue;
+        };
+
+        let range = src_span_to_lsp_range(*location, &line_numbers, encoding);
+        if params.range.start > range.end || params.range.end < range.start {
+            continue;
+        }
+        let Some(edit) = fill_labels::missing_arguments(src, *location, missing) else {
+            continue;
+        };
+        let edit = lsp_types::TextEdit {
+            range: src_span_to_lsp_range(edit.location, &line_numbers, encoding),
+            new_text: edit.new_text,
+        };
+        CodeActionBuilder::new("Add missing labelled arguments")
+            .kind(lsp_types::CodeActionKind::QUICKFIX)
+            .changes(params.text_document.uri.clone(), vec![edit])
+            .preferred(true)
+            .push_to(actions);
+    }
+}
+
+fn code_action_inexhaustive_case(
+    error: Option<&Error>,
+    params: &lsp::CodeActionParams,
+    encoding: PositionEncoding,
+    actions: &mut Vec<CodeAction>,
+) {
+    let Some((src, errors)) = type_errors(error, &params.text_document.uri) else {
+        return;
+    };
+    let line_numbers = LineNumbers::new(src);
+
+    for error in errors {
+        let TypeError::InexhaustiveCaseExpression { location, missing } = error else {
+            continue;
+        };
+
+        // Only offer the fix when the cursor is on the case expression
+        let range = src_span_to_lsp_range(*location, &line_numbers, encoding);
+        if !range_includes(&params.range, &range) {
+            continue;
+        }
+
+        // The clauses are indented one level deeper than the line the case
+        // expression starts on, and go before its closing brace.
+        let line_start = |byte_index: u32| {
+            src.get(..byte_index as usize)
+                .and_then(|before| before.rfind('\n'))
+                .map_or(0, |newline| newline as u32 + 1)
+        };
+        let case_start = line_start(location.start);
+        let indent = src
+            .get(case_start as usize..location.start as usize)
+            .map_or(0, |text| text.len() - text.trim_start().len());
+        let closing_brace = location.end.saturating_sub(1);
+        let brace_line_start = line_start(closing_brace);
+        let brace_on_own_line = brace_line_start > location.start
+            && src
+                .get(brace_line_start as usize..closing_brace as usize)
+                .is_some_and(|text| text.trim().is_empty());
+
+        let clauses = missing
+            .iter()
+            .map(|pattern| format!("{}  {pattern} -> todo\n", " ".repeat(indent)))
+            .collect::<String>();
+        let (position, new_text) = if brace_on_own_line {
+            (brace_line_start, clauses)
+        } else {
+            (closing_brace, format!("\n{clauses}{}", " ".repeat(indent)))
+        };
+
+        let edit = lsp_types::TextEdit {
+            range: src_span_to_lsp_range(SrcSpan::new(position, position), &line_numbers, encoding),
+            new_text,
+        };
+        CodeActionBuilder::new("Add missing patterns")
+            .kind(lsp_types::CodeActionKind::QUICKFIX)
+            .changes(params.text_document.uri.clone(), vec![edit])
+            .preferred(true)
+            .push_to(actions);
+    }
+}
+
+/// A snippet with a clause for each constructor of the subject of an empty
+/// case expression the cursor is in, with a tab stop in the body of each.
+fn completion_case_clauses(
+    error: Option<&Error>,
+    params: &lsp::TextDocumentPositionParams,
+    encoding: PositionEncoding,
+) -> Option<lsp::CompletionItem> {
+    let (src, errors) = type_errors(error, &params.text_document.uri)?;
+    let line_numbers = LineNumbers::new(src);
+    let byte_index =
+        line_numbers.byte_index(params.position.line, params.position.character, encoding);
+
+    errors.iter().find_map(|error| {
+        let TypeError::InexhaustiveCaseExpression { location, missing } = error else {
+            return None;
+        };
+
+        // Only empty case expressions are completed, between their braces
+        let closing_brace = location.end.checked_sub(1)?;
+        let before_brace = src.get(location.start as usize..closing_brace as usize)?;
+        let opening_brace = before_brace
+            .trim_end()
+            .strip_suffix('{')
+            .map(|before| location.start + before.len() as u32)?;
+        if src.get(closing_brace as usize..location.end as usize) != Some("}")
+            || byte_index <= opening_brace
+            || byte_index > closing_brace
+        {
+            return None;
+        }
+
+        // The clauses are indented one level deeper than the line the case
+        // expression starts on.
+        let line_start = src
+            .get(..location.start as usize)
+            .and_then(|before| before.rfind('\n'))
+            .map_or(0, |newline| newline + 1);
+        let indent = src
+            .get(line_start..location.start as usize)
+            .map_or(0, |text| text.len() - text.trim_start().len());
+        let indent = " ".repeat(indent);
+
+        let clauses = missing
+            .iter()
+            .enumerate()
+            .map(|(index, pattern)| format!("{indent}  {pattern} -> ${{{}:todo}}\n", index + 1))
+            .collect::<String>();
+        let range = src_span_to_lsp_range(
+            SrcSpan::new(opening_brace + 1, closing_brace),
+            &line_numbers,
+            encoding,
+        );
+
+        Some(lsp::CompletionItem {
+            label: missing
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            kind: Some(lsp::CompletionItemKind::SNIPPET),
+            detail: Some("Case clauses".into()),
+            insert_text_format: Some(lsp::InsertTextFormat::SNIPPET),
+            text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+                range,
+                new_text: format!("\n{clauses}{indent}"),
+            })),
+            ..Default::default()
+        })
+    })
+}
+
+fn code_action_extract(
+    module: &Module,
+    params: &lsp::CodeActionParams,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
+    options: FormatOptions,
+    actions: &mut Vec<CodeAction>,
+) {
+    let start = line_numbers.byte_index(
+        params.range.start.line,
+        params.range.start.character,
+        encoding,
+    );
+    let end = line_numbers.byte_index(params.range.end.line, params.range.end.character, encoding);
+    let selection = SrcSpan::new(start, end);
+
+    let extractions = [
+        (
+            "Extract to let binding",
+            extract::to_variable(&module.ast, &module.code, selection),
+        ),
+        (
+            "Extract to function",
+            extract::to_function(
+                &module.ast,
+                &module.code,
+                selection,
+                options.indent_width.unsigned_abs(),
+            ),
+        ),
+    ];
+    for (title, edits) in extractions {
+        let Some(edits) = edits else {
+            continue;
+        };
+        let edits = edits
+            .into_iter()
+            .map(|edit| lsp_types::TextEdit {
+                range: src_span_to_lsp_range(edit.location, line_numbers, encoding),
+                new_text: edit.new_text,
+            })
+            .collect();
+        CodeActionBuilder::new(title)
+            .kind(lsp_types::CodeActionKind::REFACTOR_EXTRACT)
+            .changes(params.text_document.uri.clone(), edits)
+            .push_to(actions);
+    }
+}
+
+fn code_action_label_arguments(
+    module: &Module,
+    modules: &im::HashMap<EcoString, ModuleInterface>,
+    params: &lsp::CodeActionParams,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
+    actions: &mut Vec<CodeAction>,
+) {
+    let byte_index = line_numbers.byte_index(
+        params.range.start.line,
+        params.range.start.character,
+        encoding,
+    );
+    let Some(call) = signature_help::find_call(&module.ast, byte_index) else {
+        return;
+    };
+    let Some(edits) = signature_help::field_map(call.fun, modules)
+        .and_then(|field_map| fill_labels::label_arguments(call, field_map))
+    else {
+        return;
+    };
+    let edits = edits
+        .into_iter()
+        .map(|edit| lsp_types::TextEdit {
+            range: src_span_to_lsp_range(edit.location, line_numbers, encoding),
+            new_text: edit.new_text,
+        })
+        .collect();
+    CodeActionBuilder::new("Add labels to arguments")
+        .kind(lsp_types::CodeActionKind::REFACTOR_REWRITE)
+        .changes(params.text_document.uri.clone(), edits)
+        .push_to(actions);
+}
+
+fn code_action_use_callback(
+    module: &Module,
+    params: &lsp::CodeActionParams,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
+    options: FormatOptions,
+    actions: &mut Vec<CodeAction>,
+) {
+    let byte_index = line_numbers.byte_index(
+        params.range.start.line,
+        params.range.start.character,
+        encoding,
+    );
+    let rewrites = [
+        (
+            "Convert to explicit callback",
+            use_callback::use_to_callback(module, byte_index, options),
+        ),
+        (
+            "Convert to `use` expression",
+            use_callback::callback_to_use(module, byte_index, options),
+        ),
+    ];
+    for (title, edit) in rewrites {
+        let Some(edit) = edit else {
+            continue;
+        };
+        let edit = lsp_types::TextEdit {
+            range: src_span_to_lsp_range(edit.location, line_numbers, encoding),
+            new_text: edit.new_text,
+        };
+        CodeActionBuilder::new(title)
+            .kind(lsp_types::CodeActionKind::REFACTOR_REWRITE)
+            .changes(params.text_document.uri.clone(), vec![edit])
+            .push_to(actions);
+    }
+}
+
+fn code_action_lint_fixes(
+    module: &Module,
+    lints: &LintsConfig,
+    params: &lsp::CodeActionParams,
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
+    actions: &mut Vec<CodeAction>,
+) {
+    let uri = &params.text_document.uri;
+
+    for warning in crate::lint::check_module(lints, &module.ast) {
+        let Some(fix) = warning.fix else {
+            continue;
+        };
+
+        // Only offer the fix when the cursor is on the problem
+        let range = src_span_to_lsp_range(warning.location, line_numbers, encoding);
+        if !range_includes(&params.range, &range) {
+            continue;
+        }
+
+        let edits = fix
+            .edits
+            .iter()
+            .map(|edit| lsp_types::TextEdit {
+                range: src_span_to_lsp_range(edit.location, line_numbers, encoding),
+                new_text: edit.new_text(&module.code),
+            })
+            .collect();
+
+        CodeActionBuilder::new(&fix.title)
+            .kind(lsp_types::CodeActionKind::QUICKFIX)
+            .changes(uri.clone(), edits)
+            .preferred(true)
+            .push_to(actions);
+    }
+}
+
 fn get_expr_qualified_name(expression: &TypedExpr) -> Option<(&EcoString, &EcoString)> {
     match expression {
         TypedExpr::Var {
@@ -0,0 +1,195 @@
+//! Going to the definition of the Erlang or JavaScript function that a Gleam
+//! function is implemented with, from the strings of its `@external`
+//! attribute.
+//!
+//! The attributes are not kept in the typed AST with their locations, so they
+//! are found by lexing the source of the module again. The function is then
+//! found with a simple search of the text of the file that defines it, as
+//! the language server can't parse Erlang or JavaScript.
+
+use std::ops::RangeInclusive;
+
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
+
+use crate::{
+    ast::SrcSpan,
+    build::Module,
+    io::FileSystemReader,
+    parse::{
+        lexer::{self, Spanned},
+        token::Token,
+    },
+};
+
+/// A function of another language referenced by an `@external` attribute,
+/// such as `@external(erlang, "wibble", "wobble")`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct External {
+    pub target: EcoString,
+    pub module: EcoString,
+    pub function: EcoString,
+}
+
+/// The external function of the attribute with one of its strings at the
+/// byte index.
+pub fn external_at(code: &str, byte_index: u32) -> Option<External> {
+    if !code.contains("@external") {
+        return None;
+    }
+
+    let tokens = lexer::make_tokenizer(code)
+        .map_while(|token| token.ok())
+        .filter(|(_, token, _)| {
+            !matches!(
+                token,
+                Token::CommentNormal
+                    | Token::CommentDoc { .. }
+                    | Token::CommentModule
+                    | Token::NewLine
+            )
+        })
+        .collect::<Vec<_>>();
+
+    tokens.windows(8).find_map(|window| {
+        let [at, attribute, _, target, _, module, _, function] = window else {
+            return None;
+        };
+        let (Token::At, Token::Name { name: attribute }, Token::Name { name: target }) =
+            (&at.1, &attribute.1, &target.1)
+        else {
+            return None;
+        };
+        let (module, module_span) = string(module)?;
+        let (function, function_span) = string(function)?;
+        let at_string = module_span.contains(&byte_index) || function_span.contains(&byte_index);
+        (attribute == "external" && at_string).then(|| External {
+            target: target.clone(),
+            module: module.clone(),
+            function: function.clone(),
+        })
+    })
+}
+
+fn string(token: &Spanned) -> Option<(&EcoString, RangeInclusive<u32>)> {
+    match token {
+        (start, Token::String { value }, end) => Some((value, *start..=*end)),
+        _ => None,
+    }
+}
+
+/// The JavaScript file imported by a module, which is relative to the
+/// directory of the Gleam module.
+pub fn javascript_file(module: &Module, path: &str) -> Option<Utf8PathBuf> {
+    let mut file = module.input_path.parent()?.to_path_buf();
+    for component in Utf8Path::new(path).components() {
+        match component {
+            Utf8Component::Normal(name) => file.push(name),
+            Utf8Component::CurDir => (),
+            Utf8Component::ParentDir => {
+                if !file.pop() {
+                    return None;
+                }
+            }
+            Utf8Component::RootDir | Utf8Component::Prefix(_) => return None,
+        }
+    }
+    Some(file)
+}
+
+/// The Erlang file defining a module, searched for in the directories and
+/// their subdirectories in order.
+pub fn erlang_file<IO: FileSystemReader>(
+    io: &IO,
+    directories: &[Utf8PathBuf],
+    module: &str,
+) -> Option<Utf8PathBuf> {
+    let file_name = format!("{module}.erl");
+    directories
+        .iter()
+        .find_map(|directory| find_file(io, directory, &file_name))
+}
+
+fn find_file<IO: FileSystemReader>(
+    io: &IO,
+    directory: &Utf8Path,
+    file_name: &str,
+) -> Option<Utf8PathBuf> {
+    io.read_dir(directory)
+        .ok()?
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .find_map(|entry| {
+            let path = entry.pathbuf;
+            if io.is_file(&path) {
+                (path.file_name() == Some(file_name)).then_some(path)
+            } else if io.is_directory(&path) {
+                find_file(io, &path, file_name)
+            } else {
+                None
+            }
+        })
+}
+
+/// The source directory of the package a module is in, such as `src` for
+/// `src/wibble/wobble.gleam`.
+pub fn package_source_directory(module: &Module) -> Option<Utf8PathBuf> {
+    let path = module.input_path.as_str();
+    let directory = path.strip_suffix(&format!("{}.gleam", module.name))?;
+    Some(Utf8PathBuf::from(directory))
+}
+
+/// Where a function is defined in the code of an Erlang or JavaScript file,
+/// found by looking for a line that starts its definition.
+pub fn function_location(code: &str, target: &str, function: &str) -> Option<SrcSpan> {
+    let mut line_start = 0;
+    for line in code.split_inclusive('\n') {
+        let name_start = match target {
+            "erlang" => erlang_definition(line, function),
+            "javascript" => javascript_definition(line, function),
+            _ => None,
+        };
+        if let Some(name_start) = name_start {
+            let start = (line_start + name_start) as u32;
+            return Some(SrcSpan::new(start, start + function.len() as u32));
+        }
+        line_start += line.len();
+    }
+    None
+}
+
+/// Erlang functions are defined by clauses at the start of a line, such as
+/// `wobble(X) ->`.
+fn erlang_definition(line: &str, function: &str) -> Option<usize> {
+    let (name_start, rest) = match line.strip_prefix('\'') {
+        Some(quoted) => (1, quoted.strip_prefix(function)?.strip_prefix('\'')?),
+        None => (0, line.strip_prefix(function)?),
+    };
+    rest.trim_start().starts_with('(').then_some(name_start)
+}
+
+/// JavaScript functions can be defined as functions, or as variables or
+/// classes holding them, and are exported from their modules.
+fn javascript_definition(line: &str, function: &str) -> Option<usize> {
+    let trimmed = line.trim_start();
+    let trimmed = trimmed
+        .strip_prefix("export ")
+        .unwrap_or(trimmed)
+        .trim_start();
+    let trimmed = trimmed
+        .strip_prefix("async ")
+        .unwrap_or(trimmed)
+        .trim_start();
+    let declaration = ["function*", "function", "const", "let", "var", "class"]
+        .into_iter()
+        .find_map(|keyword| trimmed.strip_prefix(keyword))?;
+    let name = declaration.trim_start();
+    if name.len() == declaration.len() {
+        return None;
+    }
+    let after = name.strip_prefix(function)?;
+    if after.starts_with(|c: char| c.is_alphanumeric() || c == '_' || c == '$') {
+        return None;
+    }
+    Some(line.len() - name.len())
+}
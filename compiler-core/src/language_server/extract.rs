@@ -0,0 +1,384 @@
+//! Extracting a selected expression into a `let` binding before the
+//! statement it is in, or into a new function of the module taking the
+//! variables it uses as arguments.
+
+use std::{collections::HashSet, sync::Arc};
+
+use ecow::{eco_format, EcoString};
+use itertools::Itertools;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        BinOp, ClauseGuard, Definition, Pattern, SrcSpan, TypeAst, TypedArg, TypedClauseGuard,
+        TypedExpr, TypedFunction, TypedModule, TypedPattern, TypedStatement,
+    },
+    type_::{Type, ValueConstructor, ValueConstructorVariant},
+};
+
+use super::{code_action::local_variables, rename::Edit};
+
+/// The edit extracting the selected expression into a variable defined with
+/// a `let` just before the statement the expression is in. Nothing is
+/// extracted if the expression uses a variable defined after the start of
+/// the statement, such as one bound by the pattern of a case clause, or if it
+/// is only evaluated conditionally, such as the body of a case clause or the
+/// right hand side of `&&`, without a statement of its own to go before.
+pub fn to_variable(module: &TypedModule, code: &str, selection: SrcSpan) -> Option<Vec<Edit>> {
+    let found = Found::new(module, code, selection)?;
+    let statement = found.statement?;
+    let is_defined_before = found.variables.iter().all(|variable| {
+        variable
+            .definition
+            .is_some_and(|definition| definition.start < statement.start)
+    });
+    if !is_defined_before {
+        return None;
+    }
+
+    let indent = indentation(code, statement.start)?;
+    let name = unused_name(module, "value");
+    let expression = reindent(
+        code.get(found.selection.start as usize..found.selection.end as usize)?,
+        line_indentation(code, found.selection.start),
+        indent,
+    );
+    let before = code.get(statement.start as usize..found.selection.start as usize)?;
+    let padding = " ".repeat(indent);
+    Some(vec![Edit {
+        location: SrcSpan::new(statement.start, found.selection.end),
+        new_text: format!("let {name} = {expression}\n{padding}{before}{name}"),
+    }])
+}
+
+/// The edits extracting the selected expression into a new function defined
+/// after the function it is in, called with the variables the expression
+/// uses. The body of the new function is indented by `indent_width` spaces.
+pub fn to_function(
+    module: &TypedModule,
+    code: &str,
+    selection: SrcSpan,
+    indent_width: usize,
+) -> Option<Vec<Edit>> {
+    let found = Found::new(module, code, selection)?;
+    let function = found.function?;
+
+    let name = unused_name(module, "function");
+    let arguments = found
+        .variables
+        .iter()
+        .map(|variable| &variable.name)
+        .join(", ");
+    let expression = reindent(
+        code.get(found.selection.start as usize..found.selection.end as usize)?,
+        line_indentation(code, found.selection.start),
+        indent_width,
+    );
+    let padding = " ".repeat(indent_width);
+    Some(vec![
+        Edit {
+            location: found.selection,
+            new_text: format!("{name}({arguments})"),
+        },
+        Edit {
+            location: SrcSpan::new(function.end_position, function.end_position),
+            new_text: format!("\n\nfn {name}({arguments}) {{\n{padding}{expression}\n}}"),
+        },
+    ])
+}
+
+/// The expression that is selected, and where it is.
+struct Found<'a> {
+    selection: SrcSpan,
+    function: Option<&'a TypedFunction>,
+    /// The location of the innermost statement the expression is in, if the
+    /// expression is evaluated whenever that statement is.
+    statement: Option<SrcSpan>,
+    /// The variables the expression uses that are defined outside of it, in
+    /// the order they are first used.
+    variables: Vec<Variable>,
+}
+
+struct Variable {
+    name: EcoString,
+    /// Where the variable is defined. The variables used in clause guards
+    /// aren't linked to their definitions, so this isn't always known.
+    definition: Option<SrcSpan>,
+}
+
+impl<'a> Found<'a> {
+    fn new(module: &'a TypedModule, code: &str, selection: SrcSpan) -> Option<Self> {
+        let selection = trim(code, selection)?;
+        let mut finder = ExpressionFinder {
+            code,
+            selection,
+            expression: None,
+            function: None,
+            statement: None,
+            in_capture: false,
+        };
+        finder.visit_typed_module(module);
+        let expression = finder.expression?;
+
+        let mut variables = VariableFinder {
+            selection,
+            defined: HashSet::new(),
+            variables: vec![],
+        };
+        variables.visit_typed_expr(expression);
+        // The variables a pipeline or a function capture are desugared into
+        // can't be written in the source.
+        if variables
+            .variables
+            .iter()
+            .any(|variable| variable.name.starts_with('_'))
+        {
+            return None;
+        }
+
+        Some(Self {
+            selection,
+            function: finder.function,
+            statement: finder.statement,
+            variables: variables.variables,
+        })
+    }
+}
+
+struct ExpressionFinder<'a, 'code> {
+    code: &'code str,
+    selection: SrcSpan,
+    expression: Option<&'a TypedExpr>,
+    function: Option<&'a TypedFunction>,
+    statement: Option<SrcSpan>,
+    /// Whether the expressions visited are in the body of a function capture
+    /// such as `add(_, 1)`, which has no statements in the source.
+    in_capture: bool,
+}
+
+impl ExpressionFinder<'_, '_> {
+    fn contains_selection(&self, location: SrcSpan) -> bool {
+        location.start <= self.selection.start && self.selection.end <= location.end
+    }
+
+    fn is_extractable(&self, expression: &TypedExpr) -> bool {
+        // A label shorthand such as `name:` can't be moved
+        let is_shorthand = self
+            .code
+            .get(..self.selection.end as usize)
+            .is_some_and(|code| code.ends_with(':'));
+        match expression {
+            _ if is_shorthand => false,
+            // Extracting a variable gains nothing
+            TypedExpr::Var { constructor, .. } => !constructor.variant.is_local_variable(),
+            // Calls with arguments given by a pipeline or a `use` expression
+            // can't be written without them.
+            TypedExpr::Call { args, .. } => !args.iter().any(|arg| arg.implicit),
+            _ => true,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for ExpressionFinder<'ast, '_> {
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        let location = SrcSpan::new(function.location.start, function.end_position);
+        if self.contains_selection(location) {
+            self.function = Some(function);
+            visit::visit_typed_function(self, function);
+        }
+    }
+
+    fn visit_typed_statement(&mut self, statement: &'ast TypedStatement) {
+        if !self.in_capture && self.contains_selection(statement.location()) {
+            self.statement = Some(statement.location());
+        }
+        visit::visit_typed_statement(self, statement);
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        // The outermost expression at the selection is extracted
+        if self.expression.is_none()
+            && expr.location() == self.selection
+            && self.is_extractable(expr)
+        {
+            self.expression = Some(expr);
+        }
+        if !self.contains_selection(expr.location()) {
+            return;
+        }
+
+        // An expression that is only evaluated on some of the runs of the
+        // statement it is in can't be moved before that statement.
+        let is_conditional = match expr {
+            TypedExpr::Case { clauses, .. } => clauses
+                .iter()
+                .any(|clause| self.contains_selection(clause.then.location())),
+            TypedExpr::BinOp {
+                name: BinOp::And | BinOp::Or,
+                right,
+                ..
+            } => self.contains_selection(right.location()),
+            _ => false,
+        };
+        if is_conditional {
+            self.statement = None;
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'ast SrcSpan,
+        typ: &'ast Arc<Type>,
+        is_capture: &'ast bool,
+        args: &'ast [TypedArg],
+        body: &'ast [TypedStatement],
+        return_annotation: &'ast Option<TypeAst>,
+    ) {
+        let in_capture = self.in_capture;
+        self.in_capture = *is_capture;
+        visit::visit_typed_expr_fn(
+            self,
+            location,
+            typ,
+            is_capture,
+            args,
+            body,
+            return_annotation,
+        );
+        self.in_capture = in_capture;
+    }
+}
+
+struct VariableFinder {
+    selection: SrcSpan,
+    /// The names of the variables defined in the expression.
+    defined: HashSet<EcoString>,
+    variables: Vec<Variable>,
+}
+
+impl VariableFinder {
+    fn add(&mut self, name: &EcoString, definition: Option<SrcSpan>) {
+        if !self.variables.iter().any(|variable| &variable.name == name) {
+            self.variables.push(Variable {
+                name: name.clone(),
+                definition,
+            });
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for VariableFinder {
+    fn visit_typed_expr_var(
+        &mut self,
+        _location: &'ast SrcSpan,
+        constructor: &'ast ValueConstructor,
+        name: &'ast EcoString,
+    ) {
+        if let ValueConstructorVariant::LocalVariable { location } = &constructor.variant {
+            let is_defined_outside =
+                location.start < self.selection.start || location.end > self.selection.end;
+            if is_defined_outside {
+                self.add(name, Some(*location));
+            }
+        }
+    }
+
+    fn visit_typed_arg(&mut self, arg: &'ast TypedArg) {
+        if let Some(name) = arg.names.get_variable_name() {
+            _ = self.defined.insert(name.clone());
+        }
+        visit::visit_typed_arg(self, arg);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        if let Pattern::Variable { name, .. } | Pattern::Assign { name, .. } = pattern {
+            _ = self.defined.insert(name.clone());
+        }
+        visit::visit_typed_pattern(self, pattern);
+    }
+
+    fn visit_typed_clause_guard(&mut self, guard: &'ast TypedClauseGuard) {
+        if let ClauseGuard::Var { name, .. } = guard {
+            if !self.defined.contains(name) {
+                self.add(name, None);
+            }
+        }
+        visit::visit_typed_clause_guard(self, guard);
+    }
+}
+
+/// The selection without the whitespace around it.
+fn trim(code: &str, selection: SrcSpan) -> Option<SrcSpan> {
+    let text = code.get(selection.start as usize..selection.end as usize)?;
+    let start = selection.start + (text.len() - text.trim_start().len()) as u32;
+    let end = selection.end - (text.len() - text.trim_end().len()) as u32;
+    (start < end).then_some(SrcSpan::new(start, end))
+}
+
+/// The indentation of the line the position is on, if there is only
+/// whitespace before the position on it.
+fn indentation(code: &str, position: u32) -> Option<usize> {
+    let before = code.get(..position as usize)?;
+    let line = before.rsplit('\n').next().unwrap_or(before);
+    line.trim().is_empty().then_some(line.len())
+}
+
+/// The indentation of the line the position is on.
+fn line_indentation(code: &str, position: u32) -> usize {
+    let before = code.get(..position as usize).unwrap_or_default();
+    let line = before.rsplit('\n').next().unwrap_or(before);
+    line.len() - line.trim_start().len()
+}
+
+/// Moves the lines of the code after the first from one indentation to
+/// another, keeping their indentation relative to each other.
+fn reindent(code: &str, from: usize, to: usize) -> String {
+    let padding = " ".repeat(to);
+    code.split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            let stripped = line
+                .get(..from)
+                .filter(|indent| indent.trim().is_empty())
+                .and_then(|_| line.get(from..));
+            match stripped {
+                Some(line) if index > 0 && !line.is_empty() => format!("{padding}{line}"),
+                _ => line.into(),
+            }
+        })
+        .join("\n")
+}
+
+/// A name for a new variable or function that doesn't shadow a variable,
+/// function, constant, or imported module or value of the module.
+fn unused_name(module: &TypedModule, name: &str) -> EcoString {
+    let mut taken = local_variables(module);
+    for definition in &module.definitions {
+        match definition {
+            Definition::Function(function) => {
+                _ = taken.insert(function.name.clone());
+            }
+            Definition::ModuleConstant(constant) => {
+                _ = taken.insert(constant.name.clone());
+            }
+            Definition::Import(import) => {
+                taken.extend(import.used_name());
+                taken.extend(
+                    import
+                        .unqualified_values
+                        .iter()
+                        .map(|value| value.used_name().clone()),
+                );
+            }
+            Definition::CustomType(_) | Definition::TypeAlias(_) => (),
+        }
+    }
+    if !taken.contains(name) {
+        return name.into();
+    }
+    (2..)
+        .map(|index| eco_format!("{name}_{index}"))
+        .find(|name| !taken.contains(name))
+        .unwrap_or_else(|| name.into())
+}
@@ -116,20 +116,21 @@ impl FeedbackBookKeeper {
         compilation: Compilation,
         warnings: Vec<Warning>,
     ) -> Feedback {
-        let diagnostic = error.to_diagnostic();
         let mut feedback = self.response(compilation, warnings);
 
         // A new error means that any existing errors are no longer valid. Unset them.
         self.unset_errors(&mut feedback);
 
-        match diagnostic.location.as_ref().map(|l| l.path.clone()) {
-            Some(path) => {
-                _ = self.files_with_errors.insert(path.clone());
-                feedback.append_diagnostic(path, diagnostic);
-            }
+        for diagnostic in error.to_diagnostics() {
+            match diagnostic.location.as_ref().map(|l| l.path.clone()) {
+                Some(path) => {
+                    _ = self.files_with_errors.insert(path.clone());
+                    feedback.append_diagnostic(path, diagnostic);
+                }
 
-            None => {
-                feedback.append_message(diagnostic);
+                None => {
+                    feedback.append_message(diagnostic);
+                }
             }
         }
 
@@ -142,9 +143,13 @@ impl FeedbackBookKeeper {
 
     fn insert_warning(&mut self, feedback: &mut Feedback, warning: Warning) {
         let diagnostic = warning.to_diagnostic();
-        if let Some(path) = diagnostic.location.as_ref().map(|l| l.path.clone()) {
-            _ = self.files_with_warnings.insert(path.clone());
-            feedback.append_diagnostic(path, diagnostic);
+        match diagnostic.location.as_ref().map(|l| l.path.clone()) {
+            Some(path) => {
+                _ = self.files_with_warnings.insert(path.clone());
+                feedback.append_diagnostic(path, diagnostic);
+            }
+
+            None => feedback.append_message(diagnostic),
         }
     }
 }
@@ -255,6 +260,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn locationless_warning() {
+        // Warnings without a location, such as the summary of a dependency's
+        // warnings, are sent as messages.
+
+        let mut book_keeper = FeedbackBookKeeper::default();
+        let warning = Warning::DependencyWarnings {
+            package: "wibble".into(),
+            count: 2,
+        };
+
+        let feedback = book_keeper.response(Compilation::Yes(vec![]), vec![warning.clone()]);
+
+        assert_eq!(
+            Feedback {
+                diagnostics: HashMap::new(),
+                messages: vec![warning.to_diagnostic()],
+            },
+            feedback
+        );
+    }
+
     #[test]
     fn error() {
         // The failed method sets an additional diagnostic if the error has a
@@ -460,6 +487,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -468,6 +496,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -479,6 +508,7 @@ mod tests {
                     text: "Error 3".to_string(),
                     title: "Error 3".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![],
@@ -495,6 +525,7 @@ mod tests {
                             text: "Error 1".to_string(),
                             title: "Error 1".to_string(),
                             level: Level::Error,
+                            code: None,
                         }],
                     ),
                     (
@@ -505,6 +536,7 @@ mod tests {
                             text: "Error 3".to_string(),
                             title: "Error 3".to_string(),
                             level: Level::Error,
+                            code: None,
                         }],
                     ),
                 ]),
@@ -514,6 +546,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    code: None,
                 },],
             }
         );
@@ -530,6 +563,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -538,6 +572,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -549,6 +584,7 @@ mod tests {
                     text: "Error 3".to_string(),
                     title: "Error 3".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![],
@@ -564,6 +600,7 @@ mod tests {
                         text: "Error 3".to_string(),
                         title: "Error 3".to_string(),
                         level: Level::Error,
+                        code: None,
                     }],
                 ),]),
                 messages: vec![Diagnostic {
@@ -572,6 +609,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    code: None,
                 },],
             }
         );
@@ -588,6 +626,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -596,6 +635,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -606,6 +646,7 @@ mod tests {
                 text: "Error 3".to_string(),
                 title: "Error 3".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         });
         assert_eq!(
@@ -619,6 +660,7 @@ mod tests {
                         text: "Error 1".to_string(),
                         title: "Error 1".to_string(),
                         level: Level::Error,
+                        code: None,
                     },],
                 ),]),
                 messages: vec![
@@ -628,6 +670,7 @@ mod tests {
                         text: "Error 2".to_string(),
                         title: "Error 2".to_string(),
                         level: Level::Error,
+                        code: None,
                     },
                     Diagnostic {
                         location: None,
@@ -635,6 +678,7 @@ mod tests {
                         text: "Error 3".to_string(),
                         title: "Error 3".to_string(),
                         level: Level::Error,
+                        code: None,
                     }
                 ],
             }
@@ -652,6 +696,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -660,6 +705,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -678,6 +724,7 @@ mod tests {
                             text: "Error 1".to_string(),
                             title: "Error 1".to_string(),
                             level: Level::Error,
+                            code: None,
                         },],
                     ),
                     (Utf8PathBuf::from("src/file2.gleam"), vec![],),
@@ -688,6 +735,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    code: None,
                 },],
             }
         );
@@ -704,6 +752,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -712,6 +761,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -728,6 +778,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    code: None,
                 },],
             }
         );
@@ -1,15 +1,19 @@
-use std::time::SystemTime;
+use std::{cell::RefCell, collections::HashMap, rc::Rc, time::SystemTime};
 
 use debug_ignore::DebugIgnore;
+use lsp_types::TextDocumentContentChangeEvent;
 
 use crate::{
     io::{
         memory::InMemoryFileSystem, CommandExecutor, FileSystemReader, FileSystemWriter, ReadDir,
         Stdio, WrappedReader,
     },
+    line_numbers::PositionEncoding,
     Result,
 };
 
+use super::document::Document;
+
 use camino::{Utf8Path, Utf8PathBuf};
 
 // A proxy intended for `LanguageServer` to use when files are modified in
@@ -20,10 +24,15 @@ use camino::{Utf8Path, Utf8PathBuf};
 // `InMemoryFileSystem` first and fallback to use the `ProjectIO` if the file
 // was not found in the cache.
 //
+// The files open in the editor are kept as documents, which the changes the
+// editor sends are applied to before their text is written to the cache. Like
+// the cache they are shared by all the clones of the proxy.
+//
 #[derive(Debug, Clone)]
 pub struct FileSystemProxy<IO> {
     io: DebugIgnore<IO>,
     edit_cache: InMemoryFileSystem,
+    documents: Rc<RefCell<HashMap<Utf8PathBuf, Document>>>,
 }
 
 impl<IO> FileSystemProxy<IO>
@@ -34,6 +43,7 @@ where
         Self {
             io: io.into(),
             edit_cache: InMemoryFileSystem::new(),
+            documents: Default::default(),
         }
     }
 
@@ -51,6 +61,43 @@ where
     pub fn delete_mem_cache(&self, path: &Utf8Path) -> Result<()> {
         self.edit_cache.delete_directory(path)
     }
+
+    /// Caches the text of a file that has been opened in the editor.
+    pub fn open_document(&mut self, path: &Utf8Path, text: String) -> Result<()> {
+        self.cache_document(path, Document::new(text))
+    }
+
+    /// Applies the changes the editor made to an open file and caches its new
+    /// text. Editors only send changes for files they have opened, so there is
+    /// nothing to apply them to for any other file.
+    pub fn edit_document(
+        &mut self,
+        path: &Utf8Path,
+        changes: Vec<TextDocumentContentChangeEvent>,
+        encoding: PositionEncoding,
+    ) -> Result<()> {
+        let Some(mut document) = self.documents.borrow_mut().remove(path) else {
+            return Ok(());
+        };
+        document.apply_changes(changes, encoding);
+        self.cache_document(path, document)
+    }
+
+    /// Forgets a file that has been closed in the editor, so it is read from
+    /// the disc again.
+    pub fn close_document(&mut self, path: &Utf8Path) -> Result<()> {
+        _ = self.documents.borrow_mut().remove(path);
+        self.delete_mem_cache(path)
+    }
+
+    fn cache_document(&mut self, path: &Utf8Path, document: Document) -> Result<()> {
+        let result = self.write_mem_cache(path, document.text());
+        _ = self
+            .documents
+            .borrow_mut()
+            .insert(path.to_path_buf(), document);
+        result
+    }
 }
 
 // All write operations goes to disk (for mem-cache use the dedicated `_mem_cache` methods)
@@ -0,0 +1,107 @@
+//! Filling in the labels of the arguments of a call to a function or record
+//! constructor that has labelled parameters, and the labelled arguments that
+//! are missing from one.
+
+use std::collections::HashMap;
+
+use ecow::EcoString;
+use itertools::Itertools;
+
+use crate::{
+    ast::{Definition, SrcSpan, UntypedExpr},
+    parse,
+    type_::FieldMap,
+};
+
+use super::{rename::Edit, signature_help::Call};
+
+/// The edits giving each argument of the call that isn't labelled the label
+/// of the parameter it is given for, such as `Person(name: "Lucy", age: 9)`
+/// for `Person("Lucy", 9)`.
+pub fn label_arguments(call: Call<'_>, field_map: &FieldMap) -> Option<Vec<Edit>> {
+    let labels: HashMap<_, _> = field_map
+        .fields
+        .iter()
+        .map(|(label, index)| (*index as usize, label))
+        .collect();
+    // The arguments of a typed call are in the order of the parameters, which
+    // isn't the order they are written in if some are labelled.
+    let edits = call
+        .args
+        .iter()
+        .enumerate()
+        .filter(|(_, arg)| arg.label.is_none() && !arg.implicit)
+        .filter_map(|(index, arg)| {
+            let label = labels.get(&index)?;
+            let start = arg.location.start;
+            Some(Edit {
+                location: SrcSpan::new(start, start),
+                new_text: format!("{label}: "),
+            })
+        })
+        .sorted_by_key(|edit| edit.location.start)
+        .collect_vec();
+    (!edits.is_empty()).then_some(edits)
+}
+
+/// The edit adding the labelled arguments that are missing from the call at
+/// the location, with `todo` as their values, before its closing bracket.
+pub fn missing_arguments(src: &str, location: SrcSpan, labels: &[EcoString]) -> Option<Edit> {
+    let before = src.get(..location.end as usize)?.strip_suffix(')')?;
+    if labels.is_empty() || !is_call(src, location) {
+        return None;
+    }
+
+    let arguments = before.trim_end();
+    let separator = if arguments.ends_with('(') {
+        ""
+    } else if arguments.ends_with(',') {
+        " "
+    } else {
+        ", "
+    };
+    let end = arguments.len() as u32;
+    let missing = labels
+        .iter()
+        .map(|label| format!("{label}: todo"))
+        .join(", ");
+    Some(Edit {
+        location: SrcSpan::new(end, end),
+        new_text: format!("{separator}{missing}"),
+    })
+}
+
+/// Whether the location is that of a call rather than of a constructor
+/// pattern, which the type checker reports the same errors for.
+fn is_call(src: &str, location: SrcSpan) -> bool {
+    let Ok(parsed) = parse::parse_module(src) else {
+        return false;
+    };
+    parsed
+        .module
+        .definitions
+        .iter()
+        .any(|definition| match &definition.definition {
+            Definition::Function(function) => function
+                .body
+                .iter()
+                .any(|statement| has_call(statement.value(), location)),
+            Definition::TypeAlias(_)
+            | Definition::CustomType(_)
+            | Definition::Import(_)
+            | Definition::ModuleConstant(_) => false,
+        })
+}
+
+fn has_call(expression: &UntypedExpr, location: SrcSpan) -> bool {
+    match expression {
+        UntypedExpr::Call {
+            location: call_location,
+            ..
+        } if *call_location == location => true,
+        _ => expression
+            .children()
+            .into_iter()
+            .any(|child| has_call(child, location)),
+    }
+}
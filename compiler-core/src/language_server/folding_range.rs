@@ -0,0 +1,112 @@
+//! Folding ranges, which let editors collapse the parts of a module spanning
+//! several lines: functions, custom types, case expressions and their
+//! clauses, blocks, and groups of imports. They are found in the untyped AST
+//! of the source, so modules that fail to compile can be folded too.
+
+use itertools::Itertools;
+use lsp_types as lsp;
+
+use crate::{
+    ast::{Definition, SrcSpan, UntypedExpr},
+    line_numbers::LineNumbers,
+    parse,
+};
+
+/// The folding ranges of the source of a module, ordered by the line they
+/// start on. When several start on the same line only the outermost is kept.
+pub fn folding_ranges(src: &str) -> Vec<lsp::FoldingRange> {
+    let (parsed, _) = parse::parse_module_with_recovery(src);
+    let mut folder = Folder {
+        line_numbers: LineNumbers::new(src),
+        ranges: vec![],
+    };
+
+    let mut imports: Option<SrcSpan> = None;
+    for definition in &parsed.module.definitions {
+        if let Definition::Import(import) = &definition.definition {
+            imports = Some(match imports {
+                Some(group) => SrcSpan::new(group.start, import.location.end),
+                None => import.location,
+            });
+            continue;
+        }
+        if let Some(group) = imports.take() {
+            folder.add(group, Some(lsp::FoldingRangeKind::Imports));
+        }
+
+        match &definition.definition {
+            Definition::Function(function) => {
+                folder.add(
+                    SrcSpan::new(function.location.start, function.end_position),
+                    None,
+                );
+                for statement in &function.body {
+                    folder.expression(statement.value());
+                }
+            }
+            Definition::CustomType(custom_type) => folder.add(
+                SrcSpan::new(custom_type.location.start, custom_type.end_position),
+                None,
+            ),
+            Definition::TypeAlias(_) | Definition::Import(_) | Definition::ModuleConstant(_) => (),
+        }
+    }
+    if let Some(group) = imports {
+        folder.add(group, Some(lsp::FoldingRangeKind::Imports));
+    }
+
+    folder
+        .ranges
+        .into_iter()
+        .sorted_by_key(|range| range.start_line)
+        .dedup_by(|one, other| one.start_line == other.start_line)
+        .collect()
+}
+
+struct Folder {
+    line_numbers: LineNumbers,
+    ranges: Vec<lsp::FoldingRange>,
+}
+
+impl Folder {
+    fn add(&mut self, location: SrcSpan, kind: Option<lsp::FoldingRangeKind>) {
+        // Lines are counted from zero in the protocol, and the end of the
+        // location is just past its last character.
+        let start_line = self.line_numbers.line_number(location.start) - 1;
+        let end_line = self
+            .line_numbers
+            .line_number(location.end.saturating_sub(1))
+            - 1;
+        if end_line > start_line {
+            self.ranges.push(lsp::FoldingRange {
+                start_line,
+                end_line,
+                kind,
+                ..Default::default()
+            });
+        }
+    }
+
+    fn expression(&mut self, expression: &UntypedExpr) {
+        match expression {
+            UntypedExpr::Case {
+                location, clauses, ..
+            } => {
+                self.add(*location, None);
+                for clause in clauses {
+                    self.add(clause.location, None);
+                }
+            }
+            UntypedExpr::Block { location, .. }
+            | UntypedExpr::Fn {
+                location,
+                is_capture: false,
+                ..
+            } => self.add(*location, None),
+            _ => (),
+        }
+        for child in expression.children() {
+            self.expression(child);
+        }
+    }
+}
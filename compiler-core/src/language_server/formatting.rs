@@ -0,0 +1,46 @@
+//! The edits turning the source of a module into its formatted code. Only the
+//! lines that change are edited rather than the whole module, so editors keep
+//! the position of the cursor and the regions folded in the rest of it.
+
+use lsp_types::{Position, Range, TextEdit};
+use similar::{DiffTag, TextDiff};
+
+/// The edits replacing each run of lines of the source that differs from the
+/// formatted code with the lines it is formatted as.
+pub fn edits(src: &str, formatted: &str) -> Vec<TextEdit> {
+    let diff = TextDiff::from_lines(src, formatted);
+    let lines = diff.new_slices();
+    diff.ops()
+        .iter()
+        .map(|op| op.as_tag_tuple())
+        .filter(|(tag, _, _)| *tag != DiffTag::Equal)
+        .map(|(_, old, new)| TextEdit {
+            range: Range::new(
+                Position::new(old.start as u32, 0),
+                Position::new(old.end as u32, 0),
+            ),
+            new_text: lines.get(new).unwrap_or_default().concat(),
+        })
+        .collect()
+}
+
+/// The edits that change lines of the range.
+pub fn in_range(edits: Vec<TextEdit>, range: Range) -> Vec<TextEdit> {
+    let first = range.start.line;
+    // A range ending at the start of a line doesn't include that line
+    let last = if range.end.character == 0 && range.end.line > first {
+        range.end.line - 1
+    } else {
+        range.end.line
+    };
+    edits
+        .into_iter()
+        .filter(|edit| {
+            // An edit replaces the lines from its start up to the line its
+            // end is at, or inserts lines before its start if it is empty.
+            let start = edit.range.start.line;
+            let end = edit.range.end.line;
+            start <= last && (end > first || (start == end && start >= first))
+        })
+        .collect()
+}
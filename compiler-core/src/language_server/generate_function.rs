@@ -0,0 +1,109 @@
+//! Generating a function for a call to a function that doesn't exist yet, so
+//! programs can be written top down by calling functions before they are
+//! defined.
+//!
+//! A module with an unknown variable fails to type check, so the call is
+//! found by parsing it again from the source of the module.
+
+use ecow::{eco_format, EcoString};
+use itertools::Itertools;
+
+use crate::{
+    ast::{SrcSpan, Statement, UntypedExpr},
+    parse::{self, lexer, token::Token},
+};
+
+/// The definition of a function for the call to the unknown function at the
+/// location, such as `fn add(arg_1: Int, to to: Int) {\n  todo\n}`. The types
+/// of arguments are only given where they are literals, as the types of the
+/// other arguments can't be known without type checking the call. The body is
+/// indented by `indent_width` spaces.
+pub fn definition(src: &str, name: &str, location: SrcSpan, indent_width: usize) -> Option<String> {
+    let start = location.start as usize;
+    let end = call_end(src, location.end)? as usize;
+    let Ok(statements) = parse::parse_statement_sequence(src.get(start..end)?) else {
+        return None;
+    };
+    let Statement::Expression(UntypedExpr::Call { arguments, .. }) = statements.first() else {
+        return None;
+    };
+
+    let before = src.get(..start)?.trim_end();
+    let mut parameters = vec![];
+    // A piped value is given as the first argument, and the callback of a
+    // `use` expression as the last one.
+    if before.ends_with("|>") {
+        parameters.push((None, None));
+    }
+    for argument in arguments {
+        parameters.push((argument.label.clone(), Some(&argument.value)));
+    }
+    if before.ends_with("<-") {
+        parameters.push((None, None));
+    }
+
+    let mut names: Vec<EcoString> = vec![];
+    let parameters = parameters
+        .into_iter()
+        .enumerate()
+        .map(|(index, (label, value))| {
+            let name = match (&label, value) {
+                (Some(label), _) => label.clone(),
+                (None, Some(UntypedExpr::Var { name, .. })) if is_variable(name) => name.clone(),
+                (None, _) => eco_format!("arg_{}", index + 1),
+            };
+            // Names can't be used twice, such as when the same variable is
+            // given as two arguments.
+            let name = if names.contains(&name) {
+                eco_format!("{name}_{}", index + 1)
+            } else {
+                name
+            };
+            names.push(name.clone());
+
+            let parameter = match label {
+                Some(label) => format!("{label} {name}"),
+                None => name.to_string(),
+            };
+            match value.and_then(literal_type) {
+                Some(type_) => format!("{parameter}: {type_}"),
+                None => parameter,
+            }
+        })
+        .join(", ");
+
+    let padding = " ".repeat(indent_width);
+    Some(format!("fn {name}({parameters}) {{\n{padding}todo\n}}\n"))
+}
+
+/// The end of the arguments of a call, where the function called ends at the
+/// given position.
+fn call_end(src: &str, fun_end: u32) -> Option<u32> {
+    let mut depth = 0;
+    for token in lexer::make_tokenizer(src.get(fun_end as usize..)?) {
+        let (_, token, end) = token.ok()?;
+        match token {
+            Token::LeftParen => depth += 1,
+            Token::RightParen if depth == 1 => return Some(fun_end + end),
+            Token::RightParen => depth -= 1,
+            Token::NewLine => (),
+            // Only a name followed by brackets is a call
+            _ if depth == 0 => return None,
+            _ => (),
+        }
+    }
+    None
+}
+
+fn literal_type(value: &UntypedExpr) -> Option<&'static str> {
+    match value {
+        UntypedExpr::Int { .. } => Some("Int"),
+        UntypedExpr::Float { .. } => Some("Float"),
+        UntypedExpr::String { .. } => Some("String"),
+        _ => None,
+    }
+}
+
+fn is_variable(name: &str) -> bool {
+    name.starts_with(|char: char| char.is_ascii_lowercase())
+}
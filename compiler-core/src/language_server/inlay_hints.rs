@@ -0,0 +1,110 @@
+//! Inlay hints, showing what the compiler knows about the code of a module
+//! that isn't written in it: the types inferred for `let` bindings and `todo`
+//! expressions, and the labels of arguments given without them.
+
+use std::sync::Arc;
+
+use ecow::EcoString;
+use lsp_types as lsp;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        CallArg, Pattern, SrcSpan, TodoKind, TypedAssignment, TypedExpr, PIPE_VARIABLE,
+    },
+    build::Module,
+    type_::{pretty::Printer, ModuleInterface, Type},
+};
+
+use super::signature_help;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    /// The byte index the hint is shown at.
+    pub position: u32,
+    pub label: String,
+    pub kind: lsp::InlayHintKind,
+}
+
+/// The hints for a module, in the order they appear in the source. The
+/// interfaces of the modules it imports are used to find the labels of
+/// functions selected from them.
+pub fn hints(module: &Module, modules: &im::HashMap<EcoString, ModuleInterface>) -> Vec<Hint> {
+    let mut finder = HintFinder {
+        modules,
+        hints: vec![],
+    };
+    finder.visit_typed_module(&module.ast);
+
+    let todos = module
+        .todos()
+        .into_iter()
+        .filter(|todo| todo.kind == TodoKind::Keyword);
+    for todo in todos {
+        // The hint goes straight after the `todo` keyword
+        finder.hints.push(Hint {
+            position: todo.location.start + "todo".len() as u32,
+            label: format!(": {}", Printer::new().pretty_print(&todo.type_, 0)),
+            kind: lsp::InlayHintKind::TYPE,
+        });
+    }
+
+    let mut hints = finder.hints;
+    hints.sort_by_key(|hint| hint.position);
+    hints
+}
+
+struct HintFinder<'a> {
+    modules: &'a im::HashMap<EcoString, ModuleInterface>,
+    hints: Vec<Hint>,
+}
+
+impl<'ast> Visit<'ast> for HintFinder<'_> {
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        let is_shown = match &assignment.pattern {
+            // The variables a pipeline is desugared into are not in the
+            // source.
+            Pattern::Variable { name, .. } => name != PIPE_VARIABLE,
+            Pattern::Discard { .. } => false,
+            _ => true,
+        };
+        if is_shown && assignment.annotation.is_none() {
+            let type_ = Printer::new().pretty_print(&assignment.type_(), 0);
+            self.hints.push(Hint {
+                position: assignment.pattern.location().end,
+                label: format!(": {type_}"),
+                kind: lsp::InlayHintKind::TYPE,
+            });
+        }
+        visit::visit_typed_assignment(self, assignment);
+    }
+
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'ast SrcSpan,
+        typ: &'ast Arc<Type>,
+        fun: &'ast TypedExpr,
+        args: &'ast [CallArg<TypedExpr>],
+    ) {
+        if let Some(field_map) = signature_help::field_map(fun, self.modules) {
+            for (label, index) in &field_map.fields {
+                let Some(arg) = args.get(*index as usize) else {
+                    continue;
+                };
+                // Arguments the programmer has labelled don't need a hint, and
+                // neither do variables named after the label.
+                let is_named_after_label =
+                    matches!(&arg.value, TypedExpr::Var { name, .. } if name == label);
+                if arg.implicit || arg.label.is_some() || is_named_after_label {
+                    continue;
+                }
+                self.hints.push(Hint {
+                    position: arg.location.start,
+                    label: format!("{label}: "),
+                    kind: lsp::InlayHintKind::PARAMETER,
+                });
+            }
+        }
+        visit::visit_typed_expr_call(self, location, typ, fun, args);
+    }
+}
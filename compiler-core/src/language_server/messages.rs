@@ -1,14 +1,27 @@
 use camino::Utf8PathBuf;
 use lsp::{
-    notification::{DidChangeWatchedFiles, DidOpenTextDocument},
-    request::GotoDefinition,
+    notification::{
+        DidChangeConfiguration, DidChangeWatchedFiles, DidChangeWorkspaceFolders,
+        DidOpenTextDocument,
+    },
+    request::{GotoDefinition, GotoImplementation, GotoTypeDefinition},
 };
 use lsp_types::{
     self as lsp,
     notification::{DidChangeTextDocument, DidCloseTextDocument, DidSaveTextDocument},
-    request::{CodeActionRequest, Completion, Formatting, HoverRequest},
+    request::{
+        CallHierarchyIncomingCalls, CallHierarchyOutgoingCalls, CallHierarchyPrepare,
+        CodeActionRequest, CodeLensRequest, Completion, DocumentDiagnosticRequest,
+        DocumentHighlightRequest, DocumentLinkRequest, ExecuteCommand, FoldingRangeRequest,
+        Formatting, HoverRequest, InlayHintRequest, PrepareRenameRequest, RangeFormatting,
+        References, Rename, SemanticTokensFullRequest, SemanticTokensRangeRequest,
+        SignatureHelpRequest, WorkspaceSymbolRequest,
+    },
 };
-use std::time::Duration;
+use std::{sync::mpsc, time::Duration};
+
+use super::compiled_code::{ShowCompiledCode, ShowCompiledCodeParams};
+use crate::build::CancellationToken;
 
 #[derive(Debug)]
 pub enum Message {
@@ -19,10 +32,31 @@ pub enum Message {
 #[derive(Debug)]
 pub enum Request {
     Format(lsp::DocumentFormattingParams),
+    RangeFormat(lsp::DocumentRangeFormattingParams),
+    FoldingRange(lsp::FoldingRangeParams),
     Hover(lsp::HoverParams),
     GoToDefinition(lsp::GotoDefinitionParams),
     Completion(lsp::CompletionParams),
     CodeAction(lsp::CodeActionParams),
+    InlayHint(lsp::InlayHintParams),
+    Rename(lsp::RenameParams),
+    PrepareRename(lsp::TextDocumentPositionParams),
+    References(lsp::ReferenceParams),
+    SignatureHelp(lsp::SignatureHelpParams),
+    SemanticTokensFull(lsp::SemanticTokensParams),
+    SemanticTokensRange(lsp::SemanticTokensRangeParams),
+    WorkspaceSymbol(lsp::WorkspaceSymbolParams),
+    GoToTypeDefinition(lsp::GotoDefinitionParams),
+    GoToImplementation(lsp::GotoDefinitionParams),
+    DocumentHighlight(lsp::DocumentHighlightParams),
+    PrepareCallHierarchy(lsp::CallHierarchyPrepareParams),
+    IncomingCalls(Box<lsp::CallHierarchyIncomingCallsParams>),
+    OutgoingCalls(Box<lsp::CallHierarchyOutgoingCallsParams>),
+    ShowCompiledCode(ShowCompiledCodeParams),
+    Diagnostic(lsp::DocumentDiagnosticParams),
+    CodeLens(lsp::CodeLensParams),
+    DocumentLink(lsp::DocumentLinkParams),
+    ExecuteCommand(lsp::ExecuteCommandParams),
 }
 
 impl Request {
@@ -33,6 +67,14 @@ impl Request {
                 let params = cast_request::<Formatting>(request);
                 Some(Message::Request(id, Request::Format(params)))
             }
+            "textDocument/rangeFormatting" => {
+                let params = cast_request::<RangeFormatting>(request);
+                Some(Message::Request(id, Request::RangeFormat(params)))
+            }
+            "textDocument/foldingRange" => {
+                let params = cast_request::<FoldingRangeRequest>(request);
+                Some(Message::Request(id, Request::FoldingRange(params)))
+            }
             "textDocument/hover" => {
                 let params = cast_request::<HoverRequest>(request);
                 Some(Message::Request(id, Request::Hover(params)))
@@ -49,6 +91,88 @@ impl Request {
                 let params = cast_request::<CodeActionRequest>(request);
                 Some(Message::Request(id, Request::CodeAction(params)))
             }
+            "textDocument/inlayHint" => {
+                let params = cast_request::<InlayHintRequest>(request);
+                Some(Message::Request(id, Request::InlayHint(params)))
+            }
+            "textDocument/rename" => {
+                let params = cast_request::<Rename>(request);
+                Some(Message::Request(id, Request::Rename(params)))
+            }
+            "textDocument/prepareRename" => {
+                let params = cast_request::<PrepareRenameRequest>(request);
+                Some(Message::Request(id, Request::PrepareRename(params)))
+            }
+            "textDocument/references" => {
+                let params = cast_request::<References>(request);
+                Some(Message::Request(id, Request::References(params)))
+            }
+            "textDocument/signatureHelp" => {
+                let params = cast_request::<SignatureHelpRequest>(request);
+                Some(Message::Request(id, Request::SignatureHelp(params)))
+            }
+            "textDocument/semanticTokens/full" => {
+                let params = cast_request::<SemanticTokensFullRequest>(request);
+                Some(Message::Request(id, Request::SemanticTokensFull(params)))
+            }
+            "textDocument/semanticTokens/range" => {
+                let params = cast_request::<SemanticTokensRangeRequest>(request);
+                Some(Message::Request(id, Request::SemanticTokensRange(params)))
+            }
+            "workspace/symbol" => {
+                let params = cast_request::<WorkspaceSymbolRequest>(request);
+                Some(Message::Request(id, Request::WorkspaceSymbol(params)))
+            }
+            "textDocument/typeDefinition" => {
+                let params = cast_request::<GotoTypeDefinition>(request);
+                Some(Message::Request(id, Request::GoToTypeDefinition(params)))
+            }
+            "textDocument/implementation" => {
+                let params = cast_request::<GotoImplementation>(request);
+                Some(Message::Request(id, Request::GoToImplementation(params)))
+            }
+            "textDocument/documentHighlight" => {
+                let params = cast_request::<DocumentHighlightRequest>(request);
+                Some(Message::Request(id, Request::DocumentHighlight(params)))
+            }
+            "textDocument/prepareCallHierarchy" => {
+                let params = cast_request::<CallHierarchyPrepare>(request);
+                Some(Message::Request(id, Request::PrepareCallHierarchy(params)))
+            }
+            "callHierarchy/incomingCalls" => {
+                let params = cast_request::<CallHierarchyIncomingCalls>(request);
+                Some(Message::Request(
+                    id,
+                    Request::IncomingCalls(Box::new(params)),
+                ))
+            }
+            "callHierarchy/outgoingCalls" => {
+                let params = cast_request::<CallHierarchyOutgoingCalls>(request);
+                Some(Message::Request(
+                    id,
+                    Request::OutgoingCalls(Box::new(params)),
+                ))
+            }
+            "textDocument/diagnostic" => {
+                let params = cast_request::<DocumentDiagnosticRequest>(request);
+                Some(Message::Request(id, Request::Diagnostic(params)))
+            }
+            "textDocument/codeLens" => {
+                let params = cast_request::<CodeLensRequest>(request);
+                Some(Message::Request(id, Request::CodeLens(params)))
+            }
+            "textDocument/documentLink" => {
+                let params = cast_request::<DocumentLinkRequest>(request);
+                Some(Message::Request(id, Request::DocumentLink(params)))
+            }
+            "workspace/executeCommand" => {
+                let params = cast_request::<ExecuteCommand>(request);
+                Some(Message::Request(id, Request::ExecuteCommand(params)))
+            }
+            "gleam/showCompiledCode" => {
+                let params = cast_request::<ShowCompiledCode>(request);
+                Some(Message::Request(id, Request::ShowCompiledCode(params)))
+            }
             _ => None,
         }
     }
@@ -56,12 +180,24 @@ impl Request {
 
 #[derive(Debug)]
 pub enum Notification {
-    /// A Gleam file has been modified in memory, and the new text is provided.
-    SourceFileChangedInMemory { path: Utf8PathBuf, text: String },
-    /// A Gleam file has been saved or closed in the editor.
+    /// A Gleam file has been opened in the editor, and its text is provided.
+    SourceFileOpened { path: Utf8PathBuf, text: String },
+    /// A Gleam file has been modified in memory, and the changes made to its
+    /// text are provided.
+    SourceFileChangedInMemory {
+        path: Utf8PathBuf,
+        changes: Vec<lsp::TextDocumentContentChangeEvent>,
+    },
+    /// A Gleam file has been saved in the editor.
     SourceFileMatchesDisc { path: Utf8PathBuf },
+    /// A Gleam file has been closed in the editor.
+    SourceFileClosed { path: Utf8PathBuf },
     /// gleam.toml has changed.
     ConfigFileChanged { path: Utf8PathBuf },
+    /// Folders have been removed from the workspace of the editor.
+    WorkspaceFoldersRemoved { paths: Vec<Utf8PathBuf> },
+    /// The settings of the client have changed.
+    ConfigurationChanged { settings: serde_json::Value },
     /// It's time to compile all open projects.
     CompilePlease,
 }
@@ -71,7 +207,7 @@ impl Notification {
         match notification.method.as_str() {
             "textDocument/didOpen" => {
                 let params = cast_notification::<DidOpenTextDocument>(notification);
-                let notification = Notification::SourceFileChangedInMemory {
+                let notification = Notification::SourceFileOpened {
                     path: super::path(&params.text_document.uri),
                     text: params.text_document.text,
                 };
@@ -81,7 +217,7 @@ impl Notification {
                 let params = cast_notification::<DidChangeTextDocument>(notification);
                 let notification = Notification::SourceFileChangedInMemory {
                     path: super::path(&params.text_document.uri),
-                    text: params.content_changes.into_iter().last()?.text,
+                    changes: params.content_changes,
                 };
                 Some(Message::Notification(notification))
             }
@@ -95,12 +231,40 @@ impl Notification {
             }
             "textDocument/didClose" => {
                 let params = cast_notification::<DidCloseTextDocument>(notification);
-                let notification = Notification::SourceFileMatchesDisc {
+                let notification = Notification::SourceFileClosed {
                     path: super::path(&params.text_document.uri),
                 };
                 Some(Message::Notification(notification))
             }
 
+            "workspace/didChangeWorkspaceFolders" => {
+                let params = cast_notification::<DidChangeWorkspaceFolders>(notification);
+                let paths = params
+                    .event
+                    .removed
+                    .iter()
+                    .map(|folder| super::path(&folder.uri))
+                    .collect::<Vec<_>>();
+                if paths.is_empty() {
+                    return None;
+                }
+                let notification = Notification::WorkspaceFoldersRemoved { paths };
+                Some(Message::Notification(notification))
+            }
+
+            "workspace/didChangeConfiguration" => {
+                let params = cast_notification::<DidChangeConfiguration>(notification);
+                // Clients that expect the server to pull the settings send
+                // none with the notification.
+                if params.settings.is_null() {
+                    return None;
+                }
+                let notification = Notification::ConfigurationChanged {
+                    settings: params.settings,
+                };
+                Some(Message::Notification(notification))
+            }
+
             "workspace/didChangeWatchedFiles" => {
                 let params = cast_notification::<DidChangeWatchedFiles>(notification);
                 let notification = Notification::ConfigFileChanged {
@@ -128,25 +292,53 @@ pub enum Next {
 ///
 pub struct MessageBuffer {
     messages: Vec<Message>,
+    /// The messages from the client. They are received from the connection on
+    /// a thread of their own, so that an edit can cancel the compilation in
+    /// progress as soon as it arrives.
+    incoming: mpsc::Receiver<lsp_server::Message>,
+    /// How long messages have to stop coming in for to count as a pause.
+    pause: Duration,
 }
 
 impl MessageBuffer {
-    pub fn new() -> Self {
+    pub fn new(
+        conn: &lsp_server::Connection,
+        pause: Duration,
+        cancellation: CancellationToken,
+    ) -> Self {
+        let (sender, incoming) = mpsc::channel();
+        let receiver = conn.receiver.clone();
+        let _ = std::thread::spawn(move || {
+            for message in receiver {
+                // The code being compiled is out of date once it is edited
+                if matches!(&message, lsp_server::Message::Notification(n) if n.method == "textDocument/didChange")
+                {
+                    cancellation.cancel();
+                }
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
         Self {
             messages: Vec::new(),
+            incoming,
+            pause,
         }
     }
 
-    pub fn receive(&mut self, conn: &lsp_server::Connection) -> Next {
-        let pause = Duration::from_millis(100);
+    pub fn set_pause(&mut self, pause: Duration) {
+        self.pause = pause;
+    }
 
+    pub fn receive(&mut self, conn: &lsp_server::Connection) -> Next {
         // If the buffer is empty, wait indefinitely for the first message.
         // If the buffer is not empty, wait for a short time to see if more messages are
         // coming before processing the ones we have.
         let message = if self.messages.is_empty() {
-            Some(conn.receiver.recv().expect("Receiving LSP message"))
+            Some(self.incoming.recv().expect("Receiving LSP message"))
         } else {
-            conn.receiver.recv_timeout(pause).ok()
+            self.incoming.recv_timeout(self.pause).ok()
         };
 
         // If have have not received a message then it means there is a pause in the
@@ -211,12 +403,23 @@ impl MessageBuffer {
         std::mem::take(&mut self.messages)
     }
 
+    /// Responds to a shutdown request and waits for the client to send the
+    /// exit notification that follows it.
     fn shutdown(
         &mut self,
         connection: &lsp_server::Connection,
         request: &lsp_server::Request,
     ) -> bool {
-        connection.handle_shutdown(request).expect("LSP shutdown")
+        if request.method != "shutdown" {
+            return false;
+        }
+        let response = lsp_server::Response::new_ok(request.id.clone(), ());
+        connection
+            .sender
+            .send(response.into())
+            .expect("send shutdown response");
+        let _ = self.incoming.recv_timeout(Duration::from_secs(30));
+        true
     }
 }
 
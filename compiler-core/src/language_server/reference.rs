@@ -0,0 +1,430 @@
+//! An index of where the functions, constants, record constructors, and types
+//! defined at the top level of modules are referred to in a module, built
+//! once the module has been compiled.
+
+use std::{collections::HashMap, sync::Arc};
+
+use ecow::EcoString;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        ClauseGuard, Constant, Definition, Pattern, SrcSpan, TypeAst, TypedClauseGuard,
+        TypedConstant, TypedCustomType, TypedFunction, TypedModule, TypedModuleConstant,
+        TypedPattern, TypedTypeAlias,
+    },
+    type_::{ModuleValueConstructor, Type, ValueConstructor, ValueConstructorVariant},
+};
+
+/// Something defined at the top level of a module.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Referenced {
+    /// A function, constant, or record constructor.
+    Value { module: EcoString, name: EcoString },
+    /// A custom type or type alias.
+    Type { module: EcoString, name: EcoString },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    Definition,
+    Import,
+    Usage,
+}
+
+/// The name of something defined at the top level of a module, where it is
+/// defined, imported, or used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reference {
+    pub location: SrcSpan,
+    pub kind: ReferenceKind,
+}
+
+/// Where the module refers to each thing defined at the top level of a
+/// module, including the definitions of its own.
+#[derive(Debug, Default)]
+pub struct References {
+    index: HashMap<Referenced, Vec<Reference>>,
+}
+
+impl References {
+    pub fn new(module: &TypedModule, code: &str) -> Self {
+        let mut finder = Finder {
+            code,
+            module: &module.name,
+            names: Names::new(module),
+            references: References::default(),
+        };
+        for definition in &module.definitions {
+            if let Definition::Import(import) = definition {
+                for value in &import.unqualified_values {
+                    finder.add(
+                        Referenced::Value {
+                            module: import.module.clone(),
+                            name: value.name.clone(),
+                        },
+                        name_at_start(value.location, &value.name),
+                        ReferenceKind::Import,
+                    );
+                }
+                for type_ in &import.unqualified_types {
+                    finder.add(
+                        Referenced::Type {
+                            module: import.module.clone(),
+                            name: type_.name.clone(),
+                        },
+                        name_at_start(type_.location, &type_.name),
+                        ReferenceKind::Import,
+                    );
+                }
+            }
+        }
+        finder.visit_typed_module(module);
+        let mut references = finder.references;
+        for locations in references.index.values_mut() {
+            locations.sort_by_key(|reference| reference.location.start);
+        }
+        references
+    }
+
+    /// The references to something in the module.
+    pub fn get(&self, referenced: &Referenced) -> &[Reference] {
+        self.index
+            .get(referenced)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Everything the module refers to, with the references to it.
+    pub fn iter(&self) -> impl Iterator<Item = (&Referenced, &[Reference])> {
+        self.index
+            .iter()
+            .map(|(referenced, references)| (referenced, references.as_slice()))
+    }
+
+    /// What is referred to by the name at the position in the module.
+    pub fn at(&self, byte_index: u32) -> Option<&Referenced> {
+        self.index.iter().find_map(|(referenced, references)| {
+            references
+                .iter()
+                .any(|reference| reference.location.contains(byte_index))
+                .then_some(referenced)
+        })
+    }
+}
+
+/// What the unqualified names and module aliases used in a module refer to.
+#[derive(Debug)]
+pub struct Names {
+    module: EcoString,
+    /// The functions, constants, and record constructors the module defines.
+    values: Vec<EcoString>,
+    /// The custom types and type aliases the module defines.
+    types: Vec<EcoString>,
+    /// The name each unqualified imported value is used by, and the module
+    /// and name it is defined with.
+    unqualified_values: HashMap<EcoString, (EcoString, EcoString)>,
+    unqualified_types: HashMap<EcoString, (EcoString, EcoString)>,
+    /// The module each module alias refers to.
+    aliases: HashMap<EcoString, EcoString>,
+}
+
+impl Names {
+    pub fn new(module: &TypedModule) -> Self {
+        let mut names = Names {
+            module: module.name.clone(),
+            values: vec![],
+            types: vec![],
+            unqualified_values: HashMap::new(),
+            unqualified_types: HashMap::new(),
+            aliases: HashMap::new(),
+        };
+        for definition in &module.definitions {
+            match definition {
+                Definition::Function(function) => names.values.push(function.name.clone()),
+                Definition::ModuleConstant(constant) => names.values.push(constant.name.clone()),
+                Definition::CustomType(custom_type) => {
+                    names.types.push(custom_type.name.clone());
+                    names.values.extend(
+                        custom_type
+                            .constructors
+                            .iter()
+                            .map(|constructor| constructor.name.clone()),
+                    );
+                }
+                Definition::TypeAlias(alias) => names.types.push(alias.alias.clone()),
+                Definition::Import(import) => {
+                    for value in &import.unqualified_values {
+                        let _ = names.unqualified_values.insert(
+                            value.used_name().clone(),
+                            (import.module.clone(), value.name.clone()),
+                        );
+                    }
+                    for type_ in &import.unqualified_types {
+                        let _ = names.unqualified_types.insert(
+                            type_.used_name().clone(),
+                            (import.module.clone(), type_.name.clone()),
+                        );
+                    }
+                    if let Some(alias) = import.used_name() {
+                        let _ = names.aliases.insert(alias, import.module.clone());
+                    }
+                }
+            }
+        }
+        names
+    }
+
+    /// The module and name of the value a name used in the module refers to,
+    /// qualified with a module alias or not.
+    pub fn value(&self, module: Option<&EcoString>, name: &EcoString) -> Option<Referenced> {
+        let (module, name) = match module {
+            Some(alias) => (self.aliases.get(alias)?.clone(), name.clone()),
+            None if self.values.contains(name) => (self.module.clone(), name.clone()),
+            None => self.unqualified_values.get(name)?.clone(),
+        };
+        Some(Referenced::Value { module, name })
+    }
+
+    /// The module and name of the type a name used in the module refers to,
+    /// qualified with a module alias or not.
+    pub fn type_(&self, module: Option<&EcoString>, name: &EcoString) -> Option<Referenced> {
+        let (module, name) = match module {
+            Some(alias) => (self.aliases.get(alias)?.clone(), name.clone()),
+            None if self.types.contains(name) => (self.module.clone(), name.clone()),
+            None => self.unqualified_types.get(name)?.clone(),
+        };
+        Some(Referenced::Type { module, name })
+    }
+}
+
+/// The location of the first occurrence of the name as a whole word in the
+/// code at the location, as in the head of a definition.
+pub fn name_in(code: &str, location: SrcSpan, name: &str) -> Option<SrcSpan> {
+    let text = code.get(location.start as usize..location.end as usize)?;
+    let is_name_char = |char: char| char.is_ascii_alphanumeric() || char == '_';
+    let (index, _) = text.match_indices(name).find(|(index, _)| {
+        let before = text.get(..*index).and_then(|text| text.chars().last());
+        let after = text
+            .get(index + name.len()..)
+            .and_then(|text| text.chars().next());
+        !before.is_some_and(is_name_char) && !after.is_some_and(is_name_char)
+    })?;
+    let start = location.start + index as u32;
+    Some(SrcSpan::new(start, start + name.len() as u32))
+}
+
+/// The location of a name the location of a node starts with.
+pub fn name_at_start(location: SrcSpan, name: &str) -> SrcSpan {
+    SrcSpan::new(location.start, location.start + name.len() as u32)
+}
+
+/// The location of a name the location of a node ends with.
+pub fn name_at_end(location: SrcSpan, name: &str) -> SrcSpan {
+    SrcSpan::new(location.end.saturating_sub(name.len() as u32), location.end)
+}
+
+/// The location of the name of something written with an optional module
+/// alias, such as `Wibble(1)` or `wibble.Wibble(1)`.
+pub fn name_after_alias(location: SrcSpan, module: Option<&EcoString>, name: &str) -> SrcSpan {
+    let start = match module {
+        Some(alias) => location.start + alias.len() as u32 + 1,
+        None => location.start,
+    };
+    SrcSpan::new(start, start + name.len() as u32)
+}
+
+struct Finder<'a> {
+    code: &'a str,
+    module: &'a EcoString,
+    names: Names,
+    references: References,
+}
+
+impl Finder<'_> {
+    fn add(&mut self, referenced: Referenced, location: SrcSpan, kind: ReferenceKind) {
+        self.references
+            .index
+            .entry(referenced)
+            .or_default()
+            .push(Reference { location, kind });
+    }
+
+    fn usage(&mut self, referenced: Option<Referenced>, location: SrcSpan) {
+        if let Some(referenced) = referenced {
+            self.add(referenced, location, ReferenceKind::Usage);
+        }
+    }
+
+    fn definition(&mut self, referenced: Referenced, head: SrcSpan) {
+        let name = match &referenced {
+            Referenced::Value { name, .. } | Referenced::Type { name, .. } => name.clone(),
+        };
+        if let Some(location) = name_in(self.code, head, &name) {
+            self.add(referenced, location, ReferenceKind::Definition);
+        }
+    }
+
+    fn value(&self, name: &EcoString) -> Referenced {
+        Referenced::Value {
+            module: self.module.clone(),
+            name: name.clone(),
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Finder<'_> {
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        self.definition(self.value(&function.name), function.location);
+        visit::visit_typed_function(self, function);
+    }
+
+    fn visit_typed_module_constant(&mut self, constant: &'ast TypedModuleConstant) {
+        self.definition(self.value(&constant.name), constant.location);
+        visit::visit_typed_module_constant(self, constant);
+    }
+
+    fn visit_typed_custom_type(&mut self, custom_type: &'ast TypedCustomType) {
+        let type_ = Referenced::Type {
+            module: self.module.clone(),
+            name: custom_type.name.clone(),
+        };
+        self.definition(type_, custom_type.location);
+        for constructor in &custom_type.constructors {
+            self.add(
+                self.value(&constructor.name),
+                name_at_start(constructor.location, &constructor.name),
+                ReferenceKind::Definition,
+            );
+        }
+        visit::visit_typed_custom_type(self, custom_type);
+    }
+
+    fn visit_typed_type_alias(&mut self, type_alias: &'ast TypedTypeAlias) {
+        let type_ = Referenced::Type {
+            module: self.module.clone(),
+            name: type_alias.alias.clone(),
+        };
+        self.definition(type_, type_alias.location);
+        visit::visit_typed_type_alias(self, type_alias);
+    }
+
+    fn visit_typed_expr_var(
+        &mut self,
+        location: &'ast SrcSpan,
+        constructor: &'ast ValueConstructor,
+        name: &'ast EcoString,
+    ) {
+        match constructor.variant {
+            ValueConstructorVariant::ModuleFn { .. }
+            | ValueConstructorVariant::ModuleConstant { .. }
+            | ValueConstructorVariant::Record { .. } => {
+                // A label shorthand such as `name:` also covers the colon
+                let referenced = self.names.value(None, name);
+                self.usage(referenced, name_at_start(*location, name));
+            }
+            ValueConstructorVariant::LocalVariable { .. }
+            | ValueConstructorVariant::LocalConstant { .. } => (),
+        }
+    }
+
+    fn visit_typed_expr_module_select(
+        &mut self,
+        location: &'ast SrcSpan,
+        _typ: &'ast Arc<Type>,
+        label: &'ast EcoString,
+        module_name: &'ast EcoString,
+        _module_alias: &'ast EcoString,
+        _constructor: &'ast ModuleValueConstructor,
+    ) {
+        let referenced = Referenced::Value {
+            module: module_name.clone(),
+            name: label.clone(),
+        };
+        self.add(
+            referenced,
+            name_at_end(*location, label),
+            ReferenceKind::Usage,
+        );
+    }
+
+    fn visit_typed_clause_guard(&mut self, guard: &'ast TypedClauseGuard) {
+        if let ClauseGuard::ModuleSelect {
+            location,
+            label,
+            module_name,
+            ..
+        } = guard
+        {
+            let referenced = Referenced::Value {
+                module: module_name.clone(),
+                name: label.clone(),
+            };
+            self.add(
+                referenced,
+                name_at_end(*location, label),
+                ReferenceKind::Usage,
+            );
+        }
+        visit::visit_typed_clause_guard(self, guard);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        if let Pattern::Constructor {
+            location,
+            name,
+            module,
+            ..
+        } = pattern
+        {
+            let referenced = self.names.value(module.as_ref(), name);
+            self.usage(
+                referenced,
+                name_after_alias(*location, module.as_ref(), name),
+            );
+        }
+        visit::visit_typed_pattern(self, pattern);
+    }
+
+    fn visit_typed_constant(&mut self, constant: &'ast TypedConstant) {
+        match constant {
+            Constant::Record {
+                location,
+                module,
+                name,
+                ..
+            } => {
+                let referenced = self.names.value(module.as_ref(), name);
+                self.usage(
+                    referenced,
+                    name_after_alias(*location, module.as_ref(), name),
+                );
+            }
+            Constant::Var {
+                location,
+                module,
+                name,
+                constructor: Some(constructor),
+                ..
+            } if !constructor.variant.is_local_variable() => {
+                let referenced = self.names.value(module.as_ref(), name);
+                self.usage(
+                    referenced,
+                    name_after_alias(*location, module.as_ref(), name),
+                );
+            }
+            _ => (),
+        }
+        visit::visit_typed_constant(self, constant);
+    }
+
+    fn visit_type_ast(&mut self, type_ast: &'ast TypeAst) {
+        if let TypeAst::Constructor(constructor) = type_ast {
+            let module = constructor.module.as_ref();
+            let referenced = self.names.type_(module, &constructor.name);
+            let location = name_after_alias(constructor.location, module, &constructor.name);
+            self.usage(referenced, location);
+        }
+        visit::visit_type_ast(self, type_ast);
+    }
+}
@@ -0,0 +1,727 @@
+//! Renaming of local variables, and of the functions and constants defined at
+//! the top level of a module.
+//!
+//! A local variable is renamed where it is defined and everywhere it is used
+//! in its module. A module function or constant is renamed in the module that
+//! defines it and in every module of the root package that uses it, along
+//! with the unqualified imports of it.
+//!
+//! The label of an argument of a module function is renamed in the
+//! definition of the function and in every call to it that uses the label.
+
+use std::sync::Arc;
+
+use ecow::EcoString;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        ArgNames, AssignName, ClauseGuard, Constant, Definition, Import, Pattern, SrcSpan, TypeAst,
+        TypedArg, TypedClause, TypedClauseGuard, TypedConstant, TypedExpr, TypedFunction,
+        TypedModule, TypedModuleConstant, TypedPattern, TypedStatement,
+    },
+    build::Located,
+    language_server::reference::{name_at_start, name_in, Names, Referenced},
+    parse::lexer::str_to_keyword,
+    type_::{ModuleValueConstructor, Type, ValueConstructor, ValueConstructorVariant},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Something that can be renamed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Renamed {
+    /// A variable defined in a function, identified by the location of the
+    /// pattern or argument that defines it.
+    Local {
+        name: EcoString,
+        definition: SrcSpan,
+    },
+    /// A function or constant defined at the top level of a module.
+    ModuleValue { module: EcoString, name: EcoString },
+    /// The label of an argument of a function defined at the top level of a
+    /// module.
+    Label {
+        module: EcoString,
+        function: EcoString,
+        label: EcoString,
+    },
+}
+
+impl Renamed {
+    pub fn name(&self) -> &EcoString {
+        match self {
+            Renamed::Local { name, .. } | Renamed::ModuleValue { name, .. } => name,
+            Renamed::Label { label, .. } => label,
+        }
+    }
+
+    /// The module the function, constant, or function with the label is
+    /// defined in. Local variables are not defined at the top level of a
+    /// module.
+    pub fn module(&self) -> Option<&EcoString> {
+        match self {
+            Renamed::Local { .. } => None,
+            Renamed::ModuleValue { module, .. } | Renamed::Label { module, .. } => Some(module),
+        }
+    }
+
+    fn is_value(&self, module: &str, name: &str) -> bool {
+        matches!(self, Renamed::ModuleValue { module: m, name: n } if m == module && n == name)
+    }
+
+    fn is_label_of(&self, module: &str, name: &str) -> bool {
+        matches!(self, Renamed::Label { module: m, function: f, .. } if m == module && f == name)
+    }
+
+    /// Finds what is renamed when renaming the node at the given position in
+    /// the module.
+    pub fn at(module: &TypedModule, node: Located<'_>, byte_index: u32) -> Option<Self> {
+        let names = Names::new(module);
+        match node {
+            Located::Expression(TypedExpr::Var {
+                name, constructor, ..
+            }) => match &constructor.variant {
+                ValueConstructorVariant::LocalVariable { location } => Some(Renamed::Local {
+                    name: name.clone(),
+                    definition: *location,
+                }),
+                ValueConstructorVariant::ModuleFn { .. }
+                | ValueConstructorVariant::ModuleConstant { .. } => {
+                    module_value(&names, None, name)
+                }
+                ValueConstructorVariant::LocalConstant { .. }
+                | ValueConstructorVariant::Record { .. } => None,
+            },
+
+            Located::Expression(TypedExpr::ModuleSelect {
+                label,
+                module_name,
+                constructor:
+                    ModuleValueConstructor::Fn { .. } | ModuleValueConstructor::Constant { .. },
+                ..
+            }) => Some(Renamed::ModuleValue {
+                module: module_name.clone(),
+                name: label.clone(),
+            }),
+
+            Located::Pattern(
+                Pattern::Variable { name, location, .. } | Pattern::Assign { name, location, .. },
+            ) => Some(Renamed::Local {
+                name: name.clone(),
+                definition: *location,
+            }),
+
+            Located::Pattern(Pattern::VarUsage {
+                name,
+                constructor: Some(constructor),
+                ..
+            }) => match &constructor.variant {
+                ValueConstructorVariant::LocalVariable { location } => Some(Renamed::Local {
+                    name: name.clone(),
+                    definition: *location,
+                }),
+                _ => None,
+            },
+
+            Located::Arg(arg) => match arg.names.get_label() {
+                // The label comes before the name of the argument
+                Some(label) if byte_index < arg.location.start + label.len() as u32 => {
+                    let function = function_with_arg(module, arg.location)?;
+                    Some(Renamed::Label {
+                        module: module.name.clone(),
+                        function: function.name.clone(),
+                        label: label.clone(),
+                    })
+                }
+                _ => Some(Renamed::Local {
+                    name: arg.names.get_variable_name()?.clone(),
+                    definition: arg.location,
+                }),
+            },
+
+            Located::Label {
+                label,
+                of: TypedExpr::Call { fun, .. },
+                ..
+            } => {
+                let (module, function) = called_function(&names, fun)?;
+                Some(Renamed::Label {
+                    module,
+                    function,
+                    label: label.clone(),
+                })
+            }
+
+            Located::ModuleStatement(Definition::Function(function)) => {
+                Some(Renamed::ModuleValue {
+                    module: module.name.clone(),
+                    name: function.name.clone(),
+                })
+            }
+
+            Located::ModuleStatement(Definition::ModuleConstant(constant)) => {
+                Some(Renamed::ModuleValue {
+                    module: module.name.clone(),
+                    name: constant.name.clone(),
+                })
+            }
+
+            Located::ModuleStatement(Definition::Import(import)) => import
+                .unqualified_values
+                .iter()
+                .find(|value| value.location.contains(byte_index))
+                .map(|value| Renamed::ModuleValue {
+                    module: import.module.clone(),
+                    name: value.name.clone(),
+                }),
+
+            Located::ModuleStatement(_)
+            | Located::Pattern(_)
+            | Located::Expression(_)
+            | Located::Statement(_)
+            | Located::FunctionBody(_)
+            | Located::Label { .. } => None,
+        }
+    }
+
+    /// Whether renaming this changes the module: the module defining the
+    /// value, or one importing it. Local variables are only ever changed in
+    /// the module the rename was started in.
+    pub fn is_used_by(&self, module: &TypedModule) -> bool {
+        let Some(defined_in) = self.module() else {
+            return false;
+        };
+        &module.name == defined_in
+            || module
+                .definitions
+                .iter()
+                .any(|definition| match definition {
+                    Definition::Import(import) => &import.module == defined_in,
+                    _ => false,
+                })
+    }
+}
+
+/// Why what is at a position can't be renamed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CannotRename {
+    Keyword(EcoString),
+    ModuleName,
+    /// A function or constant defined in a module of a dependency, which
+    /// can't be changed.
+    Dependency {
+        module: EcoString,
+        name: EcoString,
+    },
+    Other,
+}
+
+impl CannotRename {
+    pub fn message(&self) -> String {
+        match self {
+            CannotRename::Keyword(keyword) => format!("`{keyword}` is a keyword"),
+            CannotRename::ModuleName => "Modules can't be renamed".into(),
+            CannotRename::Dependency { module, name } => {
+                format!("`{name}` is defined in the `{module}` module of a dependency")
+            }
+            CannotRename::Other => {
+                "Only variables, functions, constants, and labels can be renamed".into()
+            }
+        }
+    }
+}
+
+/// What renaming at a position renames along with the location of the name
+/// there, or why nothing can be renamed there.
+pub type Prepared = Result<(Renamed, SrcSpan), CannotRename>;
+
+/// Finds what is renamed when renaming at the position in the module, along
+/// with the location of the name being renamed there. Renaming is only
+/// possible with the position on the name itself, not on a keyword or the
+/// module a value is qualified with.
+pub fn prepare(module: &TypedModule, code: &str, byte_index: u32) -> Prepared {
+    if let Some(keyword) = word_at(code, byte_index).filter(|word| str_to_keyword(word).is_some()) {
+        return Err(CannotRename::Keyword(keyword.into()));
+    }
+    let node = module.find_node(byte_index).ok_or(CannotRename::Other)?;
+    let is_module_name = match &node {
+        // The location of a module select doesn't cover the module name, so
+        // the name is found as a word followed by a dot instead.
+        Located::Expression(_) => word_range(code, byte_index).is_some_and(|(start, end)| {
+            code.get(end..).is_some_and(|rest| rest.starts_with('.'))
+                && code
+                    .get(start..end)
+                    .is_some_and(|name| imports_module_as(module, name))
+        }),
+        Located::ModuleStatement(Definition::Import(import)) => !import
+            .unqualified_values
+            .iter()
+            .any(|value| value.location.contains(byte_index)),
+        _ => false,
+    };
+    if is_module_name {
+        return Err(CannotRename::ModuleName);
+    }
+    let renamed = Renamed::at(module, node, byte_index).ok_or(CannotRename::Other)?;
+    let location = occurrences(module, code, &renamed)
+        .into_iter()
+        .find(|location| location.contains(byte_index))
+        .ok_or(CannotRename::Other)?;
+    Ok((renamed, location))
+}
+
+/// The name, keyword, or number the position is in.
+fn word_at(code: &str, byte_index: u32) -> Option<&str> {
+    let (start, end) = word_range(code, byte_index)?;
+    code.get(start..end)
+}
+
+/// The start and end of the name, keyword, or number the position is in.
+fn word_range(code: &str, byte_index: u32) -> Option<(usize, usize)> {
+    let is_word_char = |char: char| char.is_ascii_alphanumeric() || char == '_';
+    let index = byte_index as usize;
+    let start = code
+        .get(..index)?
+        .rfind(|char| !is_word_char(char))
+        .map_or(0, |start| start + 1);
+    let end = code
+        .get(index..)?
+        .find(|char| !is_word_char(char))
+        .map_or(code.len(), |end| index + end);
+    (start < end).then_some((start, end))
+}
+
+/// Whether the module imports a module under the name.
+fn imports_module_as(module: &TypedModule, name: &str) -> bool {
+    module
+        .definitions
+        .iter()
+        .any(|definition| match definition {
+            Definition::Import(import) => import.used_name().is_some_and(|used| used == name),
+            _ => false,
+        })
+}
+
+/// Whether the name can be given to a variable, function, or constant.
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|char| char.is_ascii_lowercase())
+        && chars.all(|char| char.is_ascii_lowercase() || char.is_ascii_digit() || char == '_')
+        && str_to_keyword(name).is_none()
+}
+
+/// An edit renaming a single occurrence of a name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub location: SrcSpan,
+    pub new_text: String,
+}
+
+/// The edits to the module renaming every occurrence of what is renamed.
+pub fn edits(module: &TypedModule, code: &str, renamed: &Renamed, new_name: &str) -> Vec<Edit> {
+    let mut finder = Finder {
+        code,
+        module: &module.name,
+        names: Names::new(module),
+        renamed,
+        new_name,
+        in_scope: None,
+        edits: vec![],
+    };
+
+    for definition in &module.definitions {
+        if let Definition::Import(import) = definition {
+            finder.import(import);
+        }
+    }
+    finder.visit_typed_module(module);
+
+    let mut edits = finder.edits;
+    edits.sort_by_key(|edit| edit.location.start);
+    edits.dedup();
+    edits
+}
+
+/// The location of every occurrence of the name of what is renamed in the
+/// module, in the order they appear. Like renaming, this tells apart
+/// variables with the same name defined in different scopes.
+pub fn occurrences(module: &TypedModule, code: &str, renamed: &Renamed) -> Vec<SrcSpan> {
+    let name = renamed.name();
+    edits(module, code, renamed, name)
+        .into_iter()
+        // A label shorthand such as `name:` is renamed as a whole, but only
+        // the name is an occurrence.
+        .map(|edit| name_at_start(edit.location, name))
+        .collect()
+}
+
+/// The module function defining the argument at the location.
+fn function_with_arg(module: &TypedModule, location: SrcSpan) -> Option<&TypedFunction> {
+    module
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Function(function)
+                if function
+                    .arguments
+                    .iter()
+                    .any(|arg| arg.location == location) =>
+            {
+                Some(function)
+            }
+            _ => None,
+        })
+}
+
+/// The module and name of the module function called, if it is called by
+/// its name.
+fn called_function(names: &Names, fun: &TypedExpr) -> Option<(EcoString, EcoString)> {
+    match fun {
+        TypedExpr::Var {
+            name, constructor, ..
+        } => match &constructor.variant {
+            ValueConstructorVariant::ModuleFn { .. } => match names.value(None, name)? {
+                Referenced::Value { module, name } => Some((module, name)),
+                Referenced::Type { .. } => None,
+            },
+            _ => None,
+        },
+        TypedExpr::ModuleSelect {
+            label,
+            module_name,
+            constructor: ModuleValueConstructor::Fn { .. },
+            ..
+        } => Some((module_name.clone(), label.clone())),
+        _ => None,
+    }
+}
+
+/// The function or constant an unqualified or qualified name used in the
+/// module refers to.
+fn module_value(names: &Names, module: Option<&EcoString>, name: &EcoString) -> Option<Renamed> {
+    match names.value(module, name)? {
+        Referenced::Value { module, name } => Some(Renamed::ModuleValue { module, name }),
+        Referenced::Type { .. } => None,
+    }
+}
+
+struct Finder<'a> {
+    code: &'a str,
+    module: &'a EcoString,
+    names: Names,
+    renamed: &'a Renamed,
+    new_name: &'a str,
+    /// The definition of the innermost variable in scope with the name being
+    /// renamed, used to tell which variable the names in guards refer to.
+    in_scope: Option<SrcSpan>,
+    edits: Vec<Edit>,
+}
+
+impl Finder<'_> {
+    fn is_local(&self, definition: SrcSpan) -> bool {
+        matches!(self.renamed, Renamed::Local { definition: d, .. } if *d == definition)
+    }
+
+    /// Renames the name at the end of the location, which is either the name
+    /// itself, a qualified name such as `module.name`, or a label shorthand
+    /// such as `name:` which is expanded to keep the label.
+    fn rename_end(&mut self, location: SrcSpan) {
+        let name = self.renamed.name();
+        let Some(text) = self
+            .code
+            .get(location.start as usize..location.end as usize)
+        else {
+            return;
+        };
+        if let Some(label) = text.strip_suffix(':') {
+            if label.trim_end() == name {
+                self.edits.push(Edit {
+                    location,
+                    new_text: format!("{name}: {}", self.new_name),
+                });
+            }
+            return;
+        }
+        if text.ends_with(name.as_str()) {
+            self.edits.push(Edit {
+                location: SrcSpan::new(location.end - name.len() as u32, location.end),
+                new_text: self.new_name.into(),
+            });
+        }
+    }
+
+    /// Renames the first occurrence of the name as a whole word in the code at
+    /// the location, as in the head of a definition.
+    fn rename_first(&mut self, location: SrcSpan) {
+        if let Some(location) = name_in(self.code, location, self.renamed.name()) {
+            self.edits.push(Edit {
+                location,
+                new_text: self.new_name.into(),
+            });
+        }
+    }
+
+    /// Renames the label the location of an argument starts with.
+    fn rename_label(&mut self, location: SrcSpan) {
+        self.edits.push(Edit {
+            location: name_at_start(location, self.renamed.name()),
+            new_text: self.new_name.into(),
+        });
+    }
+
+    fn import(&mut self, import: &Import<EcoString>) {
+        for value in &import.unqualified_values {
+            if self.renamed.is_value(&import.module, &value.name) {
+                let start = value.location.start;
+                self.edits.push(Edit {
+                    location: SrcSpan::new(start, start + value.name.len() as u32),
+                    new_text: self.new_name.into(),
+                });
+            }
+        }
+    }
+
+    /// Records a variable defined by a pattern or argument, renaming it if it
+    /// is the one renamed.
+    fn define(&mut self, name: &EcoString, location: SrcSpan) {
+        if name == self.renamed.name() {
+            self.in_scope = Some(location);
+        }
+        if self.is_local(location) {
+            self.rename_end(location);
+        }
+    }
+
+    fn scoped(&mut self, f: impl FnOnce(&mut Self)) {
+        let in_scope = self.in_scope;
+        f(self);
+        self.in_scope = in_scope;
+    }
+}
+
+impl<'ast> Visit<'ast> for Finder<'_> {
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        if self.renamed.is_value(self.module, &function.name) {
+            self.rename_first(function.location);
+        }
+        if self.renamed.is_label_of(self.module, &function.name) {
+            for arg in &function.arguments {
+                if arg.names.get_label() == Some(self.renamed.name()) {
+                    self.rename_label(arg.location);
+                }
+            }
+        }
+        self.scoped(|this| visit::visit_typed_function(this, function));
+    }
+
+    fn visit_typed_module_constant(&mut self, constant: &'ast TypedModuleConstant) {
+        if self.renamed.is_value(self.module, &constant.name) {
+            self.rename_first(constant.location);
+        }
+        visit::visit_typed_module_constant(self, constant);
+    }
+
+    fn visit_typed_arg(&mut self, arg: &'ast TypedArg) {
+        // The name comes after the label, so the label is skipped over in
+        // case it is the same as the name.
+        let (name, start) = match &arg.names {
+            ArgNames::Named { name } => (name, arg.location.start),
+            ArgNames::NamedLabelled { name, label } => {
+                (name, arg.location.start + label.len() as u32)
+            }
+            ArgNames::Discard { .. } | ArgNames::LabelledDiscard { .. } => {
+                return visit::visit_typed_arg(self, arg);
+            }
+        };
+        if name == self.renamed.name() {
+            self.in_scope = Some(arg.location);
+        }
+        if self.is_local(arg.location) {
+            self.rename_first(SrcSpan::new(start, arg.location.end));
+        }
+        visit::visit_typed_arg(self, arg);
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        match expr {
+            TypedExpr::Block { .. } => self.scoped(|this| visit::visit_typed_expr(this, expr)),
+            TypedExpr::Call { fun, args, .. } => {
+                let is_called = called_function(&self.names, fun)
+                    .is_some_and(|(module, name)| self.renamed.is_label_of(&module, &name));
+                if is_called {
+                    for arg in args {
+                        if arg.implicit || arg.label.as_ref() != Some(self.renamed.name()) {
+                            continue;
+                        }
+                        if arg.label_shorthand {
+                            // The value keeps the name of the label
+                            self.edits.push(Edit {
+                                location: arg.location,
+                                new_text: format!("{}: {}", self.new_name, self.renamed.name()),
+                            });
+                        } else {
+                            self.rename_label(arg.location);
+                        }
+                    }
+                }
+                visit::visit_typed_expr(self, expr);
+            }
+            _ => visit::visit_typed_expr(self, expr),
+        }
+    }
+
+    fn visit_typed_expr_var(
+        &mut self,
+        location: &'ast SrcSpan,
+        constructor: &'ast ValueConstructor,
+        name: &'ast EcoString,
+    ) {
+        let renamed = match &constructor.variant {
+            ValueConstructorVariant::LocalVariable { location } => self.is_local(*location),
+            ValueConstructorVariant::ModuleFn { .. }
+            | ValueConstructorVariant::ModuleConstant { .. } => {
+                // Values imported with an alias keep their alias
+                name == self.renamed.name()
+                    && module_value(&self.names, None, name).as_ref() == Some(self.renamed)
+            }
+            ValueConstructorVariant::LocalConstant { .. }
+            | ValueConstructorVariant::Record { .. } => false,
+        };
+        if renamed {
+            self.rename_end(*location);
+        }
+    }
+
+    fn visit_typed_expr_module_select(
+        &mut self,
+        location: &'ast SrcSpan,
+        _typ: &'ast Arc<Type>,
+        label: &'ast EcoString,
+        module_name: &'ast EcoString,
+        _module_alias: &'ast EcoString,
+        constructor: &'ast ModuleValueConstructor,
+    ) {
+        let is_value = matches!(
+            constructor,
+            ModuleValueConstructor::Fn { .. } | ModuleValueConstructor::Constant { .. }
+        );
+        if is_value && self.renamed.is_value(module_name, label) {
+            self.rename_end(*location);
+        }
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'ast SrcSpan,
+        typ: &'ast Arc<Type>,
+        is_capture: &'ast bool,
+        args: &'ast [TypedArg],
+        body: &'ast [TypedStatement],
+        return_annotation: &'ast Option<TypeAst>,
+    ) {
+        self.scoped(|this| {
+            visit::visit_typed_expr_fn(
+                this,
+                location,
+                typ,
+                is_capture,
+                args,
+                body,
+                return_annotation,
+            )
+        });
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'ast TypedClause) {
+        self.scoped(|this| visit::visit_typed_clause(this, clause));
+    }
+
+    fn visit_typed_clause_guard(&mut self, guard: &'ast TypedClauseGuard) {
+        match guard {
+            ClauseGuard::Var { location, name, .. }
+                if name == self.renamed.name()
+                    && self
+                        .in_scope
+                        .is_some_and(|definition| self.is_local(definition)) =>
+            {
+                self.rename_end(*location);
+            }
+            ClauseGuard::ModuleSelect {
+                location,
+                label,
+                module_name,
+                ..
+            } if self.renamed.is_value(module_name, label) => self.rename_end(*location),
+            _ => (),
+        }
+        visit::visit_typed_clause_guard(self, guard);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        match pattern {
+            Pattern::Variable { name, location, .. } => self.define(name, *location),
+            Pattern::VarUsage {
+                location,
+                constructor: Some(constructor),
+                ..
+            } => {
+                if let ValueConstructorVariant::LocalVariable {
+                    location: definition,
+                } = &constructor.variant
+                {
+                    if self.is_local(*definition) {
+                        self.rename_end(*location);
+                    }
+                }
+            }
+            Pattern::StringPrefix {
+                left_side_assignment,
+                right_location,
+                right_side_assignment,
+                ..
+            } => {
+                if let Some((name, location)) = left_side_assignment {
+                    self.define(name, *location);
+                }
+                if let AssignName::Variable(name) = right_side_assignment {
+                    self.define(name, *right_location);
+                }
+            }
+            _ => (),
+        }
+        visit::visit_typed_pattern(self, pattern);
+        // The name of an assignment pattern is bound after its pattern
+        if let Pattern::Assign { name, location, .. } = pattern {
+            self.define(name, *location);
+        }
+    }
+
+    fn visit_typed_constant(&mut self, constant: &'ast TypedConstant) {
+        if let Constant::Var {
+            location,
+            module,
+            name,
+            constructor: Some(constructor),
+            ..
+        } = constant
+        {
+            let is_value = matches!(
+                constructor.variant,
+                ValueConstructorVariant::ModuleFn { .. }
+                    | ValueConstructorVariant::ModuleConstant { .. }
+            );
+            // Values imported with an alias keep their alias
+            let referred = match module {
+                Some(_) => module_value(&self.names, module.as_ref(), name),
+                None if name == self.renamed.name() => module_value(&self.names, None, name),
+                None => None,
+            };
+            if is_value && referred.as_ref() == Some(self.renamed) {
+                self.rename_end(*location);
+            }
+        }
+        visit::visit_typed_constant(self, constant);
+    }
+}
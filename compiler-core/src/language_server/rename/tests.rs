@@ -0,0 +1,376 @@
+use crate::type_::tests::compile_module;
+
+use super::{CannotRename, Edit, Renamed};
+
+const WIBBLE: &str = "pub fn double(x) { x * 2 }
+
+pub const answer = 42
+";
+
+fn apply(src: &str, edits: &[Edit]) -> String {
+    let mut renamed = String::new();
+    let mut position = 0;
+    for edit in edits {
+        renamed.push_str(
+            src.get(position..edit.location.start as usize)
+                .unwrap_or_default(),
+        );
+        renamed.push_str(&edit.new_text);
+        position = edit.location.end as usize;
+    }
+    renamed.push_str(src.get(position..).unwrap_or_default());
+    renamed
+}
+
+/// Renames what is at the start of the first occurrence of `at` in the source.
+fn rename(src: &str, at: &str, new_name: &str) -> String {
+    let module = compile_module("app", src, None, vec![("thepackage", "wibble", WIBBLE)])
+        .expect("should compile");
+    let byte_index = src.find(at).expect("position in source") as u32;
+    let node = module.find_node(byte_index).expect("node at position");
+    let renamed = Renamed::at(&module, node, byte_index).expect("should be renamable");
+    apply(src, &super::edits(&module, src, &renamed, new_name))
+}
+
+/// Renames a value of the `wibble` module in a module importing it.
+fn rename_imported(src: &str, name: &str, new_name: &str) -> String {
+    let module = compile_module("app", src, None, vec![("thepackage", "wibble", WIBBLE)])
+        .expect("should compile");
+    let renamed = Renamed::ModuleValue {
+        module: "wibble".into(),
+        name: name.into(),
+    };
+    assert!(renamed.is_used_by(&module));
+    apply(src, &super::edits(&module, src, &renamed, new_name))
+}
+
+#[test]
+fn local_variable_is_renamed_where_it_is_used() {
+    let src = "pub fn main() {
+  let x = 1
+  let y = x + 1
+  x + y
+}
+";
+    assert_eq!(
+        rename(src, "x + 1", "value"),
+        "pub fn main() {
+  let value = 1
+  let y = value + 1
+  value + y
+}
+"
+    );
+}
+
+#[test]
+fn shadowed_variables_are_not_renamed() {
+    let src = "pub fn main() {
+  let x = 1
+  let x = x + 1
+  x
+}
+";
+    assert_eq!(
+        rename(src, "x = 1", "first"),
+        "pub fn main() {
+  let first = 1
+  let x = first + 1
+  x
+}
+"
+    );
+}
+
+#[test]
+fn labelled_argument_keeps_its_label() {
+    let src = "pub fn add(amount: Int, to x: Int) -> Int {
+  x + amount
+}
+";
+    assert_eq!(
+        rename(src, "x + amount", "number"),
+        "pub fn add(amount: Int, to number: Int) -> Int {
+  number + amount
+}
+"
+    );
+}
+
+#[test]
+fn label_shorthand_keeps_its_label() {
+    let src = "pub fn main() {
+  let amount = 1
+  add(amount:)
+}
+
+fn add(amount amount: Int) -> Int {
+  amount
+}
+";
+    assert_eq!(
+        rename(src, "amount = 1", "total"),
+        "pub fn main() {
+  let total = 1
+  add(amount: total)
+}
+
+fn add(amount amount: Int) -> Int {
+  amount
+}
+"
+    );
+}
+
+#[test]
+fn variables_in_guards_are_renamed() {
+    let src = "pub fn main(x) {
+  case x {
+    y if y > x -> y
+    y -> x
+  }
+}
+";
+    assert_eq!(
+        rename(src, "x) {", "number"),
+        "pub fn main(number) {
+  case number {
+    y if y > number -> y
+    y -> number
+  }
+}
+"
+    );
+}
+
+#[test]
+fn module_function_is_renamed_where_it_is_defined_and_used() {
+    let src = "pub fn main() {
+  let f = helper
+  helper() + f()
+}
+
+fn helper() {
+  1
+}
+";
+    assert_eq!(
+        rename(src, "helper() {", "one"),
+        "pub fn main() {
+  let f = one
+  one() + f()
+}
+
+fn one() {
+  1
+}
+"
+    );
+}
+
+#[test]
+fn module_constant_is_renamed_in_constants() {
+    let src = "const size = 1
+
+const sizes = [size, size]
+
+pub fn main() {
+  size
+}
+";
+    assert_eq!(
+        rename(src, "size\n}", "length"),
+        "const length = 1
+
+const sizes = [length, length]
+
+pub fn main() {
+  length
+}
+"
+    );
+}
+
+#[test]
+fn imported_function_is_renamed_in_importing_module() {
+    let src = "import wibble.{double}
+
+pub fn main() {
+  double(1) + wibble.double(2)
+}
+";
+    assert_eq!(
+        rename_imported(src, "double", "twice"),
+        "import wibble.{twice}
+
+pub fn main() {
+  twice(1) + wibble.twice(2)
+}
+"
+    );
+}
+
+#[test]
+fn aliased_unqualified_import_keeps_its_alias() {
+    let src = "import wibble.{answer as value}
+
+pub fn main() {
+  value + wibble.answer
+}
+";
+    assert_eq!(
+        rename_imported(src, "answer", "result"),
+        "import wibble.{result as value}
+
+pub fn main() {
+  value + wibble.result
+}
+"
+    );
+}
+
+#[test]
+fn names_must_be_valid() {
+    assert!(super::is_valid_name("wibble_2"));
+    assert!(!super::is_valid_name("Wibble"));
+    assert!(!super::is_valid_name("2wibble"));
+    assert!(!super::is_valid_name("case"));
+    assert!(!super::is_valid_name(""));
+}
+
+#[test]
+fn label_is_renamed_in_definition_and_calls() {
+    let src = "pub fn main() {
+  let amount = 1
+  add(1, amount:) + add(to: 2, amount: 3)
+}
+
+fn add(to x: Int, amount amount: Int) -> Int {
+  x + amount
+}
+";
+    assert_eq!(
+        rename(src, "amount: 3", "by"),
+        "pub fn main() {
+  let amount = 1
+  add(1, by: amount) + add(to: 2, by: 3)
+}
+
+fn add(to x: Int, by amount: Int) -> Int {
+  x + amount
+}
+"
+    );
+}
+
+#[test]
+fn label_is_renamed_from_its_definition() {
+    let src = "pub fn main() {
+  add(to: 1, amount: 2)
+}
+
+fn add(to x: Int, amount y: Int) -> Int {
+  x + y
+}
+";
+    assert_eq!(
+        rename(src, "to x", "base"),
+        "pub fn main() {
+  add(base: 1, amount: 2)
+}
+
+fn add(base x: Int, amount y: Int) -> Int {
+  x + y
+}
+"
+    );
+}
+
+#[test]
+fn label_is_renamed_in_qualified_calls() {
+    let src = "import wibble
+
+pub fn main() {
+  wibble.add(to: 1, amount: 2)
+}
+";
+    let module = compile_module(
+        "app",
+        src,
+        None,
+        vec![(
+            "thepackage",
+            "wibble",
+            "pub fn add(to x: Int, amount y: Int) { x + y }",
+        )],
+    )
+    .expect("should compile");
+    let renamed = Renamed::Label {
+        module: "wibble".into(),
+        function: "add".into(),
+        label: "amount".into(),
+    };
+    assert!(renamed.is_used_by(&module));
+    assert_eq!(
+        apply(src, &super::edits(&module, src, &renamed, "by")),
+        "import wibble
+
+pub fn main() {
+  wibble.add(to: 1, by: 2)
+}
+"
+    );
+}
+
+/// Prepares renaming at the start of the first occurrence of `at` in the
+/// source, returning the name renamed.
+fn prepare(src: &str, at: &str) -> Result<String, CannotRename> {
+    let module = compile_module("app", src, None, vec![("thepackage", "wibble", WIBBLE)])
+        .expect("should compile");
+    let byte_index = src.find(at).expect("position in source") as u32;
+    super::prepare(&module, src, byte_index).map(|(_, location)| {
+        src.get(location.start as usize..location.end as usize)
+            .unwrap_or_default()
+            .into()
+    })
+}
+
+#[test]
+fn prepare_finds_the_name_renamed() {
+    let src = "import wibble
+
+pub fn main() {
+  let x = wibble.double(1)
+  x
+}
+";
+    assert_eq!(prepare(src, "x = "), Ok("x".into()));
+    assert_eq!(prepare(src, "double"), Ok("double".into()));
+    assert_eq!(prepare(src, "main"), Ok("main".into()));
+}
+
+#[test]
+fn keywords_cannot_be_renamed() {
+    let src = "pub fn main() {
+  let x = 1
+  x
+}
+";
+    assert_eq!(
+        prepare(src, "let"),
+        Err(CannotRename::Keyword("let".into()))
+    );
+    assert_eq!(prepare(src, "fn"), Err(CannotRename::Keyword("fn".into())));
+}
+
+#[test]
+fn module_names_cannot_be_renamed() {
+    let src = "import wibble
+
+pub fn main() {
+  wibble.double(1)
+}
+";
+    assert_eq!(prepare(src, "wibble\n"), Err(CannotRename::ModuleName));
+    assert_eq!(prepare(src, "wibble."), Err(CannotRename::ModuleName));
+}
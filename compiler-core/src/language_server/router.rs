@@ -1,10 +1,12 @@
 use crate::{
+    build::CancellationToken,
     error::{FileIoAction, FileKind},
     io::{CommandExecutor, FileSystemReader, FileSystemWriter},
     language_server::{
-        engine::LanguageServerEngine, files::FileSystemProxy, progress::ProgressReporter,
-        DownloadDependencies, MakeLocker,
+        configuration::SharedConfiguration, engine::LanguageServerEngine, files::FileSystemProxy,
+        progress::ProgressReporter, DownloadDependencies, MakeLocker,
     },
+    line_numbers::PositionEncoding,
     paths::ProjectPaths,
     Error, Result,
 };
@@ -26,6 +28,10 @@ pub struct Router<IO, Reporter> {
     io: FileSystemProxy<IO>,
     engines: HashMap<Utf8PathBuf, Project<IO, Reporter>>,
     progress_reporter: Reporter,
+    position_encoding: PositionEncoding,
+    configuration: SharedConfiguration,
+    /// Cancels the compilation in progress of any of the projects.
+    cancellation: CancellationToken,
 }
 
 impl<'a, IO, Reporter> Router<IO, Reporter>
@@ -40,14 +46,27 @@ where
     // IO to be supplied from inside of gleam-core
     Reporter: ProgressReporter + Clone + 'a,
 {
-    pub fn new(progress_reporter: Reporter, io: FileSystemProxy<IO>) -> Self {
+    pub fn new(
+        progress_reporter: Reporter,
+        io: FileSystemProxy<IO>,
+        position_encoding: PositionEncoding,
+        configuration: SharedConfiguration,
+    ) -> Self {
         Self {
             io,
             engines: HashMap::new(),
             progress_reporter,
+            position_encoding,
+            configuration,
+            cancellation: CancellationToken::new(),
         }
     }
 
+    /// The token to cancel the compilation in progress with.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
     pub fn project_path(&self, path: &Utf8Path) -> Option<Utf8PathBuf> {
         find_gleam_project_parent(&self.io, path)
     }
@@ -88,6 +107,9 @@ where
             self.progress_reporter.clone(),
             self.io.clone(),
             paths,
+            self.position_encoding,
+            self.configuration.clone(),
+            self.cancellation.clone(),
         )?;
         let project = Project {
             engine,
@@ -96,11 +118,40 @@ where
         Ok(Some(entry.insert(project)))
     }
 
+    /// The projects an engine has been created for so far.
+    pub fn projects(&self) -> impl Iterator<Item = &Project<IO, Reporter>> {
+        self.engines.values()
+    }
+
+    /// The projects that have the file in one of their path dependencies.
+    pub fn projects_depending_on(&self, path: &Utf8Path) -> Vec<Utf8PathBuf> {
+        self.engines
+            .iter()
+            .filter(|(_, project)| project.engine.depends_on_path(path))
+            .map(|(project_path, _)| project_path.clone())
+            .collect()
+    }
+
     pub fn delete_engine_for_path(&mut self, path: &Utf8Path) {
         if let Some(path) = find_gleam_project_parent(&self.io, path) {
             _ = self.engines.remove(&path);
         }
     }
+
+    /// Discards the engines of all the projects within the directory, such as
+    /// a workspace folder that has been removed from the editor. Engines are
+    /// created again for any of them that are used after.
+    pub fn delete_engines_in(&mut self, directory: &Utf8Path) {
+        self.engines
+            .retain(|project_path, _| !project_path.starts_with(directory));
+    }
+
+    /// Discards the engines of all projects, such as when the target they are
+    /// analysed for has been changed, returning the paths of the projects.
+    /// Engines are created again for any of them that are used after.
+    pub fn delete_all_engines(&mut self) -> Vec<Utf8PathBuf> {
+        self.engines.drain().map(|(path, _)| path).collect()
+    }
 }
 
 /// Given a given path, find the nearest parent directory containing a
@@ -128,6 +179,17 @@ where
             continue;
         }
 
+        // Dependency packages downloaded into the build directory of a project
+        // belong to that project, which analyses their modules.
+        if let Some(project) = root
+            .parent()
+            .filter(|packages| packages.ends_with("build/packages"))
+            .and_then(|packages| packages.parent()?.parent())
+            .filter(|project| io.is_file(&project.join("gleam.toml")))
+        {
+            return Some(project.to_path_buf());
+        }
+
         // If it is a Gleam module then it must reside in the src or test directory.
         if is_module && !(directory.ends_with("test") || directory.ends_with("src")) {
             _ = directory.pop();
@@ -216,6 +278,21 @@ mod find_gleam_project_parent_tests {
         );
     }
 
+    #[test]
+    fn module_in_dependency_package() {
+        let io = InMemoryFileSystem::new();
+        io.write(Utf8Path::new("/app/gleam.toml"), "").unwrap();
+        io.write(Utf8Path::new("/app/build/packages/wibble/gleam.toml"), "")
+            .unwrap();
+        assert_eq!(
+            find_gleam_project_parent(
+                &io,
+                Utf8Path::new("/app/build/packages/wibble/src/wibble/wobble.gleam")
+            ),
+            Some(Utf8PathBuf::from("/app"))
+        );
+    }
+
     #[test]
     fn nested_projects() {
         let io = InMemoryFileSystem::new();
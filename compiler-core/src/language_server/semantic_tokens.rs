@@ -0,0 +1,303 @@
+//! Semantic tokens, which let editors highlight names by what they refer to
+//! in the typed AST rather than by how they look: modules, types, record
+//! constructors, record fields, labels, and functions, and whether the value
+//! used is deprecated.
+
+use std::sync::Arc;
+
+use ecow::EcoString;
+use lsp_types as lsp;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        CallArg, Constant, Pattern, SrcSpan, TypeAst, TypedArg, TypedConstant, TypedCustomType,
+        TypedExpr, TypedFunction, TypedModule, TypedPattern, TypedTypeAlias,
+    },
+    line_numbers::{LineNumbers, PositionEncoding},
+    type_::{
+        ModuleInterface, ModuleValueConstructor, Type, ValueConstructor, ValueConstructorVariant,
+    },
+};
+
+use super::{
+    reference::{name_after_alias, name_at_end, name_at_start, name_in},
+    src_span_to_lsp_range,
+};
+
+/// The kinds of token, in the order of the types of the legend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Module,
+    Type,
+    Constructor,
+    Field,
+    Label,
+    Function,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub location: SrcSpan,
+    pub kind: TokenKind,
+    pub deprecated: bool,
+}
+
+/// The token types and modifiers the server uses, sent to the client when
+/// the server is initialised.
+pub fn legend() -> lsp::SemanticTokensLegend {
+    lsp::SemanticTokensLegend {
+        token_types: vec![
+            lsp::SemanticTokenType::NAMESPACE,
+            lsp::SemanticTokenType::TYPE,
+            lsp::SemanticTokenType::ENUM_MEMBER,
+            lsp::SemanticTokenType::PROPERTY,
+            lsp::SemanticTokenType::PARAMETER,
+            lsp::SemanticTokenType::FUNCTION,
+        ],
+        token_modifiers: vec![lsp::SemanticTokenModifier::DEPRECATED],
+    }
+}
+
+/// The tokens of a module, in the order they appear in the source. The
+/// interfaces of the modules it imports are used to find out which values
+/// selected from them are deprecated.
+pub fn tokens(
+    module: &TypedModule,
+    code: &str,
+    modules: &im::HashMap<EcoString, ModuleInterface>,
+) -> Vec<Token> {
+    let mut finder = TokenFinder {
+        code,
+        modules,
+        tokens: vec![],
+    };
+    finder.visit_typed_module(module);
+
+    // Tokens can't overlap, which they could where a label shorthand such as
+    // `name:` is used.
+    let mut tokens = finder.tokens;
+    tokens.sort_by_key(|token| token.location.start);
+    let mut end = 0;
+    tokens.retain(|token| {
+        let is_kept = token.location.start >= end;
+        if is_kept {
+            end = token.location.end;
+        }
+        is_kept
+    });
+    tokens
+}
+
+/// Encodes tokens as the LSP expects them, each relative to the previous one.
+pub fn encode(
+    tokens: &[Token],
+    line_numbers: &LineNumbers,
+    encoding: PositionEncoding,
+) -> Vec<lsp::SemanticToken> {
+    let mut encoded = Vec::with_capacity(tokens.len());
+    let mut previous = lsp::Position::new(0, 0);
+    for token in tokens {
+        let range = src_span_to_lsp_range(token.location, line_numbers, encoding);
+        if range.start.line != range.end.line {
+            continue;
+        }
+        let delta_line = range.start.line - previous.line;
+        let delta_start = if delta_line == 0 {
+            range.start.character - previous.character
+        } else {
+            range.start.character
+        };
+        encoded.push(lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length: range.end.character - range.start.character,
+            token_type: token.kind as u32,
+            token_modifiers_bitset: u32::from(token.deprecated),
+        });
+        previous = range.start;
+    }
+    encoded
+}
+
+struct TokenFinder<'a> {
+    code: &'a str,
+    modules: &'a im::HashMap<EcoString, ModuleInterface>,
+    tokens: Vec<Token>,
+}
+
+impl TokenFinder<'_> {
+    fn add(&mut self, location: SrcSpan, kind: TokenKind) {
+        self.add_deprecated(location, kind, false);
+    }
+
+    fn add_deprecated(&mut self, location: SrcSpan, kind: TokenKind, deprecated: bool) {
+        self.tokens.push(Token {
+            location,
+            kind,
+            deprecated,
+        });
+    }
+
+    fn definition(&mut self, head: SrcSpan, name: &str, kind: TokenKind) {
+        if let Some(location) = name_in(self.code, head, name) {
+            self.add(location, kind);
+        }
+    }
+
+    /// The module alias of a name written as `alias.name`.
+    fn module_alias(&mut self, location: SrcSpan, module: Option<&EcoString>) {
+        if let Some(alias) = module {
+            let end = location.start + alias.len() as u32;
+            self.add(SrcSpan::new(location.start, end), TokenKind::Module);
+        }
+    }
+
+    fn labels<A>(&mut self, args: &[CallArg<A>]) {
+        for arg in args.iter().filter(|arg| !arg.implicit) {
+            if let Some(label) = &arg.label {
+                self.add(name_at_start(arg.location, label), TokenKind::Label);
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for TokenFinder<'_> {
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        self.definition(function.location, &function.name, TokenKind::Function);
+        visit::visit_typed_function(self, function);
+    }
+
+    fn visit_typed_custom_type(&mut self, custom_type: &'ast TypedCustomType) {
+        self.definition(custom_type.location, &custom_type.name, TokenKind::Type);
+        for constructor in &custom_type.constructors {
+            let location = name_at_start(constructor.location, &constructor.name);
+            self.add(location, TokenKind::Constructor);
+            for argument in &constructor.arguments {
+                if let Some(label) = &argument.label {
+                    self.add(name_at_start(argument.location, label), TokenKind::Field);
+                }
+            }
+        }
+        visit::visit_typed_custom_type(self, custom_type);
+    }
+
+    fn visit_typed_type_alias(&mut self, type_alias: &'ast TypedTypeAlias) {
+        self.definition(type_alias.location, &type_alias.alias, TokenKind::Type);
+        visit::visit_typed_type_alias(self, type_alias);
+    }
+
+    fn visit_typed_arg(&mut self, arg: &'ast TypedArg) {
+        if let Some(label) = arg.names.get_label() {
+            self.add(name_at_start(arg.location, label), TokenKind::Label);
+        }
+        visit::visit_typed_arg(self, arg);
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        if let TypedExpr::RecordAccess {
+            location, label, ..
+        } = expr
+        {
+            self.add(name_at_end(*location, label), TokenKind::Field);
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+
+    fn visit_typed_expr_var(
+        &mut self,
+        location: &'ast SrcSpan,
+        constructor: &'ast ValueConstructor,
+        name: &'ast EcoString,
+    ) {
+        let kind = match constructor.variant {
+            ValueConstructorVariant::ModuleFn { .. } => TokenKind::Function,
+            ValueConstructorVariant::Record { .. } => TokenKind::Constructor,
+            ValueConstructorVariant::LocalVariable { .. }
+            | ValueConstructorVariant::ModuleConstant { .. }
+            | ValueConstructorVariant::LocalConstant { .. } => return,
+        };
+        let deprecated = constructor.deprecation.is_deprecated();
+        self.add_deprecated(name_at_start(*location, name), kind, deprecated);
+    }
+
+    fn visit_typed_expr_module_select(
+        &mut self,
+        location: &'ast SrcSpan,
+        _typ: &'ast Arc<Type>,
+        label: &'ast EcoString,
+        module_name: &'ast EcoString,
+        module_alias: &'ast EcoString,
+        constructor: &'ast ModuleValueConstructor,
+    ) {
+        // The location of a module select starts at the dot after the alias
+        let start = location.start.saturating_sub(module_alias.len() as u32);
+        self.add(SrcSpan::new(start, location.start), TokenKind::Module);
+        let kind = match constructor {
+            ModuleValueConstructor::Fn { .. } => TokenKind::Function,
+            ModuleValueConstructor::Record { .. } => TokenKind::Constructor,
+            ModuleValueConstructor::Constant { .. } => return,
+        };
+        let deprecated = self
+            .modules
+            .get(module_name)
+            .and_then(|module| module.values.get(label))
+            .is_some_and(|value| value.deprecation.is_deprecated());
+        self.add_deprecated(name_at_end(*location, label), kind, deprecated);
+    }
+
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'ast SrcSpan,
+        typ: &'ast Arc<Type>,
+        fun: &'ast TypedExpr,
+        args: &'ast [CallArg<TypedExpr>],
+    ) {
+        self.labels(args);
+        visit::visit_typed_expr_call(self, location, typ, fun, args);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        if let Pattern::Constructor {
+            location,
+            name,
+            module,
+            arguments,
+            ..
+        } = pattern
+        {
+            self.module_alias(*location, module.as_ref());
+            let location = name_after_alias(*location, module.as_ref(), name);
+            self.add(location, TokenKind::Constructor);
+            self.labels(arguments);
+        }
+        visit::visit_typed_pattern(self, pattern);
+    }
+
+    fn visit_typed_constant(&mut self, constant: &'ast TypedConstant) {
+        if let Constant::Record {
+            location,
+            module,
+            name,
+            args,
+            ..
+        } = constant
+        {
+            self.module_alias(*location, module.as_ref());
+            let location = name_after_alias(*location, module.as_ref(), name);
+            self.add(location, TokenKind::Constructor);
+            self.labels(args);
+        }
+        visit::visit_typed_constant(self, constant);
+    }
+
+    fn visit_type_ast(&mut self, type_ast: &'ast TypeAst) {
+        if let TypeAst::Constructor(constructor) = type_ast {
+            let module = constructor.module.as_ref();
+            self.module_alias(constructor.location, module);
+            let location = name_after_alias(constructor.location, module, &constructor.name);
+            self.add(location, TokenKind::Type);
+        }
+        visit::visit_type_ast(self, type_ast);
+    }
+}
@@ -4,25 +4,35 @@ use super::{
 };
 use crate::{
     diagnostic::{Diagnostic, Level},
+    format::FormatOptions,
     io::{CommandExecutor, FileSystemReader, FileSystemWriter},
     language_server::{
+        code_lens::{RunParams, RUN_COMMAND},
+        compiled_code::ShowCompiledCodeParams,
+        configuration::{Configuration, SharedConfiguration},
+        diagnostics,
         engine::{self, LanguageServerEngine},
         feedback::{Feedback, FeedbackBookKeeper},
         files::FileSystemProxy,
+        folding_range, formatting,
+        rename::CannotRename,
         router::Router,
-        src_span_to_lsp_range, DownloadDependencies, MakeLocker,
+        semantic_tokens, DownloadDependencies, MakeLocker, RunGleamCommand,
     },
-    line_numbers::LineNumbers,
+    line_numbers::PositionEncoding,
+    paths::ProjectPaths,
     Result,
 };
 use camino::{Utf8Path, Utf8PathBuf};
 use debug_ignore::DebugIgnore;
 use lsp_types::{
-    self as lsp, HoverProviderCapability, InitializeParams, Position, PublishDiagnosticsParams,
-    Range, TextEdit, Url,
+    self as lsp, HoverProviderCapability, InitializeParams, PublishDiagnosticsParams, TextEdit, Url,
 };
 use serde_json::Value as Json;
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+};
 
 /// This class is responsible for handling the language server protocol and
 /// delegating the work to the engine.
@@ -37,11 +47,15 @@ use std::collections::{HashMap, HashSet};
 #[derive(Debug)]
 pub struct LanguageServer<'a, IO> {
     initialise_params: InitializeParams,
+    position_encoding: PositionEncoding,
     connection: DebugIgnore<&'a lsp_server::Connection>,
     outside_of_project_feedback: FeedbackBookKeeper,
     router: Router<IO, ConnectionProgressReporter<'a>>,
     changed_projects: HashSet<Utf8PathBuf>,
     io: FileSystemProxy<IO>,
+    configuration: SharedConfiguration,
+    /// The id of the last request sent to the client.
+    last_request_id: i32,
 }
 
 impl<'a, IO> LanguageServer<'a, IO>
@@ -51,26 +65,46 @@ where
         + CommandExecutor
         + DownloadDependencies
         + MakeLocker
+        + RunGleamCommand
         + Clone,
 {
     pub fn new(connection: &'a lsp_server::Connection, io: IO) -> Result<Self> {
-        let initialise_params = initialisation_handshake(connection);
+        let (initialise_params, position_encoding) = initialisation_handshake(connection);
         let reporter = ConnectionProgressReporter::new(connection, &initialise_params);
         let io = FileSystemProxy::new(io);
-        let router = Router::new(reporter, io.clone());
+        let configuration = initialise_params
+            .initialization_options
+            .as_ref()
+            .map(Configuration::from_json)
+            .unwrap_or_default();
+        let configuration = Arc::new(RwLock::new(configuration));
+        let router = Router::new(
+            reporter,
+            io.clone(),
+            position_encoding,
+            configuration.clone(),
+        );
         Ok(Self {
             connection: connection.into(),
             initialise_params,
+            position_encoding,
             changed_projects: HashSet::new(),
             outside_of_project_feedback: FeedbackBookKeeper::default(),
             router,
             io,
+            configuration,
+            last_request_id: 0,
         })
     }
 
     pub fn run(&mut self) -> Result<()> {
         self.start_watching_gleam_toml();
-        let mut buffer = MessageBuffer::new();
+        self.start_watching_configuration();
+        let mut buffer = MessageBuffer::new(
+            *self.connection,
+            self.configuration().compile_debounce(),
+            self.router.cancellation_token(),
+        );
 
         loop {
             match buffer.receive(*self.connection) {
@@ -80,6 +114,7 @@ where
                     for message in messages {
                         self.handle_message(message);
                     }
+                    buffer.set_pause(self.configuration().compile_debounce());
                 }
             }
         }
@@ -95,20 +130,57 @@ where
     }
 
     fn handle_request(&mut self, id: lsp_server::RequestId, request: Request) {
+        let mut error = None;
         let (payload, feedback) = match request {
             Request::Format(param) => self.format(param),
+            Request::RangeFormat(param) => self.range_format(param),
+            Request::FoldingRange(param) => self.folding_range(param),
             Request::Hover(param) => self.hover(param),
             Request::GoToDefinition(param) => self.goto_definition(param),
             Request::Completion(param) => self.completion(param),
             Request::CodeAction(param) => self.code_action(param),
+            Request::InlayHint(param) => self.inlay_hint(param),
+            Request::Rename(param) => self.rename(param),
+            Request::PrepareRename(param) => {
+                let (payload, feedback, cannot_rename) = self.prepare_rename(param);
+                error = cannot_rename.map(|reason| lsp_server::ResponseError {
+                    code: lsp_server::ErrorCode::RequestFailed as i32,
+                    message: reason.message(),
+                    data: None,
+                });
+                (payload, feedback)
+            }
+            Request::References(param) => self.references(param),
+            Request::SignatureHelp(param) => self.signature_help(param),
+            Request::SemanticTokensFull(param) => self.semantic_tokens_full(param),
+            Request::SemanticTokensRange(param) => self.semantic_tokens_range(param),
+            Request::WorkspaceSymbol(param) => self.workspace_symbol(param),
+            Request::GoToTypeDefinition(param) => self.goto_type_definition(param),
+            Request::GoToImplementation(param) => self.goto_implementation(param),
+            Request::DocumentHighlight(param) => self.document_highlight(param),
+            Request::PrepareCallHierarchy(param) => self.prepare_call_hierarchy(param),
+            Request::IncomingCalls(param) => self.incoming_calls(*param),
+            Request::OutgoingCalls(param) => self.outgoing_calls(*param),
+            Request::ShowCompiledCode(param) => self.show_compiled_code(param),
+            Request::Diagnostic(param) => self.diagnostic(param),
+            Request::CodeLens(param) => self.code_lens(param),
+            Request::DocumentLink(param) => self.document_link(param),
+            Request::ExecuteCommand(param) => self.execute_command(param),
         };
 
         self.publish_feedback(feedback);
 
-        let response = lsp_server::Response {
-            id,
-            error: None,
-            result: Some(payload),
+        let response = match error {
+            Some(error) => lsp_server::Response {
+                id,
+                error: Some(error),
+                result: None,
+            },
+            None => lsp_server::Response {
+                id,
+                error: None,
+                result: Some(payload),
+            },
         };
         self.connection
             .sender
@@ -120,10 +192,16 @@ where
         let feedback = match notification {
             Notification::CompilePlease => self.compile_please(),
             Notification::SourceFileMatchesDisc { path } => self.discard_in_memory_cache(path),
-            Notification::SourceFileChangedInMemory { path, text } => {
-                self.cache_file_in_memory(path, text)
+            Notification::SourceFileOpened { path, text } => self.open_file(path, text),
+            Notification::SourceFileChangedInMemory { path, changes } => {
+                self.edit_file_in_memory(path, changes)
             }
+            Notification::SourceFileClosed { path } => self.close_file(path),
             Notification::ConfigFileChanged { path } => self.watched_files_changed(path),
+            Notification::WorkspaceFoldersRemoved { paths } => {
+                self.workspace_folders_removed(paths)
+            }
+            Notification::ConfigurationChanged { settings } => self.configuration_changed(settings),
         };
         self.publish_feedback(feedback);
     }
@@ -137,7 +215,7 @@ where
         for (path, diagnostics) in diagnostics {
             let diagnostics = diagnostics
                 .into_iter()
-                .flat_map(diagnostic_to_lsp)
+                .flat_map(|diagnostic| diagnostics::to_lsp(diagnostic, self.position_encoding))
                 .collect::<Vec<_>>();
             let uri = path_to_uri(path);
 
@@ -190,7 +268,7 @@ where
             ),
         };
         let request = lsp_server::Request {
-            id: 1.into(),
+            id: self.next_request_id(),
             method: "client/registerCapability".into(),
             params: serde_json::value::to_value(lsp::RegistrationParams {
                 registrations: vec![watch_config],
@@ -203,6 +281,47 @@ where
             .expect("send client/registerCapability");
     }
 
+    /// An id for a request to the client that hasn't been used by another one.
+    fn next_request_id(&mut self) -> lsp_server::RequestId {
+        self.last_request_id += 1;
+        self.last_request_id.into()
+    }
+
+    /// Registers for notifications of changes to the settings of the client,
+    /// if it supports registering for them.
+    fn start_watching_configuration(&mut self) {
+        let supports_configuration = self
+            .initialise_params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|w| w.did_change_configuration)
+            .map(|c| c.dynamic_registration == Some(true))
+            .unwrap_or(false);
+
+        if !supports_configuration {
+            return;
+        }
+
+        let registration = lsp::Registration {
+            id: "watch-configuration".into(),
+            method: "workspace/didChangeConfiguration".into(),
+            register_options: None,
+        };
+        let request = lsp_server::Request {
+            id: self.next_request_id(),
+            method: "client/registerCapability".into(),
+            params: serde_json::value::to_value(lsp::RegistrationParams {
+                registrations: vec![registration],
+            })
+            .expect("client/registerCapability to json"),
+        };
+        self.connection
+            .sender
+            .send(lsp_server::Message::Request(request))
+            .expect("send client/registerCapability");
+    }
+
     fn publish_messages(&self, messages: Vec<Diagnostic>) {
         for message in messages {
             let params = lsp::ShowMessageParams {
@@ -234,8 +353,9 @@ where
             &mut LanguageServerEngine<IO, ConnectionProgressReporter<'a>>,
         ) -> engine::Response<T>,
     {
-        match self.router.project_for_path(path) {
+        match self.router.project_for_path(path.clone()) {
             Ok(Some(project)) => {
+                project.engine.load_dependency_module(&path);
                 let engine::Response {
                     result,
                     warnings,
@@ -270,26 +390,69 @@ where
 
     fn format(&mut self, params: lsp::DocumentFormattingParams) -> (Json, Feedback) {
         let path = super::path(&params.text_document.uri);
+        match self.format_edits(path) {
+            Ok(edits) => (
+                serde_json::to_value(edits).expect("to JSON value"),
+                Feedback::default(),
+            ),
+            Err(response) => response,
+        }
+    }
+
+    fn range_format(&mut self, params: lsp::DocumentRangeFormattingParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        match self.format_edits(path) {
+            Ok(edits) => {
+                let edits = formatting::in_range(edits, params.range);
+                (
+                    serde_json::to_value(edits).expect("to JSON value"),
+                    Feedback::default(),
+                )
+            }
+            Err(response) => response,
+        }
+    }
+
+    /// The edits formatting the module at the path, changing only the lines
+    /// that aren't already formatted.
+    fn format_edits(&mut self, path: Utf8PathBuf) -> Result<Vec<TextEdit>, (Json, Feedback)> {
         let mut new_text = String::new();
 
         let src = match self.io.read(&path) {
             Ok(src) => src.into(),
-            Err(error) => return self.path_error_response(path, error),
+            Err(error) => return Err(self.path_error_response(path, error)),
         };
 
-        if let Err(error) = crate::format::pretty(&mut new_text, &src, &path) {
-            return self.path_error_response(path, error);
+        let options = match self.router.project_for_path(path.clone()) {
+            Ok(Some(project)) => project
+                .engine
+                .compiler
+                .project_compiler
+                .config
+                .format
+                .options(),
+            Ok(None) | Err(_) => FormatOptions::default(),
+        };
+
+        if let Err(error) = crate::format::pretty_with_options(&mut new_text, &src, &path, options)
+        {
+            return Err(self.path_error_response(path, error));
         }
 
-        let line_count = src.lines().count() as u32;
+        Ok(formatting::edits(&src, &new_text))
+    }
 
-        let edit = TextEdit {
-            range: Range::new(Position::new(0, 0), Position::new(line_count, 0)),
-            new_text,
+    fn folding_range(&mut self, params: lsp::FoldingRangeParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        let src = match self.io.read(&path) {
+            Ok(src) => src,
+            Err(error) => return self.path_error_response(path, error),
         };
-        let json = serde_json::to_value(vec![edit]).expect("to JSON value");
-
-        (json, Feedback::default())
+        let ranges = folding_range::folding_ranges(&src);
+        (
+            serde_json::to_value(ranges).expect("to JSON value"),
+            Feedback::default(),
+        )
     }
 
     fn hover(&mut self, params: lsp::HoverParams) -> (Json, Feedback) {
@@ -302,6 +465,82 @@ where
         self.respond_with_engine(path, |engine| engine.goto_definition(params))
     }
 
+    fn goto_type_definition(&mut self, params: lsp::GotoDefinitionParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position_params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.goto_type_definition(params))
+    }
+
+    fn goto_implementation(&mut self, params: lsp::GotoDefinitionParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position_params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.goto_implementation(params))
+    }
+
+    fn document_highlight(&mut self, params: lsp::DocumentHighlightParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position_params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.document_highlight(params))
+    }
+
+    fn prepare_call_hierarchy(
+        &mut self,
+        params: lsp::CallHierarchyPrepareParams,
+    ) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position_params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.prepare_call_hierarchy(params))
+    }
+
+    fn incoming_calls(
+        &mut self,
+        params: lsp::CallHierarchyIncomingCallsParams,
+    ) -> (Json, Feedback) {
+        let path = super::path(&params.item.uri);
+        self.respond_with_engine(path, |engine| engine.incoming_calls(params))
+    }
+
+    fn outgoing_calls(
+        &mut self,
+        params: lsp::CallHierarchyOutgoingCallsParams,
+    ) -> (Json, Feedback) {
+        let path = super::path(&params.item.uri);
+        self.respond_with_engine(path, |engine| engine.outgoing_calls(params))
+    }
+
+    fn show_compiled_code(&mut self, params: ShowCompiledCodeParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.compiled_code(params))
+    }
+
+    fn diagnostic(&mut self, params: lsp::DocumentDiagnosticParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.diagnostic(params))
+    }
+
+    fn code_lens(&mut self, params: lsp::CodeLensParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.code_lens(params))
+    }
+
+    fn document_link(&mut self, params: lsp::DocumentLinkParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.document_link(params))
+    }
+
+    /// Runs the function of a `gleam.run` command given by a code lens. Its
+    /// output goes to the log of the language server.
+    fn execute_command(&mut self, params: lsp::ExecuteCommandParams) -> (Json, Feedback) {
+        let Some(run) = RunParams::from_command(&params) else {
+            return (Json::Null, Feedback::default());
+        };
+        let path = super::path(&run.uri);
+        let Some(root) = self.router.project_path(&path) else {
+            return (Json::Null, Feedback::default());
+        };
+        let paths = ProjectPaths::new(root);
+        match self.io.inner().run_gleam_command(&paths, run.arguments()) {
+            Ok(()) => (Json::Null, Feedback::default()),
+            Err(error) => self.path_error_response(path, error),
+        }
+    }
+
     fn completion(&mut self, params: lsp::CompletionParams) -> (Json, Feedback) {
         let path = super::path(&params.text_document_position.text_document.uri);
 
@@ -319,9 +558,114 @@ where
         self.respond_with_engine(path, |engine| engine.action(params))
     }
 
-    fn cache_file_in_memory(&mut self, path: Utf8PathBuf, text: String) -> Feedback {
+    fn inlay_hint(&mut self, params: lsp::InlayHintParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.inlay_hints(params))
+    }
+
+    fn rename(&mut self, params: lsp::RenameParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.rename(params))
+    }
+
+    /// Prepares a rename, along with why what is at the position can't be
+    /// renamed if it can't, which is sent to the client as an error.
+    fn prepare_rename(
+        &mut self,
+        params: lsp::TextDocumentPositionParams,
+    ) -> (Json, Feedback, Option<CannotRename>) {
+        let path = super::path(&params.text_document.uri);
+        let mut cannot_rename = None;
+        let (payload, feedback) = self.respond_with_engine(path, |engine| {
+            let engine::Response {
+                result,
+                warnings,
+                compilation,
+            } = engine.prepare_rename(params);
+            let result = result.map(|prepared| match prepared {
+                Ok(prepared) => Some(prepared),
+                Err(reason) => {
+                    cannot_rename = Some(reason);
+                    None
+                }
+            });
+            engine::Response {
+                result,
+                warnings,
+                compilation,
+            }
+        });
+        (payload, feedback, cannot_rename)
+    }
+
+    fn references(&mut self, params: lsp::ReferenceParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.references(params))
+    }
+
+    fn signature_help(&mut self, params: lsp::SignatureHelpParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position_params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.signature_help(params))
+    }
+
+    fn semantic_tokens_full(&mut self, params: lsp::SemanticTokensParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.semantic_tokens_full(params))
+    }
+
+    fn semantic_tokens_range(
+        &mut self,
+        params: lsp::SemanticTokensRangeParams,
+    ) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.semantic_tokens_range(params))
+    }
+
+    /// Searches the definitions of every project open in the editor, with the
+    /// closest matches first.
+    fn workspace_symbol(&mut self, params: lsp::WorkspaceSymbolParams) -> (Json, Feedback) {
+        let mut found = self
+            .router
+            .projects()
+            .flat_map(|project| project.engine.workspace_symbols(&params.query))
+            .collect::<Vec<_>>();
+        found.sort_by(|(score, symbol), (other_score, other)| {
+            score
+                .cmp(other_score)
+                .then_with(|| symbol.name.cmp(&other.name))
+        });
+        let symbols = found.into_iter().map(|(_, symbol)| symbol).collect();
+        let response = lsp::WorkspaceSymbolResponse::Nested(symbols);
+        let json = serde_json::to_value(response).expect("response to json");
+        (json, Feedback::default())
+    }
+
+    fn open_file(&mut self, path: Utf8PathBuf, text: String) -> Feedback {
         self.project_changed(&path);
-        if let Err(error) = self.io.write_mem_cache(&path, &text) {
+        if let Err(error) = self.io.open_document(&path, text) {
+            return self.outside_of_project_feedback.error(error);
+        }
+        Feedback::none()
+    }
+
+    fn edit_file_in_memory(
+        &mut self,
+        path: Utf8PathBuf,
+        changes: Vec<lsp::TextDocumentContentChangeEvent>,
+    ) -> Feedback {
+        self.project_changed(&path);
+        if let Err(error) = self
+            .io
+            .edit_document(&path, changes, self.position_encoding)
+        {
+            return self.outside_of_project_feedback.error(error);
+        }
+        Feedback::none()
+    }
+
+    fn close_file(&mut self, path: Utf8PathBuf) -> Feedback {
+        self.project_changed(&path);
+        if let Err(error) = self.io.close_document(&path) {
             return self.outside_of_project_feedback.error(error);
         }
         Feedback::none()
@@ -336,15 +680,61 @@ where
     }
 
     fn watched_files_changed(&mut self, path: Utf8PathBuf) -> Feedback {
+        // The projects depending on a changed path dependency could need
+        // different packages, so they are loaded again too.
+        for project_path in self.router.projects_depending_on(&path) {
+            self.router.delete_engine_for_path(&project_path);
+            _ = self.changed_projects.insert(project_path);
+        }
         self.router.delete_engine_for_path(&path);
         Feedback::none()
     }
 
+    fn workspace_folders_removed(&mut self, paths: Vec<Utf8PathBuf>) -> Feedback {
+        for path in paths {
+            self.router.delete_engines_in(&path);
+            self.changed_projects
+                .retain(|project_path| !project_path.starts_with(&path));
+        }
+        Feedback::none()
+    }
+
+    fn configuration_changed(&mut self, settings: Json) -> Feedback {
+        let configuration = Configuration::from_json(&settings);
+        let target_changed = configuration.target != self.configuration().target;
+        *self.configuration.write().expect("configuration lock") = configuration;
+
+        // The compiler of an engine is for a single target, so the projects
+        // are analysed again from scratch for the new one.
+        if target_changed {
+            self.changed_projects
+                .extend(self.router.delete_all_engines());
+        }
+        Feedback::none()
+    }
+
+    fn configuration(&self) -> Configuration {
+        self.configuration
+            .read()
+            .expect("configuration lock")
+            .clone()
+    }
+
     fn compile_please(&mut self) -> Feedback {
         let mut accumulator = Feedback::none();
         let projects = std::mem::take(&mut self.changed_projects);
         for path in projects {
-            let (_, feedback) = self.respond_with_engine(path, |e| e.compile_please());
+            let mut cancelled = false;
+            let (_, feedback) = self.respond_with_engine(path.clone(), |engine| {
+                let response = engine.compile_please();
+                cancelled = engine.is_cancelled();
+                response
+            });
+            // A compilation cancelled by an edit is done again the next time
+            // compilation is asked for.
+            if cancelled {
+                _ = self.changed_projects.insert(path);
+            }
             accumulator.append_feedback(feedback);
         }
         accumulator
@@ -355,15 +745,26 @@ where
         if let Some(project_path) = project_path {
             _ = self.changed_projects.insert(project_path);
         }
+        // Projects are compiled again when the code of their path
+        // dependencies changes too.
+        self.changed_projects
+            .extend(self.router.projects_depending_on(path));
     }
 }
 
-fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializeParams {
+fn initialisation_handshake(
+    connection: &lsp_server::Connection,
+) -> (InitializeParams, PositionEncoding) {
+    let (id, initialise_params_json) = connection.initialize_start().expect("LSP initialize");
+    let initialise_params: InitializeParams =
+        serde_json::from_value(initialise_params_json).expect("LSP InitializeParams from json");
+    let position_encoding = negotiate_position_encoding(&initialise_params);
+
     let server_capabilities = lsp::ServerCapabilities {
         text_document_sync: Some(lsp::TextDocumentSyncCapability::Options(
             lsp::TextDocumentSyncOptions {
                 open_close: Some(true),
-                change: Some(lsp::TextDocumentSyncKind::FULL),
+                change: Some(lsp::TextDocumentSyncKind::INCREMENTAL),
                 will_save: None,
                 will_save_wait_until: None,
                 save: Some(lsp::TextDocumentSyncSaveOptions::SaveOptions(
@@ -377,108 +778,125 @@ fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializePa
         hover_provider: Some(HoverProviderCapability::Simple(true)),
         completion_provider: Some(lsp::CompletionOptions {
             resolve_provider: None,
-            trigger_characters: None,
+            trigger_characters: Some(vec![".".into()]),
             all_commit_characters: None,
             work_done_progress_options: lsp::WorkDoneProgressOptions {
                 work_done_progress: None,
             },
             completion_item: None,
         }),
-        signature_help_provider: None,
+        signature_help_provider: Some(lsp::SignatureHelpOptions {
+            trigger_characters: Some(vec!["(".into(), ",".into()]),
+            retrigger_characters: None,
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
         definition_provider: Some(lsp::OneOf::Left(true)),
-        type_definition_provider: None,
-        implementation_provider: None,
-        references_provider: None,
-        document_highlight_provider: None,
+        type_definition_provider: Some(lsp::TypeDefinitionProviderCapability::Simple(true)),
+        implementation_provider: Some(lsp::ImplementationProviderCapability::Simple(true)),
+        references_provider: Some(lsp::OneOf::Left(true)),
+        document_highlight_provider: Some(lsp::OneOf::Left(true)),
         document_symbol_provider: None,
-        workspace_symbol_provider: None,
+        workspace_symbol_provider: Some(lsp::OneOf::Left(true)),
         code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
-        code_lens_provider: None,
+        code_lens_provider: Some(lsp::CodeLensOptions {
+            resolve_provider: Some(false),
+        }),
         document_formatting_provider: Some(lsp::OneOf::Left(true)),
-        document_range_formatting_provider: None,
+        document_range_formatting_provider: Some(lsp::OneOf::Left(true)),
         document_on_type_formatting_provider: None,
-        rename_provider: None,
-        document_link_provider: None,
+        rename_provider: Some(lsp::OneOf::Right(lsp::RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        })),
+        document_link_provider: Some(lsp::DocumentLinkOptions {
+            resolve_provider: Some(false),
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
         color_provider: None,
-        folding_range_provider: None,
+        folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
         declaration_provider: None,
-        execute_command_provider: None,
-        workspace: None,
-        call_hierarchy_provider: None,
-        semantic_tokens_provider: None,
+        execute_command_provider: Some(lsp::ExecuteCommandOptions {
+            commands: vec![RUN_COMMAND.into()],
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
+        workspace: Some(lsp::WorkspaceServerCapabilities {
+            workspace_folders: Some(lsp::WorkspaceFoldersServerCapabilities {
+                supported: Some(true),
+                change_notifications: Some(lsp::OneOf::Left(true)),
+            }),
+            file_operations: None,
+        }),
+        call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+        semantic_tokens_provider: Some(
+            lsp::SemanticTokensOptions {
+                work_done_progress_options: lsp::WorkDoneProgressOptions {
+                    work_done_progress: None,
+                },
+                legend: semantic_tokens::legend(),
+                range: Some(true),
+                full: Some(lsp::SemanticTokensFullOptions::Bool(true)),
+            }
+            .into(),
+        ),
         moniker_provider: None,
         linked_editing_range_provider: None,
         experimental: None,
-        position_encoding: None,
+        position_encoding: Some(match position_encoding {
+            PositionEncoding::Utf8 => lsp::PositionEncodingKind::UTF8,
+            PositionEncoding::Utf16 => lsp::PositionEncodingKind::UTF16,
+            PositionEncoding::Utf32 => lsp::PositionEncodingKind::UTF32,
+        }),
         inline_value_provider: None,
-        inlay_hint_provider: None,
-        diagnostic_provider: None,
+        inlay_hint_provider: Some(lsp::OneOf::Left(true)),
+        diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+            lsp::DiagnosticOptions {
+                identifier: Some("gleam".into()),
+                inter_file_dependencies: true,
+                workspace_diagnostics: false,
+                work_done_progress_options: lsp::WorkDoneProgressOptions {
+                    work_done_progress: None,
+                },
+            },
+        )),
+    };
+    let initialise_result = lsp::InitializeResult {
+        capabilities: server_capabilities,
+        server_info: None,
     };
-    let server_capabilities_json =
-        serde_json::to_value(server_capabilities).expect("server_capabilities_serde");
-    let initialise_params_json = connection
-        .initialize(server_capabilities_json)
+    let initialise_result_json =
+        serde_json::to_value(initialise_result).expect("server_capabilities_serde");
+    connection
+        .initialize_finish(id, initialise_result_json)
         .expect("LSP initialize");
-    let initialise_params: InitializeParams =
-        serde_json::from_value(initialise_params_json).expect("LSP InitializeParams from json");
-    initialise_params
+    (initialise_params, position_encoding)
 }
 
-fn diagnostic_to_lsp(diagnostic: Diagnostic) -> Vec<lsp::Diagnostic> {
-    let severity = match diagnostic.level {
-        Level::Error => lsp::DiagnosticSeverity::ERROR,
-        Level::Warning => lsp::DiagnosticSeverity::WARNING,
-    };
-    let hint = diagnostic.hint;
-    let mut text = diagnostic.title;
-
-    if let Some(label) = diagnostic
-        .location
+/// Picks the first of the position encodings supported by the client, which
+/// are listed in order of preference. Clients that don't list any only
+/// support UTF-16.
+fn negotiate_position_encoding(params: &InitializeParams) -> PositionEncoding {
+    params
+        .capabilities
+        .general
         .as_ref()
-        .and_then(|location| location.label.text.as_deref())
-    {
-        text.push_str("\n\n");
-        text.push_str(label);
-        if !label.ends_with(['.', '?']) {
-            text.push('.');
-        }
-    }
-
-    if !diagnostic.text.is_empty() {
-        text.push_str("\n\n");
-        text.push_str(&diagnostic.text);
-    }
-
-    // TODO: Redesign the diagnostic type so that we can be sure there is always
-    // a location. Locationless diagnostics would be handled separately.
-    let location = diagnostic
-        .location
-        .expect("Diagnostic given to LSP without location");
-    let line_numbers = LineNumbers::new(&location.src);
-
-    let main = lsp::Diagnostic {
-        range: src_span_to_lsp_range(location.label.span, &line_numbers),
-        severity: Some(severity),
-        code: None,
-        code_description: None,
-        source: None,
-        message: text,
-        related_information: None,
-        tags: None,
-        data: None,
-    };
-
-    match hint {
-        Some(hint) => {
-            let hint = lsp::Diagnostic {
-                severity: Some(lsp::DiagnosticSeverity::HINT),
-                message: hint,
-                ..main.clone()
-            };
-            vec![main, hint]
-        }
-        None => vec![main],
-    }
+        .and_then(|general| general.position_encodings.as_ref())
+        .into_iter()
+        .flatten()
+        .find_map(|kind| match kind.as_str() {
+            "utf-8" => Some(PositionEncoding::Utf8),
+            "utf-16" => Some(PositionEncoding::Utf16),
+            "utf-32" => Some(PositionEncoding::Utf32),
+            _ => None,
+        })
+        .unwrap_or_default()
 }
 
 fn path_to_uri(path: Utf8PathBuf) -> Url {
@@ -0,0 +1,164 @@
+//! Signature help, showing the parameters of the function being called while
+//! the arguments of the call are written.
+
+use std::{collections::HashMap, sync::Arc};
+
+use ecow::{eco_format, EcoString};
+use itertools::Itertools;
+use lsp_types as lsp;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        CallArg, SrcSpan, TypedExpr, TypedModule,
+    },
+    type_::{pretty::Printer, FieldMap, ModuleInterface, Type},
+};
+
+/// A call with the position inside of its brackets.
+#[derive(Debug, Clone, Copy)]
+pub struct Call<'a> {
+    pub fun: &'a TypedExpr,
+    pub args: &'a [CallArg<TypedExpr>],
+}
+
+/// The innermost call the position is inside of the brackets of.
+pub fn find_call(module: &TypedModule, byte_index: u32) -> Option<Call<'_>> {
+    let mut finder = CallFinder {
+        byte_index,
+        call: None,
+    };
+    finder.visit_typed_module(module);
+    finder.call
+}
+
+struct CallFinder<'a> {
+    byte_index: u32,
+    call: Option<Call<'a>>,
+}
+
+impl<'ast> Visit<'ast> for CallFinder<'ast> {
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'ast SrcSpan,
+        typ: &'ast Arc<Type>,
+        fun: &'ast TypedExpr,
+        args: &'ast [CallArg<TypedExpr>],
+    ) {
+        // Calls nested in the arguments are visited afterwards, so the
+        // innermost one is found last.
+        if fun.location().end < self.byte_index && self.byte_index < location.end {
+            self.call = Some(Call { fun, args });
+        }
+        visit::visit_typed_expr_call(self, location, typ, fun, args);
+    }
+}
+
+/// The signature of the function being called, such as
+/// `add(Int, to: Int) -> Int`, with the argument the position is in as the
+/// active parameter. The labels of the parameters come from the field map of
+/// the function, if it has one.
+pub fn signature_help(
+    call: Call<'_>,
+    field_map: Option<&FieldMap>,
+    byte_index: u32,
+) -> Option<lsp::SignatureHelp> {
+    let (arg_types, return_type) = call.fun.type_().fn_types()?;
+    let labels = field_map
+        .map(|field_map| {
+            field_map
+                .fields
+                .iter()
+                .map(|(label, index)| (*index as usize, label))
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+
+    // The types of all the parameters are printed with the same printer so
+    // type variables are named consistently.
+    let mut printer = Printer::new();
+    let mut label = format!("{}(", name(call.fun));
+    let mut parameters = Vec::with_capacity(arg_types.len());
+    for (index, type_) in arg_types.iter().enumerate() {
+        if index > 0 {
+            label.push_str(", ");
+        }
+        let start = utf16_length(&label);
+        if let Some(arg_label) = labels.get(&index) {
+            label.push_str(arg_label);
+            label.push_str(": ");
+        }
+        label.push_str(&printer.pretty_print(type_, 0));
+        parameters.push(lsp::ParameterInformation {
+            label: lsp::ParameterLabel::LabelOffsets([start, utf16_length(&label)]),
+            documentation: None,
+        });
+    }
+    label.push_str(") -> ");
+    label.push_str(&printer.pretty_print(&return_type, 0));
+
+    // The arguments of a typed call are in the order of the parameters, but
+    // labelled arguments can be written in any order.
+    let active_parameter = call
+        .args
+        .iter()
+        .enumerate()
+        .filter(|(_, arg)| !arg.implicit)
+        .sorted_by_key(|(_, arg)| arg.location.start)
+        .find(|(_, arg)| byte_index <= arg.location.end)
+        .map(|(index, _)| index as u32);
+
+    let documentation = call.fun.get_documentation().map(|documentation| {
+        lsp::Documentation::MarkupContent(lsp::MarkupContent {
+            kind: lsp::MarkupKind::Markdown,
+            value: documentation.into(),
+        })
+    });
+
+    Some(lsp::SignatureHelp {
+        signatures: vec![lsp::SignatureInformation {
+            label,
+            documentation,
+            parameters: Some(parameters),
+            active_parameter: None,
+        }],
+        active_signature: Some(0),
+        active_parameter,
+    })
+}
+
+/// The labels of the parameters of a called function. Values selected from a
+/// module don't have them, so they are found in the interface of the module.
+pub fn field_map<'a>(
+    fun: &'a TypedExpr,
+    modules: &'a im::HashMap<EcoString, ModuleInterface>,
+) -> Option<&'a FieldMap> {
+    match fun {
+        TypedExpr::Var { constructor, .. } => constructor.field_map(),
+        TypedExpr::ModuleSelect {
+            module_name, label, ..
+        } => modules
+            .get(module_name)
+            .and_then(|module| module.values.get(label))
+            .and_then(|value| value.field_map()),
+        _ => None,
+    }
+}
+
+/// The name a function is called by, with the module it is qualified with.
+fn name(fun: &TypedExpr) -> EcoString {
+    match fun {
+        TypedExpr::Var { name, .. } => name.clone(),
+        TypedExpr::ModuleSelect {
+            module_alias,
+            label,
+            ..
+        } => eco_format!("{module_alias}.{label}"),
+        _ => "fn".into(),
+    }
+}
+
+/// Parameter labels are offsets in UTF-16 code units.
+fn utf16_length(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
@@ -1,10 +1,11 @@
-use crate::line_numbers::LineNumbers;
+use crate::line_numbers::{LineNumbers, PositionEncoding};
 use lsp_types::{
     CodeActionContext, CodeActionParams, PartialResultParams, Position, Range,
     TextDocumentIdentifier, Url, WorkDoneProgressParams, WorkspaceEdit,
 };
 
 use super::*;
+use crate::config::{LintLevel, LintsConfig};
 
 fn remove_unused_action(src: &str, line: u32) -> String {
     let io = LanguageServerTestIO::new();
@@ -22,6 +23,125 @@ fn remove_unused_action(src: &str, line: u32) -> String {
     _ = io.src_module("app", src);
     engine.compile_please().result.expect("compiled");
 
+    apply_action(&mut engine, src, line, "Remove unused imports")
+}
+
+fn single_clause_case_action(src: &str, line: u32) -> String {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+    engine.compiler.project_compiler.config.lints =
+        LintsConfig::from([("single_clause_case", LintLevel::Warn)]);
+
+    _ = io.src_module("app", src);
+    engine.compile_please().result.expect("compiled");
+
+    apply_action(&mut engine, src, line, "Rewrite as `let`")
+}
+
+fn missing_patterns_action(src: &str, line: u32) -> String {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    _ = io.src_module("app", src);
+    assert!(engine.compile_please().result.is_err());
+
+    apply_action(&mut engine, src, line, "Add missing patterns")
+}
+
+fn generate_function_action(src: &str, line: u32, name: &str) -> String {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    _ = io.src_module("app", src);
+    assert!(engine.compile_please().result.is_err());
+
+    let title = format!("Generate function `{name}`");
+    apply_action(&mut engine, src, line, &title)
+}
+
+fn qualified_value_action(src: &str, position: Position, title: &str) -> Option<String> {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    _ = io.src_module(
+        "wibble",
+        "pub fn wobble() { 1 }\npub type Wubble { Wubble }",
+    );
+    _ = io.src_module("app", src);
+    engine.compile_please().result.expect("compiled");
+
+    apply_action_in_range(&mut engine, src, Range::new(position, position), title)
+}
+
+fn extract_action(src: &str, range: Range, title: &str) -> Option<String> {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    _ = io.src_module("app", src);
+    engine.compile_please().result.expect("compiled");
+
+    apply_action_in_range(&mut engine, src, range, title)
+}
+
+fn annotations_action(src: &str, position: Position, title: &str) -> Option<String> {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    _ = io.src_module("wibble", "pub type Wubble { Wubble }");
+    _ = io.src_module("app", src);
+    engine.compile_please().result.expect("compiled");
+
+    apply_action_in_range(&mut engine, src, Range::new(position, position), title)
+}
+
+fn use_callback_action(src: &str, position: Position, title: &str) -> Option<String> {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    _ = io.src_module("app", src);
+    engine.compile_please().result.expect("compiled");
+
+    apply_action_in_range(&mut engine, src, Range::new(position, position), title)
+}
+
+fn missing_labels_action(src: &str, line: u32) -> String {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    _ = io.src_module("app", src);
+    assert!(engine.compile_please().result.is_err());
+
+    apply_action(&mut engine, src, line, "Add missing labelled arguments")
+}
+
+fn label_arguments_action(src: &str, position: Position) -> Option<String> {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    _ = io.src_module("app", src);
+    engine.compile_please().result.expect("compiled");
+
+    let range = Range::new(position, position);
+    apply_action_in_range(&mut engine, src, range, "Add labels to arguments")
+}
+
+fn apply_action(
+    engine: &mut LanguageServerEngine<LanguageServerTestIO, LanguageServerTestIO>,
+    src: &str,
+    line: u32,
+    title: &str,
+) -> String {
+    let range = Range::new(Position::new(0, 0), Position::new(line + 1, 0));
+    apply_action_in_range(engine, src, range, title)
+        .unwrap_or_else(|| panic!("No code action produced by the engine"))
+}
+
+fn apply_action_in_range(
+    engine: &mut LanguageServerEngine<LanguageServerTestIO, LanguageServerTestIO>,
+    src: &str,
+    range: Range,
+    title: &str,
+) -> Option<String> {
     // create the code action request
     let path = Utf8PathBuf::from(if cfg!(target_family = "windows") {
         r"\\?\C:\src\app.gleam"
@@ -38,7 +158,7 @@ fn remove_unused_action(src: &str, line: u32) -> String {
             only: None,
             trigger_kind: None,
         },
-        range: Range::new(Position::new(0, 0), Position::new(line + 1, 0)),
+        range,
         work_done_progress_params: WorkDoneProgressParams {
             work_done_token: None,
         },
@@ -47,17 +167,13 @@ fn remove_unused_action(src: &str, line: u32) -> String {
         },
     };
 
-    // find the remove unused action response
-    let response = engine.action(params).result.unwrap().and_then(|actions| {
-        actions
-            .into_iter()
-            .find(|action| action.title == "Remove unused imports")
-    });
-    if let Some(action) = response {
-        apply_code_action(src, &url, &action)
-    } else {
-        panic!("No code action produced by the engine")
-    }
+    // find the action response
+    let response = engine
+        .action(params)
+        .result
+        .unwrap()
+        .and_then(|actions| actions.into_iter().find(|action| action.title == title));
+    response.map(|action| apply_code_action(src, &url, &action))
 }
 
 fn apply_code_action(src: &str, url: &Url, action: &lsp_types::CodeAction) -> String {
@@ -78,18 +194,27 @@ fn apply_code_edit(
 ) -> String {
     let mut result = src.to_string();
     let line_numbers = LineNumbers::new(src);
-    let mut offset = 0;
+    // How much longer the result is than the source before the next edit
+    let mut offset: i64 = 0;
     for (change_url, change) in changes {
         if url != change_url {
             panic!("Unknown url {}", change_url)
         }
         for edit in change {
-            let start =
-                line_numbers.byte_index(edit.range.start.line, edit.range.start.character) - offset;
-            let end =
-                line_numbers.byte_index(edit.range.end.line, edit.range.end.character) - offset;
+            let start = line_numbers.byte_index(
+                edit.range.start.line,
+                edit.range.start.character,
+                PositionEncoding::Utf16,
+            ) as i64
+                + offset;
+            let end = line_numbers.byte_index(
+                edit.range.end.line,
+                edit.range.end.character,
+                PositionEncoding::Utf16,
+            ) as i64
+                + offset;
             let range = (start as usize)..(end as usize);
-            offset += end - start;
+            offset += edit.new_text.len() as i64 - (end - start);
             result.replace_range(range, &edit.new_text);
         }
     }
@@ -225,3 +350,746 @@ pub fn main() {
     assert_eq!(remove_unused_action(code), expected.to_string())
 }
 */
+
+#[test]
+fn test_rewrite_single_clause_case() {
+    let code = "
+pub fn main(pair) {
+  let total = 1
+  case pair {
+    #(first, second) as both -> #(first + second + total, both)
+  }
+}
+";
+    let expected = "
+pub fn main(pair) {
+  let total = 1
+  let #(first, second) as both = pair
+  #(first + second + total, both)
+}
+";
+    assert_eq!(single_clause_case_action(code, 4), expected.to_string())
+}
+
+#[test]
+fn test_rewrite_single_clause_case_with_block_body() {
+    let code = "
+pub fn main(x) {
+  case x {
+    #(a, b, _) -> {
+      let c = a + b
+      c * 2
+    }
+  }
+}
+";
+    let expected = "
+pub fn main(x) {
+  let #(a, b, _) = x
+  let c = a + b
+  c * 2
+}
+";
+    assert_eq!(single_clause_case_action(code, 3), expected.to_string())
+}
+
+#[test]
+fn test_add_missing_patterns() {
+    let code = "
+pub type Wibble {
+  Wibble
+  Wobble(Int)
+  Wubble(Int, String)
+}
+
+pub fn main(x) {
+  case x {
+    Wibble -> 1
+  }
+}
+";
+    let expected = "
+pub type Wibble {
+  Wibble
+  Wobble(Int)
+  Wubble(Int, String)
+}
+
+pub fn main(x) {
+  case x {
+    Wibble -> 1
+    Wobble(_) -> todo
+    Wubble(_, _) -> todo
+  }
+}
+";
+    assert_eq!(missing_patterns_action(code, 9), expected.to_string())
+}
+
+#[test]
+fn test_add_missing_patterns_to_nested_case() {
+    let code = "
+pub fn main(x) {
+  let y = {
+    case x {
+      True -> 1
+    }
+  }
+  y
+}
+";
+    let expected = "
+pub fn main(x) {
+  let y = {
+    case x {
+      True -> 1
+      False -> todo
+    }
+  }
+  y
+}
+";
+    assert_eq!(missing_patterns_action(code, 4), expected.to_string())
+}
+
+#[test]
+fn test_generate_function() {
+    let code = "
+pub fn main() {
+  let x = 1
+  wibble(x, 2.0, \"three\")
+}
+";
+    let expected = "
+pub fn main() {
+  let x = 1
+  wibble(x, 2.0, \"three\")
+}
+
+fn wibble(x, arg_2: Float, arg_3: String) {
+  todo
+}
+";
+    assert_eq!(
+        generate_function_action(code, 3, "wibble"),
+        expected.to_string()
+    )
+}
+
+#[test]
+fn test_generate_function_with_labels() {
+    let code = "
+pub fn main() {
+  wibble(1, to: 2)
+}";
+    let expected = "
+pub fn main() {
+  wibble(1, to: 2)
+}
+
+fn wibble(arg_1: Int, to to: Int) {
+  todo
+}
+";
+    assert_eq!(
+        generate_function_action(code, 2, "wibble"),
+        expected.to_string()
+    )
+}
+
+#[test]
+fn test_generate_function_from_pipe() {
+    let code = "
+pub fn main() {
+  [1, 2]
+  |> wibble(1)
+}
+";
+    let expected = "
+pub fn main() {
+  [1, 2]
+  |> wibble(1)
+}
+
+fn wibble(arg_1, arg_2: Int) {
+  todo
+}
+";
+    assert_eq!(
+        generate_function_action(code, 3, "wibble"),
+        expected.to_string()
+    )
+}
+
+#[test]
+fn test_import_value_unqualified() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.wobble() + wibble.wobble()
+}
+";
+    let expected = "
+import wibble.{wobble}
+
+pub fn main() {
+  wobble() + wobble()
+}
+";
+    assert_eq!(
+        qualified_value_action(code, Position::new(4, 10), "Import `wobble` unqualified"),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_import_value_unqualified_with_other_unqualified_imports() {
+    let code = "
+import wibble.{type Wubble}
+
+pub fn main() -> Int {
+  wibble.wobble()
+}
+";
+    let expected = "
+import wibble.{type Wubble, wobble}
+
+pub fn main() -> Int {
+  wobble()
+}
+";
+    assert_eq!(
+        qualified_value_action(code, Position::new(4, 10), "Import `wobble` unqualified"),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_import_value_unqualified_is_not_offered_when_name_is_taken() {
+    let code = "
+import wibble
+
+pub fn main() {
+  let wobble = 1
+  wibble.wobble() + wobble
+}
+";
+    assert_eq!(
+        qualified_value_action(code, Position::new(5, 10), "Import `wobble` unqualified"),
+        None
+    )
+}
+
+#[test]
+fn test_qualify_value() {
+    let code = "
+import wibble.{type Wubble, wobble}
+
+pub fn main() -> Int {
+  wobble() + wobble()
+}
+";
+    let expected = "
+import wibble.{type Wubble}
+
+pub fn main() -> Int {
+  wibble.wobble() + wibble.wobble()
+}
+";
+    assert_eq!(
+        qualified_value_action(
+            code,
+            Position::new(4, 3),
+            "Qualify `wobble` with its module"
+        ),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_qualify_only_unqualified_value() {
+    let code = "
+import wibble.{wobble}
+
+pub fn main() {
+  wobble()
+}
+";
+    let expected = "
+import wibble
+
+pub fn main() {
+  wibble.wobble()
+}
+";
+    assert_eq!(
+        qualified_value_action(
+            code,
+            Position::new(4, 3),
+            "Qualify `wobble` with its module"
+        ),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_extract_to_let_binding() {
+    let code = "
+pub fn main(x) {
+  let y = x * 2
+  y + x * 3
+}
+";
+    let expected = "
+pub fn main(x) {
+  let y = x * 2
+  let value = x * 3
+  y + value
+}
+";
+    let range = Range::new(Position::new(3, 6), Position::new(3, 11));
+    assert_eq!(
+        extract_action(code, range, "Extract to let binding"),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_extract_to_let_binding_avoids_taken_names() {
+    let code = "
+pub fn main(value) {
+  value + 1
+}
+";
+    let expected = "
+pub fn main(value) {
+  let value_2 = value + 1
+  value_2
+}
+";
+    let range = Range::new(Position::new(2, 2), Position::new(2, 11));
+    assert_eq!(
+        extract_action(code, range, "Extract to let binding"),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_extract_to_let_binding_is_not_offered_for_clause_variables() {
+    let code = "
+pub fn main(x) {
+  case x {
+    [first, ..] -> first + 1
+    [] -> 0
+  }
+}
+";
+    let range = Range::new(Position::new(3, 19), Position::new(3, 28));
+    assert_eq!(extract_action(code, range, "Extract to let binding"), None)
+}
+
+#[test]
+fn test_extract_to_let_binding_is_not_offered_for_clause_bodies() {
+    let code = "
+pub fn main(x) {
+  case x {
+    [] -> 0
+    _ -> 1 / 0
+  }
+}
+";
+    let range = Range::new(Position::new(4, 9), Position::new(4, 14));
+    assert_eq!(extract_action(code, range, "Extract to let binding"), None)
+}
+
+#[test]
+fn test_extract_to_let_binding_in_clause_block() {
+    let code = "
+pub fn main(x) {
+  case x {
+    0 -> 0
+    _ -> {
+      let y = 1
+      y + x * 2
+    }
+  }
+}
+";
+    let expected = "
+pub fn main(x) {
+  case x {
+    0 -> 0
+    _ -> {
+      let y = 1
+      let value = x * 2
+      y + value
+    }
+  }
+}
+";
+    let range = Range::new(Position::new(6, 10), Position::new(6, 15));
+    assert_eq!(
+        extract_action(code, range, "Extract to let binding"),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_extract_to_let_binding_is_not_offered_for_right_of_and() {
+    let code = "
+pub fn main(x) {
+  x > 0 && 10 / x > 1
+}
+";
+    let range = Range::new(Position::new(2, 11), Position::new(2, 17));
+    assert_eq!(extract_action(code, range, "Extract to let binding"), None)
+}
+
+#[test]
+fn test_extract_to_function() {
+    let code = "
+pub fn main(x) {
+  case x {
+    [first, ..] -> first + 1
+    [] -> 0
+  }
+}
+";
+    let expected = "
+pub fn main(x) {
+  case x {
+    [first, ..] -> function(first)
+    [] -> 0
+  }
+}
+
+fn function(first) {
+  first + 1
+}
+";
+    let range = Range::new(Position::new(3, 19), Position::new(3, 28));
+    assert_eq!(
+        extract_action(code, range, "Extract to function"),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_extract_multiline_expression_to_function() {
+    let code = "
+pub fn main(x, y) {
+  let z = case x {
+    True -> y
+    False -> 0
+  }
+  z
+}
+";
+    let expected = "
+pub fn main(x, y) {
+  let z = function(x, y)
+  z
+}
+
+fn function(x, y) {
+  case x {
+    True -> y
+    False -> 0
+  }
+}
+";
+    let range = Range::new(Position::new(2, 10), Position::new(5, 3));
+    assert_eq!(
+        extract_action(code, range, "Extract to function"),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_extract_to_function_uses_configured_indent_width() {
+    let code = "
+pub fn main(x) {
+    x + 1
+}
+";
+    let expected = "
+pub fn main(x) {
+    function(x)
+}
+
+fn function(x) {
+    x + 1
+}
+";
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+    engine.compiler.project_compiler.config.format.indent_width = Some(4);
+
+    _ = io.src_module("app", code);
+    engine.compile_please().result.expect("compiled");
+
+    let range = Range::new(Position::new(2, 4), Position::new(2, 9));
+    assert_eq!(
+        apply_action_in_range(&mut engine, code, range, "Extract to function"),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_add_annotations() {
+    let code = "
+pub fn add(x, y) {
+  x + y
+}
+";
+    let expected = "
+pub fn add(x: Int, y: Int) -> Int {
+  x + y
+}
+";
+    assert_eq!(
+        annotations_action(code, Position::new(1, 8), "Add type annotations"),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_add_annotations_qualifies_imported_types() {
+    let code = "
+import wibble as w
+
+pub fn wubble(x) {
+  [x, w.Wubble]
+}
+";
+    let expected = "
+import wibble as w
+
+pub fn wubble(x: w.Wubble) -> List(w.Wubble) {
+  [x, w.Wubble]
+}
+";
+    assert_eq!(
+        annotations_action(code, Position::new(3, 8), "Add type annotations"),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_add_annotations_keeps_type_variable_names() {
+    let code = "
+pub fn pair(x: a, y) {
+  #(x, y)
+}
+";
+    let expected = "
+pub fn pair(x: a, y: b) -> #(a, b) {
+  #(x, y)
+}
+";
+    assert_eq!(
+        annotations_action(code, Position::new(1, 8), "Add type annotations"),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_add_annotations_to_all_public_functions() {
+    let code = "
+pub fn one() {
+  1
+}
+
+fn two() {
+  2
+}
+
+pub fn three(x) {
+  x <> \"3\"
+}
+";
+    let expected = "
+pub fn one() -> Int {
+  1
+}
+
+fn two() {
+  2
+}
+
+pub fn three(x: String) -> String {
+  x <> \"3\"
+}
+";
+    assert_eq!(
+        annotations_action(
+            code,
+            Position::new(1, 8),
+            "Add type annotations to all public functions"
+        ),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_convert_use_to_callback() {
+    let code = "
+pub fn main() {
+  use x <- wobble(1)
+  x + 1
+}
+
+fn wobble(x, f) {
+  f(x)
+}
+";
+    let expected = "
+pub fn main() {
+  wobble(1, fn(x) { x + 1 })
+}
+
+fn wobble(x, f) {
+  f(x)
+}
+";
+    assert_eq!(
+        use_callback_action(code, Position::new(2, 4), "Convert to explicit callback"),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_convert_callback_to_use() {
+    let code = "
+pub fn main() {
+  wobble(1, fn(x) {
+    let y = x + 1
+    y
+  })
+}
+
+fn wobble(x, f) {
+  f(x)
+}
+";
+    let expected = "
+pub fn main() {
+  use x <- wobble(1)
+  let y = x + 1
+  y
+}
+
+fn wobble(x, f) {
+  f(x)
+}
+";
+    assert_eq!(
+        use_callback_action(code, Position::new(2, 2), "Convert to `use` expression"),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_convert_callback_to_use_is_not_offered_before_other_statements() {
+    let code = "
+pub fn main() {
+  wobble(1, fn(x) { x })
+  2
+}
+
+fn wobble(x, f) {
+  f(x)
+}
+";
+    assert_eq!(
+        use_callback_action(code, Position::new(2, 2), "Convert to `use` expression"),
+        None
+    )
+}
+
+#[test]
+fn test_add_missing_labelled_arguments() {
+    let code = "
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+pub fn main() {
+  Person()
+}
+";
+    let expected = "
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+pub fn main() {
+  Person(name: todo, age: todo)
+}
+";
+    assert_eq!(missing_labels_action(code, 6), expected);
+}
+
+#[test]
+fn test_add_missing_labelled_arguments_after_given_arguments() {
+    let code = "
+pub fn greet(greeting: String, name name: String, times times: Int) {
+  todo
+}
+
+pub fn main() {
+  greet(\"Hello\", times: 2)
+}
+";
+    let expected = "
+pub fn greet(greeting: String, name name: String, times times: Int) {
+  todo
+}
+
+pub fn main() {
+  greet(\"Hello\", times: 2, name: todo)
+}
+";
+    assert_eq!(missing_labels_action(code, 6), expected);
+}
+
+#[test]
+fn test_add_labels_to_arguments() {
+    let code = "
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+pub fn main() {
+  Person(\"Lucy\", 9)
+}
+";
+    let expected = "
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+pub fn main() {
+  Person(name: \"Lucy\", age: 9)
+}
+";
+    assert_eq!(
+        label_arguments_action(code, Position::new(6, 10)),
+        Some(expected.to_string())
+    )
+}
+
+#[test]
+fn test_add_labels_is_not_offered_when_all_arguments_are_labelled() {
+    let code = "
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+pub fn main() {
+  Person(age: 9, name: \"Lucy\")
+}
+";
+    assert_eq!(label_arguments_action(code, Position::new(6, 10)), None)
+}
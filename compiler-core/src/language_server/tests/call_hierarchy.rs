@@ -0,0 +1,283 @@
+use lsp_types::{
+    CallHierarchyIncomingCallsParams, CallHierarchyItem, CallHierarchyOutgoingCallsParams,
+    CallHierarchyPrepareParams, Position, Range,
+};
+
+use super::*;
+
+fn prepare(tester: TestProject<'_>, position: Position) -> Option<Vec<CallHierarchyItem>> {
+    tester.at(position, |engine, param, _| {
+        let params = CallHierarchyPrepareParams {
+            text_document_position_params: param,
+            work_done_progress_params: Default::default(),
+        };
+        engine.prepare_call_hierarchy(params).result.unwrap()
+    })
+}
+
+/// The module and name of each function calling the function at the
+/// position, with where it calls it.
+fn incoming(tester: TestProject<'_>, position: Position) -> Vec<(String, String, Vec<Range>)> {
+    tester.at(position, |engine, param, _| {
+        let params = CallHierarchyPrepareParams {
+            text_document_position_params: param,
+            work_done_progress_params: Default::default(),
+        };
+        let item = engine
+            .prepare_call_hierarchy(params)
+            .result
+            .unwrap()
+            .unwrap()
+            .remove(0);
+        let params = CallHierarchyIncomingCallsParams {
+            item,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine
+            .incoming_calls(params)
+            .result
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|call| (call.from.detail.unwrap(), call.from.name, call.from_ranges))
+            .collect()
+    })
+}
+
+/// The module and name of each function the function at the position calls,
+/// with where it calls it.
+fn outgoing(tester: TestProject<'_>, position: Position) -> Vec<(String, String, Vec<Range>)> {
+    tester.at(position, |engine, param, _| {
+        let params = CallHierarchyPrepareParams {
+            text_document_position_params: param,
+            work_done_progress_params: Default::default(),
+        };
+        let item = engine
+            .prepare_call_hierarchy(params)
+            .result
+            .unwrap()
+            .unwrap()
+            .remove(0);
+        let params = CallHierarchyOutgoingCallsParams {
+            item,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine
+            .outgoing_calls(params)
+            .result
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|call| (call.to.detail.unwrap(), call.to.name, call.from_ranges))
+            .collect()
+    })
+}
+
+fn range(line: u32, start: u32, end: u32) -> Range {
+    Range::new(Position::new(line, start), Position::new(line, end))
+}
+
+#[test]
+fn prepare_on_function_definition() {
+    let code = "
+pub fn main() {
+  wibble()
+}
+
+fn wibble() {
+  1
+}
+";
+
+    let items = prepare(TestProject::for_source(code), Position::new(5, 4)).unwrap();
+    assert_eq!(items.len(), 1);
+    let item = items.first().unwrap();
+    assert_eq!(item.name, "wibble");
+    assert_eq!(item.detail.as_deref(), Some("app"));
+    assert_eq!(
+        item.range,
+        Range::new(Position::new(5, 0), Position::new(7, 1))
+    );
+    assert_eq!(item.selection_range, range(5, 3, 9));
+}
+
+#[test]
+fn prepare_on_call_of_imported_function() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.double(1)
+}
+";
+    let wibble = "pub fn double(x) { x * 2 }";
+
+    let items = prepare(
+        TestProject::for_source(code).add_module("wibble", wibble),
+        Position::new(4, 10),
+    )
+    .unwrap();
+    let item = items.first().unwrap();
+    assert_eq!(item.name, "double");
+    assert_eq!(item.detail.as_deref(), Some("wibble"));
+    assert_eq!(item.selection_range, range(0, 7, 13));
+}
+
+#[test]
+fn no_call_hierarchy_for_record_constructor() {
+    let code = "
+pub type Wibble {
+  Wibble(Int)
+}
+
+pub fn main() {
+  Wibble(1)
+}
+";
+
+    assert_eq!(
+        prepare(TestProject::for_source(code), Position::new(6, 2)),
+        None
+    );
+}
+
+#[test]
+fn no_call_hierarchy_for_dependency_function() {
+    let code = "
+import dep
+
+pub fn main() {
+  dep.wibble()
+}
+";
+
+    assert_eq!(
+        prepare(
+            TestProject::for_source(code).add_hex_module("dep", "pub fn wibble() { 1 }"),
+            Position::new(4, 7)
+        ),
+        None
+    );
+}
+
+#[test]
+fn incoming_calls_from_modules_of_the_project() {
+    let code = "
+import wibble.{double}
+
+pub fn main() {
+  double(1) + wibble.double(2)
+}
+
+fn other() {
+  double(3)
+}
+";
+    let wibble = "pub fn double(x) { x * 2 }
+
+pub fn quadruple(x) {
+  double(double(x))
+}";
+
+    assert_eq!(
+        incoming(
+            TestProject::for_source(code).add_module("wibble", wibble),
+            Position::new(4, 2)
+        ),
+        vec![
+            (
+                "app".into(),
+                "main".into(),
+                vec![range(4, 2, 8), range(4, 21, 27)]
+            ),
+            ("app".into(), "other".into(), vec![range(8, 2, 8)]),
+            (
+                "wibble".into(),
+                "quadruple".into(),
+                vec![range(3, 2, 8), range(3, 9, 15)]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn incoming_calls_ignore_constants() {
+    let code = "
+const callback = wibble
+
+pub fn main() {
+  wibble
+}
+
+fn wibble() {
+  1
+}
+";
+
+    assert_eq!(
+        incoming(TestProject::for_source(code), Position::new(7, 4)),
+        vec![("app".into(), "main".into(), vec![range(4, 2, 8)])]
+    );
+}
+
+#[test]
+fn outgoing_calls_to_modules_of_the_project() {
+    let code = "
+import wibble
+
+pub fn main() {
+  let x = wobble(1)
+  wibble.double(x) + wobble(2)
+}
+
+fn wobble(x) {
+  x
+}
+";
+    let wibble = "pub fn double(x) { x * 2 }";
+
+    assert_eq!(
+        outgoing(
+            TestProject::for_source(code).add_module("wibble", wibble),
+            Position::new(3, 8)
+        ),
+        vec![
+            (
+                "app".into(),
+                "wobble".into(),
+                vec![range(4, 10, 16), range(5, 21, 27)]
+            ),
+            ("wibble".into(), "double".into(), vec![range(5, 9, 15)]),
+        ]
+    );
+}
+
+#[test]
+fn outgoing_calls_skip_dependencies_and_constructors() {
+    let code = "
+import dep
+
+pub type Wibble {
+  Wibble(Int)
+}
+
+pub fn main() {
+  Wibble(dep.wibble())
+  wobble()
+}
+
+fn wobble() {
+  1
+}
+";
+
+    assert_eq!(
+        outgoing(
+            TestProject::for_source(code).add_hex_module("dep", "pub fn wibble() { 1 }"),
+            Position::new(7, 8)
+        ),
+        vec![("app".into(), "wobble".into(), vec![range(9, 2, 8)])]
+    );
+}
@@ -0,0 +1,143 @@
+use lsp_types::{CodeLens, CodeLensParams, Position, Range};
+
+use crate::language_server::code_lens::RunParams;
+
+use super::*;
+
+fn code_lenses(
+    engine: &mut LanguageServerEngine<LanguageServerTestIO, LanguageServerTestIO>,
+    param: TextDocumentPositionParams,
+) -> Vec<CodeLens> {
+    let params = CodeLensParams {
+        text_document: param.text_document,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    engine.code_lens(params).result.unwrap().unwrap_or_default()
+}
+
+/// The title, range, and function to run of each lens.
+fn lenses_in_test(test: &str) -> Vec<(String, Range, RunParams)> {
+    let (mut engine, param) = TestProject::for_source("")
+        .add_test_module("app_test", test)
+        .positioned_with_io_in_test(Position::new(0, 0), "app_test");
+    summarise(code_lenses(&mut engine, param))
+}
+
+fn lenses(code: &str) -> Vec<(String, Range, RunParams)> {
+    let (mut engine, param) = TestProject::for_source(code).positioned_with_io(Position::new(0, 0));
+    summarise(code_lenses(&mut engine, param))
+}
+
+fn summarise(lenses: Vec<CodeLens>) -> Vec<(String, Range, RunParams)> {
+    lenses
+        .into_iter()
+        .map(|lens| {
+            let command = lens.command.unwrap();
+            assert_eq!(command.command, "gleam.run");
+            let argument = command.arguments.unwrap().first().unwrap().clone();
+            (
+                command.title,
+                lens.range,
+                serde_json::from_value(argument).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn run_test_lens_for_each_test_function() {
+    let test = "pub fn one_test() {
+  Nil
+}
+
+pub fn two_test() {
+  Nil
+}
+";
+
+    let lenses = lenses_in_test(test);
+    let summary = lenses
+        .iter()
+        .map(|(title, range, run)| (title.as_str(), range.start.line, run.function.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![("Run test", 0, "one_test"), ("Run test", 4, "two_test")]
+    );
+    let (_, _, run) = lenses.first().unwrap();
+    assert_eq!(run.module, "app_test");
+    assert!(run.test);
+}
+
+#[test]
+fn run_lens_for_main() {
+    let code = "
+pub fn main() {
+  Nil
+}
+
+pub fn wibble() {
+  Nil
+}
+";
+
+    let lenses = lenses(code);
+    assert_eq!(lenses.len(), 1);
+    let (title, range, run) = lenses.first().unwrap();
+    assert_eq!(title, "Run");
+    assert_eq!(
+        range,
+        &Range::new(Position::new(1, 0), Position::new(1, 13))
+    );
+    assert_eq!(run.module, "app");
+    assert_eq!(run.function, "main");
+    assert!(!run.test);
+}
+
+#[test]
+fn no_lens_for_functions_that_cannot_be_run() {
+    let test = "fn private_test() {
+  Nil
+}
+
+pub fn with_argument_test(x) {
+  x
+}
+";
+
+    assert_eq!(lenses_in_test(test), vec![]);
+}
+
+#[test]
+fn gleam_arguments_to_run_test() {
+    let run = RunParams {
+        uri: lsp_types::Url::parse("file:///test/app_test.gleam").unwrap(),
+        module: "app_test".into(),
+        function: "one_test".into(),
+        test: true,
+    };
+
+    assert_eq!(
+        run.arguments(),
+        vec![
+            "test",
+            "--module",
+            "app_test",
+            "--filter",
+            "app_test.one_test"
+        ]
+    );
+}
+
+#[test]
+fn gleam_arguments_to_run_main() {
+    let run = RunParams {
+        uri: lsp_types::Url::parse("file:///src/app.gleam").unwrap(),
+        module: "app".into(),
+        function: "main".into(),
+        test: false,
+    };
+
+    assert_eq!(run.arguments(), vec!["run", "--module", "app"]);
+}
@@ -212,3 +212,30 @@ fn dep_compile_recompile() {
         ]
     )
 }
+
+#[test]
+fn edit_made_before_compilation_does_not_cancel_it() {
+    let io = LanguageServerTestIO::new();
+    let cancellation = CancellationToken::new();
+    let mut engine = LanguageServerEngine::new(
+        PackageConfig {
+            name: LSP_TEST_ROOT_PACKAGE_NAME.into(),
+            ..Default::default()
+        },
+        io.clone(),
+        FileSystemProxy::new(io.clone()),
+        io.paths.clone(),
+        PositionEncoding::Utf16,
+        Default::default(),
+        cancellation.clone(),
+    )
+    .unwrap();
+    _ = io.src_module("app", "pub fn main() { 1 }");
+
+    // The edit is in the code that is compiled
+    cancellation.cancel();
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert!(matches!(response.compilation, Compilation::Yes(_)));
+    assert!(!engine.is_cancelled());
+}
@@ -0,0 +1,72 @@
+use lsp_types::Position;
+
+use crate::{
+    build::Target,
+    language_server::compiled_code::{CompiledCode, ShowCompiledCodeParams},
+};
+
+use super::*;
+
+fn compiled_code(tester: TestProject<'_>, target: Option<Target>) -> Option<CompiledCode> {
+    tester.at(Position::new(0, 0), |engine, param, _| {
+        let params = ShowCompiledCodeParams {
+            text_document: param.text_document,
+            target,
+        };
+        engine.compiled_code(params).result.unwrap()
+    })
+}
+
+#[test]
+fn compiled_code_for_target_of_project() {
+    let code = "
+pub fn main() {
+  1
+}
+";
+
+    let compiled = compiled_code(TestProject::for_source(code), None).unwrap();
+    assert_eq!(compiled.target, Target::Erlang);
+    assert_eq!(compiled.language_id, "erlang");
+    assert!(compiled.code.contains("-module(app)."));
+    assert!(compiled.code.contains("main() ->\n    1."));
+}
+
+#[test]
+fn compiled_code_for_javascript() {
+    let code = "
+pub fn main() {
+  1
+}
+";
+
+    let compiled = compiled_code(TestProject::for_source(code), Some(Target::JavaScript)).unwrap();
+    assert_eq!(compiled.target, Target::JavaScript);
+    assert_eq!(compiled.language_id, "javascript");
+    assert!(compiled
+        .code
+        .contains("export function main() {\n  return 1;\n}"));
+}
+
+#[test]
+fn no_compiled_code_for_module_with_syntax_errors() {
+    let code = "
+pub fn main() {
+  1 +
+}
+";
+
+    // The module fails to compile, so the engine is set up without checking
+    // that it compiles.
+    let tester = TestProject::for_source(code);
+    let mut io = LanguageServerTestIO::new();
+    let mut engine = tester.build_engine(&mut io);
+    _ = io.src_module("app", code);
+    assert!(engine.compile_please().result.is_err());
+
+    let params = ShowCompiledCodeParams {
+        text_document: tester.build_path(Position::new(0, 0)).text_document,
+        target: None,
+    };
+    assert_eq!(engine.compiled_code(params).result.unwrap(), None);
+}
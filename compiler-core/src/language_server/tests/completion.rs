@@ -1,7 +1,7 @@
 use itertools::Itertools;
 use lsp_types::{
-    CompletionItem, CompletionItemKind, CompletionTextEdit, Documentation, MarkupContent,
-    MarkupKind, Position, Range, TextEdit,
+    CompletionItem, CompletionItemKind, CompletionTextEdit, Documentation, InsertTextFormat,
+    MarkupContent, MarkupKind, Position, Range, TextEdit,
 };
 
 use super::*;
@@ -16,6 +16,39 @@ fn completion(tester: TestProject<'_>, position: Position) -> Vec<CompletionItem
     })
 }
 
+/// The completions at the position in a module that doesn't compile, as while
+/// a field access such as `wibble.` is being written. The modules given as the
+/// previous code of the root package are compiled first.
+fn completion_with_errors(
+    tester: TestProject<'_>,
+    previous: &str,
+    position: Position,
+) -> Vec<CompletionItem> {
+    let mut io = LanguageServerTestIO::new();
+    let mut engine = tester.build_engine(&mut io);
+    _ = io.src_module("app", previous);
+    engine.compile_please().result.expect("compiled");
+
+    _ = io.src_module("app", tester.src);
+    assert!(engine.compile_please().result.is_err());
+
+    let params = tester.build_path(position);
+    engine
+        .completion(params, tester.src.into())
+        .result
+        .unwrap()
+        .unwrap_or_default()
+}
+
+fn field_completion(label: &str, type_: &str) -> CompletionItem {
+    CompletionItem {
+        label: label.into(),
+        kind: Some(CompletionItemKind::FIELD),
+        detail: Some(type_.into()),
+        ..Default::default()
+    }
+}
+
 fn completion_at_default_position(tester: TestProject<'_>) -> Vec<CompletionItem> {
     let src = &format!("fn typing_in_here() {{\n  0\n}}\n {}", tester.src);
     let tester = TestProject { src, ..tester };
@@ -1455,3 +1488,324 @@ pub fn main() {
         },]
     );
 }
+
+#[test]
+fn completions_for_record_fields() {
+    let code = "
+type Wibble {
+  Wibble(wibble: Int, wobble: String)
+}
+
+fn main(value: Wibble) {
+  value.
+}
+";
+
+    assert_eq!(
+        completion_with_errors(TestProject::for_source(code), "", Position::new(6, 8)),
+        vec![
+            field_completion("wibble", "Int"),
+            field_completion("wobble", "String")
+        ]
+    );
+}
+
+#[test]
+fn completions_for_record_fields_shared_by_all_variants() {
+    let code = "
+type Wibble {
+  Wibble(name: String, wibble: Int)
+  Wobble(name: String, wobble: Float)
+}
+
+fn main(values: List(Wibble)) {
+  let assert [value, ..] = values
+  value.
+}
+";
+
+    assert_eq!(
+        completion_with_errors(TestProject::for_source(code), "", Position::new(8, 8)),
+        vec![field_completion("name", "String")]
+    );
+}
+
+#[test]
+fn completions_for_record_fields_of_imported_type() {
+    let previous = "import wibble
+
+pub fn main(value: wibble.Wibble) {
+  value
+}
+";
+    let code = "import wibble
+
+pub fn main(value: wibble.Wibble) {
+  value.
+}
+";
+    let wibble = "pub type Wibble {
+  Wibble(wibble: Int, wobble: List(String))
+}";
+
+    assert_eq!(
+        completion_with_errors(
+            TestProject::for_source(code).add_module("wibble", wibble),
+            previous,
+            Position::new(3, 8)
+        ),
+        vec![
+            field_completion("wibble", "Int"),
+            field_completion("wobble", "List(String)")
+        ]
+    );
+}
+
+#[test]
+fn no_completions_for_record_fields_of_imported_opaque_type() {
+    let previous = "import wibble
+
+pub fn main(value: wibble.Wibble) {
+  value
+}
+";
+    let code = "import wibble
+
+pub fn main(value: wibble.Wibble) {
+  value.
+}
+";
+    let wibble = "pub opaque type Wibble {
+  Wibble(wibble: Int)
+}";
+
+    let completions = completion_with_errors(
+        TestProject::for_source(code).add_module("wibble", wibble),
+        previous,
+        Position::new(3, 8),
+    );
+    assert!(completions
+        .iter()
+        .all(|completion| completion.kind != Some(CompletionItemKind::FIELD)));
+}
+
+fn unimported_completions(tester: TestProject<'_>, position: Position) -> Vec<CompletionItem> {
+    completion(tester, position)
+        .into_iter()
+        .filter(|completion| completion.additional_text_edits.is_some())
+        .collect_vec()
+}
+
+fn import_edit(line: u32, new_text: &str) -> Option<Vec<TextEdit>> {
+    let position = Position::new(line, 0);
+    Some(vec![TextEdit {
+        range: Range::new(position, position),
+        new_text: new_text.into(),
+    }])
+}
+
+#[test]
+fn completions_for_values_of_unimported_modules() {
+    let code = "pub fn main() {
+  let dou = 1
+  dou
+}
+";
+    let wibble = "pub fn double(x) { x * 2 }
+
+pub fn triple(x) { x * 3 }
+
+fn dou() { 1 }";
+
+    assert_eq!(
+        unimported_completions(
+            TestProject::for_source(code).add_module("wibble", wibble),
+            Position::new(2, 5)
+        ),
+        vec![CompletionItem {
+            label: "wibble.double".into(),
+            kind: Some(CompletionItemKind::FUNCTION),
+            detail: Some("fn(Int) -> Int".into()),
+            filter_text: Some("double".into()),
+            sort_text: Some("~wibble.double".into()),
+            additional_text_edits: import_edit(0, "import wibble\n\n"),
+            ..Default::default()
+        }]
+    );
+}
+
+#[test]
+fn completions_for_values_of_unimported_modules_are_imported_after_imports() {
+    let code = "import other
+
+pub fn main() {
+  let dou = other.one()
+  dou
+}
+";
+    let other = "pub fn one() { 1 }
+
+pub fn double(x) { x * 2 }";
+    let wibble = "pub fn double(x) { x * 2 }";
+
+    assert_eq!(
+        unimported_completions(
+            TestProject::for_source(code)
+                .add_module("other", other)
+                .add_module("wibble", wibble),
+            Position::new(4, 5)
+        ),
+        vec![CompletionItem {
+            label: "wibble.double".into(),
+            kind: Some(CompletionItemKind::FUNCTION),
+            detail: Some("fn(Int) -> Int".into()),
+            filter_text: Some("double".into()),
+            sort_text: Some("~wibble.double".into()),
+            additional_text_edits: import_edit(1, "import wibble\n"),
+            ..Default::default()
+        }]
+    );
+}
+
+#[test]
+fn no_completions_for_values_of_unimported_modules_after_a_dot() {
+    let code = "import other
+
+pub fn main() {
+  other.dou
+}
+";
+    let other = "pub fn dou() { 1 }";
+    let wibble = "pub fn double(x) { x * 2 }";
+
+    assert_eq!(
+        unimported_completions(
+            TestProject::for_source(code)
+                .add_module("other", other)
+                .add_module("wibble", wibble),
+            Position::new(3, 11)
+        ),
+        vec![]
+    );
+}
+
+#[test]
+fn completions_for_types_of_unimported_modules() {
+    let code = "pub type Wibble {
+  Wibble
+}
+
+pub fn main() -> Wibble {
+  Wibble
+}
+";
+    let wibble = "pub type Wibble {
+  Wobble
+}";
+
+    assert_eq!(
+        unimported_completions(
+            TestProject::for_source(code).add_module("wibble", wibble),
+            Position::new(4, 20)
+        ),
+        vec![CompletionItem {
+            label: "wibble.Wibble".into(),
+            kind: Some(CompletionItemKind::CLASS),
+            detail: Some("Type".into()),
+            filter_text: Some("Wibble".into()),
+            sort_text: Some("~wibble.Wibble".into()),
+            additional_text_edits: import_edit(0, "import wibble\n\n"),
+            ..Default::default()
+        }]
+    );
+}
+
+#[test]
+fn case_clauses_for_empty_case_expression() {
+    let previous = "
+pub type Wibble {
+  Wibble(Int)
+  Wobble
+}
+
+pub fn main(x: Wibble) {
+  x
+}
+";
+    let code = "
+pub type Wibble {
+  Wibble(Int)
+  Wobble
+}
+
+pub fn main(x: Wibble) {
+  case x {}
+}
+";
+
+    assert_eq!(
+        completion_with_errors(
+            TestProject::for_source(code),
+            previous,
+            Position::new(7, 10)
+        ),
+        vec![CompletionItem {
+            label: "Wibble(_), Wobble".into(),
+            kind: Some(CompletionItemKind::SNIPPET),
+            detail: Some("Case clauses".into()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+                range: Range::new(Position::new(7, 10), Position::new(7, 10)),
+                new_text: "\n    Wibble(_) -> ${1:todo}\n    Wobble -> ${2:todo}\n  ".into(),
+            })),
+            ..Default::default()
+        }]
+    );
+}
+
+#[test]
+fn case_clauses_replace_whitespace_between_braces() {
+    let previous = "
+pub fn main(x: Bool) {
+  x
+}
+";
+    let code = "
+pub fn main(x: Bool) {
+  case x {
+  }
+}
+";
+
+    let completions =
+        completion_with_errors(TestProject::for_source(code), previous, Position::new(3, 2));
+    let completion = completions.first().unwrap();
+    assert_eq!(completion.label, "False, True");
+    assert_eq!(
+        completion.text_edit,
+        Some(CompletionTextEdit::Edit(TextEdit {
+            range: Range::new(Position::new(2, 10), Position::new(3, 2)),
+            new_text: "\n    False -> ${1:todo}\n    True -> ${2:todo}\n  ".into(),
+        }))
+    );
+}
+
+#[test]
+fn no_case_clauses_outside_empty_case_expression() {
+    let previous = "
+pub fn main(x: Bool) {
+  x
+}
+";
+    let code = "
+pub fn main(x: Bool) {
+  case x {}
+}
+";
+
+    let completions =
+        completion_with_errors(TestProject::for_source(code), previous, Position::new(2, 2));
+    assert!(completions
+        .iter()
+        .all(|completion| completion.kind != Some(CompletionItemKind::SNIPPET)));
+}
@@ -0,0 +1,146 @@
+use lsp_types::{
+    Hover, HoverContents, HoverParams, InlayHintParams, MarkedString, Position, Range,
+};
+
+use crate::{
+    build::Target,
+    language_server::configuration::{Configuration, InlayHintsConfiguration},
+};
+
+use super::*;
+
+fn configure(
+    engine: &LanguageServerEngine<LanguageServerTestIO, LanguageServerTestIO>,
+    configuration: Configuration,
+) {
+    *engine.configuration.write().unwrap() = configuration;
+}
+
+fn hover_text(hover: Hover) -> String {
+    match hover.contents {
+        HoverContents::Scalar(MarkedString::String(text)) => text,
+        contents => panic!("unexpected hover contents {contents:?}"),
+    }
+}
+
+#[test]
+fn default_configuration() {
+    assert_eq!(
+        Configuration::from_json(&serde_json::json!({})),
+        Configuration {
+            hexdocs_links: true,
+            inlay_hints: InlayHintsConfiguration { enabled: true },
+            target: None,
+            compile_debounce: 100,
+        }
+    );
+}
+
+#[test]
+fn configuration_nested_in_gleam_section() {
+    let settings = serde_json::json!({
+        "gleam": {
+            "hexdocsLinks": false,
+            "inlayHints": { "enabled": false },
+            "target": "javascript",
+            "compileDebounce": 250,
+        }
+    });
+
+    assert_eq!(
+        Configuration::from_json(&settings),
+        Configuration {
+            hexdocs_links: false,
+            inlay_hints: InlayHintsConfiguration { enabled: false },
+            target: Some(Target::JavaScript),
+            compile_debounce: 250,
+        }
+    );
+}
+
+#[test]
+fn settings_left_out_keep_their_default() {
+    let settings = serde_json::json!({ "target": "erlang" });
+
+    assert_eq!(
+        Configuration::from_json(&settings),
+        Configuration {
+            target: Some(Target::Erlang),
+            ..Configuration::default()
+        }
+    );
+}
+
+#[test]
+fn invalid_setting_keeps_its_default() {
+    let settings = serde_json::json!({
+        "compileDebounce": "soon",
+        "hexdocsLinks": false,
+        "inlayHints": { "enabled": "sometimes" },
+    });
+
+    assert_eq!(
+        Configuration::from_json(&settings),
+        Configuration {
+            hexdocs_links: false,
+            ..Configuration::default()
+        }
+    );
+}
+
+#[test]
+fn disabled_inlay_hints() {
+    let code = "
+pub fn main() {
+  let x = 1
+  x
+}
+";
+
+    let hints = TestProject::for_source(code).at(Position::new(0, 0), |engine, param, _| {
+        configure(
+            engine,
+            Configuration {
+                inlay_hints: InlayHintsConfiguration { enabled: false },
+                ..Configuration::default()
+            },
+        );
+        let params = InlayHintParams {
+            text_document: param.text_document,
+            range: Range::new(Position::new(0, 0), Position::new(u32::MAX, 0)),
+            work_done_progress_params: Default::default(),
+        };
+        engine.inlay_hints(params).result.unwrap()
+    });
+
+    assert!(hints.is_none());
+}
+
+#[test]
+fn hover_without_hexdocs_link() {
+    let code = "
+import example_module
+fn main() {
+  example_module.my_fn
+}
+";
+
+    let tester =
+        TestProject::for_source(code).add_hex_module("example_module", "pub fn my_fn() { Nil }");
+    let hover = tester.at(Position::new(3, 19), |engine, param, _| {
+        configure(
+            engine,
+            Configuration {
+                hexdocs_links: false,
+                ..Configuration::default()
+            },
+        );
+        let params = HoverParams {
+            text_document_position_params: param,
+            work_done_progress_params: Default::default(),
+        };
+        engine.hover(params).result.unwrap().unwrap()
+    });
+
+    assert_eq!(hover_text(hover), "```gleam\nfn() -> Nil\n```\n");
+}
@@ -632,3 +632,233 @@ fn main() {
         })
     )
 }
+
+#[test]
+fn goto_definition_in_dependency_module() {
+    let dependency = "pub fn wibble() { wobble() }
+
+fn wobble() { Nil }
+";
+    let tester = TestProject::for_source("pub fn main() { Nil }")
+        .add_hex_module("example_module", dependency);
+
+    let mut io = LanguageServerTestIO::new();
+    let mut engine = tester.build_engine(&mut io);
+    _ = io.src_module("app", tester.src);
+    engine.compile_please().result.expect("compiled");
+
+    let path = Utf8PathBuf::from(if cfg!(target_family = "windows") {
+        r"\\?\C:\build\packages\hex\src\example_module.gleam"
+    } else {
+        "/build/packages/hex/src/example_module.gleam"
+    });
+    let uri = Url::from_file_path(&path).unwrap();
+    engine.load_dependency_module(&path);
+
+    let params = GotoDefinitionParams {
+        text_document_position_params: TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new(uri.clone()),
+            Position::new(0, 18),
+        ),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    assert_eq!(
+        engine.goto_definition(params).result.unwrap(),
+        Some(Location {
+            uri,
+            range: Range {
+                start: Position {
+                    line: 2,
+                    character: 0
+                },
+                end: Position {
+                    line: 2,
+                    character: 11
+                }
+            }
+        })
+    )
+}
+
+#[test]
+fn goto_definition_of_label() {
+    let code = "
+pub type Wibble {
+  Wibble(wibbles: Int, wobbles: Int)
+}
+
+pub fn main() {
+  Wibble(wibbles: 1, wobbles: 2)
+}
+";
+
+    assert_eq!(
+        definition(TestProject::for_source(code), Position::new(6, 22)),
+        Some(Location {
+            uri: Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+                r"\\?\C:\src\app.gleam"
+            } else {
+                "/src/app.gleam"
+            }))
+            .unwrap(),
+            range: Range {
+                start: Position {
+                    line: 2,
+                    character: 23
+                },
+                end: Position {
+                    line: 2,
+                    character: 31
+                }
+            }
+        })
+    )
+}
+
+fn definition_with_files(
+    tester: TestProject<'_>,
+    files: &[(&str, &str)],
+    position: Position,
+) -> Option<Location> {
+    let mut io = LanguageServerTestIO::new();
+    let mut engine = tester.build_engine(&mut io);
+    for (name, code) in files {
+        _ = io.src_file(name, code);
+    }
+    _ = io.src_module("app", tester.src);
+    engine.compile_please().result.expect("compiled");
+
+    let params = GotoDefinitionParams {
+        text_document_position_params: tester.build_path(position),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    engine.goto_definition(params).result.unwrap()
+}
+
+#[test]
+fn goto_definition_of_external_erlang_function() {
+    let code = "
+@external(erlang, \"app_ffi\", \"wobble\")
+pub fn wobble() -> Nil {
+  Nil
+}
+";
+    let erlang = "-module(app_ffi).
+-export([wobble/0]).
+
+wobble() ->
+    nil.
+";
+
+    assert_eq!(
+        definition_with_files(
+            TestProject::for_source(code),
+            &[("app_ffi.erl", erlang)],
+            Position::new(1, 32)
+        ),
+        Some(Location {
+            uri: Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+                r"\\?\C:\src\app_ffi.erl"
+            } else {
+                "/src/app_ffi.erl"
+            }))
+            .unwrap(),
+            range: Range::new(Position::new(3, 0), Position::new(3, 6))
+        })
+    )
+}
+
+#[test]
+fn goto_definition_of_external_erlang_module() {
+    let code = "
+@external(erlang, \"app_ffi\", \"wobble\")
+pub fn wobble() -> Nil {
+  Nil
+}
+";
+    let erlang = "-module(app_ffi).
+-export([wibble/0]).
+
+wibble() ->
+    nil.
+";
+
+    // The start of the file is used when the function can't be found
+    assert_eq!(
+        definition_with_files(
+            TestProject::for_source(code),
+            &[("ffi/app_ffi.erl", erlang)],
+            Position::new(1, 20)
+        ),
+        Some(Location {
+            uri: Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+                r"\\?\C:\src\ffi\app_ffi.erl"
+            } else {
+                "/src/ffi/app_ffi.erl"
+            }))
+            .unwrap(),
+            range: Range::new(Position::new(0, 0), Position::new(0, 0))
+        })
+    )
+}
+
+#[test]
+fn goto_definition_of_external_javascript_function() {
+    let code = "
+@external(javascript, \"./ffi.mjs\", \"wobble\")
+pub fn wobble() -> Nil {
+  Nil
+}
+";
+    let javascript = "import { Ok } from \"./gleam.mjs\";
+
+export function wobble() {
+  return undefined;
+}
+";
+
+    assert_eq!(
+        definition_with_files(
+            TestProject::for_source(code),
+            &[("ffi.mjs", javascript)],
+            Position::new(1, 40)
+        ),
+        Some(Location {
+            uri: Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+                r"\\?\C:\src\ffi.mjs"
+            } else {
+                "/src/ffi.mjs"
+            }))
+            .unwrap(),
+            range: Range::new(Position::new(2, 16), Position::new(2, 22))
+        })
+    )
+}
+
+#[test]
+fn goto_definition_of_external_gleam_module() {
+    let code = "
+@external(erlang, \"wibble@wobble\", \"go\")
+pub fn go() -> Nil {
+  Nil
+}
+";
+
+    assert_eq!(
+        definition(
+            TestProject::for_source(code).add_module("wibble/wobble", "pub fn go() { Nil }"),
+            Position::new(1, 38)
+        ),
+        Some(Location {
+            uri: Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+                r"\\?\C:\src\wibble\wobble.gleam"
+            } else {
+                "/src/wibble/wobble.gleam"
+            }))
+            .unwrap(),
+            range: Range::new(Position::new(0, 0), Position::new(0, 11))
+        })
+    )
+}
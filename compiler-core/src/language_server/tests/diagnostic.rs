@@ -0,0 +1,188 @@
+use lsp_types::{
+    CodeActionContext, CodeActionParams, Diagnostic, DiagnosticSeverity, DocumentDiagnosticParams,
+    DocumentDiagnosticReport, DocumentDiagnosticReportResult, Position, Range, TextEdit,
+};
+
+use super::*;
+
+/// The diagnostics pulled for the module after compiling the project, which
+/// may fail.
+fn diagnostics(tester: &TestProject<'_>) -> Vec<Diagnostic> {
+    let mut io = LanguageServerTestIO::new();
+    let mut engine = tester.build_engine(&mut io);
+    _ = io.src_module("app", tester.src);
+    _ = engine.compile_please();
+    pull_diagnostics(&mut engine, tester)
+}
+
+fn pull_diagnostics(
+    engine: &mut LanguageServerEngine<LanguageServerTestIO, LanguageServerTestIO>,
+    tester: &TestProject<'_>,
+) -> Vec<Diagnostic> {
+    let params = DocumentDiagnosticParams {
+        text_document: tester.build_path(Position::new(0, 0)).text_document,
+        identifier: None,
+        previous_result_id: None,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    match engine.diagnostic(params).result.unwrap() {
+        DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Full(report)) => {
+            report.full_document_diagnostic_report.items
+        }
+        report => panic!("expected a full report, got {report:?}"),
+    }
+}
+
+#[test]
+fn no_diagnostics_for_module_without_problems() {
+    let code = "
+pub fn main() {
+  1
+}
+";
+
+    assert_eq!(diagnostics(&TestProject::for_source(code)), vec![]);
+}
+
+#[test]
+fn diagnostics_for_warnings_with_fixes() {
+    let code = "import wibble
+
+pub fn main() {
+  1
+}
+";
+    let tester = TestProject::for_source(code).add_module("wibble", "pub fn wobble() { 1 }");
+
+    // The hint of the warning is given as a diagnostic of its own
+    let diagnostics = diagnostics(&tester);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(
+        diagnostics.get(1).unwrap().severity,
+        Some(DiagnosticSeverity::HINT)
+    );
+    let diagnostic = diagnostics.first().unwrap();
+    assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::WARNING));
+    assert_eq!(
+        diagnostic.range,
+        Range::new(Position::new(0, 0), Position::new(0, 13))
+    );
+    assert_eq!(
+        diagnostic.data,
+        Some(serde_json::json!({
+            "fixes": [{
+                "title": "Remove unused import",
+                "edits": [{
+                    "range": {
+                        "start": { "line": 0, "character": 0 },
+                        "end": { "line": 0, "character": 13 },
+                    },
+                    "newText": "",
+                }],
+            }],
+        }))
+    );
+}
+
+#[test]
+fn diagnostics_for_errors_with_related_information() {
+    let code = "
+pub fn main() {
+  1
+}
+
+pub fn main() {
+  2
+}
+";
+
+    let diagnostics = diagnostics(&TestProject::for_source(code));
+    let diagnostic = diagnostics.first().unwrap();
+    assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::ERROR));
+    assert_eq!(diagnostic.range.start.line, 5);
+    let related = diagnostic.related_information.as_ref().unwrap();
+    assert_eq!(related.len(), 1);
+    let related = related.first().unwrap();
+    assert_eq!(related.message, "First defined here");
+    assert_eq!(related.location.uri, tester_uri());
+    assert_eq!(related.location.range.start.line, 1);
+}
+
+#[test]
+fn warnings_of_module_compiled_again_are_replaced() {
+    let code = "import wibble
+
+pub fn main() {
+  1
+}
+";
+    let fixed = "import wibble
+
+pub fn main() {
+  wibble.wobble()
+}
+";
+    let tester = TestProject::for_source(code).add_module("wibble", "pub fn wobble() { 1 }");
+
+    let mut io = LanguageServerTestIO::new();
+    let mut engine = tester.build_engine(&mut io);
+    _ = io.src_module("app", code);
+    engine.compile_please().result.expect("compiled");
+    assert_eq!(pull_diagnostics(&mut engine, &tester).len(), 2);
+
+    _ = io.src_module("app", fixed);
+    engine.compile_please().result.expect("compiled");
+    assert_eq!(pull_diagnostics(&mut engine, &tester), vec![]);
+}
+
+#[test]
+fn code_action_for_fix_of_diagnostic() {
+    let code = "import wibble
+
+pub fn main() {
+  1
+}
+";
+    let tester = TestProject::for_source(code).add_module("wibble", "pub fn wobble() { 1 }");
+
+    let mut io = LanguageServerTestIO::new();
+    let mut engine = tester.build_engine(&mut io);
+    _ = io.src_module("app", code);
+    engine.compile_please().result.expect("compiled");
+    let diagnostics = pull_diagnostics(&mut engine, &tester);
+    let diagnostic = diagnostics.first().unwrap().clone();
+
+    let params = CodeActionParams {
+        text_document: tester.build_path(Position::new(0, 0)).text_document,
+        range: diagnostic.range,
+        context: CodeActionContext {
+            diagnostics: vec![diagnostic.clone()],
+            only: None,
+            trigger_kind: None,
+        },
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    let actions = engine.action(params).result.unwrap().unwrap();
+    let action = actions
+        .iter()
+        .find(|action| action.title == "Remove unused import")
+        .unwrap();
+    assert_eq!(action.diagnostics, Some(vec![diagnostic]));
+    let changes = action.edit.as_ref().unwrap().changes.as_ref().unwrap();
+    assert_eq!(
+        changes.get(&tester_uri()),
+        Some(&vec![TextEdit {
+            range: Range::new(Position::new(0, 0), Position::new(0, 13)),
+            new_text: "".into(),
+        }])
+    );
+}
+
+fn tester_uri() -> lsp_types::Url {
+    TestProject::for_source("")
+        .build_path(Position::new(0, 0))
+        .text_document
+        .uri
+}
@@ -0,0 +1,148 @@
+use lsp_types::{Position, Range, TextDocumentContentChangeEvent};
+
+use crate::{
+    io::FileSystemReader,
+    language_server::{document::Document, files::FileSystemProxy},
+    line_numbers::PositionEncoding,
+};
+
+use super::LanguageServerTestIO;
+
+fn change(start: (u32, u32), end: (u32, u32), text: &str) -> TextDocumentContentChangeEvent {
+    TextDocumentContentChangeEvent {
+        range: Some(Range::new(
+            Position::new(start.0, start.1),
+            Position::new(end.0, end.1),
+        )),
+        range_length: None,
+        text: text.into(),
+    }
+}
+
+fn edited(
+    src: &str,
+    changes: Vec<TextDocumentContentChangeEvent>,
+    encoding: PositionEncoding,
+) -> Document {
+    let mut document = Document::new(src.into());
+    document.apply_changes(changes, encoding);
+    document
+}
+
+#[test]
+fn insert_text() {
+    let document = edited(
+        "pub fn main() {\n  1\n}\n",
+        vec![change((1, 3), (1, 3), " + 2")],
+        PositionEncoding::Utf16,
+    );
+    assert_eq!(
+        document,
+        Document::new("pub fn main() {\n  1 + 2\n}\n".into())
+    );
+}
+
+#[test]
+fn replace_lines() {
+    let document = edited(
+        "pub fn main() {\n  1\n}\n",
+        vec![change((0, 15), (2, 0), "\n  let x = 1\n  x\n")],
+        PositionEncoding::Utf16,
+    );
+    assert_eq!(
+        document,
+        Document::new("pub fn main() {\n  let x = 1\n  x\n}\n".into())
+    );
+}
+
+#[test]
+fn changes_are_applied_in_order() {
+    let document = edited(
+        "one\ntwo\n",
+        vec![
+            change((1, 0), (2, 0), ""),
+            change((0, 3), (0, 3), "\nthree"),
+            change((1, 0), (1, 0), "two and "),
+        ],
+        PositionEncoding::Utf16,
+    );
+    assert_eq!(document, Document::new("one\ntwo and three\n".into()));
+}
+
+#[test]
+fn change_without_a_range_replaces_the_text() {
+    let document = edited(
+        "one\ntwo\n",
+        vec![TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "three\n".into(),
+        }],
+        PositionEncoding::Utf16,
+    );
+    assert_eq!(document, Document::new("three\n".into()));
+}
+
+#[test]
+fn change_after_multi_byte_characters() {
+    let src = "let x = \"🐙\"\n";
+    let expected = Document::new("let x = \"🐙🐙\"\n".into());
+
+    let document = edited(
+        src,
+        vec![change((0, 13), (0, 13), "🐙")],
+        PositionEncoding::Utf8,
+    );
+    assert_eq!(document, expected);
+    let document = edited(
+        src,
+        vec![change((0, 11), (0, 11), "🐙")],
+        PositionEncoding::Utf16,
+    );
+    assert_eq!(document, expected);
+    let document = edited(
+        src,
+        vec![change((0, 10), (0, 10), "🐙")],
+        PositionEncoding::Utf32,
+    );
+    assert_eq!(document, expected);
+}
+
+#[test]
+fn proxy_applies_changes_to_open_documents() {
+    let io = LanguageServerTestIO::new();
+    let path = io.src_module("app", "pub fn main() {\n  1\n}\n");
+    let mut proxy = FileSystemProxy::new(io);
+
+    proxy
+        .open_document(&path, "pub fn main() {\n  2\n}\n".into())
+        .unwrap();
+    proxy
+        .edit_document(
+            &path,
+            vec![change((1, 3), (1, 3), " + 3")],
+            PositionEncoding::Utf16,
+        )
+        .unwrap();
+    assert_eq!(proxy.read(&path).unwrap(), "pub fn main() {\n  2 + 3\n}\n");
+
+    // Once closed the file is read from the disc again
+    proxy.close_document(&path).unwrap();
+    assert_eq!(proxy.read(&path).unwrap(), "pub fn main() {\n  1\n}\n");
+}
+
+#[test]
+fn proxy_ignores_changes_to_documents_that_are_not_open() {
+    let io = LanguageServerTestIO::new();
+    let path = io.src_module("app", "pub fn main() {\n  1\n}\n");
+    let mut proxy = FileSystemProxy::new(io);
+
+    proxy
+        .edit_document(
+            &path,
+            vec![change((1, 3), (1, 3), " + 3")],
+            PositionEncoding::Utf16,
+        )
+        .unwrap();
+    assert_eq!(proxy.read(&path).unwrap(), "pub fn main() {\n  1\n}\n");
+}
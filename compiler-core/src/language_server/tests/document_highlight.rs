@@ -0,0 +1,119 @@
+use lsp_types::{
+    DocumentHighlight, DocumentHighlightKind, DocumentHighlightParams, Position, Range,
+};
+
+use super::*;
+
+fn document_highlight(
+    tester: TestProject<'_>,
+    position: Position,
+) -> Option<Vec<DocumentHighlight>> {
+    tester.at(position, |engine, param, _| {
+        let params = DocumentHighlightParams {
+            text_document_position_params: param,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine.document_highlight(params).result.unwrap()
+    })
+}
+
+fn highlight(line: u32, start: u32, end: u32, kind: DocumentHighlightKind) -> DocumentHighlight {
+    DocumentHighlight {
+        range: Range::new(Position::new(line, start), Position::new(line, end)),
+        kind: Some(kind),
+    }
+}
+
+#[test]
+fn highlight_local_variable() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  wibble + wibble
+}";
+
+    assert_eq!(
+        document_highlight(TestProject::for_source(code), Position::new(3, 2)),
+        Some(vec![
+            highlight(2, 6, 12, DocumentHighlightKind::WRITE),
+            highlight(3, 2, 8, DocumentHighlightKind::READ),
+            highlight(3, 11, 17, DocumentHighlightKind::READ),
+        ])
+    );
+}
+
+#[test]
+fn highlight_does_not_group_shadowed_variables() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  let wibble = wibble + 1
+  wibble
+}";
+
+    assert_eq!(
+        document_highlight(TestProject::for_source(code), Position::new(4, 2)),
+        Some(vec![
+            highlight(3, 6, 12, DocumentHighlightKind::WRITE),
+            highlight(4, 2, 8, DocumentHighlightKind::READ),
+        ])
+    );
+    assert_eq!(
+        document_highlight(TestProject::for_source(code), Position::new(2, 6)),
+        Some(vec![
+            highlight(2, 6, 12, DocumentHighlightKind::WRITE),
+            highlight(3, 15, 21, DocumentHighlightKind::READ),
+        ])
+    );
+}
+
+#[test]
+fn highlight_argument() {
+    let code = "
+pub fn add(x: Int, to y: Int) {
+  x + y
+}";
+
+    assert_eq!(
+        document_highlight(TestProject::for_source(code), Position::new(2, 6)),
+        Some(vec![
+            highlight(1, 22, 23, DocumentHighlightKind::WRITE),
+            highlight(2, 6, 7, DocumentHighlightKind::READ),
+        ])
+    );
+}
+
+#[test]
+fn highlight_function() {
+    let code = "
+fn wobble() {
+  1
+}
+
+pub fn main() {
+  wobble() + wobble()
+}";
+
+    assert_eq!(
+        document_highlight(TestProject::for_source(code), Position::new(6, 2)),
+        Some(vec![
+            highlight(1, 3, 9, DocumentHighlightKind::TEXT),
+            highlight(6, 2, 8, DocumentHighlightKind::TEXT),
+            highlight(6, 13, 19, DocumentHighlightKind::TEXT),
+        ])
+    );
+}
+
+#[test]
+fn no_highlight_for_literal() {
+    let code = "
+pub fn main() {
+  1
+}";
+
+    assert_eq!(
+        document_highlight(TestProject::for_source(code), Position::new(2, 2)),
+        None
+    );
+}
@@ -0,0 +1,115 @@
+use itertools::Itertools;
+use lsp_types::{DocumentLink, DocumentLinkParams, Position};
+
+use crate::line_numbers::LineNumbers;
+
+use super::*;
+
+fn document_links(tester: TestProject<'_>) -> Vec<DocumentLink> {
+    let (mut engine, param) = tester.positioned_with_io(Position::new(0, 0));
+    let params = DocumentLinkParams {
+        text_document: param.text_document,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    engine
+        .document_link(params)
+        .result
+        .unwrap()
+        .unwrap_or_default()
+}
+
+/// The linked text and the target of each link.
+fn links(tester: TestProject<'_>, code: &str) -> Vec<(String, String)> {
+    let line_numbers = LineNumbers::new(code);
+    document_links(tester)
+        .into_iter()
+        .map(|link| {
+            let start = line_numbers.byte_index(
+                link.range.start.line,
+                link.range.start.character,
+                PositionEncoding::Utf16,
+            );
+            let end = line_numbers.byte_index(
+                link.range.end.line,
+                link.range.end.character,
+                PositionEncoding::Utf16,
+            );
+            let text = code.get(start as usize..end as usize).unwrap().to_string();
+            (text, link.target.unwrap().to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn import_of_hex_module_links_to_hexdocs() {
+    let code = "import example/module\n";
+    let tester = TestProject::for_source(code).add_hex_module("example/module", "pub const x = 1");
+
+    assert_eq!(
+        links(tester, code),
+        vec![(
+            "example/module".to_string(),
+            "https://hexdocs.pm/hex/example/module.html".to_string()
+        )]
+    );
+}
+
+#[test]
+fn import_of_project_module_links_to_its_source() {
+    let code = "import wibble.{x} as wobble\n";
+    let tester = TestProject::for_source(code).add_module("wibble", "pub const x = 1");
+
+    let links = links(tester, code);
+    assert_eq!(links.len(), 1);
+    let (text, target) = links.first().unwrap();
+    assert_eq!(text, "wibble");
+    assert!(target.ends_with("/src/wibble.gleam"), "{target}");
+}
+
+#[test]
+fn import_of_path_dependency_module_links_to_its_source() {
+    let code = "import example_module\n";
+    let tester = TestProject::for_source(code).add_dep_module("example_module", "pub const x = 1");
+
+    let links = links(tester, code);
+    assert_eq!(links.len(), 1);
+    let (text, target) = links.first().unwrap();
+    assert_eq!(text, "example_module");
+    assert!(
+        target.ends_with("/dep/src/example_module.gleam"),
+        "{target}"
+    );
+}
+
+#[test]
+fn module_name_containing_import_keyword() {
+    let code = "import port\n";
+    let tester = TestProject::for_source(code).add_hex_module("port", "pub const x = 1");
+
+    assert_eq!(
+        links(tester, code),
+        vec![(
+            "port".to_string(),
+            "https://hexdocs.pm/hex/port.html".to_string()
+        )]
+    );
+}
+
+#[test]
+fn each_import_is_linked() {
+    let code = "import example/one
+import example/two
+";
+    let tester = TestProject::for_source(code)
+        .add_hex_module("example/one", "pub const x = 1")
+        .add_hex_module("example/two", "pub const y = 1");
+
+    assert_eq!(
+        links(tester, code)
+            .into_iter()
+            .map(|(text, _)| text)
+            .collect_vec(),
+        vec!["example/one", "example/two"]
+    );
+}
@@ -0,0 +1,97 @@
+use lsp_types::{FoldingRange, FoldingRangeKind};
+
+use crate::language_server::folding_range::folding_ranges;
+
+fn fold(start_line: u32, end_line: u32) -> FoldingRange {
+    FoldingRange {
+        start_line,
+        end_line,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn fold_functions_and_types() {
+    let src = "pub type Wibble {
+  Wibble
+  Wobble
+}
+
+pub fn main() {
+  1
+}
+
+pub fn one_line() { 1 }
+";
+
+    assert_eq!(folding_ranges(src), vec![fold(0, 3), fold(5, 7)]);
+}
+
+#[test]
+fn fold_case_expressions_and_clauses() {
+    let src = "pub fn main(x) {
+  case x {
+    1 -> {
+      2
+    }
+    _ -> 3
+  }
+}
+";
+
+    assert_eq!(
+        folding_ranges(src),
+        vec![fold(0, 7), fold(1, 6), fold(2, 4)]
+    );
+}
+
+#[test]
+fn fold_blocks_and_anonymous_functions() {
+    let src = "pub fn main() {
+  let x = {
+    1
+  }
+  fn(y) {
+    y
+  }
+}
+";
+
+    assert_eq!(
+        folding_ranges(src),
+        vec![fold(0, 7), fold(1, 3), fold(4, 6)]
+    );
+}
+
+#[test]
+fn fold_groups_of_imports() {
+    let src = "import gleam/io
+import gleam/list
+import gleam/string
+
+pub fn main() {
+  io.println(\"Hello\")
+}
+";
+
+    assert_eq!(
+        folding_ranges(src),
+        vec![
+            FoldingRange {
+                kind: Some(FoldingRangeKind::Imports),
+                ..fold(0, 2)
+            },
+            fold(4, 6),
+        ]
+    );
+}
+
+#[test]
+fn fold_module_that_does_not_type_check() {
+    let src = "pub fn main() {
+  1 + \"wibble\"
+}
+";
+
+    assert_eq!(folding_ranges(src), vec![fold(0, 2)]);
+}
@@ -0,0 +1,78 @@
+use lsp_types::{Position, Range, TextEdit};
+
+use crate::language_server::formatting;
+
+fn edit(start: u32, end: u32, new_text: &str) -> TextEdit {
+    TextEdit {
+        range: Range::new(Position::new(start, 0), Position::new(end, 0)),
+        new_text: new_text.into(),
+    }
+}
+
+#[test]
+fn only_changed_lines_are_edited() {
+    let src = "pub fn main() {
+  1+1
+}
+
+pub fn other() {
+    2
+}
+";
+    let formatted = "pub fn main() {
+  1 + 1
+}
+
+pub fn other() {
+  2
+}
+";
+
+    assert_eq!(
+        formatting::edits(src, formatted),
+        vec![edit(1, 2, "  1 + 1\n"), edit(5, 6, "  2\n")]
+    );
+}
+
+#[test]
+fn formatted_code_is_not_edited() {
+    let src = "pub fn main() {
+  1
+}
+";
+
+    assert_eq!(formatting::edits(src, src), vec![]);
+}
+
+#[test]
+fn removed_lines_are_edited() {
+    let src = "pub fn main() {
+  1
+
+
+}
+";
+    let formatted = "pub fn main() {
+  1
+}
+";
+
+    assert_eq!(formatting::edits(src, formatted), vec![edit(2, 4, "")]);
+}
+
+#[test]
+fn only_edits_in_range_are_kept() {
+    let edits = vec![edit(1, 2, "  1 + 1\n"), edit(5, 6, "  2\n")];
+
+    assert_eq!(
+        formatting::in_range(
+            edits.clone(),
+            Range::new(Position::new(4, 0), Position::new(6, 0))
+        ),
+        vec![edit(5, 6, "  2\n")]
+    );
+    assert_eq!(
+        formatting::in_range(edits, Range::new(Position::new(2, 0), Position::new(4, 0))),
+        vec![]
+    );
+}
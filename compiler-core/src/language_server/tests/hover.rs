@@ -82,6 +82,40 @@ fn() -> Nil
     );
 }
 
+#[test]
+fn hover_after_multi_byte_characters() {
+    // `🐙` takes two UTF-16 code units and four bytes.
+    let code = "
+pub fn main() {
+  let wibble = \"🐙\"
+  \"🐙🐙\" <> wibble
+}
+";
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(3, 14)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam
+String
+```
+A locally defined variable."
+                    .to_string()
+            )),
+            range: Some(Range {
+                start: Position {
+                    line: 3,
+                    character: 12,
+                },
+                end: Position {
+                    line: 3,
+                    character: 18,
+                },
+            },),
+        })
+    );
+}
+
 // https://github.com/gleam-lang/gleam/issues/2654
 #[test]
 fn hover_local_function_in_pipe() {
@@ -596,3 +630,240 @@ fn do_stuff() {
         })
     );
 }
+
+#[test]
+fn hover_in_module_with_syntax_error() {
+    let code = "
+fn add_2(x) {
+  x + 2
+}
+
+fn broken() {
+  1 +
+}
+";
+    let tester = TestProject::for_source(code);
+    let mut io = LanguageServerTestIO::new();
+    let mut engine = tester.build_engine(&mut io);
+    _ = io.src_module("app", code);
+
+    let response = engine.compile_please();
+    assert!(response.result.is_err());
+
+    let params = HoverParams {
+        text_document_position_params: tester.build_path(Position::new(2, 2)),
+        work_done_progress_params: Default::default(),
+    };
+    assert_eq!(
+        engine.hover(params).result.expect("should hover"),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam\nInt\n```\nA locally defined variable.".to_string()
+            )),
+            range: Some(Range {
+                start: Position {
+                    line: 2,
+                    character: 2,
+                },
+                end: Position {
+                    line: 2,
+                    character: 3,
+                },
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_impure_function() {
+    let code = r#"
+@external(erlang, "io", "format")
+fn print(x: String) -> Nil
+
+fn main() {
+  print("Hello")
+}
+"#;
+
+    let hover = hover(TestProject::for_source(code), Position::new(5, 3)).expect("hover");
+    insta::assert_debug_snapshot!(hover);
+}
+
+#[test]
+fn hover_impure_function_definition() {
+    let code = "
+fn main() {
+  panic
+}
+";
+
+    let hover = hover(TestProject::for_source(code), Position::new(1, 3)).expect("hover");
+    insta::assert_debug_snapshot!(hover);
+}
+
+#[test]
+fn hover_in_dependency_module() {
+    let tester = TestProject::for_source("pub fn main() { Nil }")
+        .add_hex_module("example_module", "pub fn wibble(x: Int) { x }");
+
+    let mut io = LanguageServerTestIO::new();
+    let mut engine = tester.build_engine(&mut io);
+    _ = io.src_module("app", tester.src);
+    engine.compile_please().result.expect("compiled");
+
+    let path = Utf8PathBuf::from(if cfg!(target_family = "windows") {
+        r"\\?\C:\build\packages\hex\src\example_module.gleam"
+    } else {
+        "/build/packages/hex/src/example_module.gleam"
+    });
+    engine.load_dependency_module(&path);
+
+    let params = HoverParams {
+        text_document_position_params: TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new(Url::from_file_path(&path).unwrap()),
+            Position::new(0, 24),
+        ),
+        work_done_progress_params: Default::default(),
+    };
+    let hover = engine.hover(params).result.unwrap().expect("hover");
+    assert_eq!(
+        hover.contents,
+        HoverContents::Scalar(MarkedString::String(
+            "```gleam\nInt\n```\nA locally defined variable.".into()
+        ))
+    );
+}
+
+#[test]
+fn hover_label_of_function_argument() {
+    let code = "
+fn add(to x: Int, amount y: Int) {
+  x + y
+}
+
+pub fn main() {
+  add(to: 1, amount: 2)
+}
+";
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(6, 14)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam\namount: Int\n```\n".to_string()
+            )),
+            range: Some(Range {
+                start: Position {
+                    line: 6,
+                    character: 13,
+                },
+                end: Position {
+                    line: 6,
+                    character: 19,
+                },
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_label_of_record_constructor_argument_with_docs() {
+    let code = "
+pub type Wibble {
+  Wibble(
+    /// How many times it wobbles
+    wobbles: Int,
+  )
+}
+
+pub fn main() {
+  Wibble(wobbles: 1)
+}
+";
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(9, 10)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam\nwobbles: Int\n```\n How many times it wobbles\n".to_string()
+            )),
+            range: Some(Range {
+                start: Position {
+                    line: 9,
+                    character: 9,
+                },
+                end: Position {
+                    line: 9,
+                    character: 16,
+                },
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_label_of_record_update_field() {
+    let code = "
+pub type Wibble {
+  Wibble(
+    /// How many times it wobbles
+    wobbles: Int,
+    wibbles: Int,
+  )
+}
+
+pub fn main(wibble: Wibble) {
+  Wibble(..wibble, wobbles: 1)
+}
+";
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(10, 20)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam\nwobbles: Int\n```\n How many times it wobbles\n".to_string()
+            )),
+            range: Some(Range {
+                start: Position {
+                    line: 10,
+                    character: 19,
+                },
+                end: Position {
+                    line: 10,
+                    character: 26,
+                },
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_label_of_imported_record_constructor_argument() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.Wibble(wobbles: 1)
+}
+";
+    let wibble = "
+pub type Wibble {
+  Wibble(
+    /// How many times it wobbles
+    wobbles: Int,
+  )
+}
+";
+
+    let hover = hover(
+        TestProject::for_source(code).add_module("wibble", wibble),
+        Position::new(4, 17),
+    )
+    .unwrap();
+    assert_eq!(
+        hover.contents,
+        HoverContents::Scalar(MarkedString::String(
+            "```gleam\nwobbles: Int\n```\n How many times it wobbles\n".to_string()
+        ))
+    );
+}
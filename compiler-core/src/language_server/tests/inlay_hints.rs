@@ -0,0 +1,154 @@
+use lsp_types::{InlayHintLabel, InlayHintParams, Position, Range};
+
+use super::*;
+
+fn inlay_hints(src: &str) -> Vec<(Position, String)> {
+    project_inlay_hints(TestProject::for_source(src))
+}
+
+fn project_inlay_hints(tester: TestProject<'_>) -> Vec<(Position, String)> {
+    tester.at(Position::new(0, 0), |engine, param, _| {
+        let params = InlayHintParams {
+            text_document: param.text_document,
+            range: Range::new(Position::new(0, 0), Position::new(u32::MAX, 0)),
+            work_done_progress_params: Default::default(),
+        };
+        engine
+            .inlay_hints(params)
+            .result
+            .expect("should return hints")
+            .unwrap_or_default()
+            .into_iter()
+            .map(|hint| match hint.label {
+                InlayHintLabel::String(label) => (hint.position, label),
+                InlayHintLabel::LabelParts(_) => panic!("unexpected label parts"),
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn todo_types_are_shown() {
+    let code = "
+pub fn main() {
+  let x: Int = todo
+  wibble(todo as \"wobble\")
+}
+
+fn wibble(x: List(String)) -> Nil {
+  Nil
+}
+";
+
+    assert_eq!(
+        inlay_hints(code),
+        vec![
+            (Position::new(2, 19), ": Int".to_string()),
+            (Position::new(3, 13), ": List(String)".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn unknown_todo_types_are_shown_as_type_variables() {
+    let code = "
+pub fn main() {
+  todo
+}
+";
+
+    assert_eq!(
+        inlay_hints(code),
+        vec![(Position::new(2, 6), ": a".to_string())]
+    );
+}
+
+#[test]
+fn empty_functions_have_no_hints() {
+    let code = "
+pub fn main() -> Int {}
+";
+
+    assert_eq!(inlay_hints(code), vec![]);
+}
+
+#[test]
+fn types_of_let_bindings_are_shown() {
+    let code = "
+pub fn main() {
+  let x = 1
+  let #(a, b) = #(x, \"wibble\")
+  let _ = a
+  b
+}
+";
+
+    assert_eq!(
+        inlay_hints(code),
+        vec![
+            (Position::new(2, 7), ": Int".to_string()),
+            (Position::new(3, 13), ": #(Int, String)".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn labels_of_unlabelled_arguments_are_shown() {
+    let code = "
+pub fn main() {
+  let to = 2
+  add(1, to)
+  add(1, 3)
+  add(1, to: 4)
+}
+
+fn add(x: Int, to y: Int) -> Int {
+  x + y
+}
+";
+
+    assert_eq!(
+        inlay_hints(code),
+        vec![
+            (Position::new(2, 8), ": Int".to_string()),
+            (Position::new(4, 9), "to: ".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn labels_of_functions_of_other_modules_are_shown() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.greet(\"Jak\", \"Hi\")
+}
+";
+    let wibble = "pub fn greet(name: String, greeting greeting: String) -> String {
+  greeting <> name
+}";
+
+    assert_eq!(
+        project_inlay_hints(TestProject::for_source(code).add_module("wibble", wibble)),
+        vec![(Position::new(4, 22), "greeting: ".to_string())]
+    );
+}
+
+#[test]
+fn piped_arguments_have_no_hints() {
+    let code = "
+pub fn main() {
+  1 |> add(2)
+}
+
+fn add(to x: Int, and y: Int) -> Int {
+  x + y
+}
+";
+
+    assert_eq!(
+        inlay_hints(code),
+        vec![(Position::new(2, 11), "and: ".to_string())]
+    );
+}
@@ -1,8 +1,26 @@
 mod action;
+mod call_hierarchy;
+mod code_lens;
 mod compilation;
+mod compiled_code;
 mod completion;
+mod configuration;
 mod definition;
+mod diagnostic;
+mod document;
+mod document_highlight;
+mod document_link;
+mod folding_range;
+mod formatting;
 mod hover;
+mod inlay_hints;
+mod references;
+mod rename;
+mod router;
+mod semantic_tokens;
+mod signature_help;
+mod type_definition;
+mod workspace_symbol;
 
 use std::{
     collections::HashMap,
@@ -17,6 +35,7 @@ use camino::{Utf8Path, Utf8PathBuf};
 use lsp_types::{Position, TextDocumentIdentifier, TextDocumentPositionParams, Url};
 
 use crate::{
+    build::CancellationToken,
     config::PackageConfig,
     io::{
         memory::InMemoryFileSystem, CommandExecutor, FileSystemReader, FileSystemWriter, ReadDir,
@@ -26,6 +45,7 @@ use crate::{
         engine::LanguageServerEngine, files::FileSystemProxy, progress::ProgressReporter,
         DownloadDependencies, LockGuard, Locker, MakeLocker,
     },
+    line_numbers::PositionEncoding,
     manifest::{Base16Checksum, Manifest, ManifestPackage, ManifestPackageSource},
     paths::ProjectPaths,
     requirement::Requirement,
@@ -85,6 +105,12 @@ impl LanguageServerTestIO {
         path
     }
 
+    pub fn src_file(&self, name: &str, code: &str) -> Utf8PathBuf {
+        let path = self.paths.src_directory().join(name);
+        self.module(&path, code);
+        path
+    }
+
     pub fn path_dep_module(&self, dep: &str, name: &str, code: &str) -> Utf8PathBuf {
         let dep_dir = self.paths.root().join(dep).join("src");
         let path = dep_dir.join(name).with_extension("gleam");
@@ -292,9 +318,7 @@ fn add_package_from_manifest<B>(
                 version: Range::new("1.0.0".into()),
             },
             ManifestPackageSource::Local { ref path } => Requirement::Path { path: path.into() },
-            ManifestPackageSource::Git { ref repo, .. } => Requirement::Git {
-                git: repo.clone(),
-            },
+            ManifestPackageSource::Git { ref repo, .. } => Requirement::git(repo),
         },
     );
     write_toml_from_manifest(engine, toml_path, package);
@@ -313,9 +337,7 @@ fn add_dev_package_from_manifest<B>(
                 version: Range::new("1.0.0".into()),
             },
             ManifestPackageSource::Local { ref path } => Requirement::Path { path: path.into() },
-            ManifestPackageSource::Git { ref repo, .. } => Requirement::Git {
-                git: repo.clone(),
-            },
+            ManifestPackageSource::Git { ref repo, .. } => Requirement::git(repo),
         },
     );
     write_toml_from_manifest(engine, toml_path, package);
@@ -363,6 +385,9 @@ fn setup_engine(
         io.clone(),
         FileSystemProxy::new(io.clone()),
         io.paths.clone(),
+        PositionEncoding::Utf16,
+        Default::default(),
+        CancellationToken::new(),
     )
     .unwrap()
 }
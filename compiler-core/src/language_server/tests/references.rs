@@ -0,0 +1,153 @@
+use lsp_types::{Location, Position, Range, ReferenceContext, ReferenceParams, Url};
+
+use super::*;
+
+fn references(
+    tester: TestProject<'_>,
+    position: Position,
+    include_declaration: bool,
+) -> Option<Vec<Location>> {
+    tester.at(position, |engine, param, _| {
+        let params = ReferenceParams {
+            text_document_position: param,
+            context: ReferenceContext {
+                include_declaration,
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let response = engine.references(params);
+
+        response.result.unwrap()
+    })
+}
+
+fn location(module: &str, line: u32, start: u32, end: u32) -> Location {
+    let uri = Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+        format!(r"\\?\C:\src\{module}.gleam")
+    } else {
+        format!("/src/{module}.gleam")
+    }))
+    .unwrap();
+    Location {
+        uri,
+        range: Range::new(Position::new(line, start), Position::new(line, end)),
+    }
+}
+
+#[test]
+fn references_to_function_in_modules_importing_it() {
+    let code = "
+import wibble.{double}
+
+pub fn main() {
+  double(1) + wibble.double(2)
+}";
+    let wibble = "pub fn double(x) { x * 2 }";
+    let unrelated = "pub fn double() { 2 }";
+
+    assert_eq!(
+        references(
+            TestProject::for_source(code)
+                .add_module("wibble", wibble)
+                .add_module("unrelated", unrelated),
+            Position::new(4, 2),
+            true
+        ),
+        Some(vec![
+            location("app", 1, 15, 21),
+            location("app", 4, 2, 8),
+            location("app", 4, 21, 27),
+            location("wibble", 0, 7, 13),
+        ])
+    );
+}
+
+#[test]
+fn references_to_type() {
+    let code = "
+pub type Wibble {
+  Wibble(Int)
+  Wobble
+}
+
+pub fn main(wibble: Wibble) -> Wibble {
+  case wibble {
+    Wibble(_) -> Wobble
+    Wobble -> Wibble(1)
+  }
+}";
+
+    assert_eq!(
+        references(TestProject::for_source(code), Position::new(6, 20), true),
+        Some(vec![
+            location("app", 1, 9, 15),
+            location("app", 6, 20, 26),
+            location("app", 6, 31, 37),
+        ])
+    );
+}
+
+#[test]
+fn references_to_constructor_without_declaration() {
+    let code = "
+pub type Wibble {
+  Wibble(Int)
+  Wobble
+}
+
+pub fn main(wibble: Wibble) -> Wibble {
+  case wibble {
+    Wibble(_) -> Wobble
+    Wobble -> Wibble(1)
+  }
+}";
+
+    assert_eq!(
+        references(TestProject::for_source(code), Position::new(8, 17), false),
+        Some(vec![location("app", 8, 17, 23), location("app", 9, 4, 10)])
+    );
+}
+
+#[test]
+fn references_to_constant_in_other_module() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.answer + wibble.answer
+}";
+    let wibble = "pub const answer = 1
+
+pub fn double() {
+  answer * 2
+}";
+
+    assert_eq!(
+        references(
+            TestProject::for_source(code).add_module("wibble", wibble),
+            Position::new(4, 9),
+            true
+        ),
+        Some(vec![
+            location("app", 4, 9, 15),
+            location("app", 4, 25, 31),
+            location("wibble", 0, 10, 16),
+            location("wibble", 3, 2, 8),
+        ])
+    );
+}
+
+#[test]
+fn no_references_for_local_variable() {
+    let code = "
+pub fn main() {
+  let x = 1
+  x
+}";
+
+    assert_eq!(
+        references(TestProject::for_source(code), Position::new(3, 2), true),
+        None
+    );
+}
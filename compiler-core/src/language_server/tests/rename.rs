@@ -0,0 +1,212 @@
+use lsp_types::{Position, PrepareRenameResponse, Range, RenameParams, TextEdit, Url};
+
+use crate::language_server::rename::CannotRename;
+
+use super::*;
+
+fn rename(
+    tester: TestProject<'_>,
+    position: Position,
+    new_name: &str,
+) -> Option<HashMap<Url, Vec<TextEdit>>> {
+    tester.at(position, |engine, param, _| {
+        let params = RenameParams {
+            text_document_position: param,
+            new_name: new_name.into(),
+            work_done_progress_params: Default::default(),
+        };
+        let response = engine.rename(params);
+
+        response.result.unwrap().and_then(|edit| edit.changes)
+    })
+}
+
+fn module_url(path: &str) -> Url {
+    Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+        format!(r"\\?\C:\src\{path}.gleam")
+    } else {
+        format!("/src/{path}.gleam")
+    }))
+    .unwrap()
+}
+
+fn edit(line: u32, start: u32, end: u32, new_text: &str) -> TextEdit {
+    TextEdit {
+        range: Range::new(Position::new(line, start), Position::new(line, end)),
+        new_text: new_text.into(),
+    }
+}
+
+#[test]
+fn rename_local_variable() {
+    let code = "
+pub fn main() {
+  let x = 1
+  x + x
+}";
+
+    assert_eq!(
+        rename(TestProject::for_source(code), Position::new(3, 2), "value"),
+        Some(HashMap::from([(
+            module_url("app"),
+            vec![
+                edit(2, 6, 7, "value"),
+                edit(3, 2, 3, "value"),
+                edit(3, 6, 7, "value"),
+            ]
+        )]))
+    );
+}
+
+#[test]
+fn rename_function_in_modules_importing_it() {
+    let code = "
+import wibble.{double}
+
+pub fn main() {
+  double() + wibble.double()
+}";
+    let wibble = "pub fn double() { 1 }";
+    let unrelated = "pub fn wibble() { 2 }";
+
+    assert_eq!(
+        rename(
+            TestProject::for_source(code)
+                .add_module("wibble", wibble)
+                .add_module("unrelated", unrelated),
+            Position::new(4, 2),
+            "twice"
+        ),
+        Some(HashMap::from([
+            (
+                module_url("app"),
+                vec![
+                    edit(1, 15, 21, "twice"),
+                    edit(4, 2, 8, "twice"),
+                    edit(4, 20, 26, "twice"),
+                ]
+            ),
+            (module_url("wibble"), vec![edit(0, 7, 13, "twice")]),
+        ]))
+    );
+}
+
+#[test]
+fn rename_with_invalid_name() {
+    let code = "
+pub fn main() {
+  let x = 1
+  x
+}";
+
+    assert_eq!(
+        rename(TestProject::for_source(code), Position::new(3, 2), "Value"),
+        None
+    );
+}
+
+#[test]
+fn dependency_functions_are_not_renamed() {
+    let code = "
+import dep.{wibble}
+
+pub fn main() {
+  wibble()
+}";
+
+    assert_eq!(
+        rename(
+            TestProject::for_source(code).add_dep_module("dep", "pub fn wibble() { 1 }"),
+            Position::new(4, 2),
+            "wobble"
+        ),
+        None
+    );
+}
+
+fn prepare_rename(
+    tester: TestProject<'_>,
+    position: Position,
+) -> Result<PrepareRenameResponse, CannotRename> {
+    tester.at(position, |engine, param, _| {
+        engine.prepare_rename(param).result.unwrap()
+    })
+}
+
+#[test]
+fn prepare_rename_label() {
+    let code = "
+pub fn main() {
+  add(amount: 1)
+}
+
+fn add(amount x: Int) -> Int {
+  x
+}";
+
+    assert_eq!(
+        prepare_rename(TestProject::for_source(code), Position::new(2, 7)),
+        Ok(PrepareRenameResponse::RangeWithPlaceholder {
+            range: Range::new(Position::new(2, 6), Position::new(2, 12)),
+            placeholder: "amount".into(),
+        })
+    );
+}
+
+#[test]
+fn prepare_rename_keyword() {
+    let code = "
+pub fn main() {
+  let x = 1
+  x
+}";
+
+    assert_eq!(
+        prepare_rename(TestProject::for_source(code), Position::new(2, 3)),
+        Err(CannotRename::Keyword("let".into()))
+    );
+}
+
+#[test]
+fn prepare_rename_dependency_function() {
+    let code = "
+import dep.{wibble}
+
+pub fn main() {
+  wibble()
+}";
+
+    assert_eq!(
+        prepare_rename(
+            TestProject::for_source(code).add_dep_module("dep", "pub fn wibble() { 1 }"),
+            Position::new(4, 2),
+        ),
+        Err(CannotRename::Dependency {
+            module: "dep".into(),
+            name: "wibble".into()
+        })
+    );
+}
+
+#[test]
+fn rename_label_in_modules_importing_it() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.add(amount: 1)
+}";
+    let wibble = "pub fn add(amount x: Int) { x }";
+
+    assert_eq!(
+        rename(
+            TestProject::for_source(code).add_module("wibble", wibble),
+            Position::new(4, 13),
+            "by"
+        ),
+        Some(HashMap::from([
+            (module_url("app"), vec![edit(4, 13, 19, "by")]),
+            (module_url("wibble"), vec![edit(0, 11, 17, "by")]),
+        ]))
+    );
+}
@@ -0,0 +1,97 @@
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::{
+    io::FileSystemWriter,
+    language_server::{files::FileSystemProxy, router::Router},
+    line_numbers::PositionEncoding,
+};
+
+use super::*;
+
+const CONFIG: &str = "name = \"package\"\nversion = \"1.0.0\"\n";
+
+fn workspace() -> Router<LanguageServerTestIO, LanguageServerTestIO> {
+    let io = LanguageServerTestIO::new();
+    io.write(Utf8Path::new("/workspace/one/gleam.toml"), CONFIG)
+        .unwrap();
+    io.write(Utf8Path::new("/workspace/two/gleam.toml"), CONFIG)
+        .unwrap();
+    Router::new(
+        io.clone(),
+        FileSystemProxy::new(io),
+        PositionEncoding::Utf16,
+        Default::default(),
+    )
+}
+
+fn has_project(
+    router: &mut Router<LanguageServerTestIO, LanguageServerTestIO>,
+    path: &str,
+) -> bool {
+    router
+        .project_for_path(Utf8PathBuf::from(path))
+        .unwrap()
+        .is_some()
+}
+
+#[test]
+fn engine_for_each_project_of_workspace() {
+    let mut router = workspace();
+
+    assert!(has_project(&mut router, "/workspace/one/src/app.gleam"));
+    assert!(has_project(&mut router, "/workspace/two/src/app.gleam"));
+    assert!(has_project(
+        &mut router,
+        "/workspace/one/test/app_test.gleam"
+    ));
+    assert!(!has_project(&mut router, "/workspace/app.gleam"));
+    assert_eq!(router.projects().count(), 2);
+}
+
+#[test]
+fn engines_of_removed_folder_are_discarded() {
+    let mut router = workspace();
+    assert!(has_project(&mut router, "/workspace/one/src/app.gleam"));
+    assert!(has_project(&mut router, "/workspace/two/src/app.gleam"));
+
+    router.delete_engines_in(Utf8Path::new("/workspace/one"));
+    assert_eq!(router.projects().count(), 1);
+
+    // The engine is created again if the project is used after
+    assert!(has_project(&mut router, "/workspace/one/src/app.gleam"));
+    assert_eq!(router.projects().count(), 2);
+}
+
+#[test]
+fn projects_depending_on_a_path_dependency() {
+    let mut io = LanguageServerTestIO::new();
+    io.write(Utf8Path::new("/workspace/app/gleam.toml"), CONFIG)
+        .unwrap();
+    io.write(Utf8Path::new("/workspace/lib/gleam.toml"), CONFIG)
+        .unwrap();
+    io.manifest.packages.push(ManifestPackage {
+        name: "lib".into(),
+        version: Version::new(1, 0, 0),
+        build_tools: vec!["gleam".into()],
+        otp_app: None,
+        requirements: vec![],
+        source: ManifestPackageSource::Local {
+            path: Utf8PathBuf::from("/workspace/lib"),
+        },
+    });
+    let mut router = Router::new(
+        io.clone(),
+        FileSystemProxy::new(io),
+        PositionEncoding::Utf16,
+        Default::default(),
+    );
+    assert!(has_project(&mut router, "/workspace/app/src/app.gleam"));
+
+    assert_eq!(
+        router.projects_depending_on(Utf8Path::new("/workspace/lib/src/lib.gleam")),
+        vec![Utf8PathBuf::from("/workspace/app")]
+    );
+    assert!(router
+        .projects_depending_on(Utf8Path::new("/workspace/app/src/app.gleam"))
+        .is_empty());
+}
@@ -0,0 +1,144 @@
+use lsp_types::{
+    Position, Range, SemanticToken, SemanticTokensParams, SemanticTokensRangeParams,
+    SemanticTokensRangeResult, SemanticTokensResult,
+};
+
+use super::*;
+
+/// The line, start, length, type, and modifiers of each token.
+type Token = (u32, u32, u32, u32, u32);
+
+fn decode(tokens: Vec<SemanticToken>) -> Vec<Token> {
+    let mut line = 0;
+    let mut start = 0;
+    tokens
+        .into_iter()
+        .map(|token| {
+            if token.delta_line == 0 {
+                start += token.delta_start;
+            } else {
+                line += token.delta_line;
+                start = token.delta_start;
+            }
+            (
+                line,
+                start,
+                token.length,
+                token.token_type,
+                token.token_modifiers_bitset,
+            )
+        })
+        .collect()
+}
+
+fn semantic_tokens(tester: TestProject<'_>) -> Vec<Token> {
+    tester.at(Position::new(0, 0), |engine, param, _| {
+        let params = SemanticTokensParams {
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            text_document: param.text_document,
+        };
+        match engine.semantic_tokens_full(params).result.unwrap() {
+            Some(SemanticTokensResult::Tokens(tokens)) => decode(tokens.data),
+            result => panic!("unexpected result {result:?}"),
+        }
+    })
+}
+
+fn semantic_tokens_in(tester: TestProject<'_>, range: Range) -> Vec<Token> {
+    tester.at(Position::new(0, 0), |engine, param, _| {
+        let params = SemanticTokensRangeParams {
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            text_document: param.text_document,
+            range,
+        };
+        match engine.semantic_tokens_range(params).result.unwrap() {
+            Some(SemanticTokensRangeResult::Tokens(tokens)) => decode(tokens.data),
+            result => panic!("unexpected result {result:?}"),
+        }
+    })
+}
+
+const MODULE: u32 = 0;
+const TYPE: u32 = 1;
+const CONSTRUCTOR: u32 = 2;
+const FIELD: u32 = 3;
+const LABEL: u32 = 4;
+const FUNCTION: u32 = 5;
+
+const CODE: &str = "
+import wibble
+
+pub type Pet {
+  Dog(name: String)
+}
+
+pub fn main(pet: Pet) -> String {
+  let Dog(name: n) = pet
+  wibble.greet(n) <> pet.name
+}";
+
+const WIBBLE: &str = "pub fn greet(name: String) -> String { name }";
+
+#[test]
+fn tokens_for_module() {
+    assert_eq!(
+        semantic_tokens(TestProject::for_source(CODE).add_module("wibble", WIBBLE)),
+        vec![
+            (3, 9, 3, TYPE, 0),
+            (4, 2, 3, CONSTRUCTOR, 0),
+            (4, 6, 4, FIELD, 0),
+            (4, 12, 6, TYPE, 0),
+            (7, 7, 4, FUNCTION, 0),
+            (7, 17, 3, TYPE, 0),
+            (7, 25, 6, TYPE, 0),
+            (8, 6, 3, CONSTRUCTOR, 0),
+            (8, 10, 4, LABEL, 0),
+            (9, 2, 6, MODULE, 0),
+            (9, 9, 5, FUNCTION, 0),
+            (9, 25, 4, FIELD, 0),
+        ]
+    );
+}
+
+#[test]
+fn tokens_for_range_of_module() {
+    let range = Range::new(Position::new(8, 0), Position::new(10, 0));
+
+    assert_eq!(
+        semantic_tokens_in(
+            TestProject::for_source(CODE).add_module("wibble", WIBBLE),
+            range
+        ),
+        vec![
+            (8, 6, 3, CONSTRUCTOR, 0),
+            (8, 10, 4, LABEL, 0),
+            (9, 2, 6, MODULE, 0),
+            (9, 9, 5, FUNCTION, 0),
+            (9, 25, 4, FIELD, 0),
+        ]
+    );
+}
+
+#[test]
+fn deprecated_functions_are_marked() {
+    let code = "
+pub fn main() {
+  old()
+}
+
+@deprecated(\"Use something else\")
+fn old() {
+  1
+}";
+
+    assert_eq!(
+        semantic_tokens(TestProject::for_source(code)),
+        vec![
+            (1, 7, 4, FUNCTION, 0),
+            (2, 2, 3, FUNCTION, 1),
+            (6, 3, 3, FUNCTION, 0)
+        ]
+    );
+}
@@ -0,0 +1,115 @@
+use lsp_types::{
+    ParameterInformation, ParameterLabel, Position, SignatureHelp, SignatureHelpParams,
+    SignatureInformation,
+};
+
+use super::*;
+
+fn signature_help(tester: TestProject<'_>, position: Position) -> Option<SignatureHelp> {
+    tester.at(position, |engine, param, _| {
+        let params = SignatureHelpParams {
+            context: None,
+            text_document_position_params: param,
+            work_done_progress_params: Default::default(),
+        };
+        let response = engine.signature_help(params);
+
+        response.result.unwrap()
+    })
+}
+
+fn signature(label: &str, parameters: &[[u32; 2]], active_parameter: u32) -> SignatureHelp {
+    let parameters = parameters
+        .iter()
+        .map(|offsets| ParameterInformation {
+            label: ParameterLabel::LabelOffsets(*offsets),
+            documentation: None,
+        })
+        .collect();
+    SignatureHelp {
+        signatures: vec![SignatureInformation {
+            label: label.into(),
+            documentation: None,
+            parameters: Some(parameters),
+            active_parameter: None,
+        }],
+        active_signature: Some(0),
+        active_parameter: Some(active_parameter),
+    }
+}
+
+#[test]
+fn signature_help_for_labelled_argument() {
+    let code = "
+pub fn main() {
+  add(1, to: 2)
+}
+
+fn add(x: Int, to y: Int) -> Int {
+  x + y
+}";
+
+    assert_eq!(
+        signature_help(TestProject::for_source(code), Position::new(2, 9)),
+        Some(signature("add(Int, to: Int) -> Int", &[[4, 7], [9, 16]], 1))
+    );
+}
+
+#[test]
+fn signature_help_for_function_of_other_module() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.greet(\"Jak\", greeting: \"Hi\")
+}";
+    let wibble = "pub fn greet(name: String, greeting greeting: String) -> String {
+  greeting <> name
+}";
+
+    assert_eq!(
+        signature_help(
+            TestProject::for_source(code).add_module("wibble", wibble),
+            Position::new(4, 16)
+        ),
+        Some(signature(
+            "wibble.greet(String, greeting: String) -> String",
+            &[[13, 19], [21, 37]],
+            0
+        ))
+    );
+}
+
+#[test]
+fn signature_help_for_innermost_call() {
+    let code = "
+pub fn main() {
+  add(inc(1), 2)
+}
+
+fn add(x: Int, y: Int) -> Int {
+  x + y
+}
+
+fn inc(x: Int) -> Int {
+  x + 1
+}";
+
+    assert_eq!(
+        signature_help(TestProject::for_source(code), Position::new(2, 10)),
+        Some(signature("inc(Int) -> Int", &[[4, 7]], 0))
+    );
+}
+
+#[test]
+fn no_signature_help_outside_of_call() {
+    let code = "
+pub fn main() {
+  1
+}";
+
+    assert_eq!(
+        signature_help(TestProject::for_source(code), Position::new(2, 2)),
+        None
+    );
+}
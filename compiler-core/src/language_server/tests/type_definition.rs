@@ -0,0 +1,103 @@
+use lsp_types::{GotoDefinitionParams, Location, Position, Range, Url};
+
+use super::*;
+
+fn type_definition(tester: TestProject<'_>, position: Position) -> Option<Vec<Location>> {
+    tester.at(position, |engine, param, _| {
+        let params = GotoDefinitionParams {
+            text_document_position_params: param,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine.goto_type_definition(params).result.unwrap()
+    })
+}
+
+fn implementation(tester: TestProject<'_>, position: Position) -> Option<Vec<Location>> {
+    tester.at(position, |engine, param, _| {
+        let params = GotoDefinitionParams {
+            text_document_position_params: param,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine.goto_implementation(params).result.unwrap()
+    })
+}
+
+fn location(module: &str, line: u32, start: u32, end: u32) -> Location {
+    let uri = Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+        format!(r"\\?\C:\src\{module}.gleam")
+    } else {
+        format!("/src/{module}.gleam")
+    }))
+    .unwrap();
+    Location {
+        uri,
+        range: Range::new(Position::new(line, start), Position::new(line, end)),
+    }
+}
+
+const CODE: &str = "
+import wibble.{type Pet}
+
+pub fn main(pets: List(Pet)) {
+  pets
+}";
+
+const WIBBLE: &str = "pub type Pet {
+  Dog
+  Cat
+}";
+
+#[test]
+fn type_definition_of_variable() {
+    assert_eq!(
+        type_definition(
+            TestProject::for_source(CODE).add_module("wibble", WIBBLE),
+            Position::new(4, 2)
+        ),
+        Some(vec![location("wibble", 0, 0, 12)])
+    );
+}
+
+#[test]
+fn prelude_types_have_no_type_definition() {
+    let code = "
+pub fn main() {
+  let x = 1
+  x
+}";
+
+    assert_eq!(
+        type_definition(TestProject::for_source(code), Position::new(3, 2)),
+        Some(vec![])
+    );
+}
+
+#[test]
+fn implementation_of_type_in_annotation() {
+    assert_eq!(
+        implementation(
+            TestProject::for_source(CODE).add_module("wibble", WIBBLE),
+            Position::new(3, 23)
+        ),
+        Some(vec![
+            location("wibble", 1, 2, 5),
+            location("wibble", 2, 2, 5)
+        ])
+    );
+}
+
+#[test]
+fn implementation_of_custom_type_definition() {
+    let code = "
+pub type Pet {
+  Dog
+  Cat
+}";
+
+    assert_eq!(
+        implementation(TestProject::for_source(code), Position::new(1, 10)),
+        Some(vec![location("app", 2, 2, 5), location("app", 3, 2, 5)])
+    );
+}
@@ -0,0 +1,92 @@
+use lsp_types::{Location, OneOf, Position, Range, SymbolKind, Url, WorkspaceSymbol};
+
+use super::*;
+
+const CODE: &str = "
+pub type Pet {
+  Dog
+}
+
+pub fn to_string(pet: Pet) -> String {
+  \"dog\"
+}
+
+const default_name = \"Rex\"
+";
+
+const WIBBLE: &str = "pub fn list_to_set() {
+  1
+}";
+
+/// The symbols matching the query, with the closest matches first.
+fn workspace_symbols(query: &str) -> Vec<WorkspaceSymbol> {
+    let tester = TestProject::for_source(CODE).add_module("wibble", WIBBLE);
+    tester.at(Position::new(0, 0), |engine, _, _| {
+        let mut found = engine.workspace_symbols(query);
+        found.sort_by(|(score, symbol), (other_score, other)| {
+            score
+                .cmp(other_score)
+                .then_with(|| symbol.name.cmp(&other.name))
+        });
+        found.into_iter().map(|(_, symbol)| symbol).collect()
+    })
+}
+
+fn names(symbols: &[WorkspaceSymbol]) -> Vec<(&str, SymbolKind, Option<&str>)> {
+    symbols
+        .iter()
+        .map(|symbol| {
+            (
+                symbol.name.as_str(),
+                symbol.kind,
+                symbol.container_name.as_deref(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn all_definitions_are_found_without_query() {
+    assert_eq!(
+        names(&workspace_symbols("")),
+        vec![
+            ("Dog", SymbolKind::CONSTRUCTOR, Some("app")),
+            ("Pet", SymbolKind::STRUCT, Some("app")),
+            ("default_name", SymbolKind::CONSTANT, Some("app")),
+            ("list_to_set", SymbolKind::FUNCTION, Some("wibble")),
+            ("to_string", SymbolKind::FUNCTION, Some("app")),
+        ]
+    );
+}
+
+#[test]
+fn closest_matches_are_first() {
+    assert_eq!(
+        names(&workspace_symbols("ts")),
+        vec![
+            ("to_string", SymbolKind::FUNCTION, Some("app")),
+            ("list_to_set", SymbolKind::FUNCTION, Some("wibble")),
+        ]
+    );
+}
+
+#[test]
+fn symbols_are_located_at_their_name() {
+    let uri = Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+        r"\\?\C:\src\app.gleam"
+    } else {
+        "/src/app.gleam"
+    }))
+    .unwrap();
+
+    assert_eq!(
+        workspace_symbols("dog")
+            .into_iter()
+            .map(|symbol| symbol.location)
+            .collect::<Vec<_>>(),
+        vec![OneOf::Left(Location {
+            uri,
+            range: Range::new(Position::new(2, 2), Position::new(2, 5)),
+        })]
+    );
+}
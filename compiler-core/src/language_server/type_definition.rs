@@ -0,0 +1,81 @@
+//! Finding the types of values and the types written in annotations, for
+//! going to the definitions of types and to the constructors of types.
+
+use ecow::EcoString;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        TypeAst, TypeAstConstructor, TypedModule,
+    },
+    type_::{prelude::is_prelude_module, Type, TypeVar},
+};
+
+/// The module and name of each named type a type is made of, such as `Pet`
+/// for `List(Pet)`, in the order they appear in it. Prelude types are left
+/// out as they aren't defined in a module with a source.
+pub fn named_types(type_: &Type) -> Vec<(EcoString, EcoString)> {
+    let mut types = vec![];
+    add_named_types(type_, &mut types);
+    types
+}
+
+fn add_named_types(type_: &Type, types: &mut Vec<(EcoString, EcoString)>) {
+    match type_ {
+        Type::Named {
+            module, name, args, ..
+        } => {
+            let named = (module.clone(), name.clone());
+            if !is_prelude_module(module) && !types.contains(&named) {
+                types.push(named);
+            }
+            for arg in args {
+                add_named_types(arg, types);
+            }
+        }
+        Type::Fn { args, retrn } => {
+            for arg in args {
+                add_named_types(arg, types);
+            }
+            add_named_types(retrn, types);
+        }
+        Type::Tuple { elems } => {
+            for elem in elems {
+                add_named_types(elem, types);
+            }
+        }
+        Type::Var { type_ } => match &*type_.borrow() {
+            TypeVar::Link { type_ } => add_named_types(type_, types),
+            TypeVar::Unbound { .. } | TypeVar::Generic { .. } => (),
+        },
+    }
+}
+
+/// The innermost named type written in an annotation at the position, such
+/// as `Pet` for a position on it in `List(Pet)`.
+pub fn annotation_at(module: &TypedModule, byte_index: u32) -> Option<&TypeAstConstructor> {
+    let mut finder = AnnotationFinder {
+        byte_index,
+        found: None,
+    };
+    finder.visit_typed_module(module);
+    finder.found
+}
+
+struct AnnotationFinder<'a> {
+    byte_index: u32,
+    found: Option<&'a TypeAstConstructor>,
+}
+
+impl<'ast> Visit<'ast> for AnnotationFinder<'ast> {
+    fn visit_type_ast(&mut self, type_ast: &'ast TypeAst) {
+        if let TypeAst::Constructor(constructor) = type_ast {
+            if constructor.location.contains(self.byte_index) {
+                self.found = Some(constructor);
+            }
+        }
+        // The arguments of the type are visited afterwards, so the innermost
+        // type is found last.
+        visit::visit_type_ast(self, type_ast);
+    }
+}
@@ -0,0 +1,321 @@
+//! Rewriting a `use` expression into the call it stands for, with the rest of
+//! the block given to it as an anonymous function, and rewriting a call given
+//! an anonymous function as its last argument into a `use` expression.
+//!
+//! The typed AST has no `use` expressions, so the code of the call is parsed
+//! again and printed with the formatter once rewritten.
+
+use std::sync::Arc;
+
+use ecow::EcoString;
+use itertools::Itertools;
+use vec1::Vec1;
+
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        Arg, ArgNames, CallArg, Pattern, SrcSpan, Statement, TypeAst, TypedArg, TypedExpr,
+        TypedFunction, TypedStatement, UntypedExpr, UntypedStatement, Use, UseAssignment,
+    },
+    build::Module,
+    format::{self, FormatOptions},
+    parse::{self, extra::ModuleExtra},
+    type_::Type,
+};
+
+use super::rename::Edit;
+
+/// The edit rewriting the `use` expression at the position into a call taking
+/// the rest of the block as its last argument.
+pub fn use_to_callback(module: &Module, byte_index: u32, options: FormatOptions) -> Option<Edit> {
+    let location = find_call(module, byte_index, true)?;
+    let statements = parse(module, location)?;
+
+    let mut statements = statements.into_iter();
+    let Some(Statement::Use(use_)) = statements.next() else {
+        return None;
+    };
+    let body = Vec1::try_from_vec(statements.collect()).ok()?;
+    let arguments = use_
+        .assignments
+        .into_iter()
+        .map(|assignment| {
+            let names = match assignment.pattern {
+                Pattern::Variable { name, .. } => ArgNames::Named { name },
+                Pattern::Discard { name, .. } => ArgNames::Discard { name },
+                // Other patterns would have to be matched with a `let` in the
+                // body of the function.
+                _ => return None,
+            };
+            Some(Arg {
+                names,
+                location: assignment.location,
+                annotation: assignment.annotation,
+                type_: (),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let callback_location = SrcSpan::new(body.first().location().start, body.last().location().end);
+    let callback = CallArg {
+        label: None,
+        location: callback_location,
+        value: UntypedExpr::Fn {
+            location: callback_location,
+            is_capture: false,
+            arguments,
+            body,
+            return_annotation: None,
+        },
+        implicit: false,
+        label_shorthand: false,
+    };
+    let (fun, mut arguments) = match *use_.call {
+        UntypedExpr::Call { fun, arguments, .. } => (fun, arguments),
+        fun => (Box::new(fun), vec![]),
+    };
+    arguments.push(callback);
+    let call = UntypedExpr::Call {
+        location: SrcSpan::new(use_.location.start, callback_location.end),
+        fun,
+        arguments,
+    };
+
+    let new_text = print(
+        module,
+        location,
+        &Vec1::new(Statement::Expression(call)),
+        options,
+    )?;
+    Some(Edit { location, new_text })
+}
+
+/// The edit rewriting the call at the position, which must be the last
+/// statement of its block, into a `use` expression followed by the body of
+/// the anonymous function given as its last argument.
+pub fn callback_to_use(module: &Module, byte_index: u32, options: FormatOptions) -> Option<Edit> {
+    let location = find_call(module, byte_index, false)?;
+    let statements = parse(module, location)?;
+    if statements.len() != 1 {
+        return None;
+    }
+    let Statement::Expression(UntypedExpr::Call {
+        location: call_location,
+        fun,
+        mut arguments,
+    }) = statements.into_vec().pop()?
+    else {
+        return None;
+    };
+
+    let callback = arguments.pop()?;
+    let UntypedExpr::Fn {
+        location: callback_location,
+        is_capture: false,
+        arguments: parameters,
+        body,
+        // A `use` expression can't give the return type of its callback
+        return_annotation: None,
+    } = callback.value
+    else {
+        return None;
+    };
+    if callback.label.is_some() {
+        return None;
+    }
+    let assignments = parameters
+        .into_iter()
+        .map(|parameter| {
+            let pattern = match parameter.names {
+                ArgNames::Named { name } => Pattern::Variable {
+                    location: parameter.location,
+                    name,
+                    type_: (),
+                },
+                ArgNames::Discard { name } => Pattern::Discard {
+                    name,
+                    location: parameter.location,
+                    type_: (),
+                },
+                ArgNames::LabelledDiscard { .. } | ArgNames::NamedLabelled { .. } => return None,
+            };
+            Some(UseAssignment {
+                location: parameter.location,
+                pattern,
+                annotation: parameter.annotation,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let use_location = SrcSpan::new(call_location.start, callback_location.start);
+    let use_ = Use {
+        location: use_location,
+        call: Box::new(UntypedExpr::Call {
+            location: use_location,
+            fun,
+            arguments,
+        }),
+        assignments,
+    };
+    let mut statements = Vec1::new(Statement::Use(use_));
+    statements.extend(body);
+
+    let new_text = print(module, location, &statements, options)?;
+    Some(Edit { location, new_text })
+}
+
+/// The location of the call the position is on that is given an anonymous
+/// function as its last argument, either written explicitly or by a `use`
+/// expression.
+fn find_call(module: &Module, byte_index: u32, is_use: bool) -> Option<SrcSpan> {
+    let mut finder = CallFinder {
+        byte_index,
+        is_use,
+        found: None,
+    };
+    finder.visit_typed_module(&module.ast);
+    finder.found
+}
+
+struct CallFinder {
+    byte_index: u32,
+    is_use: bool,
+    found: Option<SrcSpan>,
+}
+
+impl CallFinder {
+    /// Only a call that is the last statement of a block can be rewritten, as
+    /// the callback of a `use` expression is the rest of its block.
+    fn check_last(&mut self, statements: &[TypedStatement]) {
+        let Some(Statement::Expression(TypedExpr::Call { location, args, .. })) = statements.last()
+        else {
+            return;
+        };
+        let Some(callback) = args.last() else {
+            return;
+        };
+        let is_callback = matches!(
+            callback.value,
+            TypedExpr::Fn {
+                is_capture: false,
+                ..
+            }
+        );
+        // The position must be on the call rather than in its callback
+        let is_on_call =
+            location.start <= self.byte_index && self.byte_index < callback.location.start;
+        if is_callback && is_on_call && callback.label.is_none() && callback.implicit == self.is_use
+        {
+            self.found = Some(*location);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CallFinder {
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        self.check_last(&function.body);
+        visit::visit_typed_function(self, function);
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        if let TypedExpr::Block { statements, .. } = expr {
+            self.check_last(statements);
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'ast SrcSpan,
+        typ: &'ast Arc<Type>,
+        is_capture: &'ast bool,
+        args: &'ast [TypedArg],
+        body: &'ast [TypedStatement],
+        return_annotation: &'ast Option<TypeAst>,
+    ) {
+        self.check_last(body);
+        visit::visit_typed_expr_fn(
+            self,
+            location,
+            typ,
+            is_capture,
+            args,
+            body,
+            return_annotation,
+        );
+    }
+}
+
+/// Parses the statements at the location in the code of the module. Their
+/// locations start from the start of the location.
+fn parse(module: &Module, location: SrcSpan) -> Option<Vec1<UntypedStatement>> {
+    let code = module
+        .code
+        .get(location.start as usize..location.end as usize)?;
+    parse::parse_statement_sequence(code).ok()
+}
+
+/// Prints the statements parsed from the code at the location, with the
+/// comments and empty lines of the module that are there. Nothing is printed
+/// if any of the comments would be lost.
+fn print(
+    module: &Module,
+    location: SrcSpan,
+    statements: &Vec1<UntypedStatement>,
+    options: FormatOptions,
+) -> Option<String> {
+    let code: EcoString = module
+        .code
+        .get(location.start as usize..location.end as usize)?
+        .into();
+    let is_within = |span: &&SrcSpan| location.start <= span.start && span.end <= location.end;
+    let comments = module
+        .extra
+        .comments
+        .iter()
+        .filter(is_within)
+        .map(|span| SrcSpan::new(span.start - location.start, span.end - location.start))
+        .collect::<Vec<_>>();
+    let extra = ModuleExtra {
+        comments: comments.clone(),
+        empty_lines: module
+            .extra
+            .empty_lines
+            .iter()
+            .filter(|position| location.start <= **position && **position < location.end)
+            .map(|position| position - location.start)
+            .collect(),
+        ..ModuleExtra::new()
+    };
+
+    // The lines after the first are indented to the column the code starts
+    // at, so it is printed as if it was at the start of a line.
+    let before = module.code.get(..location.start as usize)?;
+    let column = before.len() - before.rfind('\n').map_or(0, |index| index + 1);
+    let options = FormatOptions {
+        line_width: options.line_width - column as isize,
+        ..options
+    };
+    let text = format::statements(statements, &code, &extra, options);
+
+    let is_commented = comments.iter().all(|span| {
+        code.get(span.start as usize..span.end as usize)
+            .is_some_and(|comment| text.contains(comment))
+    });
+    if !is_commented {
+        return None;
+    }
+    let padding = " ".repeat(column);
+    let text = text
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            if index == 0 || line.is_empty() {
+                line.to_string()
+            } else {
+                format!("{padding}{line}")
+            }
+        })
+        .join("\n");
+    Some(text)
+}
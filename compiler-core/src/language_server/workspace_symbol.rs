@@ -0,0 +1,95 @@
+//! The definitions of the modules of a project, for editors to search by
+//! name with `workspace/symbol`.
+
+use ecow::EcoString;
+use lsp_types as lsp;
+
+use crate::ast::{Definition, SrcSpan, TypedModule};
+
+use super::reference::{name_at_start, name_in};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: EcoString,
+    pub kind: lsp::SymbolKind,
+    /// The location of the name of the definition.
+    pub location: SrcSpan,
+    pub deprecated: bool,
+}
+
+/// The functions, constants, types, and record constructors a module defines,
+/// whether they are public or not.
+pub fn symbols(module: &TypedModule, code: &str) -> Vec<Symbol> {
+    let mut symbols = vec![];
+    let mut add = |name: &EcoString, kind, location: Option<SrcSpan>, deprecated| {
+        if let Some(location) = location {
+            symbols.push(Symbol {
+                name: name.clone(),
+                kind,
+                location,
+                deprecated,
+            });
+        }
+    };
+
+    for definition in &module.definitions {
+        match definition {
+            Definition::Function(function) => add(
+                &function.name,
+                lsp::SymbolKind::FUNCTION,
+                name_in(code, function.location, &function.name),
+                function.deprecation.is_deprecated(),
+            ),
+            Definition::ModuleConstant(constant) => add(
+                &constant.name,
+                lsp::SymbolKind::CONSTANT,
+                name_in(code, constant.location, &constant.name),
+                constant.deprecation.is_deprecated(),
+            ),
+            Definition::CustomType(custom_type) => {
+                let deprecated = custom_type.deprecation.is_deprecated();
+                add(
+                    &custom_type.name,
+                    lsp::SymbolKind::STRUCT,
+                    name_in(code, custom_type.location, &custom_type.name),
+                    deprecated,
+                );
+                for constructor in &custom_type.constructors {
+                    add(
+                        &constructor.name,
+                        lsp::SymbolKind::CONSTRUCTOR,
+                        Some(name_at_start(constructor.location, &constructor.name)),
+                        deprecated,
+                    );
+                }
+            }
+            Definition::TypeAlias(alias) => add(
+                &alias.alias,
+                lsp::SymbolKind::STRUCT,
+                name_in(code, alias.location, &alias.alias),
+                alias.deprecation.is_deprecated(),
+            ),
+            Definition::Import(_) => (),
+        }
+    }
+    symbols
+}
+
+/// How well a name matches a query, lower being better, if the characters of
+/// the query appear in the name in order, ignoring case. Each character of
+/// the name skipped before or between them makes the match worse, so
+/// `to_string` matches `ts` better than `list_to_set` does.
+pub fn fuzzy_score(query: &str, name: &str) -> Option<usize> {
+    let mut name = name
+        .chars()
+        .map(|char| char.to_ascii_lowercase())
+        .enumerate();
+    let mut score = 0;
+    let mut next = 0;
+    for char in query.chars().map(|char| char.to_ascii_lowercase()) {
+        let (index, _) = name.find(|(_, name_char)| *name_char == char)?;
+        score += index - next;
+        next = index + 1;
+    }
+    Some(score)
+}
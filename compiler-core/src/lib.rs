@@ -75,6 +75,7 @@ pub mod io;
 pub mod javascript;
 pub mod language_server;
 pub mod line_numbers;
+pub mod lint;
 pub mod manifest;
 pub mod metadata;
 pub mod package_interface;
@@ -82,6 +83,7 @@ pub mod parse;
 pub mod paths;
 pub mod pretty;
 pub mod requirement;
+pub mod shell;
 pub mod type_;
 pub mod uid;
 pub mod version;
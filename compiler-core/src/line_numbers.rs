@@ -1,7 +1,34 @@
+use std::collections::BTreeMap;
+
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
 pub struct LineNumbers {
     pub line_starts: Vec<u32>,
     pub length: u32,
+    /// The number of bytes taken by each character that is more than one byte
+    /// long in UTF-8, by the byte index the character starts at.
+    pub multi_byte_characters: BTreeMap<u32, u8>,
+}
+
+/// The unit that the column of a position is counted in. Language server
+/// clients can count columns in bytes, UTF-16 code units, or characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PositionEncoding {
+    Utf8,
+    #[default]
+    Utf16,
+    Utf32,
+}
+
+impl PositionEncoding {
+    /// The number of code units taken by a character that is this many bytes
+    /// long in UTF-8.
+    fn code_units(self, utf8_length: u8) -> u32 {
+        match self {
+            PositionEncoding::Utf8 => utf8_length as u32,
+            PositionEncoding::Utf16 if utf8_length == 4 => 2,
+            PositionEncoding::Utf16 | PositionEncoding::Utf32 => 1,
+        }
+    }
 }
 
 impl LineNumbers {
@@ -11,6 +38,11 @@ impl LineNumbers {
             line_starts: std::iter::once(0)
                 .chain(src.match_indices('\n').map(|(i, _)| i as u32 + 1))
                 .collect(),
+            multi_byte_characters: src
+                .char_indices()
+                .filter(|(_, character)| character.len_utf8() > 1)
+                .map(|(i, character)| (i as u32, character.len_utf8() as u8))
+                .collect(),
         }
     }
 
@@ -22,26 +54,102 @@ impl LineNumbers {
             + 1
     }
 
-    // TODO: handle unicode characters that may be more than 1 byte in width
+    /// Get the line and column number for a byte index, with the column
+    /// counted in characters.
     pub fn line_and_column_number(&self, byte_index: u32) -> LineColumn {
+        self.line_and_column_number_in(byte_index, PositionEncoding::Utf32)
+    }
+
+    /// Get the line and column number for a byte index, with the column
+    /// counted in code units of the given encoding.
+    pub fn line_and_column_number_in(
+        &self,
+        byte_index: u32,
+        encoding: PositionEncoding,
+    ) -> LineColumn {
         let line = self.line_number(byte_index);
-        let column = byte_index
-            - self
-                .line_starts
-                .get(line as usize - 1)
-                .copied()
-                .unwrap_or_default()
-            + 1;
-        LineColumn { line, column }
+        let line_start = self
+            .line_starts
+            .get(line as usize - 1)
+            .copied()
+            .unwrap_or_default();
+        let column = self
+            .multi_byte_characters
+            .range(line_start..byte_index)
+            .fold(byte_index - line_start, |column, (_, length)| {
+                column.saturating_sub(*length as u32) + encoding.code_units(*length)
+            });
+        LineColumn {
+            line,
+            column: column + 1,
+        }
     }
 
-    // TODO: handle unicode characters that may be more than 1 byte in width
-    /// 0 indexed line and character to byte index
-    pub fn byte_index(&self, line: u32, character: u32) -> u32 {
-        match self.line_starts.get((line) as usize) {
-            Some(line_index) => *line_index + character,
-            None => self.length,
+    /// 0 indexed line and character to byte index, with the character
+    /// counted in code units of the given encoding. A character past the end
+    /// of the line is moved back to the end of the line.
+    pub fn byte_index(&self, line: u32, character: u32, encoding: PositionEncoding) -> u32 {
+        let Some(line_start) = self.line_starts.get(line as usize) else {
+            return self.length;
+        };
+        let line_end = self
+            .line_starts
+            .get(line as usize + 1)
+            .map_or(self.length, |next_line_start| next_line_start - 1);
+
+        let mut byte_index = *line_start;
+        let mut remaining = character;
+        for (start, length) in self.multi_byte_characters.range(line_start..&line_end) {
+            let single_byte_characters = start - byte_index;
+            if remaining <= single_byte_characters {
+                break;
+            }
+            remaining -= single_byte_characters;
+            byte_index = *start;
+
+            // A position in the middle of a character is moved to its start.
+            let code_units = encoding.code_units(*length);
+            if remaining < code_units {
+                return byte_index;
+            }
+            remaining -= code_units;
+            byte_index += *length as u32;
         }
+        (byte_index + remaining).min(line_end)
+    }
+
+    /// Updates the line numbers for the source having the bytes from `start`
+    /// to `end` replaced with `text`, without going over the rest of the
+    /// source again.
+    pub fn edit(&mut self, start: u32, end: u32, text: &str) {
+        let inserted = LineNumbers::new(text);
+        let moved = |index: u32| index - (end - start) + inserted.length;
+
+        // A line starts after each newline, so the lines starting in
+        // `start + 1..=end` began in the replaced text.
+        let kept_lines = self.line_starts.partition_point(|line| *line <= start);
+        let moved_lines = self.line_starts.partition_point(|line| *line <= end);
+        let later_lines = self.line_starts.split_off(moved_lines);
+        self.line_starts.truncate(kept_lines);
+        self.line_starts.extend(
+            (inserted.line_starts.iter().skip(1))
+                .map(|line| line + start)
+                .chain(later_lines.into_iter().map(moved)),
+        );
+
+        let mut later_characters = self.multi_byte_characters.split_off(&start);
+        let later_characters = later_characters.split_off(&end);
+        self.multi_byte_characters.extend(
+            (inserted.multi_byte_characters.iter())
+                .map(|(index, length)| (index + start, *length))
+                .chain(
+                    later_characters
+                        .into_iter()
+                        .map(|(index, length)| (moved(index), length)),
+                ),
+        );
+
+        self.length = moved(self.length);
     }
 }
 
@@ -55,10 +163,99 @@ pub fn main() {
 "#;
     let line_numbers = LineNumbers::new(src);
 
-    assert_eq!(line_numbers.byte_index(0, 0), 0);
-    assert_eq!(line_numbers.byte_index(0, 4), 4);
-    assert_eq!(line_numbers.byte_index(100, 1), src.len() as u32);
-    assert_eq!(line_numbers.byte_index(2, 1), 18);
+    assert_eq!(line_numbers.byte_index(0, 0, PositionEncoding::Utf16), 0);
+    assert_eq!(line_numbers.byte_index(0, 4, PositionEncoding::Utf16), 4);
+    assert_eq!(
+        line_numbers.byte_index(100, 1, PositionEncoding::Utf16),
+        src.len() as u32
+    );
+    assert_eq!(line_numbers.byte_index(2, 1, PositionEncoding::Utf16), 18);
+}
+
+#[test]
+fn byte_index_with_multi_byte_characters() {
+    // `é` is 2 bytes long in UTF-8 and `🐙` is 4 bytes long, taking 2 code
+    // units in UTF-16.
+    let src = "x\nlet é🐙 = 1\n";
+    let line_numbers = LineNumbers::new(src);
+    let equals = src.find('=').expect("equals") as u32;
+
+    assert_eq!(
+        line_numbers.byte_index(1, 8, PositionEncoding::Utf16),
+        equals
+    );
+    assert_eq!(
+        line_numbers.byte_index(1, 7, PositionEncoding::Utf32),
+        equals
+    );
+    assert_eq!(
+        line_numbers.byte_index(1, 11, PositionEncoding::Utf8),
+        equals
+    );
+    // In the middle of `🐙`
+    assert_eq!(line_numbers.byte_index(1, 6, PositionEncoding::Utf16), 8);
+}
+
+#[test]
+fn line_and_column_number_with_multi_byte_characters() {
+    let src = "x\nlet é🐙 = 1\n";
+    let line_numbers = LineNumbers::new(src);
+    let equals = src.find('=').expect("equals") as u32;
+
+    let position = line_numbers.line_and_column_number_in(equals, PositionEncoding::Utf8);
+    assert_eq!((position.line, position.column), (2, 12));
+    let position = line_numbers.line_and_column_number_in(equals, PositionEncoding::Utf16);
+    assert_eq!((position.line, position.column), (2, 9));
+    let position = line_numbers.line_and_column_number(equals);
+    assert_eq!((position.line, position.column), (2, 8));
+}
+
+#[test]
+fn byte_index_past_end_of_line() {
+    let src = "let x = 1\nlet y = 2";
+    let line_numbers = LineNumbers::new(src);
+
+    assert_eq!(line_numbers.byte_index(0, 100, PositionEncoding::Utf16), 9);
+    assert_eq!(line_numbers.byte_index(1, 100, PositionEncoding::Utf16), 19);
+}
+
+#[cfg(test)]
+fn assert_edit(src: &str, start: u32, end: u32, text: &str) {
+    let mut line_numbers = LineNumbers::new(src);
+    line_numbers.edit(start, end, text);
+
+    let mut edited = src.to_string();
+    edited.replace_range(start as usize..end as usize, text);
+    assert_eq!(line_numbers, LineNumbers::new(&edited));
+}
+
+#[test]
+fn edit_inserting_lines() {
+    assert_edit("one\ntwo\nthree\n", 5, 5, "a\nb\nc");
+}
+
+#[test]
+fn edit_removing_lines() {
+    assert_edit("one\ntwo\nthree\nfour", 2, 12, "");
+}
+
+#[test]
+fn edit_replacing_lines() {
+    assert_edit("one\ntwo\nthree\nfour", 4, 13, "2\n3\n3.5\n");
+}
+
+#[test]
+fn edit_at_line_boundaries() {
+    assert_edit("one\ntwo\nthree", 3, 4, "");
+    assert_edit("one\ntwo\nthree", 4, 4, "\n");
+    assert_edit("one\ntwo\nthree", 0, 13, "");
+}
+
+#[test]
+fn edit_with_multi_byte_characters() {
+    assert_edit("é\n🐙 and é\n€", 3, 7, "€\n🐙");
+    assert_edit("é\n🐙 and é\n€", 0, 2, "");
+    assert_edit("é\n🐙 and é\n€", 18, 18, "🐙");
 }
 
 #[derive(Debug, Clone, Copy)]
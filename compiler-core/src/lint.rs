@@ -0,0 +1,155 @@
+//! Lints are additional checks run over the typed AST of a module once it
+//! has been successfully type checked.
+//!
+//! Unlike errors they never stop compilation, and unlike the warnings emitted
+//! by the type checker each lint can be enabled or disabled individually in
+//! the `[lints]` section of `gleam.toml`. Any problems they find are reported
+//! through the normal warning pipeline.
+//!
+//! To add a new lint implement the `Lint` trait in a new submodule, usually
+//! with the help of the typed AST visitor in `crate::ast::visit`, and add it
+//! to the list returned by `all`.
+
+mod let_assert;
+mod single_clause_case;
+
+#[cfg(test)]
+mod tests;
+
+use ecow::EcoString;
+
+use crate::{
+    ast::{SrcSpan, TypedModule},
+    config::{LintLevel, LintsConfig},
+};
+
+pub trait Lint {
+    /// The name used to refer to the lint in `gleam.toml`.
+    fn name(&self) -> &'static str;
+
+    /// The level of the lint when it has not been configured.
+    fn default_level(&self) -> LintLevel;
+
+    /// Checks the module, returning a warning for each problem found.
+    fn check(&self, module: &TypedModule) -> Vec<LintWarning>;
+}
+
+/// A problem found by a lint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintWarning {
+    /// The name of the lint that found the problem.
+    pub lint: &'static str,
+    pub location: SrcSpan,
+    pub title: EcoString,
+    pub label: EcoString,
+    pub text: EcoString,
+    pub hint: Option<EcoString>,
+    /// A change to the source code that fixes the problem, if there is one
+    /// that can be made automatically.
+    pub fix: Option<LintFix>,
+}
+
+/// A change to the source code suggested by a lint, which the language
+/// server offers as a quick fix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFix {
+    pub title: EcoString,
+    pub edits: Vec<LintEdit>,
+}
+
+/// Replaces the source code at `location` with `replacement`.
+///
+/// The lints work on the typed AST rather than the source code, so the
+/// replacement is made out of new text and spans of the existing source code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintEdit {
+    pub location: SrcSpan,
+    pub replacement: Vec<Replacement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Replacement {
+    /// New text. Each newline is followed by the indentation of the line the
+    /// edit starts on.
+    Text(EcoString),
+    /// A copy of the existing source code at this location.
+    Source(SrcSpan),
+}
+
+impl LintEdit {
+    /// The text to replace the source code at `location` with.
+    pub fn new_text(&self, src: &str) -> String {
+        let indentation = line_indentation(src, self.location.start);
+
+        let mut text = String::new();
+        for part in &self.replacement {
+            match part {
+                Replacement::Text(new_text) => {
+                    text.push_str(&new_text.replace("\n", &format!("\n{indentation}")))
+                }
+
+                // Source code moved from a more indented line is dedented to
+                // line up with the rest of the replacement.
+                Replacement::Source(location) => {
+                    let source = src
+                        .get(location.start as usize..location.end as usize)
+                        .unwrap_or_default();
+                    let dedent = line_indentation(src, location.start)
+                        .len()
+                        .saturating_sub(indentation.len());
+                    for (i, line) in source.split('\n').enumerate() {
+                        if i > 0 {
+                            text.push('\n');
+                        }
+                        let whitespace = line
+                            .chars()
+                            .take(dedent)
+                            .take_while(|character| *character == ' ' || *character == '\t')
+                            .count();
+                        text.push_str(line.get(whitespace..).unwrap_or(line));
+                    }
+                }
+            }
+        }
+        text
+    }
+}
+
+/// The whitespace at the start of the line containing the byte index.
+fn line_indentation(src: &str, byte_index: u32) -> &str {
+    let line_start = src
+        .get(..byte_index as usize)
+        .and_then(|before| before.rfind('\n'))
+        .map_or(0, |newline| newline + 1);
+    let line = src.get(line_start..).unwrap_or_default();
+    let length = line
+        .chars()
+        .take_while(|character| *character == ' ' || *character == '\t')
+        .count();
+    line.get(..length).unwrap_or_default()
+}
+
+/// All the lints known to the compiler.
+pub fn all() -> Vec<Box<dyn Lint>> {
+    vec![
+        Box::new(let_assert::LetAssert),
+        Box::new(single_clause_case::SingleClauseCase),
+    ]
+}
+
+/// Runs all the lints enabled by the configuration on the given module.
+pub fn check_module(config: &LintsConfig, module: &TypedModule) -> Vec<LintWarning> {
+    all()
+        .into_iter()
+        .filter(|lint| {
+            let level = config
+                .level(lint.name())
+                .unwrap_or_else(|| lint.default_level());
+            match level {
+                LintLevel::Allow => false,
+                LintLevel::Warn => true,
+            }
+        })
+        .flat_map(|lint| lint.check(module))
+        .collect()
+}
@@ -0,0 +1,56 @@
+use crate::{
+    ast::{
+        visit::{self, Visit},
+        AssignmentKind, TypedAssignment, TypedModule,
+    },
+    config::LintLevel,
+};
+
+use super::{Lint, LintWarning};
+
+/// Warns about `let assert`, which crashes the program when the value doesn't
+/// match the pattern. Off by default.
+///
+/// ```gleam
+/// let assert Ok(x) = result
+/// ```
+pub struct LetAssert;
+
+impl Lint for LetAssert {
+    fn name(&self) -> &'static str {
+        "let_assert"
+    }
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Allow
+    }
+
+    fn check(&self, module: &TypedModule) -> Vec<LintWarning> {
+        let mut finder = LetAssertFinder { warnings: vec![] };
+        finder.visit_typed_module(module);
+        finder.warnings
+    }
+}
+
+struct LetAssertFinder {
+    warnings: Vec<LintWarning>,
+}
+
+impl<'ast> Visit<'ast> for LetAssertFinder {
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        if let AssignmentKind::Assert { .. } = assignment.kind {
+            self.warnings.push(LintWarning {
+                lint: LetAssert.name(),
+                location: assignment.location,
+                title: "Use of `let assert`".into(),
+                label: "This will crash if the pattern doesn't match".into(),
+                text: "`let assert` crashes the program when the value doesn't match the
+pattern."
+                    .into(),
+                hint: Some("Handle every possible value with a `case` expression instead.".into()),
+                fix: None,
+            });
+        }
+        visit::visit_typed_assignment(self, assignment);
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::HashSet;
+
+use crate::{
+    analyse::Inferred,
+    ast::{
+        visit::{self, Visit},
+        Pattern, SrcSpan, Statement, TypedClause, TypedExpr, TypedFunction, TypedModule,
+        TypedPattern, TypedStatement,
+    },
+    config::LintLevel,
+    type_::PatternConstructor,
+};
+
+use super::{Lint, LintEdit, LintFix, LintWarning, Replacement};
+
+/// Warns about `case` expressions with a single clause that always matches,
+/// which can be written more simply with `let`. Off by default.
+///
+/// ```gleam
+/// case pair {
+///   #(first, second) -> first + second
+/// }
+/// ```
+pub struct SingleClauseCase;
+
+impl Lint for SingleClauseCase {
+    fn name(&self) -> &'static str {
+        "single_clause_case"
+    }
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Allow
+    }
+
+    fn check(&self, module: &TypedModule) -> Vec<LintWarning> {
+        let mut finder = SingleClauseCaseFinder {
+            warnings: vec![],
+            final_cases: HashSet::new(),
+        };
+        finder.visit_typed_module(module);
+        finder.warnings
+    }
+}
+
+struct SingleClauseCaseFinder {
+    warnings: Vec<LintWarning>,
+    /// The start of each `case` expression that is the last statement of a
+    /// function or block. Only these can be rewritten as `let` without the
+    /// variables they bind shadowing ones used by the statements after them.
+    final_cases: HashSet<u32>,
+}
+
+impl SingleClauseCaseFinder {
+    fn record_final_case(&mut self, statements: &[TypedStatement]) {
+        if let Some(Statement::Expression(TypedExpr::Case { location, .. })) = statements.last() {
+            _ = self.final_cases.insert(location.start);
+        }
+    }
+
+    fn check_case(&mut self, location: SrcSpan, subjects: &[TypedExpr], clauses: &[TypedClause]) {
+        let [clause] = clauses else {
+            return;
+        };
+        if clause.guard.is_some()
+            || !clause.alternative_patterns.is_empty()
+            || !clause.pattern.iter().all(is_irrefutable)
+        {
+            return;
+        }
+
+        // With several subjects the variables bound by the pattern of one
+        // subject could shadow ones used by the subjects after it.
+        let fix = match (subjects, clause.pattern.as_slice()) {
+            ([subject], [pattern]) if self.final_cases.contains(&location.start) => {
+                Some(rewrite_as_let(location, subject, pattern, &clause.then))
+            }
+            _ => None,
+        };
+
+        self.warnings.push(LintWarning {
+            lint: SingleClauseCase.name(),
+            location,
+            title: "Case expression with a single clause".into(),
+            label: "This can be written with `let`".into(),
+            text: "This `case` expression has a single clause that always matches, so the
+value can be destructured with `let` instead."
+                .into(),
+            hint: Some("Replace the `case` expression with a `let` assignment.".into()),
+            fix,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for SingleClauseCaseFinder {
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        self.record_final_case(&function.body);
+        visit::visit_typed_function(self, function);
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        match expr {
+            TypedExpr::Block { statements, .. } => self.record_final_case(statements),
+            TypedExpr::Fn { body, .. } => self.record_final_case(body),
+            TypedExpr::Case {
+                location,
+                subjects,
+                clauses,
+                ..
+            } => self.check_case(*location, subjects, clauses),
+            _ => {}
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+}
+
+/// Whether the pattern matches every value of its type.
+fn is_irrefutable(pattern: &TypedPattern) -> bool {
+    match pattern {
+        Pattern::Variable { .. } | Pattern::Discard { .. } => true,
+        Pattern::Assign { pattern, .. } => is_irrefutable(pattern),
+        Pattern::Tuple { elems, .. } => elems.iter().all(is_irrefutable),
+        Pattern::List { elements, tail, .. } => {
+            elements.is_empty() && tail.as_deref().is_some_and(is_irrefutable)
+        }
+        Pattern::Constructor {
+            constructor:
+                Inferred::Known(PatternConstructor {
+                    constructors_count: 1,
+                    ..
+                }),
+            arguments,
+            ..
+        } => arguments
+            .iter()
+            .all(|argument| is_irrefutable(&argument.value)),
+        Pattern::Int { .. }
+        | Pattern::Float { .. }
+        | Pattern::String { .. }
+        | Pattern::VarUsage { .. }
+        | Pattern::Constant { .. }
+        | Pattern::Constructor { .. }
+        | Pattern::BitArray { .. }
+        | Pattern::StringPrefix { .. } => false,
+    }
+}
+
+/// The location of the whole pattern, including any `as` assignment.
+fn pattern_location(pattern: &TypedPattern) -> SrcSpan {
+    match pattern {
+        Pattern::Assign {
+            location, pattern, ..
+        } => SrcSpan::new(pattern_location(pattern).start, location.end),
+        _ => pattern.location(),
+    }
+}
+
+/// Replaces the `case` expression with a `let` assignment of its subject,
+/// followed by the body of the clause.
+///
+/// ```gleam
+/// let #(first, second) = pair
+/// first + second
+/// ```
+fn rewrite_as_let(
+    location: SrcSpan,
+    subject: &TypedExpr,
+    pattern: &TypedPattern,
+    then: &TypedExpr,
+) -> LintFix {
+    let mut replacement = vec![
+        Replacement::Text("let ".into()),
+        Replacement::Source(pattern_location(pattern)),
+        Replacement::Text(" = ".into()),
+        Replacement::Source(subject.location()),
+        Replacement::Text("\n".into()),
+    ];
+
+    // The statements of a block body are moved out of the block.
+    let body = match then {
+        TypedExpr::Block { statements, .. } => SrcSpan::new(
+            statements.first().location().start,
+            statements.last().location().end,
+        ),
+        then => then.location(),
+    };
+    replacement.push(Replacement::Source(body));
+
+    LintFix {
+        title: "Rewrite as `let`".into(),
+        edits: vec![LintEdit {
+            location,
+            replacement,
+        }],
+    }
+}
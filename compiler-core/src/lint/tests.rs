@@ -0,0 +1,195 @@
+use camino::Utf8PathBuf;
+
+use crate::{
+    config::{LintLevel, LintsConfig},
+    type_::tests::compile_module,
+    warning::Warning,
+};
+
+fn lint_warnings(config: &LintsConfig, src: &str) -> String {
+    let module = compile_module("test_module", src, None, vec![]).expect("should compile");
+    super::check_module(config, &module)
+        .into_iter()
+        .map(|warning| {
+            Warning::Type {
+                path: Utf8PathBuf::from("/src/warning/wrn.gleam"),
+                src: src.into(),
+                warning: crate::type_::Warning::Lint { warning },
+            }
+            .to_pretty_string()
+        })
+        .collect()
+}
+
+#[test]
+fn lints_can_be_enabled() {
+    let src = "
+pub fn main(x) {
+  let assert Ok(y) = x
+  y
+}
+";
+    let config = LintsConfig::from([("let_assert", LintLevel::Warn)]);
+    insta::assert_snapshot!(lint_warnings(&config, src));
+}
+
+#[test]
+fn lints_can_be_disabled() {
+    let src = "
+pub fn main(x) {
+  let assert Ok(y) = x
+  y
+}
+";
+    let config = LintsConfig::from([("let_assert", LintLevel::Allow)]);
+    assert_eq!(lint_warnings(&config, src), "");
+}
+
+#[test]
+fn lints_use_their_default_level_when_not_configured() {
+    let src = "
+pub fn main(x) {
+  let assert Ok(y) = x
+  y
+}
+";
+    assert_eq!(lint_warnings(&LintsConfig::default(), src), "");
+}
+
+#[test]
+fn lint_names_are_unique() {
+    let lints = super::all();
+    let mut names = lints.iter().map(|lint| lint.name()).collect::<Vec<_>>();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names.len(), lints.len());
+}
+
+#[test]
+fn let_assert_in_nested_function() {
+    let src = "
+pub fn main(xs) {
+  fn() {
+    let assert [x, ..] = xs
+    x
+  }
+}
+";
+    let config = LintsConfig::from([("let_assert", LintLevel::Warn)]);
+    insta::assert_snapshot!(lint_warnings(&config, src));
+}
+
+#[test]
+fn single_clause_case() {
+    let src = "
+pub fn main(pair) {
+  case pair {
+    #(first, [..rest]) -> #(first, rest)
+  }
+}
+";
+    let config = LintsConfig::from([("single_clause_case", LintLevel::Warn)]);
+    insta::assert_snapshot!(lint_warnings(&config, src));
+}
+
+#[test]
+fn single_clause_case_with_single_constructor_type() {
+    let src = "
+pub type Wrapper {
+  Wrapper(value: Int)
+}
+
+pub fn main(wrapper) {
+  case wrapper {
+    Wrapper(value:) -> value
+  }
+}
+";
+    let config = LintsConfig::from([("single_clause_case", LintLevel::Warn)]);
+    insta::assert_snapshot!(lint_warnings(&config, src));
+}
+
+#[test]
+fn single_clause_case_with_refutable_pattern() {
+    let src = "
+pub type Shape {
+  Circle(Int)
+  Square(Int)
+}
+
+pub fn main(shape, x) {
+  let assert Nil = case shape {
+    Circle(_) -> Nil
+    Square(_) -> Nil
+  }
+  case x {
+    [_, ..] | [] -> Nil
+  }
+}
+";
+    let config = LintsConfig::from([("single_clause_case", LintLevel::Warn)]);
+    assert_eq!(lint_warnings(&config, src), "");
+}
+
+#[test]
+fn single_clause_case_with_guard() {
+    let src = "
+pub fn main(x) {
+  case x {
+    y if y > 1 -> y
+    y -> y
+  }
+}
+";
+    let config = LintsConfig::from([("single_clause_case", LintLevel::Warn)]);
+    assert_eq!(lint_warnings(&config, src), "");
+}
+
+#[test]
+fn single_clause_case_fix_is_only_offered_for_final_statements() {
+    let src = "
+pub fn main(x) {
+  case x {
+    y -> y
+  }
+  case x {
+    y -> y
+  }
+}
+";
+    let config = LintsConfig::from([("single_clause_case", LintLevel::Warn)]);
+    let module = compile_module("test_module", src, None, vec![]).expect("should compile");
+    let fixes = super::check_module(&config, &module)
+        .into_iter()
+        .map(|warning| warning.fix.map(|fix| fix.edits))
+        .collect::<Vec<_>>();
+
+    let [None, Some(edits)] = fixes.as_slice() else {
+        panic!("expected only the final case to be fixed, got {fixes:?}");
+    };
+    let new_text = edits
+        .iter()
+        .map(|edit| edit.new_text(src))
+        .collect::<Vec<_>>();
+    assert_eq!(new_text, vec!["let y = x\n  y"]);
+}
+
+#[test]
+fn single_clause_case_fix_is_not_offered_for_several_subjects() {
+    // Rewriting as `let x = y` then `let y = x` would use the new `x`
+    let src = "
+pub fn main(x, y) {
+  case y, x {
+    x, y -> #(x, y)
+  }
+}
+";
+    let config = LintsConfig::from([("single_clause_case", LintLevel::Warn)]);
+    let module = compile_module("test_module", src, None, vec![]).expect("should compile");
+    let fixes = super::check_module(&config, &module)
+        .into_iter()
+        .map(|warning| warning.fix)
+        .collect::<Vec<_>>();
+
+    assert_eq!(fixes, vec![None]);
+}
@@ -13,8 +13,9 @@ use crate::{
     schema_capnp::{self as schema, *},
     type_::{
         self, expression::Implementations, AccessorsMap, Deprecation, FieldMap, ModuleInterface,
-        RecordAccessor, Type, TypeConstructor, TypeValueConstructor, TypeValueConstructorField,
-        TypeVariantConstructors, ValueConstructor, ValueConstructorVariant,
+        Purity, RecordAccessor, Type, TypeConstructor, TypeValueConstructor,
+        TypeValueConstructorField, TypeVariantConstructors, ValueConstructor,
+        ValueConstructorVariant,
     },
     uid::UniqueIdGenerator,
     Result,
@@ -50,6 +51,9 @@ macro_rules! read_hashmap {
 pub struct ModuleDecoder {
     ids: UniqueIdGenerator,
     type_var_id_map: HashMap<u64, u64>,
+    /// Types without parameters are decoded once and shared by every value
+    /// and constructor using them.
+    named_types: HashMap<(EcoString, EcoString), Arc<Type>>,
 }
 
 impl ModuleDecoder {
@@ -57,6 +61,7 @@ impl ModuleDecoder {
         Self {
             ids,
             type_var_id_map: Default::default(),
+            named_types: Default::default(),
         }
     }
 
@@ -118,16 +123,31 @@ impl ModuleDecoder {
 
     fn type_app(&mut self, reader: &schema::type_::app::Reader<'_>) -> Result<Arc<Type>> {
         let package = reader.get_package()?.into();
-        let module = reader.get_module()?.into();
-        let name = reader.get_name()?.into();
+        let module: EcoString = reader.get_module()?.into();
+        let name: EcoString = reader.get_name()?.into();
         let args = read_vec!(&reader.get_parameters()?, self, type_);
-        Ok(Arc::new(Type::Named {
-            publicity: Publicity::Public,
-            package,
-            module,
-            name,
-            args,
-        }))
+        if !args.is_empty() {
+            return Ok(Arc::new(Type::Named {
+                publicity: Publicity::Public,
+                package,
+                module,
+                name,
+                args,
+            }));
+        }
+        let type_ = self
+            .named_types
+            .entry((module.clone(), name.clone()))
+            .or_insert_with(|| {
+                Arc::new(Type::Named {
+                    publicity: Publicity::Public,
+                    package,
+                    module,
+                    name,
+                    args,
+                })
+            });
+        Ok(type_.clone())
     }
 
     fn type_fn(&mut self, reader: &schema::type_::fn_::Reader<'_>) -> Result<Arc<Type>> {
@@ -309,6 +329,7 @@ impl ModuleDecoder {
     ) -> Result<CallArg<TypedConstant>> {
         Ok(CallArg {
             implicit: false,
+            label_shorthand: false,
             label: Default::default(),
             location: Default::default(),
             value: self.constant(reader)?,
@@ -469,6 +490,8 @@ impl ModuleDecoder {
             location: self.src_span(&reader.get_location()?)?,
             documentation: self.optional_string(reader.get_documentation()?),
             implementations: self.implementations(reader.get_implementations()?),
+            purity: self.purity(reader.get_purity()?),
+            must_use: reader.get_must_use(),
         })
     }
 
@@ -482,6 +505,14 @@ impl ModuleDecoder {
         }
     }
 
+    fn purity(&self, purity: schema::Purity) -> Purity {
+        match purity {
+            schema::Purity::Pure => Purity::Pure,
+            schema::Purity::Impure => Purity::Impure,
+            schema::Purity::Unknown => Purity::Unknown,
+        }
+    }
+
     fn record(
         &self,
         reader: &value_constructor_variant::record::Reader<'_>,
@@ -540,6 +571,11 @@ impl ModuleDecoder {
         Ok(LineNumbers {
             length: reader.get_length(),
             line_starts: read_vec!(reader.get_line_starts()?, self, line_starts),
+            multi_byte_characters: reader
+                .get_multi_byte_characters()?
+                .iter()
+                .map(|character| (character.get_byte_index(), character.get_length()))
+                .collect(),
         })
     }
 }
@@ -7,11 +7,12 @@ use crate::{
     },
     schema_capnp::{self as schema, *},
     type_::{
-        self, expression::Implementations, AccessorsMap, Deprecation, FieldMap, RecordAccessor,
-        Type, TypeConstructor, TypeValueConstructor, TypeVar, TypeVariantConstructors,
-        ValueConstructor, ValueConstructorVariant,
+        self, expression::Implementations, AccessorsMap, Deprecation, FieldMap, Purity,
+        RecordAccessor, Type, TypeConstructor, TypeValueConstructor, TypeVar,
+        TypeVariantConstructors, ValueConstructor, ValueConstructorVariant,
     },
 };
+use itertools::Itertools;
 use std::{collections::HashMap, ops::Deref, sync::Arc};
 
 #[derive(Debug)]
@@ -57,10 +58,24 @@ impl<'a> ModuleEncoder<'a> {
     fn set_line_numbers(&mut self, module: &mut module::Builder<'_>) {
         let mut line_numbers = module.reborrow().init_line_numbers();
         line_numbers.set_length(self.data.line_numbers.length);
-        let line_starts =
-            line_numbers.init_line_starts(self.data.line_numbers.line_starts.len() as u32);
+        let mut line_starts = line_numbers
+            .reborrow()
+            .init_line_starts(self.data.line_numbers.line_starts.len() as u32);
         for (i, l) in self.data.line_numbers.line_starts.iter().enumerate() {
-            line_starts.reborrow().set(i as u32, *l);
+            line_starts.set(i as u32, *l);
+        }
+        let mut characters = line_numbers
+            .init_multi_byte_characters(self.data.line_numbers.multi_byte_characters.len() as u32);
+        for (i, (byte_index, length)) in self
+            .data
+            .line_numbers
+            .multi_byte_characters
+            .iter()
+            .enumerate()
+        {
+            let mut character = characters.reborrow().get(i as u32);
+            character.set_byte_index(*byte_index);
+            character.set_length(*length);
         }
     }
 
@@ -79,7 +94,7 @@ impl<'a> ModuleEncoder<'a> {
         let mut builder = module
             .reborrow()
             .init_accessors(self.data.accessors.len() as u32);
-        for (i, (key, map)) in self.data.accessors.iter().enumerate() {
+        for (i, (key, map)) in sorted(&self.data.accessors).enumerate() {
             let mut property = builder.reborrow().get(i as u32);
             property.set_key(key);
             self.build_accessors_map(property.init_value(), map);
@@ -93,7 +108,7 @@ impl<'a> ModuleEncoder<'a> {
     ) {
         self.build_type(builder.reborrow().init_type(), &accessors.type_);
         let mut builder = builder.init_accessors(accessors.accessors.len() as u32);
-        for (i, (name, accessor)) in accessors.accessors.iter().enumerate() {
+        for (i, (name, accessor)) in sorted(&accessors.accessors).enumerate() {
             let mut property = builder.reborrow().get(i as u32);
             property.set_key(name);
             self.build_record_accessor(property.init_value(), accessor)
@@ -113,7 +128,7 @@ impl<'a> ModuleEncoder<'a> {
     fn set_module_types(&mut self, module: &mut module::Builder<'_>) {
         tracing::trace!("Writing module metadata types");
         let mut types = module.reborrow().init_types(self.data.types.len() as u32);
-        for (i, (name, type_)) in self.data.types.iter().enumerate() {
+        for (i, (name, type_)) in sorted(&self.data.types).enumerate() {
             let mut property = types.reborrow().get(i as u32);
             property.set_key(name);
             self.build_type_constructor(property.init_value(), type_)
@@ -125,7 +140,7 @@ impl<'a> ModuleEncoder<'a> {
         let mut types_constructors = module
             .reborrow()
             .init_types_constructors(self.data.types_value_constructors.len() as u32);
-        for (i, (name, data)) in self.data.types_value_constructors.iter().enumerate() {
+        for (i, (name, data)) in sorted(&self.data.types_value_constructors).enumerate() {
             let mut property = types_constructors.reborrow().get(i as u32);
             property.set_key(name);
             self.build_type_variant_constructors(property.init_value(), data)
@@ -155,7 +170,7 @@ impl<'a> ModuleEncoder<'a> {
     fn set_module_values(&mut self, module: &mut module::Builder<'_>) {
         tracing::trace!("Writing module metadata values");
         let mut values = module.reborrow().init_values(self.data.values.len() as u32);
-        for (i, (name, value)) in self.data.values.iter().enumerate() {
+        for (i, (name, value)) in sorted(&self.data.values).enumerate() {
             let mut property = values.reborrow().get(i as u32);
             property.set_key(name);
             self.build_value_constructor(property.init_value(), value)
@@ -228,6 +243,14 @@ impl<'a> ModuleEncoder<'a> {
         }
     }
 
+    fn purity(&self, purity: Purity) -> crate::schema_capnp::Purity {
+        match purity {
+            Purity::Pure => crate::schema_capnp::Purity::Pure,
+            Purity::Impure => crate::schema_capnp::Purity::Impure,
+            Purity::Unknown => crate::schema_capnp::Purity::Unknown,
+        }
+    }
+
     fn build_src_span(&mut self, mut builder: src_span::Builder<'_>, span: SrcSpan) {
         builder.set_start(span.start);
         builder.set_end(span.end);
@@ -291,8 +314,12 @@ impl<'a> ModuleEncoder<'a> {
                 location,
                 documentation: doc,
                 implementations,
+                purity,
+                must_use,
             } => {
                 let mut builder = builder.init_module_fn();
+                builder.set_purity(self.purity(*purity));
+                builder.set_must_use(*must_use);
                 builder.set_name(name);
                 builder.set_module(module);
                 builder.set_arity(*arity as u16);
@@ -318,7 +345,7 @@ impl<'a> ModuleEncoder<'a> {
     fn build_field_map(&mut self, mut builder: field_map::Builder<'_>, field_map: &FieldMap) {
         builder.set_arity(field_map.arity);
         let mut builder = builder.init_fields(field_map.fields.len() as u32);
-        for (i, (name, &position)) in field_map.fields.iter().enumerate() {
+        for (i, (name, &position)) in sorted(&field_map.fields).enumerate() {
             let mut field = builder.reborrow().get(i as u32);
             field.set_key(name);
             field.init_value().set_value(position);
@@ -527,3 +554,10 @@ impl<'a> ModuleEncoder<'a> {
         builder.set_can_run_on_javascript(implementations.can_run_on_javascript);
     }
 }
+
+/// The entries of a map in the order of their keys, so encoding the same
+/// interface always gives the same bytes, and the same ids to its type
+/// variables.
+fn sorted<V>(map: &HashMap<EcoString, V>) -> impl Iterator<Item = (&EcoString, &V)> {
+    map.iter().sorted_by(|(a, _), (b, _)| a.cmp(b))
+}
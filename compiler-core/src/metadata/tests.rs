@@ -1,3 +1,4 @@
+use ecow::EcoString;
 use rand::Rng;
 use type_::{AccessorsMap, FieldMap, RecordAccessor};
 
@@ -10,9 +11,9 @@ use crate::{
     build::Origin,
     line_numbers::LineNumbers,
     type_::{
-        self, expression::Implementations, Deprecation, ModuleInterface, Type, TypeConstructor,
-        TypeValueConstructor, TypeValueConstructorField, TypeVariantConstructors, ValueConstructor,
-        ValueConstructorVariant,
+        self, expression::Implementations, Deprecation, ModuleInterface, Purity, Type,
+        TypeConstructor, TypeValueConstructor, TypeValueConstructorField, TypeVariantConstructors,
+        ValueConstructor, ValueConstructorVariant,
     },
     uid::UniqueIdGenerator,
 };
@@ -100,6 +101,36 @@ fn empty_module() {
     assert_eq!(roundtrip(&module), module);
 }
 
+#[test]
+fn encoding_is_the_same_whatever_order_values_are_in() {
+    let module = constant_module(Constant::Int {
+        location: Default::default(),
+        value: "1".into(),
+    });
+    let value = module.values.get("one").unwrap().clone();
+    let names = ["a", "b", "c", "d", "e", "f", "g", "h"];
+    let values = |names: &mut dyn Iterator<Item = &&str>| {
+        names
+            .map(|name| (EcoString::from(*name), value.clone()))
+            .collect::<HashMap<_, _>>()
+    };
+    let forwards = ModuleInterface {
+        values: values(&mut names.iter()),
+        ..module.clone()
+    };
+    let backwards = ModuleInterface {
+        values: values(&mut names.iter().rev()),
+        ..module
+    };
+
+    let encoded = ModuleEncoder::new(&forwards).encode().unwrap();
+    assert_eq!(encoded, ModuleEncoder::new(&backwards).encode().unwrap());
+    assert_eq!(
+        encoded,
+        ModuleEncoder::new(&roundtrip(&forwards)).encode().unwrap()
+    );
+}
+
 #[test]
 fn with_line_numbers() {
     let module = ModuleInterface {
@@ -123,6 +154,29 @@ fn with_line_numbers() {
     assert_eq!(roundtrip(&module), module);
 }
 
+#[test]
+fn with_multi_byte_characters_in_line_numbers() {
+    let module = ModuleInterface {
+        is_internal: false,
+        contains_todo: false,
+        package: "some_package".into(),
+        origin: Origin::Src,
+        name: "one/two".into(),
+        types: HashMap::new(),
+        types_value_constructors: HashMap::new(),
+        values: HashMap::new(),
+        unused_imports: Vec::new(),
+        accessors: HashMap::new(),
+        line_numbers: LineNumbers::new(
+            "const a = \"é\"
+        const b = \"🐙\"
+        const c = \"€\"",
+        ),
+        src_path: "some_path".into(),
+    };
+    assert_eq!(roundtrip(&module), module);
+}
+
 #[test]
 fn module_with_private_type() {
     let module = ModuleInterface {
@@ -404,6 +458,103 @@ fn module_fn_value() {
                         can_run_on_erlang: true,
                         can_run_on_javascript: true,
                     },
+                    purity: Purity::Pure,
+                    must_use: false,
+                },
+            },
+        )]
+        .into(),
+        line_numbers: LineNumbers::new(""),
+        src_path: "some_path".into(),
+    };
+    assert_eq!(roundtrip(&module), module);
+}
+
+#[test]
+fn module_fn_value_purity() {
+    let function = |name: &str, purity| {
+        (
+            name.into(),
+            ValueConstructor {
+                publicity: Publicity::Public,
+                deprecation: Deprecation::NotDeprecated,
+                type_: type_::int(),
+                variant: ValueConstructorVariant::ModuleFn {
+                    documentation: None,
+                    name: name.into(),
+                    field_map: None,
+                    module: "a".into(),
+                    arity: 0,
+                    location: SrcSpan { start: 0, end: 10 },
+                    implementations: Implementations {
+                        gleam: true,
+                        uses_erlang_externals: false,
+                        uses_javascript_externals: false,
+                        can_run_on_erlang: true,
+                        can_run_on_javascript: true,
+                    },
+                    purity,
+                    must_use: false,
+                },
+            },
+        )
+    };
+    let module = ModuleInterface {
+        is_internal: false,
+        contains_todo: false,
+        package: "some_package".into(),
+        origin: Origin::Src,
+        name: "a".into(),
+        types: HashMap::new(),
+        unused_imports: Vec::new(),
+        types_value_constructors: HashMap::new(),
+        accessors: HashMap::new(),
+        values: [
+            function("pure", Purity::Pure),
+            function("impure", Purity::Impure),
+            function("unknown", Purity::Unknown),
+        ]
+        .into(),
+        line_numbers: LineNumbers::new(""),
+        src_path: "some_path".into(),
+    };
+    assert_eq!(roundtrip(&module), module);
+}
+
+#[test]
+fn must_use_module_fn_value() {
+    let module = ModuleInterface {
+        is_internal: false,
+        contains_todo: false,
+        package: "some_package".into(),
+        origin: Origin::Src,
+        name: "a".into(),
+        types: HashMap::new(),
+        types_value_constructors: HashMap::new(),
+        unused_imports: Vec::new(),
+        accessors: HashMap::new(),
+        values: [(
+            "one".into(),
+            ValueConstructor {
+                publicity: Publicity::Public,
+                deprecation: Deprecation::NotDeprecated,
+                type_: type_::int(),
+                variant: ValueConstructorVariant::ModuleFn {
+                    documentation: None,
+                    name: "one".into(),
+                    field_map: None,
+                    module: "a".into(),
+                    arity: 2,
+                    location: SrcSpan { start: 0, end: 10 },
+                    implementations: Implementations {
+                        gleam: true,
+                        uses_erlang_externals: false,
+                        uses_javascript_externals: false,
+                        can_run_on_erlang: true,
+                        can_run_on_javascript: true,
+                    },
+                    purity: Purity::Unknown,
+                    must_use: true,
                 },
             },
         )]
@@ -451,6 +602,8 @@ fn deprecated_module_fn_value() {
                         can_run_on_erlang: true,
                         can_run_on_javascript: true,
                     },
+                    purity: Purity::Pure,
+                    must_use: false,
                 },
             },
         )]
@@ -496,6 +649,8 @@ fn private_module_fn_value() {
                         can_run_on_erlang: true,
                         can_run_on_javascript: true,
                     },
+                    purity: Purity::Pure,
+                    must_use: false,
                 },
             },
         )]
@@ -543,6 +698,8 @@ fn module_fn_value_regression() {
                         can_run_on_erlang: true,
                         can_run_on_javascript: true,
                     },
+                    purity: Purity::Pure,
+                    must_use: false,
                 },
             },
         )]
@@ -589,6 +746,8 @@ fn module_fn_value_with_field_map() {
                         can_run_on_erlang: true,
                         can_run_on_javascript: true,
                     },
+                    purity: Purity::Pure,
+                    must_use: false,
                 },
             },
         )]
@@ -848,6 +1007,7 @@ fn constant_record() {
         args: vec![
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 label: None,
                 location: Default::default(),
                 value: Constant::Float {
@@ -857,6 +1017,7 @@ fn constant_record() {
             },
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 label: None,
                 location: Default::default(),
                 value: Constant::Int {
@@ -1210,6 +1371,8 @@ fn module_fn_value_with_external_implementations() {
                         can_run_on_erlang: false,
                         can_run_on_javascript: true,
                     },
+                    purity: Purity::Pure,
+                    must_use: false,
                 },
             },
         )]
@@ -1256,6 +1419,8 @@ fn internal_module_fn() {
                         can_run_on_erlang: true,
                         can_run_on_javascript: true,
                     },
+                    purity: Purity::Pure,
+                    must_use: false,
                 },
             },
         )]
@@ -1327,3 +1492,18 @@ fn type_variable_ids_in_constructors_are_shared() {
 
     assert_eq!(roundtrip(&module).types_value_constructors, expected);
 }
+
+#[test]
+fn types_without_parameters_are_shared() {
+    let mut module = constant_module(Constant::Int {
+        location: Default::default(),
+        value: "1".into(),
+    });
+    let one = module.values.get("one").unwrap().clone();
+    let _ = module.values.insert("two".into(), one);
+
+    let module = roundtrip(&module);
+    let one = &module.values.get("one").unwrap().type_;
+    let two = &module.values.get("two").unwrap().type_;
+    assert!(Arc::ptr_eq(one, two));
+}
@@ -2,8 +2,9 @@ use std::{collections::HashMap, ops::Deref};
 
 use ecow::EcoString;
 use itertools::Itertools;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+pub mod compatibility;
 #[cfg(test)]
 mod tests;
 
@@ -16,7 +17,7 @@ use crate::{
 use crate::build::{Module, Package};
 
 /// The public interface of a package that gets serialised as a json object.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct PackageInterface {
     name: EcoString,
@@ -28,7 +29,7 @@ pub struct PackageInterface {
     modules: HashMap<EcoString, ModuleInterface>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct ModuleInterface {
     /// A vector with the lines composing the module's documentation (that is
@@ -48,7 +49,7 @@ pub struct ModuleInterface {
     functions: HashMap<EcoString, FunctionInterface>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct TypeDefinitionInterface {
     /// The definition's documentation comment (that is every line preceded by
@@ -57,6 +58,9 @@ pub struct TypeDefinitionInterface {
     /// If the definition has a deprecation annotation `@deprecated("...")`
     /// this field will hold the reason of the deprecation.
     deprecation: Option<DeprecationInterface>,
+    /// If the definition has a `@since("...")` attribute this field will hold
+    /// the version of the package it was added in.
+    since: Option<EcoString>,
     /// The number of type variables in the type definition.
     /// ```gleam
     /// /// This type has 2 type variables.
@@ -71,7 +75,7 @@ pub struct TypeDefinitionInterface {
     constructors: Vec<TypeConstructorInterface>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct TypeConstructorInterface {
     /// The constructor's documentation comment (that is every line preceded by
@@ -95,7 +99,7 @@ pub struct TypeConstructorInterface {
     parameters: Vec<ParameterInterface>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct TypeAliasInterface {
     /// The constructor's documentation comment (that is every line preceded by
@@ -104,6 +108,9 @@ pub struct TypeAliasInterface {
     /// If the alias has a deprecation annotation `@deprecated("...")`
     /// this field will hold the reason of the deprecation.
     deprecation: Option<DeprecationInterface>,
+    /// If the alias has a `@since("...")` attribute this field will hold
+    /// the version of the package it was added in.
+    since: Option<EcoString>,
     /// The number of type variables in the type alias definition.
     /// ```gleam
     /// /// This type alias has 2 type variables.
@@ -118,7 +125,7 @@ pub struct TypeAliasInterface {
     alias: TypeInterface,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct ConstantInterface {
     /// The constant's documentation comment (that is every line preceded by
@@ -127,6 +134,9 @@ pub struct ConstantInterface {
     /// If the constant has a deprecation annotation `@deprecated("...")`
     /// this field will hold the reason of the deprecation.
     deprecation: Option<DeprecationInterface>,
+    /// If the constant has a `@since("...")` attribute this field will hold
+    /// the version of the package it was added in.
+    since: Option<EcoString>,
     implementations: ImplementationsInterface,
     /// The constant's type.
     #[serde(rename = "type")]
@@ -135,7 +145,7 @@ pub struct ConstantInterface {
 
 /// A module's function. This differs from a simple `Fn` type as its arguments
 /// can be labelled.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct FunctionInterface {
     /// The function's documentation comment (that is every line preceded by
@@ -144,6 +154,9 @@ pub struct FunctionInterface {
     /// If the constant has a deprecation annotation `@deprecated("...")`
     /// this field will hold the reason of the deprecation.
     deprecation: Option<DeprecationInterface>,
+    /// If the function has a `@since("...")` attribute this field will hold
+    /// the version of the package it was added in.
+    since: Option<EcoString>,
     implementations: ImplementationsInterface,
     parameters: Vec<ParameterInterface>,
     #[serde(rename = "return")]
@@ -151,7 +164,7 @@ pub struct FunctionInterface {
 }
 
 /// Informations about how a value is implemented.
-#[derive(Debug, Serialize, Copy, Clone)]
+#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct ImplementationsInterface {
     /// Set to `true` if the const/function has a pure Gleam implementation
@@ -273,7 +286,7 @@ impl ImplementationsInterface {
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct DeprecationInterface {
     /// The reason for the deprecation.
@@ -291,7 +304,7 @@ impl DeprecationInterface {
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde(tag = "kind")]
 #[serde(rename_all = "kebab-case")]
 pub enum TypeInterface {
@@ -339,7 +352,7 @@ pub enum TypeInterface {
     },
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct ParameterInterface {
     /// If the parameter is labelled this will hold the label's name.
@@ -389,6 +402,7 @@ impl ModuleInterface {
                     documentation,
                     opaque,
                     deprecation,
+                    since,
                     typed_parameters,
                     parameters: _,
                     location: _,
@@ -408,6 +422,7 @@ impl ModuleInterface {
                         TypeDefinitionInterface {
                             documentation: documentation.clone(),
                             deprecation: DeprecationInterface::from_deprecation(deprecation),
+                            since: since.clone(),
                             parameters: typed_parameters.len(),
                             constructors: if *opaque {
                                 vec![]
@@ -442,6 +457,7 @@ impl ModuleInterface {
                     type_,
                     documentation,
                     deprecation,
+                    since,
                     location: _,
                     type_ast: _,
                 }) => {
@@ -450,6 +466,7 @@ impl ModuleInterface {
                         TypeAliasInterface {
                             documentation: documentation.clone(),
                             deprecation: DeprecationInterface::from_deprecation(deprecation),
+                            since: since.clone(),
                             parameters: parameters.len(),
                             alias: TypeInterface::from_type(type_.as_ref()),
                         },
@@ -464,6 +481,7 @@ impl ModuleInterface {
                     documentation,
                     implementations,
                     deprecation,
+                    since,
                     location: _,
                     annotation: _,
                     value: _,
@@ -476,6 +494,7 @@ impl ModuleInterface {
                             ),
                             type_: TypeInterface::from_type(type_.as_ref()),
                             deprecation: DeprecationInterface::from_deprecation(deprecation),
+                            since: since.clone(),
                             documentation: documentation.clone(),
                         },
                     );
@@ -487,6 +506,7 @@ impl ModuleInterface {
                     name,
                     arguments,
                     deprecation,
+                    since,
                     return_type,
                     documentation,
                     implementations,
@@ -496,6 +516,7 @@ impl ModuleInterface {
                     return_annotation: _,
                     external_erlang: _,
                     external_javascript: _,
+                    must_use: _,
                 }) => {
                     let mut id_map = IdMap::new();
                     let _ = functions.insert(
@@ -505,6 +526,7 @@ impl ModuleInterface {
                                 implementations,
                             ),
                             deprecation: DeprecationInterface::from_deprecation(deprecation),
+                            since: since.clone(),
                             documentation: documentation.clone(),
                             parameters: arguments
                                 .iter()
@@ -566,12 +588,7 @@ fn from_type_helper(type_: &Type, id_map: &mut IdMap) -> TypeInterface {
                 .collect(),
         },
 
-        Type::Var { type_ } => match type_
-            .as_ref()
-            .try_borrow()
-            .expect("borrow type after inference")
-            .deref()
-        {
+        Type::Var { type_ } => match type_.borrow().deref() {
             TypeVar::Link { type_ } => from_type_helper(type_, id_map),
             // Since package serialisation happens after inference there
             // should be no unbound type variables.
@@ -654,12 +671,7 @@ impl IdMap {
             // These types have no id to add to the map.
             Type::Named { .. } | Type::Fn { .. } | Type::Tuple { .. } => (),
             // If the type is actually a type variable whose id needs to be mapped.
-            Type::Var { type_ } => match type_
-                .as_ref()
-                .try_borrow()
-                .expect("borrow type after inference")
-                .deref()
-            {
+            Type::Var { type_ } => match type_.borrow().deref() {
                 TypeVar::Link { .. } => (),
                 TypeVar::Unbound { id } | TypeVar::Generic { id } => {
                     let _ = self.map_id(*id);
@@ -0,0 +1,407 @@
+//! Finding the changes to the public API of a package that break code using
+//! it, by comparing the interface of a release with the interface of the
+//! release before it.
+//!
+//! A change is breaking when code that compiled against the previous release
+//! can fail to compile against the new one: removing a module or definition,
+//! changing the type of a value, or adding or removing constructors of a type
+//! that code could pattern match on.
+
+use ecow::EcoString;
+use hexpm::version::Version;
+
+use crate::build::Target;
+
+use super::{
+    ConstantInterface, FunctionInterface, ImplementationsInterface, ModuleInterface,
+    PackageInterface, ParameterInterface, TypeAliasInterface, TypeConstructorInterface,
+    TypeDefinitionInterface,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// A change to the public API of a package that can break code using it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BreakingChange {
+    RemovedModule {
+        module: EcoString,
+    },
+    RemovedFunction {
+        module: EcoString,
+        name: EcoString,
+    },
+    /// The parameters or return type of the function changed, or one of its
+    /// parameters lost its label.
+    ChangedFunction {
+        module: EcoString,
+        name: EcoString,
+    },
+    RemovedConstant {
+        module: EcoString,
+        name: EcoString,
+    },
+    ChangedConstant {
+        module: EcoString,
+        name: EcoString,
+    },
+    RemovedTypeAlias {
+        module: EcoString,
+        name: EcoString,
+    },
+    ChangedTypeAlias {
+        module: EcoString,
+        name: EcoString,
+    },
+    RemovedType {
+        module: EcoString,
+        name: EcoString,
+    },
+    ChangedTypeParameters {
+        module: EcoString,
+        name: EcoString,
+    },
+    /// A constructor was removed, which includes the type being made opaque.
+    RemovedConstructor {
+        module: EcoString,
+        type_name: EcoString,
+        constructor: EcoString,
+    },
+    /// A constructor was added, so `case` expressions matching on every
+    /// constructor of the type are no longer exhaustive.
+    AddedConstructor {
+        module: EcoString,
+        type_name: EcoString,
+        constructor: EcoString,
+    },
+    ChangedConstructor {
+        module: EcoString,
+        type_name: EcoString,
+        constructor: EcoString,
+    },
+    /// A function or constant can no longer be used on a target it could be
+    /// used on before.
+    DroppedTarget {
+        module: EcoString,
+        name: EcoString,
+        target: Target,
+    },
+}
+
+impl BreakingChange {
+    /// The module of the definition that changed.
+    pub fn module(&self) -> &EcoString {
+        match self {
+            BreakingChange::RemovedModule { module }
+            | BreakingChange::RemovedFunction { module, .. }
+            | BreakingChange::ChangedFunction { module, .. }
+            | BreakingChange::RemovedConstant { module, .. }
+            | BreakingChange::ChangedConstant { module, .. }
+            | BreakingChange::RemovedTypeAlias { module, .. }
+            | BreakingChange::ChangedTypeAlias { module, .. }
+            | BreakingChange::RemovedType { module, .. }
+            | BreakingChange::ChangedTypeParameters { module, .. }
+            | BreakingChange::RemovedConstructor { module, .. }
+            | BreakingChange::AddedConstructor { module, .. }
+            | BreakingChange::ChangedConstructor { module, .. }
+            | BreakingChange::DroppedTarget { module, .. } => module,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            BreakingChange::RemovedModule { module } => {
+                format!("The module `{module}` was removed.")
+            }
+            BreakingChange::RemovedFunction { module, name } => {
+                format!("The function `{module}.{name}` was removed.")
+            }
+            BreakingChange::ChangedFunction { module, name } => {
+                format!("The type of the function `{module}.{name}` changed.")
+            }
+            BreakingChange::RemovedConstant { module, name } => {
+                format!("The constant `{module}.{name}` was removed.")
+            }
+            BreakingChange::ChangedConstant { module, name } => {
+                format!("The type of the constant `{module}.{name}` changed.")
+            }
+            BreakingChange::RemovedTypeAlias { module, name } => {
+                format!("The type alias `{module}.{name}` was removed.")
+            }
+            BreakingChange::ChangedTypeAlias { module, name } => {
+                format!("The type alias `{module}.{name}` changed.")
+            }
+            BreakingChange::RemovedType { module, name } => {
+                format!("The type `{module}.{name}` was removed.")
+            }
+            BreakingChange::ChangedTypeParameters { module, name } => {
+                format!("The number of parameters of the type `{module}.{name}` changed.")
+            }
+            BreakingChange::RemovedConstructor {
+                module,
+                type_name,
+                constructor,
+            } => format!(
+                "The constructor `{constructor}` of the type `{module}.{type_name}` was removed."
+            ),
+            BreakingChange::AddedConstructor {
+                module,
+                type_name,
+                constructor,
+            } => format!(
+                "The constructor `{constructor}` was added to the type `{module}.{type_name}`."
+            ),
+            BreakingChange::ChangedConstructor {
+                module,
+                type_name,
+                constructor,
+            } => format!(
+                "The fields of the constructor `{constructor}` of the type \
+`{module}.{type_name}` changed."
+            ),
+            BreakingChange::DroppedTarget {
+                module,
+                name,
+                target,
+            } => format!("`{module}.{name}` can no longer be used on the {target} target."),
+        }
+    }
+}
+
+/// The breaking changes between the interface of a release of a package and
+/// the interface of the release before it, sorted by module.
+pub fn breaking_changes(
+    previous: &PackageInterface,
+    current: &PackageInterface,
+) -> Vec<BreakingChange> {
+    let mut changes = vec![];
+    for (name, previous_module) in &previous.modules {
+        match current.modules.get(name) {
+            Some(current_module) => {
+                module_changes(name, previous_module, current_module, &mut changes)
+            }
+            None => changes.push(BreakingChange::RemovedModule {
+                module: name.clone(),
+            }),
+        }
+    }
+    changes.sort_by_cached_key(|change| (change.module().clone(), change.description()));
+    changes
+}
+
+/// Whether a release may make breaking changes. That is when it is a new
+/// major version, or a new minor version of a `0.x` package.
+pub fn allows_breaking_changes(previous: &Version, current: &Version) -> bool {
+    if current.major != previous.major {
+        return current.major > previous.major;
+    }
+    current.major == 0 && current.minor > previous.minor
+}
+
+fn module_changes(
+    module: &EcoString,
+    previous: &ModuleInterface,
+    current: &ModuleInterface,
+    changes: &mut Vec<BreakingChange>,
+) {
+    for (name, previous_function) in &previous.functions {
+        match current.functions.get(name) {
+            Some(current_function) => {
+                function_changes(module, name, previous_function, current_function, changes)
+            }
+            None => changes.push(BreakingChange::RemovedFunction {
+                module: module.clone(),
+                name: name.clone(),
+            }),
+        }
+    }
+
+    for (name, previous_constant) in &previous.constants {
+        match current.constants.get(name) {
+            Some(current_constant) => {
+                constant_changes(module, name, previous_constant, current_constant, changes)
+            }
+            None => changes.push(BreakingChange::RemovedConstant {
+                module: module.clone(),
+                name: name.clone(),
+            }),
+        }
+    }
+
+    for (name, previous_alias) in &previous.type_aliases {
+        match current.type_aliases.get(name) {
+            Some(current_alias) if alias_is_compatible(previous_alias, current_alias) => (),
+            Some(_) => changes.push(BreakingChange::ChangedTypeAlias {
+                module: module.clone(),
+                name: name.clone(),
+            }),
+            None => changes.push(BreakingChange::RemovedTypeAlias {
+                module: module.clone(),
+                name: name.clone(),
+            }),
+        }
+    }
+
+    for (name, previous_type) in &previous.types {
+        match current.types.get(name) {
+            Some(current_type) => type_changes(module, name, previous_type, current_type, changes),
+            None => changes.push(BreakingChange::RemovedType {
+                module: module.clone(),
+                name: name.clone(),
+            }),
+        }
+    }
+}
+
+fn function_changes(
+    module: &EcoString,
+    name: &EcoString,
+    previous: &FunctionInterface,
+    current: &FunctionInterface,
+    changes: &mut Vec<BreakingChange>,
+) {
+    if previous.return_ != current.return_
+        || !parameters_are_compatible(&previous.parameters, &current.parameters)
+    {
+        changes.push(BreakingChange::ChangedFunction {
+            module: module.clone(),
+            name: name.clone(),
+        });
+    }
+    target_changes(
+        module,
+        name,
+        previous.implementations,
+        current.implementations,
+        changes,
+    );
+}
+
+fn constant_changes(
+    module: &EcoString,
+    name: &EcoString,
+    previous: &ConstantInterface,
+    current: &ConstantInterface,
+    changes: &mut Vec<BreakingChange>,
+) {
+    if previous.type_ != current.type_ {
+        changes.push(BreakingChange::ChangedConstant {
+            module: module.clone(),
+            name: name.clone(),
+        });
+    }
+    target_changes(
+        module,
+        name,
+        previous.implementations,
+        current.implementations,
+        changes,
+    );
+}
+
+fn target_changes(
+    module: &EcoString,
+    name: &EcoString,
+    previous: ImplementationsInterface,
+    current: ImplementationsInterface,
+    changes: &mut Vec<BreakingChange>,
+) {
+    let dropped = [
+        (
+            Target::Erlang,
+            previous.can_run_on_erlang && !current.can_run_on_erlang,
+        ),
+        (
+            Target::JavaScript,
+            previous.can_run_on_javascript && !current.can_run_on_javascript,
+        ),
+    ];
+    for (target, _) in dropped.into_iter().filter(|(_, dropped)| *dropped) {
+        changes.push(BreakingChange::DroppedTarget {
+            module: module.clone(),
+            name: name.clone(),
+            target,
+        });
+    }
+}
+
+fn alias_is_compatible(previous: &TypeAliasInterface, current: &TypeAliasInterface) -> bool {
+    previous.parameters == current.parameters && previous.alias == current.alias
+}
+
+fn type_changes(
+    module: &EcoString,
+    name: &EcoString,
+    previous: &TypeDefinitionInterface,
+    current: &TypeDefinitionInterface,
+    changes: &mut Vec<BreakingChange>,
+) {
+    if previous.parameters != current.parameters {
+        changes.push(BreakingChange::ChangedTypeParameters {
+            module: module.clone(),
+            name: name.clone(),
+        });
+        // The types of the fields are bound to change with the parameters, so
+        // they are not reported as well.
+        return;
+    }
+
+    for previous_constructor in &previous.constructors {
+        let constructor = previous_constructor.name.clone();
+        match find_constructor(&current.constructors, &constructor) {
+            Some(current_constructor)
+                if parameters_are_compatible(
+                    &previous_constructor.parameters,
+                    &current_constructor.parameters,
+                ) => {}
+            Some(_) => changes.push(BreakingChange::ChangedConstructor {
+                module: module.clone(),
+                type_name: name.clone(),
+                constructor,
+            }),
+            None => changes.push(BreakingChange::RemovedConstructor {
+                module: module.clone(),
+                type_name: name.clone(),
+                constructor,
+            }),
+        }
+    }
+
+    // Code can't match on the constructors of an opaque or external type, so
+    // adding constructors to a type that had none doesn't break it.
+    if previous.constructors.is_empty() {
+        return;
+    }
+    for current_constructor in &current.constructors {
+        if find_constructor(&previous.constructors, &current_constructor.name).is_none() {
+            changes.push(BreakingChange::AddedConstructor {
+                module: module.clone(),
+                type_name: name.clone(),
+                constructor: current_constructor.name.clone(),
+            });
+        }
+    }
+}
+
+fn find_constructor<'a>(
+    constructors: &'a [TypeConstructorInterface],
+    name: &EcoString,
+) -> Option<&'a TypeConstructorInterface> {
+    constructors
+        .iter()
+        .find(|constructor| &constructor.name == name)
+}
+
+/// Parameters are compatible when they have the same types, and every label
+/// they had is kept. Labelling a parameter that had no label doesn't break
+/// calls to the function.
+fn parameters_are_compatible(
+    previous: &[ParameterInterface],
+    current: &[ParameterInterface],
+) -> bool {
+    previous.len() == current.len()
+        && previous.iter().zip(current).all(|(previous, current)| {
+            previous.type_ == current.type_
+                && (previous.label.is_none() || previous.label == current.label)
+        })
+}
@@ -0,0 +1,259 @@
+use hexpm::version::Version;
+
+use crate::{build::Target, package_interface::tests::compile_package};
+
+use super::{allows_breaking_changes, breaking_changes, BreakingChange, PackageInterface};
+
+/// The interface of a package, read back from the JSON it is published as.
+fn interface(module: &str, src: &str) -> PackageInterface {
+    let json = compile_package(Some(module), src, None);
+    serde_json::from_str(&json).expect("package interface json")
+}
+
+fn changes(previous: &str, current: &str) -> Vec<BreakingChange> {
+    breaking_changes(
+        &interface("wibble", previous),
+        &interface("wibble", current),
+    )
+}
+
+#[test]
+fn unchanged_interface() {
+    let src = "
+pub type Wibble { Wobble(label: Int) }
+pub type Alias = List(Int)
+pub const wubble = 1
+pub fn main(x: a) -> a { x }
+";
+    assert_eq!(changes(src, src), vec![]);
+}
+
+#[test]
+fn additions_are_not_breaking() {
+    assert_eq!(
+        changes(
+            "pub fn main() { 1 }",
+            "pub fn main() { 1 }
+pub fn wobble() { 2 }
+pub type Wibble { Wibble }
+pub const wubble = 1"
+        ),
+        vec![]
+    );
+}
+
+#[test]
+fn removed_module() {
+    assert_eq!(
+        breaking_changes(
+            &interface("wibble", "pub fn main() { 1 }"),
+            &interface("wobble", "pub fn main() { 1 }"),
+        ),
+        vec![BreakingChange::RemovedModule {
+            module: "wibble".into()
+        }]
+    );
+}
+
+#[test]
+fn removed_definitions() {
+    assert_eq!(
+        changes(
+            "pub fn main() { 1 }
+pub type Wibble { Wibble }
+pub type Alias = Int
+pub const wubble = 1",
+            "fn main() { 1 }"
+        ),
+        vec![
+            BreakingChange::RemovedConstant {
+                module: "wibble".into(),
+                name: "wubble".into()
+            },
+            BreakingChange::RemovedFunction {
+                module: "wibble".into(),
+                name: "main".into()
+            },
+            BreakingChange::RemovedType {
+                module: "wibble".into(),
+                name: "Wibble".into()
+            },
+            BreakingChange::RemovedTypeAlias {
+                module: "wibble".into(),
+                name: "Alias".into()
+            },
+        ]
+    );
+}
+
+#[test]
+fn changed_types() {
+    assert_eq!(
+        changes(
+            "pub fn main(x: Int) { x }
+pub type Alias = Int
+pub const wubble = 1",
+            "pub fn main(x: Float) { x }
+pub type Alias = Float
+pub const wubble = 1.0"
+        ),
+        vec![
+            BreakingChange::ChangedTypeAlias {
+                module: "wibble".into(),
+                name: "Alias".into()
+            },
+            BreakingChange::ChangedConstant {
+                module: "wibble".into(),
+                name: "wubble".into()
+            },
+            BreakingChange::ChangedFunction {
+                module: "wibble".into(),
+                name: "main".into()
+            },
+        ]
+    );
+}
+
+#[test]
+fn renamed_type_variables_are_not_breaking() {
+    assert_eq!(
+        changes(
+            "pub fn main(x: a, y: b) -> #(a, b) { #(x, y) }",
+            "pub fn main(first: one, second: two) -> #(one, two) { #(first, second) }"
+        ),
+        vec![]
+    );
+}
+
+#[test]
+fn adding_a_label_is_not_breaking() {
+    assert_eq!(
+        changes(
+            "pub fn main(x: Int) { x }",
+            "pub fn main(times x: Int) { x }"
+        ),
+        vec![]
+    );
+}
+
+#[test]
+fn removing_a_label_is_breaking() {
+    assert_eq!(
+        changes(
+            "pub fn main(times x: Int) { x }",
+            "pub fn main(x: Int) { x }"
+        ),
+        vec![BreakingChange::ChangedFunction {
+            module: "wibble".into(),
+            name: "main".into()
+        }]
+    );
+}
+
+#[test]
+fn changed_constructors() {
+    assert_eq!(
+        changes(
+            "pub type Wibble { Wobble(Int) Wubble }",
+            "pub type Wibble { Wobble(Float) Other }"
+        ),
+        vec![
+            BreakingChange::AddedConstructor {
+                module: "wibble".into(),
+                type_name: "Wibble".into(),
+                constructor: "Other".into()
+            },
+            BreakingChange::RemovedConstructor {
+                module: "wibble".into(),
+                type_name: "Wibble".into(),
+                constructor: "Wubble".into()
+            },
+            BreakingChange::ChangedConstructor {
+                module: "wibble".into(),
+                type_name: "Wibble".into(),
+                constructor: "Wobble".into()
+            },
+        ]
+    );
+}
+
+#[test]
+fn making_a_type_opaque_is_breaking() {
+    assert_eq!(
+        changes(
+            "pub type Wibble { Wobble }",
+            "pub opaque type Wibble { Wobble }"
+        ),
+        vec![BreakingChange::RemovedConstructor {
+            module: "wibble".into(),
+            type_name: "Wibble".into(),
+            constructor: "Wobble".into()
+        }]
+    );
+}
+
+#[test]
+fn adding_constructors_to_an_opaque_type_is_not_breaking() {
+    assert_eq!(
+        changes(
+            "pub opaque type Wibble { Wobble }",
+            "pub opaque type Wibble { Wobble Wubble }"
+        ),
+        vec![]
+    );
+}
+
+#[test]
+fn changed_type_parameters() {
+    assert_eq!(
+        changes(
+            "pub type Wibble { Wobble }",
+            "pub type Wibble(a) { Wobble }"
+        ),
+        vec![BreakingChange::ChangedTypeParameters {
+            module: "wibble".into(),
+            name: "Wibble".into()
+        }]
+    );
+}
+
+#[test]
+fn dropped_target() {
+    assert_eq!(
+        changes(
+            "pub fn main() -> Int { 1 }",
+            "@external(erlang, \"wibble\", \"main\")
+pub fn main() -> Int"
+        ),
+        vec![BreakingChange::DroppedTarget {
+            module: "wibble".into(),
+            name: "main".into(),
+            target: Target::JavaScript,
+        }]
+    );
+}
+
+#[test]
+fn versions_allowing_breaking_changes() {
+    let version = |version| Version::parse(version).expect("version");
+    assert!(allows_breaking_changes(
+        &version("1.2.3"),
+        &version("2.0.0")
+    ));
+    assert!(allows_breaking_changes(
+        &version("0.2.3"),
+        &version("0.3.0")
+    ));
+    assert!(!allows_breaking_changes(
+        &version("1.2.3"),
+        &version("1.3.0")
+    ));
+    assert!(!allows_breaking_changes(
+        &version("1.2.3"),
+        &version("1.2.4")
+    ));
+    assert!(!allows_breaking_changes(
+        &version("0.2.3"),
+        &version("0.2.4")
+    ));
+}
@@ -7,7 +7,10 @@ use hexpm::version::Identifier;
 use crate::{
     analyse::TargetSupport,
     build::{Module, Origin, Package, Target},
-    config::{Docs, ErlangConfig, JavaScriptConfig, PackageConfig, Repository},
+    config::{
+        Docs, ErlangConfig, FormatConfig, JavaScriptConfig, LintsConfig, PackageConfig, Repository,
+        WarningsConfig,
+    },
     line_numbers::LineNumbers,
     type_::PRELUDE_MODULE_NAME,
     uid::UniqueIdGenerator,
@@ -150,7 +153,7 @@ fn package_from_module(module: Module) -> Package {
             gleam_version: Some("1.0.0".into()),
             licences: vec![],
             description: "description".into(),
-            documentation: Docs { pages: vec![] },
+            documentation: Docs::default(),
             dependencies: std::collections::HashMap::new(),
             dev_dependencies: std::collections::HashMap::new(),
             repository: Repository::default(),
@@ -161,6 +164,10 @@ fn package_from_module(module: Module) -> Package {
             internal_modules: Some(vec![GlobBuilder::new("internals/*")
                 .build()
                 .expect("internals glob")]),
+            warnings: WarningsConfig::default(),
+            lints: LintsConfig::default(),
+            format: FormatConfig::default(),
+            remove_echo: false,
         },
         modules: vec![module],
     }
@@ -296,3 +303,22 @@ pub type Box(a, b) {
 pub fn internal_modules_are_not_exported() {
     assert_package_interface_with_name!("internals/internal_module", "pub fn main() { 1 }");
 }
+
+#[test]
+pub fn since_attributes() {
+    assert_package_interface!(
+        r#"
+@since("1.1.0")
+pub type Wibble = Int
+
+@since("1.2.0")
+pub type Wobble { Wobble }
+
+@since("1.3.0")
+pub const wubble = 1
+
+@since("1.4.0")
+pub fn main() { Wobble }
+"#
+    );
+}
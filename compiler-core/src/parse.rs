@@ -52,7 +52,7 @@
 pub mod error;
 pub mod extra;
 pub mod lexer;
-mod token;
+pub mod token;
 
 use crate::analyse::Inferred;
 use crate::ast::{
@@ -113,14 +113,16 @@ enum InternalAttribute {
 struct Attributes {
     target: Option<Target>,
     deprecated: Deprecation,
+    since: Option<EcoString>,
     external_erlang: Option<(EcoString, EcoString)>,
     external_javascript: Option<(EcoString, EcoString)>,
     internal: InternalAttribute,
+    must_use: bool,
 }
 
 impl Attributes {
     fn has_function_only(&self) -> bool {
-        self.external_erlang.is_some() || self.external_javascript.is_some()
+        self.external_erlang.is_some() || self.external_javascript.is_some() || self.must_use
     }
 }
 
@@ -135,10 +137,25 @@ pub fn parse_module(src: &str) -> Result<Parsed, ParseError> {
     Ok(parsed)
 }
 
-//
-// Test Interface
-//
-#[cfg(test)]
+/// Parses a module, carrying on after syntax errors so that as much of the
+/// module as possible can still be analysed. Definitions that could not be
+/// parsed are left out, and the body of a function that could not be parsed
+/// is replaced with an `UntypedExpr::Invalid` expression.
+///
+/// The errors are returned in the order in which they were found.
+///
+pub fn parse_module_with_recovery(src: &str) -> (Parsed, Vec<ParseError>) {
+    let lex = lexer::make_tokenizer(src);
+    let mut parser = Parser::new(lex);
+    parser.recovered_errors = Some(vec![]);
+    let (mut parsed, errors) = parser.parse_module_with_recovery();
+    parsed.extra = parser.extra;
+    (parsed, errors)
+}
+
+/// Parses a sequence of statements, such as the body of a function or the
+/// input of the shell, rather than a module.
+///
 pub fn parse_statement_sequence(src: &str) -> Result<Vec1<UntypedStatement>, ParseError> {
     let lex = lexer::make_tokenizer(src);
     let mut parser = Parser::new(lex);
@@ -151,6 +168,39 @@ pub fn parse_statement_sequence(src: &str) -> Result<Vec1<UntypedStatement>, Par
     }
 }
 
+/// Parses the source of an integer literal, as produced by the lexer, into its
+/// value. Values too large to fit into an `i128` saturate to `i128::MAX` or
+/// `-i128::MAX`.
+///
+/// Returns `None` if the literal is not valid.
+///
+pub fn parse_int_value(value: &str) -> Option<i128> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+
+    let (radix, digits) = if let Some(digits) = value.strip_prefix("0x") {
+        (16, digits)
+    } else if let Some(digits) = value.strip_prefix("0o") {
+        (8, digits)
+    } else if let Some(digits) = value.strip_prefix("0b") {
+        (2, digits)
+    } else {
+        (10, value)
+    };
+
+    let mut result: i128 = 0;
+    for digit in digits.chars().filter(|c| *c != '_') {
+        let digit = digit.to_digit(radix)?;
+        result = result
+            .saturating_mul(radix as i128)
+            .saturating_add(digit as i128);
+    }
+
+    Some(if negative { -result } else { result })
+}
+
 //
 // Test Interface
 //
@@ -178,6 +228,9 @@ pub struct Parser<T: Iterator<Item = LexResult>> {
     tok1: Option<Spanned>,
     extra: ModuleExtra,
     doc_comments: VecDeque<(u32, String)>,
+    /// When parsing with error recovery the syntax errors that have been
+    /// recovered from are collected here.
+    recovered_errors: Option<Vec<ParseError>>,
 }
 impl<T> Parser<T>
 where
@@ -191,6 +244,7 @@ where
             tok1: None,
             extra: ModuleExtra::new(),
             doc_comments: VecDeque::new(),
+            recovered_errors: None,
         };
         parser.advance();
         parser.advance();
@@ -212,6 +266,94 @@ where
         })
     }
 
+    fn parse_module_with_recovery(&mut self) -> (Parsed, Vec<ParseError>) {
+        let mut definitions = vec![];
+        loop {
+            let start = self.tok0.as_ref().map(|(start, _, _)| *start);
+            match self.parse_definition() {
+                Ok(Some(definition)) => {
+                    definitions.push(definition);
+                    continue;
+                }
+                Ok(None) => match self.tok0.as_ref().map(|(start, _, end)| (*start, *end)) {
+                    None => break,
+                    Some((start, end)) => self.record_recovered_error(ParseError {
+                        error: ParseErrorType::UnexpectedToken {
+                            expected: vec!["An import, const, type, or function.".into()],
+                            hint: None,
+                        },
+                        location: SrcSpan { start, end },
+                    }),
+                },
+                Err(error) => self.record_recovered_error(error),
+            }
+
+            // Make sure that we always move forward, even if the error was
+            // at the start of what looked like a definition.
+            if self.tok0.as_ref().map(|(start, _, _)| *start) == start {
+                self.advance();
+            }
+            let _ = self.skip_to_next_definition();
+        }
+
+        let mut errors: Vec<_> = self
+            .lex_errors
+            .iter()
+            .map(|error| ParseError {
+                error: ParseErrorType::LexError {
+                    error: error.clone(),
+                },
+                location: error.location,
+            })
+            .collect();
+        errors.extend(self.recovered_errors.take().unwrap_or_default());
+
+        let module = Module {
+            name: "".into(),
+            documentation: vec![],
+            type_info: (),
+            definitions,
+        };
+        let parsed = Parsed {
+            module,
+            extra: Default::default(),
+        };
+        (parsed, errors)
+    }
+
+    fn record_recovered_error(&mut self, error: ParseError) {
+        self.recovered_errors
+            .as_mut()
+            .expect("Recovering from an error when not parsing with recovery")
+            .push(error);
+    }
+
+    /// Skips tokens until one that can only be the start of a module level
+    /// definition, returning the end of the last token skipped.
+    /// Anonymous functions can appear within expressions so `fn` is only
+    /// taken to start a definition when it is followed by a name.
+    fn skip_to_next_definition(&mut self) -> Option<u32> {
+        let mut end = None;
+        loop {
+            match (&self.tok0, &self.tok1) {
+                (
+                    None
+                    | Some((
+                        _,
+                        Token::Import | Token::Const | Token::Type | Token::Pub | Token::At,
+                        _,
+                    )),
+                    _,
+                )
+                | (Some((_, Token::Fn, _)), Some((_, Token::Name { .. }, _))) => return end,
+                (Some((_, _, token_end)), _) => {
+                    end = Some(*token_end);
+                    self.advance();
+                }
+            }
+        }
+    }
+
     // The way the parser is currently implemented, it cannot exit immediately while advancing
     // the token stream upon seeing a LexError. That is to avoid having to put `?` all over the
     // place and instead we collect LexErrors in `self.lex_errors` and attempt to continue parsing.
@@ -479,6 +621,23 @@ where
                 }
             }
 
+            // echo, echo expr
+            Some((start, Token::Echo, end)) => {
+                self.advance();
+                // An expression on a later line is the next statement rather
+                // than the value to print, as in `x |> echo` ending a pipeline.
+                let expression = if self.next_token_on_new_line(end) {
+                    None
+                } else {
+                    self.parse_expression()?
+                };
+                let end = expression.as_ref().map_or(end, |e| e.location().end);
+                UntypedExpr::Echo {
+                    location: SrcSpan { start, end },
+                    expression: expression.map(Box::new),
+                }
+            }
+
             Some((start, Token::Hash, _)) => {
                 self.advance();
                 let _ = self.expect_one(&Token::LeftParen)?;
@@ -699,7 +858,7 @@ where
 
         // field access and call can stack up
         loop {
-            if let Some((dot_start, _)) = self.maybe_one(&Token::Dot) {
+            if let Some((dot_start, dot_end)) = self.maybe_one(&Token::Dot) {
                 let start = expr.location().start;
                 // field access
                 match self.tok0.take() {
@@ -747,6 +906,35 @@ where
                         }
                     }
 
+                    // When recovering from errors a field access that is
+                    // still being written, such as `wibble.`, is kept as the
+                    // value before the dot followed by an invalid expression,
+                    // so the language server can tell its type to complete
+                    // the name of the field.
+                    t0 if self.recovered_errors.is_some() => {
+                        let location = match &t0 {
+                            Some((start, _, end)) => SrcSpan::new(*start, *end),
+                            None => SrcSpan::new(dot_start, dot_end),
+                        };
+                        self.tok0 = t0;
+                        self.record_recovered_error(ParseError {
+                            error: ParseErrorType::UnexpectedToken {
+                                expected: vec!["A positive integer or a field name.".into()],
+                                hint: None,
+                            },
+                            location,
+                        });
+                        let dot = SrcSpan::new(dot_start, dot_end);
+                        expr = UntypedExpr::Block {
+                            location: SrcSpan::new(start, dot_end),
+                            statements: vec1![
+                                Statement::Expression(expr),
+                                Statement::Expression(UntypedExpr::Invalid { location: dot }),
+                            ],
+                        };
+                        break;
+                    }
+
                     t0 => {
                         self.tok0 = t0;
                         return self.next_tok_unexpected(vec![
@@ -959,19 +1147,32 @@ where
                 }
 
                 if self.maybe_one(&Token::Dot).is_some() {
-                    // We're doing this to get a better error message instead of a generic
-                    // `I was expecting a type`, you can have a look at this issue to get
-                    // a better idea: https://github.com/gleam-lang/gleam/issues/2841.
-                    match self.expect_constructor_pattern(Some((start, name, end))) {
-                        Ok(result) => result,
-                        Err(ParseError {
-                            location: SrcSpan { end, .. },
-                            ..
-                        }) => {
-                            return parse_error(
-                                ParseErrorType::InvalidModuleTypePattern,
-                                SrcSpan { start, end },
-                            )
+                    if let Some((_, Token::Name { .. }, _)) = self.tok0 {
+                        // A constant from another module. Whether the module
+                        // is imported is checked by the type checker.
+                        let (_, constant, end) = self.expect_name()?;
+                        Pattern::Constant {
+                            location: SrcSpan { start, end },
+                            module: name,
+                            name: constant,
+                            constructor: None,
+                            type_: (),
+                        }
+                    } else {
+                        // We're doing this to get a better error message instead of a generic
+                        // `I was expecting a type`, you can have a look at this issue to get
+                        // a better idea: https://github.com/gleam-lang/gleam/issues/2841.
+                        match self.expect_constructor_pattern(Some((start, name, end))) {
+                            Ok(result) => result,
+                            Err(ParseError {
+                                location: SrcSpan { end, .. },
+                                ..
+                            }) => {
+                                return parse_error(
+                                    ParseErrorType::InvalidModuleTypePattern,
+                                    SrcSpan { start, end },
+                                )
+                            }
                         }
                     }
                 } else {
@@ -1468,12 +1669,13 @@ where
     ) -> Result<Option<CallArg<UntypedPattern>>, ParseError> {
         match (self.tok0.take(), self.tok1.take()) {
             // named arg
-            (Some((start, Token::Name { name }, _)), Some((col_s, Token::Colon, col_e))) => {
+            (Some((start, Token::Name { name }, _)), Some((_, Token::Colon, end))) => {
                 self.advance();
                 self.advance();
                 if let Some(value) = self.parse_pattern()? {
                     Ok(Some(CallArg {
                         implicit: false,
+                        label_shorthand: false,
                         location: SrcSpan {
                             start,
                             end: value.location().end,
@@ -1482,13 +1684,20 @@ where
                         value,
                     }))
                 } else {
-                    parse_error(
-                        ParseErrorType::ExpectedPattern,
-                        SrcSpan {
-                            start: col_s,
-                            end: col_e,
+                    // A label with no pattern is shorthand for assigning the
+                    // field to a variable with the same name as the label.
+                    let location = SrcSpan { start, end };
+                    Ok(Some(CallArg {
+                        implicit: false,
+                        label_shorthand: true,
+                        location,
+                        label: Some(name.clone()),
+                        value: Pattern::Variable {
+                            location,
+                            name,
+                            type_: (),
                         },
-                    )
+                    }))
                 }
             }
             // unnamed arg
@@ -1498,6 +1707,7 @@ where
                 if let Some(value) = self.parse_pattern()? {
                     Ok(Some(CallArg {
                         implicit: false,
+                        label_shorthand: false,
                         location: value.location(),
                         label: None,
                         value,
@@ -1511,9 +1721,10 @@ where
 
     // examples:
     //   a: expr
+    //   a:
     fn parse_record_update_arg(&mut self) -> Result<Option<UntypedRecordUpdateArg>, ParseError> {
         if let Some((start, label, _)) = self.maybe_name() {
-            let _ = self.expect_one(&Token::Colon)?;
+            let (_, end) = self.expect_one(&Token::Colon)?;
             let value = self.parse_expression()?;
             if let Some(value) = value {
                 Ok(Some(UntypedRecordUpdateArg {
@@ -1523,9 +1734,21 @@ where
                         end: value.location().end,
                     },
                     value,
+                    label_shorthand: false,
                 }))
             } else {
-                self.next_tok_unexpected(vec!["An expression".into()])
+                // A label with no value is shorthand for using the variable
+                // with the same name as the label.
+                let location = SrcSpan { start, end };
+                Ok(Some(UntypedRecordUpdateArg {
+                    label: label.clone(),
+                    location,
+                    value: UntypedExpr::Var {
+                        location,
+                        name: label,
+                    },
+                    label_shorthand: true,
+                }))
             }
         } else {
             Ok(None)
@@ -1569,9 +1792,18 @@ where
         let return_annotation = self.parse_type_annotation(&Token::RArrow)?;
 
         let (body, end, end_position) = match self.maybe_one(&Token::LeftBrace) {
-            Some(_) => {
-                let some_body = self.parse_statement_seq()?;
-                let (_, rbr_e) = self.expect_one(&Token::RightBrace)?;
+            Some((lbr_s, lbr_e)) => {
+                let (some_body, rbr_e) = match self.parse_function_body() {
+                    Ok(body) => body,
+                    Err(error) if !is_anon && self.recovered_errors.is_some() => {
+                        self.record_recovered_error(error);
+                        let body_end = self.skip_to_next_definition().unwrap_or(lbr_e);
+                        let location = SrcSpan::new(lbr_s, body_end);
+                        let body = vec1![Statement::Expression(UntypedExpr::Invalid { location })];
+                        (Some(body), body_end)
+                    }
+                    Err(error) => return Err(error),
+                };
                 let end = return_annotation
                     .as_ref()
                     .map(|l| l.location().end)
@@ -1582,7 +1814,7 @@ where
                         location: SrcSpan { start, end },
                         message: None,
                     })],
-                    Some((body, _)) => body,
+                    Some(body) => body,
                 };
 
                 (body, end, rbr_e)
@@ -1614,6 +1846,8 @@ where
             return_type: (),
             return_annotation,
             deprecation: std::mem::take(&mut attributes.deprecated),
+            since: attributes.since.take(),
+            must_use: std::mem::take(&mut attributes.must_use),
             external_erlang: attributes.external_erlang.take(),
             external_javascript: attributes.external_javascript.take(),
             implementations: Implementations {
@@ -1626,6 +1860,12 @@ where
         })))
     }
 
+    fn parse_function_body(&mut self) -> Result<(Option<Vec1<UntypedStatement>>, u32), ParseError> {
+        let body = self.parse_statement_seq()?.map(|(body, _)| body);
+        let (_, rbr_e) = self.expect_one(&Token::RightBrace)?;
+        Ok((body, rbr_e))
+    }
+
     fn publicity(
         &self,
         public: bool,
@@ -1742,13 +1982,16 @@ where
     //   expr
     //   a: _
     //   a: expr
+    //   a:
     fn parse_fn_arg(&mut self) -> Result<Option<ParserArg>, ParseError> {
         let mut start = 0;
+        let mut label_end = 0;
         let label = match (self.tok0.take(), &self.tok1) {
-            (Some((s, Token::Name { name }, _)), Some((_, Token::Colon, _))) => {
+            (Some((s, Token::Name { name }, _)), Some((_, Token::Colon, e))) => {
+                start = s;
+                label_end = *e;
                 self.advance();
                 self.advance();
-                start = s;
                 Some(name)
             }
             (t0, _) => {
@@ -1764,20 +2007,44 @@ where
             };
             Ok(Some(ParserArg::Arg(Box::new(CallArg {
                 implicit: false,
+                label_shorthand: false,
                 label,
                 location,
                 value,
             }))))
-        } else if let Some((start, name, end)) = self.maybe_discard_name() {
-            let mut location = SrcSpan { start, end };
-            if label.is_some() {
-                location.start = start
+        } else if let Some((hole_start, name, end)) = self.maybe_discard_name() {
+            let location = SrcSpan {
+                start: hole_start,
+                end,
+            };
+            let arg_location = if label.is_some() {
+                SrcSpan { start, end }
+            } else {
+                location
             };
             Ok(Some(ParserArg::Hole {
                 location,
+                arg_location,
                 name,
                 label,
             }))
+        } else if let Some(label) = label {
+            // A label with no value is shorthand for passing the variable
+            // with the same name as the label.
+            let location = SrcSpan {
+                start,
+                end: label_end,
+            };
+            Ok(Some(ParserArg::Arg(Box::new(CallArg {
+                implicit: false,
+                label_shorthand: true,
+                label: Some(label.clone()),
+                location,
+                value: UntypedExpr::Var {
+                    location,
+                    name: label,
+                },
+            }))))
         } else {
             Ok(None)
         }
@@ -1843,6 +2110,7 @@ where
                     type_ast: t,
                     type_: (),
                     deprecation: std::mem::take(&mut attributes.deprecated),
+                    since: attributes.since.take(),
                 })));
             } else {
                 return parse_error(ParseErrorType::ExpectedType, SrcSpan::new(eq_s, eq_e));
@@ -1861,6 +2129,7 @@ where
             constructors,
             typed_parameters: vec![],
             deprecation: std::mem::take(&mut attributes.deprecated),
+            since: attributes.since.take(),
         })))
     }
 
@@ -2255,6 +2524,7 @@ where
                 value: Box::new(value),
                 type_: (),
                 deprecation: attributes.deprecated.clone(),
+                since: attributes.since.clone(),
                 implementations: Implementations {
                     gleam: true,
                     can_run_on_erlang: true,
@@ -2460,11 +2730,13 @@ where
 
     // examples:
     //  name: const
+    //  name:
     //  const
     fn parse_const_record_arg(&mut self) -> Result<Option<CallArg<UntypedConstant>>, ParseError> {
         let name = match (self.tok0.take(), &self.tok1) {
             // Named arg
-            (Some((start, Token::Name { name }, end)), Some((_, Token::Colon, _))) => {
+            (Some((start, Token::Name { name }, _)), Some((_, Token::Colon, end))) => {
+                let end = *end;
                 self.advance();
                 self.advance();
                 Some((start, name, end))
@@ -2481,6 +2753,7 @@ where
             if let Some((start, label, _)) = name {
                 Ok(Some(CallArg {
                     implicit: false,
+                    label_shorthand: false,
                     location: SrcSpan {
                         start,
                         end: value.location().end,
@@ -2491,13 +2764,29 @@ where
             } else {
                 Ok(Some(CallArg {
                     implicit: false,
+                    label_shorthand: false,
                     location: value.location(),
                     value,
                     label: None,
                 }))
             }
-        } else if name.is_some() {
-            self.next_tok_unexpected(vec!["a constant value".into()])?
+        } else if let Some((start, label, end)) = name {
+            // A label with no value is shorthand for using the constant with
+            // the same name as the label.
+            let location = SrcSpan { start, end };
+            Ok(Some(CallArg {
+                implicit: false,
+                label_shorthand: true,
+                location,
+                value: Constant::Var {
+                    location,
+                    module: None,
+                    name: label.clone(),
+                    constructor: None,
+                    typ: (),
+                },
+                label: Some(label),
+            }))
         } else {
             Ok(None)
         }
@@ -2865,6 +3154,19 @@ where
         }
     }
 
+    /// Whether there is a line break between the position and the next token.
+    fn next_token_on_new_line(&self, position: u32) -> bool {
+        let Some((start, _, _)) = &self.tok0 else {
+            return false;
+        };
+        self.extra
+            .new_lines
+            .iter()
+            .rev()
+            .take_while(|new_line| **new_line >= position)
+            .any(|new_line| new_line < start)
+    }
+
     // Moves the token stream forward
     fn advance(&mut self) {
         let _ = self.next_tok();
@@ -2881,6 +3183,9 @@ where
                 Some(Ok((s, Token::EmptyLine, _))) => {
                     self.extra.empty_lines.push(s);
                 }
+                Some(Ok((s, Token::NewLine, _))) => {
+                    self.extra.new_lines.push(s);
+                }
                 Some(Ok((start, Token::CommentNormal, end))) => {
                     self.extra.comments.push(SrcSpan { start, end });
                 }
@@ -2974,7 +3279,12 @@ where
                 let _ = self.expect_one(&Token::LeftParen)?;
                 self.parse_deprecated_attribute(start, end, attributes)
             }
+            "since" => {
+                let _ = self.expect_one(&Token::LeftParen)?;
+                self.parse_since_attribute(start, end, attributes)
+            }
             "internal" => self.parse_internal_attribute(start, end, attributes),
+            "must_use" => self.parse_must_use_attribute(start, end, attributes),
             _ => parse_error(ParseErrorType::UnknownAttribute, SrcSpan { start, end }),
         }?;
 
@@ -3055,6 +3365,34 @@ where
         Ok(end)
     }
 
+    fn parse_since_attribute(
+        &mut self,
+        start: u32,
+        end: u32,
+        attributes: &mut Attributes,
+    ) -> Result<u32, ParseError> {
+        if attributes.since.is_some() {
+            return parse_error(ParseErrorType::DuplicateAttribute, SrcSpan::new(start, end));
+        }
+        let (_, version, _) = self.expect_string()?;
+        let (_, end) = self.expect_one(&Token::RightParen)?;
+        attributes.since = Some(version);
+        Ok(end)
+    }
+
+    fn parse_must_use_attribute(
+        &mut self,
+        start: u32,
+        end: u32,
+        attributes: &mut Attributes,
+    ) -> Result<u32, ParseError> {
+        if attributes.must_use {
+            return parse_error(ParseErrorType::DuplicateAttribute, SrcSpan::new(start, end));
+        }
+        attributes.must_use = true;
+        Ok(end)
+    }
+
     fn parse_internal_attribute(
         &mut self,
         start: u32,
@@ -3442,7 +3780,8 @@ fn is_reserved_word(tok: Token) -> bool {
         | Token::EndOfFile
         | Token::CommentNormal
         | Token::CommentModule
-        | Token::EmptyLine => false,
+        | Token::EmptyLine
+        | Token::NewLine => false,
     }
 }
 
@@ -3452,7 +3791,10 @@ pub enum ParserArg {
     Arg(Box<CallArg<UntypedExpr>>),
     Hole {
         name: EcoString,
+        /// The location of the hole itself.
         location: SrcSpan,
+        /// The location of the whole argument, including its label.
+        arg_location: SrcSpan,
         label: Option<EcoString>,
     },
 }
@@ -3470,6 +3812,7 @@ pub fn make_call(
             ParserArg::Arg(arg) => Ok(*arg),
             ParserArg::Hole {
                 location,
+                arg_location,
                 name,
                 label,
             } => {
@@ -3487,8 +3830,9 @@ pub fn make_call(
 
                 Ok(CallArg {
                     implicit: false,
+                    label_shorthand: false,
                     label,
-                    location,
+                    location: arg_location,
                     value: UntypedExpr::Var {
                         location,
                         name: CAPTURE_VARIABLE.into(),
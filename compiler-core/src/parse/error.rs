@@ -1,4 +1,5 @@
 use crate::ast::SrcSpan;
+use crate::diagnostic::Code;
 use crate::error::wrap;
 use ecow::EcoString;
 use heck::{ToSnakeCase, ToUpperCamelCase};
@@ -289,6 +290,58 @@ pub enum ParseErrorType {
     InvalidModuleTypePattern,   // for patterns that have a dot like: `name.thing`
 }
 
+impl ParseErrorType {
+    /// The stable code identifying this kind of syntax error.
+    pub fn code(&self) -> Code {
+        match self {
+            ParseErrorType::ExpectedEqual => Code::Error(100),
+            ParseErrorType::ExpectedExpr => Code::Error(101),
+            ParseErrorType::ExpectedName => Code::Error(102),
+            ParseErrorType::ExpectedPattern => Code::Error(103),
+            ParseErrorType::ExpectedType => Code::Error(104),
+            ParseErrorType::ExpectedUpName => Code::Error(105),
+            ParseErrorType::ExpectedValue => Code::Error(106),
+            ParseErrorType::ExpectedStatement => Code::Error(107),
+            ParseErrorType::ExpectedDefinition => Code::Error(108),
+            ParseErrorType::ExpectedFunctionDefinition => Code::Error(109),
+            ParseErrorType::ExprLparStart => Code::Error(110),
+            ParseErrorType::ExtraSeparator => Code::Error(111),
+            ParseErrorType::IncorrectName => Code::Error(112),
+            ParseErrorType::IncorrectUpName => Code::Error(113),
+            ParseErrorType::InvalidBitArraySegment => Code::Error(114),
+            ParseErrorType::InvalidBitArrayUnit => Code::Error(115),
+            ParseErrorType::InvalidTailPattern => Code::Error(116),
+            ParseErrorType::InvalidTupleAccess => Code::Error(117),
+            ParseErrorType::LexError { .. } => Code::Error(118),
+            ParseErrorType::NestedBitArrayPattern => Code::Error(119),
+            ParseErrorType::NoExpression => Code::Error(120),
+            ParseErrorType::NoLetBinding => Code::Error(121),
+            ParseErrorType::NoValueAfterEqual => Code::Error(122),
+            ParseErrorType::NotConstType => Code::Error(123),
+            ParseErrorType::OpNakedRight => Code::Error(124),
+            ParseErrorType::OpaqueTypeAlias => Code::Error(125),
+            ParseErrorType::TooManyArgHoles => Code::Error(126),
+            ParseErrorType::DuplicateAttribute => Code::Error(127),
+            ParseErrorType::UnknownAttribute => Code::Error(128),
+            ParseErrorType::UnknownTarget => Code::Error(129),
+            ParseErrorType::ListSpreadWithoutElements => Code::Error(130),
+            ParseErrorType::ListSpreadFollowedByElements => Code::Error(131),
+            ParseErrorType::LowcaseBooleanPattern => Code::Error(132),
+            ParseErrorType::UnexpectedLabel => Code::Error(133),
+            ParseErrorType::UnexpectedEof => Code::Error(134),
+            ParseErrorType::UnexpectedReservedWord => Code::Error(135),
+            ParseErrorType::UnexpectedToken { .. } => Code::Error(136),
+            ParseErrorType::ExpectedBoolean => Code::Error(137),
+            ParseErrorType::UnexpectedFunction => Code::Error(138),
+            ParseErrorType::ConcatPatternVariableLeftHandSide => Code::Error(139),
+            ParseErrorType::ListSpreadWithoutTail => Code::Error(140),
+            ParseErrorType::ExpectedFunctionBody => Code::Error(141),
+            ParseErrorType::RedundantInternalAttribute => Code::Error(142),
+            ParseErrorType::InvalidModuleTypePattern => Code::Error(143),
+        }
+    }
+}
+
 impl LexicalError {
     pub fn to_parse_error_info(&self) -> (&'static str, Vec<String>) {
         match &self.error {
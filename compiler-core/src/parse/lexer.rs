@@ -439,8 +439,10 @@ where
 
                 let mut newlines = 0;
                 while let Some('\n' | ' ' | '\t' | '\x0C') = self.chr0 {
+                    let position = self.get_pos();
                     if self.next_char() == Some('\n') {
                         newlines += 1;
+                        self.emit((position, Token::NewLine, position + 1));
                     }
                 }
                 if newlines > 1 {
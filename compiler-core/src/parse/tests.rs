@@ -1,8 +1,9 @@
-use crate::ast::SrcSpan;
+use crate::ast::{Definition, SrcSpan, Statement, UntypedExpr};
 use crate::parse::error::{
     InvalidUnicodeEscapeError, LexicalError, LexicalErrorType, ParseError, ParseErrorType,
 };
 use camino::Utf8PathBuf;
+use itertools::Itertools;
 
 use pretty_assertions::assert_eq;
 
@@ -48,6 +49,49 @@ pub fn expect_module_error(src: &str) -> String {
     error.pretty_string()
 }
 
+macro_rules! assert_parse_module_with_recovery {
+    ($src:expr) => {
+        let result = $crate::parse::tests::parse_with_recovery($src);
+        insta::assert_snapshot!(insta::internals::AutoName, result, $src);
+    };
+}
+
+pub fn parse_with_recovery(src: &str) -> String {
+    let (parsed, errors) = crate::parse::parse_module_with_recovery(src);
+    let definitions = parsed
+        .module
+        .definitions
+        .iter()
+        .map(|definition| match &definition.definition {
+            Definition::Function(function)
+                if matches!(
+                    function.body.first(),
+                    Statement::Expression(UntypedExpr::Invalid { .. })
+                ) =>
+            {
+                format!("fn {} with an invalid body", function.name)
+            }
+            Definition::Function(function) => format!("fn {}", function.name),
+            Definition::TypeAlias(alias) => format!("type {}", alias.alias),
+            Definition::CustomType(custom_type) => format!("type {}", custom_type.name),
+            Definition::Import(import) => format!("import {}", import.module),
+            Definition::ModuleConstant(constant) => format!("const {}", constant.name),
+        })
+        .join("\n");
+    let errors = errors
+        .into_iter()
+        .map(|error| {
+            crate::error::Error::Parse {
+                src: src.into(),
+                path: Utf8PathBuf::from("/src/parse/error.gleam"),
+                error,
+            }
+            .pretty_string()
+        })
+        .join("\n");
+    format!("{definitions}\n\n{errors}")
+}
+
 pub fn expect_error(src: &str) -> String {
     let result = crate::parse::parse_statement_sequence(src).expect_err("should not parse");
     let error = crate::error::Error::Parse {
@@ -624,6 +668,44 @@ pub fn main() -> Nil {
     );
 }
 
+#[test]
+fn multiple_must_use_attributes() {
+    assert_module_error!(
+        r#"
+@must_use
+@must_use
+pub fn main() -> Nil {
+  Nil
+}
+"#
+    );
+}
+
+#[test]
+fn multiple_since_attributes() {
+    assert_module_error!(
+        r#"
+@since("1.0.0")
+@since("1.1.0")
+pub fn main() -> Nil {
+  Nil
+}
+"#
+    );
+}
+
+#[test]
+fn must_use_attribute_on_type() {
+    assert_module_error!(
+        r#"
+@must_use
+pub type Wibble {
+  Wibble
+}
+"#
+    );
+}
+
 #[test]
 fn attributes_with_no_definition() {
     assert_module_error!(
@@ -849,7 +931,7 @@ fn wrong_record_access_pattern() {
         "
 pub fn main() {
   case wibble {
-    wibble.thing -> 1
+    wibble.1 -> 1
   }
 }
 "
@@ -1020,3 +1102,105 @@ const a = A(\"a\", let)
 "
     );
 }
+
+#[test]
+fn int_values() {
+    use crate::parse::parse_int_value;
+    assert_eq!(parse_int_value("0"), Some(0));
+    assert_eq!(parse_int_value("1_000_000"), Some(1_000_000));
+    assert_eq!(parse_int_value("-42"), Some(-42));
+    assert_eq!(parse_int_value("0xFF"), Some(255));
+    assert_eq!(parse_int_value("0o17"), Some(15));
+    assert_eq!(parse_int_value("-0b1010"), Some(-10));
+    assert_eq!(
+        parse_int_value("999999999999999999999999999999999999999999999999"),
+        Some(i128::MAX)
+    );
+    assert_eq!(parse_int_value("1.0"), None);
+}
+
+#[test]
+fn recover_from_invalid_function_body() {
+    assert_parse_module_with_recovery!(
+        "
+pub fn wibble() {
+  1 +
+}
+
+pub fn wobble() {
+  fn(x) { x }
+}
+"
+    );
+}
+
+#[test]
+fn recover_from_invalid_definitions() {
+    assert_parse_module_with_recovery!(
+        "
+import gleam/io
+
+pub type Wibble {
+  Wibble(
+
+const wobble = 1
+
+pub fn main( {
+  io.println(\"Hello\")
+}
+
+pub fn wubble() {
+  wobble
+}
+"
+    );
+}
+
+#[test]
+fn recover_from_unexpected_top_level_tokens() {
+    assert_parse_module_with_recovery!(
+        "
+pub fn wibble() {
+  1
+}
+}
+
+let x = 1
+
+fn wobble() {
+  wibble()
+}
+"
+    );
+}
+
+#[test]
+fn recover_from_incomplete_field_access() {
+    assert_parse_module_with_recovery!(
+        "
+pub fn main(wibble) {
+  wibble.
+}
+"
+    );
+}
+
+#[test]
+fn echo_of_binary_operation() {
+    assert_parse!("echo 1 + 2");
+}
+
+#[test]
+fn echo_in_pipeline() {
+    assert_parse!("1 |> echo |> wibble");
+}
+
+#[test]
+fn echo_ending_pipeline_followed_by_statement() {
+    assert_parse!("1 |> echo\nNil");
+}
+
+#[test]
+fn echo_with_value_on_next_line() {
+    assert_parse!("echo\n1");
+}
@@ -63,6 +63,7 @@ pub enum Token {
     CommentNormal,
     CommentModule,
     EmptyLine,
+    NewLine,
     // Keywords (alphabetically):
     As,
     Assert,
@@ -140,6 +141,7 @@ impl fmt::Display for Token {
             Token::Echo => "echo",
             Token::Else => "else",
             Token::EmptyLine => "EMPTYLINE",
+            Token::NewLine => "NEWLINE",
             Token::EndOfFile => "EOF",
             Token::Equal => "=",
             Token::EqualEqual => "==",
@@ -82,6 +82,10 @@ impl ProjectPaths {
         self.build_directory().join("erlang-shipment")
     }
 
+    pub fn javascript_bundle_directory(&self) -> Utf8PathBuf {
+        self.build_directory().join("javascript-bundle")
+    }
+
     pub fn build_documentation_directory(&self, package: &str) -> Utf8PathBuf {
         self.build_directory_for_mode(Mode::Dev)
             .join("docs")
@@ -178,6 +178,63 @@ pub enum Document<'a> {
 
     /// A string that is cheap to copy
     EcoString(EcoString),
+
+    /// A string that doesn't count towards the width of the line it is
+    /// printed on, such as markup surrounding the text of a document
+    ZeroWidthString(String),
+
+    /// Marks the code printed after it as generated from the source code at
+    /// this byte index, for source maps. Nothing is printed for it
+    SourceLocation(u32),
+}
+
+/// A position in printed code that was generated from the source code at a
+/// byte index. The line and column are counted from 0, with the column
+/// counted in UTF-16 code units as source maps expect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceMapping {
+    pub line: u32,
+    pub column: u32,
+    pub byte_index: u32,
+}
+
+/// A writer that keeps track of the position of the code it is given, so the
+/// source locations of a document can be recorded as it is printed.
+#[derive(Debug, Default)]
+struct SourceMappingWriter {
+    code: String,
+    line: u32,
+    column: u32,
+    mappings: Vec<SourceMapping>,
+}
+
+impl std::fmt::Write for SourceMappingWriter {
+    fn write_str(&mut self, str: &str) -> std::fmt::Result {
+        for character in str.chars() {
+            if character == '\n' {
+                self.line += 1;
+                self.column = 0;
+            } else {
+                self.column += character.len_utf16() as u32;
+            }
+        }
+        self.code.push_str(str);
+        Ok(())
+    }
+}
+
+impl Utf8Writer for SourceMappingWriter {
+    fn convert_err<E: std::error::Error>(&self, error: E) -> crate::Error {
+        self.code.convert_err(error)
+    }
+
+    fn source_location(&mut self, byte_index: u32) {
+        self.mappings.push(SourceMapping {
+            line: self.line,
+            column: self.column,
+            byte_index,
+        });
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -320,6 +377,7 @@ fn fits(
             Document::Str(s) => current_width += s.len() as isize,
             Document::String(s) => current_width += s.len() as isize,
             Document::EcoString(s) => current_width += s.len() as isize,
+            Document::ZeroWidthString(_) | Document::SourceLocation(_) => {}
 
             // If we get to a break we need to first see if it has to be
             // rendered as its unbroken or broken string, depending on the mode.
@@ -488,6 +546,13 @@ fn format(
                 writer.str_write(s)?;
             }
 
+            // Zero width strings are printed without changing the width.
+            Document::ZeroWidthString(s) => writer.str_write(s)?,
+
+            // Nothing is printed for a source location, the writer is told
+            // that the code that follows was generated from it.
+            Document::SourceLocation(byte_index) => writer.source_location(*byte_index),
+
             // If multiple documents need to be printed, then they are all
             // pushed to the front of the queue and will be printed one by one.
             Document::Vec(vec) => {
@@ -571,6 +636,14 @@ pub fn break_<'a>(broken: &'a str, unbroken: &'a str) -> Document<'a> {
     }
 }
 
+pub fn zero_width_string<'a>(string: String) -> Document<'a> {
+    Document::ZeroWidthString(string)
+}
+
+pub fn source_location<'a>(byte_index: u32) -> Document<'a> {
+    Document::SourceLocation(byte_index)
+}
+
 pub fn flex_break<'a>(broken: &'a str, unbroken: &'a str) -> Document<'a> {
     Document::Break {
         broken,
@@ -631,6 +704,18 @@ impl<'a> Document<'a> {
         buffer
     }
 
+    /// Prints the document along with the positions in the printed code of
+    /// each of its source locations.
+    pub fn to_pretty_string_with_source_mappings(
+        self,
+        limit: isize,
+    ) -> (String, Vec<SourceMapping>) {
+        let mut writer = SourceMappingWriter::default();
+        self.pretty_print(limit, &mut writer)
+            .expect("Writing to string buffer failed");
+        (writer.code, writer.mappings)
+    }
+
     pub fn surround(self, open: impl Documentable<'a>, closed: impl Documentable<'a>) -> Self {
         open.to_doc().append(self).append(closed)
     }
@@ -650,10 +735,39 @@ impl<'a> Document<'a> {
             EcoString(s) => s.is_empty(),
             String(s) => s.is_empty(),
             Str(s) => s.is_empty(),
+            ZeroWidthString(_) | SourceLocation(_) => true,
             // assuming `broken` and `unbroken` are equivalent
             Break { broken, .. } => broken.is_empty(),
             ForceBroken(d) | Nest(_, _, _, d) | Group(d) | NextBreakFits(d, _) => d.is_empty(),
             Vec(docs) => docs.iter().all(|d| d.is_empty()),
         }
     }
+
+    /// Changes the unit of indentation used by the document, so that
+    /// something nested by `from` spaces is nested by `to` spaces instead.
+    pub fn scale_nesting(self, from: isize, to: isize) -> Self {
+        use Document::*;
+        match self {
+            Nest(indent, mode, condition, doc) => Nest(
+                indent * to / from,
+                mode,
+                condition,
+                Box::new(doc.scale_nesting(from, to)),
+            ),
+            ForceBroken(doc) => ForceBroken(Box::new(doc.scale_nesting(from, to))),
+            NextBreakFits(doc, mode) => NextBreakFits(Box::new(doc.scale_nesting(from, to)), mode),
+            Group(doc) => Group(Box::new(doc.scale_nesting(from, to))),
+            Vec(docs) => Vec(docs
+                .into_iter()
+                .map(|doc| doc.scale_nesting(from, to))
+                .collect()),
+            Line(_)
+            | Break { .. }
+            | String(_)
+            | Str(_)
+            | EcoString(_)
+            | ZeroWidthString(_)
+            | SourceLocation(_) => self,
+        }
+    }
 }
@@ -315,3 +315,36 @@ fn set_nesting() {
     let doc = Vec(vec!["foo".to_doc(), break_("", " "), "bar".to_doc()]).group();
     assert_eq!("foo\nbar", doc.set_nesting(0).nest(2).to_pretty_string(1));
 }
+
+#[test]
+fn scale_nesting_test() {
+    let doc = concat([
+        "one".to_doc(),
+        concat([
+            line(),
+            "two".to_doc(),
+            concat([line(), "three".to_doc()]).nest(2),
+        ])
+        .nest(2),
+        line(),
+        "four".to_doc(),
+    ]);
+    assert_eq!(
+        "one\n    two\n        three\nfour",
+        doc.scale_nesting(2, 4).to_pretty_string(80)
+    );
+}
+
+#[test]
+fn zero_width_string_test() {
+    let doc = concat([
+        zero_width_string("<b>".into()),
+        "one".to_doc(),
+        zero_width_string("</b>".into()),
+        break_("", " "),
+        "two".to_doc(),
+    ])
+    .group();
+    assert_eq!("<b>one</b> two", doc.to_pretty_string(7));
+    assert!(zero_width_string("<b>".into()).is_empty());
+}
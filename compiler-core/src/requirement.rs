@@ -13,9 +13,24 @@ use serde::Deserialize;
 #[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
 #[serde(untagged, remote = "Self")]
 pub enum Requirement {
-    Hex { version: Range },
-    Path { path: Utf8PathBuf },
-    Git { git: EcoString },
+    Hex {
+        version: Range,
+    },
+    Path {
+        path: Utf8PathBuf,
+    },
+    /// Git dependencies can't be downloaded yet, so the branch, tag and
+    /// subdirectory are only recorded for now.
+    Git {
+        git: EcoString,
+        /// The branch to use the latest commit of.
+        branch: Option<EcoString>,
+        /// The tag of the commit to use.
+        tag: Option<EcoString>,
+        /// The directory of the repository the package is in, for
+        /// repositories with more than one package.
+        subdir: Option<Utf8PathBuf>,
+    },
 }
 
 impl Requirement {
@@ -30,7 +45,30 @@ impl Requirement {
     }
 
     pub fn git(url: &str) -> Requirement {
-        Requirement::Git { git: url.into() }
+        Requirement::Git {
+            git: url.into(),
+            branch: None,
+            tag: None,
+            subdir: None,
+        }
+    }
+
+    pub fn git_branch(url: &str, branch: &str) -> Requirement {
+        Requirement::Git {
+            git: url.into(),
+            branch: Some(branch.into()),
+            tag: None,
+            subdir: None,
+        }
+    }
+
+    pub fn git_tag(url: &str, tag: &str) -> Requirement {
+        Requirement::Git {
+            git: url.into(),
+            branch: None,
+            tag: Some(tag.into()),
+            subdir: None,
+        }
     }
 
     pub fn to_toml(&self, root_path: &Utf8Path) -> String {
@@ -44,7 +82,28 @@ impl Requirement {
                     make_relative(root_path, path).as_str().replace('\\', "/")
                 )
             }
-            Requirement::Git { git: url } => format!(r#"{{ git = "{}" }}"#, url),
+            Requirement::Git {
+                git: url,
+                branch,
+                tag,
+                subdir,
+            } => {
+                let mut toml = format!(r#"{{ git = "{}""#, url);
+                if let Some(branch) = branch {
+                    toml.push_str(&format!(r#", branch = "{}""#, branch));
+                }
+                if let Some(tag) = tag {
+                    toml.push_str(&format!(r#", tag = "{}""#, tag));
+                }
+                if let Some(subdir) = subdir {
+                    toml.push_str(&format!(
+                        r#", subdir = "{}""#,
+                        subdir.as_str().replace('\\', "/")
+                    ));
+                }
+                toml.push_str(" }");
+                toml
+            }
         }
     }
 }
@@ -56,11 +115,27 @@ impl Serialize for Requirement {
     where
         S: Serializer,
     {
-        let mut map = serializer.serialize_map(Some(1))?;
+        let mut map = serializer.serialize_map(None)?;
         match self {
             Requirement::Hex { version: range } => map.serialize_entry("version", range)?,
             Requirement::Path { path } => map.serialize_entry("path", path)?,
-            Requirement::Git { git: url } => map.serialize_entry("git", url)?,
+            Requirement::Git {
+                git: url,
+                branch,
+                tag,
+                subdir,
+            } => {
+                map.serialize_entry("git", url)?;
+                if let Some(branch) = branch {
+                    map.serialize_entry("branch", branch)?;
+                }
+                if let Some(tag) = tag {
+                    map.serialize_entry("tag", tag)?;
+                }
+                if let Some(subdir) = subdir {
+                    map.serialize_entry("subdir", subdir)?;
+                }
+            }
         }
         map.end()
     }
@@ -99,7 +174,17 @@ impl<'de> Visitor<'de> for RequirementVisitor {
     where
         M: MapAccess<'de>,
     {
-        Requirement::deserialize(de::value::MapAccessDeserializer::new(visitor))
+        let requirement = Requirement::deserialize(de::value::MapAccessDeserializer::new(visitor))?;
+        match requirement {
+            Requirement::Git {
+                branch: Some(_),
+                tag: Some(_),
+                ..
+            } => Err(de::Error::custom(
+                "a git dependency can have a branch or a tag, not both",
+            )),
+            _ => Ok(requirement),
+        }
     }
 }
 
@@ -125,6 +210,9 @@ mod tests {
             hex = { version = "~> 1.0.0" }
             local = { path = "/path/to/package" }
             github = { git = "https://github.com/gleam-lang/otp.git" }
+            branch = { git = "https://github.com/gleam-lang/otp.git", branch = "main" }
+            tag = { git = "https://github.com/gleam-lang/otp.git", tag = "v1.0.0" }
+            subdir = { git = "https://github.com/gleam-lang/otp.git", subdir = "packages/otp" }
         "#;
         let deps: HashMap<String, Requirement> = toml::from_str(toml).unwrap();
         assert_eq!(deps["short"], Requirement::hex("~> 0.5"));
@@ -134,5 +222,60 @@ mod tests {
             deps["github"],
             Requirement::git("https://github.com/gleam-lang/otp.git")
         );
+        assert_eq!(
+            deps["branch"],
+            Requirement::git_branch("https://github.com/gleam-lang/otp.git", "main")
+        );
+        assert_eq!(
+            deps["tag"],
+            Requirement::git_tag("https://github.com/gleam-lang/otp.git", "v1.0.0")
+        );
+        assert_eq!(
+            deps["subdir"],
+            Requirement::Git {
+                git: "https://github.com/gleam-lang/otp.git".into(),
+                branch: None,
+                tag: None,
+                subdir: Some("packages/otp".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn git_requirement_with_branch_and_tag() {
+        let toml = r#"
+            otp = { git = "https://github.com/gleam-lang/otp.git", branch = "main", tag = "v1.0.0" }
+        "#;
+        let error = toml::from_str::<HashMap<String, Requirement>>(toml).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("a git dependency can have a branch or a tag, not both"));
+    }
+
+    #[test]
+    fn git_requirement_to_toml() {
+        let root = Utf8Path::new("/");
+        assert_eq!(
+            Requirement::git("https://github.com/gleam-lang/otp.git").to_toml(root),
+            r#"{ git = "https://github.com/gleam-lang/otp.git" }"#
+        );
+        assert_eq!(
+            Requirement::git_branch("https://github.com/gleam-lang/otp.git", "main").to_toml(root),
+            r#"{ git = "https://github.com/gleam-lang/otp.git", branch = "main" }"#
+        );
+        assert_eq!(
+            Requirement::git_tag("https://github.com/gleam-lang/otp.git", "v1.0.0").to_toml(root),
+            r#"{ git = "https://github.com/gleam-lang/otp.git", tag = "v1.0.0" }"#
+        );
+        assert_eq!(
+            Requirement::Git {
+                git: "https://github.com/gleam-lang/otp.git".into(),
+                branch: Some("main".into()),
+                tag: None,
+                subdir: Some("packages/otp".into()),
+            }
+            .to_toml(root),
+            r#"{ git = "https://github.com/gleam-lang/otp.git", branch = "main", subdir = "packages/otp" }"#
+        );
     }
 }
@@ -0,0 +1,382 @@
+//! The compiler side of `gleam shell`, which evaluates Gleam entered at a
+//! prompt.
+//!
+//! Each input is compiled as a module of its own, which has the imports
+//! entered so far and a `run` function evaluating the input. The module is
+//! type checked against the modules of the project and generated for the
+//! target, and the runtime loads it and prints the value `run` returns.
+//!
+//! Variables bound by `let` are kept by the runtime, in the process dictionary
+//! on Erlang and a map in JavaScript, so the code that bound them isn't run
+//! again. Later inputs read them back with a type annotation worked out from
+//! the type they were inferred to have.
+
+use std::collections::HashMap;
+
+use camino::Utf8PathBuf;
+use ecow::EcoString;
+use itertools::Itertools;
+
+use crate::{
+    analyse::TargetSupport,
+    ast::{Definition, Statement, TypedExpr, TypedModule, UntypedStatement},
+    build::{Built, Origin, Target},
+    codegen::TypeScriptDeclarations,
+    config::PackageConfig,
+    error::Error,
+    line_numbers::LineNumbers,
+    type_::{prelude::is_prelude_module, Type, TypeVar},
+    uid::UniqueIdGenerator,
+    warning::TypeWarningEmitter,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// The directory of the build of the root package the modules compiled for
+/// the shell are written to.
+pub const MODULE_DIRECTORY: &str = "gleam_shell";
+
+/// The JavaScript module keeping the variables bound in the shell, written to
+/// the module directory.
+pub const JAVASCRIPT_FFI: &str = r#"const bindings = new Map();
+
+export function get(name) {
+  return bindings.get(name);
+}
+
+export function put(name, value) {
+  bindings.set(name, value);
+}
+"#;
+
+const VALUE: &str = "gleam_shell_value";
+
+/// The imports and variables entered in the shell so far.
+#[derive(Debug, Default)]
+pub struct Session {
+    imports: Vec<EcoString>,
+    bindings: Vec<Binding>,
+    inputs: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Binding {
+    name: EcoString,
+    /// The type of the variable, written so it can be used as an annotation,
+    /// or nothing if the type can't be referred to, such as a private type.
+    annotation: Option<String>,
+    /// The modules the annotation refers to.
+    modules: Vec<EcoString>,
+}
+
+/// An input compiled to the target's code.
+#[derive(Debug)]
+pub struct Compiled {
+    /// The name of the generated module, such as `gleam_shell/input_1`.
+    pub module: EcoString,
+    /// The Erlang or JavaScript code of the module.
+    pub code: String,
+    import: Option<EcoString>,
+    bindings: Vec<Binding>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compiles an input against the modules of the project. The session is
+    /// only changed by `ran`, once the input has been run successfully.
+    pub fn compile(
+        &mut self,
+        built: &Built,
+        config: &PackageConfig,
+        target: Target,
+        input: &str,
+    ) -> Result<Compiled, Error> {
+        self.inputs += 1;
+        let module = EcoString::from(format!("{MODULE_DIRECTORY}/input_{}", self.inputs));
+        let path = Utf8PathBuf::from(format!("{module}.gleam"));
+
+        let import = input.trim();
+        let (src, import) = if import.starts_with("import ") {
+            (self.source(&[import.into()], ""), Some(import.into()))
+        } else {
+            let statements =
+                crate::parse::parse_statement_sequence(input).map_err(|error| Error::Parse {
+                    path: path.clone(),
+                    src: input.into(),
+                    error,
+                })?;
+            (self.source(&[], &body(input, &statements)), None)
+        };
+
+        let mut parsed = crate::parse::parse_module(&src).map_err(|error| Error::Parse {
+            path: path.clone(),
+            src: src.as_str().into(),
+            error,
+        })?;
+        parsed.module.name = module.clone();
+        let line_numbers = LineNumbers::new(&src);
+        let ast = crate::analyse::infer_module::<()>(
+            target,
+            &UniqueIdGenerator::new(),
+            parsed.module,
+            Origin::Src,
+            built.module_interfaces(),
+            &TypeWarningEmitter::null(),
+            &HashMap::new(),
+            TargetSupport::NotEnforced,
+            line_numbers.clone(),
+            config,
+            path.clone(),
+        )
+        .map_err(|errors| Error::Type {
+            path: path.clone(),
+            src: src.as_str().into(),
+            errors,
+        })?;
+
+        let code = match target {
+            Target::Erlang => crate::erlang::module(&ast, &line_numbers)?,
+            Target::JavaScript => crate::javascript::module(
+                &ast,
+                &line_numbers,
+                &path,
+                &src.as_str().into(),
+                TargetSupport::NotEnforced,
+                TypeScriptDeclarations::None,
+            )?,
+        };
+
+        Ok(Compiled {
+            module,
+            code,
+            import,
+            bindings: stored_bindings(&ast),
+        })
+    }
+
+    /// Records the import or variables of an input that ran successfully, so
+    /// later inputs can use them.
+    pub fn ran(&mut self, compiled: Compiled) {
+        self.imports.extend(compiled.import);
+        for binding in compiled.bindings {
+            self.bindings.retain(|other| other.name != binding.name);
+            self.bindings.push(binding);
+        }
+    }
+
+    /// The source of the module for an input, with the session's imports and
+    /// variables.
+    fn source(&self, imports: &[EcoString], body: &str) -> String {
+        let mut src = String::new();
+        for import in self.imports.iter().chain(imports) {
+            src.push_str(import);
+            src.push('\n');
+        }
+        let modules = self
+            .bindings
+            .iter()
+            .flat_map(|binding| &binding.modules)
+            .unique();
+        for module in modules {
+            src.push_str(&format!("import {module} as {}\n", module_alias(module)));
+        }
+
+        src.push_str(
+            r#"
+@external(erlang, "erlang", "get")
+@external(javascript, "./ffi.mjs", "get")
+fn shell_binding(name: String) -> a
+
+@external(erlang, "erlang", "put")
+@external(javascript, "./ffi.mjs", "put")
+fn shell_store(name: String, value: a) -> b
+
+pub fn run() {
+"#,
+        );
+        for binding in &self.bindings {
+            let annotation = match &binding.annotation {
+                Some(annotation) => format!(": {annotation}"),
+                None => String::new(),
+            };
+            src.push_str(&format!(
+                "  let {name}{annotation} = shell_binding(\"{name}\")\n",
+                name = binding.name
+            ));
+        }
+        if body.is_empty() {
+            src.push_str("  Nil\n");
+        } else {
+            src.push_str(body);
+        }
+        src.push_str("}\n");
+        src
+    }
+}
+
+/// The body of the `run` function for an input of statements. The variables
+/// they bind are stored, and if the last statement is a `let` its value is
+/// returned.
+fn body(input: &str, statements: &[UntypedStatement]) -> String {
+    let mut body = String::new();
+    let mut variables = vec![];
+    let last = statements.len() - 1;
+    let mut returned = None;
+
+    for (index, statement) in statements.iter().enumerate() {
+        let location = statement.location();
+        let code = input
+            .get(location.start as usize..location.end as usize)
+            .unwrap_or_default();
+        match statement {
+            Statement::Assignment(assignment) => {
+                variables.extend(assignment.pattern.bound_variables());
+                if index == last {
+                    let value = assignment.value.location();
+                    let start = (value.start - location.start) as usize;
+                    let end = (value.end - location.start) as usize;
+                    body.push_str(&format!(
+                        "  let {VALUE} = {}\n",
+                        code.get(start..end).unwrap_or_default()
+                    ));
+                    body.push_str(&format!(
+                        "  {}{VALUE}{}\n",
+                        code.get(..start).unwrap_or_default(),
+                        code.get(end..).unwrap_or_default()
+                    ));
+                    returned = Some(VALUE);
+                } else {
+                    body.push_str(&format!("  {code}\n"));
+                }
+            }
+            // The rest of the input after a `use` is a function, so the
+            // variables it binds are not stored.
+            Statement::Use(_) => {
+                body.push_str(&format!(
+                    "  {}\n",
+                    input.get(location.start as usize..).unwrap_or_default()
+                ));
+                return body;
+            }
+            Statement::Expression(_) if index == last => {
+                body.push_str(&format!("  let {VALUE} = {code}\n"));
+                returned = Some(VALUE);
+            }
+            Statement::Expression(_) => body.push_str(&format!("  {code}\n")),
+        }
+    }
+
+    for (name, _) in variables.iter().unique_by(|(name, _)| name) {
+        body.push_str(&format!("  let _ = shell_store(\"{name}\", {name})\n"));
+    }
+    body.push_str(&format!("  {}\n", returned.unwrap_or("Nil")));
+    body
+}
+
+/// The variables stored by the `run` function of a compiled input, along
+/// with their types.
+fn stored_bindings(module: &TypedModule) -> Vec<Binding> {
+    let Some(run) = module
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Function(function) if function.name == "run" => Some(function),
+            _ => None,
+        })
+    else {
+        return vec![];
+    };
+
+    run.body
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::Assignment(assignment) => match assignment.value.as_ref() {
+                TypedExpr::Call { fun, args, .. } => match (fun.as_ref(), args.as_slice()) {
+                    (TypedExpr::Var { name: function, .. }, [name, value])
+                        if function == "shell_store" =>
+                    {
+                        let TypedExpr::String { value: name, .. } = &name.value else {
+                            return None;
+                        };
+                        let mut modules = vec![];
+                        let annotation = annotation(&value.value.type_(), &mut modules);
+                        Some(Binding {
+                            name: name.clone(),
+                            annotation,
+                            modules,
+                        })
+                    }
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Writes a type so it can be used as an annotation in the module of a later
+/// input, with the modules of named types imported under an alias.
+fn annotation(type_: &Type, modules: &mut Vec<EcoString>) -> Option<String> {
+    match type_ {
+        Type::Named {
+            publicity,
+            module,
+            name,
+            args,
+            ..
+        } => {
+            if !publicity.is_importable() {
+                return None;
+            }
+            let args = args
+                .iter()
+                .map(|arg| annotation(arg, modules))
+                .collect::<Option<Vec<_>>>()?;
+            let name = if is_prelude_module(module) {
+                name.to_string()
+            } else {
+                if !modules.contains(module) {
+                    modules.push(module.clone());
+                }
+                format!("{}.{name}", module_alias(module))
+            };
+            Some(if args.is_empty() {
+                name
+            } else {
+                format!("{name}({})", args.join(", "))
+            })
+        }
+
+        Type::Fn { args, retrn } => {
+            let args = args
+                .iter()
+                .map(|arg| annotation(arg, modules))
+                .collect::<Option<Vec<_>>>()?;
+            let retrn = annotation(retrn, modules)?;
+            Some(format!("fn({}) -> {retrn}", args.join(", ")))
+        }
+
+        Type::Tuple { elems } => {
+            let elems = elems
+                .iter()
+                .map(|elem| annotation(elem, modules))
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("#({})", elems.join(", ")))
+        }
+
+        Type::Var { type_ } => match &*type_.borrow() {
+            TypeVar::Link { type_ } => annotation(type_, modules),
+            TypeVar::Unbound { id } | TypeVar::Generic { id } => Some(format!("t{id}")),
+        },
+    }
+}
+
+/// The alias a module used in an annotation is imported as.
+fn module_alias(module: &str) -> String {
+    format!("gleam_shell_{}", module.replace('/', "_"))
+}
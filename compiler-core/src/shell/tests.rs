@@ -0,0 +1,101 @@
+use super::{Compiled, Session};
+use crate::{
+    ast::Publicity,
+    type_::{tests::compile_module, Type},
+};
+
+fn body(input: &str) -> String {
+    let statements = crate::parse::parse_statement_sequence(input).expect("should parse");
+    super::body(input, &statements)
+}
+
+/// Runs an input in the session as the shell would, without generating code
+/// for it.
+fn run(session: &mut Session, input: &str) -> String {
+    let (src, import) = if input.starts_with("import ") {
+        (session.source(&[input.into()], ""), Some(input.into()))
+    } else {
+        (session.source(&[], &body(input)), None)
+    };
+    let module = compile_module(
+        "gleam_shell/input",
+        &src,
+        None,
+        vec![("thepackage", "wibble", "pub type Wibble(a) { Wibble(a) }")],
+    )
+    .expect("should compile");
+    session.ran(Compiled {
+        module: "gleam_shell/input".into(),
+        code: String::new(),
+        import,
+        bindings: super::stored_bindings(&module),
+    });
+    src
+}
+
+#[test]
+fn last_expression_is_returned() {
+    assert_eq!(
+        body("let x = 1\nx + 1"),
+        "  let x = 1
+  let gleam_shell_value = x + 1
+  let _ = shell_store(\"x\", x)
+  gleam_shell_value
+"
+    );
+}
+
+#[test]
+fn value_of_last_assignment_is_returned() {
+    assert_eq!(
+        body("let #(a, b) = #(1, 2)"),
+        "  let gleam_shell_value = #(1, 2)
+  let #(a, b) = gleam_shell_value
+  let _ = shell_store(\"a\", a)
+  let _ = shell_store(\"b\", b)
+  gleam_shell_value
+"
+    );
+}
+
+#[test]
+fn variables_are_read_back_with_their_types() {
+    let mut session = Session::new();
+    let _ = run(&mut session, "import wibble");
+    let _ = run(
+        &mut session,
+        "let x = wibble.Wibble([1])\nlet f = fn(a) { a + 1 }",
+    );
+    assert_eq!(
+        session.source(&[], "  x\n"),
+        r#"import wibble
+import wibble as gleam_shell_wibble
+
+@external(erlang, "erlang", "get")
+@external(javascript, "./ffi.mjs", "get")
+fn shell_binding(name: String) -> a
+
+@external(erlang, "erlang", "put")
+@external(javascript, "./ffi.mjs", "put")
+fn shell_store(name: String, value: a) -> b
+
+pub fn run() {
+  let x: gleam_shell_wibble.Wibble(List(Int)) = shell_binding("x")
+  let f: fn(Int) -> Int = shell_binding("f")
+  x
+}
+"#
+    );
+}
+
+#[test]
+fn private_types_are_not_annotated() {
+    let type_ = Type::Named {
+        publicity: Publicity::Private,
+        package: "thepackage".into(),
+        module: "wibble".into(),
+        name: "Hidden".into(),
+        args: vec![],
+    };
+    assert_eq!(super::annotation(&type_, &mut vec![]), None);
+}
@@ -7,16 +7,18 @@ pub(crate) mod pattern;
 pub(crate) mod pipe;
 pub(crate) mod prelude;
 pub mod pretty;
+pub(crate) mod purity;
 #[cfg(test)]
 pub mod tests;
 
 use camino::Utf8PathBuf;
 use ecow::EcoString;
 pub use environment::*;
-pub use error::{Error, UnifyErrorSituation, Warning};
+pub use error::{Error, TypeConstraint, TypeConstraintKind, UnifyErrorSituation, Warning};
 pub(crate) use expression::ExprTyper;
 pub use fields::FieldMap;
 pub use prelude::*;
+pub use purity::Purity;
 use serde::Serialize;
 
 use crate::{
@@ -34,10 +36,9 @@ use error::*;
 use hydrator::Hydrator;
 use itertools::Itertools;
 use std::{
-    cell::RefCell,
     collections::{HashMap, HashSet},
     ops::Deref,
-    sync::Arc,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
 };
 
 pub trait HasType {
@@ -71,7 +72,7 @@ pub enum Type {
 
     /// A type variable. See the contained `TypeVar` enum for more information.
     ///
-    Var { type_: Arc<RefCell<TypeVar>> },
+    Var { type_: Arc<TypeVarCell> },
 
     /// A tuple is an ordered collection of 0 or more values, each of which
     /// can have a different type, so the `tuple` type is the sum of all the
@@ -188,6 +189,17 @@ impl Type {
         }
     }
 
+    /// The type of the elements of the list, if the type is a `List`.
+    pub fn list_element_type(&self) -> Option<Arc<Self>> {
+        match self {
+            Self::Named {
+                module, name, args, ..
+            } if "List" == name && is_prelude_module(module) => args.first().cloned(),
+            Self::Var { type_ } => type_.borrow().list_element_type(),
+            _ => None,
+        }
+    }
+
     pub fn named_type_name(&self) -> Option<(EcoString, EcoString)> {
         match self {
             Self::Named { module, name, .. } => Some((module.clone(), name.clone())),
@@ -367,6 +379,9 @@ pub enum ValueConstructorVariant {
         location: SrcSpan,
         documentation: Option<EcoString>,
         implementations: Implementations,
+        purity: Purity,
+        /// Whether the function was marked with `@must_use`.
+        must_use: bool,
     },
 
     /// A constructor for a custom type
@@ -383,6 +398,29 @@ pub enum ValueConstructorVariant {
 }
 
 impl ValueConstructorVariant {
+    /// Whether calling the value can have side effects, if it is a module
+    /// function.
+    pub fn purity(&self) -> Option<Purity> {
+        match self {
+            Self::ModuleFn { purity, .. } => Some(*purity),
+            Self::LocalVariable { .. }
+            | Self::ModuleConstant { .. }
+            | Self::LocalConstant { .. }
+            | Self::Record { .. } => None,
+        }
+    }
+
+    /// Whether the value is a module function marked with `@must_use`.
+    pub fn is_must_use(&self) -> bool {
+        match self {
+            Self::ModuleFn { must_use, .. } => *must_use,
+            Self::LocalVariable { .. }
+            | Self::ModuleConstant { .. }
+            | Self::LocalConstant { .. }
+            | Self::Record { .. } => false,
+        }
+    }
+
     fn to_module_value_constructor(
         &self,
         type_: Arc<Type>,
@@ -429,6 +467,7 @@ impl ValueConstructorVariant {
                 module: module_name.clone(),
                 documentation: None,
                 location: *location,
+                purity: Purity::Unknown,
             },
 
             Self::ModuleFn {
@@ -436,12 +475,14 @@ impl ValueConstructorVariant {
                 module,
                 location,
                 documentation,
+                purity,
                 ..
             } => ModuleValueConstructor::Fn {
                 name: name.clone(),
                 module: module.clone(),
                 documentation: documentation.clone(),
                 location: *location,
+                purity: *purity,
             },
         }
     }
@@ -527,6 +568,7 @@ pub enum ModuleValueConstructor {
         module: EcoString,
         name: EcoString,
         documentation: Option<EcoString>,
+        purity: Purity,
     },
 
     Constant {
@@ -554,9 +596,13 @@ impl ModuleValueConstructor {
     }
 }
 
+/// A public function of a module that can be run by `gleam run`.
 #[derive(Debug, Clone)]
 pub struct ModuleFunction {
     pub package: EcoString,
+    /// Whether the function takes the command line arguments as a
+    /// `List(String)`, rather than taking no arguments.
+    pub takes_arguments: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -684,21 +730,26 @@ impl ModuleInterface {
         }
     }
 
-    pub fn get_main_function(&self, target: Target) -> Result<ModuleFunction, crate::Error> {
+    /// The public function of the module with the name, if it can be run on
+    /// the target.
+    pub fn get_main_function(
+        &self,
+        function: &str,
+        target: Target,
+    ) -> Result<ModuleFunction, crate::Error> {
         let not_found = || crate::Error::ModuleDoesNotHaveMainFunction {
             module: self.name.clone(),
+            function: function.into(),
         };
 
-        // Module must have a value with the name "main"
-        let value = self
-            .values
-            .get(&EcoString::from("main"))
-            .ok_or_else(not_found)?;
+        // Module must have a public value with the name of the function
+        let value = self.get_public_value(function).ok_or_else(not_found)?;
 
-        assert_suitable_main_function(value, &self.name, target)?;
+        let takes_arguments = assert_suitable_main_function(value, &self.name, function, target)?;
 
         Ok(ModuleFunction {
             package: self.package.clone(),
+            takes_arguments,
         })
     }
 
@@ -729,6 +780,8 @@ pub struct PatternConstructor {
     pub module: Option<EcoString>,
     pub location: SrcSpan,
     pub constructor_index: u16,
+    /// The number of constructors the type has.
+    pub constructors_count: u16,
 }
 
 impl PatternConstructor {
@@ -771,6 +824,70 @@ pub enum TypeVar {
     Generic { id: u64 },
 }
 
+/// The mutable cell holding a type variable, which is linked to a type once
+/// inference works out what the variable is.
+///
+/// It is a lock rather than a `RefCell` so module interfaces can be shared
+/// between the threads modules are analysed on. The variables of an interface
+/// are not changed once the module has been analysed, so the lock is only
+/// contended while a module is being analysed on a single thread.
+pub struct TypeVarCell {
+    type_var: RwLock<TypeVar>,
+    /// The id of the variable the cell was created with, which it keeps once
+    /// it has been linked to a type.
+    id: Option<u64>,
+}
+
+impl TypeVarCell {
+    pub fn new(type_var: TypeVar) -> Self {
+        let id = match type_var {
+            TypeVar::Unbound { id } | TypeVar::Generic { id } => Some(id),
+            TypeVar::Link { .. } => None,
+        };
+        Self {
+            type_var: RwLock::new(type_var),
+            id,
+        }
+    }
+
+    pub fn borrow(&self) -> RwLockReadGuard<'_, TypeVar> {
+        self.type_var.read().expect("Type variable lock poisoned")
+    }
+
+    pub fn borrow_mut(&self) -> RwLockWriteGuard<'_, TypeVar> {
+        self.type_var.write().expect("Type variable lock poisoned")
+    }
+
+    /// The id of the variable the cell was created with, if it was created
+    /// as a variable rather than already linked to a type.
+    pub fn id(&self) -> Option<u64> {
+        self.id
+    }
+}
+
+impl Clone for TypeVarCell {
+    fn clone(&self) -> Self {
+        Self {
+            type_var: RwLock::new(self.borrow().clone()),
+            id: self.id,
+        }
+    }
+}
+
+impl PartialEq for TypeVarCell {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other) || *self.borrow() == *other.borrow()
+    }
+}
+
+impl Eq for TypeVarCell {}
+
+impl std::fmt::Debug for TypeVarCell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.borrow().fmt(f)
+    }
+}
+
 impl TypeVar {
     pub fn is_unbound(&self) -> bool {
         match self {
@@ -842,6 +959,13 @@ impl TypeVar {
         }
     }
 
+    pub fn list_element_type(&self) -> Option<Arc<Type>> {
+        match self {
+            Self::Link { type_ } => type_.list_element_type(),
+            Self::Unbound { .. } | Self::Generic { .. } => None,
+        }
+    }
+
     pub fn named_type_name(&self) -> Option<(EcoString, EcoString)> {
         match self {
             Self::Link { type_ } => type_.named_type_name(),
@@ -938,6 +1062,25 @@ impl ValueConstructor {
             }
         }
     }
+
+    /// The literal int, float, or string value of a module constant, following
+    /// any constants that are defined as another constant. Returns `None` for
+    /// anything else, as such values can't be used in patterns.
+    pub fn pattern_literal(&self) -> Option<&TypedConstant> {
+        match &self.variant {
+            ValueConstructorVariant::ModuleConstant { literal, .. } => match literal {
+                Constant::Int { .. } | Constant::Float { .. } | Constant::String { .. } => {
+                    Some(literal)
+                }
+                Constant::Var {
+                    constructor: Some(constructor),
+                    ..
+                } => constructor.pattern_literal(),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1110,14 +1253,17 @@ pub enum FieldAccessUsage {
     Other,
 }
 
-/// Verify that a value is suitable to be used as a main function.
+/// Verify that a value is suitable to be used as a main function, returning
+/// whether it takes the command line arguments.
 fn assert_suitable_main_function(
     value: &ValueConstructor,
     module_name: &EcoString,
+    function: &str,
     target: Target,
-) -> Result<(), crate::Error> {
+) -> Result<bool, crate::Error> {
     let not_found = || crate::Error::ModuleDoesNotHaveMainFunction {
         module: module_name.clone(),
+        function: function.into(),
     };
 
     // The value must be a module function
@@ -1134,17 +1280,22 @@ fn assert_suitable_main_function(
     if !implementations.supports(target) {
         return Err(crate::Error::MainFunctionDoesNotSupportTarget {
             module: module_name.clone(),
+            function: function.into(),
             target,
         });
     }
 
-    // The function must be zero arity
-    if *arity != 0 {
+    // The function must take no arguments, or the command line arguments
+    let takes_arguments = value.type_.fn_types().is_some_and(|(args, _)| {
+        matches!(args.as_slice(), [arg] if arg.list_element_type().is_some_and(|element| element.is_string()))
+    });
+    if *arity != 0 && !takes_arguments {
         return Err(crate::Error::MainFunctionHasWrongArity {
             module: module_name.clone(),
+            function: function.into(),
             arity: *arity,
         });
     }
 
-    Ok(())
+    Ok(takes_arguments)
 }
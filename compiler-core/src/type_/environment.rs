@@ -2,12 +2,13 @@ use crate::{
     analyse::TargetSupport,
     ast::{Publicity, PIPE_VARIABLE},
     build::Target,
+    config::WarningsConfig,
     uid::UniqueIdGenerator,
     warning::TypeWarningEmitter,
 };
 
 use super::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug)]
 pub struct Environment<'a> {
@@ -62,6 +63,14 @@ pub struct Environment<'a> {
     /// Whether a `todo` expression has been encountered in this module.
     /// This is used by the build tool to refuse to publish packages that are unfinished.
     pub todo_encountered: bool,
+
+    /// Configuration for the opt-in warnings enabled by the package.
+    pub warnings_config: WarningsConfig,
+
+    /// The reasons type variables have been bound to the types they are
+    /// linked to, keyed by the id of the variable. These are used to explain
+    /// why a type was inferred when reporting type mismatches.
+    type_constraints: HashMap<u64, TypeConstraint>,
 }
 
 impl<'a> Environment<'a> {
@@ -99,6 +108,8 @@ impl<'a> Environment<'a> {
             entity_usages: vec![HashMap::new()],
             target_support,
             todo_encountered: false,
+            warnings_config: WarningsConfig::default(),
+            type_constraints: HashMap::new(),
         }
     }
 }
@@ -112,14 +123,15 @@ pub struct UnusedModuleAlias {
 /// For Keeping track of entity usages and knowing which error to display.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EntityKind {
-    PrivateConstant,
+    // The span of private definitions is the location of the whole definition
+    PrivateConstant(SrcSpan),
     // String here is the type constructor's type name
     PrivateTypeConstructor(EcoString),
-    PrivateFunction,
+    PrivateFunction(SrcSpan),
     ImportedConstructor,
     ImportedType,
     ImportedValue,
-    PrivateType,
+    PrivateType(SrcSpan),
     Variable,
 }
 
@@ -323,6 +335,7 @@ impl<'a> Environment<'a> {
                     UnknownTypeConstructorError::Module {
                         name: module_name.clone(),
                         imported_modules: self.importable_modules.keys().cloned().collect(),
+                        suggestions: self.module_suggestions(module_name),
                     }
                 })?;
                 let _ = self.unused_modules.remove(module_name);
@@ -373,6 +386,7 @@ impl<'a> Environment<'a> {
                     UnknownTypeConstructorError::Module {
                         name: name.clone(),
                         imported_modules: self.importable_modules.keys().cloned().collect(),
+                        suggestions: vec![],
                     }
                 })?;
                 module.types_value_constructors.get(name).ok_or_else(|| {
@@ -400,6 +414,7 @@ impl<'a> Environment<'a> {
                     name: name.clone(),
                     variables: self.local_value_names(),
                     type_with_name_in_scope,
+                    suggestions: self.value_module_suggestions(name),
                 }
             }),
 
@@ -408,6 +423,7 @@ impl<'a> Environment<'a> {
                     UnknownValueConstructorError::Module {
                         name: module_name.clone(),
                         imported_modules: self.importable_modules.keys().cloned().collect(),
+                        suggestions: self.module_suggestions(module_name),
                     }
                 })?;
                 let _ = self.unused_modules.remove(module_name);
@@ -494,10 +510,148 @@ impl<'a> Environment<'a> {
         }
     }
 
+    /// Records the given location as the reason each of the given type
+    /// variables has been bound, for the ones that have been bound since they
+    /// were collected with `unbound_type_variables`. If a variable already
+    /// has a reason it is kept, as that is where its type was first inferred.
+    ///
+    pub fn record_type_constraints(
+        &mut self,
+        variables: Vec<Arc<TypeVarCell>>,
+        location: SrcSpan,
+        kind: TypeConstraintKind,
+    ) {
+        for variable in variables {
+            self.record_type_constraint(variable, location, kind);
+        }
+    }
+
+    fn record_type_constraint(
+        &mut self,
+        variable: Arc<TypeVarCell>,
+        location: SrcSpan,
+        kind: TypeConstraintKind,
+    ) {
+        let linked = match variable.borrow().deref() {
+            TypeVar::Link { type_ } => type_.clone(),
+            TypeVar::Unbound { .. } | TypeVar::Generic { .. } => return,
+        };
+        let Some(key) = variable.id() else {
+            return;
+        };
+        if self.type_constraints.contains_key(&key) {
+            return;
+        }
+        let _ = self.type_constraints.insert(
+            key,
+            TypeConstraint {
+                location,
+                kind,
+                type_: type_snapshot(&linked),
+            },
+        );
+
+        // Variables created while unifying (such as the element types of a
+        // tuple pattern) are bound for the same reason.
+        for variable in linked_type_variables(&linked) {
+            self.record_type_constraint(variable, location, kind);
+        }
+    }
+
+    /// The recorded reasons for the type variables in the given types having
+    /// been bound, ordered by where they appear in the source.
+    ///
+    pub fn type_constraints(&self, types: &[Arc<Type>]) -> Vec<TypeConstraint> {
+        let mut constraints = vec![];
+        for type_ in types {
+            self.collect_type_constraints(type_, &mut constraints);
+        }
+        constraints.sort_by_key(|constraint| constraint.location.start);
+        constraints
+    }
+
+    fn collect_type_constraints(&self, type_: &Type, constraints: &mut Vec<TypeConstraint>) {
+        match type_ {
+            Type::Var { type_: variable } => {
+                if let Some(constraint) = variable
+                    .id()
+                    .and_then(|key| self.type_constraints.get(&key))
+                {
+                    if !constraints
+                        .iter()
+                        .any(|existing| existing.location == constraint.location)
+                    {
+                        constraints.push(constraint.clone());
+                    }
+                }
+                if let TypeVar::Link { type_ } = variable.borrow().deref() {
+                    self.collect_type_constraints(type_, constraints);
+                }
+            }
+
+            Type::Named { args, .. } => {
+                for arg in args {
+                    self.collect_type_constraints(arg, constraints);
+                }
+            }
+
+            Type::Fn { args, retrn } => {
+                for arg in args {
+                    self.collect_type_constraints(arg, constraints);
+                }
+                self.collect_type_constraints(retrn, constraints);
+            }
+
+            Type::Tuple { elems } => {
+                for elem in elems {
+                    self.collect_type_constraints(elem, constraints);
+                }
+            }
+        }
+    }
+
+    /// Emits a warning if we are compiling for JavaScript and the given
+    /// integer can't be represented exactly by a JavaScript number.
+    ///
+    pub fn warn_if_unsafe_javascript_int(&self, value: i128, location: SrcSpan) {
+        if self.target == Target::JavaScript && !is_safe_javascript_int(value) {
+            self.warnings
+                .emit(Warning::JavaScriptUnsafeInt { location });
+        }
+    }
+
+    /// Emits a warning if a variable being defined has the same name as one
+    /// from an enclosing scope. Variables are only ever registered within
+    /// function bodies, so any enclosing variable belongs to the same
+    /// function.
+    fn warn_if_shadowing(&self, name: &EcoString, location: SrcSpan) {
+        let Some((_, enclosing_scopes)) = self.entity_usages.split_last() else {
+            return;
+        };
+        let shadowed = enclosing_scopes
+            .iter()
+            .rev()
+            .find_map(|scope| match scope.get(name) {
+                Some((EntityKind::Variable, location, _)) => Some(*location),
+                _ => None,
+            });
+        if let Some(shadowed_location) = shadowed {
+            self.warnings.emit(Warning::ShadowedVariable {
+                location,
+                name: name.clone(),
+                shadowed_location,
+            });
+        }
+    }
+
     /// Inserts an entity at the current scope for usage tracking.
     pub fn init_usage(&mut self, name: EcoString, kind: EntityKind, location: SrcSpan) {
         use EntityKind::*;
 
+        if kind == Variable && self.warnings_config.shadowing {
+            self.warn_if_shadowing(&name, location);
+        }
+
         match self
             .entity_usages
             .last_mut()
@@ -509,7 +663,7 @@ impl<'a> Environment<'a> {
             // TODO: Improve this so that we can tell if an imported overridden
             // type is actually used or not by tracking whether usages apply to
             // the value or type scope
-            Some((ImportedType | PrivateType, _, _)) => {}
+            Some((ImportedType | PrivateType(_), _, _)) => {}
 
             Some((kind, location, false)) => {
                 // an entity was overwritten in the top most scope without being used
@@ -544,6 +698,19 @@ impl<'a> Environment<'a> {
         }
     }
 
+    /// Marks every entity and imported module currently in scope as used.
+    pub fn mark_all_entities_used(&mut self) {
+        self.unused_modules.clear();
+        self.unused_module_aliases.clear();
+        for (_, _, used) in self
+            .entity_usages
+            .iter_mut()
+            .flat_map(|scope| scope.values_mut())
+        {
+            *used = true;
+        }
+    }
+
     /// Converts entities with a usage count of 0 to warnings.
     /// Returns the list of unused imported module location for the removed unused lsp action.
     pub fn convert_unused_to_warnings(&mut self) -> Vec<SrcSpan> {
@@ -582,25 +749,37 @@ impl<'a> Environment<'a> {
                     name,
                     imported: true,
                     location,
+                    definition_location: location,
                 },
                 EntityKind::ImportedConstructor => Warning::UnusedConstructor {
                     name,
                     imported: true,
                     location,
                 },
-                EntityKind::PrivateConstant => {
-                    Warning::UnusedPrivateModuleConstant { name, location }
+                EntityKind::PrivateConstant(definition_location) => {
+                    Warning::UnusedPrivateModuleConstant {
+                        name,
+                        location,
+                        definition_location,
+                    }
                 }
                 EntityKind::PrivateTypeConstructor(_) => Warning::UnusedConstructor {
                     name,
                     imported: false,
                     location,
                 },
-                EntityKind::PrivateFunction => Warning::UnusedPrivateFunction { name, location },
-                EntityKind::PrivateType => Warning::UnusedType {
+                EntityKind::PrivateFunction(definition_location) => {
+                    Warning::UnusedPrivateFunction {
+                        name,
+                        location,
+                        definition_location,
+                    }
+                }
+                EntityKind::PrivateType(definition_location) => Warning::UnusedType {
                     name,
                     imported: false,
                     location,
+                    definition_location,
                 },
                 EntityKind::ImportedValue => Warning::UnusedImportedValue { name, location },
                 EntityKind::Variable => Warning::UnusedVariable { name, location },
@@ -617,6 +796,142 @@ impl<'a> Environment<'a> {
             .cloned()
             .collect()
     }
+
+    /// Modules that define a public value with the given name, for use when
+    /// the name is not in scope. Imported modules are suggested as qualified
+    /// references, other modules as imports.
+    ///
+    pub fn value_module_suggestions(&self, name: &EcoString) -> Vec<ModuleSuggestion> {
+        let imported = self
+            .imported_modules
+            .iter()
+            .filter(|(_, (_, module))| module.get_public_value(name).is_some())
+            .map(|(alias, _)| ModuleSuggestion::UseQualified {
+                module: alias.clone(),
+                name: name.clone(),
+            });
+
+        let importable = self
+            .suggestable_modules()
+            .filter(|module| module.get_public_value(name).is_some())
+            .map(|module| ModuleSuggestion::Import {
+                module: module.name.clone(),
+            });
+
+        imported.chain(importable).sorted().collect()
+    }
+
+    /// Modules that have not been imported and whose name ends with the given
+    /// module name, for use when a module is referred to without importing it.
+    ///
+    pub fn module_suggestions(&self, name: &EcoString) -> Vec<ModuleSuggestion> {
+        self.suggestable_modules()
+            .filter(|module| module.name.rsplit('/').next() == Some(name.as_str()))
+            .map(|module| ModuleSuggestion::Import {
+                module: module.name.clone(),
+            })
+            .sorted()
+            .collect()
+    }
+
+    /// Modules that could be imported by the current module but have not been
+    /// yet.
+    fn suggestable_modules(&self) -> impl Iterator<Item = &ModuleInterface> {
+        let imported: HashSet<_> = self
+            .imported_modules
+            .values()
+            .map(|(_, module)| &module.name)
+            .collect();
+
+        self.importable_modules.values().filter(move |module| {
+            module.name != self.current_module
+                && !is_prelude_module(&module.name)
+                && !imported.contains(&module.name)
+                && module.origin != Origin::Test
+                && (!module.is_internal || module.package == self.current_package)
+        })
+    }
+}
+
+/// Collects the type variables of a type that have not been bound yet, so
+/// that the reason for them being bound can later be recorded with
+/// `Environment::record_type_constraints`.
+///
+pub fn unbound_type_variables(type_: &Type) -> Vec<Arc<TypeVarCell>> {
+    fn collect(type_: &Type, variables: &mut Vec<Arc<TypeVarCell>>) {
+        match type_ {
+            Type::Var { type_: variable } => match variable.borrow().deref() {
+                TypeVar::Unbound { .. } => variables.push(variable.clone()),
+                TypeVar::Link { type_ } => collect(type_, variables),
+                TypeVar::Generic { .. } => (),
+            },
+            Type::Named { args, .. } => args.iter().for_each(|arg| collect(arg, variables)),
+            Type::Fn { args, retrn } => {
+                args.iter().for_each(|arg| collect(arg, variables));
+                collect(retrn, variables);
+            }
+            Type::Tuple { elems } => elems.iter().for_each(|elem| collect(elem, variables)),
+        }
+    }
+
+    let mut variables = vec![];
+    collect(type_, &mut variables);
+    variables
+}
+
+/// A copy of a type as it currently is, unaffected by any of its unbound type
+/// variables being bound later on.
+fn type_snapshot(type_: &Type) -> Arc<Type> {
+    match type_ {
+        Type::Named {
+            publicity,
+            package,
+            module,
+            name,
+            args,
+        } => Arc::new(Type::Named {
+            publicity: *publicity,
+            package: package.clone(),
+            module: module.clone(),
+            name: name.clone(),
+            args: args.iter().map(|arg| type_snapshot(arg)).collect(),
+        }),
+        Type::Fn { args, retrn } => fn_(
+            args.iter().map(|arg| type_snapshot(arg)).collect(),
+            type_snapshot(retrn),
+        ),
+        Type::Tuple { elems } => tuple(elems.iter().map(|elem| type_snapshot(elem)).collect()),
+        Type::Var { type_: variable } => match variable.borrow().deref() {
+            TypeVar::Link { type_ } => type_snapshot(type_),
+            variable => Arc::new(Type::Var {
+                type_: Arc::new(TypeVarCell::new(variable.clone())),
+            }),
+        },
+    }
+}
+
+/// The outermost type variables within a type that have been linked to
+/// another type.
+fn linked_type_variables(type_: &Type) -> Vec<Arc<TypeVarCell>> {
+    fn collect(type_: &Type, variables: &mut Vec<Arc<TypeVarCell>>) {
+        match type_ {
+            Type::Var { type_: variable } => {
+                if let TypeVar::Link { .. } = variable.borrow().deref() {
+                    variables.push(variable.clone())
+                }
+            }
+            Type::Named { args, .. } => args.iter().for_each(|arg| collect(arg, variables)),
+            Type::Fn { args, retrn } => {
+                args.iter().for_each(|arg| collect(arg, variables));
+                collect(retrn, variables);
+            }
+            Type::Tuple { elems } => elems.iter().for_each(|elem| collect(elem, variables)),
+        }
+    }
+
+    let mut variables = vec![];
+    collect(type_, &mut variables);
+    variables
 }
 
 /// Unify two types that should be the same.
@@ -745,3 +1060,11 @@ pub fn unify(t1: Arc<Type>, t2: Arc<Type>) -> Result<(), UnifyError> {
         }),
     }
 }
+
+/// The largest integer that can be represented exactly by a JavaScript number,
+/// `Number.MAX_SAFE_INTEGER`.
+const JAVASCRIPT_MAX_SAFE_INTEGER: i128 = 9_007_199_254_740_991;
+
+pub fn is_safe_javascript_int(value: i128) -> bool {
+    (-JAVASCRIPT_MAX_SAFE_INTEGER..=JAVASCRIPT_MAX_SAFE_INTEGER).contains(&value)
+}
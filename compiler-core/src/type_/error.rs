@@ -1,11 +1,14 @@
 use crate::{
     ast::{BinOp, SrcSpan, TodoKind},
     build::Target,
+    diagnostic::Code,
+    exhaustiveness::MissingPattern,
     type_::Type,
 };
 
 use camino::Utf8PathBuf;
 use std::sync::Arc;
+use vec1::Vec1;
 
 use crate::ast::Layer;
 use ecow::EcoString;
@@ -20,6 +23,28 @@ pub struct UnknownType {
     pub name: EcoString,
 }
 
+/// A suggestion of another module that a name could be referring to, used
+/// when a name or module is not in scope.
+#[derive(Debug, Eq, PartialEq, Clone, PartialOrd, Ord)]
+pub enum ModuleSuggestion {
+    /// An imported module has a public value with the name, so it can be
+    /// referenced qualified with the module's name. e.g. `list.map`.
+    UseQualified { module: EcoString, name: EcoString },
+
+    /// A module that has not been imported yet defines the name, or is the
+    /// module being referred to. e.g. `import gleam/list`.
+    Import { module: EcoString },
+}
+
+impl ModuleSuggestion {
+    pub fn suggestion(&self) -> String {
+        match self {
+            ModuleSuggestion::UseQualified { module, name } => format!("{module}.{name}"),
+            ModuleSuggestion::Import { module } => format!("import {module}"),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
 pub enum RecordVariants {
     HasVariants,
@@ -50,6 +75,8 @@ pub enum Error {
         name: EcoString,
         variables: Vec<EcoString>,
         type_with_name_in_scope: bool,
+        /// Other modules that define a public value with this name.
+        suggestions: Vec<ModuleSuggestion>,
     },
 
     UnknownType {
@@ -62,6 +89,8 @@ pub enum Error {
         location: SrcSpan,
         name: EcoString,
         imported_modules: Vec<EcoString>,
+        /// Importable modules that could be the one being referred to.
+        suggestions: Vec<ModuleSuggestion>,
     },
 
     UnknownModuleType {
@@ -104,6 +133,8 @@ pub enum Error {
         location: SrcSpan,
         expected: usize,
         given: usize,
+        /// The labels of the fields that aren't given, in the order of the
+        /// fields.
         labels: Vec<EcoString>,
     },
 
@@ -129,6 +160,9 @@ pub enum Error {
         expected: Arc<Type>,
         given: Arc<Type>,
         rigid_type_names: im::HashMap<u64, EcoString>,
+        /// The places that forced the type variables of the two types to be
+        /// the types they were inferred to be, if known.
+        trace: Vec<TypeConstraint>,
     },
 
     RecursiveType {
@@ -317,13 +351,13 @@ pub enum Error {
     /// values of the type.
     InexhaustiveCaseExpression {
         location: SrcSpan,
-        missing: Vec<EcoString>,
+        missing: Vec<MissingPattern>,
     },
 
     /// Let assignment's pattern does not match all possible values of the type.
     InexhaustiveLetAssignment {
         location: SrcSpan,
-        missing: Vec<EcoString>,
+        missing: Vec<MissingPattern>,
     },
 
     /// A type alias has a type variable but it is not used in the definition.
@@ -370,6 +404,40 @@ pub enum Error {
         name: EcoString,
         location: SrcSpan,
     },
+
+    /// `echo` is used on its own outside of a pipeline, so there is no value
+    /// for it to print.
+    ///
+    /// ```gleam
+    /// pub fn main() {
+    ///   echo
+    /// }
+    /// ```
+    EchoWithNoFollowingExpression {
+        location: SrcSpan,
+    },
+
+    /// A constant is used in a pattern but its value is not an int, float, or
+    /// string literal, so it can't be matched against.
+    ///
+    /// ```gleam
+    /// // pets.gleam
+    /// pub const names = ["Lucy", "Nubi"]
+    ///
+    /// // main.gleam
+    /// import pets
+    ///
+    /// pub fn main(x) {
+    ///   case x {
+    ///     pets.names -> True
+    ///     _ -> False
+    ///   }
+    /// }
+    /// ```
+    NonLiteralConstantPattern {
+        location: SrcSpan,
+        name: EcoString,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -410,6 +478,11 @@ pub enum Warning {
         location: SrcSpan,
     },
 
+    UnusedMustUseValue {
+        location: SrcSpan,
+        name: EcoString,
+    },
+
     NoFieldsRecordUpdate {
         location: SrcSpan,
     },
@@ -422,6 +495,9 @@ pub enum Warning {
         location: SrcSpan,
         imported: bool,
         name: EcoString,
+        /// The location of the whole definition of a private type, which is
+        /// removed to fix the warning.
+        definition_location: SrcSpan,
     },
 
     UnusedConstructor {
@@ -449,11 +525,13 @@ pub enum Warning {
     UnusedPrivateModuleConstant {
         location: SrcSpan,
         name: EcoString,
+        definition_location: SrcSpan,
     },
 
     UnusedPrivateFunction {
         location: SrcSpan,
         name: EcoString,
+        definition_location: SrcSpan,
     },
 
     UnusedVariable {
@@ -540,9 +618,155 @@ pub enum Warning {
     RedundantAssertAssignment {
         location: SrcSpan,
     },
+
+    /// This happens when a variable is defined with the same name as a
+    /// variable from an enclosing scope of the same function, making the
+    /// outer variable inaccessible. This warning is opt-in.
+    ///
+    /// ```gleam
+    /// let x = 1
+    /// case y {
+    ///   Ok(x) -> x
+    /// //   ^ This shadows the `x` defined above
+    ///   Error(_) -> x
+    /// }
+    /// ```
+    ShadowedVariable {
+        location: SrcSpan,
+        name: EcoString,
+        shadowed_location: SrcSpan,
+    },
+
+    /// A public definition of an application that can't be reached from any
+    /// of its `main` functions. This is only reported when dead code
+    /// detection has been enabled in the package's `gleam.toml`.
+    ///
+    UnusedPublicDefinition {
+        location: SrcSpan,
+        name: EcoString,
+        kind: PublicDefinitionKind,
+        definition_location: SrcSpan,
+    },
+
+    /// A public definition of an internal module that isn't used by any other
+    /// module of the package, including its tests. Internal modules are not
+    /// part of the package's API, so the definition is dead code. This is only
+    /// reported when dead code detection has been enabled in the package's
+    /// `gleam.toml`.
+    ///
+    UnusedInternalDefinition {
+        location: SrcSpan,
+        name: EcoString,
+        kind: PublicDefinitionKind,
+        definition_location: SrcSpan,
+    },
+
+    /// An integer literal, or arithmetic on integer literals, whose value is
+    /// outside of the range that JavaScript numbers can represent exactly.
+    /// Only emitted when compiling for the JavaScript target.
+    ///
+    /// ```gleam
+    /// pub fn main() {
+    ///   9_007_199_254_740_993
+    /// }
+    /// ```
+    JavaScriptUnsafeInt {
+        location: SrcSpan,
+    },
+
+    /// A problem found by one of the lints enabled in `gleam.toml`.
+    ///
+    Lint {
+        warning: crate::lint::LintWarning,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PublicDefinitionKind {
+    Function,
+    Constant,
+    Type,
+    Constructor,
+}
+
+impl PublicDefinitionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PublicDefinitionKind::Function => "function",
+            PublicDefinitionKind::Constant => "constant",
+            PublicDefinitionKind::Type => "type",
+            PublicDefinitionKind::Constructor => "constructor",
+        }
+    }
+}
+
+impl From<Error> for Vec1<Error> {
+    fn from(error: Error) -> Self {
+        Vec1::new(error)
+    }
 }
 
 impl Error {
+    /// The stable code identifying this kind of error.
+    pub fn code(&self) -> Code {
+        match self {
+            Error::SrcImportingTest { .. } => Code::Error(200),
+            Error::BitArraySegmentError { .. } => Code::Error(201),
+            Error::UnknownLabels { .. } => Code::Error(202),
+            Error::UnknownVariable { .. } => Code::Error(203),
+            Error::UnknownType { .. } => Code::Error(204),
+            Error::UnknownModule { .. } => Code::Error(205),
+            Error::UnknownModuleType { .. } => Code::Error(206),
+            Error::UnknownModuleValue { .. } => Code::Error(207),
+            Error::UnknownModuleField { .. } => Code::Error(208),
+            Error::NotFn { .. } => Code::Error(209),
+            Error::UnknownRecordField { .. } => Code::Error(210),
+            Error::IncorrectArity { .. } => Code::Error(211),
+            Error::UpdateMultiConstructorType { .. } => Code::Error(212),
+            Error::UnnecessarySpreadOperator { .. } => Code::Error(213),
+            Error::IncorrectTypeArity { .. } => Code::Error(214),
+            Error::CouldNotUnify { .. } => Code::Error(215),
+            Error::RecursiveType { .. } => Code::Error(216),
+            Error::DuplicateName { .. } => Code::Error(217),
+            Error::DuplicateImport { .. } => Code::Error(218),
+            Error::DuplicateTypeName { .. } => Code::Error(219),
+            Error::DuplicateArgument { .. } => Code::Error(220),
+            Error::DuplicateField { .. } => Code::Error(221),
+            Error::PrivateTypeLeak { .. } => Code::Error(222),
+            Error::UnexpectedLabelledArg { .. } => Code::Error(223),
+            Error::PositionalArgumentAfterLabelled { .. } => Code::Error(224),
+            Error::IncorrectNumClausePatterns { .. } => Code::Error(225),
+            Error::NonLocalClauseGuardVariable { .. } => Code::Error(226),
+            Error::ExtraVarInAlternativePattern { .. } => Code::Error(227),
+            Error::MissingVarInAlternativePattern { .. } => Code::Error(228),
+            Error::DuplicateVarInPattern { .. } => Code::Error(229),
+            Error::OutOfBoundsTupleIndex { .. } => Code::Error(230),
+            Error::NotATuple { .. } => Code::Error(231),
+            Error::NotATupleUnbound { .. } => Code::Error(232),
+            Error::RecordAccessUnknownType { .. } => Code::Error(233),
+            Error::RecordUpdateInvalidConstructor { .. } => Code::Error(234),
+            Error::UnexpectedTypeHole { .. } => Code::Error(235),
+            Error::ReservedModuleName { .. } => Code::Error(236),
+            Error::KeywordInModuleName { .. } => Code::Error(237),
+            Error::NotExhaustivePatternMatch { .. } => Code::Error(238),
+            Error::ArgumentNameAlreadyUsed { .. } => Code::Error(239),
+            Error::UnlabelledAfterlabelled { .. } => Code::Error(240),
+            Error::RecursiveTypeAlias { .. } => Code::Error(241),
+            Error::ExternalMissingAnnotation { .. } => Code::Error(242),
+            Error::NoImplementation { .. } => Code::Error(243),
+            Error::UnsupportedExpressionTarget { .. } => Code::Error(244),
+            Error::InvalidExternalJavascriptModule { .. } => Code::Error(245),
+            Error::InvalidExternalJavascriptFunction { .. } => Code::Error(246),
+            Error::InexhaustiveCaseExpression { .. } => Code::Error(247),
+            Error::InexhaustiveLetAssignment { .. } => Code::Error(248),
+            Error::UnusedTypeAliasParameter { .. } => Code::Error(249),
+            Error::DuplicateTypeParameter { .. } => Code::Error(250),
+            Error::UnsupportedPublicFunctionTarget { .. } => Code::Error(251),
+            Error::EchoWithNoFollowingExpression { .. } => Code::Error(252),
+            Error::NonLiteralConstantPattern { .. } => Code::Error(253),
+        }
+    }
+
     pub fn with_unify_error_situation(mut self, new_situation: UnifyErrorSituation) -> Self {
         match self {
             Error::CouldNotUnify {
@@ -555,6 +779,16 @@ impl Error {
         }
     }
 
+    pub fn with_unify_error_trace(mut self, new_trace: Vec<TypeConstraint>) -> Self {
+        match self {
+            Error::CouldNotUnify { ref mut trace, .. } => {
+                *trace = new_trace;
+                self
+            }
+            _ => self,
+        }
+    }
+
     pub fn with_unify_error_rigid_names(mut self, new_names: &im::HashMap<u64, EcoString>) -> Self {
         match self {
             Error::CouldNotUnify {
@@ -570,6 +804,42 @@ impl Error {
 }
 
 impl Warning {
+    /// The stable code identifying this kind of warning.
+    pub fn code(&self) -> Code {
+        match self {
+            Warning::Todo { .. } => Code::Warning(100),
+            Warning::ImplicitlyDiscardedResult { .. } => Code::Warning(101),
+            Warning::UnusedLiteral { .. } => Code::Warning(102),
+            Warning::UnusedValue { .. } => Code::Warning(103),
+            Warning::NoFieldsRecordUpdate { .. } => Code::Warning(104),
+            Warning::AllFieldsRecordUpdate { .. } => Code::Warning(105),
+            Warning::UnusedType { .. } => Code::Warning(106),
+            Warning::UnusedConstructor { .. } => Code::Warning(107),
+            Warning::UnusedImportedValue { .. } => Code::Warning(108),
+            Warning::UnusedImportedModule { .. } => Code::Warning(109),
+            Warning::UnusedImportedModuleAlias { .. } => Code::Warning(110),
+            Warning::UnusedPrivateModuleConstant { .. } => Code::Warning(111),
+            Warning::UnusedPrivateFunction { .. } => Code::Warning(112),
+            Warning::UnusedVariable { .. } => Code::Warning(113),
+            Warning::UnnecessaryDoubleIntNegation { .. } => Code::Warning(114),
+            Warning::UnnecessaryDoubleBoolNegation { .. } => Code::Warning(115),
+            Warning::InefficientEmptyListCheck { .. } => Code::Warning(116),
+            Warning::TransitiveDependencyImported { .. } => Code::Warning(117),
+            Warning::DeprecatedItem { .. } => Code::Warning(118),
+            Warning::UnreachableCaseClause { .. } => Code::Warning(119),
+            Warning::CaseMatchOnLiteralTuple { .. } => Code::Warning(120),
+            Warning::OpaqueExternalType { .. } => Code::Warning(121),
+            Warning::InternalTypeLeak { .. } => Code::Warning(122),
+            Warning::RedundantAssertAssignment { .. } => Code::Warning(123),
+            Warning::ShadowedVariable { .. } => Code::Warning(124),
+            Warning::UnusedPublicDefinition { .. } => Code::Warning(125),
+            Warning::JavaScriptUnsafeInt { .. } => Code::Warning(126),
+            Warning::Lint { .. } => Code::Warning(127),
+            Warning::UnusedMustUseValue { .. } => Code::Warning(128),
+            Warning::UnusedInternalDefinition { .. } => Code::Warning(129),
+        }
+    }
+
     pub fn into_warning(self, path: Utf8PathBuf, src: EcoString) -> crate::Warning {
         crate::Warning::Type {
             path,
@@ -585,11 +855,13 @@ pub enum UnknownValueConstructorError {
         name: EcoString,
         variables: Vec<EcoString>,
         type_with_name_in_scope: bool,
+        suggestions: Vec<ModuleSuggestion>,
     },
 
     Module {
         name: EcoString,
         imported_modules: Vec<EcoString>,
+        suggestions: Vec<ModuleSuggestion>,
     },
 
     ModuleValue {
@@ -608,20 +880,24 @@ pub fn convert_get_value_constructor_error(
             name,
             variables,
             type_with_name_in_scope,
+            suggestions,
         } => Error::UnknownVariable {
             location,
             name,
             variables,
             type_with_name_in_scope,
+            suggestions,
         },
 
         UnknownValueConstructorError::Module {
             name,
             imported_modules,
+            suggestions,
         } => Error::UnknownModule {
             location,
             name,
             imported_modules,
+            suggestions,
         },
 
         UnknownValueConstructorError::ModuleValue {
@@ -653,6 +929,7 @@ pub enum UnknownTypeConstructorError {
     Module {
         name: EcoString,
         imported_modules: Vec<EcoString>,
+        suggestions: Vec<ModuleSuggestion>,
     },
 
     ModuleType {
@@ -676,10 +953,12 @@ pub fn convert_get_type_constructor_error(
         UnknownTypeConstructorError::Module {
             name,
             imported_modules,
+            suggestions,
         } => Error::UnknownModule {
             location: *location,
             name,
             imported_modules,
+            suggestions,
         },
 
         UnknownTypeConstructorError::ModuleType {
@@ -791,6 +1070,45 @@ fn unify_enclosed_type_test() {
     );
 }
 
+/// The reason a type variable was inferred to be a certain type, used to
+/// explain type mismatch errors.
+///
+/// ```gleam
+/// pub fn main() {
+///   let wibble = fn(a, b) { [a, b] }
+///   wibble(1, "a")
+/// //       ^ Because of this argument `b` must be an `Int`
+/// }
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeConstraint {
+    pub location: SrcSpan,
+    pub kind: TypeConstraintKind,
+    /// The type variable that was constrained. Once printed this will show
+    /// the type it was inferred to be.
+    pub type_: Arc<Type>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeConstraintKind {
+    Argument,
+    Annotation,
+    Pattern,
+    Operator,
+}
+
+impl TypeConstraintKind {
+    pub fn description(&self) -> &'static str {
+        match self {
+            TypeConstraintKind::Argument => "argument",
+            TypeConstraintKind::Annotation => "annotation",
+            TypeConstraintKind::Pattern => "pattern",
+            TypeConstraintKind::Operator => "operator",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UnifyErrorSituation {
     /// Clauses in a case expression were found to return different types.
@@ -935,6 +1253,7 @@ impl UnifyError {
                 given,
                 situation: note,
                 rigid_type_names: im::hashmap![],
+                trace: vec![],
             },
 
             Self::ExtraVarInAlternativePattern { name } => {
@@ -2,17 +2,19 @@ use super::{pipe::PipeTyper, *};
 use crate::{
     analyse::infer_bit_array_option,
     ast::{
-        Arg, Assignment, AssignmentKind, BinOp, BitArrayOption, BitArraySegment, CallArg, Clause,
-        ClauseGuard, Constant, HasLocation, Layer, RecordUpdateSpread, SrcSpan, Statement,
-        TodoKind, TypeAst, TypedArg, TypedAssignment, TypedClause, TypedClauseGuard, TypedConstant,
-        TypedExpr, TypedMultiPattern, TypedStatement, UntypedArg, UntypedAssignment, UntypedClause,
-        UntypedClauseGuard, UntypedConstant, UntypedConstantBitArraySegment, UntypedExpr,
-        UntypedExprBitArraySegment, UntypedMultiPattern, UntypedStatement, Use, UseAssignment,
-        USE_ASSIGNMENT_VARIABLE,
+        visit::Visit, Arg, Assignment, AssignmentKind, BinOp, BitArrayOption, BitArraySegment,
+        CallArg, Clause, ClauseGuard, Constant, HasLocation, Layer, RecordUpdateSpread, SrcSpan,
+        Statement, TodoKind, TypeAst, TypedArg, TypedAssignment, TypedClause, TypedClauseGuard,
+        TypedConstant, TypedExpr, TypedMultiPattern, TypedStatement, UntypedArg, UntypedAssignment,
+        UntypedClause, UntypedClauseGuard, UntypedConstant, UntypedConstantBitArraySegment,
+        UntypedExpr, UntypedExprBitArraySegment, UntypedMultiPattern, UntypedStatement, Use,
+        UseAssignment, USE_ASSIGNMENT_VARIABLE,
     },
     build::Target,
     exhaustiveness,
+    parse::parse_int_value,
 };
+use ecow::eco_format;
 use id_arena::Arena;
 use im::hashmap;
 use itertools::Itertools;
@@ -149,6 +151,12 @@ pub(crate) struct ExprTyper<'a, 'b> {
 
     // Type hydrator for creating types from annotations
     pub(crate) hydrator: Hydrator,
+
+    /// Errors found in statements of the function body. Rather than stopping
+    /// at the first error the typer replaces the statement with an invalid
+    /// expression and carries on, so that independent mistakes can all be
+    /// reported at once.
+    pub(crate) errors: Vec<Error>,
 }
 
 impl<'a, 'b> ExprTyper<'a, 'b> {
@@ -172,6 +180,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             environment,
             implementations,
             current_function_definition: definition,
+            errors: vec![],
         }
     }
 
@@ -220,10 +229,17 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             // treat it as a `panic` expression during analysis.
             UntypedExpr::Placeholder { location } => self.infer_panic(location, None),
 
+            UntypedExpr::Invalid { location } => Ok(self.infer_invalid(location)),
+
             UntypedExpr::Panic {
                 location, message, ..
             } => self.infer_panic(location, message),
 
+            UntypedExpr::Echo {
+                location,
+                expression,
+            } => self.infer_echo(location, expression),
+
             UntypedExpr::Var { location, name, .. } => self.infer_var(name, location),
 
             UntypedExpr::Int {
@@ -357,6 +373,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         Ok(TypedExpr::Todo {
             location,
             type_,
+            kind,
             message,
         })
     }
@@ -383,6 +400,23 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         })
     }
 
+    fn infer_echo(
+        &mut self,
+        location: SrcSpan,
+        expression: Option<Box<UntypedExpr>>,
+    ) -> Result<TypedExpr, Error> {
+        // An `echo` with nothing after it is only valid as a step of a
+        // pipeline, where it is handled by the pipe typer.
+        let expression = match expression {
+            Some(expression) => self.infer(*expression)?,
+            None => return Err(Error::EchoWithNoFollowingExpression { location }),
+        };
+        Ok(TypedExpr::Echo {
+            location,
+            expression: Box::new(expression),
+        })
+    }
+
     fn infer_string(&mut self, value: EcoString, location: SrcSpan) -> TypedExpr {
         TypedExpr::String {
             location,
@@ -392,6 +426,11 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
     }
 
     fn infer_int(&mut self, value: EcoString, location: SrcSpan) -> TypedExpr {
+        if let Some(int_value) = parse_int_value(&value) {
+            self.environment
+                .warn_if_unsafe_javascript_int(int_value, location);
+        }
+
         TypedExpr::Int {
             location,
             value,
@@ -409,12 +448,18 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
     /// Emit a warning if the given expressions should not be discarded.
     /// e.g. because it's a literal (why was it made in the first place?)
+    /// e.g. because it's the result of a function marked with `@must_use`
     /// e.g. because it's of the `Result` type (errors should be handled)
     fn expression_discarded(&mut self, discarded: &TypedExpr) {
         if discarded.is_literal() {
             self.environment.warnings.emit(Warning::UnusedLiteral {
                 location: discarded.location(),
             });
+        } else if let Some(name) = self.must_use_function_called(discarded) {
+            self.environment.warnings.emit(Warning::UnusedMustUseValue {
+                location: discarded.location(),
+                name,
+            });
         } else if discarded.type_().is_result() {
             self.environment
                 .warnings
@@ -428,6 +473,38 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         }
     }
 
+    /// The name of the `@must_use` function whose return value is the value
+    /// of the expression, if there is one. The last step of a pipeline is the
+    /// call that produces its value.
+    fn must_use_function_called(&self, expression: &TypedExpr) -> Option<EcoString> {
+        match expression {
+            TypedExpr::Pipeline { finally, .. } => self.must_use_function_called(finally),
+
+            TypedExpr::Call { fun, .. } => match fun.as_ref() {
+                TypedExpr::Var {
+                    name, constructor, ..
+                } if constructor.variant.is_must_use() => Some(name.clone()),
+
+                TypedExpr::ModuleSelect {
+                    label,
+                    module_name,
+                    module_alias,
+                    ..
+                } => self
+                    .environment
+                    .importable_modules
+                    .get(module_name)
+                    .and_then(|module| module.get_public_value(label))
+                    .filter(|value| value.variant.is_must_use())
+                    .map(|_| eco_format!("{module_alias}.{label}")),
+
+                _ => None,
+            },
+
+            _ => None,
+        }
+    }
+
     pub(crate) fn infer_statements(
         &mut self,
         untyped: Vec1<UntypedStatement>,
@@ -454,13 +531,24 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
             match statement {
                 Statement::Use(use_) => {
-                    let expression = self.infer_use(use_, location, untyped.collect())?;
+                    let use_location = use_.location;
+                    let expression = match self.infer_use(use_, location, untyped.collect()) {
+                        Ok(expression) => expression,
+                        Err(error) => {
+                            let location = SrcSpan::new(use_location.start, location.end);
+                            Statement::Expression(self.recover_from_error(error, location))
+                        }
+                    };
                     statements.push(expression);
                     break; // Inferring the use has consumed the rest of the exprs
                 }
 
                 Statement::Expression(expression) => {
-                    let expression = self.infer(expression)?;
+                    let expression_location = expression.location();
+                    let expression = match self.infer(expression) {
+                        Ok(expression) => expression,
+                        Err(error) => self.recover_from_error(error, expression_location),
+                    };
 
                     // This isn't the final expression in the sequence, so call the
                     // `expression_discarded` function to see if anything is being
@@ -473,8 +561,22 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 }
 
                 Statement::Assignment(assignment) => {
-                    let assignment = self.infer_assignment(assignment)?;
-                    statements.push(Statement::Assignment(assignment));
+                    let assignment_location = assignment.location;
+                    let variables = assignment.pattern.bound_variables();
+                    match self.infer_assignment(assignment) {
+                        Ok(assignment) => statements.push(Statement::Assignment(assignment)),
+                        Err(error) => {
+                            // The variables are still bound so that later uses
+                            // of them don't report errors of their own.
+                            for (name, location) in variables {
+                                let typ = self.new_unbound_var();
+                                self.environment.insert_local_variable(name, location, typ);
+                            }
+                            statements.push(Statement::Expression(
+                                self.recover_from_error(error, assignment_location),
+                            ))
+                        }
+                    }
                 }
             }
         }
@@ -482,6 +584,49 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         Ok(Vec1::try_from_vec(statements).expect("empty sequence"))
     }
 
+    /// Combines the result of type checking with any errors recorded while
+    /// doing so, failing if there were any.
+    pub(crate) fn with_recorded_errors<T>(
+        &mut self,
+        result: Result<T, Error>,
+    ) -> Result<T, Vec1<Error>> {
+        let errors = std::mem::take(&mut self.errors);
+        match (result, Vec1::try_from_vec(errors)) {
+            (Ok(value), Err(_)) => Ok(value),
+            (Ok(_), Ok(errors)) => Err(errors),
+            (Err(error), Ok(mut errors)) => {
+                errors.push(error);
+                Err(errors)
+            }
+            (Err(error), Err(_)) => Err(Vec1::new(error)),
+        }
+    }
+
+    /// Records an error so that type checking can carry on, returning an
+    /// invalid expression of unknown type to use in place of the one that
+    /// could not be type checked.
+    /// Source that could not be parsed is given an unknown type. The parse
+    /// error has already been reported, and as the invalid code could have
+    /// referred to anything in scope nothing is reported as being unused.
+    fn infer_invalid(&mut self, location: SrcSpan) -> TypedExpr {
+        self.environment.mark_all_entities_used();
+        TypedExpr::Invalid {
+            location,
+            typ: self.new_unbound_var(),
+        }
+    }
+
+    fn recover_from_error(&mut self, error: Error, location: SrcSpan) -> TypedExpr {
+        // The names of any annotated type variables are only known while
+        // still in their scope, so they are added to the error here.
+        let error = error.with_unify_error_rigid_names(&self.hydrator.rigid_names());
+        self.errors.push(error);
+        TypedExpr::Invalid {
+            location,
+            typ: self.new_unbound_var(),
+        }
+    }
+
     fn infer_use(
         &mut self,
         use_: Use,
@@ -526,6 +671,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             // This argument is implicitly given by Gleam's use syntax so we
             // mark it as such.
             implicit: true,
+            label_shorthand: false,
         });
 
         let call = self.infer(UntypedExpr::Call {
@@ -670,9 +816,11 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             .map(|element| {
                 let element = self.infer(element)?;
                 // Ensure they all have the same type
-                unify(typ.clone(), element.type_()).map_err(|e| {
-                    convert_unify_error(e.list_element_mismatch(), element.location())
-                })?;
+                unify(typ.clone(), element.type_())
+                    .map_err(|e| convert_unify_error(e.list_element_mismatch(), element.location()))
+                    .map_err(|e| {
+                        self.explain_type_mismatch(e, &[typ.clone(), element.type_()], &[&element])
+                    })?;
                 Ok(element)
             })
             .try_collect()?;
@@ -683,7 +831,10 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 let tail = self.infer(*tail)?;
                 // Ensure the tail has the same type as the preceding elements
                 unify(typ.clone(), tail.type_())
-                    .map_err(|e| convert_unify_error(e.list_tail_mismatch(), tail.location()))?;
+                    .map_err(|e| convert_unify_error(e.list_tail_mismatch(), tail.location()))
+                    .map_err(|e| {
+                        self.explain_type_mismatch(e, &[typ.clone(), tail.type_()], &[&tail])
+                    })?;
                 Some(Box::new(tail))
             }
             None => None,
@@ -921,8 +1072,16 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             BinOp::Eq | BinOp::NotEq => {
                 let left = self.infer(left)?;
                 let right = self.infer(right)?;
-                unify(left.type_(), right.type_())
-                    .map_err(|e| convert_unify_error(e, right.location()))?;
+                self.unify_and_record_constraints(
+                    left.type_(),
+                    right.type_(),
+                    location,
+                    TypeConstraintKind::Operator,
+                )
+                .map_err(|e| convert_unify_error(e, right.location()))
+                .map_err(|e| {
+                    self.explain_type_mismatch(e, &[left.type_(), right.type_()], &[&left, &right])
+                })?;
 
                 self.check_for_inefficient_empty_list_check(name, &left, &right, location);
 
@@ -957,17 +1116,32 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         };
 
         let left = self.infer(left)?;
-        unify(input_type.clone(), left.type_()).map_err(|e| {
+        self.unify_and_record_constraints(
+            input_type.clone(),
+            left.type_(),
+            location,
+            TypeConstraintKind::Operator,
+        )
+        .map_err(|e| {
             e.operator_situation(name)
                 .into_error(left.type_defining_location())
-        })?;
+        })
+        .map_err(|e| self.explain_type_mismatch(e, &[left.type_()], &[&left]))?;
         let right = self.infer(right)?;
-        unify(input_type, right.type_()).map_err(|e| {
+        self.unify_and_record_constraints(
+            input_type,
+            right.type_(),
+            location,
+            TypeConstraintKind::Operator,
+        )
+        .map_err(|e| {
             e.operator_situation(name)
                 .into_error(right.type_defining_location())
-        })?;
+        })
+        .map_err(|e| self.explain_type_mismatch(e, &[right.type_()], &[&right]))?;
 
         self.check_for_inefficient_empty_list_check(name, &left, &right, location);
+        self.check_for_unsafe_javascript_int_arithmetic(name, &left, &right, location);
 
         Ok(TypedExpr::BinOp {
             location,
@@ -978,6 +1152,90 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         })
     }
 
+    /// Unifies two types, recording the given location as the reason for any
+    /// of their type variables being bound in the process. These are later
+    /// used to explain type mismatches involving those variables.
+    fn unify_and_record_constraints(
+        &mut self,
+        expected: Arc<Type>,
+        given: Arc<Type>,
+        location: SrcSpan,
+        kind: TypeConstraintKind,
+    ) -> Result<(), UnifyError> {
+        let mut variables = unbound_type_variables(&expected);
+        variables.extend(unbound_type_variables(&given));
+        unify(expected, given)?;
+        self.environment
+            .record_type_constraints(variables, location, kind);
+        Ok(())
+    }
+
+    /// Adds to a type mismatch error the places that caused the type
+    /// variables of the mismatched types, and of any local variables used by
+    /// the mismatched expressions, to be inferred.
+    fn explain_type_mismatch(
+        &self,
+        error: Error,
+        types: &[Arc<Type>],
+        expressions: &[&TypedExpr],
+    ) -> Error {
+        if !matches!(error, Error::CouldNotUnify { .. }) {
+            return error;
+        }
+
+        // Referencing a local variable instantiates its type, so the type
+        // variables it was inferred from are only found in its definition.
+        let mut types = types.to_vec();
+        let mut variables = LocalVariables::default();
+        for expression in expressions {
+            variables.visit_typed_expr(expression);
+        }
+        for (name, location) in variables.0 {
+            match self.environment.scope.get(&name) {
+                Some(ValueConstructor {
+                    variant: ValueConstructorVariant::LocalVariable { location: defined },
+                    type_,
+                    ..
+                }) if *defined == location => types.push(type_.clone()),
+                _ => (),
+            }
+        }
+
+        let trace = self.environment.type_constraints(&types);
+        error.with_unify_error_trace(trace)
+    }
+
+    /// Checks for arithmetic on integer literals that results in a value that
+    /// can't be represented exactly on JavaScript.
+    ///
+    /// Operands that are unsafe themselves have already been warned about, so
+    /// we only emit a warning if the operation is what makes the value unsafe.
+    fn check_for_unsafe_javascript_int_arithmetic(
+        &mut self,
+        binop: BinOp,
+        left: &TypedExpr,
+        right: &TypedExpr,
+        location: SrcSpan,
+    ) {
+        if self.environment.target != Target::JavaScript {
+            return;
+        }
+
+        let (Some(left), Some(right)) = (constant_int_value(left), constant_int_value(right))
+        else {
+            return;
+        };
+        if !is_safe_javascript_int(left) || !is_safe_javascript_int(right) {
+            return;
+        }
+
+        let Some(value) = constant_int_operation(binop, left, right) else {
+            return;
+        };
+        self.environment
+            .warn_if_unsafe_javascript_int(value, location);
+    }
+
     /// Checks for inefficient usage of `list.length` for checking for the empty list.
     ///
     /// If we find one of these usages, emit a warning to use `list.is_empty` instead.
@@ -1045,31 +1303,57 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             annotation,
             location,
         } = assignment;
-        let value = self.in_new_scope(|value_typer| value_typer.infer(*value))?;
+        // If the value can't be type checked the pattern is still bound, so
+        // that uses of its variables later on don't result in more errors.
+        let value_location = value.location();
+        let value = match self.in_new_scope(|value_typer| value_typer.infer(*value)) {
+            Ok(value) => value,
+            Err(error) => self.recover_from_error(error, value_location),
+        };
         let value_typ = value.type_();
 
         // Ensure the pattern matches the type of the value
+        let pattern_location = pattern.location();
+        let variables = unbound_type_variables(&value_typ);
         let pattern = pattern::PatternTyper::new(self.environment, &self.hydrator)
-            .unify(pattern, value_typ.clone())?;
+            .unify(pattern, value_typ.clone())
+            .map_err(|e| {
+                self.explain_type_mismatch(e, std::slice::from_ref(&value_typ), &[&value])
+            })?;
+        self.environment.record_type_constraints(
+            variables,
+            pattern_location,
+            TypeConstraintKind::Pattern,
+        );
 
         // Check that any type annotation is accurate.
         if let Some(annotation) = &annotation {
             let ann_typ = self
                 .type_from_ast(annotation)
                 .map(|t| self.instantiate(t, &mut hashmap![]))?;
-            unify(ann_typ, value_typ.clone())
-                .map_err(|e| convert_unify_error(e, value.type_defining_location()))?;
+            self.unify_and_record_constraints(
+                ann_typ.clone(),
+                value_typ.clone(),
+                annotation.location(),
+                TypeConstraintKind::Annotation,
+            )
+            .map_err(|e| convert_unify_error(e, value.type_defining_location()))
+            .map_err(|e| self.explain_type_mismatch(e, &[ann_typ, value_typ.clone()], &[&value]))?;
         }
 
-        // Do not perform exhaustiveness checking if user explicitly used `let assert ... = ...`.
-        let exhaustiveness_check = self.check_let_exhaustiveness(location, value.type_(), &pattern);
-        match kind {
-            AssignmentKind::Let => exhaustiveness_check?,
-            AssignmentKind::Assert { location } if exhaustiveness_check.is_ok() => self
-                .environment
-                .warnings
-                .emit(Warning::RedundantAssertAssignment { location }),
-            AssignmentKind::Assert { .. } => {}
+        // Do not perform exhaustiveness checking if user explicitly used `let assert ... = ...`,
+        // or if the type of the value is unknown as it could not be type checked.
+        if !value.is_invalid() {
+            let exhaustiveness_check =
+                self.check_let_exhaustiveness(location, value.type_(), &pattern);
+            match kind {
+                AssignmentKind::Let => exhaustiveness_check?,
+                AssignmentKind::Assert { location } if exhaustiveness_check.is_ok() => self
+                    .environment
+                    .warnings
+                    .emit(Warning::RedundantAssertAssignment { location }),
+                AssignmentKind::Assert { .. } => {}
+            }
         }
 
         Ok(Assignment {
@@ -1177,19 +1461,38 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         subjects: &[Arc<Type>],
         location: &SrcSpan,
     ) -> Result<(TypedMultiPattern, Vec<TypedMultiPattern>), Error> {
+        let pattern_location = match (pattern.first(), pattern.last()) {
+            (Some(first), Some(last)) => SrcSpan::new(first.location().start, last.location().end),
+            _ => *location,
+        };
+        let variables = subjects
+            .iter()
+            .flat_map(|subject| unbound_type_variables(subject))
+            .collect_vec();
+
         let mut pattern_typer = pattern::PatternTyper::new(self.environment, &self.hydrator);
-        let typed_pattern = pattern_typer.infer_multi_pattern(pattern, subjects, location)?;
-
-        // Each case clause has one or more patterns that may match the
-        // subject in order for the clause to be selected, so we must type
-        // check every pattern.
-        let mut typed_alternatives = Vec::with_capacity(alternatives.len());
-        for m in alternatives {
-            typed_alternatives
-                .push(pattern_typer.infer_alternative_multi_pattern(m, subjects, location)?);
-        }
+        let typed = pattern_typer
+            .infer_multi_pattern(pattern, subjects, location)
+            .and_then(|typed_pattern| {
+                // Each case clause has one or more patterns that may match the
+                // subject in order for the clause to be selected, so we must type
+                // check every pattern.
+                let mut typed_alternatives = Vec::with_capacity(alternatives.len());
+                for m in alternatives {
+                    typed_alternatives.push(
+                        pattern_typer.infer_alternative_multi_pattern(m, subjects, location)?,
+                    );
+                }
+                Ok((typed_pattern, typed_alternatives))
+            })
+            .map_err(|e| self.explain_type_mismatch(e, subjects, &[]))?;
 
-        Ok((typed_pattern, typed_alternatives))
+        self.environment.record_type_constraints(
+            variables,
+            pattern_location,
+            TypeConstraintKind::Pattern,
+        );
+        Ok(typed)
     }
 
     fn infer_optional_clause_guard(
@@ -1614,6 +1917,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                     name: module_alias.clone(),
                     location: *module_location,
                     imported_modules: self.environment.imported_modules.keys().cloned().collect(),
+                    suggestions: self.environment.module_suggestions(module_alias),
                 })?;
 
             let constructor =
@@ -1852,6 +2156,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                      label,
                      value,
                      location,
+                     ..
                  }| {
                     let value = self.infer(value.clone())?;
                     let spread_field = self.infer_known_record_expression_access(
@@ -1925,6 +2230,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                                 .module_types
                                 .keys()
                                 .any(|typ| typ == name),
+                            suggestions: self.environment.value_module_suggestions(name),
                         })?;
 
                 // Register the value as seen for detection of unused values
@@ -1948,6 +2254,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                             .keys()
                             .cloned()
                             .collect(),
+                        suggestions: self.environment.module_suggestions(module_name),
                     })?;
                 module
                     .values
@@ -2000,7 +2307,13 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         let inferred = match value {
             Constant::Int {
                 location, value, ..
-            } => Ok(Constant::Int { location, value }),
+            } => {
+                if let Some(int_value) = parse_int_value(&value) {
+                    self.environment
+                        .warn_if_unsafe_javascript_int(int_value, location);
+                }
+                Ok(Constant::Int { location, value })
+            }
 
             Constant::Float {
                 location, value, ..
@@ -2170,6 +2483,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                             value,
                             location,
                             implicit,
+                            label_shorthand,
                         } = arg;
                         let value = self.infer_const(&None, value)?;
                         unify(typ.clone(), value.type_())
@@ -2179,6 +2493,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                             value,
                             implicit,
                             location,
+                            label_shorthand,
                         })
                     })
                     .try_collect()?;
@@ -2354,6 +2669,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                     value,
                     location,
                     implicit,
+                    label_shorthand,
                 } = arg;
                 let value = self.infer_call_argument(value, typ.clone())?;
                 Ok(CallArg {
@@ -2361,6 +2677,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                     value,
                     implicit,
                     location,
+                    label_shorthand,
                 })
             })
             .try_collect()?;
@@ -2372,6 +2689,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         value: UntypedExpr,
         typ: Arc<Type>,
     ) -> Result<TypedExpr, Error> {
+        let expected = typ.clone();
         let typ = collapse_links(typ);
 
         let value = match (&*typ, value) {
@@ -2408,7 +2726,14 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             (_, value) => self.infer(value),
         }?;
 
-        unify(typ, value.type_()).map_err(|e| convert_unify_error(e, value.location()))?;
+        self.unify_and_record_constraints(
+            typ.clone(),
+            value.type_(),
+            value.location(),
+            TypeConstraintKind::Argument,
+        )
+        .map_err(|e| convert_unify_error(e, value.location()))
+        .map_err(|e| self.explain_type_mismatch(e, &[expected, value.type_()], &[&value]))?;
         Ok(value)
     }
 
@@ -2487,10 +2812,16 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
             // Check that any return type is accurate.
             if let Some(return_type) = return_type {
-                unify(return_type, body.last().type_()).map_err(|e| {
-                    e.return_annotation_mismatch()
+                unify(return_type.clone(), body.last().type_()).map_err(|e| {
+                    let error = e
+                        .return_annotation_mismatch()
                         .into_error(body.last().type_defining_location())
-                        .with_unify_error_rigid_names(&body_rigid_names)
+                        .with_unify_error_rigid_names(&body_rigid_names);
+                    body_typer.explain_type_mismatch(
+                        error,
+                        &[return_type, body.last().type_()],
+                        &[],
+                    )
                 })?;
             }
 
@@ -2591,7 +2922,12 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
         // Perform exhaustiveness checking, building a decision tree
         compiler.set_pattern_arena(arena.into_inner());
-        let output = compiler.compile(rows);
+        let output = match subject_variables.as_slice() {
+            // A case expression without clauses is missing every constructor
+            // of its subject, rather than a single discard pattern.
+            [variable] if rows.is_empty() => compiler.compile_without_rows(variable.clone()),
+            _ => compiler.compile(rows),
+        };
 
         // Error for missing clauses that would cause a crash
         if output.diagnostics.missing {
@@ -2736,6 +3072,7 @@ impl UseAssignments {
                 | Pattern::Float { .. }
                 | Pattern::String { .. }
                 | Pattern::VarUsage { .. }
+                | Pattern::Constant { .. }
                 | Pattern::Assign { .. }
                 | Pattern::List { .. }
                 | Pattern::Constructor { .. }
@@ -2766,3 +3103,44 @@ impl UseAssignments {
         assignments
     }
 }
+
+/// The value of an integer expression if it can be computed at compile time,
+/// that is if it only does arithmetic on integer literals.
+///
+fn constant_int_value(expr: &TypedExpr) -> Option<i128> {
+    match expr {
+        TypedExpr::Int { value, .. } => parse_int_value(value),
+        TypedExpr::NegateInt { value, .. } => constant_int_value(value).map(|value| -value),
+        TypedExpr::BinOp {
+            name, left, right, ..
+        } => constant_int_operation(*name, constant_int_value(left)?, constant_int_value(right)?),
+        _ => None,
+    }
+}
+
+fn constant_int_operation(binop: BinOp, left: i128, right: i128) -> Option<i128> {
+    match binop {
+        BinOp::AddInt => Some(left.saturating_add(right)),
+        BinOp::SubInt => Some(left.saturating_sub(right)),
+        BinOp::MultInt => Some(left.saturating_mul(right)),
+        _ => None,
+    }
+}
+
+/// Collects the local variables referenced by an expression, along with
+/// where they were defined.
+#[derive(Debug, Default)]
+struct LocalVariables(Vec<(EcoString, SrcSpan)>);
+
+impl<'ast> Visit<'ast> for LocalVariables {
+    fn visit_typed_expr_var(
+        &mut self,
+        _location: &'ast SrcSpan,
+        constructor: &'ast ValueConstructor,
+        name: &'ast EcoString,
+    ) {
+        if let ValueConstructorVariant::LocalVariable { location } = &constructor.variant {
+            self.0.push((name.clone(), *location));
+        }
+    }
+}
@@ -127,14 +127,17 @@ impl FieldMap {
         }
     }
 
+    /// The labels of the fields no labelled argument is given for, in the
+    /// order of the fields.
+    ///
     pub fn incorrect_arity_labels<A>(&self, args: &[CallArg<A>]) -> Vec<EcoString> {
         let given: HashSet<_> = args.iter().filter_map(|arg| arg.label.as_ref()).collect();
 
         self.fields
-            .keys()
-            .filter(|f| !given.contains(f))
-            .cloned()
-            .sorted()
+            .iter()
+            .filter(|(label, _)| !given.contains(label))
+            .sorted_by_key(|(_, index)| **index)
+            .map(|(label, _)| label.clone())
             .collect()
     }
 }
@@ -8,6 +8,7 @@ use super::*;
 use crate::{
     analyse::Inferred,
     ast::{AssignName, Layer, UntypedPatternBitArraySegment},
+    parse::parse_int_value,
 };
 use std::sync::Arc;
 
@@ -197,6 +198,42 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
         })
     }
 
+    fn infer_constant_pattern(
+        &mut self,
+        location: SrcSpan,
+        module: EcoString,
+        name: EcoString,
+        type_: Arc<Type>,
+    ) -> Result<TypedPattern, Error> {
+        let constructor = self
+            .environment
+            .get_value_constructor(Some(&module), &name)
+            .map_err(|e| convert_get_value_constructor_error(e, location))?
+            .clone();
+
+        if constructor.pattern_literal().is_none() {
+            return Err(Error::NonLiteralConstantPattern { location, name });
+        }
+
+        if let Deprecation::Deprecated { message } = &constructor.deprecation {
+            self.environment.warnings.emit(Warning::DeprecatedItem {
+                location,
+                message: message.clone(),
+                layer: Layer::Value,
+            })
+        }
+
+        let constant_type = constructor.type_.clone();
+        unify(type_, constant_type.clone()).map_err(|e| convert_unify_error(e, location))?;
+        Ok(Pattern::Constant {
+            location,
+            module,
+            name,
+            constructor: Some(Box::new(constructor)),
+            type_: constant_type,
+        })
+    }
+
     /// When we have an assignment or a case expression we unify the pattern with the
     /// inferred type of the subject in order to determine what variables to insert
     /// into the environment (or to detect a type error).
@@ -213,6 +250,13 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                 location,
             }),
 
+            Pattern::Constant {
+                location,
+                module,
+                name,
+                ..
+            } => self.infer_constant_pattern(location, module, name, type_),
+
             Pattern::Variable { name, location, .. } => {
                 self.insert_variable(&name, type_.clone(), location)
                     .map_err(|e| convert_unify_error(e, location))?;
@@ -237,6 +281,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                             .module_types
                             .keys()
                             .any(|typ| typ == &name),
+                        suggestions: self.environment.value_module_suggestions(&name),
                     })?;
                 self.environment.increment_usage(&name);
                 let typ =
@@ -302,6 +347,10 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
 
             Pattern::Int { location, value } => {
                 unify(type_, int()).map_err(|e| convert_unify_error(e, location))?;
+                if let Some(int_value) = parse_int_value(&value) {
+                    self.environment
+                        .warn_if_unsafe_javascript_int(int_value, location);
+                }
                 Ok(Pattern::Int { location, value })
             }
 
@@ -358,6 +407,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                     situation: None,
                     location,
                     rigid_type_names: hashmap![],
+                    trace: vec![],
                 }),
             },
 
@@ -405,6 +455,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                         situation: None,
                         location,
                         rigid_type_names: hashmap![],
+                        trace: vec![],
                     })
                 }
             },
@@ -473,6 +524,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                                     location: spread_location,
                                     label: None,
                                     implicit: false,
+                                    label_shorthand: false,
                                 };
 
                                 pattern_args.insert(index_of_first_labelled_arg, new_call_arg);
@@ -504,6 +556,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                                         location: spread_location,
                                         label: None,
                                         implicit: true,
+                                        label_shorthand: false,
                                     });
                                 }
                             };
@@ -519,6 +572,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                         module,
                         location,
                         constructor_index,
+                        constructors_count,
                         ..
                     } => PatternConstructor {
                         documentation: documentation.clone(),
@@ -527,6 +581,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                         module: Some(module.clone()),
                         location: *location,
                         constructor_index: *constructor_index,
+                        constructors_count: *constructors_count,
                     },
                     ValueConstructorVariant::LocalVariable { .. }
                     | ValueConstructorVariant::LocalConstant { .. }
@@ -563,6 +618,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                                         location,
                                         implicit,
                                         label,
+                                        label_shorthand,
                                     } = arg;
                                     let value = self.unify(value, typ.clone())?;
                                     Ok(CallArg {
@@ -570,6 +626,7 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
                                         location,
                                         implicit,
                                         label,
+                                        label_shorthand,
                                     })
                                 })
                                 .try_collect()?;
@@ -99,6 +99,15 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
                     }
                 }
 
+                // left |> echo
+                UntypedExpr::Echo {
+                    location,
+                    expression: None,
+                } => TypedExpr::Echo {
+                    location,
+                    expression: Box::new(self.typed_left_hand_value_variable()),
+                },
+
                 // right(left)
                 call => self.infer_apply_pipe(call)?,
             };
@@ -121,6 +130,7 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
             // This argument is given implicitly by the pipe, not explicitly by
             // the programmer.
             implicit: true,
+            label_shorthand: false,
         }
     }
 
@@ -134,6 +144,7 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
             // This argument is given implicitly by the pipe, not explicitly by
             // the programmer.
             implicit: true,
+            label_shorthand: false,
         }
     }
 
@@ -9,10 +9,13 @@ use crate::{
 
 use super::{
     ModuleInterface, Type, TypeConstructor, TypeValueConstructor, TypeValueConstructorField,
-    TypeVar, TypeVariantConstructors, ValueConstructor, ValueConstructorVariant,
+    TypeVar, TypeVarCell, TypeVariantConstructors, ValueConstructor, ValueConstructorVariant,
 };
 use crate::type_::Deprecation::NotDeprecated;
-use std::{cell::RefCell, collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
 
 const BIT_ARRAY: &str = "BitArray";
 const BOOL: &str = "Bool";
@@ -61,53 +64,28 @@ impl PreludeType {
 }
 
 pub fn int() -> Arc<Type> {
-    Arc::new(Type::Named {
-        publicity: Publicity::Public,
-        name: INT.into(),
-        module: PRELUDE_MODULE_NAME.into(),
-        package: PRELUDE_PACKAGE_NAME.into(),
-        args: vec![],
-    })
+    static TYPE: OnceLock<Arc<Type>> = OnceLock::new();
+    interned(&TYPE, INT)
 }
 
 pub fn float() -> Arc<Type> {
-    Arc::new(Type::Named {
-        args: vec![],
-        publicity: Publicity::Public,
-        name: FLOAT.into(),
-        module: PRELUDE_MODULE_NAME.into(),
-        package: PRELUDE_PACKAGE_NAME.into(),
-    })
+    static TYPE: OnceLock<Arc<Type>> = OnceLock::new();
+    interned(&TYPE, FLOAT)
 }
 
 pub fn bool() -> Arc<Type> {
-    Arc::new(Type::Named {
-        args: vec![],
-        publicity: Publicity::Public,
-        name: BOOL.into(),
-        module: PRELUDE_MODULE_NAME.into(),
-        package: PRELUDE_PACKAGE_NAME.into(),
-    })
+    static TYPE: OnceLock<Arc<Type>> = OnceLock::new();
+    interned(&TYPE, BOOL)
 }
 
 pub fn string() -> Arc<Type> {
-    Arc::new(Type::Named {
-        args: vec![],
-        publicity: Publicity::Public,
-        name: STRING.into(),
-        module: PRELUDE_MODULE_NAME.into(),
-        package: PRELUDE_PACKAGE_NAME.into(),
-    })
+    static TYPE: OnceLock<Arc<Type>> = OnceLock::new();
+    interned(&TYPE, STRING)
 }
 
 pub fn nil() -> Arc<Type> {
-    Arc::new(Type::Named {
-        args: vec![],
-        publicity: Publicity::Public,
-        name: NIL.into(),
-        module: PRELUDE_MODULE_NAME.into(),
-        package: PRELUDE_PACKAGE_NAME.into(),
-    })
+    static TYPE: OnceLock<Arc<Type>> = OnceLock::new();
+    interned(&TYPE, NIL)
 }
 
 pub fn list(t: Arc<Type>) -> Arc<Type> {
@@ -155,41 +133,48 @@ pub fn named(
 }
 
 pub fn bits() -> Arc<Type> {
-    Arc::new(Type::Named {
-        args: vec![],
-        publicity: Publicity::Public,
-        name: BIT_ARRAY.into(),
-        module: PRELUDE_MODULE_NAME.into(),
-        package: PRELUDE_PACKAGE_NAME.into(),
-    })
+    static TYPE: OnceLock<Arc<Type>> = OnceLock::new();
+    interned(&TYPE, BIT_ARRAY)
 }
 
 pub fn utf_codepoint() -> Arc<Type> {
-    Arc::new(Type::Named {
-        args: vec![],
-        publicity: Publicity::Public,
-        name: UTF_CODEPOINT.into(),
-        module: PRELUDE_MODULE_NAME.into(),
-        package: PRELUDE_PACKAGE_NAME.into(),
-    })
+    static TYPE: OnceLock<Arc<Type>> = OnceLock::new();
+    interned(&TYPE, UTF_CODEPOINT)
+}
+
+/// The types of the prelude without parameters are used by a great many
+/// expressions, so each one is created once and shared rather than allocated
+/// again for every use.
+fn interned(type_: &OnceLock<Arc<Type>>, name: &str) -> Arc<Type> {
+    type_
+        .get_or_init(|| {
+            Arc::new(Type::Named {
+                args: vec![],
+                publicity: Publicity::Public,
+                name: name.into(),
+                module: PRELUDE_MODULE_NAME.into(),
+                package: PRELUDE_PACKAGE_NAME.into(),
+            })
+        })
+        .clone()
 }
 
 pub fn generic_var(id: u64) -> Arc<Type> {
     Arc::new(Type::Var {
-        type_: Arc::new(RefCell::new(TypeVar::Generic { id })),
+        type_: Arc::new(TypeVarCell::new(TypeVar::Generic { id })),
     })
 }
 
 pub fn unbound_var(id: u64) -> Arc<Type> {
     Arc::new(Type::Var {
-        type_: Arc::new(RefCell::new(TypeVar::Unbound { id })),
+        type_: Arc::new(TypeVarCell::new(TypeVar::Unbound { id })),
     })
 }
 
 #[cfg(test)]
 pub fn link(type_: Arc<Type>) -> Arc<Type> {
     Arc::new(Type::Var {
-        type_: Arc::new(RefCell::new(TypeVar::Link { type_ })),
+        type_: Arc::new(TypeVarCell::new(TypeVar::Link { type_ })),
     })
 }
 
@@ -1,27 +1,102 @@
-use super::{Type, TypeVar};
+use super::{prelude::is_prelude_module, Type, TypeVar};
 use crate::{
+    ast::{Definition, TypedModule},
     docvec,
     pretty::{nil, *},
 };
-use ecow::EcoString;
-use std::sync::Arc;
+use ecow::{eco_format, EcoString};
+use std::{collections::HashMap, sync::Arc};
 
 #[cfg(test)]
 use super::*;
-#[cfg(test)]
-use std::cell::RefCell;
 
 #[cfg(test)]
 use pretty_assertions::assert_eq;
 
 const INDENT: isize = 2;
 
+/// Types that link to their documentation are printed between these zero
+/// width markers: the link's URL comes between `LINK_START` and `LINK_TEXT`,
+/// and the name of the type between `LINK_TEXT` and `LINK_END`. This lets the
+/// rest of the printed text be escaped before the links are turned into HTML.
+pub const LINK_START: char = '\u{E000}';
+pub const LINK_TEXT: char = '\u{E001}';
+pub const LINK_END: char = '\u{E002}';
+
 #[derive(Debug, Default)]
 pub struct Printer {
     names: im::HashMap<u64, EcoString>,
     uid: u64,
     // A mapping of printd type names to the module that they are defined in.
     printed_types: im::HashMap<EcoString, EcoString>,
+    // The URL of the documentation of each package whose types should be
+    // printed as links.
+    package_docs: im::HashMap<EcoString, EcoString>,
+    // The names types are written with in the module the types are printed
+    // for, if they are printed for one.
+    type_names: Option<TypeNames>,
+}
+
+/// The names the types defined in modules are written with in a module: the
+/// name a type is imported unqualified as, or its name qualified with the
+/// alias of the module defining it.
+#[derive(Debug, Clone, Default)]
+pub struct TypeNames {
+    module: EcoString,
+    unqualified: HashMap<(EcoString, EcoString), EcoString>,
+    aliases: HashMap<EcoString, EcoString>,
+}
+
+impl TypeNames {
+    pub fn new(module: &TypedModule) -> Self {
+        let mut names = TypeNames {
+            module: module.name.clone(),
+            ..Default::default()
+        };
+        for definition in &module.definitions {
+            let Definition::Import(import) = definition else {
+                continue;
+            };
+            for type_ in &import.unqualified_types {
+                let _ = names.unqualified.insert(
+                    (import.module.clone(), type_.name.clone()),
+                    type_.used_name().clone(),
+                );
+            }
+            if let Some(alias) = import.used_name() {
+                let _ = names.aliases.insert(import.module.clone(), alias);
+            }
+        }
+        names
+    }
+
+    /// Whether the type can be referred to in the module, as it is defined
+    /// in it, in the prelude, or in a module it imports.
+    pub fn is_in_scope(&self, module: &str, name: &str) -> bool {
+        module == self.module
+            || is_prelude_module(module)
+            || self.unqualified.contains_key(&(module.into(), name.into()))
+            || self.aliases.contains_key(module)
+    }
+
+    /// The name the type is written with in the module. Types from modules
+    /// that aren't imported are qualified with the last segment of the name
+    /// of their module.
+    pub fn name(&self, module: &str, name: &EcoString) -> EcoString {
+        if module == self.module || is_prelude_module(module) {
+            return name.clone();
+        }
+        if let Some(unqualified) = self.unqualified.get(&(module.into(), name.clone())) {
+            return unqualified.clone();
+        }
+        match self.aliases.get(module) {
+            Some(alias) => eco_format!("{alias}.{name}"),
+            None => {
+                let alias = module.rsplit('/').next().unwrap_or(module);
+                eco_format!("{alias}.{name}")
+            }
+        }
+    }
 }
 
 impl Printer {
@@ -33,6 +108,18 @@ impl Printer {
         self.names = names;
     }
 
+    /// Prints the names of types from the given packages as links to their
+    /// documentation, which is found at the URL given for each package.
+    pub fn with_package_docs(&mut self, package_docs: im::HashMap<EcoString, EcoString>) {
+        self.package_docs = package_docs;
+    }
+
+    /// Prints the names of types as they are written in the module the names
+    /// are for, rather than unqualified unless that would be ambiguous.
+    pub fn with_type_names(&mut self, type_names: TypeNames) {
+        self.type_names = Some(type_names);
+    }
+
     /// Render a Type as a well formatted string.
     ///
     pub fn pretty_print(&mut self, typ: &Type, initial_indent: usize) -> String {
@@ -53,14 +140,30 @@ impl Printer {
     pub fn print<'a>(&mut self, typ: &Type) -> Document<'a> {
         match typ {
             Type::Named {
-                name, args, module, ..
+                name,
+                args,
+                module,
+                package,
+                ..
             } => {
-                let doc = if self.name_clashes_if_unqualified(name, module) {
+                let doc = if let Some(type_names) = &self.type_names {
+                    type_names.name(module, name).to_doc()
+                } else if self.name_clashes_if_unqualified(name, module) {
                     qualify_type_name(module, name)
                 } else {
                     let _ = self.printed_types.insert(name.clone(), module.clone());
                     name.to_doc()
                 };
+                let doc = match self.package_docs.get(package) {
+                    Some(url) => docvec![
+                        zero_width_string(format!(
+                            "{LINK_START}{url}{module}.html#{name}{LINK_TEXT}"
+                        )),
+                        doc,
+                        zero_width_string(LINK_END.to_string()),
+                    ],
+                    None => doc,
+                };
                 if args.is_empty() {
                     doc
                 } else {
@@ -109,7 +212,12 @@ impl Printer {
                 n.to_doc()
             }
             None => {
-                let n = self.next_letter();
+                // Names given with `with_names` aren't used for other type
+                // variables.
+                let mut n = self.next_letter();
+                while self.names.values().any(|name| *name == n) {
+                    n = self.next_letter();
+                }
                 let _ = self.names.insert(id, n.clone());
                 let _ = self.printed_types.insert(n.clone(), "".into());
                 n.to_doc()
@@ -318,7 +426,7 @@ fn pretty_print_test() {
     );
     assert_string!(
         Type::Var {
-            type_: Arc::new(RefCell::new(TypeVar::Link {
+            type_: Arc::new(TypeVarCell::new(TypeVar::Link {
                 type_: Arc::new(Type::Named {
                     args: vec![],
                     module: "whatever".into(),
@@ -332,17 +440,17 @@ fn pretty_print_test() {
     );
     assert_string!(
         Type::Var {
-            type_: Arc::new(RefCell::new(TypeVar::Unbound { id: 2231 })),
+            type_: Arc::new(TypeVarCell::new(TypeVar::Unbound { id: 2231 })),
         },
         "a",
     );
     assert_string!(
         fn_(
             vec![Arc::new(Type::Var {
-                type_: Arc::new(RefCell::new(TypeVar::Unbound { id: 78 })),
+                type_: Arc::new(TypeVarCell::new(TypeVar::Unbound { id: 78 })),
             })],
             Arc::new(Type::Var {
-                type_: Arc::new(RefCell::new(TypeVar::Unbound { id: 2 })),
+                type_: Arc::new(TypeVarCell::new(TypeVar::Unbound { id: 2 })),
             }),
         ),
         "fn(a) -> b",
@@ -350,10 +458,10 @@ fn pretty_print_test() {
     assert_string!(
         fn_(
             vec![Arc::new(Type::Var {
-                type_: Arc::new(RefCell::new(TypeVar::Generic { id: 78 })),
+                type_: Arc::new(TypeVarCell::new(TypeVar::Generic { id: 78 })),
             })],
             Arc::new(Type::Var {
-                type_: Arc::new(RefCell::new(TypeVar::Generic { id: 2 })),
+                type_: Arc::new(TypeVarCell::new(TypeVar::Generic { id: 2 })),
             }),
         ),
         "fn(a) -> b",
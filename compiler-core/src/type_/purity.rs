@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use ecow::EcoString;
+
+use crate::ast::{
+    visit::{self, Visit},
+    AssignmentKind, CallArg, Definition, SrcSpan, TypedAssignment, TypedDefinition, TypedExpr,
+    TypedStatement,
+};
+
+use super::{ModuleValueConstructor, Type, ValueConstructorVariant};
+
+/// Whether calling a function can have side effects.
+///
+/// This is recorded in the module interface so the language server can show
+/// where effects happen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Purity {
+    /// The function has no side effects: it doesn't use external code, it
+    /// can't panic, and it only calls other pure functions.
+    Pure,
+    /// The function may have side effects. This is assumed when the purity
+    /// of a function isn't known otherwise, so that nothing treats an effect
+    /// as removable by mistake.
+    #[default]
+    Impure,
+    /// The function calls functions whose purity can't be known, such as
+    /// functions passed to it as arguments.
+    Unknown,
+}
+
+impl Purity {
+    /// The purity of code that does both of the things with the given
+    /// purities.
+    pub fn merge(self, other: Purity) -> Purity {
+        match (self, other) {
+            (Purity::Impure, _) | (_, Purity::Impure) => Purity::Impure,
+            (Purity::Unknown, _) | (_, Purity::Unknown) => Purity::Unknown,
+            (Purity::Pure, Purity::Pure) => Purity::Pure,
+        }
+    }
+
+    pub fn is_pure(self) -> bool {
+        self == Purity::Pure
+    }
+}
+
+/// Works out the purity of each function in a group of mutually recursive
+/// functions that have just been type checked.
+///
+/// The calls between the functions of the group were typed before the purity
+/// of the others was known, so their purities are worked out together: each
+/// starts out pure and is made less pure until none of them change.
+pub fn infer_group_purity(
+    group: &[TypedDefinition],
+    module: &EcoString,
+) -> HashMap<EcoString, Purity> {
+    let functions = group
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Function(function) => Some(function),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut purities: HashMap<EcoString, Purity> = functions
+        .iter()
+        .map(|function| (function.name.clone(), Purity::Pure))
+        .collect();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for function in &functions {
+            let purity =
+                if function.external_erlang.is_some() || function.external_javascript.is_some() {
+                    Purity::Impure
+                } else {
+                    body_purity(&function.body, module, &purities)
+                };
+            let previous = purities.insert(function.name.clone(), purity);
+            changed = changed || previous != Some(purity);
+        }
+    }
+    purities
+}
+
+/// The purity of a function body. Calls to the functions named in `group`
+/// have the purity given there rather than the one they were typed with.
+fn body_purity(
+    body: &[TypedStatement],
+    module: &EcoString,
+    group: &HashMap<EcoString, Purity>,
+) -> Purity {
+    let mut checker = PurityChecker {
+        module,
+        group,
+        purity: Purity::Pure,
+    };
+    for statement in body {
+        checker.visit_typed_statement(statement);
+    }
+    checker.purity
+}
+
+struct PurityChecker<'a> {
+    module: &'a EcoString,
+    group: &'a HashMap<EcoString, Purity>,
+    purity: Purity,
+}
+
+impl PurityChecker<'_> {
+    fn called_function_purity(&self, fun: &TypedExpr) -> Purity {
+        match fun {
+            TypedExpr::Var { constructor, .. } => match &constructor.variant {
+                ValueConstructorVariant::ModuleFn { name, module, .. }
+                    if module == self.module && self.group.contains_key(name) =>
+                {
+                    self.group.get(name).copied().unwrap_or_default()
+                }
+                ValueConstructorVariant::ModuleFn { purity, .. } => *purity,
+                ValueConstructorVariant::Record { .. } => Purity::Pure,
+                ValueConstructorVariant::LocalVariable { .. }
+                | ValueConstructorVariant::ModuleConstant { .. }
+                | ValueConstructorVariant::LocalConstant { .. } => Purity::Unknown,
+            },
+
+            TypedExpr::ModuleSelect { constructor, .. } => match constructor {
+                ModuleValueConstructor::Fn { purity, .. } => *purity,
+                ModuleValueConstructor::Record { .. } => Purity::Pure,
+                ModuleValueConstructor::Constant { .. } => Purity::Unknown,
+            },
+
+            _ => Purity::Unknown,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for PurityChecker<'_> {
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        match expr {
+            TypedExpr::Todo { .. } | TypedExpr::Panic { .. } | TypedExpr::Echo { .. } => {
+                self.purity = Purity::Impure;
+            }
+            // Creating an anonymous function doesn't run its body.
+            TypedExpr::Fn { .. } => return,
+            _ => {}
+        }
+        visit::visit_typed_expr(self, expr);
+    }
+
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'ast SrcSpan,
+        typ: &'ast std::sync::Arc<Type>,
+        fun: &'ast TypedExpr,
+        args: &'ast [CallArg<TypedExpr>],
+    ) {
+        self.purity = self.purity.merge(self.called_function_purity(fun));
+        visit::visit_typed_expr_call(self, location, typ, fun, args);
+    }
+
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        if let AssignmentKind::Assert { .. } = assignment.kind {
+            self.purity = Purity::Impure;
+        }
+        visit::visit_typed_assignment(self, assignment);
+    }
+}
@@ -3,9 +3,9 @@ use crate::{
     analyse::TargetSupport,
     ast::{TypedModule, TypedStatement, UntypedExpr, UntypedModule},
     build::{Origin, Target},
-    config::PackageConfig,
+    config::{PackageConfig, WarningsConfig},
     error::Error,
-    type_::{build_prelude, expression::FunctionDefinition, pretty::Printer},
+    type_::{build_prelude, expression::FunctionDefinition, pretty::Printer, Purity},
     uid::UniqueIdGenerator,
     warning::{TypeWarningEmitter, VectorWarningEmitterIO, WarningEmitter, WarningEmitterIO},
 };
@@ -28,6 +28,7 @@ mod guards;
 mod imports;
 mod pipes;
 mod pretty;
+mod purity;
 mod target_implementations;
 mod type_alias;
 mod use_;
@@ -123,17 +124,19 @@ macro_rules! assert_module_syntax_error {
 macro_rules! assert_error {
     ($src:expr, $error:expr $(,)?) => {
         let result = $crate::type_::tests::compile_statement_sequence($src)
-            .expect_err("should infer an error");
+            .expect_err("should infer an error")
+            .first()
+            .clone();
         assert_eq!(($src, sort_options($error)), ($src, sort_options(result)),);
     };
 
     ($src:expr) => {
-        let error = $crate::type_::tests::compile_statement_sequence($src)
+        let errors = $crate::type_::tests::compile_statement_sequence($src)
             .expect_err("should infer an error");
         let error = $crate::error::Error::Type {
             src: $src.into(),
             path: camino::Utf8PathBuf::from("/src/one/two.gleam"),
-            error,
+            errors,
         };
         let output = error.pretty_string();
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
@@ -173,12 +176,80 @@ fn get_warnings(src: &str, deps: Vec<DependencyModule<'_>>) -> Vec<Warning> {
         .map(|warning| match warning {
             crate::Warning::Type { warning, .. } => warning,
             crate::Warning::InvalidSource { .. } => panic!("Invalid module file name"),
+            crate::Warning::DependencyWarnings { .. } => panic!("Dependency warnings"),
+            crate::Warning::Cached { .. } => panic!("Cached warning"),
+            crate::Warning::Denied { .. } => panic!("Denied warning"),
+        })
+        .collect_vec()
+}
+
+fn get_warnings_with_config(
+    src: &str,
+    deps: Vec<DependencyModule<'_>>,
+    warnings_config: WarningsConfig,
+) -> Vec<Warning> {
+    get_warnings_with_opts(src, deps, Target::Erlang, warnings_config)
+}
+
+fn get_warnings_with_target(
+    src: &str,
+    deps: Vec<DependencyModule<'_>>,
+    target: Target,
+) -> Vec<Warning> {
+    get_warnings_with_opts(src, deps, target, WarningsConfig::default())
+}
+
+fn get_warnings_with_opts(
+    src: &str,
+    deps: Vec<DependencyModule<'_>>,
+    target: Target,
+    warnings_config: WarningsConfig,
+) -> Vec<Warning> {
+    let warnings = VectorWarningEmitterIO::default();
+    _ = compile_module_with_warnings_config(
+        "test_module",
+        src,
+        Some(Arc::new(warnings.clone())),
+        deps,
+        target,
+        TargetSupport::NotEnforced,
+        warnings_config,
+    )
+    .expect("should successfully infer");
+    warnings
+        .take()
+        .into_iter()
+        .map(|warning| match warning {
+            crate::Warning::Type { warning, .. } => warning,
+            crate::Warning::InvalidSource { .. } => panic!("Invalid module file name"),
+            crate::Warning::DependencyWarnings { .. } => panic!("Dependency warnings"),
+            crate::Warning::Cached { .. } => panic!("Cached warning"),
+            crate::Warning::Denied { .. } => panic!("Denied warning"),
         })
         .collect_vec()
 }
 
 fn get_printed_warnings(src: &str, deps: Vec<DependencyModule<'_>>) -> String {
-    let warnings = get_warnings(src, deps);
+    print_warnings(src, get_warnings(src, deps))
+}
+
+fn get_printed_warnings_with_config(
+    src: &str,
+    deps: Vec<DependencyModule<'_>>,
+    warnings_config: WarningsConfig,
+) -> String {
+    print_warnings(src, get_warnings_with_config(src, deps, warnings_config))
+}
+
+fn get_printed_warnings_with_target(
+    src: &str,
+    deps: Vec<DependencyModule<'_>>,
+    target: Target,
+) -> String {
+    print_warnings(src, get_warnings_with_target(src, deps, target))
+}
+
+fn print_warnings(src: &str, warnings: Vec<Warning>) -> String {
     let mut nocolor = termcolor::Buffer::no_color();
     for warning in warnings {
         let path = Utf8PathBuf::from("/src/warning/wrn.gleam");
@@ -244,6 +315,48 @@ macro_rules! assert_warning {
     };
 }
 
+#[macro_export]
+macro_rules! assert_warning_with_config {
+    ($config:expr, $src:expr $(,)?) => {
+        let output = $crate::type_::tests::get_printed_warnings_with_config($src, vec![], $config);
+        assert!(!output.is_empty());
+        insta::assert_snapshot!(insta::internals::AutoName, output, $src);
+    };
+}
+
+#[macro_export]
+macro_rules! assert_no_warnings_with_config {
+    ($config:expr, $src:expr $(,)?) => {
+        let warnings = $crate::type_::tests::get_warnings_with_config($src, vec![], $config);
+        assert_eq!(warnings, vec![]);
+    };
+}
+
+#[macro_export]
+macro_rules! assert_js_warning {
+    ($src:expr $(,)?) => {
+        let output = $crate::type_::tests::get_printed_warnings_with_target(
+            $src,
+            vec![],
+            $crate::build::Target::JavaScript,
+        );
+        assert!(!output.is_empty());
+        insta::assert_snapshot!(insta::internals::AutoName, output, $src);
+    };
+}
+
+#[macro_export]
+macro_rules! assert_js_no_warnings {
+    ($src:expr $(,)?) => {
+        let warnings = $crate::type_::tests::get_warnings_with_target(
+            $src,
+            vec![],
+            $crate::build::Target::JavaScript,
+        );
+        assert_eq!(warnings, vec![]);
+    };
+}
+
 #[macro_export]
 macro_rules! assert_no_warnings {
     ($src:expr $(,)?) => {
@@ -259,7 +372,9 @@ macro_rules! assert_no_warnings {
     };
 }
 
-fn compile_statement_sequence(src: &str) -> Result<Vec1<TypedStatement>, crate::type_::Error> {
+fn compile_statement_sequence(
+    src: &str,
+) -> Result<Vec1<TypedStatement>, Vec1<crate::type_::Error>> {
     let ast = crate::parse::parse_statement_sequence(src).expect("syntax error");
     let mut modules = im::HashMap::new();
     let ids = UniqueIdGenerator::new();
@@ -268,23 +383,26 @@ fn compile_statement_sequence(src: &str) -> Result<Vec1<TypedStatement>, crate::
     // to have one place where we create all this required state for use in each
     // place.
     let _ = modules.insert(PRELUDE_MODULE_NAME.into(), build_prelude(&ids));
-    crate::type_::ExprTyper::new(
-        &mut crate::type_::Environment::new(
-            ids,
-            "thepackage".into(),
-            "themodule".into(),
-            Target::Erlang,
-            &modules,
-            &TypeWarningEmitter::null(),
-            TargetSupport::Enforced,
-        ),
+    let warnings = TypeWarningEmitter::null();
+    let mut environment = crate::type_::Environment::new(
+        ids,
+        "thepackage".into(),
+        "themodule".into(),
+        Target::Erlang,
+        &modules,
+        &warnings,
+        TargetSupport::Enforced,
+    );
+    let mut expr_typer = crate::type_::ExprTyper::new(
+        &mut environment,
         FunctionDefinition {
             has_body: true,
             has_erlang_external: false,
             has_javascript_external: false,
         },
-    )
-    .infer_statements(ast)
+    );
+    let result = expr_typer.infer_statements(ast);
+    expr_typer.with_recorded_errors(result)
 }
 
 fn infer(src: &str) -> String {
@@ -344,7 +462,7 @@ pub fn compile_module(
     src: &str,
     warnings: Option<Arc<dyn WarningEmitterIO>>,
     dep: Vec<DependencyModule<'_>>,
-) -> Result<TypedModule, crate::type_::Error> {
+) -> Result<TypedModule, Vec1<crate::type_::Error>> {
     compile_module_with_opts(
         module_name,
         src,
@@ -362,7 +480,27 @@ pub fn compile_module_with_opts(
     dep: Vec<DependencyModule<'_>>,
     target: Target,
     target_support: TargetSupport,
-) -> Result<TypedModule, crate::type_::Error> {
+) -> Result<TypedModule, Vec1<crate::type_::Error>> {
+    compile_module_with_warnings_config(
+        module_name,
+        src,
+        warnings,
+        dep,
+        target,
+        target_support,
+        WarningsConfig::default(),
+    )
+}
+
+pub fn compile_module_with_warnings_config(
+    module_name: &str,
+    src: &str,
+    warnings: Option<Arc<dyn WarningEmitterIO>>,
+    dep: Vec<DependencyModule<'_>>,
+    target: Target,
+    target_support: TargetSupport,
+    warnings_config: WarningsConfig,
+) -> Result<TypedModule, Vec1<crate::type_::Error>> {
     let ids = UniqueIdGenerator::new();
     let mut modules = im::HashMap::new();
     let warnings = TypeWarningEmitter::new(
@@ -413,6 +551,7 @@ pub fn compile_module_with_opts(
     ast.name = module_name.into();
     let mut config = crate::config::PackageConfig::default();
     config.name = "thepackage".into();
+    config.warnings = warnings_config;
     crate::analyse::infer_module(
         target,
         &ids,
@@ -437,7 +576,7 @@ pub fn module_error_with_target(
     deps: Vec<DependencyModule<'_>>,
     target: Target,
 ) -> String {
-    let error = compile_module_with_opts(
+    let errors = compile_module_with_opts(
         "themodule",
         src,
         None,
@@ -449,7 +588,7 @@ pub fn module_error_with_target(
     let error = Error::Type {
         src: src.into(),
         path: Utf8PathBuf::from("/src/one/two.gleam"),
-        error,
+        errors,
     };
     error.pretty_string()
 }
@@ -507,18 +646,21 @@ fn field_map_reorder_test() {
         args: vec![
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 location: Default::default(),
                 label: None,
                 value: int("1"),
             },
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 location: Default::default(),
                 label: None,
                 value: int("2"),
             },
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 location: Default::default(),
                 label: None,
                 value: int("3"),
@@ -528,18 +670,21 @@ fn field_map_reorder_test() {
         expected_args: vec![
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 location: Default::default(),
                 label: None,
                 value: int("1"),
             },
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 location: Default::default(),
                 label: None,
                 value: int("2"),
             },
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 location: Default::default(),
                 label: None,
                 value: int("3"),
@@ -554,18 +699,21 @@ fn field_map_reorder_test() {
         args: vec![
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 location: Default::default(),
                 label: None,
                 value: int("1"),
             },
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 location: Default::default(),
                 label: None,
                 value: int("2"),
             },
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 location: Default::default(),
                 label: Some("last".into()),
                 value: int("3"),
@@ -575,18 +723,21 @@ fn field_map_reorder_test() {
         expected_args: vec![
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 location: Default::default(),
                 label: None,
                 value: int("1"),
             },
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 location: Default::default(),
                 label: None,
                 value: int("2"),
             },
             CallArg {
                 implicit: false,
+                label_shorthand: false,
                 location: Default::default(),
                 label: Some("last".into()),
                 value: int("3"),
@@ -730,6 +881,11 @@ fn simple_exprs() {
     assert_infer!("todo + 1", "Int");
     assert_infer!("todo(\"test\") + 1", "Int");
 
+    // echo
+    assert_infer!("echo 1", "Int");
+    assert_infer!("echo [1, 2] |> echo", "List(Int)");
+    assert_infer!("1 + echo 2", "Int");
+
     // hex, octal, and binary literals
     assert_infer!("0xF", "Int");
     assert_infer!("0o11", "Int");
@@ -2085,7 +2241,9 @@ fn assert_suitable_main_function_not_module_function() {
             },
         },
     };
-    assert!(assert_suitable_main_function(&value, &"module".into(), Target::Erlang).is_err(),);
+    assert!(
+        assert_suitable_main_function(&value, &"module".into(), "main", Target::Erlang).is_err(),
+    );
 }
 
 #[test]
@@ -2108,9 +2266,13 @@ fn assert_suitable_main_function_wrong_arity() {
                 can_run_on_erlang: true,
                 can_run_on_javascript: true,
             },
+            purity: Purity::Pure,
+            must_use: false,
         },
     };
-    assert!(assert_suitable_main_function(&value, &"module".into(), Target::Erlang).is_err(),);
+    assert!(
+        assert_suitable_main_function(&value, &"module".into(), "main", Target::Erlang).is_err(),
+    );
 }
 
 #[test]
@@ -2133,9 +2295,13 @@ fn assert_suitable_main_function_ok() {
                 can_run_on_erlang: true,
                 can_run_on_javascript: true,
             },
+            purity: Purity::Pure,
+            must_use: false,
         },
     };
-    assert!(assert_suitable_main_function(&value, &"module".into(), Target::Erlang).is_ok(),);
+    assert!(
+        assert_suitable_main_function(&value, &"module".into(), "main", Target::Erlang).is_ok(),
+    );
 }
 
 #[test]
@@ -2158,9 +2324,13 @@ fn assert_suitable_main_function_erlang_not_supported() {
                 can_run_on_erlang: false,
                 can_run_on_javascript: true,
             },
+            purity: Purity::Pure,
+            must_use: false,
         },
     };
-    assert!(assert_suitable_main_function(&value, &"module".into(), Target::Erlang).is_err(),);
+    assert!(
+        assert_suitable_main_function(&value, &"module".into(), "main", Target::Erlang).is_err(),
+    );
 }
 
 #[test]
@@ -2183,7 +2353,45 @@ fn assert_suitable_main_function_javascript_not_supported() {
                 can_run_on_erlang: true,
                 can_run_on_javascript: false,
             },
+            purity: Purity::Pure,
+            must_use: false,
+        },
+    };
+    assert!(
+        assert_suitable_main_function(&value, &"module".into(), "main", Target::JavaScript)
+            .is_err(),
+    );
+}
+
+#[test]
+fn assert_suitable_main_function_taking_arguments() {
+    let value = |type_| ValueConstructor {
+        publicity: Publicity::Public,
+        deprecation: Deprecation::NotDeprecated,
+        type_,
+        variant: ValueConstructorVariant::ModuleFn {
+            name: "name".into(),
+            field_map: None,
+            arity: 1,
+            documentation: None,
+            location: Default::default(),
+            module: "module".into(),
+            implementations: Implementations {
+                gleam: true,
+                uses_erlang_externals: false,
+                uses_javascript_externals: false,
+                can_run_on_erlang: true,
+                can_run_on_javascript: true,
+            },
+            purity: Purity::Pure,
+            must_use: false,
         },
     };
-    assert!(assert_suitable_main_function(&value, &"module".into(), Target::JavaScript).is_err(),);
+    let arguments = value(fn_(vec![list(string())], nil()));
+    assert!(
+        assert_suitable_main_function(&arguments, &"module".into(), "run", Target::Erlang)
+            .is_ok_and(|takes_arguments| takes_arguments)
+    );
+    let ints = value(fn_(vec![list(int())], nil()));
+    assert!(assert_suitable_main_function(&ints, &"module".into(), "run", Target::Erlang).is_err());
 }
@@ -1763,3 +1763,208 @@ fn list() {
 fn mismatched_list_tail() {
     assert_error!("[\"foo\", ..[1, 2]]");
 }
+
+#[test]
+fn unknown_variable_defined_in_imported_module() {
+    assert_with_module_error!(
+        ("gleam/list", "pub fn map(list, fun) { list }"),
+        "
+import gleam/list
+pub fn main() {
+  map([], fn(x) { x })
+}
+",
+    );
+}
+
+#[test]
+fn unknown_variable_defined_in_unimported_module() {
+    assert_with_module_error!(
+        ("gleam/result", "pub fn unwrap(result, default) { default }"),
+        "
+pub fn main() {
+  unwrap(Ok(1), 0)
+}
+",
+    );
+}
+
+#[test]
+fn unknown_variable_defined_in_multiple_unimported_modules() {
+    assert_with_module_error!(
+        ("gleam/list", "pub fn map(list, fun) { list }"),
+        ("gleam/option", "pub fn map(option, fun) { option }"),
+        "
+pub fn main() {
+  map([], fn(x) { x })
+}
+",
+    );
+}
+
+#[test]
+fn unknown_module_that_can_be_imported() {
+    assert_with_module_error!(
+        ("gleam/list", "pub fn map(list, fun) { list }"),
+        "
+pub fn main() {
+  list.map([], fn(x) { x })
+}
+",
+    );
+}
+
+#[test]
+fn type_mismatch_explains_type_inferred_from_argument() {
+    assert_module_error!(
+        "
+fn pair(a: x, b: x) { [a, b] }
+
+pub fn main() {
+  pair(1, \"wibble\")
+}
+"
+    );
+}
+
+#[test]
+fn type_mismatch_explains_type_inferred_from_pattern() {
+    assert_module_error!(
+        "
+pub fn main(x) {
+  case x {
+    #(1, _) -> Nil
+    _ -> Nil
+  }
+  x.1 <> \"wibble\"
+  x.0 +. 1.0
+}
+"
+    );
+}
+
+#[test]
+fn type_mismatch_explains_type_inferred_from_annotation() {
+    assert_module_error!(
+        "
+pub fn main(x) {
+  let y: List(Int) = x
+  [\"wibble\", ..x]
+}
+"
+    );
+}
+
+#[test]
+fn type_mismatch_explains_type_inferred_from_operator() {
+    assert_module_error!(
+        "
+pub fn main(x, y) {
+  let z = x + 1
+  y <> \"wibble\"
+  #(x, y) == #(\"wibble\", 1)
+}
+"
+    );
+}
+
+#[test]
+fn multiple_errors_in_one_function() {
+    assert_module_error!(
+        "
+pub fn main() {
+  let x = 1 + \"wibble\"
+  wobble
+  [1, 2.0]
+}
+"
+    );
+}
+
+#[test]
+fn variable_from_invalid_assignment_does_not_cause_more_errors() {
+    assert_module_error!(
+        "
+pub fn main() {
+  let #(x, y) = wibble
+  x + y
+}
+"
+    );
+}
+
+#[test]
+fn errors_in_multiple_functions() {
+    assert_module_error!(
+        "
+pub fn wibble() {
+  1 + 1.0
+}
+
+pub fn wobble() {
+  \"wobble\" <> 1
+}
+"
+    );
+}
+
+#[test]
+fn multiple_errors_in_use_callback() {
+    assert_module_error!(
+        "
+pub fn main() {
+  use x <- apply(1)
+  x <> \"wibble\"
+  wobble
+}
+
+fn apply(x, f) {
+  f(x)
+}
+"
+    );
+}
+
+#[test]
+fn echo_with_no_following_expression() {
+    assert_error!("echo");
+}
+
+#[test]
+fn echo_at_the_start_of_a_pipeline() {
+    assert_error!("echo |> fn(x) { x }");
+}
+
+#[test]
+fn non_literal_constant_in_case_pattern() {
+    assert_with_module_error!(
+        ("pets", r#"pub const names = ["Lucy", "Nubi"]"#),
+        r#"
+import pets
+
+pub fn main(x) {
+  case x {
+    pets.names -> True
+    _ -> False
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn constant_in_case_pattern_of_wrong_type() {
+    assert_with_module_error!(
+        ("answers", "pub const answer = 42"),
+        r#"
+import answers
+
+pub fn main(x: String) {
+  case x {
+    answers.answer -> True
+    _ -> False
+  }
+}
+"#
+    );
+}
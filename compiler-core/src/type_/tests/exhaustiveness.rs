@@ -1,4 +1,9 @@
-use crate::{assert_module_error, assert_no_warnings, assert_warning};
+use crate::{
+    assert_module_error, assert_module_infer, assert_no_warnings, assert_warning,
+    assert_with_module_error,
+    exhaustiveness::MissingPattern,
+    type_::{tests::compile_module, Error},
+};
 
 #[test]
 fn whatever() {
@@ -490,6 +495,107 @@ pub fn main(x) {
     );
 }
 
+#[test]
+fn bit_array_discard_rest() {
+    assert_no_warnings!(
+        r#"
+pub fn main(x) {
+  case x {
+    <<>> -> 1
+    <<_:bits>> -> 2
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn bit_array_first_bit() {
+    assert_no_warnings!(
+        r#"
+pub fn main(x) {
+  case x {
+    <<>> -> 1
+    <<0:1, _:bits>> -> 2
+    <<1:1, _:bits>> -> 3
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn bit_array_empty_or_bytes() {
+    assert_module_error!(
+        r#"
+pub fn main(x) {
+  case x {
+    <<>> -> 1
+    <<_, _:bytes>> -> 2
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn bit_array_rest_bytes_is_not_a_catch_all() {
+    assert_module_error!(
+        r#"
+pub fn main(x) {
+  case x {
+    <<rest:bytes>> -> rest
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn bit_array_duplicate_pattern() {
+    assert_warning!(
+        r#"
+pub fn main(x) {
+  case x {
+    <<1>> -> 1
+    <<1>> -> 2
+    _ -> 3
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn bit_array_covered_by_earlier_pattern() {
+    assert_warning!(
+        r#"
+pub fn main(x) {
+  case x {
+    <<1, _:bits>> -> 1
+    <<1, 2>> -> 2
+    _ -> 3
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn bit_array_unknown_segments() {
+    assert_no_warnings!(
+        r#"
+pub fn main(x) {
+  case x {
+    <<a:float>> -> a
+    <<a:float>> -> a
+    _ -> 1.0
+  }
+}
+"#
+    );
+}
+
 #[test]
 fn int() {
     assert_no_warnings!(
@@ -915,3 +1021,183 @@ pub fn main(x: something) {
 "#
     );
 }
+
+#[test]
+fn missing_patterns_are_structured() {
+    let src = "
+pub type Wrapper {
+  Wrapper(Result(Int, Nil), List(Int))
+}
+
+pub fn main(x) {
+  case x {
+    Wrapper(Ok(_), []) -> 0
+  }
+}
+";
+    let error = compile_module("my_module", src, None, vec![])
+        .expect_err("should be inexhaustive")
+        .first()
+        .clone();
+    let Error::InexhaustiveCaseExpression { missing, .. } = error else {
+        panic!("expected an inexhaustive case expression error, got {error:?}");
+    };
+    let wrapper = |first, second| MissingPattern::Constructor {
+        module: "my_module".into(),
+        name: "Wrapper".into(),
+        arguments: vec![first, second],
+    };
+    let error_pattern = MissingPattern::Constructor {
+        module: "gleam".into(),
+        name: "Error".into(),
+        arguments: vec![MissingPattern::Discard],
+    };
+    let empty_list = MissingPattern::List {
+        elements: vec![],
+        tail: false,
+    };
+    let non_empty_list = MissingPattern::List {
+        elements: vec![MissingPattern::Discard],
+        tail: true,
+    };
+    assert_eq!(
+        missing,
+        vec![
+            wrapper(error_pattern, empty_list),
+            wrapper(MissingPattern::Discard, non_empty_list),
+        ]
+    );
+    assert_eq!(
+        missing
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .collect::<Vec<_>>(),
+        vec!["Wrapper(Error(_), [])", "Wrapper(_, [_, ..])"]
+    );
+}
+
+#[test]
+fn constant_pattern_is_not_exhaustive() {
+    assert_with_module_error!(
+        ("answers", "pub const answer = 42"),
+        r#"
+import answers
+
+pub fn main(x) {
+  case x {
+    answers.answer -> True
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn constant_pattern_before_catch_all() {
+    assert_no_warnings!(
+        ("thepackage", "answers", "pub const answer = 42"),
+        r#"
+import answers
+
+pub fn main(x) {
+  case x {
+    answers.answer -> True
+    _ -> False
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn constant_pattern_matches_same_value_as_literal() {
+    assert_warning!(
+        ("answers", "pub const answer = 42"),
+        r#"
+import answers
+
+pub fn main(x) {
+  case x {
+    42 -> 1
+    answers.answer -> 2
+    _ -> 3
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn case_pattern_with_constant_name_binds_variable() {
+    assert_module_infer!(
+        r#"
+pub const answers = [42]
+
+pub fn main(x) {
+  case x {
+    answers -> answers
+  }
+}
+"#,
+        vec![("answers", "List(Int)"), ("main", "fn(a) -> a")]
+    );
+}
+
+#[test]
+fn let_pattern_with_constant_name_binds_variable() {
+    assert_warning!(
+        r#"
+const answer = 42
+
+pub fn main(x) {
+  let answer = x
+  answer + 1
+}
+"#
+    );
+}
+
+#[test]
+fn case_without_clauses_is_missing_every_constructor() {
+    let src = "
+pub type Wibble {
+  Wibble(Int, String)
+  Wobble
+}
+
+pub fn main(x: Wibble) {
+  case x {}
+}
+";
+    let error = compile_module("my_module", src, None, vec![])
+        .expect_err("should be inexhaustive")
+        .first()
+        .clone();
+    let Error::InexhaustiveCaseExpression { missing, .. } = error else {
+        panic!("expected an inexhaustive case expression error, got {error:?}");
+    };
+    assert_eq!(
+        missing
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .collect::<Vec<_>>(),
+        vec!["Wibble(_, _)", "Wobble"]
+    );
+}
+
+#[test]
+fn case_without_clauses_on_int_is_missing_discard() {
+    let src = "
+pub fn main(x: Int) {
+  case x {}
+}
+";
+    let error = compile_module("my_module", src, None, vec![])
+        .expect_err("should be inexhaustive")
+        .first()
+        .clone();
+    let Error::InexhaustiveCaseExpression { missing, .. } = error else {
+        panic!("expected an inexhaustive case expression error, got {error:?}");
+    };
+    assert_eq!(missing, vec![MissingPattern::Discard]);
+}
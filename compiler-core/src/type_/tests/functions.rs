@@ -168,3 +168,58 @@ pub fn two(x) {
 "#
     );
 }
+
+#[test]
+fn label_shorthand_in_call() {
+    assert_module_infer!(
+        r#"
+pub fn main() {
+  let name = "Louis"
+  let age = 32
+  wibble(age:, name:)
+}
+
+fn wibble(name name: String, age age: Int) -> #(String, Int) {
+  #(name, age)
+}
+"#,
+        vec![("main", "fn() -> #(String, Int)")]
+    );
+}
+
+#[test]
+fn label_shorthand_in_constructor() {
+    assert_module_infer!(
+        r#"
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+pub fn main() {
+  let name = "Louis"
+  let age = 32
+  let Person(name:, ..) = Person(name:, age:)
+  name
+}
+"#,
+        vec![
+            ("Person", "fn(String, Int) -> Person"),
+            ("main", "fn() -> String")
+        ]
+    );
+}
+
+#[test]
+fn label_shorthand_with_unknown_variable() {
+    assert_module_error!(
+        r#"
+pub fn main() {
+  wibble(name:)
+}
+
+fn wibble(name name: String) -> String {
+  name
+}
+"#
+    );
+}
@@ -1,4 +1,4 @@
-use crate::{assert_infer_with_module, assert_with_module_error};
+use crate::{assert_infer_with_module, assert_module_error, assert_with_module_error};
 
 // https://github.com/gleam-lang/gleam/issues/1760
 #[test]
@@ -264,3 +264,87 @@ pub fn main(x: Foo) {
 }",
     );
 }
+
+#[test]
+fn qualified_constant_in_case_pattern() {
+    assert_infer_with_module!(
+        ("one/two", "pub const answer = 42"),
+        "
+import one/two
+
+pub fn main(x) {
+  case x {
+    two.answer -> True
+    _ -> False
+  }
+}
+",
+        vec![("main", "fn(Int) -> Bool")],
+    );
+}
+
+#[test]
+fn qualified_constant_in_case_pattern_before_import() {
+    assert_infer_with_module!(
+        ("one/two", "pub const answer = 42"),
+        "
+pub fn main(x) {
+  case x {
+    two.answer -> True
+    _ -> False
+  }
+}
+
+import one/two
+",
+        vec![("main", "fn(Int) -> Bool")],
+    );
+}
+
+#[test]
+fn qualified_constant_in_case_pattern_of_unknown_module() {
+    assert_module_error!(
+        "
+pub fn main(x) {
+  case x {
+    wibble.answer -> True
+    _ -> False
+  }
+}
+"
+    );
+}
+
+#[test]
+fn unqualified_constant_in_case_pattern_binds_variable() {
+    assert_infer_with_module!(
+        ("one/two", "pub const greeting = \"Hello\""),
+        "
+import one/two.{greeting}
+
+pub fn main(x) {
+  case x {
+    greeting -> True
+  }
+}
+",
+        vec![("main", "fn(a) -> Bool")],
+    );
+}
+
+#[test]
+fn unknown_qualified_constant_in_case_pattern() {
+    assert_with_module_error!(
+        ("one/two", "pub const answer = 42"),
+        "
+import one/two
+
+pub fn main(x) {
+  case x {
+    two.question -> True
+    _ -> False
+  }
+}
+",
+    );
+}
@@ -27,3 +27,22 @@ pub fn c() {
         ]
     );
 }
+
+#[test]
+fn echo_in_pipeline() {
+    assert_module_infer!(
+        "
+pub fn main() {
+  [1, 2, 3]
+  |> echo
+  |> length
+  |> echo
+}
+
+fn length(list: List(a)) -> Int {
+  todo
+}
+",
+        vec![("main", "fn() -> Int")]
+    );
+}
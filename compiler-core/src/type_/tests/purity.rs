@@ -0,0 +1,186 @@
+use ecow::EcoString;
+use itertools::Itertools;
+
+use crate::type_::Purity;
+
+use super::{compile_module, DependencyModule};
+
+fn purities(src: &str, deps: Vec<DependencyModule<'_>>) -> Vec<(EcoString, Purity)> {
+    compile_module("test_module", src, None, deps)
+        .expect("compile src")
+        .type_info
+        .values
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value.variant.purity()?)))
+        .sorted_by(|(one, _), (other, _)| one.cmp(other))
+        .collect_vec()
+}
+
+#[test]
+fn pure_function() {
+    let src = r#"
+pub fn add(x, y) { x + y }
+pub fn wrap(x) { Ok(#(x, [x])) }
+"#;
+    assert_eq!(
+        purities(src, vec![]),
+        vec![("add".into(), Purity::Pure), ("wrap".into(), Purity::Pure)]
+    );
+}
+
+#[test]
+fn external_function_is_impure() {
+    let src = r#"
+@external(erlang, "io", "format")
+pub fn print(x: String) -> Nil
+
+@external(erlang, "wibble", "wobble")
+pub fn with_gleam_implementation() -> Int {
+  1
+}
+"#;
+    assert_eq!(
+        purities(src, vec![]),
+        vec![
+            ("print".into(), Purity::Impure),
+            ("with_gleam_implementation".into(), Purity::Impure)
+        ]
+    );
+}
+
+#[test]
+fn functions_that_can_crash_are_impure() {
+    let src = r#"
+pub fn uses_panic() { panic }
+pub fn uses_todo() { todo }
+pub fn uses_let_assert(x) {
+  let assert Ok(y) = x
+  y
+}
+pub fn uses_echo(x) { echo x }
+"#;
+    assert_eq!(
+        purities(src, vec![]),
+        vec![
+            ("uses_echo".into(), Purity::Impure),
+            ("uses_let_assert".into(), Purity::Impure),
+            ("uses_panic".into(), Purity::Impure),
+            ("uses_todo".into(), Purity::Impure),
+        ]
+    );
+}
+
+#[test]
+fn calling_impure_function_is_impure() {
+    let src = r#"
+@external(erlang, "io", "format")
+fn print(x: String) -> Nil
+
+pub fn main() {
+  let x = "Hello"
+  x |> print
+}
+"#;
+    assert_eq!(
+        purities(src, vec![]),
+        vec![
+            ("main".into(), Purity::Impure),
+            ("print".into(), Purity::Impure)
+        ]
+    );
+}
+
+#[test]
+fn calling_imported_impure_function_is_impure() {
+    let src = r#"
+import io
+pub fn main() { io.println("Hello") }
+"#;
+    let io = r#"
+@external(erlang, "io", "format")
+pub fn println(x: String) -> Nil
+"#;
+    assert_eq!(
+        purities(src, vec![("thepackage", "io", io)]),
+        vec![("main".into(), Purity::Impure)]
+    );
+}
+
+#[test]
+fn calling_function_argument_has_unknown_purity() {
+    let src = r#"
+pub fn apply(f, x) { f(x) }
+pub fn double(x) { apply(fn(x) { x * 2 }, x) }
+"#;
+    assert_eq!(
+        purities(src, vec![]),
+        vec![
+            ("apply".into(), Purity::Unknown),
+            ("double".into(), Purity::Unknown)
+        ]
+    );
+}
+
+#[test]
+fn creating_anonymous_function_is_pure() {
+    let src = r#"
+pub fn later() { fn() { panic } }
+"#;
+    assert_eq!(purities(src, vec![]), vec![("later".into(), Purity::Pure)]);
+}
+
+#[test]
+fn recursive_function() {
+    let src = r#"
+pub fn length(xs, acc) {
+  case xs {
+    [] -> acc
+    [_, ..rest] -> length(rest, acc + 1)
+  }
+}
+"#;
+    assert_eq!(purities(src, vec![]), vec![("length".into(), Purity::Pure)]);
+}
+
+#[test]
+fn mutually_recursive_functions() {
+    let src = r#"
+pub fn is_even(x) {
+  case x {
+    0 -> True
+    _ -> is_odd(x - 1)
+  }
+}
+
+pub fn is_odd(x) {
+  case x {
+    0 -> False
+    _ if x < 0 -> panic
+    _ -> is_even(x - 1)
+  }
+}
+
+pub fn pure_even(x) {
+  case x {
+    0 -> True
+    _ -> pure_odd(x - 1)
+  }
+}
+
+pub fn pure_odd(x) {
+  case x {
+    0 -> False
+    _ -> pure_even(x - 1)
+  }
+}
+"#;
+    assert_eq!(
+        purities(src, vec![]),
+        vec![
+            ("is_even".into(), Purity::Impure),
+            ("is_odd".into(), Purity::Impure),
+            ("pure_even".into(), Purity::Pure),
+            ("pure_odd".into(), Purity::Pure),
+        ]
+    );
+}
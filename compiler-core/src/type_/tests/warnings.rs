@@ -1,6 +1,10 @@
 use super::*;
 use crate::ast::TodoKind;
-use crate::{assert_no_warnings, assert_warning, assert_warnings_with_imports};
+use crate::config::WarningsConfig;
+use crate::{
+    assert_js_no_warnings, assert_js_warning, assert_no_warnings, assert_no_warnings_with_config,
+    assert_warning, assert_warning_with_config, assert_warnings_with_imports,
+};
 
 #[test]
 fn unknown_label() {
@@ -20,7 +24,7 @@ fn todo_warning_test() {
             kind: TodoKind::Keyword,
             location: SrcSpan { start: 17, end: 21 },
             typ: Arc::new(Type::Var {
-                type_: Arc::new(RefCell::new(TypeVar::Link { type_: int() })),
+                type_: Arc::new(TypeVarCell::new(TypeVar::Link { type_: int() })),
             }),
         },
     );
@@ -245,7 +249,8 @@ fn unused_private_type_warnings_test() {
         Warning::UnusedType {
             name: "X".into(),
             location: SrcSpan { start: 0, end: 6 },
-            imported: false
+            imported: false,
+            definition_location: SrcSpan { start: 0, end: 6 },
         }
     );
 }
@@ -263,7 +268,8 @@ fn unused_private_type_warnings_test3() {
         Warning::UnusedType {
             name: "X".into(),
             location: SrcSpan { start: 0, end: 12 },
-            imported: false
+            imported: false,
+            definition_location: SrcSpan { start: 0, end: 12 },
         }
     );
 }
@@ -318,6 +324,7 @@ fn unused_private_fn_warnings_test() {
         Warning::UnusedPrivateFunction {
             name: "a".into(),
             location: SrcSpan { start: 0, end: 6 },
+            definition_location: SrcSpan { start: 0, end: 12 },
         }
     );
 }
@@ -339,6 +346,7 @@ fn unused_private_const_warnings_test() {
         Warning::UnusedPrivateModuleConstant {
             name: "a".into(),
             location: SrcSpan { start: 6, end: 7 },
+            definition_location: SrcSpan { start: 6, end: 11 },
         }
     );
 }
@@ -1108,6 +1116,7 @@ fn unused_alias_warning_test() {
         Warning::UnusedPrivateModuleConstant {
             name: "one".into(),
             location: SrcSpan { start: 61, end: 64 },
+            definition_location: SrcSpan { start: 61, end: 70 },
         },
         Warning::UnusedImportedModuleAlias {
             alias:"bar".into(),
@@ -1143,6 +1152,7 @@ fn unused_alias_for_duplicate_module_no_warning_for_alias_test() {
         Warning::UnusedPrivateModuleConstant {
             name: "one".into(),
             location: SrcSpan { start: 76, end: 79 },
+            definition_location: SrcSpan { start: 76, end: 89 },
         },
         Warning::UnusedImportedModule {
             name: "bar".into(),
@@ -1495,3 +1505,287 @@ pub fn main() {
 "
     );
 }
+
+fn shadowing() -> WarningsConfig {
+    WarningsConfig {
+        shadowing: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn shadowing_in_case_clause() {
+    assert_warning_with_config!(
+        shadowing(),
+        "
+pub fn main(x, y) {
+  case y {
+    Ok(x) -> x
+    Error(_) -> x
+  }
+}
+"
+    );
+}
+
+#[test]
+fn shadowing_in_anonymous_function() {
+    assert_warning_with_config!(
+        shadowing(),
+        "
+pub fn main() {
+  let x = 1
+  fn(x) { x + 1 }(x)
+}
+"
+    );
+}
+
+#[test]
+fn shadowing_in_block() {
+    assert_warning_with_config!(
+        shadowing(),
+        "
+pub fn main() {
+  let x = 1
+  let y = {
+    let x = x + 1
+    x
+  }
+  x + y
+}
+"
+    );
+}
+
+#[test]
+fn rebinding_in_same_scope_is_not_shadowing() {
+    assert_no_warnings_with_config!(
+        shadowing(),
+        "
+pub fn main(x) {
+  let x = x + 1
+  let x = x + 1
+  x
+}
+"
+    );
+}
+
+#[test]
+fn shadowing_variable_of_other_function_is_allowed() {
+    assert_no_warnings_with_config!(
+        shadowing(),
+        "
+fn other(x) {
+  x
+}
+
+pub fn main() {
+  let x = 1
+  other(x)
+}
+"
+    );
+}
+
+#[test]
+fn shadowing_warning_is_opt_in() {
+    assert_no_warnings!(
+        "
+pub fn main(x, y) {
+  case y {
+    Ok(x) -> x
+    Error(_) -> x
+  }
+}
+"
+    );
+}
+
+#[test]
+fn javascript_unsafe_int_literal() {
+    assert_js_warning!(
+        "
+pub fn main() {
+  9_007_199_254_740_992
+}
+"
+    );
+}
+
+#[test]
+fn javascript_unsafe_negative_int_literal() {
+    assert_js_warning!(
+        "
+pub fn main() {
+  -9_007_199_254_740_992
+}
+"
+    );
+}
+
+#[test]
+fn javascript_unsafe_int_constant() {
+    assert_js_warning!(
+        "
+pub const big = 0x20000000000000
+"
+    );
+}
+
+#[test]
+fn javascript_unsafe_int_pattern() {
+    assert_js_warning!(
+        "
+pub fn main(x) {
+  case x {
+    9_007_199_254_740_992 -> True
+    _ -> False
+  }
+}
+"
+    );
+}
+
+#[test]
+fn javascript_unsafe_int_arithmetic() {
+    assert_js_warning!(
+        "
+pub fn main() {
+  9_007_199_254_740_991 + 1
+}
+"
+    );
+}
+
+#[test]
+fn javascript_unsafe_int_arithmetic_is_only_reported_once() {
+    assert_js_warning!(
+        "
+pub fn main() {
+  1_000_000_000 * 1_000_000_000 * 2 + 1
+}
+"
+    );
+}
+
+#[test]
+fn javascript_safe_int_literals() {
+    assert_js_no_warnings!(
+        "
+pub fn main() {
+  #(9_007_199_254_740_991, -9_007_199_254_740_991, 9_007_199_254_740_991 - 1)
+}
+"
+    );
+}
+
+#[test]
+fn unsafe_int_literal_is_allowed_on_erlang() {
+    assert_no_warnings!(
+        "
+pub fn main() {
+  9_007_199_254_740_992 * 10
+}
+"
+    );
+}
+
+#[test]
+fn discarded_must_use_call() {
+    assert_warning!(
+        "
+@must_use
+fn new() -> Int { 1 }
+
+pub fn main() {
+  new()
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn discarded_must_use_pipeline() {
+    assert_warning!(
+        "
+@must_use
+fn add(builder: List(Int), item: Int) -> List(Int) { [item, ..builder] }
+
+pub fn main() {
+  [] |> add(1) |> add(2)
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn discarded_imported_must_use_call() {
+    assert_warning!(
+        (
+            "builder",
+            "@must_use
+pub fn new() -> Int { 1 }"
+        ),
+        "
+import builder
+
+pub fn main() {
+  builder.new()
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn discarded_unqualified_imported_must_use_call() {
+    assert_warning!(
+        (
+            "builder",
+            "@must_use
+pub fn new() -> Int { 1 }"
+        ),
+        "
+import builder.{new}
+
+pub fn main() {
+  new()
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn discarded_must_use_result_warns_once() {
+    assert_warning!(
+        "
+@must_use
+fn parse() -> Result(Int, Nil) { Ok(1) }
+
+pub fn main() {
+  parse()
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn used_must_use_call() {
+    assert_no_warnings!(
+        "
+@must_use
+fn new() -> Int { 1 }
+
+pub fn main() {
+  let _ = new()
+  new()
+}
+"
+    );
+}
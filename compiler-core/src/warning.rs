@@ -1,8 +1,9 @@
 use crate::{
-    ast::TodoKind,
+    ast::{SrcSpan, TodoKind},
+    config::{WarningLevel, WarningsConfig},
     diagnostic::{self, Diagnostic, Location},
     error::wrap,
-    type_::{self, pretty::Printer},
+    type_::{self, error::PublicDefinitionKind, pretty::Printer},
 };
 use camino::Utf8PathBuf;
 use debug_ignore::DebugIgnore;
@@ -69,6 +70,12 @@ pub struct WarningEmitter {
     /// package only, the count is reset back to zero after the dependencies are
     /// compiled.
     count: Arc<AtomicUsize>,
+    /// The number of warnings emitted that are of a category set to `deny`,
+    /// which is reset along with the count of all warnings.
+    denied_count: Arc<AtomicUsize>,
+    /// The levels of the categories of warnings, as configured in the
+    /// `[warnings]` section of `gleam.toml`.
+    config: Arc<WarningsConfig>,
     emitter: DebugIgnore<Arc<dyn WarningEmitterIO>>,
 }
 
@@ -76,23 +83,53 @@ impl WarningEmitter {
     pub fn new(emitter: Arc<dyn WarningEmitterIO>) -> Self {
         Self {
             count: Arc::new(AtomicUsize::new(0)),
+            denied_count: Arc::new(AtomicUsize::new(0)),
+            config: Arc::new(WarningsConfig::default()),
             emitter: DebugIgnore(emitter),
         }
     }
 
+    /// An emitter sharing the counts and output of this one, which reports
+    /// the warnings at the levels set for their categories in the config.
+    pub fn with_levels(&self, config: WarningsConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            ..self.clone()
+        }
+    }
+
     pub fn null() -> Self {
         Self::new(Arc::new(NullWarningEmitterIO))
     }
 
     pub fn reset_count(&self) {
         self.count.store(0, Ordering::Relaxed);
+        self.denied_count.store(0, Ordering::Relaxed);
     }
 
     pub fn count(&self) -> usize {
         self.count.load(Ordering::Relaxed)
     }
 
+    /// The number of warnings emitted that are of a category set to `deny`.
+    pub fn denied_count(&self) -> usize {
+        self.denied_count.load(Ordering::Relaxed)
+    }
+
     pub fn emit(&self, warning: Warning) {
+        let level = warning
+            .category()
+            .map_or(WarningLevel::Warn, |category| self.config.level(category));
+        let warning = match level {
+            WarningLevel::Allow => return,
+            WarningLevel::Warn => warning,
+            WarningLevel::Deny => {
+                _ = self.denied_count.fetch_add(1, Ordering::Relaxed);
+                Warning::Denied {
+                    warning: Box::new(warning),
+                }
+            }
+        };
         _ = self.count.fetch_add(1, Ordering::Relaxed);
         self.emitter.emit_warning(warning);
     }
@@ -147,10 +184,119 @@ pub enum Warning {
     InvalidSource {
         path: Utf8PathBuf,
     },
+    /// The warnings of a dependency package that were not shown, as set with
+    /// the `dependencies` key of the `[warnings]` section of `gleam.toml`.
+    DependencyWarnings {
+        package: EcoString,
+        count: usize,
+    },
+    /// A warning of a module that was loaded from the cache rather than type
+    /// checked again, stored as the diagnostic it was shown as when the module
+    /// was type checked.
+    Cached {
+        diagnostic: Diagnostic,
+    },
+    /// A warning of a category set to `deny` in the `[warnings]` section of
+    /// `gleam.toml`, which is reported as an error.
+    Denied {
+        warning: Box<Warning>,
+    },
 }
 
 impl Warning {
+    /// The stable code identifying this kind of warning. Warnings loaded from
+    /// the cache have the code they were shown with, which they may not have
+    /// if they were cached by an older version of the compiler.
+    pub fn code(&self) -> Option<diagnostic::Code> {
+        match self {
+            Warning::Type { warning, .. } => Some(warning.code()),
+            Warning::InvalidSource { .. } => Some(diagnostic::Code::Warning(1)),
+            Warning::DependencyWarnings { .. } => Some(diagnostic::Code::Warning(2)),
+            Warning::Cached { diagnostic } => diagnostic.code,
+            Warning::Denied { warning } => warning.code(),
+        }
+    }
+
+    /// The category of the warning that its level can be configured with, if
+    /// it has one. The category is found from the code so that it is the same
+    /// for warnings loaded from the cache.
+    pub fn category(&self) -> Option<WarningCategory> {
+        match self {
+            Warning::Denied { .. } => None,
+            Warning::Type { .. }
+            | Warning::InvalidSource { .. }
+            | Warning::DependencyWarnings { .. }
+            | Warning::Cached { .. } => self.code().and_then(WarningCategory::of_code),
+        }
+    }
+
+    /// The changes to the module that fix the problem the warning reports,
+    /// for the warnings that can be fixed automatically.
+    pub fn fixes(&self) -> Vec<diagnostic::Fix> {
+        let (warning, src) = match self {
+            Warning::Type { warning, src, .. } => (warning, src),
+            Warning::Denied { warning } => return warning.fixes(),
+            Warning::InvalidSource { .. }
+            | Warning::DependencyWarnings { .. }
+            | Warning::Cached { .. } => return vec![],
+        };
+        let (title, span) = match warning {
+            type_::Warning::UnusedImportedModule { location, .. } => {
+                ("Remove unused import".into(), *location)
+            }
+            type_::Warning::UnusedImportedModuleAlias { location, .. } => {
+                ("Remove unused alias".into(), *location)
+            }
+            type_::Warning::UnusedPrivateFunction {
+                definition_location,
+                ..
+            } => (
+                "Remove unused function".into(),
+                definition_lines(src, *definition_location),
+            ),
+            type_::Warning::UnusedPrivateModuleConstant {
+                definition_location,
+                ..
+            } => (
+                "Remove unused constant".into(),
+                definition_lines(src, *definition_location),
+            ),
+            type_::Warning::UnusedType {
+                imported: false,
+                definition_location,
+                ..
+            } => (
+                "Remove unused type".into(),
+                definition_lines(src, *definition_location),
+            ),
+            // Removing a constructor could leave its type without any, so
+            // only whole definitions are removed.
+            type_::Warning::UnusedPublicDefinition {
+                kind,
+                definition_location,
+                ..
+            }
+            | type_::Warning::UnusedInternalDefinition {
+                kind,
+                definition_location,
+                ..
+            } if *kind != PublicDefinitionKind::Constructor => (
+                format!("Remove unused {}", kind.as_str()),
+                definition_lines(src, *definition_location),
+            ),
+            _ => return vec![],
+        };
+        vec![diagnostic::Fix {
+            title,
+            edits: vec![diagnostic::Edit {
+                span,
+                replacement: String::new(),
+            }],
+        }]
+    }
+
     pub fn to_diagnostic(&self) -> Diagnostic {
+        let code = self.code();
         match self {
             Warning::InvalidSource { path } => Diagnostic {
                 title: "Invalid module name".into(),
@@ -159,11 +305,32 @@ Module names must begin with a lowercase letter and contain
 only lowercase alphanumeric characters or underscores."
                     .into(),
                 level: diagnostic::Level::Warning,
+                code,
                 location: None,
                 hint: Some(format!(
                     "Rename `{path}` to be valid, or remove this file from the project source."
                 )),
             },
+            Warning::DependencyWarnings { package, count } => Diagnostic {
+                title: "Warnings in dependency".into(),
+                text: match count {
+                    1 => format!("The `{package}` package has 1 warning."),
+                    _ => format!("The `{package}` package has {count} warnings."),
+                },
+                level: diagnostic::Level::Warning,
+                code,
+                location: None,
+                hint: Some(
+                    "Set `dependencies = \"show\"` in the `[warnings]` section of \
+`gleam.toml` to see them."
+                        .into(),
+                ),
+            },
+            Warning::Cached { diagnostic } => diagnostic.clone(),
+            Warning::Denied { warning } => Diagnostic {
+                level: diagnostic::Level::Error,
+                ..warning.to_diagnostic()
+            },
             Self::Type { path, warning, src } => match warning {
                 type_::Warning::Todo {
                     kind,
@@ -200,6 +367,7 @@ expression.",
                         title,
                         text,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             path: path.to_path_buf(),
                             src: src.clone(),
@@ -220,6 +388,7 @@ expression.",
                         "If you are sure you don't need it you can assign it to `_`.".into(),
                     ),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -236,6 +405,7 @@ expression.",
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -254,6 +424,7 @@ expression.",
                         "Add some fields to change or replace it with the record itself.".into(),
                     ),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -270,6 +441,7 @@ expression.",
                     text: "".into(),
                     hint: Some("It is better style to use the record creation syntax.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -299,6 +471,7 @@ expression.",
                         text: "".into(),
                         hint: Some("You can safely remove it.".into()),
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -329,6 +502,7 @@ expression.",
                         text: "".into(),
                         hint: Some("You can safely remove it.".into()),
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -346,6 +520,7 @@ expression.",
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -374,6 +549,7 @@ Hint: You can safely remove it.
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -391,6 +567,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -407,6 +584,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -423,6 +601,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -439,6 +618,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some(format!("You can ignore it with an underscore: `_{name}`.")),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -454,6 +634,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove this.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -469,6 +650,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove this.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -498,6 +680,7 @@ need to know if the list is empty or not.
                         text,
                         hint,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -531,6 +714,7 @@ Run this command to add it to your dependencies:
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -566,6 +750,7 @@ Run this command to add it to your dependencies:
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -588,6 +773,7 @@ the same values.\n"
                         text,
                         hint: Some("It can be safely removed.".into()),
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -608,6 +794,7 @@ the same values.\n"
                             .into(),
                     ),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -624,6 +811,7 @@ the same values.\n"
                     text: "This type has no constructors so making it opaque is redundant.".into(),
                     hint: Some("Remove the `opaque` qualifier from the type definition.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -635,11 +823,34 @@ the same values.\n"
                     }),
                 },
 
+                type_::Warning::UnusedMustUseValue { location, name } => Diagnostic {
+                    title: "Unused must use value".into(),
+                    text: wrap(&format!(
+                        "The function `{name}` is marked with `@must_use`, so its \
+return value is expected to be used."
+                    )),
+                    hint: Some(
+                        "If you are sure you don't need it you can assign it to `_`.".into(),
+                    ),
+                    level: diagnostic::Level::Warning,
+                    code,
+                    location: Some(Location {
+                        path: path.to_path_buf(),
+                        src: src.clone(),
+                        label: diagnostic::Label {
+                            text: Some("This value is never used".into()),
+                            span: *location,
+                        },
+                        extra_labels: Vec::new(),
+                    }),
+                },
+
                 type_::Warning::UnusedValue { location } => Diagnostic {
                     title: "Unused value".into(),
                     text: "".into(),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -673,6 +884,7 @@ hidden from the package's documentation.",
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             label: diagnostic::Label {
                                 text: None,
@@ -690,6 +902,7 @@ hidden from the package's documentation.",
                         .into(),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         label: diagnostic::Label {
                             text: Some("You can remove this".into()),
@@ -700,6 +913,132 @@ hidden from the package's documentation.",
                         extra_labels: vec![],
                     }),
                 },
+                type_::Warning::ShadowedVariable {
+                    location,
+                    name,
+                    shadowed_location,
+                } => Diagnostic {
+                    title: "Shadowed variable".into(),
+                    text: format!(
+                        "This definition of `{name}` shadows a variable with the same name
+defined in an enclosing scope, so the outer variable can no longer be used here."
+                    ),
+                    hint: Some("You can give one of the variables a different name.".into()),
+                    level: diagnostic::Level::Warning,
+                    code,
+                    location: Some(Location {
+                        label: diagnostic::Label {
+                            text: Some(format!("This shadows `{name}`")),
+                            span: *location,
+                        },
+                        path: path.clone(),
+                        src: src.clone(),
+                        extra_labels: vec![diagnostic::Label {
+                            text: Some("Originally defined here".into()),
+                            span: *shadowed_location,
+                        }],
+                    }),
+                },
+
+                type_::Warning::UnusedPublicDefinition {
+                    location,
+                    name,
+                    kind,
+                    definition_location: _,
+                } => {
+                    let kind = kind.as_str();
+                    Diagnostic {
+                        title: format!("Unused public {kind}"),
+                        text: format!(
+                            "The public {kind} `{name}` can't be reached from any `main` function
+of this application."
+                        ),
+                        hint: Some("You can safely remove it.".into()),
+                        level: diagnostic::Level::Warning,
+                        code,
+                        location: Some(Location {
+                            src: src.clone(),
+                            path: path.to_path_buf(),
+                            label: diagnostic::Label {
+                                text: Some(format!("This {kind} is never used")),
+                                span: *location,
+                            },
+                            extra_labels: Vec::new(),
+                        }),
+                    }
+                }
+
+                type_::Warning::UnusedInternalDefinition {
+                    location,
+                    name,
+                    kind,
+                    ..
+                } => {
+                    let kind = kind.as_str();
+                    Diagnostic {
+                        title: format!("Unused internal {kind}"),
+                        text: format!(
+                            "The public {kind} `{name}` of this internal module isn't used
+by any other module of this package."
+                        ),
+                        hint: Some("You can safely remove it.".into()),
+                        level: diagnostic::Level::Warning,
+                        code,
+                        location: Some(Location {
+                            src: src.clone(),
+                            path: path.to_path_buf(),
+                            label: diagnostic::Label {
+                                text: Some(format!("This {kind} is never used")),
+                                span: *location,
+                            },
+                            extra_labels: Vec::new(),
+                        }),
+                    }
+                }
+
+                type_::Warning::JavaScriptUnsafeInt { location } => Diagnostic {
+                    title: "Int is outside the JavaScript safe integer range".into(),
+                    text: "This integer value is too large to be represented exactly by
+JavaScript's number type, so precision will silently be lost when it is
+used on the JavaScript target.
+
+To avoid this warning integer values must be between
+-9_007_199_254_740_991 and 9_007_199_254_740_991, the range of
+JavaScript's `Number.MIN_SAFE_INTEGER` and `Number.MAX_SAFE_INTEGER`."
+                        .into(),
+                    hint: None,
+                    level: diagnostic::Level::Warning,
+                    code,
+                    location: Some(Location {
+                        src: src.clone(),
+                        path: path.to_path_buf(),
+                        label: diagnostic::Label {
+                            text: Some("This is not a safe integer on JavaScript".into()),
+                            span: *location,
+                        },
+                        extra_labels: Vec::new(),
+                    }),
+                },
+
+                type_::Warning::Lint { warning } => Diagnostic {
+                    title: warning.title.to_string(),
+                    text: format!(
+                        "{}\n\nThis warning was emitted by the `{}` lint.",
+                        warning.text, warning.lint
+                    ),
+                    hint: warning.hint.as_ref().map(|hint| hint.to_string()),
+                    level: diagnostic::Level::Warning,
+                    code,
+                    location: Some(Location {
+                        src: src.clone(),
+                        path: path.to_path_buf(),
+                        label: diagnostic::Label {
+                            text: Some(warning.label.to_string()),
+                            span: warning.location,
+                        },
+                        extra_labels: Vec::new(),
+                    }),
+                },
             },
         }
     }
@@ -717,3 +1056,233 @@ hidden from the package's documentation.",
         String::from_utf8(nocolor.into_inner()).expect("Warning printing produced invalid utf8")
     }
 }
+
+/// The span to remove to delete a definition: the whole lines it is written
+/// on, along with the documentation comments and attributes above it.
+fn definition_lines(src: &str, definition: SrcSpan) -> SrcSpan {
+    let line_start = |index: usize| {
+        src.get(..index)
+            .and_then(|before| before.rfind('\n'))
+            .map_or(0, |newline| newline + 1)
+    };
+
+    let mut start = line_start(definition.start as usize);
+    while start > 0 {
+        let previous = line_start(start - 1);
+        let line = src.get(previous..start).unwrap_or_default().trim_start();
+        if !line.starts_with("///") && !line.starts_with('@') {
+            break;
+        }
+        start = previous;
+    }
+
+    let end = definition.end as usize;
+    let end = match src.get(end..).and_then(|after| after.find('\n')) {
+        Some(newline) => end + newline + 1,
+        None => src.len(),
+    };
+    SrcSpan::new(start as u32, end as u32)
+}
+
+/// A category of warnings that can be allowed, warned about, or denied in the
+/// `[warnings]` section of `gleam.toml`:
+///
+/// ```toml
+/// [warnings]
+/// unused_imports = "deny"
+/// todo = "allow"
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningCategory {
+    /// Uses of `todo`.
+    Todo,
+    /// Imported modules and values that are not used.
+    UnusedImports,
+    /// Private functions, constants, types, and constructors that are not
+    /// used.
+    UnusedDefinitions,
+    /// Variables that are not used.
+    UnusedVariables,
+    /// Values that are created and then discarded without being used.
+    UnusedValues,
+    /// Uses of deprecated values and types.
+    Deprecated,
+    /// Case clauses that can never be matched.
+    UnreachablePatterns,
+    /// Code that does nothing or could be written more simply, such as
+    /// double negations and record updates of all fields.
+    RedundantCode,
+}
+
+impl WarningCategory {
+    /// The category of the warnings with the code, if they have one.
+    pub fn of_code(code: diagnostic::Code) -> Option<Self> {
+        let diagnostic::Code::Warning(number) = code else {
+            return None;
+        };
+        match number {
+            100 => Some(Self::Todo),
+            108..=110 => Some(Self::UnusedImports),
+            106 | 107 | 111 | 112 => Some(Self::UnusedDefinitions),
+            113 => Some(Self::UnusedVariables),
+            101..=103 | 128 => Some(Self::UnusedValues),
+            118 => Some(Self::Deprecated),
+            119 => Some(Self::UnreachablePatterns),
+            104 | 105 | 114..=116 | 120 | 123 => Some(Self::RedundantCode),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ast::SrcSpan, config::WarningLevel};
+
+    fn unused_import() -> Warning {
+        Warning::Type {
+            path: Utf8PathBuf::from("src/wibble.gleam"),
+            src: "import wobble".into(),
+            warning: type_::Warning::UnusedImportedModule {
+                location: SrcSpan::new(0, 13),
+                name: "wobble".into(),
+            },
+        }
+    }
+
+    fn emitter(
+        category: WarningCategory,
+        level: WarningLevel,
+    ) -> (WarningEmitter, Arc<VectorWarningEmitterIO>) {
+        let (emitter, emitted) = WarningEmitter::vector();
+        let mut config = WarningsConfig::default();
+        _ = config.levels.insert(category, level);
+        (emitter.with_levels(config), emitted)
+    }
+
+    #[test]
+    fn cached_warnings_without_a_code_have_no_category() {
+        let warning = Warning::Cached {
+            diagnostic: Diagnostic {
+                title: "Unused variable".into(),
+                text: "".into(),
+                level: diagnostic::Level::Warning,
+                location: None,
+                hint: None,
+                code: None,
+            },
+        };
+        assert_eq!(warning.code(), None);
+        assert_eq!(warning.category(), None);
+    }
+
+    #[test]
+    fn allowed_warnings_are_not_emitted() {
+        let (emitter, emitted) = emitter(WarningCategory::UnusedImports, WarningLevel::Allow);
+        emitter.emit(unused_import());
+        assert_eq!(emitter.count(), 0);
+        assert_eq!(emitted.take(), vec![]);
+    }
+
+    #[test]
+    fn denied_warnings_are_errors() {
+        let (emitter, emitted) = emitter(WarningCategory::UnusedImports, WarningLevel::Deny);
+        emitter.emit(unused_import());
+        assert_eq!(emitter.count(), 1);
+        assert_eq!(emitter.denied_count(), 1);
+
+        let warnings = emitted.take();
+        assert_eq!(
+            warnings,
+            vec![Warning::Denied {
+                warning: Box::new(unused_import())
+            }]
+        );
+        let diagnostic = warnings.first().unwrap().to_diagnostic();
+        assert_eq!(diagnostic.level, diagnostic::Level::Error);
+        assert_eq!(diagnostic.code, Some(diagnostic::Code::Warning(109)));
+    }
+
+    #[test]
+    fn warnings_of_other_categories_are_warned_about() {
+        let (emitter, emitted) = emitter(WarningCategory::Todo, WarningLevel::Deny);
+        emitter.emit(unused_import());
+        assert_eq!(emitter.count(), 1);
+        assert_eq!(emitter.denied_count(), 0);
+        assert_eq!(emitted.take(), vec![unused_import()]);
+    }
+
+    #[test]
+    fn cached_warnings_use_the_level_of_their_category() {
+        let (emitter, emitted) = emitter(WarningCategory::UnusedImports, WarningLevel::Allow);
+        emitter.emit(Warning::Cached {
+            diagnostic: unused_import().to_diagnostic(),
+        });
+        assert_eq!(emitted.take(), vec![]);
+    }
+
+    fn removed_spans(warning: &Warning) -> Vec<(String, SrcSpan)> {
+        warning
+            .fixes()
+            .into_iter()
+            .flat_map(|fix| {
+                let title = fix.title;
+                fix.edits
+                    .into_iter()
+                    .map(move |edit| (title.clone(), edit.span))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unused_private_function_is_removed_with_its_documentation() {
+        let src = "pub fn main() {\n  Nil\n}\n\n/// Docs\n@deprecated(\"Old\")\nfn wibble() {\n  Nil\n}\n";
+        let warning = Warning::Type {
+            path: Utf8PathBuf::from("src/wibble.gleam"),
+            src: src.into(),
+            warning: type_::Warning::UnusedPrivateFunction {
+                location: SrcSpan::new(53, 64),
+                name: "wibble".into(),
+                definition_location: SrcSpan::new(53, 74),
+            },
+        };
+        assert_eq!(
+            removed_spans(&warning),
+            vec![("Remove unused function".into(), SrcSpan::new(25, 75))]
+        );
+    }
+
+    #[test]
+    fn unused_private_constant_is_removed_with_its_keyword() {
+        let src = "const wibble = 1";
+        let warning = Warning::Type {
+            path: Utf8PathBuf::from("src/wibble.gleam"),
+            src: src.into(),
+            warning: type_::Warning::UnusedPrivateModuleConstant {
+                location: SrcSpan::new(6, 12),
+                name: "wibble".into(),
+                definition_location: SrcSpan::new(6, 16),
+            },
+        };
+        assert_eq!(
+            removed_spans(&warning),
+            vec![("Remove unused constant".into(), SrcSpan::new(0, 16))]
+        );
+    }
+
+    #[test]
+    fn unused_imported_types_are_not_removed() {
+        let warning = Warning::Type {
+            path: Utf8PathBuf::from("src/wibble.gleam"),
+            src: "import wobble.{type Wobble}".into(),
+            warning: type_::Warning::UnusedType {
+                location: SrcSpan::new(15, 26),
+                imported: true,
+                name: "Wobble".into(),
+                definition_location: SrcSpan::new(15, 26),
+            },
+        };
+        assert_eq!(removed_spans(&warning), vec![]);
+    }
+}
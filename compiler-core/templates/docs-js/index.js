@@ -168,7 +168,7 @@ window.Gleam = (function () {
     else el.addEventListener(type, handler);
   };
 
-  const searchLoaded = function (index, docs) {
+  const searchLoaded = function (terms, docs) {
     const preview_words_after = 10;
     const preview_words_before = 5;
     const previews = 3;
@@ -209,32 +209,7 @@ window.Gleam = (function () {
         return;
       }
 
-      let results = index.query(function (query) {
-        const tokens = lunr.tokenizer(input);
-        query.term(tokens, {
-          boost: 10,
-        });
-        query.term(tokens, {
-          boost: 5,
-          wildcard: lunr.Query.wildcard.TRAILING,
-        });
-        query.term(tokens, {
-          wildcard: lunr.Query.wildcard.LEADING | lunr.Query.wildcard.TRAILING,
-        });
-      });
-
-      if (results.length == 0 && input.length > 2) {
-        const tokens = lunr.tokenizer(input).filter(function (token, i) {
-          return token.str.length < 20;
-        });
-        if (tokens.length > 0) {
-          results = index.query(function (query) {
-            query.term(tokens, {
-              editDistance: Math.round(Math.sqrt(input.length / 2 - 1)),
-            });
-          });
-        }
-      }
+      const results = search(terms, docs, input);
 
       if (results.length == 0) {
         const noResultsDiv = document.createElement("div");
@@ -542,28 +517,106 @@ window.Gleam = (function () {
     });
   };
 
-  self.initSearch = function initSeach(docs) {
-    // enable support for hyphenated search words
-    lunr.tokenizer.separator = /[\s/]+/;
-
-    const index = lunr(function () {
-      this.ref("id");
-      this.field("title", { boost: 200 });
-      this.field("content", { boost: 2 });
-      this.field("url");
-      this.metadataWhitelist = ["position"];
-
-      for (let [i, entry] of docs.entries()) {
-        this.add({
-          id: i,
-          title: entry.title,
-          content: entry.content,
-          url: `${window.unnest}/${entry.url}`,
-        });
+  const fieldBoosts = { title: 200, content: 2 };
+
+  // Splits text into lowercase words the same way the search index was built
+  // by `gleam docs build`.
+  const searchWords = function (text) {
+    return text.toLowerCase().match(/[\p{L}\p{N}_]+/gu) || [];
+  };
+
+  // The number of typos a word may have and still match a term.
+  const allowedTypos = function (word) {
+    if (word.length < 4) return 0;
+    if (word.length < 8) return 1;
+    return 2;
+  };
+
+  // The number of insertions, deletions, substitutions, and swaps of
+  // adjacent letters needed to turn one string into another, or `limit + 1`
+  // if it is greater than `limit`.
+  const editDistance = function (a, b, limit) {
+    if (Math.abs(a.length - b.length) > limit) return limit + 1;
+    let beforePrevious = [];
+    let previous = Array.from({ length: b.length + 1 }, (_, i) => i);
+    for (let i = 1; i <= a.length; i++) {
+      const current = [i];
+      let rowMinimum = i;
+      for (let j = 1; j <= b.length; j++) {
+        const cost = a[i - 1] === b[j - 1] ? 0 : 1;
+        current[j] = Math.min(
+          previous[j] + 1,
+          current[j - 1] + 1,
+          previous[j - 1] + cost
+        );
+        if (i > 1 && j > 1 && a[i - 1] === b[j - 2] && a[i - 2] === b[j - 1]) {
+          current[j] = Math.min(current[j], beforePrevious[j - 2] + 1);
+        }
+        rowMinimum = Math.min(rowMinimum, current[j]);
       }
+      if (rowMinimum > limit) return limit + 1;
+      beforePrevious = previous;
+      previous = current;
+    }
+    return previous[b.length];
+  };
+
+  // How well a term from the index matches a searched word, or 0 if it
+  // doesn't match.
+  const termWeight = function (term, word) {
+    if (term === word) return 10;
+    if (term.startsWith(word)) return 5;
+    if (word.length > 2 && term.includes(word)) return 1;
+    const typos = allowedTypos(word);
+    if (typos > 0) {
+      const distance = editDistance(word, term, typos);
+      if (distance <= typos) return 2 / (distance + 1);
+      // Also allow typos in words that are the start of a term.
+      const prefix = term.slice(0, word.length);
+      if (editDistance(word, prefix, typos) <= typos) return 0.5;
+    }
+    return 0;
+  };
+
+  // Finds the pages matching the input, giving results in the same shape as
+  // lunr so they can be shown the same way.
+  const search = function (terms, docs, input) {
+    const words = searchWords(input);
+    const matches = new Map();
+    for (const word of words) {
+      for (const term in terms) {
+        const weight = termWeight(term, word);
+        if (weight === 0) continue;
+        const postings = terms[term];
+        const idf = Math.log(1 + docs.length / postings.length);
+        for (const [ref, field, positions] of postings) {
+          let match = matches.get(ref);
+          if (!match) {
+            match = {
+              ref,
+              score: 0,
+              words: new Set(),
+              matchData: { metadata: {} },
+            };
+            matches.set(ref, match);
+          }
+          const frequency = 1 + Math.log(positions.length);
+          match.score += weight * fieldBoosts[field] * idf * frequency;
+          match.words.add(word);
+          const metadata = (match.matchData.metadata[term] ||= {});
+          const fieldData = (metadata[field] ||= { position: [] });
+          fieldData.position.push(...positions);
+        }
+      }
+    }
+    // Pages that match more of the searched words come first.
+    return Array.from(matches.values()).sort(function (a, b) {
+      return b.words.size - a.words.size || b.score - a.score;
     });
+  };
 
-    searchLoaded(index, docs);
+  self.initSearch = function initSearch(data) {
+    searchLoaded(data.terms, data.docs);
   };
 
   const init = function () {
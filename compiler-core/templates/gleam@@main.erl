@@ -1,14 +1,20 @@
 -module('{{ application }}@@main').
 
--export([run/1]).
+-export([run/1, run/3]).
 
 run(Module) ->
+    run(Module, main, false).
+
+run(Module, Function, TakesArguments) ->
     io:setopts(standard_io, [binary, {encoding, utf8}]),
     io:setopts(standard_error, [{encoding, utf8}]),
     try
         {ok, _} = application:ensure_all_started('{{ application }}'),
         erlang:process_flag(trap_exit, false),
-        Module:main(),
+        case TakesArguments of
+            true -> Module:Function(arguments());
+            false -> Module:Function()
+        end,
         erlang:halt(0)
     catch
         Class:Reason:StackTrace ->
@@ -16,6 +22,9 @@ run(Module) ->
             erlang:halt(127, [{flush, true}])
     end.
 
+arguments() ->
+    [unicode:characters_to_binary(Argument) || Argument <- init:get_plain_arguments()].
+
 print_error(Class, Reason, StackTrace) -> 
     E = erl_error:format_exception(
         1, Class, Reason, StackTrace, fun stack_filter/3, 
@@ -358,3 +358,45 @@ export function makeError(variant, module, line, fn, message, extra) {
   for (let k in extra) error[k] = extra[k];
   return error;
 }
+
+// @internal
+export function echo(value, module, line) {
+  let string = `${module}:${line}\n${inspect(value)}\n`;
+  if (globalThis.process?.stderr?.write) {
+    globalThis.process.stderr.write(string);
+  } else if (globalThis.Deno) {
+    globalThis.Deno.stderr.writeSync(new TextEncoder().encode(string));
+  } else {
+    console.log(string.trimEnd());
+  }
+  return value;
+}
+
+// @internal
+export function inspect(value) {
+  if (value === undefined) return "Nil";
+  if (value === true) return "True";
+  if (value === false) return "False";
+  if (typeof value === "string") return JSON.stringify(value);
+  if (typeof value === "number") return value.toString();
+  if (typeof value === "function") return "//fn(...) { ... }";
+  if (Array.isArray(value)) return `#(${value.map(inspect).join(", ")})`;
+  if (value instanceof List) {
+    return `[${value.toArray().map(inspect).join(", ")}]`;
+  }
+  if (value instanceof BitArray) {
+    return `<<${Array.from(value.buffer).join(", ")}>>`;
+  }
+  if (value instanceof UtfCodepoint) {
+    return `//utfcodepoint(${String.fromCodePoint(value.value)})`;
+  }
+  if (value instanceof CustomType) {
+    let fields = Object.keys(value).map((label) => {
+      let field = inspect(value[label]);
+      return isNaN(parseInt(label)) ? `${label}: ${field}` : field;
+    });
+    let name = value.constructor.name;
+    return fields.length === 0 ? name : `${name}(${fields.join(", ")})`;
+  }
+  return `//js(${String(value)})`;
+}
@@ -6,17 +6,21 @@ mod wasm_filesystem;
 use camino::Utf8PathBuf;
 use gleam_core::{
     build::{
-        Mode, NullTelemetry, PackageCompiler, StaleTracker, Target, TargetCodegenConfiguration,
+        Mode, Module, NullTelemetry, PackageCompiler, StaleTracker, Target,
+        TargetCodegenConfiguration,
     },
     config::PackageConfig,
+    diagnostic::json::JsonDiagnostic,
     io::{FileSystemReader, FileSystemWriter},
+    metadata::ModuleDecoder,
     uid::UniqueIdGenerator,
     warning::{VectorWarningEmitterIO, WarningEmitter},
     Error,
 };
 use hexpm::version::Version;
 use im::HashMap;
-use std::{cell::RefCell, sync::Arc};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::{cell::RefCell, collections::BTreeMap, fmt, sync::Arc};
 use wasm_filesystem::WasmFileSystem;
 
 use wasm_bindgen::prelude::*;
@@ -113,16 +117,47 @@ pub fn read_file_bytes(project_id: usize, path: &str) -> Option<Vec<u8>> {
 ///
 #[wasm_bindgen]
 pub fn compile_package(project_id: usize, target: &str) -> Result<(), String> {
-    let target = match target.to_lowercase().as_str() {
-        "erl" | "erlang" => Target::Erlang,
-        "js" | "javascript" => Target::JavaScript,
-        _ => {
-            let msg = format!("Unknown target `{target}`, expected `erlang` or `javascript`");
-            return Err(msg);
+    let target = parse_target(target)?;
+    _ = do_compile_package(get_project(project_id), target).map_err(|e| e.pretty_string())?;
+    Ok(())
+}
+
+/// Compile a project with any number of modules. The files of the project are
+/// given as an object mapping paths to their contents, either strings or
+/// `Uint8Array`s, and replace the files of the virtual file system:
+///
+/// - Gleam modules go in `/src`, such as `/src/app/router.gleam`.
+/// - The `.cache` files of the modules of precompiled dependencies go in
+///   `/lib`, such as `/lib/gleam_stdlib/_gleam_artefacts/gleam@list.cache`,
+///   and can be imported by the modules of the project.
+///
+/// Returns an object with the generated code of each module of the project
+/// by module name in `modules`, and the errors and warnings in `diagnostics`
+/// in the same format as `gleam build --message-format=json`.
+///
+#[wasm_bindgen]
+pub fn compile_project(project_id: usize, target: &str, files: JsValue) -> Result<JsValue, String> {
+    let target = parse_target(target)?;
+    let files: BTreeMap<String, FileContents> =
+        serde_wasm_bindgen::from_value(files).map_err(|e| e.to_string())?;
+
+    let project = get_project(project_id);
+    project.fs.reset();
+    project.warnings.reset();
+    for (path, contents) in files {
+        let path = Utf8PathBuf::from(path);
+        match contents {
+            FileContents::Text(text) => project.fs.write(&path, &text),
+            FileContents::Bytes(bytes) => project.fs.write_bytes(&path, &bytes),
         }
-    };
+        .expect("writing file")
+    }
 
-    do_compile_package(get_project(project_id), target).map_err(|e| e.pretty_string())
+    let compiled = do_compile_project(project, target);
+    // Serialised as plain JavaScript objects rather than `Map`s
+    compiled
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| e.to_string())
 }
 
 /// Get the compiled JavaScript output for a given module.
@@ -131,9 +166,7 @@ pub fn compile_package(project_id: usize, target: &str) -> Result<(), String> {
 ///
 #[wasm_bindgen]
 pub fn read_compiled_javascript(project_id: usize, module_name: &str) -> Option<String> {
-    let fs = get_filesystem(project_id);
-    let path = format!("/build/{}.mjs", module_name);
-    fs.read(&Utf8PathBuf::from(path)).ok()
+    read_compiled_module(&get_filesystem(project_id), Target::JavaScript, module_name)
 }
 
 /// Get the compiled Erlang output for a given module.
@@ -142,14 +175,78 @@ pub fn read_compiled_javascript(project_id: usize, module_name: &str) -> Option<
 ///
 #[wasm_bindgen]
 pub fn read_compiled_erlang(project_id: usize, module_name: &str) -> Option<String> {
-    let fs = get_filesystem(project_id);
-    let path = format!(
-        "/build/_gleam_artefacts/{}.erl",
-        module_name.replace('/', "@")
-    );
+    read_compiled_module(&get_filesystem(project_id), Target::Erlang, module_name)
+}
+
+fn read_compiled_module(fs: &WasmFileSystem, target: Target, module_name: &str) -> Option<String> {
+    let path = match target {
+        Target::JavaScript => format!("/build/{}.mjs", module_name),
+        Target::Erlang => format!(
+            "/build/_gleam_artefacts/{}.erl",
+            module_name.replace('/', "@")
+        ),
+    };
     fs.read(&Utf8PathBuf::from(path)).ok()
 }
 
+fn parse_target(target: &str) -> Result<Target, String> {
+    match target.to_lowercase().as_str() {
+        "erl" | "erlang" => Ok(Target::Erlang),
+        "js" | "javascript" => Ok(Target::JavaScript),
+        _ => Err(format!(
+            "Unknown target `{target}`, expected `erlang` or `javascript`"
+        )),
+    }
+}
+
+/// The contents of a file given to `compile_project`.
+#[derive(Debug)]
+enum FileContents {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl<'de> Deserialize<'de> for FileContents {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FileContentsVisitor;
+
+        impl<'de> de::Visitor<'de> for FileContentsVisitor {
+            type Value = FileContents;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string or a Uint8Array")
+            }
+
+            fn visit_str<E: de::Error>(self, text: &str) -> Result<Self::Value, E> {
+                Ok(FileContents::Text(text.into()))
+            }
+
+            fn visit_string<E: de::Error>(self, text: String) -> Result<Self::Value, E> {
+                Ok(FileContents::Text(text))
+            }
+
+            fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+                Ok(FileContents::Bytes(bytes.into()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(FileContents::Bytes(bytes))
+            }
+        }
+
+        deserializer.deserialize_any(FileContentsVisitor)
+    }
+}
+
+/// The result of `compile_project`.
+#[derive(Debug, Serialize)]
+struct CompiledProject {
+    /// The generated code of each module, by module name. This is empty if
+    /// the project failed to compile.
+    modules: BTreeMap<String, String>,
+    diagnostics: Vec<JsonDiagnostic>,
+}
+
 /// Clear any stored warnings. This is performed automatically when before compilation.
 ///
 #[wasm_bindgen]
@@ -164,7 +261,36 @@ pub fn pop_warning(project_id: usize) -> Option<String> {
     get_warnings(project_id).pop().map(|w| w.to_pretty_string())
 }
 
-fn do_compile_package(project: Project, target: Target) -> Result<(), Error> {
+fn do_compile_project(project: Project, target: Target) -> CompiledProject {
+    let result = do_compile_package(project.clone(), target);
+    let mut diagnostics = project
+        .warnings
+        .take()
+        .iter()
+        .map(JsonDiagnostic::from_warning)
+        .collect::<Vec<_>>();
+
+    let modules = match result {
+        Ok(modules) => modules
+            .iter()
+            .filter_map(|module| {
+                let code = read_compiled_module(&project.fs, target, &module.name)?;
+                Some((module.name.to_string(), code))
+            })
+            .collect(),
+        Err(error) => {
+            diagnostics.extend(JsonDiagnostic::from_error(&error));
+            BTreeMap::new()
+        }
+    };
+
+    CompiledProject {
+        modules,
+        diagnostics,
+    }
+}
+
+fn do_compile_package(project: Project, target: Target) -> Result<Vec<Module>, Error> {
     let ids = UniqueIdGenerator::new();
     let mut type_manifests = im::HashMap::new();
     let mut defined_modules = im::HashMap::new();
@@ -181,6 +307,7 @@ fn do_compile_package(project: Project, target: Target) -> Result<(), Error> {
         Target::Erlang => TargetCodegenConfiguration::Erlang { app_file: None },
         Target::JavaScript => TargetCodegenConfiguration::JavaScript {
             emit_typescript_definitions: false,
+            emit_source_maps: false,
             prelude_location: Utf8PathBuf::from("./gleam_prelude.mjs"),
         },
     };
@@ -190,6 +317,16 @@ fn do_compile_package(project: Project, target: Target) -> Result<(), Error> {
     let lib = Utf8PathBuf::from("/lib");
     let out = Utf8PathBuf::from("/build");
     let package = Utf8PathBuf::from("/");
+
+    // The modules of precompiled dependencies can be imported using their
+    // cached interfaces
+    for path in project.fs.gleam_cache_files(&lib) {
+        let bytes = project.fs.read_bytes(&path)?;
+        let interface = ModuleDecoder::new(ids.clone()).read(bytes.as_slice())?;
+        _ = defined_modules.insert(interface.name.clone(), path);
+        _ = type_manifests.insert(interface.name.clone(), interface);
+    }
+
     let mut compiler = PackageCompiler::new(
         &config,
         Mode::Dev,
@@ -203,13 +340,12 @@ fn do_compile_package(project: Project, target: Target) -> Result<(), Error> {
     compiler.write_entrypoint = false;
     compiler.write_metadata = false;
     compiler.compile_beam_bytecode = true;
-    _ = compiler.compile(
+    compiler.compile(
         &warning_emitter,
         &mut type_manifests,
         &mut defined_modules,
+        &mut im::HashSet::new(),
         &mut StaleTracker::default(),
         &NullTelemetry,
-    )?;
-
-    Ok(())
+    )
 }
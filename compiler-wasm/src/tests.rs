@@ -85,3 +85,66 @@ fn test_warnings() {
     assert!(pop_warning(0).is_some());
     assert!(pop_warning(0).is_none());
 }
+
+#[derive(Debug, serde::Deserialize)]
+struct CompiledProjectOutput {
+    modules: BTreeMap<String, String>,
+    diagnostics: Vec<DiagnosticOutput>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct DiagnosticOutput {
+    severity: String,
+    path: Option<String>,
+}
+
+fn compile_project_files(files: &[(&str, &str)]) -> CompiledProjectOutput {
+    let files: BTreeMap<_, _> = files.iter().copied().collect();
+    let files = serde_wasm_bindgen::to_value(&files).unwrap();
+    let output = compile_project(0, "javascript", files).unwrap();
+    serde_wasm_bindgen::from_value(output).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_compile_project() {
+    let output = compile_project_files(&[
+        ("/src/one/two.gleam", "pub const x = 1"),
+        ("/src/up/down.gleam", "import one/two pub fn go() { two.x }"),
+    ]);
+
+    assert!(output.diagnostics.is_empty());
+    assert_eq!(
+        output.modules.get("one/two"),
+        Some(&"export const x = 1;\n".to_string())
+    );
+    assert!(output.modules.contains_key("up/down"));
+}
+
+#[wasm_bindgen_test]
+fn test_compile_project_replaces_files() {
+    _ = compile_project_files(&[("/src/one.gleam", "pub const x = 1")]);
+    let output = compile_project_files(&[("/src/two.gleam", "pub const y = 1")]);
+
+    assert_eq!(output.modules.keys().collect::<Vec<_>>(), vec!["two"]);
+}
+
+#[wasm_bindgen_test]
+fn test_compile_project_diagnostics() {
+    let output = compile_project_files(&[
+        ("/src/one.gleam", "const x = 1"),
+        ("/src/two.gleam", "import one pub fn go() { 1 + \"one\" }"),
+    ]);
+
+    assert!(output.modules.is_empty());
+    assert_eq!(
+        output
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.severity.as_str(), diagnostic.path.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("warning", Some("/src/one.gleam")),
+            ("error", Some("/src/two.gleam")),
+        ]
+    );
+}
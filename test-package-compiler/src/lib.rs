@@ -37,6 +37,7 @@ pub fn prepare(path: &str) -> String {
         },
         Target::JavaScript => TargetCodegenConfiguration::JavaScript {
             emit_typescript_definitions: config.javascript.typescript_declarations,
+            emit_source_maps: config.javascript.source_maps,
             prelude_location: Utf8PathBuf::from("../prelude.mjs"),
         },
     };
@@ -64,10 +65,12 @@ pub fn prepare(path: &str) -> String {
     compiler.write_metadata = true;
     compiler.compile_beam_bytecode = false;
     compiler.copy_native_files = false;
+    compiler.detect_dead_code = config.warnings.dead_code;
     let result = compiler.compile(
         &warning_emitter,
         &mut modules,
         &mut im::HashMap::new(),
+        &mut im::HashSet::new(),
         &mut StaleTracker::default(),
         &NullTelemetry,
     );